@@ -0,0 +1,1165 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use anyhow::Error;
+use chrono::Utc;
+use hyper::header::AUTHORIZATION;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use log::{error, info};
+use reqwest::Url;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::{
+    metrics::metrics_registry::MetricsRegistry,
+    models::discovery_policy::DiscoveryPolicy,
+    repos::{
+        additional_channel_store::AdditionalChannelStore,
+        apikey_store::ApiKeyStore,
+        blocklist_store::BlocklistStore,
+        channel_popularity_store::ChannelPopularityStore,
+        channel_store::ChannelStore,
+        crawl_audit_store::CrawlAuditStore,
+        digest_report_store::DigestReportStore,
+        non_guitar_channel_store::NonGuitarChannelStore,
+        review_queue_store::{ReviewQueueStore, REVIEW_STATUS_APPROVED, REVIEW_STATUS_REJECTED},
+        settings_store::SettingsStore,
+        site_stats_store::SiteStatsStore,
+        tag_profile_store::TagProfileStore,
+        video_store::VideoStore,
+    },
+    services::opt_out_service::OptOutService,
+    utils::{
+        api_key_health_utils::mask_api_key,
+        consts::{
+            CLASSIFICATION_OVERRIDES, CLASSIFICATION_OVERRIDE_EXCLUDE,
+            CLASSIFICATION_OVERRIDE_INCLUDE, DEACTIVATION_REASON_CLASSIFICATION_OVERRIDE,
+            FEATURE_FLAGS,
+        },
+        health::Health,
+        maintenance::Maintenance,
+        song_utils::get_song_key,
+        youtube_url_utils::{parse_youtube_url, YoutubeResource},
+    },
+};
+
+const MIN_REFRESH_OVERRIDE_INTERVAL_SECONDS: i64 = 15 * 60;
+const COVERS_LIMIT: i64 = 100;
+const DEFAULT_CRAWLS_LIMIT: i64 = 10;
+const MAX_CRAWLS_LIMIT: i64 = 500;
+const DEFAULT_DIGESTS_LIMIT: i64 = 7;
+const MAX_DIGESTS_LIMIT: i64 = 100;
+const MAX_PAGE_VIEW_CHANNELS: usize = 1000;
+const DEFAULT_REBRANDED_DAYS: i64 = 30;
+const REBRANDED_LIMIT: i64 = 500;
+const RESTRICTED_VIDEOS_LIMIT: i64 = 500;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SubmitChannelRequest {
+    url: String,
+    #[serde(default)]
+    ignore_guitar_terms: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MaintenanceRequest {
+    reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FeatureFlagRequest {
+    enabled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlockChannelRequest {
+    reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OptOutRequest {
+    email: String,
+    #[serde(default)]
+    reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfirmOptOutRequest {
+    confirmation_code: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClassificationOverrideRequest {
+    classification_override: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageViewsRequest {
+    /// Page views by channel id since the previous report
+    views: HashMap<String, i64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RefreshOverrideRequest {
+    interval_seconds: i64,
+    until: i64,
+}
+
+pub struct AdminApi {
+    channel_repo: Box<dyn ChannelStore>,
+    video_repo: Box<dyn VideoStore>,
+    review_queue_repo: Box<dyn ReviewQueueStore>,
+    additional_channel_repo: Box<dyn AdditionalChannelStore>,
+    blocklist_repo: Box<dyn BlocklistStore>,
+    non_guitar_channel_repo: Box<dyn NonGuitarChannelStore>,
+    tag_profile_repo: Box<dyn TagProfileStore>,
+    settings_repo: Box<dyn SettingsStore>,
+    crawl_audit_repo: Box<dyn CrawlAuditStore>,
+    site_stats_repo: Box<dyn SiteStatsStore>,
+    digest_report_repo: Box<dyn DigestReportStore>,
+    popularity_repo: Box<dyn ChannelPopularityStore>,
+    apikey_repo: Box<dyn ApiKeyStore>,
+    opt_out_service: OptOutService,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+    metrics: Arc<MetricsRegistry>,
+    token: Option<String>,
+}
+
+impl AdminApi {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        channel_repo: Box<dyn ChannelStore>,
+        video_repo: Box<dyn VideoStore>,
+        review_queue_repo: Box<dyn ReviewQueueStore>,
+        additional_channel_repo: Box<dyn AdditionalChannelStore>,
+        blocklist_repo: Box<dyn BlocklistStore>,
+        non_guitar_channel_repo: Box<dyn NonGuitarChannelStore>,
+        tag_profile_repo: Box<dyn TagProfileStore>,
+        settings_repo: Box<dyn SettingsStore>,
+        crawl_audit_repo: Box<dyn CrawlAuditStore>,
+        site_stats_repo: Box<dyn SiteStatsStore>,
+        digest_report_repo: Box<dyn DigestReportStore>,
+        popularity_repo: Box<dyn ChannelPopularityStore>,
+        apikey_repo: Box<dyn ApiKeyStore>,
+        opt_out_service: OptOutService,
+        maintenance: Arc<Maintenance>,
+        health: Arc<Health>,
+        metrics: Arc<MetricsRegistry>,
+        token: Option<String>,
+    ) -> AdminApi {
+        AdminApi {
+            channel_repo,
+            video_repo,
+            review_queue_repo,
+            additional_channel_repo,
+            blocklist_repo,
+            non_guitar_channel_repo,
+            tag_profile_repo,
+            settings_repo,
+            crawl_audit_repo,
+            site_stats_repo,
+            digest_report_repo,
+            popularity_repo,
+            apikey_repo,
+            opt_out_service,
+            maintenance,
+            health,
+            metrics,
+            token,
+        }
+    }
+
+    pub async fn serve(self, host: &str, port: u16) -> Result<(), Error> {
+        let api = Arc::new(self);
+        let addr = SocketAddr::from((host.parse::<IpAddr>()?, port));
+
+        let make_service = make_service_fn(move |_| {
+            let api = api.clone();
+
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req| {
+                    let api = api.clone();
+
+                    async move { Ok::<_, Infallible>(api.handle(req).await) }
+                }))
+            }
+        });
+
+        info!("Admin API listening on {}", addr);
+        Server::bind(&addr).serve(make_service).await?;
+
+        Ok(())
+    }
+
+    async fn handle(&self, req: Request<Body>) -> Response<Body> {
+        let method = req.method().clone();
+        let path = req.uri().path().to_string();
+        let segments = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<&str>>();
+
+        if !is_public_route(&method, &segments) && !self.is_authorized(&req) {
+            return json_response(
+                StatusCode::UNAUTHORIZED,
+                json!({"error": "Missing or invalid bearer token"}),
+            );
+        }
+
+        let result = match (&method, segments.as_slice()) {
+            (&Method::GET, ["healthz"]) => self.get_health().await,
+            (&Method::GET, ["readyz"]) => self.get_readiness().await,
+            (&Method::GET, ["status"]) => self.get_status_page().await,
+            (&Method::GET, ["metrics"]) => self.get_metrics(),
+            (&Method::PUT, ["maintenance"]) => self.enable_maintenance(req).await,
+            (&Method::DELETE, ["maintenance"]) => self.disable_maintenance().await,
+            (&Method::GET, ["feature-flags"]) => self.get_feature_flags().await,
+            (&Method::PUT, ["feature-flags", flag]) => self.set_feature_flag(flag, req).await,
+            (&Method::GET, ["discovery-policy"]) => self.get_discovery_policy().await,
+            (&Method::PUT, ["discovery-policy"]) => self.set_discovery_policy(req).await,
+            (&Method::GET, ["review-queue"]) => self.get_review_queue().await,
+            (&Method::POST, ["review-queue", channel_id, "approve"]) => {
+                self.approve_review(channel_id).await
+            }
+            (&Method::POST, ["review-queue", channel_id, "reject"]) => {
+                self.reject_review(channel_id).await
+            }
+            (&Method::GET, ["blocklist"]) => self.get_blocklist().await,
+            (&Method::PUT, ["blocklist", channel]) => self.block_channel(channel, req).await,
+            (&Method::DELETE, ["blocklist", channel]) => self.unblock_channel(channel).await,
+            (&Method::GET, ["opt-outs"]) => self.get_opt_outs().await,
+            (&Method::PUT, ["opt-outs", channel]) => self.opt_out_channel(channel, req).await,
+            (&Method::POST, ["opt-outs", channel, "confirm"]) => {
+                self.confirm_opt_out(channel, req).await
+            }
+            (&Method::GET, ["additional-channels"]) => self.get_additional_channels().await,
+            (&Method::DELETE, ["additional-channels", channel_id]) => {
+                self.delete_additional_channel(channel_id).await
+            }
+            (&Method::GET, ["site-stats"]) => self.get_site_stats(None).await,
+            (&Method::GET, ["site-stats", version]) => self.get_site_stats(Some(version)).await,
+            (&Method::GET, ["digests"]) => self.get_digests(&req).await,
+            (&Method::POST, ["page-views"]) => self.record_page_views(req).await,
+            (&Method::GET, ["api-keys"]) => self.get_api_keys().await,
+            (&Method::GET, ["channels"]) => self.get_channels(&req).await,
+            (&Method::POST, ["channels"]) => self.submit_channel(req).await,
+            (&Method::GET, ["channels", "rebranded"]) => self.get_rebranded_channels(&req).await,
+            (&Method::GET, ["videos", video_id]) => self.get_video(video_id).await,
+            (&Method::GET, ["covers"]) => self.get_covers(&req).await,
+            (&Method::GET, ["channels", channel_id, "tag-profile"]) => {
+                self.get_tag_profile(channel_id).await
+            }
+            (&Method::GET, ["channels", channel_id, "restricted-videos"]) => {
+                self.get_restricted_videos(channel_id).await
+            }
+            (&Method::GET, ["channels", channel_id, "crawls"]) => {
+                self.get_crawls(channel_id, &req).await
+            }
+            (&Method::PUT, ["channels", channel_id, "refresh-override"]) => {
+                self.set_refresh_override(channel_id, req).await
+            }
+            (&Method::DELETE, ["channels", channel_id, "refresh-override"]) => {
+                self.clear_refresh_override(channel_id).await
+            }
+            (&Method::PUT, ["channels", channel_id, "classification-override"]) => {
+                self.set_classification_override(channel_id, req).await
+            }
+            (&Method::DELETE, ["channels", channel_id, "classification-override"]) => {
+                self.clear_classification_override(channel_id).await
+            }
+            _ => Ok(json_response(
+                StatusCode::NOT_FOUND,
+                json!({"error": "Not found"}),
+            )),
+        };
+
+        match result {
+            Ok(response) => response,
+            Err(e) => {
+                error!("Error in admin api {} {}: {}", method, path, e);
+                json_response(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    json!({"error": e.to_string()}),
+                )
+            }
+        }
+    }
+
+    /// Without a configured token every request is authorized, the api then only listens on a
+    /// loopback address.
+    fn is_authorized(&self, req: &Request<Body>) -> bool {
+        let token = match &self.token {
+            Some(token) => token,
+            None => return true,
+        };
+
+        req.headers()
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|bearer| is_same_token(bearer, token))
+    }
+
+    // YouTube being unreachable is only reported, restarting or unrouting the crawler won't fix it
+    async fn get_health(&self) -> Result<Response<Body>, Error> {
+        let youtube_api = self.health.is_youtube_api_reachable().await;
+
+        Ok(json_response(
+            StatusCode::OK,
+            json!({
+                "status": "ok",
+                "checks": {
+                    "youtubeApi": youtube_api,
+                },
+            }),
+        ))
+    }
+
+    async fn get_readiness(&self) -> Result<Response<Body>, Error> {
+        let database = self.health.is_database_reachable().await;
+
+        let last_successful_crawls = self
+            .health
+            .get_last_successes()
+            .await
+            .into_iter()
+            .map(|(component, at)| (component, Value::from(at.to_rfc3339())))
+            .collect::<serde_json::Map<String, Value>>();
+
+        let ready = database;
+        let status = if ready {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        };
+
+        Ok(json_response(
+            status,
+            json!({
+                "ready": ready,
+                "checks": {
+                    "database": database,
+                },
+                "maintenance": self.maintenance.get_state().await.is_some(),
+                "queueDepths": self.health.get_queue_depths().await,
+                "lastSuccessfulCrawls": last_successful_crawls,
+            }),
+        ))
+    }
+
+    async fn get_status_page(&self) -> Result<Response<Body>, Error> {
+        let state = self.maintenance.get_state().await;
+        let paused_components = self.maintenance.get_paused_components().await;
+
+        let status = match &state {
+            Some(state) => format!(
+                "<p>Maintenance since {}: {}</p>",
+                state.since.to_rfc3339(),
+                escape_html(&state.reason)
+            ),
+            None => "<p>Crawling is running</p>".to_string(),
+        };
+
+        let paused = paused_components
+            .iter()
+            .map(|component| format!("<li>{}</li>", escape_html(component)))
+            .collect::<Vec<String>>()
+            .join("");
+
+        let html = format!(
+            "<!DOCTYPE html><html><head><title>Crawler status</title></head><body>\
+            <h1>Crawler status</h1>{}<h2>Paused components</h2><ul>{}</ul></body></html>",
+            status, paused
+        );
+
+        Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header("Content-Type", "text/html; charset=utf-8")
+            .body(Body::from(html))?)
+    }
+
+    fn get_metrics(&self) -> Result<Response<Body>, Error> {
+        Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header("Content-Type", "text/plain; version=0.0.4")
+            .body(Body::from(self.metrics.render()))?)
+    }
+
+    async fn enable_maintenance(&self, req: Request<Body>) -> Result<Response<Body>, Error> {
+        let body = match read_json::<MaintenanceRequest>(req).await {
+            Ok(body) => body,
+            Err(e) => return Ok(bad_request_response(&e.to_string())),
+        };
+
+        self.maintenance.enable(&body.reason).await;
+
+        info!("Maintenance mode enabled: {}", body.reason);
+
+        Ok(json_response(
+            StatusCode::OK,
+            json!({"maintenance": true, "reason": body.reason}),
+        ))
+    }
+
+    async fn disable_maintenance(&self) -> Result<Response<Body>, Error> {
+        self.maintenance.disable().await;
+
+        info!("Maintenance mode disabled");
+
+        Ok(json_response(StatusCode::OK, json!({"maintenance": false})))
+    }
+
+    async fn get_feature_flags(&self) -> Result<Response<Body>, Error> {
+        let flags = self
+            .settings_repo
+            .get_feature_flags()
+            .await?
+            .into_iter()
+            .map(|(flag, enabled)| (flag, Value::Bool(enabled)))
+            .collect::<serde_json::Map<String, Value>>();
+
+        Ok(json_response(StatusCode::OK, Value::Object(flags)))
+    }
+
+    async fn set_feature_flag(
+        &self,
+        flag: &str,
+        req: Request<Body>,
+    ) -> Result<Response<Body>, Error> {
+        if !FEATURE_FLAGS.contains(&flag) {
+            return Ok(json_response(
+                StatusCode::NOT_FOUND,
+                json!({"error": format!("Unknown feature flag {}", flag)}),
+            ));
+        }
+
+        let body = match read_json::<FeatureFlagRequest>(req).await {
+            Ok(body) => body,
+            Err(e) => return Ok(bad_request_response(&e.to_string())),
+        };
+
+        self.settings_repo
+            .set_feature_enabled(flag, body.enabled)
+            .await?;
+
+        info!("Feature flag {} set to {}", flag, body.enabled);
+
+        Ok(json_response(StatusCode::OK, json!({ flag: body.enabled })))
+    }
+
+    async fn get_discovery_policy(&self) -> Result<Response<Body>, Error> {
+        let policy = self.settings_repo.get_discovery_policy().await?;
+
+        Ok(json_response(StatusCode::OK, serde_json::to_value(policy)?))
+    }
+
+    /// Replaces the whole policy, thresholds missing from the body are cleared.
+    async fn set_discovery_policy(&self, req: Request<Body>) -> Result<Response<Body>, Error> {
+        let policy = match read_json::<DiscoveryPolicy>(req).await {
+            Ok(policy) => policy,
+            Err(e) => return Ok(bad_request_response(&e.to_string())),
+        };
+
+        if let (Some(min), Some(max)) = (policy.min_subscribers, policy.max_subscribers) {
+            if min > max {
+                return Ok(bad_request_response(
+                    "minSubscribers must not be above maxSubscribers",
+                ));
+            }
+        }
+
+        self.settings_repo.set_discovery_policy(&policy).await?;
+
+        info!("Discovery policy set to {:?}", policy);
+
+        Ok(json_response(StatusCode::OK, serde_json::to_value(policy)?))
+    }
+
+    async fn get_review_queue(&self) -> Result<Response<Body>, Error> {
+        let pending = self.review_queue_repo.get_pending().await?;
+
+        Ok(json_response(
+            StatusCode::OK,
+            serde_json::to_value(pending)?,
+        ))
+    }
+
+    async fn approve_review(&self, channel_id: &str) -> Result<Response<Body>, Error> {
+        let updated = self
+            .review_queue_repo
+            .set_decision(channel_id, REVIEW_STATUS_APPROVED)
+            .await?;
+
+        if !updated {
+            return Ok(not_pending_response(channel_id));
+        }
+
+        self.non_guitar_channel_repo.delete(channel_id).await?;
+        self.additional_channel_repo
+            .insert(channel_id, true)
+            .await?;
+
+        info!("Review approved for channel {}", channel_id);
+
+        Ok(json_response(
+            StatusCode::OK,
+            json!({"channel": channel_id, "status": REVIEW_STATUS_APPROVED}),
+        ))
+    }
+
+    async fn reject_review(&self, channel_id: &str) -> Result<Response<Body>, Error> {
+        let updated = self
+            .review_queue_repo
+            .set_decision(channel_id, REVIEW_STATUS_REJECTED)
+            .await?;
+
+        if !updated {
+            return Ok(not_pending_response(channel_id));
+        }
+
+        info!("Review rejected for channel {}", channel_id);
+
+        Ok(json_response(
+            StatusCode::OK,
+            json!({"channel": channel_id, "status": REVIEW_STATUS_REJECTED}),
+        ))
+    }
+
+    async fn get_channels(&self, req: &Request<Body>) -> Result<Response<Body>, Error> {
+        let params = query_params(req);
+
+        let channel_ids = self
+            .channel_repo
+            .get_ids_by_country_and_topic(
+                params.get("country").map(|country| country.as_str()),
+                params.get("topic").map(|topic| topic.as_str()),
+            )
+            .await?;
+
+        Ok(json_response(StatusCode::OK, json!(channel_ids)))
+    }
+
+    /// Channels that changed their avatar or banner within the last `days`.
+    async fn get_rebranded_channels(&self, req: &Request<Body>) -> Result<Response<Body>, Error> {
+        let days = match query_params(req).get("days") {
+            Some(days) => match days.parse::<i64>() {
+                Ok(days) if days > 0 => days,
+                _ => return Ok(bad_request_response("days must be a positive number")),
+            },
+            None => DEFAULT_REBRANDED_DAYS,
+        };
+
+        let channel_ids = self
+            .channel_repo
+            .get_ids_rebranded_since(Utc::now() - chrono::Duration::days(days), REBRANDED_LIMIT)
+            .await?;
+
+        Ok(json_response(StatusCode::OK, json!(channel_ids)))
+    }
+
+    async fn submit_channel(&self, req: Request<Body>) -> Result<Response<Body>, Error> {
+        let body = match read_json::<SubmitChannelRequest>(req).await {
+            Ok(body) => body,
+            Err(e) => return Ok(bad_request_response(&e.to_string())),
+        };
+
+        let channel_id = match parse_youtube_url(&body.url) {
+            Some(YoutubeResource::Channel(channel_id)) => channel_id,
+            Some(YoutubeResource::Handle(handle)) => handle,
+            Some(YoutubeResource::Video(_)) => {
+                return Ok(bad_request_response(
+                    "Expected a channel URL but got a video URL",
+                ))
+            }
+            None => return Ok(bad_request_response("Not a valid YouTube channel URL")),
+        };
+
+        self.additional_channel_repo
+            .insert(&channel_id, body.ignore_guitar_terms)
+            .await?;
+
+        info!("Channel {} submitted for crawling", channel_id);
+
+        Ok(json_response(
+            StatusCode::ACCEPTED,
+            json!({"channel": channel_id}),
+        ))
+    }
+
+    async fn get_blocklist(&self) -> Result<Response<Body>, Error> {
+        let blocked = self.blocklist_repo.get_all().await?;
+
+        Ok(json_response(
+            StatusCode::OK,
+            serde_json::to_value(blocked)?,
+        ))
+    }
+
+    /// Also drops the channel from the additional channels still waiting for a crawl.
+    async fn block_channel(
+        &self,
+        channel: &str,
+        req: Request<Body>,
+    ) -> Result<Response<Body>, Error> {
+        let channel = match parse_youtube_url(channel) {
+            Some(YoutubeResource::Channel(channel_id)) => channel_id,
+            Some(YoutubeResource::Handle(handle)) => handle,
+            _ => return Ok(bad_request_response("Not a channel id or handle")),
+        };
+
+        let body = match read_json::<BlockChannelRequest>(req).await {
+            Ok(body) => body,
+            Err(e) => return Ok(bad_request_response(&e.to_string())),
+        };
+
+        self.blocklist_repo.block(&channel, &body.reason).await?;
+        self.additional_channel_repo.delete_one(&channel).await?;
+
+        info!("Channel {} blocked: {}", channel, body.reason);
+
+        Ok(json_response(
+            StatusCode::OK,
+            json!({"channel": channel, "reason": body.reason}),
+        ))
+    }
+
+    async fn unblock_channel(&self, channel: &str) -> Result<Response<Body>, Error> {
+        if !self.blocklist_repo.unblock(channel).await? {
+            return Ok(json_response(
+                StatusCode::NOT_FOUND,
+                json!({"error": format!("Channel {} is not blocked", channel)}),
+            ));
+        }
+
+        info!("Channel {} unblocked", channel);
+
+        Ok(json_response(StatusCode::OK, json!({"channel": channel})))
+    }
+
+    async fn get_opt_outs(&self) -> Result<Response<Body>, Error> {
+        let opt_outs = self.opt_out_service.get_all().await?;
+
+        Ok(json_response(
+            StatusCode::OK,
+            serde_json::to_value(opt_outs)?,
+        ))
+    }
+
+    /// Registers the removal request of a channel owner. The confirmation code is only mailed to
+    /// the owner's email, the request takes effect once confirmed with it.
+    async fn opt_out_channel(
+        &self,
+        channel: &str,
+        req: Request<Body>,
+    ) -> Result<Response<Body>, Error> {
+        let channel = match parse_channel(channel) {
+            Some(channel) => channel,
+            None => return Ok(bad_request_response("Not a channel id or handle")),
+        };
+
+        let body = match read_json::<OptOutRequest>(req).await {
+            Ok(body) => body,
+            Err(e) => return Ok(bad_request_response(&e.to_string())),
+        };
+
+        if !body.email.contains('@') {
+            return Ok(bad_request_response("email must be an email address"));
+        }
+
+        match self
+            .opt_out_service
+            .request(&channel, &body.email, &body.reason)
+            .await?
+        {
+            Some(channel) => Ok(get_opt_out_requested_response(&channel)),
+            None => Ok(json_response(
+                StatusCode::SERVICE_UNAVAILABLE,
+                json!({"error": "Opt-outs need notifications.email to mail the confirmation code"}),
+            )),
+        }
+    }
+
+    /// Confirms a removal request, which purges the stored channel.
+    async fn confirm_opt_out(
+        &self,
+        channel: &str,
+        req: Request<Body>,
+    ) -> Result<Response<Body>, Error> {
+        let channel = match parse_channel(channel) {
+            Some(channel) => channel,
+            None => return Ok(bad_request_response("Not a channel id or handle")),
+        };
+
+        let body = match read_json::<ConfirmOptOutRequest>(req).await {
+            Ok(body) => body,
+            Err(e) => return Ok(bad_request_response(&e.to_string())),
+        };
+
+        match self
+            .opt_out_service
+            .confirm(&channel, &body.confirmation_code)
+            .await?
+        {
+            Some(opt_out) => Ok(json_response(
+                StatusCode::OK,
+                json!({"channel": opt_out.channel, "purged": opt_out.purged}),
+            )),
+            None => Ok(json_response(
+                StatusCode::NOT_FOUND,
+                json!({"error": format!("No opt-out of {} waits for this code", channel)}),
+            )),
+        }
+    }
+
+    async fn get_additional_channels(&self) -> Result<Response<Body>, Error> {
+        let additional_channels = self.additional_channel_repo.get_all().await?;
+
+        Ok(json_response(
+            StatusCode::OK,
+            serde_json::to_value(additional_channels)?,
+        ))
+    }
+
+    async fn delete_additional_channel(&self, channel_id: &str) -> Result<Response<Body>, Error> {
+        self.additional_channel_repo.delete_one(channel_id).await?;
+
+        info!("Additional channel {} removed", channel_id);
+
+        Ok(json_response(
+            StatusCode::OK,
+            json!({"channel": channel_id}),
+        ))
+    }
+
+    async fn get_video(&self, video_id: &str) -> Result<Response<Body>, Error> {
+        match self.video_repo.find_by_id(video_id).await? {
+            Some(video) => Ok(json_response(StatusCode::OK, serde_json::to_value(video)?)),
+            None => Ok(json_response(
+                StatusCode::NOT_FOUND,
+                json!({"error": format!("No video found for id {}", video_id)}),
+            )),
+        }
+    }
+
+    async fn get_covers(&self, req: &Request<Body>) -> Result<Response<Body>, Error> {
+        let params = query_params(req);
+
+        let song_key = match params.get("song").map(|song| get_song_key(song)) {
+            Some(song_key) if !song_key.is_empty() => song_key,
+            _ => return Ok(bad_request_response("song is required")),
+        };
+        let artist_key = params.get("artist").map(|artist| get_song_key(artist));
+
+        let videos = self
+            .video_repo
+            .get_covers(&song_key, artist_key.as_deref(), COVERS_LIMIT)
+            .await?;
+
+        Ok(json_response(StatusCode::OK, serde_json::to_value(videos)?))
+    }
+
+    async fn get_restricted_videos(&self, channel_id: &str) -> Result<Response<Body>, Error> {
+        let videos = self
+            .video_repo
+            .get_restricted_by_channel(channel_id, RESTRICTED_VIDEOS_LIMIT)
+            .await?;
+
+        Ok(json_response(StatusCode::OK, serde_json::to_value(videos)?))
+    }
+
+    async fn get_tag_profile(&self, channel_id: &str) -> Result<Response<Body>, Error> {
+        match self.tag_profile_repo.get(channel_id).await? {
+            Some(profile) => Ok(json_response(
+                StatusCode::OK,
+                serde_json::to_value(profile)?,
+            )),
+            None => Ok(json_response(
+                StatusCode::NOT_FOUND,
+                json!({"error": format!("No tag profile found for channel {}", channel_id)}),
+            )),
+        }
+    }
+
+    /// Returns the latest site stats, or the given version of them.
+    async fn get_site_stats(&self, version: Option<&str>) -> Result<Response<Body>, Error> {
+        let stats = match version {
+            Some(version) => match version.parse::<i64>() {
+                Ok(version) => self.site_stats_repo.get_by_version(version).await?,
+                Err(_) => return Ok(bad_request_response("version must be a number")),
+            },
+            None => self.site_stats_repo.get_latest().await?,
+        };
+
+        match stats {
+            Some(stats) => Ok(json_response(StatusCode::OK, serde_json::to_value(stats)?)),
+            None => Ok(json_response(
+                StatusCode::NOT_FOUND,
+                json!({"error": "No site stats found"}),
+            )),
+        }
+    }
+
+    /// Latest digests of the reporting job first.
+    async fn get_digests(&self, req: &Request<Body>) -> Result<Response<Body>, Error> {
+        let limit = match query_params(req).get("limit") {
+            Some(limit) => match limit.parse::<i64>() {
+                Ok(limit) if (1..=MAX_DIGESTS_LIMIT).contains(&limit) => limit,
+                _ => {
+                    return Ok(bad_request_response(&format!(
+                        "limit must be between 1 and {}",
+                        MAX_DIGESTS_LIMIT
+                    )))
+                }
+            },
+            None => DEFAULT_DIGESTS_LIMIT,
+        };
+
+        let digests = self.digest_report_repo.get_latest(limit).await?;
+
+        Ok(json_response(
+            StatusCode::OK,
+            serde_json::to_value(digests)?,
+        ))
+    }
+
+    /// Health and usage of the api keys, with the keys masked.
+    async fn get_api_keys(&self) -> Result<Response<Body>, Error> {
+        let api_keys = self
+            .apikey_repo
+            .get_all()
+            .await?
+            .into_iter()
+            .map(|api_key| {
+                json!({
+                    "key": mask_api_key(&api_key.key),
+                    "health": api_key.health,
+                    "usedQuota": api_key.used_quota,
+                    "dailyQuota": api_key.daily_quota,
+                    "consecutiveErrors": api_key.consecutive_errors,
+                    "lastError": api_key.last_error,
+                    "lastErrorAt": api_key.last_error_at,
+                    "disabledAt": api_key.disabled_at,
+                })
+            })
+            .collect::<Vec<Value>>();
+
+        Ok(json_response(StatusCode::OK, Value::Array(api_keys)))
+    }
+
+    /// Page views the website reports, which raise the scrape priority of the channels.
+    async fn record_page_views(&self, req: Request<Body>) -> Result<Response<Body>, Error> {
+        let body = match read_json::<PageViewsRequest>(req).await {
+            Ok(body) => body,
+            Err(e) => return Ok(bad_request_response(&e.to_string())),
+        };
+
+        if body.views.len() > MAX_PAGE_VIEW_CHANNELS {
+            return Ok(bad_request_response(&format!(
+                "views must hold at most {} channels",
+                MAX_PAGE_VIEW_CHANNELS
+            )));
+        }
+
+        if body.views.values().any(|count| *count <= 0) {
+            return Ok(bad_request_response("views must be greater than 0"));
+        }
+
+        self.popularity_repo.record_views(&body.views).await?;
+
+        Ok(json_response(
+            StatusCode::OK,
+            json!({"channels": body.views.len()}),
+        ))
+    }
+
+    async fn get_crawls(
+        &self,
+        channel_id: &str,
+        req: &Request<Body>,
+    ) -> Result<Response<Body>, Error> {
+        let limit = match query_params(req).get("limit") {
+            Some(limit) => match limit.parse::<i64>() {
+                Ok(limit) if (1..=MAX_CRAWLS_LIMIT).contains(&limit) => limit,
+                _ => {
+                    return Ok(bad_request_response(&format!(
+                        "limit must be between 1 and {}",
+                        MAX_CRAWLS_LIMIT
+                    )))
+                }
+            },
+            None => DEFAULT_CRAWLS_LIMIT,
+        };
+
+        let runs = self.crawl_audit_repo.get_latest(channel_id, limit).await?;
+
+        Ok(json_response(StatusCode::OK, serde_json::to_value(runs)?))
+    }
+
+    async fn set_refresh_override(
+        &self,
+        channel_id: &str,
+        req: Request<Body>,
+    ) -> Result<Response<Body>, Error> {
+        let body = match read_json::<RefreshOverrideRequest>(req).await {
+            Ok(body) => body,
+            Err(e) => return Ok(bad_request_response(&e.to_string())),
+        };
+
+        if body.interval_seconds < MIN_REFRESH_OVERRIDE_INTERVAL_SECONDS {
+            return Ok(bad_request_response(&format!(
+                "intervalSeconds must be at least {}",
+                MIN_REFRESH_OVERRIDE_INTERVAL_SECONDS
+            )));
+        }
+
+        if body.until <= Utc::now().timestamp() {
+            return Ok(bad_request_response("until must be in the future"));
+        }
+
+        let updated = self
+            .channel_repo
+            .set_refresh_override(channel_id, body.interval_seconds, body.until)
+            .await?;
+
+        if !updated {
+            return Ok(channel_not_found_response(channel_id));
+        }
+
+        info!(
+            "Refresh override set for channel {}: every {} seconds until {}",
+            channel_id, body.interval_seconds, body.until
+        );
+
+        Ok(json_response(
+            StatusCode::OK,
+            json!({
+                "channel": channel_id,
+                "refreshOverride": {
+                    "intervalSeconds": body.interval_seconds,
+                    "until": body.until,
+                }
+            }),
+        ))
+    }
+
+    async fn clear_refresh_override(&self, channel_id: &str) -> Result<Response<Body>, Error> {
+        let updated = self.channel_repo.clear_refresh_override(channel_id).await?;
+
+        if !updated {
+            return Ok(channel_not_found_response(channel_id));
+        }
+
+        info!("Refresh override cleared for channel {}", channel_id);
+
+        Ok(json_response(
+            StatusCode::OK,
+            json!({"channel": channel_id}),
+        ))
+    }
+
+    /// Force-included channels are crawled again, which brings back deactivated ones.
+    /// Force-excluded channels are deactivated right away.
+    async fn set_classification_override(
+        &self,
+        channel_id: &str,
+        req: Request<Body>,
+    ) -> Result<Response<Body>, Error> {
+        let body = match read_json::<ClassificationOverrideRequest>(req).await {
+            Ok(body) => body,
+            Err(e) => return Ok(bad_request_response(&e.to_string())),
+        };
+
+        let classification_override = body.classification_override.as_str();
+        if !CLASSIFICATION_OVERRIDES.contains(&classification_override) {
+            return Ok(bad_request_response(&format!(
+                "classificationOverride must be one of {}",
+                CLASSIFICATION_OVERRIDES.join(", ")
+            )));
+        }
+
+        let updated = self
+            .channel_repo
+            .set_classification_override(channel_id, classification_override)
+            .await?;
+
+        if !updated {
+            return Ok(channel_not_found_response(channel_id));
+        }
+
+        match classification_override {
+            CLASSIFICATION_OVERRIDE_INCLUDE => {
+                self.non_guitar_channel_repo.delete(channel_id).await?;
+                self.additional_channel_repo
+                    .insert(channel_id, true)
+                    .await?;
+            }
+            CLASSIFICATION_OVERRIDE_EXCLUDE => {
+                self.channel_repo
+                    .deactivate(channel_id, DEACTIVATION_REASON_CLASSIFICATION_OVERRIDE)
+                    .await?;
+            }
+            _ => {}
+        }
+
+        info!(
+            "Classification override {} set for channel {}",
+            classification_override, channel_id
+        );
+
+        Ok(json_response(
+            StatusCode::OK,
+            json!({
+                "channel": channel_id,
+                "classificationOverride": classification_override,
+            }),
+        ))
+    }
+
+    async fn clear_classification_override(
+        &self,
+        channel_id: &str,
+    ) -> Result<Response<Body>, Error> {
+        let updated = self
+            .channel_repo
+            .clear_classification_override(channel_id)
+            .await?;
+
+        if !updated {
+            return Ok(channel_not_found_response(channel_id));
+        }
+
+        info!("Classification override cleared for channel {}", channel_id);
+
+        Ok(json_response(
+            StatusCode::OK,
+            json!({"channel": channel_id}),
+        ))
+    }
+}
+
+/// Probes, the status page and metrics are read by load balancers and scrapers without the token.
+fn is_public_route(method: &Method, segments: &[&str]) -> bool {
+    method == Method::GET
+        && matches!(
+            segments,
+            ["healthz"] | ["readyz"] | ["status"] | ["metrics"]
+        )
+}
+
+/// Compares in constant time for tokens of the same length.
+fn is_same_token(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+fn parse_channel(channel: &str) -> Option<String> {
+    match parse_youtube_url(channel) {
+        Some(YoutubeResource::Channel(channel_id)) => Some(channel_id),
+        Some(YoutubeResource::Handle(handle)) => Some(handle),
+        _ => None,
+    }
+}
+
+fn query_params(req: &Request<Body>) -> HashMap<String, String> {
+    Url::parse(&format!("http://localhost{}", req.uri()))
+        .map(|url| url.query_pairs().into_owned().collect())
+        .unwrap_or_default()
+}
+
+async fn read_json<T: DeserializeOwned>(req: Request<Body>) -> Result<T, Error> {
+    let bytes = hyper::body::to_bytes(req.into_body()).await?;
+    let value = serde_json::from_slice::<T>(&bytes)?;
+
+    Ok(value)
+}
+
+fn bad_request_response(message: &str) -> Response<Body> {
+    json_response(StatusCode::BAD_REQUEST, json!({ "error": message }))
+}
+
+fn channel_not_found_response(channel_id: &str) -> Response<Body> {
+    json_response(
+        StatusCode::NOT_FOUND,
+        json!({"error": format!("No channel found for id {}", channel_id)}),
+    )
+}
+
+fn not_pending_response(channel_id: &str) -> Response<Body> {
+    json_response(
+        StatusCode::NOT_FOUND,
+        json!({"error": format!("Channel {} is not pending review", channel_id)}),
+    )
+}
+
+/// Leaves out the confirmation code, which only the mail to the owner carries.
+fn get_opt_out_requested_response(channel: &str) -> Response<Body> {
+    json_response(StatusCode::ACCEPTED, json!({ "channel": channel }))
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn json_response(status: StatusCode, body: Value) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::{Method, StatusCode};
+    use serde_json::{json, Value};
+
+    #[test]
+    fn only_probes_status_and_metrics_are_public() {
+        assert!(super::is_public_route(&Method::GET, &["healthz"]));
+        assert!(super::is_public_route(&Method::GET, &["metrics"]));
+        assert!(!super::is_public_route(&Method::GET, &["opt-outs"]));
+        assert!(!super::is_public_route(&Method::PUT, &["maintenance"]));
+    }
+
+    #[test]
+    fn compares_tokens() {
+        assert!(super::is_same_token("secret", "secret"));
+        assert!(!super::is_same_token("secreT", "secret"));
+        assert!(!super::is_same_token("secret2", "secret"));
+    }
+
+    #[tokio::test]
+    async fn opt_out_request_response_has_no_code() {
+        let response = super::get_opt_out_requested_response("UC123");
+
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body = serde_json::from_slice::<Value>(&bytes).unwrap();
+
+        assert_eq!(body, json!({"channel": "UC123"}));
+        assert!(body.get("confirmationCode").is_none());
+    }
+}
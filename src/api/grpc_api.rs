@@ -0,0 +1,311 @@
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use anyhow::Error;
+use futures::{Stream, StreamExt};
+use log::{error, info, warn};
+use mongodb::bson::{Bson, Document};
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
+use tonic::{transport::Server, Request, Response, Status};
+
+use crate::{
+    events::{broadcast_publisher::BroadcastPublisher, entity_event::EntityEvent},
+    repos::channel_store::ChannelStore,
+    services::crawl_request_service::{
+        CrawlRequestService, CRAWL_REQUEST_STATUS_BLOCKED, CRAWL_REQUEST_STATUS_DUPLICATE,
+        CRAWL_REQUEST_STATUS_KNOWN, CRAWL_REQUEST_STATUS_QUEUED,
+    },
+    utils::{crawl_request_utils::parse_requested_channel, health::Health},
+};
+
+pub mod proto {
+    tonic::include_proto!("crawler.v1");
+}
+
+use proto::{
+    crawl_event::Event,
+    crawler_control_server::{CrawlerControl, CrawlerControlServer},
+    ChannelStatus, CrawlEvent, GetChannelStatusRequest, GetQueueDepthRequest, QueueDepth,
+    StreamCrawlEventsRequest, SubmitChannelRequest, SubmitChannelResponse, SubmitStatus,
+};
+
+type CrawlEventStream = Pin<Box<dyn Stream<Item = Result<CrawlEvent, Status>> + Send>>;
+
+/// Serves the `CrawlerControl` service of `proto/crawler.proto`, so other services can submit
+/// channels and follow the crawler without reading its database.
+pub struct GrpcApi {
+    channel_repo: Box<dyn ChannelStore>,
+    crawl_request_service: Arc<CrawlRequestService>,
+    event_broadcast: Arc<BroadcastPublisher>,
+    health: Arc<Health>,
+}
+
+impl GrpcApi {
+    pub fn new(
+        channel_repo: Box<dyn ChannelStore>,
+        crawl_request_service: Arc<CrawlRequestService>,
+        event_broadcast: Arc<BroadcastPublisher>,
+        health: Arc<Health>,
+    ) -> GrpcApi {
+        GrpcApi {
+            channel_repo,
+            crawl_request_service,
+            event_broadcast,
+            health,
+        }
+    }
+
+    pub async fn serve(self, port: u16) -> Result<(), Error> {
+        let addr = SocketAddr::from(([0, 0, 0, 0], port));
+
+        info!("gRPC API listening on {}", addr);
+        Server::builder()
+            .add_service(CrawlerControlServer::new(self))
+            .serve(addr)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl CrawlerControl for GrpcApi {
+    async fn submit_channel(
+        &self,
+        request: Request<SubmitChannelRequest>,
+    ) -> Result<Response<SubmitChannelResponse>, Status> {
+        let channel = parse_requested_channel(&request.into_inner().channel)
+            .map_err(Status::invalid_argument)?;
+
+        let status = self
+            .crawl_request_service
+            .request(&channel)
+            .await
+            .map_err(|e| {
+                error!("Failed to handle submitted channel {}: {}", channel, e);
+                Status::internal("failed to submit channel")
+            })?;
+
+        let status = match status {
+            CRAWL_REQUEST_STATUS_QUEUED => SubmitStatus::Queued,
+            CRAWL_REQUEST_STATUS_KNOWN => SubmitStatus::Known,
+            CRAWL_REQUEST_STATUS_DUPLICATE => SubmitStatus::Duplicate,
+            CRAWL_REQUEST_STATUS_BLOCKED => SubmitStatus::Blocked,
+            _ => SubmitStatus::Unspecified,
+        };
+
+        Ok(Response::new(SubmitChannelResponse {
+            channel,
+            status: status.into(),
+        }))
+    }
+
+    async fn get_channel_status(
+        &self,
+        request: Request<GetChannelStatusRequest>,
+    ) -> Result<Response<ChannelStatus>, Status> {
+        let channel_id = request.into_inner().channel_id;
+
+        let channel = self
+            .channel_repo
+            .find_by_id(&channel_id)
+            .await
+            .map_err(|e| {
+                error!("Failed to get channel {}: {}", channel_id, e);
+                Status::internal("failed to get channel")
+            })?
+            .ok_or_else(|| Status::not_found(format!("Channel {} not found", channel_id)))?;
+
+        Ok(Response::new(get_channel_status(&channel_id, &channel)))
+    }
+
+    async fn get_queue_depth(
+        &self,
+        _request: Request<GetQueueDepthRequest>,
+    ) -> Result<Response<QueueDepth>, Status> {
+        let queues = self
+            .health
+            .get_queue_depths()
+            .await
+            .into_iter()
+            .map(|(name, depth)| (name, depth as u64))
+            .collect();
+
+        Ok(Response::new(QueueDepth { queues }))
+    }
+
+    type StreamCrawlEventsStream = CrawlEventStream;
+
+    async fn stream_crawl_events(
+        &self,
+        request: Request<StreamCrawlEventsRequest>,
+    ) -> Result<Response<Self::StreamCrawlEventsStream>, Status> {
+        let channel_ids = request
+            .into_inner()
+            .channel_ids
+            .into_iter()
+            .collect::<HashSet<String>>();
+
+        let stream =
+            BroadcastStream::new(self.event_broadcast.subscribe()).filter_map(move |event| {
+                let event = match event {
+                    Ok(event) if channel_ids.is_empty() || channel_ids.contains(event.key()) => {
+                        Some(Ok(CrawlEvent::from(&event)))
+                    }
+                    Ok(_) => None,
+                    Err(BroadcastStreamRecvError::Lagged(count)) => {
+                        warn!("Event stream subscriber missed {} events", count);
+                        None
+                    }
+                };
+
+                async move { event }
+            });
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+impl From<&EntityEvent> for CrawlEvent {
+    fn from(event: &EntityEvent) -> Self {
+        let (occurred_at, event_type) = match event {
+            EntityEvent::VideoUpserted {
+                video_id,
+                occurred_at,
+                ..
+            } => (
+                occurred_at,
+                Event::VideoUpserted(proto::VideoUpserted {
+                    video_id: video_id.to_string(),
+                }),
+            ),
+            EntityEvent::ChannelDiscovered { occurred_at, .. } => (
+                occurred_at,
+                Event::ChannelDiscovered(proto::ChannelDiscovered {}),
+            ),
+            EntityEvent::ChannelUpdated { occurred_at, .. } => {
+                (occurred_at, Event::ChannelUpdated(proto::ChannelUpdated {}))
+            }
+            EntityEvent::ChannelDeactivated {
+                reason,
+                occurred_at,
+                ..
+            } => (
+                occurred_at,
+                Event::ChannelDeactivated(proto::ChannelDeactivated {
+                    reason: reason.to_string(),
+                }),
+            ),
+            EntityEvent::ChannelRedirected {
+                redirects_to,
+                occurred_at,
+                ..
+            } => (
+                occurred_at,
+                Event::ChannelRedirected(proto::ChannelRedirected {
+                    redirects_to: redirects_to.to_string(),
+                }),
+            ),
+            EntityEvent::MilestoneReached {
+                milestone,
+                subscribers,
+                occurred_at,
+                ..
+            } => (
+                occurred_at,
+                Event::MilestoneReached(proto::MilestoneReached {
+                    milestone: *milestone,
+                    subscribers: *subscribers,
+                }),
+            ),
+            EntityEvent::TopicDriftDetected {
+                recent_density,
+                baseline_density,
+                occurred_at,
+                ..
+            } => (
+                occurred_at,
+                Event::TopicDriftDetected(proto::TopicDriftDetected {
+                    recent_density: *recent_density,
+                    baseline_density: *baseline_density,
+                }),
+            ),
+        };
+
+        CrawlEvent {
+            channel_id: event.key().to_string(),
+            occurred_at: *occurred_at,
+            event: Some(event_type),
+        }
+    }
+}
+
+fn get_channel_status(channel_id: &str, channel: &Document) -> ChannelStatus {
+    let get_str = |key| channel.get_str(key).unwrap_or_default().to_string();
+    let get_i64 = |key| match channel.get(key) {
+        Some(Bson::Int32(number)) => *number as i64,
+        Some(Bson::Int64(number)) => *number,
+        _ => 0,
+    };
+
+    let last_crawl_at = channel
+        .get_datetime("lastCrawl")
+        .map(|date| date.timestamp_millis() / 1000)
+        .unwrap_or(0);
+
+    ChannelStatus {
+        channel_id: channel_id.to_string(),
+        title: get_str("title"),
+        status: get_str("status"),
+        lifecycle: get_str("lifecycle"),
+        subscribers: get_i64("subscribers"),
+        views: get_i64("views"),
+        video_count: get_i64("videoCount"),
+        last_crawl_at,
+        last_upload_at: get_i64("lastUploadAt"),
+        deactivation_reason: get_str("deactivationReason"),
+        redirects_to: get_str("redirectsTo"),
+        topic_drift: channel.get_document("topicDrift").is_ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::{doc, DateTime};
+
+    use super::proto::{crawl_event::Event, CrawlEvent};
+    use crate::events::entity_event::EntityEvent;
+
+    #[test]
+    fn converts_entity_events_and_channels() {
+        let event = CrawlEvent::from(&EntityEvent::ChannelRedirected {
+            channel_id: "UC1".to_string(),
+            redirects_to: "UC2".to_string(),
+            occurred_at: 1650000000,
+        });
+
+        assert_eq!(event.channel_id, "UC1");
+        assert_eq!(event.occurred_at, 1650000000);
+        assert!(
+            matches!(event.event, Some(Event::ChannelRedirected(e)) if e.redirects_to == "UC2")
+        );
+
+        let status = super::get_channel_status(
+            "UC1",
+            &doc! {
+                "title": "Guitar",
+                "status": "active",
+                "subscribers": 1200i32,
+                "lastCrawl": DateTime::from_millis(1650000000000),
+                "topicDrift": {"recentDensity": 0.1},
+            },
+        );
+
+        assert_eq!(status.subscribers, 1200);
+        assert_eq!(status.last_crawl_at, 1650000000);
+        assert_eq!(status.video_count, 0);
+        assert!(status.topic_drift);
+    }
+}
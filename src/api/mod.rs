@@ -0,0 +1,2 @@
+pub mod admin_api;
+pub mod grpc_api;
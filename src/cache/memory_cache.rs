@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::Error;
+use async_trait::async_trait;
+
+use crate::cache::response_cache::ResponseCache;
+
+/// Keeps responses in process. When full, expired entries are dropped first, then the entry
+/// closest to expiring.
+pub struct MemoryCache {
+    entries: Mutex<HashMap<String, (Instant, String)>>,
+    ttl: Duration,
+    max_entries: usize,
+}
+
+impl MemoryCache {
+    pub fn new(ttl: Duration, max_entries: usize) -> MemoryCache {
+        MemoryCache {
+            entries: Mutex::new(HashMap::new()),
+            ttl,
+            max_entries,
+        }
+    }
+}
+
+#[async_trait]
+impl ResponseCache for MemoryCache {
+    async fn get(&self, key: &str) -> Result<Option<String>, Error> {
+        let entries = self.entries.lock().unwrap();
+
+        let body = entries
+            .get(key)
+            .filter(|(expires_at, _)| *expires_at > Instant::now())
+            .map(|(_, body)| body.to_string());
+
+        Ok(body)
+    }
+
+    async fn set(&self, key: &str, body: &str) -> Result<(), Error> {
+        let mut entries = self.entries.lock().unwrap();
+        let now = Instant::now();
+
+        if entries.len() >= self.max_entries && !entries.contains_key(key) {
+            entries.retain(|_, (expires_at, _)| *expires_at > now);
+        }
+
+        if entries.len() >= self.max_entries && !entries.contains_key(key) {
+            let first_expiring = entries
+                .iter()
+                .min_by_key(|(_, (expires_at, _))| *expires_at)
+                .map(|(key, _)| key.to_string());
+
+            if let Some(first_expiring) = first_expiring {
+                entries.remove(&first_expiring);
+            }
+        }
+
+        entries.insert(key.to_string(), (now + self.ttl, body.to_string()));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::MemoryCache;
+    use crate::cache::response_cache::ResponseCache;
+
+    #[tokio::test]
+    async fn evicts_first_expiring_entry_when_full() {
+        let cache = MemoryCache::new(Duration::from_secs(60), 2);
+
+        cache.set("first", "1").await.unwrap();
+        cache.set("second", "2").await.unwrap();
+        cache.set("third", "3").await.unwrap();
+
+        let first = cache.get("first").await.unwrap();
+        let second = cache.get("second").await.unwrap();
+
+        assert!(first.is_none() || second.is_none());
+        assert_eq!(cache.get("third").await.unwrap(), Some("3".to_string()));
+        assert_eq!(cache.entries.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn expired_entries_are_missing() {
+        let cache = MemoryCache::new(Duration::ZERO, 10);
+
+        cache.set("key", "body").await.unwrap();
+
+        assert_eq!(cache.get("key").await.unwrap(), None);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod memory_cache;
+pub mod redis_cache;
+pub mod response_cache;
@@ -0,0 +1,42 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use redis::{aio::ConnectionManager, AsyncCommands};
+
+use crate::cache::response_cache::ResponseCache;
+
+/// Shares responses between crawler instances. Redis expires the entries itself.
+pub struct RedisCache {
+    connection: ConnectionManager,
+    ttl_seconds: usize,
+}
+
+impl RedisCache {
+    pub async fn connect(url: &str, ttl_seconds: usize) -> Result<RedisCache, Error> {
+        let client = redis::Client::open(url)?;
+        let connection = ConnectionManager::new(client).await?;
+
+        Ok(RedisCache {
+            connection,
+            ttl_seconds,
+        })
+    }
+}
+
+#[async_trait]
+impl ResponseCache for RedisCache {
+    async fn get(&self, key: &str) -> Result<Option<String>, Error> {
+        let mut connection = self.connection.clone();
+        let body: Option<String> = connection.get(key).await?;
+
+        Ok(body)
+    }
+
+    async fn set(&self, key: &str, body: &str) -> Result<(), Error> {
+        let mut connection = self.connection.clone();
+        connection
+            .set_ex::<_, _, ()>(key, body, self.ttl_seconds)
+            .await?;
+
+        Ok(())
+    }
+}
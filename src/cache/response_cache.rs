@@ -0,0 +1,42 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use reqwest::Url;
+
+/// Caches raw api response bodies for the configured time to live, implemented in memory by
+/// `MemoryCache` and shared between instances by `RedisCache`.
+#[async_trait]
+pub trait ResponseCache: Send + Sync {
+    async fn get(&self, key: &str) -> Result<Option<String>, Error>;
+
+    async fn set(&self, key: &str, body: &str) -> Result<(), Error>;
+}
+
+/// Builds the cache key from the endpoint and its params. The api key is left out since any key
+/// gets the same response.
+pub fn get_cache_key(url: &str) -> String {
+    let url = match Url::parse(url) {
+        Ok(url) => url,
+        Err(_) => return url.to_string(),
+    };
+
+    let params = url
+        .query_pairs()
+        .filter(|(name, _)| name != "key")
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect::<Vec<String>>();
+
+    format!("youtube:{}?{}", url.path(), params.join("&"))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn cache_key_without_api_key() {
+        assert_eq!(
+            super::get_cache_key(
+                "https://www.googleapis.com/youtube/v3/channels?part=id&forHandle=@handle&key=secret"
+            ),
+            "youtube:/youtube/v3/channels?part=id&forHandle=@handle"
+        );
+    }
+}
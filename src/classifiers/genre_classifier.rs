@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Error};
+use mongodb::bson::{DateTime, Document};
+
+use crate::{
+    classifiers::rule_based_genre_classifier::RuleBasedGenreClassifier,
+    utils::consts::GENRE_CLASSIFIER_RULES,
+};
+
+// A channel gets a genre tag once its videos score this high for the genre
+const MIN_GENRE_TAG_SCORE: f64 = 0.25;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenreScore {
+    /// One of the `GENRE_` consts
+    pub genre: &'static str,
+    /// Between 0 and 1
+    pub score: f64,
+}
+
+/// Scores how much a channel is about each genre or style from the texts of its latest videos.
+/// Implemented by the keyword rules of `RuleBasedGenreClassifier`, selected by
+/// `classification.genre_classifier`.
+pub trait GenreClassifier: Send + Sync {
+    /// Scores of the genres found in the videos, best first. Videos hold `title`, `description`
+    /// and `tags`.
+    fn score(&self, videos: &[Document]) -> Vec<GenreScore>;
+
+    /// The genres the channel is tagged with, best first.
+    fn get_genre_tags(&self, videos: &[Document]) -> Vec<GenreScore> {
+        self.score(videos)
+            .into_iter()
+            .filter(|genre| genre.score >= MIN_GENRE_TAG_SCORE)
+            .collect()
+    }
+}
+
+pub fn build_genre_classifier(name: &str) -> Result<Arc<dyn GenreClassifier>, Error> {
+    match name {
+        GENRE_CLASSIFIER_RULES => Ok(Arc::new(RuleBasedGenreClassifier::new())),
+        name => Err(anyhow!("Unknown genre classifier {}", name)),
+    }
+}
+
+/// The fields stored on the channel: `genreTags` with the genre names and `genreScores` by genre.
+pub fn get_genre_tags_document(genres: &[GenreScore]) -> Document {
+    let mut scores = Document::new();
+    for genre in genres {
+        scores.insert(genre.genre, genre.score);
+    }
+
+    let mut document = Document::new();
+    document.insert(
+        "genreTags",
+        genres
+            .iter()
+            .map(|genre| genre.genre)
+            .collect::<Vec<&str>>(),
+    );
+    document.insert("genreScores", scores);
+    document.insert("genreTagsUpdatedAt", DateTime::now());
+
+    document
+}
@@ -0,0 +1,4 @@
+pub mod genre_classifier;
+pub mod rule_based_genre_classifier;
+pub mod rule_based_video_type_classifier;
+pub mod video_type_classifier;
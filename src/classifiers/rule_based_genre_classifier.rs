@@ -0,0 +1,223 @@
+use mongodb::bson::Document;
+use regex::Regex;
+
+use crate::{
+    classifiers::genre_classifier::{GenreClassifier, GenreScore},
+    utils::{
+        consts::{
+            GENRE_BASS, GENRE_BLUES, GENRE_CLASSICAL, GENRE_GEAR_REVIEWS, GENRE_LUTHIER,
+            GENRE_METAL,
+        },
+        document_utils::get_strings,
+    },
+};
+
+// Fewer videos say too little about a channel
+const MIN_VIDEOS: usize = 5;
+// A keyword only in the description counts less than one in the title or tags
+const DESCRIPTION_WEIGHT: f64 = 0.5;
+
+const GENRE_KEYWORDS: [(&str, &[&str]); 6] = [
+    (
+        GENRE_BLUES,
+        &[
+            "blues",
+            "12 bar",
+            "delta blues",
+            "slide guitar",
+            "srv",
+            "bb king",
+        ],
+    ),
+    (
+        GENRE_METAL,
+        &[
+            "metal",
+            "metalcore",
+            "djent",
+            "thrash",
+            "death metal",
+            "breakdown",
+            "drop [a-d]",
+            "7[- ]string",
+            "8[- ]string",
+        ],
+    ),
+    (
+        GENRE_CLASSICAL,
+        &[
+            "classical guitar",
+            "classical",
+            "bach",
+            "tarrega",
+            "villa[- ]lobos",
+            "flamenco",
+            "nylon string",
+        ],
+    ),
+    (
+        GENRE_BASS,
+        &[
+            "bass",
+            "bass guitar",
+            "bassist",
+            "slap",
+            "bass line",
+            "bassline",
+        ],
+    ),
+    (
+        GENRE_LUTHIER,
+        &[
+            "luthier",
+            "lutherie",
+            "guitar build",
+            "building a guitar",
+            "fret ?work",
+            "refret",
+            "setup",
+            "restoration",
+            "repair",
+        ],
+    ),
+    (
+        GENRE_GEAR_REVIEWS,
+        &[
+            "review",
+            "demo",
+            "unboxing",
+            "shootout",
+            "pedal",
+            "amp",
+            "first impressions",
+            "ngd",
+        ],
+    ),
+];
+
+/// Scores each genre by the share of the videos naming one of its keywords, a match in the
+/// description alone counting half.
+pub struct RuleBasedGenreClassifier {
+    genres: Vec<(&'static str, Regex)>,
+}
+
+impl RuleBasedGenreClassifier {
+    pub fn new() -> RuleBasedGenreClassifier {
+        let genres = GENRE_KEYWORDS
+            .iter()
+            .map(|(genre, keywords)| {
+                let pattern = format!(r"(?i)\b(?:{})(?:\W|$)", keywords.join("|"));
+
+                (*genre, Regex::new(&pattern).unwrap())
+            })
+            .collect();
+
+        RuleBasedGenreClassifier { genres }
+    }
+}
+
+impl Default for RuleBasedGenreClassifier {
+    fn default() -> Self {
+        RuleBasedGenreClassifier::new()
+    }
+}
+
+impl GenreClassifier for RuleBasedGenreClassifier {
+    fn score(&self, videos: &[Document]) -> Vec<GenreScore> {
+        if videos.len() < MIN_VIDEOS {
+            return vec![];
+        }
+
+        let texts = videos
+            .iter()
+            .map(|video| {
+                let title_and_tags = format!(
+                    "{}\n{}",
+                    video.get_str("title").unwrap_or_default(),
+                    get_strings(video, "tags").join("\n")
+                );
+
+                (
+                    title_and_tags,
+                    video.get_str("description").unwrap_or_default(),
+                )
+            })
+            .collect::<Vec<(String, &str)>>();
+
+        let mut scores = self
+            .genres
+            .iter()
+            .map(|(genre, regex)| {
+                let matches = texts
+                    .iter()
+                    .map(|(title_and_tags, description)| {
+                        if regex.is_match(title_and_tags) {
+                            1.0
+                        } else if regex.is_match(description) {
+                            DESCRIPTION_WEIGHT
+                        } else {
+                            0.0
+                        }
+                    })
+                    .sum::<f64>();
+
+                GenreScore {
+                    genre,
+                    score: matches / videos.len() as f64,
+                }
+            })
+            .filter(|genre| genre.score > 0.0)
+            .collect::<Vec<GenreScore>>();
+
+        scores.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap()
+                .then_with(|| a.genre.cmp(b.genre))
+        });
+
+        scores
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::doc;
+
+    use crate::{
+        classifiers::genre_classifier::GenreClassifier,
+        utils::consts::{GENRE_BLUES, GENRE_GEAR_REVIEWS, GENRE_METAL},
+    };
+
+    use super::RuleBasedGenreClassifier;
+
+    #[test]
+    fn tags_the_main_genres() {
+        let classifier = RuleBasedGenreClassifier::new();
+        let videos = vec![
+            doc! {"title": "Slow blues in A", "tags": ["blues", "improv"]},
+            doc! {"title": "SRV style shuffle", "description": "Texas blues licks"},
+            doc! {"title": "12 bar blues for beginners"},
+            doc! {"title": "Sunday jam", "description": "A blues jam with the band"},
+            doc! {"title": "Overdrive pedal review", "tags": ["blues"]},
+            doc! {"title": "Q&A"},
+        ];
+
+        let genres = classifier.get_genre_tags(&videos);
+
+        assert_eq!(
+            genres
+                .iter()
+                .map(|genre| genre.genre)
+                .collect::<Vec<&str>>(),
+            vec![GENRE_BLUES]
+        );
+        assert_eq!(genres[0].score, 4.5 / 6.0);
+
+        let scores = classifier.score(&videos);
+        assert_eq!(scores[1].genre, GENRE_GEAR_REVIEWS);
+        assert!(!scores.iter().any(|genre| genre.genre == GENRE_METAL));
+
+        assert!(classifier.score(&videos[..4]).is_empty());
+    }
+}
@@ -0,0 +1,211 @@
+use regex::Regex;
+
+use crate::{
+    classifiers::video_type_classifier::{VideoTypeClassification, VideoTypeClassifier},
+    utils::consts::{
+        VIDEO_TYPE_COVER, VIDEO_TYPE_LESSON, VIDEO_TYPE_PERFORMANCE, VIDEO_TYPE_REVIEW,
+        VIDEO_TYPE_VLOG,
+    },
+};
+
+// A keyword in the title says more about a video than one in its tags or description
+const TITLE_WEIGHT: f64 = 3.0;
+const TAGS_WEIGHT: f64 = 2.0;
+const DESCRIPTION_WEIGHT: f64 = 1.0;
+// Scores below a title match lower the confidence
+const CONFIDENT_SCORE: f64 = 3.0;
+
+const VIDEO_TYPE_KEYWORDS: [(&str, &[&str]); 5] = [
+    (
+        VIDEO_TYPE_LESSON,
+        &[
+            "lesson",
+            "tutorial",
+            "how to play",
+            "learn",
+            "licks?",
+            "exercises?",
+            "technique",
+            "masterclass",
+        ],
+    ),
+    (VIDEO_TYPE_COVER, &["cover", "covers", "covered"]),
+    (
+        VIDEO_TYPE_REVIEW,
+        &[
+            "review",
+            "demo",
+            "unboxing",
+            "shootout",
+            "comparison",
+            "vs\\.?",
+            "first impressions",
+            "ngd",
+        ],
+    ),
+    (
+        VIDEO_TYPE_VLOG,
+        &[
+            "vlog",
+            "q&a",
+            "day in the life",
+            "behind the scenes",
+            "studio tour",
+            "update",
+            "story time",
+        ],
+    ),
+    (
+        VIDEO_TYPE_PERFORMANCE,
+        &[
+            "live",
+            "performance",
+            "playthrough",
+            "jam",
+            "improv",
+            "improvisation",
+            "original song",
+            "session",
+        ],
+    ),
+];
+
+const DIFFICULTY_KEYWORDS: [(&str, &[&str]); 3] = [
+    ("beginner", &["beginners?", "easy", "first lesson"]),
+    ("intermediate", &["intermediate"]),
+    ("advanced", &["advanced", "hard", "shred"]),
+];
+
+/// Scores each video type by its keywords in the title, tags and description. The confidence is
+/// the share of the best type in all scores, lowered when only tags or the description matched.
+pub struct RuleBasedVideoTypeClassifier {
+    video_types: Vec<(&'static str, Regex)>,
+    difficulties: Vec<(&'static str, Regex)>,
+}
+
+impl RuleBasedVideoTypeClassifier {
+    pub fn new() -> RuleBasedVideoTypeClassifier {
+        RuleBasedVideoTypeClassifier {
+            video_types: build_matchers(&VIDEO_TYPE_KEYWORDS),
+            difficulties: build_matchers(&DIFFICULTY_KEYWORDS),
+        }
+    }
+
+    fn get_difficulty(&self, title: &str, tags: &str) -> Option<&'static str> {
+        self.difficulties
+            .iter()
+            .find(|(_, regex)| regex.is_match(title) || regex.is_match(tags))
+            .map(|(difficulty, _)| *difficulty)
+    }
+}
+
+impl Default for RuleBasedVideoTypeClassifier {
+    fn default() -> Self {
+        RuleBasedVideoTypeClassifier::new()
+    }
+}
+
+impl VideoTypeClassifier for RuleBasedVideoTypeClassifier {
+    fn classify(
+        &self,
+        title: &str,
+        description: &str,
+        tags: &[String],
+    ) -> Option<VideoTypeClassification> {
+        let tags = tags.join("\n");
+
+        let scores = self
+            .video_types
+            .iter()
+            .map(|(video_type, regex)| {
+                let score = [
+                    (title, TITLE_WEIGHT),
+                    (tags.as_str(), TAGS_WEIGHT),
+                    (description, DESCRIPTION_WEIGHT),
+                ]
+                .iter()
+                .filter(|(text, _)| regex.is_match(text))
+                .map(|(_, weight)| weight)
+                .sum::<f64>();
+
+                (*video_type, score)
+            })
+            .collect::<Vec<(&str, f64)>>();
+
+        let total = scores.iter().map(|(_, score)| score).sum::<f64>();
+        let (video_type, best) = scores
+            .into_iter()
+            .fold(
+                None,
+                |best: Option<(&str, f64)>, (video_type, score)| match best {
+                    Some((_, best_score)) if best_score >= score => best,
+                    _ => Some((video_type, score)),
+                },
+            )
+            .filter(|(_, score)| *score > 0.0)?;
+
+        let difficulty = if video_type == VIDEO_TYPE_LESSON {
+            self.get_difficulty(title, &tags)
+        } else {
+            None
+        };
+
+        Some(VideoTypeClassification {
+            video_type,
+            confidence: best / total * (best / CONFIDENT_SCORE).min(1.0),
+            difficulty,
+        })
+    }
+}
+
+fn build_matchers(keywords: &[(&'static str, &[&str])]) -> Vec<(&'static str, Regex)> {
+    keywords
+        .iter()
+        .map(|(label, keywords)| {
+            let pattern = format!(r"(?i)\b(?:{})(?:\W|$)", keywords.join("|"));
+
+            (*label, Regex::new(&pattern).unwrap())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        classifiers::video_type_classifier::{VideoTypeClassification, VideoTypeClassifier},
+        utils::consts::{VIDEO_TYPE_LESSON, VIDEO_TYPE_REVIEW},
+    };
+
+    use super::RuleBasedVideoTypeClassifier;
+
+    #[test]
+    fn classify_by_title() {
+        let classifier = RuleBasedVideoTypeClassifier::new();
+
+        assert_eq!(
+            classifier.classify("Easy blues lick lesson for beginners", "", &[]),
+            Some(VideoTypeClassification {
+                video_type: VIDEO_TYPE_LESSON,
+                confidence: 1.0,
+                difficulty: Some("beginner"),
+            })
+        );
+        assert_eq!(classifier.classify("Sunday afternoon", "", &[]), None);
+    }
+
+    #[test]
+    fn lower_confidence_on_mixed_matches() {
+        let classifier = RuleBasedVideoTypeClassifier::new();
+        let classification = classifier
+            .classify(
+                "Helix vs Kemper",
+                "Full lesson on the patches below",
+                &["review".to_string()],
+            )
+            .unwrap();
+
+        assert_eq!(classification.video_type, VIDEO_TYPE_REVIEW);
+        assert!(classification.confidence > 0.5 && classification.confidence < 1.0);
+        assert_eq!(classification.difficulty, None);
+    }
+}
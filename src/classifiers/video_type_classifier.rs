@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Error};
+use mongodb::bson::Document;
+
+use crate::{
+    classifiers::rule_based_video_type_classifier::RuleBasedVideoTypeClassifier,
+    utils::{consts::VIDEO_TYPE_CLASSIFIER_RULES, document_utils::get_strings},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VideoTypeClassification {
+    /// One of the `VIDEO_TYPE_` consts
+    pub video_type: &'static str,
+    /// Between 0 and 1
+    pub confidence: f64,
+    /// `beginner`, `intermediate` or `advanced` when the video names a level
+    pub difficulty: Option<&'static str>,
+}
+
+/// Labels videos as lesson, cover, review, vlog or performance. Implemented by the keyword rules
+/// of `RuleBasedVideoTypeClassifier`, selected by `classification.video_type_classifier`.
+pub trait VideoTypeClassifier: Send + Sync {
+    fn classify(
+        &self,
+        title: &str,
+        description: &str,
+        tags: &[String],
+    ) -> Option<VideoTypeClassification>;
+
+    /// Stores `videoType` on a video document, after its tags were added.
+    fn append_video_type(&self, video: &mut Document) {
+        let classification = self.classify(
+            video.get_str("title").unwrap_or_default(),
+            video.get_str("description").unwrap_or_default(),
+            &get_strings(video, "tags"),
+        );
+
+        if let Some(classification) = classification {
+            video.insert("videoType", classification.video_type);
+            video.insert("videoTypeConfidence", classification.confidence);
+
+            if let Some(difficulty) = classification.difficulty {
+                video.insert("difficulty", difficulty);
+            }
+        }
+    }
+}
+
+pub fn build_video_type_classifier(name: &str) -> Result<Arc<dyn VideoTypeClassifier>, Error> {
+    match name {
+        VIDEO_TYPE_CLASSIFIER_RULES => Ok(Arc::new(RuleBasedVideoTypeClassifier::new())),
+        name => Err(anyhow!("Unknown video type classifier {}", name)),
+    }
+}
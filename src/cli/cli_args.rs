@@ -0,0 +1,193 @@
+use clap::{Parser, Subcommand};
+
+use crate::export::exporter::ExportFormat;
+use crate::utils::consts::CHANNEL_SOURCE_IMPORT;
+
+/// Runs the crawler daemon, or a single operation when a subcommand is given.
+#[derive(Debug, Parser)]
+#[command(name = "crawler")]
+pub struct CliArgs {
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CliCommand {
+    /// Add or scrape a single channel
+    Channel {
+        #[command(subcommand)]
+        command: ChannelCommand,
+    },
+    /// Backfill the full upload history of a channel
+    Backfill {
+        /// Channel id
+        channel_id: String,
+    },
+    /// Keep channels from being discovered again
+    Blocklist {
+        #[command(subcommand)]
+        command: BlocklistCommand,
+    },
+    /// Manage the channels added for crawling
+    Additional {
+        #[command(subcommand)]
+        command: AdditionalCommand,
+    },
+    /// Run the channel discovery
+    Discovery {
+        #[command(subcommand)]
+        command: DiscoveryCommand,
+    },
+    /// Import channels from a file
+    Import {
+        #[command(subcommand)]
+        command: ImportCommand,
+    },
+    /// Export stored channels or videos
+    Export {
+        #[command(subcommand)]
+        command: ExportCommand,
+    },
+    /// Print channel and video counts of the corpus
+    Stats,
+    /// Apply the pending schema migrations of the MongoDB database
+    Migrate {
+        /// Only list the pending migrations
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Delete stored data
+    Purge {
+        #[command(subcommand)]
+        command: PurgeCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ChannelCommand {
+    /// Queue a channel for the additional channel crawler
+    Add {
+        /// Channel id, @handle or channel url
+        channel: String,
+        #[arg(long)]
+        ignore_guitar_terms: bool,
+    },
+    /// Scrape a channel and its latest videos right away
+    Scrape {
+        /// Channel id, @handle or channel url
+        channel: String,
+        #[arg(long)]
+        ignore_guitar_terms: bool,
+    },
+    /// Merge a duplicate channel entry, its videos and stats into the canonical channel
+    Merge {
+        /// Channel id of the duplicate
+        channel_id: String,
+        /// Channel id to keep
+        canonical_id: String,
+    },
+    /// Show the latest scrape and discovery runs of a channel
+    Crawls {
+        /// Channel id
+        channel_id: String,
+        #[arg(long, default_value_t = 10)]
+        limit: i64,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum BlocklistCommand {
+    /// Block a channel, it also leaves the additional channels
+    Add {
+        /// Channel id, @handle or channel url
+        channel: String,
+        #[arg(long)]
+        reason: String,
+    },
+    /// Allow a blocked channel to be discovered again
+    Remove {
+        /// Channel id, @handle or channel url
+        channel: String,
+    },
+    /// List the blocked channels
+    List,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AdditionalCommand {
+    /// List the channels waiting for the additional channel crawler
+    List,
+    /// Remove a channel from the additional channels
+    Remove {
+        /// Channel id or @handle
+        channel: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PurgeCommand {
+    /// Delete all stored data of a channel and anonymize its audit entries
+    Channel {
+        /// Channel id
+        channel_id: String,
+        /// Only count the affected documents per collection
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ImportCommand {
+    /// Scrape the channels listed in a CSV or JSON file
+    Channels {
+        /// CSV file, or JSON file ending in .json, of channel ids, handles or urls
+        file: String,
+        /// Stored as the source of newly added channels
+        #[arg(long, default_value = CHANNEL_SOURCE_IMPORT)]
+        source: String,
+        #[arg(long)]
+        ignore_guitar_terms: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ExportCommand {
+    /// Export channels, optionally only those with enough subscribers
+    Channels {
+        #[command(flatten)]
+        options: ExportOptions,
+        #[arg(long)]
+        min_subscribers: Option<i64>,
+    },
+    /// Export the hot videos, optionally of a single channel
+    Videos {
+        #[command(flatten)]
+        options: ExportOptions,
+        #[arg(long)]
+        channel_id: Option<String>,
+    },
+}
+
+#[derive(Debug, clap::Args)]
+pub struct ExportOptions {
+    #[arg(long, value_enum, default_value_t = ExportFormat::Ndjson)]
+    pub format: ExportFormat,
+    /// Comma separated fields, dotted paths select nested fields
+    #[arg(long, value_delimiter = ',')]
+    pub fields: Vec<String>,
+    /// File to write to instead of stdout
+    #[arg(long)]
+    pub output: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DiscoveryCommand {
+    /// Check the subscriptions of all active channels once and scrape new guitar channels
+    RunOnce,
+    /// Print the acceptance rate and api cost of the channels queued by each source
+    Report {
+        /// Only count channels decided in the last days
+        #[arg(long, default_value_t = 30)]
+        days: i64,
+    },
+}
@@ -0,0 +1 @@
+pub mod cli_args;
@@ -0,0 +1,4 @@
+#[derive(Debug)]
+pub struct CrawlAboutCommand {
+    pub channel_id: String,
+}
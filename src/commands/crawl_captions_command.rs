@@ -0,0 +1,5 @@
+#[derive(Debug)]
+pub struct CrawlCaptionsCommand {
+    pub video_id: String,
+    pub channel_id: String,
+}
@@ -1,5 +1,46 @@
-#[derive(Debug)]
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    models::youtube_channel_details::YoutubeStatisticsItem,
+    queue::idempotency_filter::get_idempotency_key,
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct CrawlChannelCommand {
+    /// Either a channel id or an `@handle`, which the channel scraper resolves to a channel id.
     pub channel_id: String,
     pub ignore_guitar_terms: bool,
+    /// Stored as `source` when the channel is added, e.g. `discovery` or `import`
+    pub source: Option<String>,
+    /// Commands with the same key are crawled once, empty for commands queued without one
+    #[serde(default)]
+    pub idempotency_key: String,
+    /// `channels.list` details the sender already paid for, the scraper does not load them again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel_details: Option<Box<YoutubeStatisticsItem>>,
+    /// How often the command was handed back to the queue to be retried
+    #[serde(default)]
+    pub retries: u32,
+}
+
+impl CrawlChannelCommand {
+    pub fn new(
+        channel_id: String,
+        ignore_guitar_terms: bool,
+        source: Option<String>,
+    ) -> CrawlChannelCommand {
+        let idempotency_key =
+            get_idempotency_key(&channel_id, ignore_guitar_terms, Utc::now().timestamp());
+
+        CrawlChannelCommand {
+            channel_id,
+            ignore_guitar_terms,
+            source,
+            idempotency_key,
+            channel_details: None,
+            retries: 0,
+        }
+    }
 }
@@ -1,2 +1,4 @@
+pub mod crawl_about_command;
+pub mod crawl_captions_command;
 pub mod crawl_channel_command;
 pub mod crawl_videos_command;
@@ -0,0 +1,88 @@
+use chrono::{Duration as ChronoDuration, Utc};
+use log::info;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::Sender;
+use tokio::time::sleep;
+
+use crate::{
+    commands::crawl_about_command::CrawlAboutCommand,
+    errors::crawler_error::CrawlerError,
+    repos::{channel_store::ChannelStore, lock_store::LockStore},
+    utils::{health::Health, maintenance::Maintenance},
+};
+
+const CHANNELS_PER_CRAWL: i64 = 100;
+// About texts rarely change, a monthly refresh is enough
+const ABOUT_REFRESH_DAYS: i64 = 30;
+
+const LOCK_NAME: &str = "aboutCrawler";
+
+pub struct AboutCrawler {
+    sender: Sender<CrawlAboutCommand>,
+    channel_repo: Box<dyn ChannelStore>,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    health: Arc<Health>,
+}
+
+impl AboutCrawler {
+    pub fn new(
+        sender: Sender<CrawlAboutCommand>,
+        channel_repo: Box<dyn ChannelStore>,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        health: Arc<Health>,
+    ) -> AboutCrawler {
+        AboutCrawler {
+            sender,
+            channel_repo,
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            health,
+        }
+    }
+
+    pub async fn crawl(&self) -> Result<(), CrawlerError> {
+        loop {
+            self.maintenance.checkpoint("about crawler").await;
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
+            info!("Start about crawler");
+
+            let crawled_before = Utc::now() - ChronoDuration::days(ABOUT_REFRESH_DAYS);
+            let channels = self
+                .channel_repo
+                .get_ids_about_crawled_before(crawled_before, CHANNELS_PER_CRAWL)
+                .await?;
+
+            info!(
+                "Found {} channels with outdated about links",
+                channels.len()
+            );
+
+            for channel_id in channels {
+                self.sender.send(CrawlAboutCommand { channel_id }).await?;
+            }
+
+            self.health.record_success(LOCK_NAME).await;
+
+            info!(
+                "Wait for {} seconds until next crawl",
+                self.interval_seconds
+            );
+
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+}
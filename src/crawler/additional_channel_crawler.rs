@@ -1,32 +1,59 @@
-use anyhow::Error;
 use log::info;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc::Sender;
 use tokio::time::sleep;
 
 use crate::commands::crawl_channel_command::CrawlChannelCommand;
-use crate::repos::additional_channel_repo::AdditionalChannelRepository;
+use crate::errors::crawler_error::CrawlerError;
+use crate::repos::additional_channel_store::AdditionalChannelStore;
+use crate::repos::lock_store::LockStore;
+use crate::utils::{consts::CHANNEL_SOURCE_ADDITIONAL, health::Health, maintenance::Maintenance};
 
-const TEN_MINUTES_IN_SECONDS: u64 = 10 * 60;
+const LOCK_NAME: &str = "additionalChannelCrawler";
 
 pub struct AdditionalChannelCrawler {
     sender: Sender<CrawlChannelCommand>,
-    additional_channel_repo: AdditionalChannelRepository,
+    additional_channel_repo: Box<dyn AdditionalChannelStore>,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    health: Arc<Health>,
 }
 
 impl AdditionalChannelCrawler {
     pub fn new(
         sender: Sender<CrawlChannelCommand>,
-        additional_channel_repo: AdditionalChannelRepository,
+        additional_channel_repo: Box<dyn AdditionalChannelStore>,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        health: Arc<Health>,
     ) -> AdditionalChannelCrawler {
         AdditionalChannelCrawler {
             sender,
             additional_channel_repo,
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            health,
         }
     }
 
-    pub async fn crawl(&self) -> Result<(), Error> {
+    pub async fn crawl(&self) -> Result<(), CrawlerError> {
         loop {
+            self.maintenance
+                .checkpoint("additional channel crawler")
+                .await;
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
             info!("Start additional channel crawler");
             let additional_channels = self.additional_channel_repo.get_all().await?;
 
@@ -38,22 +65,25 @@ impl AdditionalChannelCrawler {
 
                 info!("Send additional channel for crawling: {}", channel_id);
 
-                let cmd = CrawlChannelCommand {
-                    channel_id: channel_id.clone(),
+                let cmd = CrawlChannelCommand::new(
+                    channel_id.clone(),
                     ignore_guitar_terms,
-                };
+                    Some(CHANNEL_SOURCE_ADDITIONAL.to_string()),
+                );
 
                 self.sender.send(cmd).await?;
 
                 self.additional_channel_repo.delete_one(&channel_id).await?;
             }
 
+            self.health.record_success(LOCK_NAME).await;
+
             info!(
                 "Wait for {} seconds until next crawl",
-                TEN_MINUTES_IN_SECONDS
+                self.interval_seconds
             );
 
-            sleep(Duration::from_secs(TEN_MINUTES_IN_SECONDS)).await;
+            sleep(Duration::from_secs(self.interval_seconds)).await;
         }
     }
 }
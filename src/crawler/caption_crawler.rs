@@ -0,0 +1,98 @@
+use chrono::Utc;
+use log::info;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::Sender;
+use tokio::time::sleep;
+
+use crate::{
+    commands::crawl_captions_command::CrawlCaptionsCommand,
+    errors::crawler_error::CrawlerError,
+    repos::{
+        channel_probation_store::ChannelProbationStore, lock_store::LockStore,
+        video_store::VideoStore,
+    },
+    utils::{health::Health, maintenance::Maintenance},
+};
+
+const VIDEOS_PER_CRAWL: i64 = 500;
+
+const LOCK_NAME: &str = "captionCrawler";
+
+pub struct CaptionCrawler {
+    sender: Sender<CrawlCaptionsCommand>,
+    video_repo: Box<dyn VideoStore>,
+    /// Channels on probation get captions once confirmed
+    probation_repo: Option<Box<dyn ChannelProbationStore>>,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    health: Arc<Health>,
+}
+
+impl CaptionCrawler {
+    pub fn new(
+        sender: Sender<CrawlCaptionsCommand>,
+        video_repo: Box<dyn VideoStore>,
+        probation_repo: Option<Box<dyn ChannelProbationStore>>,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        health: Arc<Health>,
+    ) -> CaptionCrawler {
+        CaptionCrawler {
+            sender,
+            video_repo,
+            probation_repo,
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            health,
+        }
+    }
+
+    pub async fn crawl(&self) -> Result<(), CrawlerError> {
+        loop {
+            self.maintenance.checkpoint("caption crawler").await;
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
+            info!("Start caption crawler");
+
+            let probation_ids = match &self.probation_repo {
+                Some(probation_repo) => probation_repo.get_undecided_ids().await?,
+                None => vec![],
+            };
+            let videos = self
+                .video_repo
+                .get_ids_without_captions(Utc::now().timestamp(), &probation_ids, VIDEOS_PER_CRAWL)
+                .await?;
+
+            info!("Found {} videos without captions", videos.len());
+
+            for (video_id, channel_id) in videos {
+                let command = CrawlCaptionsCommand {
+                    video_id,
+                    channel_id,
+                };
+
+                self.sender.send(command).await?;
+            }
+
+            self.health.record_success(LOCK_NAME).await;
+
+            info!(
+                "Wait for {} seconds until next crawl",
+                self.interval_seconds
+            );
+
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+}
@@ -0,0 +1,314 @@
+use chrono::Utc;
+use log::{error, info, warn};
+use mongodb::bson::doc;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::{
+    classifiers::video_type_classifier::VideoTypeClassifier,
+    errors::crawler_error::CrawlerError,
+    models::{youtube_playlist_items::PlaylistItem, youtube_video_details::YouTubeVideoItem},
+    repos::{
+        backfill_store::BackfillStore,
+        channel_probation_store::ChannelProbationStore,
+        channel_store::ChannelStore,
+        lock_store::LockStore,
+        settings_store::{is_feature_enabled, SettingsStore},
+        video_store::VideoStore,
+    },
+    scraper::video_scraper::append_video_details,
+    services::{
+        gear_extraction_service::GearExtractionService,
+        song_recognition_service::SongRecognitionService, url_resolver_service::UrlResolverService,
+        youtube_service::YoutubeService,
+    },
+    utils::{
+        consts::{DATA_SOURCE_YOUTUBE_DATA_API, FEATURE_BACKFILL_ENABLED},
+        date_utils::parse_first_timestamp,
+        health::Health,
+        maintenance::Maintenance,
+    },
+};
+
+const CHANNELS_PER_CRAWL: usize = 10;
+const MAX_PAGES_PER_CHANNEL_PER_CRAWL: usize = 20;
+
+const LOCK_NAME: &str = "channelBackfillCrawler";
+
+pub struct ChannelBackfillCrawler {
+    channel_repo: Box<dyn ChannelStore>,
+    video_repo: Box<dyn VideoStore>,
+    backfill_repo: Box<dyn BackfillStore>,
+    settings_repo: Box<dyn SettingsStore>,
+    youtube_service: YoutubeService,
+    song_recognition_service: Arc<SongRecognitionService>,
+    gear_extraction_service: GearExtractionService,
+    video_type_classifier: Arc<dyn VideoTypeClassifier>,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    health: Arc<Health>,
+    /// Channels on probation are backfilled once confirmed
+    probation_repo: Option<Box<dyn ChannelProbationStore>>,
+    url_resolver_service: Option<Arc<UrlResolverService>>,
+}
+
+impl ChannelBackfillCrawler {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        channel_repo: Box<dyn ChannelStore>,
+        video_repo: Box<dyn VideoStore>,
+        backfill_repo: Box<dyn BackfillStore>,
+        settings_repo: Box<dyn SettingsStore>,
+        youtube_service: YoutubeService,
+        song_recognition_service: Arc<SongRecognitionService>,
+        gear_extraction_service: GearExtractionService,
+        video_type_classifier: Arc<dyn VideoTypeClassifier>,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        health: Arc<Health>,
+        probation_repo: Option<Box<dyn ChannelProbationStore>>,
+        url_resolver_service: Option<Arc<UrlResolverService>>,
+    ) -> ChannelBackfillCrawler {
+        ChannelBackfillCrawler {
+            channel_repo,
+            video_repo,
+            backfill_repo,
+            settings_repo,
+            youtube_service,
+            song_recognition_service,
+            gear_extraction_service,
+            video_type_classifier,
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            health,
+            probation_repo,
+            url_resolver_service,
+        }
+    }
+
+    pub async fn crawl(&self) -> Result<(), CrawlerError> {
+        loop {
+            self.maintenance
+                .checkpoint("channel backfill crawler")
+                .await;
+
+            if !is_feature_enabled(self.settings_repo.as_ref(), FEATURE_BACKFILL_ENABLED).await {
+                info!("Channel backfill is disabled, skipping run");
+                sleep(Duration::from_secs(self.interval_seconds)).await;
+                continue;
+            }
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
+            info!("Start channel backfill crawler");
+
+            let mut skipped_ids = self.backfill_repo.get_completed_ids().await?;
+            if let Some(probation_repo) = &self.probation_repo {
+                skipped_ids.extend(probation_repo.get_undecided_ids().await?);
+            }
+            let pending_ids = self
+                .channel_repo
+                .get_all_ids()
+                .await?
+                .into_iter()
+                .filter(|channel_id| !skipped_ids.contains(channel_id))
+                .take(CHANNELS_PER_CRAWL)
+                .collect::<Vec<String>>();
+
+            info!("Found {} channels to backfill", pending_ids.len());
+
+            for channel_id in pending_ids {
+                if let Err(e) = self.backfill_channel(&channel_id).await {
+                    error!("Error in backfill of channel {}: {}", channel_id, e);
+                }
+            }
+
+            self.health.record_success(LOCK_NAME).await;
+
+            info!(
+                "Wait for {} seconds until next crawl",
+                self.interval_seconds
+            );
+
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+
+    pub async fn backfill_channel(&self, channel_id: &str) -> Result<(), CrawlerError> {
+        let uploads_playlist_id = match channel_id.strip_prefix("UC") {
+            Some(channel_suffix) => format!("UU{}", channel_suffix),
+            None => {
+                let error = format!("No uploads playlist for channel id {}", channel_id);
+                self.backfill_repo
+                    .set_completed(channel_id, 0, Some(error))
+                    .await?;
+
+                return Ok(());
+            }
+        };
+        let mut page_token = self.backfill_repo.get_page_token(channel_id).await?;
+
+        for _ in 0..MAX_PAGES_PER_CHANNEL_PER_CRAWL {
+            let page = match self
+                .youtube_service
+                .get_playlist_items_page(&uploads_playlist_id, page_token.as_deref())
+                .await
+            {
+                Ok(page) => page,
+                // Transient errors keep the cursor, the backfill resumes there next crawl
+                Err(e) if e.is_retryable() => {
+                    self.backfill_repo
+                        .set_error(channel_id, &e.to_string())
+                        .await?;
+
+                    return Err(e);
+                }
+                Err(e) => {
+                    self.backfill_repo
+                        .set_completed(channel_id, 0, Some(e.to_string()))
+                        .await?;
+
+                    return Err(e);
+                }
+            };
+
+            let videos_ingested = self.ingest_page(channel_id, &page.items).await?;
+
+            match page.next_page_token {
+                Some(next_page_token) => {
+                    self.backfill_repo
+                        .set_page_token(channel_id, &next_page_token, videos_ingested)
+                        .await?;
+                    page_token = Some(next_page_token);
+                }
+                None => {
+                    info!("Backfill of channel {} completed", channel_id);
+                    self.backfill_repo
+                        .set_completed(channel_id, videos_ingested, None)
+                        .await?;
+
+                    if let Err(e) = self
+                        .gear_extraction_service
+                        .update_channel_gear(channel_id)
+                        .await
+                    {
+                        warn!("Failed to update gear of channel {}: {}", channel_id, e);
+                    }
+
+                    return Ok(());
+                }
+            }
+        }
+
+        info!(
+            "Backfill of channel {} paused after {} pages",
+            channel_id, MAX_PAGES_PER_CHANNEL_PER_CRAWL
+        );
+
+        Ok(())
+    }
+
+    async fn ingest_page(
+        &self,
+        channel_id: &str,
+        items: &[PlaylistItem],
+    ) -> Result<i64, CrawlerError> {
+        let video_ids = items
+            .iter()
+            .map(|item| item.content_details.video_id.clone())
+            .collect::<Vec<String>>();
+
+        let details_lookup = self
+            .youtube_service
+            .get_video_details(&video_ids)
+            .await?
+            .into_iter()
+            .map(|item| (item.id.clone(), item))
+            .collect::<HashMap<String, YouTubeVideoItem>>();
+
+        let mut videos_ingested = 0;
+
+        for item in items {
+            let video_id = &item.content_details.video_id;
+            let details = details_lookup.get(video_id);
+            let api_published_at = details
+                .and_then(|d| d.snippet.as_ref())
+                .and_then(|snippet| snippet.published_at)
+                .map(|published_at| published_at.to_rfc3339())
+                .unwrap_or_default();
+            // The snippet date is when the video was added to the playlist
+            let candidates = [
+                (
+                    "videoPublishedAt",
+                    item.content_details
+                        .video_published_at
+                        .as_deref()
+                        .unwrap_or_default(),
+                ),
+                ("publishedAt", item.snippet.published_at.as_str()),
+                ("api", api_published_at.as_str()),
+            ];
+
+            let published = match parse_first_timestamp(&candidates, Utc::now().timestamp()) {
+                Some((published, "videoPublishedAt")) => published,
+                Some((published, source)) => {
+                    info!(
+                        "Invalid published date of video {}, using the {} date",
+                        video_id, source
+                    );
+                    published
+                }
+                None => {
+                    warn!("Skip video {} without a valid published date", video_id);
+                    continue;
+                }
+            };
+
+            let views = details
+                .and_then(|d| d.statistics.as_ref())
+                .and_then(|s| s.view_count)
+                .unwrap_or(0);
+
+            let mut vid = doc! {
+                "_id": video_id,
+                "title": &item.snippet.title,
+                "description": &item.snippet.description,
+                "publishedAt": published.timestamp(),
+                "updatedAt": Utc::now().timestamp(),
+                "views": views,
+                "channel": channel_id,
+                "dataSource": DATA_SOURCE_YOUTUBE_DATA_API,
+            };
+
+            let resolved_urls = match &self.url_resolver_service {
+                Some(url_resolver_service) => {
+                    url_resolver_service
+                        .resolve_all(&item.snippet.description)
+                        .await
+                }
+                None => HashMap::new(),
+            };
+            append_video_details(&mut vid, &item.snippet.description, details, &resolved_urls);
+            self.song_recognition_service
+                .append_cover_of(&mut vid, &item.snippet.title);
+            self.gear_extraction_service.append_gear(&mut vid);
+            self.video_type_classifier.append_video_type(&mut vid);
+
+            self.video_repo.upsert(video_id, vid).await?;
+            videos_ingested += 1;
+        }
+
+        Ok(videos_ingested)
+    }
+}
@@ -1,36 +1,99 @@
 use crate::{
     commands::crawl_channel_command::CrawlChannelCommand,
+    errors::crawler_error::{ApiErrorReason, CrawlerError},
+    models::{
+        crawl_stats::CrawlStats, discovery_cursor::DiscoveryCursor,
+        discovery_policy::DiscoveryPolicy, youtube_channel_details::YoutubeStatisticsItem,
+    },
     repos::{
-        additional_channel_repo::AdditionalChannelRepository, channel_repo::ChannelRepository,
-        settings_repo::SettingsRepository,
+        additional_channel_store::AdditionalChannelStore,
+        blocklist_store::BlocklistStore,
+        channel_store::ChannelStore,
+        crawl_audit_store::{CrawlAuditStore, CRAWL_KIND_DISCOVERY},
+        lock_store::LockStore,
+        opt_out_store::OptOutStore,
+        policy_rejection_store::PolicyRejectionStore,
+        review_queue_store::ReviewQueueStore,
+        settings_store::{is_feature_enabled, SettingsStore},
+    },
+    services::{
+        collaboration_service::CollaborationService, guitar_terms_service::GuitarTermsService,
+        youtube_service::YoutubeService,
+    },
+    utils::{
+        channel_metadata_utils::get_handle,
+        consts::{
+            CHANNEL_SOURCE_COLLABORATION, CHANNEL_SOURCE_DISCOVERY,
+            CLASSIFICATION_OVERRIDE_EXCLUDE, FEATURE_DISCOVERY_ENABLED,
+        },
+        crawl_budget::CrawlBudget,
+        date_utils::parse_first_timestamp,
+        discovery_policy_utils::{check_activity, check_channel_stats},
+        health::Health,
+        maintenance::Maintenance,
     },
-    services::{guitar_terms_service::GuitarTermsService, youtube_service::YoutubeService},
-    utils::consts::ONE_DAYS_IN_SECONDS,
 };
-use anyhow::Error;
 use chrono::Utc;
-use log::info;
+use log::{info, warn};
+use mongodb::bson::doc;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc::Sender;
 use tokio::time::sleep;
 
+const REVIEW_QUEUE_MIN_PARTIAL_SCORE: f64 = 0.5;
+
+const LOCK_NAME: &str = "channelDiscoveryCrawler";
+
+/// What the discovery policy says about a channel.
+#[derive(Debug)]
+enum PolicyDecision {
+    /// With the details the policy was checked against, unless the policy is empty or the api
+    /// failed and the channel scraper decides
+    Accepted(Option<Box<YoutubeStatisticsItem>>),
+    Rejected(String),
+}
+
 pub struct ChannelDiscoveryCrawler {
     sender: Sender<CrawlChannelCommand>,
-    channel_repo: ChannelRepository,
-    settings_repo: SettingsRepository,
+    channel_repo: Box<dyn ChannelStore>,
+    settings_repo: Box<dyn SettingsStore>,
     youtube_service: YoutubeService,
     guitar_terms_service: GuitarTermsService,
-    additional_channel_repo: AdditionalChannelRepository,
+    collaboration_service: CollaborationService,
+    additional_channel_repo: Box<dyn AdditionalChannelStore>,
+    review_queue_repo: Box<dyn ReviewQueueStore>,
+    blocklist_repo: Box<dyn BlocklistStore>,
+    opt_out_repo: Box<dyn OptOutStore>,
+    policy_rejection_repo: Box<dyn PolicyRejectionStore>,
+    crawl_audit_repo: Box<dyn CrawlAuditStore>,
+    budget: CrawlBudget,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    health: Arc<Health>,
 }
 
 impl ChannelDiscoveryCrawler {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         sender: Sender<CrawlChannelCommand>,
-        channel_repo: ChannelRepository,
-        settings_repo: SettingsRepository,
+        channel_repo: Box<dyn ChannelStore>,
+        settings_repo: Box<dyn SettingsStore>,
         youtube_service: YoutubeService,
         guitar_terms_service: GuitarTermsService,
-        additional_channel_repo: AdditionalChannelRepository,
+        collaboration_service: CollaborationService,
+        additional_channel_repo: Box<dyn AdditionalChannelStore>,
+        review_queue_repo: Box<dyn ReviewQueueStore>,
+        blocklist_repo: Box<dyn BlocklistStore>,
+        opt_out_repo: Box<dyn OptOutStore>,
+        policy_rejection_repo: Box<dyn PolicyRejectionStore>,
+        crawl_audit_repo: Box<dyn CrawlAuditStore>,
+        budget: CrawlBudget,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        health: Arc<Health>,
     ) -> ChannelDiscoveryCrawler {
         ChannelDiscoveryCrawler {
             sender,
@@ -38,88 +101,582 @@ impl ChannelDiscoveryCrawler {
             settings_repo,
             youtube_service,
             guitar_terms_service,
+            collaboration_service,
             additional_channel_repo,
+            review_queue_repo,
+            blocklist_repo,
+            opt_out_repo,
+            policy_rejection_repo,
+            crawl_audit_repo,
+            budget,
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            health,
         }
     }
 
-    pub async fn crawl(&self) -> Result<(), Error> {
+    pub async fn crawl(&self) -> Result<(), CrawlerError> {
         println!("Start channel discovery crawler");
 
         loop {
+            self.maintenance
+                .checkpoint("channel discovery crawler")
+                .await;
+
+            if !is_feature_enabled(self.settings_repo.as_ref(), FEATURE_DISCOVERY_ENABLED).await {
+                info!("Channel discovery is disabled, skipping run");
+                sleep(Duration::from_secs(self.interval_seconds)).await;
+                continue;
+            }
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
             if self.should_crawl().await.unwrap_or(false) {
-                let channel_ids = self.channel_repo.get_ids_upload_last_month(8000).await?;
-
-                for channel_id in channel_ids {
-                    info!("Check subscriptions of channel {}", channel_id);
-
-                    let subscriptions = self
-                        .youtube_service
-                        .get_channel_subscriptions(&channel_id)
-                        .await
-                        .unwrap_or(vec![]);
-
-                    for snippet in subscriptions {
-                        let sub_channel_id = snippet.resource_id.channel_id;
-
-                        let guitar_terms_result = self
-                            .guitar_terms_service
-                            .has_guitar_term(
-                                &sub_channel_id,
-                                &snippet.title,
-                                &snippet.description,
-                                false,
-                            )
-                            .await;
-
-                        let is_newly_discovered =
-                            self.is_channel_newly_discovered(&sub_channel_id).await?;
-
-                        let is_not_non_guitar_channel = self
-                            .guitar_terms_service
-                            .is_not_listed_as_non_guitar_channel(&sub_channel_id)
-                            .await;
-
-                        if is_newly_discovered
-                            && is_not_non_guitar_channel
-                            && guitar_terms_result.has_guitar_term
-                        {
-                            info!("Send channel for crawling: {}", sub_channel_id);
-
-                            let cmd = CrawlChannelCommand {
-                                channel_id: sub_channel_id.clone(),
-                                ignore_guitar_terms: false,
-                            };
-
-                            self.sender.send(cmd).await?;
-                        } else {
-                            info!("Channel {} does not qualify as a newly discovered channel (is_newly_discovered = {}, is_not_non_guitar_channel = {}, has_guitar_term = {})", sub_channel_id, is_newly_discovered, is_not_non_guitar_channel, guitar_terms_result.has_guitar_term);
+                self.discover().await?;
+                self.health.record_success(LOCK_NAME).await;
+            }
+
+            info!(
+                "Wait for {} seconds until next crawl",
+                self.interval_seconds
+            );
+
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+
+    /// Checks the subscriptions of all active channels once, regardless of the last crawl. A pass
+    /// that was interrupted, or ran out of budget, resumes after the last checked channel, in
+    /// channel id order.
+    pub async fn discover(&self) -> Result<(), CrawlerError> {
+        self.budget.start();
+
+        let mut channel_ids = self.channel_repo.get_ids_upload_last_month(8000).await?;
+        let policy = self.settings_repo.get_discovery_policy().await?;
+
+        channel_ids.sort();
+
+        let mut position = 0;
+        if let Some(cursor) = self.settings_repo.get_discovery_cursor().await? {
+            info!(
+                "Resume discovery after channel {} ({} channels checked)",
+                cursor.channel_id, cursor.position
+            );
+
+            channel_ids.retain(|channel_id| channel_id > &cursor.channel_id);
+            position = cursor.position;
+        }
+
+        for channel_id in channel_ids {
+            if let Some(reason) = self.budget.get_exhausted_reason() {
+                info!(
+                    "Discovery budget exhausted after {} channels ({}), resume on the next run",
+                    position, reason
+                );
+                return Ok(());
+            }
+
+            info!("Check subscriptions of channel {}", channel_id);
+
+            let started_at = Utc::now();
+            let units_spent = self.youtube_service.units_spent();
+            let subscriptions_result = self
+                .youtube_service
+                .get_channel_subscriptions(&channel_id)
+                .await;
+
+            let api_units = self.youtube_service.units_spent() - units_spent;
+            self.budget.record(api_units, 1);
+
+            let stats = CrawlStats {
+                api_units: api_units as i64,
+                ..CrawlStats::default()
+            };
+            if let Err(e) = self
+                .crawl_audit_repo
+                .insert(
+                    &channel_id,
+                    CRAWL_KIND_DISCOVERY,
+                    started_at,
+                    &stats,
+                    subscriptions_result.as_ref().err(),
+                )
+                .await
+            {
+                warn!(
+                    "Failed to record discovery of channel {}: {}",
+                    channel_id, e
+                );
+            }
+
+            let subscriptions = subscriptions_result.unwrap_or(vec![]);
+
+            for snippet in subscriptions {
+                let sub_channel_id = snippet.resource_id.channel_id;
+
+                let guitar_terms_result = self
+                    .guitar_terms_service
+                    .has_guitar_term(&sub_channel_id, &snippet.title, &snippet.description, false)
+                    .await;
+
+                let is_newly_discovered = self.is_channel_newly_discovered(&sub_channel_id).await?;
+
+                let is_not_non_guitar_channel = self
+                    .guitar_terms_service
+                    .is_not_listed_as_non_guitar_channel(&sub_channel_id)
+                    .await;
+
+                if is_newly_discovered
+                    && is_not_non_guitar_channel
+                    && guitar_terms_result.has_guitar_term
+                {
+                    let channel_details = match self.check_policy(&policy, &sub_channel_id).await? {
+                        PolicyDecision::Accepted(channel_details) => channel_details,
+                        PolicyDecision::Rejected(reason) => {
+                            info!(
+                                "Skip channel {}, it fails the discovery policy: {}",
+                                sub_channel_id, reason
+                            );
+                            continue;
                         }
-                    }
+                    };
+
+                    info!("Send channel for crawling: {}", sub_channel_id);
+
+                    let mut cmd = CrawlChannelCommand::new(
+                        sub_channel_id.clone(),
+                        false,
+                        Some(CHANNEL_SOURCE_DISCOVERY.to_string()),
+                    );
+                    cmd.channel_details = channel_details;
+
+                    self.sender.send(cmd).await?;
+                } else if is_newly_discovered
+                    && !guitar_terms_result.is_blacklisted
+                    && guitar_terms_result.partial_score >= REVIEW_QUEUE_MIN_PARTIAL_SCORE
+                    && !self.review_queue_repo.exists(&sub_channel_id).await?
+                {
+                    info!(
+                        "Send channel {} to review queue (score = {})",
+                        sub_channel_id, guitar_terms_result.partial_score
+                    );
+
+                    let candidate = doc! {
+                        "title": &snippet.title,
+                        "description": &snippet.description,
+                        "score": guitar_terms_result.partial_score,
+                        "evidence": {
+                            "matchedTerms": &guitar_terms_result.partial_terms,
+                            "source": &channel_id,
+                        },
+                    };
+
+                    self.review_queue_repo
+                        .insert_pending(&sub_channel_id, candidate)
+                        .await?;
+                } else {
+                    info!("Channel {} does not qualify as a newly discovered channel (is_newly_discovered = {}, is_not_non_guitar_channel = {}, has_guitar_term = {})", sub_channel_id, is_newly_discovered, is_not_non_guitar_channel, guitar_terms_result.has_guitar_term);
                 }
+            }
 
-                let crawl_timestamp = Utc::now().timestamp();
-                self.settings_repo
-                    .set_last_discovery_crawl(crawl_timestamp)
-                    .await;
+            position += 1;
+            self.settings_repo
+                .set_discovery_cursor(&DiscoveryCursor {
+                    channel_id,
+                    position,
+                })
+                .await?;
+        }
+
+        // Channels mentioned in the videos of many known channels are likely guitar channels too
+        for candidate in self
+            .collaboration_service
+            .take_discovery_candidates()
+            .await?
+        {
+            if self.blocklist_repo.is_blocked(&candidate).await?
+                || self.opt_out_repo.is_opted_out(&candidate).await?
+                || self.is_force_excluded(&candidate).await?
+            {
+                info!("Skip blocked collaboration candidate {}", candidate);
+                continue;
             }
 
-            info!("Wait for {} seconds until next crawl", ONE_DAYS_IN_SECONDS);
+            let channel_details = match self.check_policy(&policy, &candidate).await? {
+                PolicyDecision::Accepted(channel_details) => channel_details,
+                PolicyDecision::Rejected(reason) => {
+                    info!(
+                        "Skip collaboration candidate {}, it fails the discovery policy: {}",
+                        candidate, reason
+                    );
+                    continue;
+                }
+            };
+
+            info!("Send collaboration candidate for crawling: {}", candidate);
 
-            sleep(Duration::from_secs(ONE_DAYS_IN_SECONDS)).await;
+            let mut cmd = CrawlChannelCommand::new(
+                candidate.clone(),
+                false,
+                Some(CHANNEL_SOURCE_COLLABORATION.to_string()),
+            );
+            cmd.channel_details = channel_details;
+
+            self.sender.send(cmd).await?;
+            self.collaboration_service.mark_queued(&candidate).await?;
         }
+
+        let crawl_timestamp = Utc::now().timestamp();
+        self.settings_repo
+            .set_last_discovery_crawl(crawl_timestamp)
+            .await;
+        self.settings_repo.clear_discovery_cursor().await?;
+
+        Ok(())
     }
 
-    async fn should_crawl(&self) -> Result<bool, Error> {
+    /// Rejections are stored, so a rejected channel costs no quota again until they expire.
+    async fn check_policy(
+        &self,
+        policy: &DiscoveryPolicy,
+        channel: &str,
+    ) -> Result<PolicyDecision, CrawlerError> {
+        if policy.is_empty() {
+            return Ok(PolicyDecision::Accepted(None));
+        }
+
+        if self.policy_rejection_repo.is_rejected(channel).await? {
+            return Ok(PolicyDecision::Rejected(
+                "rejected within the last 30 days".to_string(),
+            ));
+        }
+
+        let (rejection, channel_details) = self.get_policy_rejection(policy, channel).await;
+
+        // Opt-outs by handle only match once the api told the handle, they are not rejections
+        if let Some(handle) = channel_details.as_ref().and_then(get_handle) {
+            if self.opt_out_repo.is_opted_out(handle).await? {
+                return Ok(PolicyDecision::Rejected(format!("{} opted out", handle)));
+            }
+        }
+
+        match rejection {
+            Some(reason) => {
+                self.policy_rejection_repo.reject(channel, &reason).await?;
+                Ok(PolicyDecision::Rejected(reason))
+            }
+            None => Ok(PolicyDecision::Accepted(channel_details.map(Box::new))),
+        }
+    }
+
+    /// Checks the channel against the policy with `channels.list`, and its uploads playlist when
+    /// the policy limits inactivity. Channels the api fails on are left to the channel scraper.
+    /// Returns the loaded details along with the rejection.
+    async fn get_policy_rejection(
+        &self,
+        policy: &DiscoveryPolicy,
+        channel: &str,
+    ) -> (Option<String>, Option<YoutubeStatisticsItem>) {
+        let channel_id = if channel.starts_with('@') {
+            match self.youtube_service.resolve_handle(channel).await {
+                Ok(Some(channel_id)) => channel_id,
+                Ok(None) => return (None, None),
+                Err(e) => {
+                    warn!("Failed to resolve handle {}: {}", channel, e);
+                    return (None, None);
+                }
+            }
+        } else {
+            channel.to_string()
+        };
+
+        let details = match self.youtube_service.get_channel_details(&channel_id).await {
+            Ok(details) => details,
+            Err(e) => {
+                warn!("Failed to load details of channel {}: {}", channel_id, e);
+                return (None, None);
+            }
+        };
+
+        let subscribers = if details.statistics.hidden_subscriber_count {
+            None
+        } else {
+            details.statistics.subscriber_count
+        };
+        let videos = details.statistics.video_count;
+
+        if let Some(reason) = check_channel_stats(policy, subscribers, videos) {
+            return (Some(reason), Some(details));
+        }
+
+        if policy.max_inactive_days.is_none() {
+            return (None, Some(details));
+        }
+
+        let rejection = match self.get_last_upload_at(&channel_id).await {
+            Ok(last_upload_at) => check_activity(policy, Utc::now().timestamp(), last_upload_at),
+            Err(e) => {
+                warn!("Failed to load uploads of channel {}: {}", channel_id, e);
+                None
+            }
+        };
+
+        (rejection, Some(details))
+    }
+
+    /// The uploads playlist lists the newest upload first.
+    async fn get_last_upload_at(&self, channel_id: &str) -> Result<Option<i64>, CrawlerError> {
+        let uploads_playlist_id = match channel_id.strip_prefix("UC") {
+            Some(channel_suffix) => format!("UU{}", channel_suffix),
+            None => return Ok(None),
+        };
+
+        let page = match self
+            .youtube_service
+            .get_playlist_items_page(&uploads_playlist_id, None)
+            .await
+        {
+            Ok(page) => page,
+            // Channels without uploads have no uploads playlist
+            Err(CrawlerError::ApiError {
+                reason: ApiErrorReason::NotFound,
+                ..
+            }) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let last_upload_at = match page.items.first() {
+            Some(item) => {
+                let candidates = [
+                    (
+                        "videoPublishedAt",
+                        item.content_details
+                            .video_published_at
+                            .as_deref()
+                            .unwrap_or_default(),
+                    ),
+                    ("publishedAt", item.snippet.published_at.as_str()),
+                ];
+
+                parse_first_timestamp(&candidates, Utc::now().timestamp())
+                    .map(|(published, _)| published.timestamp())
+            }
+            None => None,
+        };
+
+        Ok(last_upload_at)
+    }
+
+    async fn should_crawl(&self) -> Result<bool, CrawlerError> {
         let last_crawl_timestamp = self.settings_repo.get_last_discovery_crawl().await?;
         let seconds_since_last_crawl = Utc::now().timestamp() - last_crawl_timestamp;
 
-        Ok(seconds_since_last_crawl >= ONE_DAYS_IN_SECONDS as i64)
+        Ok(seconds_since_last_crawl >= self.interval_seconds as i64)
     }
 
-    async fn is_channel_newly_discovered(&self, channel_id: &str) -> Result<bool, Error> {
+    async fn is_force_excluded(&self, channel_id: &str) -> Result<bool, CrawlerError> {
+        let classification_override = self
+            .channel_repo
+            .get_classification_override(channel_id)
+            .await?;
+
+        Ok(classification_override.as_deref() == Some(CLASSIFICATION_OVERRIDE_EXCLUDE))
+    }
+
+    /// Blocked and opted-out channels count as known, so they never reach the scraper or the
+    /// review queue.
+    async fn is_channel_newly_discovered(&self, channel_id: &str) -> Result<bool, CrawlerError> {
         let channel_exists = self.channel_repo.exists(channel_id).await?;
         let additional_exists = self.additional_channel_repo.exists(channel_id).await?;
+        let is_blocked = self.blocklist_repo.is_blocked(channel_id).await?
+            || self.opt_out_repo.is_opted_out(channel_id).await?;
+
+        Ok(!channel_exists && !additional_exists && !is_blocked)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use chrono::{Duration as ChronoDuration, Utc};
+    use tokio::sync::mpsc;
+
+    use crate::{
+        models::{
+            config::{CrawlBudgetConfig, NotificationsConfig},
+            discovery_policy::DiscoveryPolicy,
+            youtube_channel_details::{Statistics, YoutubeStatisticsItem},
+            youtube_playlist_items::{
+                PlaylistItem, PlaylistItemContentDetails, PlaylistItemSnippet, YouTubePlaylistItems,
+            },
+        },
+        notifications::notification_service::NotificationService,
+        repos::store_factory::StoreFactory,
+        services::{
+            collaboration_service::CollaborationService, guitar_terms_service::GuitarTermsService,
+            youtube_service::YoutubeService,
+        },
+        test_support::{
+            fake_stores::{
+                unreachable_mongo_client, FakeApiKeyStore, FakeChannelStore, FakeQuotaBreakerStore,
+            },
+            mock_youtube::MockYoutube,
+        },
+        utils::{
+            api_scheduler::{ApiCaller, ApiPriority, ApiScheduler},
+            crawl_budget::CrawlBudget,
+            health::Health,
+            maintenance::Maintenance,
+        },
+    };
+
+    use super::ChannelDiscoveryCrawler;
+
+    const CHANNEL_ID: &str = "UCnewguitar";
+
+    fn build_crawler(youtube: &MockYoutube) -> ChannelDiscoveryCrawler {
+        let stores = StoreFactory::mongodb(unreachable_mongo_client(), "test");
+        let youtube_service = YoutubeService::new(
+            Box::new(FakeApiKeyStore::default()),
+            Box::new(FakeQuotaBreakerStore::default()),
+            Arc::new(ApiScheduler::new(Duration::ZERO)),
+            ApiCaller::new("channelDiscoveryCrawler", ApiPriority::Discovery),
+            youtube.api_base_url(),
+            Arc::new(NotificationService::new(
+                &NotificationsConfig::default(),
+                None,
+            )),
+            None,
+        );
+
+        ChannelDiscoveryCrawler::new(
+            mpsc::channel(10).0,
+            Box::new(FakeChannelStore::default()),
+            stores.settings_store(),
+            youtube_service,
+            GuitarTermsService::new(vec![], vec![], stores.non_guitar_channel_store()),
+            CollaborationService::new(
+                stores.collab_edge_store(),
+                Box::new(FakeChannelStore::default()),
+            ),
+            stores.additional_channel_store(),
+            stores.review_queue_store(),
+            stores.blocklist_store(),
+            stores.opt_out_store(),
+            stores.policy_rejection_store(),
+            stores.crawl_audit_store(),
+            CrawlBudget::new(CrawlBudgetConfig::default()),
+            Arc::new(Maintenance::new(None)),
+            stores.lock_store(),
+            60,
+            Arc::new(Health::new(stores.clone(), &youtube.api_base_url())),
+        )
+    }
+
+    fn get_channel(subscribers: i64, videos: i64) -> YoutubeStatisticsItem {
+        YoutubeStatisticsItem {
+            id: CHANNEL_ID.to_string(),
+            statistics: Statistics {
+                subscriber_count: Some(subscribers),
+                video_count: Some(videos),
+                ..Statistics::default()
+            },
+            ..YoutubeStatisticsItem::default()
+        }
+    }
+
+    fn get_uploads(days_since_last_upload: i64) -> YouTubePlaylistItems {
+        let published_at = (Utc::now() - ChronoDuration::days(days_since_last_upload)).to_rfc3339();
+
+        YouTubePlaylistItems {
+            items: vec![PlaylistItem {
+                snippet: PlaylistItemSnippet {
+                    published_at: published_at.clone(),
+                    ..PlaylistItemSnippet::default()
+                },
+                content_details: PlaylistItemContentDetails {
+                    video_id: "video1".to_string(),
+                    video_published_at: Some(published_at),
+                },
+                ..PlaylistItem::default()
+            }],
+            ..YouTubePlaylistItems::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn policy_rejects_channel_by_its_details() {
+        let youtube = MockYoutube::start().await;
+        youtube.mount_channel_details(get_channel(50, 20)).await;
+        let crawler = build_crawler(&youtube);
+
+        let policy = DiscoveryPolicy {
+            min_subscribers: Some(100),
+            ..DiscoveryPolicy::default()
+        };
+
+        assert!(crawler
+            .get_policy_rejection(&policy, CHANNEL_ID)
+            .await
+            .0
+            .is_some());
+        assert_eq!(youtube.received_api_requests("channels").await, 1);
+        assert_eq!(youtube.received_api_requests("playlistItems").await, 0);
+    }
+
+    #[tokio::test]
+    async fn policy_checks_the_last_upload() {
+        let youtube = MockYoutube::start().await;
+        youtube.mount_channel_details(get_channel(500, 20)).await;
+        youtube.mount_uploads(CHANNEL_ID, &get_uploads(400)).await;
+        let crawler = build_crawler(&youtube);
+
+        let mut policy = DiscoveryPolicy {
+            min_subscribers: Some(100),
+            max_inactive_days: Some(365),
+            ..DiscoveryPolicy::default()
+        };
+
+        assert!(crawler
+            .get_policy_rejection(&policy, CHANNEL_ID)
+            .await
+            .0
+            .is_some());
+
+        policy.max_inactive_days = Some(500);
+
+        // The scraper reuses the details of accepted channels
+        let (rejection, channel_details) = crawler.get_policy_rejection(&policy, CHANNEL_ID).await;
+        assert!(rejection.is_none());
+        assert_eq!(channel_details.unwrap().id, CHANNEL_ID);
+    }
+
+    #[tokio::test]
+    async fn policy_leaves_channels_the_api_fails_on_to_the_scraper() {
+        let youtube = MockYoutube::start().await;
+        youtube
+            .mount_api_error("channels", "id", CHANNEL_ID, 500, "backendError")
+            .await;
+        let crawler = build_crawler(&youtube);
+
+        let policy = DiscoveryPolicy {
+            min_subscribers: Some(100),
+            ..DiscoveryPolicy::default()
+        };
 
-        Ok(!channel_exists && !additional_exists)
+        assert!(crawler
+            .get_policy_rejection(&policy, CHANNEL_ID)
+            .await
+            .0
+            .is_none());
     }
 }
@@ -1,60 +1,120 @@
-use anyhow::Error;
 use chrono::Utc;
-use log::info;
+use log::{info, warn};
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc::Sender;
 use tokio::time::sleep;
 
 use crate::{
-    commands::crawl_channel_command::CrawlChannelCommand, repos::channel_repo::ChannelRepository,
+    commands::crawl_channel_command::CrawlChannelCommand,
+    errors::crawler_error::CrawlerError,
+    repos::{
+        channel_popularity_store::ChannelPopularityStore, channel_store::ChannelStore,
+        lock_store::LockStore,
+    },
+    utils::{health::Health, maintenance::Maintenance, popularity_utils::sort_by_popularity},
 };
 
-const FIFTEEN_MINUTES_IN_SECONDS: u64 = 15 * 60;
+const LOCK_NAME: &str = "channelUpdateCrawler";
 
 pub struct ChannelUpdateCrawler {
-    channel_repo: ChannelRepository,
+    channel_repo: Box<dyn ChannelStore>,
+    popularity_repo: Box<dyn ChannelPopularityStore>,
     sender: Sender<CrawlChannelCommand>,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    health: Arc<Health>,
 }
 
 impl ChannelUpdateCrawler {
     pub fn new(
         sender: Sender<CrawlChannelCommand>,
-        channel_repo: ChannelRepository,
+        channel_repo: Box<dyn ChannelStore>,
+        popularity_repo: Box<dyn ChannelPopularityStore>,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        health: Arc<Health>,
     ) -> ChannelUpdateCrawler {
         ChannelUpdateCrawler {
             channel_repo,
+            popularity_repo,
             sender,
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            health,
         }
     }
 
-    pub async fn crawl(&self) -> Result<(), Error> {
+    pub async fn crawl(&self) -> Result<(), CrawlerError> {
         loop {
+            self.maintenance.checkpoint("channel update crawler").await;
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
             info!("Start channel update crawler");
 
+            let override_channel_ids = self
+                .channel_repo
+                .get_ids_with_due_refresh_override(Utc::now())
+                .await?;
+
+            info!(
+                "Found {} channels with a due refresh override",
+                override_channel_ids.len()
+            );
+
+            for channel_id in override_channel_ids.iter() {
+                let cmd = CrawlChannelCommand::new(channel_id.to_string(), false, None);
+
+                self.sender.send(cmd).await?;
+            }
+
             let last_crawl_before = Utc::now() - chrono::Duration::days(1);
             let last_upload_after = Utc::now() - chrono::Duration::weeks(52);
-            let channel_ids = self
+            let mut channel_ids = self
                 .channel_repo
                 .get_ids_last_crawled_before(last_crawl_before, last_upload_after)
                 .await?;
+            self.sort_by_popularity(&mut channel_ids).await;
 
             info!("Found {} channels to update", channel_ids.len());
 
             for channel_id in channel_ids {
-                let cmd = CrawlChannelCommand {
-                    channel_id,
-                    ignore_guitar_terms: false,
-                };
+                if override_channel_ids.contains(&channel_id) {
+                    continue;
+                }
+
+                let cmd = CrawlChannelCommand::new(channel_id, false, None);
 
                 self.sender.send(cmd).await?;
             }
 
+            self.health.record_success(LOCK_NAME).await;
+
             info!(
                 "Wait for {} seconds until next crawl",
-                FIFTEEN_MINUTES_IN_SECONDS
+                self.interval_seconds
             );
 
-            sleep(Duration::from_secs(FIFTEEN_MINUTES_IN_SECONDS)).await;
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+
+    /// Popular channels go first, so their stats are the freshest. Without the scores the
+    /// channels keep their order.
+    async fn sort_by_popularity(&self, channel_ids: &mut [String]) {
+        match self.popularity_repo.get_scores(channel_ids).await {
+            Ok(scores) => sort_by_popularity(channel_ids, &scores),
+            Err(e) => warn!("Failed to get popularity of channels: {}", e),
         }
     }
 }
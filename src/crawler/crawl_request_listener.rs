@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use futures::StreamExt;
+use log::{info, warn};
+use serde_json::json;
+
+use crate::{
+    errors::crawler_error::CrawlerError,
+    services::crawl_request_service::{
+        CrawlRequestService, CRAWL_REQUEST_STATUS_FAILED, CRAWL_REQUEST_STATUS_INVALID,
+    },
+    utils::{crawl_request_utils::parse_crawl_request, maintenance::Maintenance},
+};
+
+/// Takes `{"channel": "<id, handle or url>"}` crawl requests of other services from a NATS
+/// subject and hands them to the crawl request service. Requests sent with a reply subject get
+/// `{"channel", "status"}` back.
+pub struct CrawlRequestListener {
+    client: async_nats::Client,
+    subject: String,
+    queue_group: String,
+    crawl_request_service: Arc<CrawlRequestService>,
+    maintenance: Arc<Maintenance>,
+}
+
+impl CrawlRequestListener {
+    pub fn new(
+        client: async_nats::Client,
+        subject: String,
+        queue_group: String,
+        crawl_request_service: Arc<CrawlRequestService>,
+        maintenance: Arc<Maintenance>,
+    ) -> CrawlRequestListener {
+        CrawlRequestListener {
+            client,
+            subject,
+            queue_group,
+            crawl_request_service,
+            maintenance,
+        }
+    }
+
+    pub async fn listen(&self) -> Result<(), CrawlerError> {
+        let mut subscriber = self
+            .client
+            .queue_subscribe(self.subject.clone(), self.queue_group.clone())
+            .await
+            .map_err(|e| CrawlerError::QueueError(e.to_string()))?;
+
+        info!(
+            "Listen for crawl requests on {} in queue group {}",
+            self.subject, self.queue_group
+        );
+
+        while let Some(message) = subscriber.next().await {
+            self.maintenance.checkpoint("crawl request listener").await;
+
+            let (channel, status) = match parse_crawl_request(&message.payload) {
+                Ok(channel) => match self.crawl_request_service.request(&channel).await {
+                    Ok(status) => (channel, status),
+                    Err(e) => {
+                        warn!("Failed to handle crawl request for {}: {}", channel, e);
+                        (channel, CRAWL_REQUEST_STATUS_FAILED)
+                    }
+                },
+                Err(reason) => {
+                    warn!("Skip crawl request: {}", reason);
+                    (String::new(), CRAWL_REQUEST_STATUS_INVALID)
+                }
+            };
+
+            if let Some(reply) = message.reply {
+                let body = json!({"channel": channel, "status": status}).to_string();
+
+                if let Err(e) = self.client.publish(reply, body.into()).await {
+                    warn!("Failed to reply to crawl request for {}: {}", channel, e);
+                }
+            }
+        }
+
+        Err(CrawlerError::QueueError(format!(
+            "Subscription to {} closed",
+            self.subject
+        )))
+    }
+}
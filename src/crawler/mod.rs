@@ -1,4 +1,8 @@
+pub mod about_crawler;
 pub mod additional_channel_crawler;
+pub mod caption_crawler;
+pub mod channel_backfill_crawler;
 pub mod channel_discovery_crawler;
 pub mod channel_update_crawler;
+pub mod crawl_request_listener;
 pub mod new_video_crawler;
@@ -1,38 +1,92 @@
-use anyhow::Error;
-use log::info;
+use chrono::Utc;
+use log::{info, warn};
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc::Sender;
 use tokio::time::sleep;
 
-const SIXTY_MINUTES_IN_SECONDS: u64 = 60 * 60;
-
 use crate::{
-    commands::crawl_videos_command::CrawlVideosCommand, repos::channel_repo::ChannelRepository,
+    commands::crawl_videos_command::CrawlVideosCommand,
+    errors::crawler_error::CrawlerError,
+    repos::{
+        channel_popularity_store::ChannelPopularityStore, channel_store::ChannelStore,
+        lock_store::LockStore,
+    },
+    utils::{
+        crawl_budget::CrawlBudget, health::Health, maintenance::Maintenance,
+        popularity_utils::sort_by_popularity,
+    },
 };
 
+const LOCK_NAME: &str = "newVideoCrawler";
+
 pub struct NewVideoCrawler {
     sender: Sender<CrawlVideosCommand>,
-    channel_repo: ChannelRepository,
+    channel_repo: Box<dyn ChannelStore>,
+    popularity_repo: Box<dyn ChannelPopularityStore>,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    budget: Arc<CrawlBudget>,
+    health: Arc<Health>,
 }
 
 impl NewVideoCrawler {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         sender: Sender<CrawlVideosCommand>,
-        channel_repo: ChannelRepository,
+        channel_repo: Box<dyn ChannelStore>,
+        popularity_repo: Box<dyn ChannelPopularityStore>,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        budget: Arc<CrawlBudget>,
+        health: Arc<Health>,
     ) -> NewVideoCrawler {
         NewVideoCrawler {
             sender,
             channel_repo,
+            popularity_repo,
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            budget,
+            health,
         }
     }
 
-    pub async fn crawl(&self) -> Result<(), Error> {
+    pub async fn crawl(&self) -> Result<(), CrawlerError> {
         loop {
+            self.maintenance.checkpoint("new video crawler").await;
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
             info!("Start new video crawler");
 
-            let channels = self.channel_repo.get_all_ids().await?;
+            let mut channels = self.channel_repo.get_ids_due_for_scrape(Utc::now()).await?;
+            self.sort_by_popularity(&mut channels).await;
+            info!("{} channels are due for a video crawl", channels.len());
+
+            // The video scraper spends the budget and skips the rest of the queue once it is
+            // exhausted. Channels left out, the least viewed ones, stay due for the next cycle.
+            self.budget.start();
+            for (sent, channel) in channels.iter().enumerate() {
+                if let Some(reason) = self.budget.get_exhausted_reason() {
+                    info!(
+                        "Scrape budget exhausted after {} channels ({}), {} left for the next cycle",
+                        sent,
+                        reason,
+                        channels.len() - sent
+                    );
+                    break;
+                }
 
-            for channel in channels {
                 let command = CrawlVideosCommand {
                     channel_id: channel.clone(),
                 };
@@ -40,12 +94,23 @@ impl NewVideoCrawler {
                 self.sender.send(command).await?;
             }
 
+            self.health.record_success(LOCK_NAME).await;
+
             info!(
                 "Wait for {} seconds until next crawl",
-                SIXTY_MINUTES_IN_SECONDS
+                self.interval_seconds
             );
 
-            sleep(Duration::from_secs(SIXTY_MINUTES_IN_SECONDS)).await;
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+
+    /// Popular channels go first, so their stats are the freshest. Without the scores the
+    /// channels keep their order.
+    async fn sort_by_popularity(&self, channel_ids: &mut [String]) {
+        match self.popularity_repo.get_scores(channel_ids).await {
+            Ok(scores) => sort_by_popularity(channel_ids, &scores),
+            Err(e) => warn!("Failed to get popularity of channels: {}", e),
         }
     }
 }
@@ -0,0 +1,317 @@
+use std::num::ParseIntError;
+
+use mongodb::bson::document::ValueAccessError;
+use thiserror::Error;
+use tokio::sync::mpsc::error::SendError;
+
+/// Errors of the scrapers, crawlers and services, so callers can tell errors worth a retry from
+/// bad input.
+#[derive(Debug, Error)]
+#[allow(clippy::enum_variant_names)]
+pub enum CrawlerError {
+    /// The RSS feed, timed text or channel page endpoints failed or kept rate limiting
+    #[error("{0}")]
+    FeedError(String),
+    #[error("{message}")]
+    ApiError {
+        message: String,
+        reason: ApiErrorReason,
+    },
+    /// Repositories return anyhow errors, which count as database errors unless they wrap a known
+    /// error
+    #[error("{0}")]
+    DbError(anyhow::Error),
+    #[error("{0}")]
+    ParseError(String),
+    /// The receiving scraper of a command queue stopped
+    #[error("{0}")]
+    QueueError(String),
+}
+
+/// Why a Data API call failed, read from the status and the `reason` of the error response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiErrorReason {
+    QuotaExceeded,
+    /// The api key is invalid, expired or deleted
+    KeyInvalid,
+    Forbidden,
+    NotFound,
+    /// YouTube failed on its side, e.g. with a 500 or `backendError`
+    BackendError,
+    /// Network errors and responses of no other kind
+    Other,
+}
+
+impl ApiErrorReason {
+    pub fn from_response(status: u16, body: &str) -> ApiErrorReason {
+        if body.contains("quotaExceeded") || body.contains("dailyLimitExceeded") {
+            ApiErrorReason::QuotaExceeded
+        } else if body.contains("keyInvalid")
+            || body.contains("keyExpired")
+            || body.contains("API_KEY_INVALID")
+        {
+            ApiErrorReason::KeyInvalid
+        } else if status >= 500 || body.contains("backendError") {
+            ApiErrorReason::BackendError
+        } else if status == 403 {
+            ApiErrorReason::Forbidden
+        } else if status == 404 {
+            ApiErrorReason::NotFound
+        } else {
+            ApiErrorReason::Other
+        }
+    }
+
+    /// Name of the reason, shared by the crawl audit log and the api key health.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ApiErrorReason::QuotaExceeded => "quota",
+            ApiErrorReason::KeyInvalid => "keyInvalid",
+            ApiErrorReason::Forbidden => "forbidden",
+            ApiErrorReason::NotFound => "notFound",
+            ApiErrorReason::BackendError => "backendError",
+            ApiErrorReason::Other => "api",
+        }
+    }
+
+    /// Forbidden and missing resources say nothing about the key that asked for them.
+    pub fn is_key_error(&self) -> bool {
+        matches!(
+            self,
+            ApiErrorReason::QuotaExceeded
+                | ApiErrorReason::KeyInvalid
+                | ApiErrorReason::BackendError
+        )
+    }
+}
+
+impl CrawlerError {
+    pub fn api(message: String) -> CrawlerError {
+        CrawlerError::ApiError {
+            message,
+            reason: ApiErrorReason::Other,
+        }
+    }
+
+    pub fn api_status(status: u16, body: &str) -> CrawlerError {
+        let reason = ApiErrorReason::from_response(status, body);
+
+        CrawlerError::ApiError {
+            message: match reason {
+                ApiErrorReason::QuotaExceeded => "Youtube API quota exceeded".to_string(),
+                ApiErrorReason::KeyInvalid => "Youtube API key invalid".to_string(),
+                _ => format!("Youtube API Response Error: {}", status),
+            },
+            reason,
+        }
+    }
+
+    pub fn not_found(message: String) -> CrawlerError {
+        CrawlerError::ApiError {
+            message,
+            reason: ApiErrorReason::NotFound,
+        }
+    }
+
+    /// Missing or forbidden channels and unparsable responses fail the same way again. Calls
+    /// failing on an invalid key are retried with another key.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            CrawlerError::FeedError(_) | CrawlerError::DbError(_) => true,
+            CrawlerError::ApiError { reason, .. } => {
+                !matches!(reason, ApiErrorReason::NotFound | ApiErrorReason::Forbidden)
+            }
+            CrawlerError::ParseError(_) | CrawlerError::QueueError(_) => false,
+        }
+    }
+
+    /// Name of the kind of error, kept in the crawl audit log to count errors by category.
+    pub fn category(&self) -> &'static str {
+        match self {
+            CrawlerError::FeedError(_) => "feed",
+            CrawlerError::ApiError { reason, .. } => reason.name(),
+            CrawlerError::DbError(_) => "db",
+            CrawlerError::ParseError(_) => "parse",
+            CrawlerError::QueueError(_) => "queue",
+        }
+    }
+}
+
+/// Keeps the kind of crawler errors and of the known errors that passed through an anyhow error,
+/// errors of any other type count as database errors.
+impl From<anyhow::Error> for CrawlerError {
+    fn from(error: anyhow::Error) -> Self {
+        let error = match error.downcast::<CrawlerError>() {
+            Ok(crawler_error) => return crawler_error,
+            Err(error) => error,
+        };
+        let error = match error.downcast::<mongodb::error::Error>() {
+            Ok(mongo_error) => return mongo_error.into(),
+            Err(error) => error,
+        };
+        let error = match error.downcast::<ValueAccessError>() {
+            Ok(access_error) => return access_error.into(),
+            Err(error) => error,
+        };
+        let error = match error.downcast::<serde_json::Error>() {
+            Ok(json_error) => return json_error.into(),
+            Err(error) => error,
+        };
+        match error.downcast::<reqwest::Error>() {
+            Ok(http_error) => CrawlerError::FeedError(http_error.to_string()),
+            Err(error) => CrawlerError::DbError(error),
+        }
+    }
+}
+
+impl From<mongodb::error::Error> for CrawlerError {
+    fn from(error: mongodb::error::Error) -> Self {
+        CrawlerError::DbError(error.into())
+    }
+}
+
+impl From<serde_json::Error> for CrawlerError {
+    fn from(error: serde_json::Error) -> Self {
+        CrawlerError::ParseError(error.to_string())
+    }
+}
+
+impl From<quick_xml::DeError> for CrawlerError {
+    fn from(error: quick_xml::DeError) -> Self {
+        CrawlerError::ParseError(error.to_string())
+    }
+}
+
+impl From<ParseIntError> for CrawlerError {
+    fn from(error: ParseIntError) -> Self {
+        CrawlerError::ParseError(error.to_string())
+    }
+}
+
+impl From<chrono::ParseError> for CrawlerError {
+    fn from(error: chrono::ParseError) -> Self {
+        CrawlerError::ParseError(error.to_string())
+    }
+}
+
+impl From<ValueAccessError> for CrawlerError {
+    fn from(error: ValueAccessError) -> Self {
+        CrawlerError::ParseError(error.to_string())
+    }
+}
+
+impl From<regex::Error> for CrawlerError {
+    fn from(error: regex::Error) -> Self {
+        CrawlerError::ParseError(error.to_string())
+    }
+}
+
+impl<T> From<SendError<T>> for CrawlerError {
+    fn from(error: SendError<T>) -> Self {
+        CrawlerError::QueueError(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ApiErrorReason, CrawlerError};
+
+    #[test]
+    fn api_status_kinds() {
+        let quota = super::CrawlerError::api_status(403, r#"{"reason": "quotaExceeded"}"#);
+        let forbidden = super::CrawlerError::api_status(403, "");
+        let not_found = super::CrawlerError::api_status(404, "");
+
+        assert!(matches!(
+            quota,
+            CrawlerError::ApiError {
+                reason: ApiErrorReason::QuotaExceeded,
+                ..
+            }
+        ));
+        assert!(quota.is_retryable());
+        assert!(!forbidden.is_retryable());
+        assert!(!not_found.is_retryable());
+        assert!(super::CrawlerError::api_status(500, "").is_retryable());
+
+        assert_eq!(quota.category(), "quota");
+        assert_eq!(forbidden.category(), "forbidden");
+        assert_eq!(not_found.category(), "notFound");
+        assert_eq!(
+            super::CrawlerError::api_status(500, "").category(),
+            "backendError"
+        );
+        assert_eq!(super::CrawlerError::api_status(400, "").category(), "api");
+    }
+
+    #[test]
+    fn api_error_reasons() {
+        let key_invalid = r#"{"error": {"code": 400, "message": "API key not valid. Please pass a valid API key.", "errors": [{"reason": "badRequest"}], "details": [{"reason": "API_KEY_INVALID"}]}}"#;
+
+        assert_eq!(
+            ApiErrorReason::from_response(400, key_invalid),
+            ApiErrorReason::KeyInvalid
+        );
+        assert_eq!(
+            ApiErrorReason::from_response(403, r#"{"errors": [{"reason": "dailyLimitExceeded"}]}"#),
+            ApiErrorReason::QuotaExceeded
+        );
+        assert_eq!(
+            ApiErrorReason::from_response(503, r#"{"errors": [{"reason": "backendError"}]}"#),
+            ApiErrorReason::BackendError
+        );
+        assert_eq!(
+            ApiErrorReason::from_response(
+                403,
+                r#"{"errors": [{"reason": "subscriptionForbidden"}]}"#
+            ),
+            ApiErrorReason::Forbidden
+        );
+        assert_eq!(
+            ApiErrorReason::from_response(404, ""),
+            ApiErrorReason::NotFound
+        );
+
+        assert!(ApiErrorReason::KeyInvalid.is_key_error());
+        assert!(!ApiErrorReason::Forbidden.is_key_error());
+        assert!(super::CrawlerError::api_status(400, key_invalid).is_retryable());
+    }
+
+    #[test]
+    fn keeps_kind_through_anyhow() {
+        let error: anyhow::Error = super::CrawlerError::ParseError("invalid".to_string()).into();
+
+        assert!(matches!(
+            super::CrawlerError::from(error),
+            CrawlerError::ParseError(_)
+        ));
+    }
+
+    #[test]
+    fn downcasts_known_errors_through_anyhow() {
+        let json_error = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        let access_error = mongodb::bson::doc! {}.get_str("name").unwrap_err();
+
+        assert!(matches!(
+            super::CrawlerError::from(anyhow::Error::from(json_error)),
+            CrawlerError::ParseError(_)
+        ));
+        assert!(matches!(
+            super::CrawlerError::from(anyhow::Error::from(access_error)),
+            CrawlerError::ParseError(_)
+        ));
+        assert!(!super::CrawlerError::from(anyhow::Error::from(
+            serde_json::from_str::<serde_json::Value>("]").unwrap_err()
+        ))
+        .is_retryable());
+    }
+
+    #[test]
+    fn falls_back_to_db_error() {
+        let error = super::CrawlerError::from(anyhow::anyhow!("connection reset"));
+
+        assert!(matches!(error, CrawlerError::DbError(_)));
+        assert_eq!(error.category(), "db");
+        assert!(error.is_retryable());
+    }
+}
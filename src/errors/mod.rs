@@ -0,0 +1 @@
+pub mod crawler_error;
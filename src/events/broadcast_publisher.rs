@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use tokio::sync::broadcast::{self, Receiver, Sender};
+
+use crate::events::{entity_event::EntityEvent, event_publisher::EventPublisher};
+
+/// Hands entity events to in-process subscribers, like the gRPC event streams, before passing
+/// them on to the configured publisher. Subscribers lagging more than `capacity` events behind
+/// miss the oldest ones.
+pub struct BroadcastPublisher {
+    sender: Sender<EntityEvent>,
+    publisher: Option<Arc<dyn EventPublisher>>,
+}
+
+impl BroadcastPublisher {
+    pub fn new(capacity: usize, publisher: Option<Arc<dyn EventPublisher>>) -> BroadcastPublisher {
+        let (sender, _) = broadcast::channel(capacity);
+
+        BroadcastPublisher { sender, publisher }
+    }
+
+    pub fn subscribe(&self) -> Receiver<EntityEvent> {
+        self.sender.subscribe()
+    }
+}
+
+#[async_trait]
+impl EventPublisher for BroadcastPublisher {
+    async fn publish(&self, event: &EntityEvent) -> Result<(), Error> {
+        // Sending only fails while nobody is subscribed
+        let _ = self.sender.send(event.clone());
+
+        match &self.publisher {
+            Some(publisher) => publisher.publish(event).await,
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::events::{entity_event::EntityEvent, event_publisher::EventPublisher};
+
+    #[tokio::test]
+    async fn subscribers_receive_published_events() {
+        let publisher = super::BroadcastPublisher::new(8, None);
+        let event = EntityEvent::ChannelDiscovered {
+            channel_id: "UC1".to_string(),
+            occurred_at: 0,
+        };
+
+        publisher.publish(&event).await.unwrap();
+
+        let mut receiver = publisher.subscribe();
+        publisher.publish(&event).await.unwrap();
+
+        assert_eq!(receiver.recv().await.unwrap(), event);
+        assert!(receiver.try_recv().is_err());
+    }
+}
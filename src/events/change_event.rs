@@ -0,0 +1,234 @@
+use chrono::Utc;
+use mongodb::bson::{Bson, Document};
+use mongodb::change_stream::event::{ChangeStreamEvent, OperationType};
+use serde::Serialize;
+use serde_json::Value;
+
+pub const CHANGE_OPERATION_INSERT: &str = "insert";
+pub const CHANGE_OPERATION_UPDATE: &str = "update";
+pub const CHANGE_OPERATION_DELETE: &str = "delete";
+
+/// A change of a stored channel or video, normalized from a MongoDB change event or a row of the
+/// change outbox of the postgres backend. Replaces are published as updates.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeEvent {
+    pub collection: String,
+    pub operation: &'static str,
+    pub id: String,
+    /// Events are keyed by channel like entity events, empty when a deleted video had no
+    /// pre-image
+    pub channel_id: String,
+    /// Only with pre-images enabled on the collection
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub updated_fields: Vec<String>,
+    pub removed_fields: Vec<String>,
+    pub occurred_at: i64,
+}
+
+impl ChangeEvent {
+    pub fn key(&self) -> &str {
+        if self.channel_id.is_empty() {
+            &self.id
+        } else {
+            &self.channel_id
+        }
+    }
+}
+
+/// Returns `None` for events that do not change a single document, like drops.
+pub fn get_change_event(event: &ChangeStreamEvent<Document>) -> Option<ChangeEvent> {
+    let operation = match event.operation_type {
+        OperationType::Insert => CHANGE_OPERATION_INSERT,
+        OperationType::Update | OperationType::Replace => CHANGE_OPERATION_UPDATE,
+        OperationType::Delete => CHANGE_OPERATION_DELETE,
+        _ => return None,
+    };
+    let collection = event.ns.as_ref()?.coll.clone()?;
+    let id = match event.document_key.as_ref()?.get("_id")? {
+        Bson::String(id) => id.to_string(),
+        id => id.to_string(),
+    };
+
+    let before = event.full_document_before_change.as_ref();
+    let after = event.full_document.as_ref();
+    let channel_id = get_channel_id(&collection, &id, before, after);
+
+    let (updated_fields, removed_fields) = match &event.update_description {
+        Some(description) => (
+            description.updated_fields.keys().cloned().collect(),
+            description.removed_fields.clone(),
+        ),
+        None => (vec![], vec![]),
+    };
+
+    Some(ChangeEvent {
+        collection,
+        operation,
+        id,
+        channel_id,
+        before: before.map(to_json),
+        after: after.map(to_json),
+        updated_fields,
+        removed_fields,
+        occurred_at: event
+            .cluster_time
+            .map(|time| time.time as i64)
+            .unwrap_or_else(|| Utc::now().timestamp()),
+    })
+}
+
+/// Postgres keeps both versions of a changed document, so the changed fields are the top-level
+/// fields that differ between them.
+pub fn get_outbox_change_event(
+    collection: String,
+    operation: &str,
+    id: String,
+    before: Option<Document>,
+    after: Option<Document>,
+    occurred_at: i64,
+) -> Option<ChangeEvent> {
+    let operation = match operation {
+        CHANGE_OPERATION_INSERT => CHANGE_OPERATION_INSERT,
+        CHANGE_OPERATION_UPDATE => CHANGE_OPERATION_UPDATE,
+        CHANGE_OPERATION_DELETE => CHANGE_OPERATION_DELETE,
+        _ => return None,
+    };
+    let channel_id = get_channel_id(&collection, &id, before.as_ref(), after.as_ref());
+
+    let (updated_fields, removed_fields) = match (&before, &after) {
+        (Some(before), Some(after)) if operation == CHANGE_OPERATION_UPDATE => (
+            after
+                .iter()
+                .filter(|(key, value)| before.get(key.as_str()) != Some(value))
+                .map(|(key, _)| key.clone())
+                .collect(),
+            before
+                .keys()
+                .filter(|key| !after.contains_key(key.as_str()))
+                .cloned()
+                .collect(),
+        ),
+        _ => (vec![], vec![]),
+    };
+
+    Some(ChangeEvent {
+        collection,
+        operation,
+        id,
+        channel_id,
+        before: before.as_ref().map(to_json),
+        after: after.as_ref().map(to_json),
+        updated_fields,
+        removed_fields,
+        occurred_at,
+    })
+}
+
+fn get_channel_id(
+    collection: &str,
+    id: &str,
+    before: Option<&Document>,
+    after: Option<&Document>,
+) -> String {
+    if collection == "channels" {
+        return id.to_string();
+    }
+
+    after
+        .or(before)
+        .and_then(|doc| doc.get_str("channel").ok())
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn to_json(doc: &Document) -> Value {
+    Bson::Document(doc.clone()).into_relaxed_extjson()
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::{doc, from_document, Document, Timestamp};
+    use mongodb::change_stream::event::ChangeStreamEvent;
+    use serde_json::json;
+
+    #[test]
+    fn normalizes_change_stream_events() {
+        let event: ChangeStreamEvent<Document> = from_document(doc! {
+            "_id": {"_data": "token"},
+            "operationType": "update",
+            "ns": {"db": "guitarchannels", "coll": "videos"},
+            "documentKey": {"_id": "video1"},
+            "updateDescription": {"updatedFields": {"views": 12i64}, "removedFields": ["tags"]},
+            "clusterTime": Timestamp { time: 1650000000, increment: 1 },
+            "fullDocument": {"_id": "video1", "channel": "UC1", "views": 12i64},
+        })
+        .unwrap();
+
+        let change = super::get_change_event(&event).unwrap();
+
+        assert_eq!(change.operation, super::CHANGE_OPERATION_UPDATE);
+        assert_eq!(change.key(), "UC1");
+        assert_eq!(change.before, None);
+        assert_eq!(
+            change.after,
+            Some(json!({"_id": "video1", "channel": "UC1", "views": 12}))
+        );
+        assert_eq!(change.updated_fields, vec!["views"]);
+        assert_eq!(change.removed_fields, vec!["tags"]);
+        assert_eq!(change.occurred_at, 1650000000);
+
+        let drop: ChangeStreamEvent<Document> = from_document(doc! {
+            "_id": {"_data": "token"},
+            "operationType": "drop",
+            "ns": {"db": "guitarchannels", "coll": "videos"},
+        })
+        .unwrap();
+
+        assert!(super::get_change_event(&drop).is_none());
+    }
+
+    #[test]
+    fn diffs_outbox_changes() {
+        let change = super::get_outbox_change_event(
+            "videos".to_string(),
+            "update",
+            "video1".to_string(),
+            Some(doc! {"_id": "video1", "channel": "UC1", "views": 10i64, "tags": ["riff"]}),
+            Some(doc! {"_id": "video1", "channel": "UC1", "views": 12i64, "title": "Solo"}),
+            1650000000,
+        )
+        .unwrap();
+
+        assert_eq!(change.key(), "UC1");
+        assert_eq!(change.updated_fields, vec!["views", "title"]);
+        assert_eq!(change.removed_fields, vec!["tags"]);
+        assert_eq!(
+            change.before,
+            Some(json!({"_id": "video1", "channel": "UC1", "views": 10, "tags": ["riff"]}))
+        );
+
+        let insert = super::get_outbox_change_event(
+            "channels".to_string(),
+            "insert",
+            "UC2".to_string(),
+            None,
+            Some(doc! {"_id": "UC2", "title": "Riffs"}),
+            1650000000,
+        )
+        .unwrap();
+
+        assert_eq!(insert.key(), "UC2");
+        assert!(insert.updated_fields.is_empty());
+        assert!(super::get_outbox_change_event(
+            "videos".to_string(),
+            "truncate",
+            "video1".to_string(),
+            None,
+            None,
+            1650000000
+        )
+        .is_none());
+    }
+}
@@ -0,0 +1,9 @@
+use anyhow::Error;
+use async_trait::async_trait;
+
+use crate::events::change_event::ChangeEvent;
+
+#[async_trait]
+pub trait ChangeSink: Send + Sync {
+    async fn send(&self, event: &ChangeEvent) -> Result<(), Error>;
+}
@@ -0,0 +1,126 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Error;
+use log::{info, warn};
+use mongodb::bson::doc;
+use mongodb::options::{ChangeStreamOptions, FullDocumentBeforeChangeType, FullDocumentType};
+use mongodb::Client;
+use tokio::time::sleep;
+
+use crate::{
+    events::{change_event::get_change_event, change_sink::ChangeSink},
+    repos::settings_repo::SettingsRepository,
+    utils::{db::get_db_name, maintenance::Maintenance},
+};
+
+const WATCHED_COLLECTIONS: [&str; 3] = ["channels", "videos", "coldvideos"];
+// The resume token is stored after this many changes, and whenever the stream is idle
+const TOKEN_SAVE_INTERVAL: u32 = 100;
+// Failed streams are opened again after a delay doubling up to the maximum
+const RETRY_BASE_SECONDS: u64 = 1;
+const RETRY_MAX_SECONDS: u64 = 300;
+
+/// Tails the MongoDB change streams of channels and videos and passes their changes on to the
+/// sink, so downstream indexes stay in sync without full re-syncs. Resumes after the last stored
+/// token on restarts, so changes since then are sent again at least once. A failing sink or
+/// stream does the same after a backoff, instead of ending the publisher.
+pub struct ChangeStreamPublisher {
+    client: Client,
+    environment: String,
+    settings_repo: SettingsRepository,
+    sink: Arc<dyn ChangeSink>,
+    maintenance: Arc<Maintenance>,
+}
+
+impl ChangeStreamPublisher {
+    pub fn new(
+        client: Client,
+        environment: String,
+        settings_repo: SettingsRepository,
+        sink: Arc<dyn ChangeSink>,
+        maintenance: Arc<Maintenance>,
+    ) -> ChangeStreamPublisher {
+        ChangeStreamPublisher {
+            client,
+            environment,
+            settings_repo,
+            sink,
+            maintenance,
+        }
+    }
+
+    pub async fn run(&self) {
+        let mut retry_seconds = RETRY_BASE_SECONDS;
+
+        loop {
+            let mut saved_token = false;
+
+            if let Err(e) = self.publish_changes(&mut saved_token).await {
+                // A stream that got going before it failed starts over with a short delay
+                if saved_token {
+                    retry_seconds = RETRY_BASE_SECONDS;
+                }
+
+                warn!(
+                    "Change stream failed, resuming after the stored token in {} seconds: {}",
+                    retry_seconds, e
+                );
+                sleep(Duration::from_secs(retry_seconds)).await;
+                retry_seconds = (retry_seconds * 2).min(RETRY_MAX_SECONDS);
+            }
+        }
+    }
+
+    /// Only returns on errors, `saved_token` tells whether a resume token was stored before.
+    async fn publish_changes(&self, saved_token: &mut bool) -> Result<(), Error> {
+        let db = self.client.database(&get_db_name(&self.environment));
+        let resume_after = self.settings_repo.get_change_stream_token().await?;
+
+        info!(
+            "Watch changes of {:?}, resuming: {}",
+            WATCHED_COLLECTIONS,
+            resume_after.is_some()
+        );
+
+        let pipeline = vec![doc! {
+            "$match": {
+                "ns.coll": { "$in": WATCHED_COLLECTIONS.to_vec() },
+                "operationType": { "$in": ["insert", "update", "replace", "delete"] },
+            }
+        }];
+        // Pre-images need changeStreamPreAndPostImages on the collections, without it `before`
+        // stays empty
+        let options = ChangeStreamOptions::builder()
+            .full_document(Some(FullDocumentType::UpdateLookup))
+            .full_document_before_change(Some(FullDocumentBeforeChangeType::WhenAvailable))
+            .resume_after(resume_after)
+            .build();
+
+        let mut stream = db.watch(pipeline, options).await?;
+        let mut unsaved_changes = 0;
+
+        loop {
+            self.maintenance.checkpoint("change stream publisher").await;
+
+            let idle = match stream.next_if_any().await? {
+                Some(event) => {
+                    if let Some(change) = get_change_event(&event) {
+                        self.sink.send(&change).await?;
+                    }
+                    unsaved_changes += 1;
+                    false
+                }
+                None => true,
+            };
+
+            if unsaved_changes > 0 && (idle || unsaved_changes >= TOKEN_SAVE_INTERVAL) {
+                if let Some(token) = stream.resume_token() {
+                    self.settings_repo.set_change_stream_token(&token).await?;
+                    *saved_token = true;
+                }
+                unsaved_changes = 0;
+            }
+        }
+    }
+}
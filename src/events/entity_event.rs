@@ -0,0 +1,76 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all_fields = "camelCase")]
+pub enum EntityEvent {
+    VideoUpserted {
+        video_id: String,
+        channel_id: String,
+        occurred_at: i64,
+    },
+    ChannelDiscovered {
+        channel_id: String,
+        occurred_at: i64,
+    },
+    ChannelUpdated {
+        channel_id: String,
+        occurred_at: i64,
+    },
+    ChannelDeactivated {
+        channel_id: String,
+        reason: String,
+        occurred_at: i64,
+    },
+    ChannelRedirected {
+        channel_id: String,
+        redirects_to: String,
+        occurred_at: i64,
+    },
+    MilestoneReached {
+        channel_id: String,
+        milestone: i64,
+        subscribers: i64,
+        occurred_at: i64,
+    },
+    TopicDriftDetected {
+        channel_id: String,
+        recent_density: f64,
+        baseline_density: f64,
+        occurred_at: i64,
+    },
+}
+
+impl EntityEvent {
+    /// Events are keyed by channel so all events of one channel keep their order.
+    pub fn key(&self) -> &str {
+        match self {
+            EntityEvent::VideoUpserted { channel_id, .. } => channel_id,
+            EntityEvent::ChannelDiscovered { channel_id, .. } => channel_id,
+            EntityEvent::ChannelUpdated { channel_id, .. } => channel_id,
+            EntityEvent::ChannelDeactivated { channel_id, .. } => channel_id,
+            EntityEvent::ChannelRedirected { channel_id, .. } => channel_id,
+            EntityEvent::MilestoneReached { channel_id, .. } => channel_id,
+            EntityEvent::TopicDriftDetected { channel_id, .. } => channel_id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EntityEvent;
+
+    #[test]
+    fn serializes_with_type_tag_and_camel_case_fields() {
+        let event = EntityEvent::VideoUpserted {
+            video_id: "video".to_string(),
+            channel_id: "channel".to_string(),
+            occurred_at: 1650000000,
+        };
+
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"type":"VideoUpserted","videoId":"video","channelId":"channel","occurredAt":1650000000}"#
+        );
+        assert_eq!(event.key(), "channel");
+    }
+}
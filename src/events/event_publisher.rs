@@ -0,0 +1,9 @@
+use anyhow::Error;
+use async_trait::async_trait;
+
+use crate::events::entity_event::EntityEvent;
+
+#[async_trait]
+pub trait EventPublisher: Send + Sync {
+    async fn publish(&self, event: &EntityEvent) -> Result<(), Error>;
+}
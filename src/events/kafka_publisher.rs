@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::ClientConfig;
+
+use crate::events::{
+    change_event::ChangeEvent, change_sink::ChangeSink, entity_event::EntityEvent,
+    event_publisher::EventPublisher,
+};
+
+const SEND_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct KafkaPublisher {
+    producer: FutureProducer,
+    topic: String,
+}
+
+impl KafkaPublisher {
+    pub fn new(brokers: &str, topic: String) -> Result<KafkaPublisher, Error> {
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set("message.timeout.ms", "5000")
+            .create()?;
+
+        Ok(KafkaPublisher { producer, topic })
+    }
+
+    async fn send_json(&self, key: &str, payload: &str) -> Result<(), Error> {
+        let record = FutureRecord::to(&self.topic).key(key).payload(payload);
+
+        self.producer
+            .send(record, SEND_TIMEOUT)
+            .await
+            .map_err(|(e, _)| anyhow!("Failed to publish event to kafka: {}", e))?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EventPublisher for KafkaPublisher {
+    async fn publish(&self, event: &EntityEvent) -> Result<(), Error> {
+        self.send_json(event.key(), &serde_json::to_string(event)?)
+            .await
+    }
+}
+
+#[async_trait]
+impl ChangeSink for KafkaPublisher {
+    async fn send(&self, event: &ChangeEvent) -> Result<(), Error> {
+        self.send_json(event.key(), &serde_json::to_string(event)?)
+            .await
+    }
+}
@@ -0,0 +1,11 @@
+pub mod broadcast_publisher;
+pub mod change_event;
+pub mod change_sink;
+pub mod change_stream_publisher;
+pub mod entity_event;
+pub mod event_publisher;
+pub mod kafka_publisher;
+pub mod nats_change_sink;
+pub mod postgres_change_stream_publisher;
+pub mod publishing_channel_store;
+pub mod publishing_video_store;
@@ -0,0 +1,34 @@
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
+
+use crate::events::{change_event::ChangeEvent, change_sink::ChangeSink};
+
+/// Publishes change events as JSON to a NATS subject, suffixed with the collection, e.g.
+/// `crawler.changes.videos`.
+pub struct NatsChangeSink {
+    client: async_nats::Client,
+    subject: String,
+}
+
+impl NatsChangeSink {
+    pub fn new(client: async_nats::Client, subject: String) -> NatsChangeSink {
+        NatsChangeSink { client, subject }
+    }
+}
+
+#[async_trait]
+impl ChangeSink for NatsChangeSink {
+    async fn send(&self, event: &ChangeEvent) -> Result<(), Error> {
+        let payload = serde_json::to_vec(event)?;
+
+        self.client
+            .publish(
+                format!("{}.{}", self.subject, event.collection),
+                payload.into(),
+            )
+            .await
+            .map_err(|e| anyhow!("Failed to publish change to nats: {}", e))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,217 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Error;
+use log::{info, warn};
+use serde_json::Value;
+use tokio::time::sleep;
+use tokio_postgres::Client;
+
+use crate::{
+    events::{change_event::get_outbox_change_event, change_sink::ChangeSink},
+    utils::{document_utils::from_json, maintenance::Maintenance},
+};
+
+const TABLE: &str = "change_outbox";
+const WATCHED_TABLES: [&str; 2] = ["channels", "videos"];
+const CHANGES_PER_POLL: i64 = 100;
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+// Failed polls are retried after a delay doubling up to the maximum
+const RETRY_BASE_SECONDS: u64 = 1;
+const RETRY_MAX_SECONDS: u64 = 300;
+
+/// Records the changes of channels and videos in the outbox, with both versions of the document.
+/// Archived videos are rows flagged `cold`, their changes are recorded like those of `coldvideos`
+/// and archiving like the delete and insert MongoDB sees.
+const RECORD_CHANGE_FUNCTION: &str = "
+CREATE OR REPLACE FUNCTION record_change() RETURNS trigger AS $$
+DECLARE
+    old_collection TEXT := TG_TABLE_NAME;
+    new_collection TEXT := TG_TABLE_NAME;
+    old_doc JSONB;
+    new_doc JSONB;
+BEGIN
+    IF TG_OP <> 'INSERT' THEN
+        old_doc := jsonb_build_object('_id', OLD.id) || OLD.doc;
+        IF TG_TABLE_NAME = 'videos' AND OLD.cold THEN
+            old_collection := 'coldvideos';
+        END IF;
+    END IF;
+    IF TG_OP <> 'DELETE' THEN
+        new_doc := jsonb_build_object('_id', NEW.id) || NEW.doc;
+        IF TG_TABLE_NAME = 'videos' AND NEW.cold THEN
+            new_collection := 'coldvideos';
+        END IF;
+    END IF;
+
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO change_outbox (collection, operation, document_id, after)
+        VALUES (new_collection, 'insert', NEW.id, new_doc);
+    ELSIF TG_OP = 'DELETE' THEN
+        INSERT INTO change_outbox (collection, operation, document_id, before)
+        VALUES (old_collection, 'delete', OLD.id, old_doc);
+    ELSIF old_collection <> new_collection THEN
+        INSERT INTO change_outbox (collection, operation, document_id, before)
+        VALUES (old_collection, 'delete', OLD.id, old_doc);
+        INSERT INTO change_outbox (collection, operation, document_id, after)
+        VALUES (new_collection, 'insert', NEW.id, new_doc);
+    ELSIF old_doc IS DISTINCT FROM new_doc THEN
+        INSERT INTO change_outbox (collection, operation, document_id, before, after)
+        VALUES (new_collection, 'update', NEW.id, old_doc, new_doc);
+    END IF;
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;";
+
+/// Creates the change outbox and the triggers filling it if the change stream is enabled, and
+/// drops the triggers if not, so a disabled change stream does not fill the outbox.
+pub async fn set_up_change_outbox(client: &Client, enabled: bool) -> Result<(), Error> {
+    if !enabled {
+        for table in WATCHED_TABLES {
+            client
+                .batch_execute(&format!(
+                    "DROP TRIGGER IF EXISTS {0}_change_outbox ON {0};",
+                    table
+                ))
+                .await?;
+        }
+
+        return Ok(());
+    }
+
+    client
+        .batch_execute(&format!(
+            "CREATE TABLE IF NOT EXISTS {} (
+                position BIGSERIAL PRIMARY KEY,
+                collection TEXT NOT NULL,
+                operation TEXT NOT NULL,
+                document_id TEXT NOT NULL,
+                before JSONB,
+                after JSONB,
+                occurred_at BIGINT NOT NULL DEFAULT extract(epoch FROM now())::bigint
+            );",
+            TABLE
+        ))
+        .await?;
+    client.batch_execute(RECORD_CHANGE_FUNCTION).await?;
+
+    for table in WATCHED_TABLES {
+        client
+            .batch_execute(&format!(
+                "DROP TRIGGER IF EXISTS {0}_change_outbox ON {0};
+                CREATE TRIGGER {0}_change_outbox
+                AFTER INSERT OR UPDATE OR DELETE ON {0}
+                FOR EACH ROW EXECUTE FUNCTION record_change();",
+                table
+            ))
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Passes the changes of channels and videos recorded in the change outbox of the postgres backend
+/// on to the sink, like `ChangeStreamPublisher` does with MongoDB change streams. Changes are
+/// deleted from the outbox once sent, so restarts and a failing sink send the unsent ones, and may
+/// send the last ones again, after a backoff.
+pub struct PostgresChangeStreamPublisher {
+    client: Arc<Client>,
+    sink: Arc<dyn ChangeSink>,
+    maintenance: Arc<Maintenance>,
+}
+
+impl PostgresChangeStreamPublisher {
+    pub fn new(
+        client: Arc<Client>,
+        sink: Arc<dyn ChangeSink>,
+        maintenance: Arc<Maintenance>,
+    ) -> PostgresChangeStreamPublisher {
+        PostgresChangeStreamPublisher {
+            client,
+            sink,
+            maintenance,
+        }
+    }
+
+    pub async fn run(&self) {
+        let mut retry_seconds = RETRY_BASE_SECONDS;
+
+        info!("Publish changes of {:?} from the outbox", WATCHED_TABLES);
+
+        loop {
+            self.maintenance.checkpoint("change stream publisher").await;
+
+            match self.publish_changes().await {
+                Ok(0) => {
+                    retry_seconds = RETRY_BASE_SECONDS;
+                    sleep(POLL_INTERVAL).await;
+                }
+                Ok(_) => retry_seconds = RETRY_BASE_SECONDS,
+                Err(e) => {
+                    warn!(
+                        "Change outbox failed, retrying in {} seconds: {}",
+                        retry_seconds, e
+                    );
+                    sleep(Duration::from_secs(retry_seconds)).await;
+                    retry_seconds = (retry_seconds * 2).min(RETRY_MAX_SECONDS);
+                }
+            }
+        }
+    }
+
+    /// Sends the oldest changes and returns how many were sent. Only the sent rows are deleted,
+    /// changes committed meanwhile with a lower position are sent by the next poll.
+    async fn publish_changes(&self) -> Result<usize, Error> {
+        let rows = self
+            .client
+            .query(
+                &format!(
+                    "SELECT position, collection, operation, document_id, before, after, occurred_at
+                    FROM {} ORDER BY position LIMIT $1",
+                    TABLE
+                ),
+                &[&CHANGES_PER_POLL],
+            )
+            .await?;
+        let mut positions: Vec<i64> = vec![];
+
+        for row in &rows {
+            let change = get_outbox_change_event(
+                row.get(1),
+                row.get(2),
+                row.get(3),
+                row.get::<_, Option<Value>>(4).map(from_json),
+                row.get::<_, Option<Value>>(5).map(from_json),
+                row.get(6),
+            );
+
+            if let Some(change) = change {
+                if let Err(e) = self.sink.send(&change).await {
+                    self.delete(&positions).await?;
+                    return Err(e);
+                }
+            }
+            positions.push(row.get(0));
+        }
+
+        self.delete(&positions).await?;
+
+        Ok(positions.len())
+    }
+
+    async fn delete(&self, positions: &[i64]) -> Result<(), Error> {
+        if positions.is_empty() {
+            return Ok(());
+        }
+
+        self.client
+            .execute(
+                &format!("DELETE FROM {} WHERE position = ANY($1)", TABLE),
+                &[&positions],
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,514 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use log::error;
+use mongodb::bson::Document;
+
+use crate::classifiers::genre_classifier::GenreScore;
+use crate::events::{entity_event::EntityEvent, event_publisher::EventPublisher};
+use crate::models::feed_state::FeedState;
+use crate::models::upload_pattern::UploadPattern;
+use crate::repos::channel_store::ChannelStore;
+use crate::utils::ban_evasion_utils::BanEvasionMatch;
+use crate::utils::channel_page_utils::ChannelPageHints;
+use crate::utils::gear_utils::GearCount;
+use crate::utils::link_utils::LinkCheck;
+use crate::utils::topic_drift_utils::TopicDrift;
+
+/// Wraps a channel store and publishes `ChannelDiscovered` for the first write of a channel and
+/// `ChannelUpdated` for every later change. Publishing failures are logged and never fail the
+/// write.
+pub struct PublishingChannelStore {
+    store: Box<dyn ChannelStore>,
+    publisher: Arc<dyn EventPublisher>,
+}
+
+impl PublishingChannelStore {
+    pub fn new(
+        store: Box<dyn ChannelStore>,
+        publisher: Arc<dyn EventPublisher>,
+    ) -> PublishingChannelStore {
+        PublishingChannelStore { store, publisher }
+    }
+
+    async fn publish(&self, id: &str, event: &EntityEvent) {
+        if let Err(e) = self.publisher.publish(event).await {
+            error!("Failed to publish event for channel {}: {}", id, e);
+        }
+    }
+
+    async fn publish_updated(&self, id: &str) {
+        let event = EntityEvent::ChannelUpdated {
+            channel_id: id.to_string(),
+            occurred_at: Utc::now().timestamp(),
+        };
+        self.publish(id, &event).await;
+    }
+}
+
+#[async_trait]
+impl ChannelStore for PublishingChannelStore {
+    async fn upsert(&self, id: &str, channel: Document) -> bool {
+        let is_new = self.store.upsert(id, channel).await;
+
+        let channel_id = id.to_string();
+        let occurred_at = Utc::now().timestamp();
+        let event = if is_new {
+            EntityEvent::ChannelDiscovered {
+                channel_id,
+                occurred_at,
+            }
+        } else {
+            EntityEvent::ChannelUpdated {
+                channel_id,
+                occurred_at,
+            }
+        };
+        self.publish(id, &event).await;
+
+        is_new
+    }
+
+    async fn exists(&self, channel_id: &str) -> Result<bool, Error> {
+        self.store.exists(channel_id).await
+    }
+
+    async fn get_id_by_handle(&self, handle: &str) -> Result<Option<String>, Error> {
+        self.store.get_id_by_handle(handle).await
+    }
+
+    async fn find_by_id(&self, id: &str) -> Result<Option<Document>, Error> {
+        self.store.find_by_id(id).await
+    }
+
+    async fn get_all_ids(&self) -> Result<Vec<String>, Error> {
+        self.store.get_all_ids().await
+    }
+
+    async fn get_page(
+        &self,
+        after_id: Option<&str>,
+        min_subscribers: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<Document>, Error> {
+        self.store.get_page(after_id, min_subscribers, limit).await
+    }
+
+    async fn get_ids_upload_last_month(
+        &self,
+        min_subscribers_count: i64,
+    ) -> Result<Vec<String>, Error> {
+        self.store
+            .get_ids_upload_last_month(min_subscribers_count)
+            .await
+    }
+
+    async fn get_ids_by_country_and_topic(
+        &self,
+        country: Option<&str>,
+        topic: Option<&str>,
+    ) -> Result<Vec<String>, Error> {
+        self.store
+            .get_ids_by_country_and_topic(country, topic)
+            .await
+    }
+
+    async fn get_ids_last_upload_before(
+        &self,
+        last_upload_before: chrono::DateTime<Utc>,
+    ) -> Result<Vec<String>, Error> {
+        self.store
+            .get_ids_last_upload_before(last_upload_before)
+            .await
+    }
+
+    async fn get_ids_due_for_scrape(
+        &self,
+        now: chrono::DateTime<Utc>,
+    ) -> Result<Vec<String>, Error> {
+        self.store.get_ids_due_for_scrape(now).await
+    }
+
+    async fn get_ids_about_crawled_before(
+        &self,
+        crawled_before: chrono::DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<String>, Error> {
+        self.store
+            .get_ids_about_crawled_before(crawled_before, limit)
+            .await
+    }
+
+    async fn get_ids_rebranded_since(
+        &self,
+        since: chrono::DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<String>, Error> {
+        self.store.get_ids_rebranded_since(since, limit).await
+    }
+
+    async fn get_ids_last_crawled_before(
+        &self,
+        last_crawl_before: chrono::DateTime<Utc>,
+        last_upload_after: chrono::DateTime<Utc>,
+    ) -> Result<Vec<String>, Error> {
+        self.store
+            .get_ids_last_crawled_before(last_crawl_before, last_upload_after)
+            .await
+    }
+
+    async fn count_grouped_by(
+        &self,
+        field: &str,
+        default_key: &str,
+    ) -> Result<Vec<(String, i64)>, Error> {
+        self.store.count_grouped_by(field, default_key).await
+    }
+
+    async fn count_created_since(&self, since: chrono::DateTime<Utc>) -> Result<i64, Error> {
+        self.store.count_created_since(since).await
+    }
+
+    async fn count_uploading_by_country(
+        &self,
+        uploaded_since: i64,
+        limit: i64,
+    ) -> Result<Vec<(String, i64)>, Error> {
+        self.store
+            .count_uploading_by_country(uploaded_since, limit)
+            .await
+    }
+
+    async fn get_ids_with_due_refresh_override(
+        &self,
+        now: chrono::DateTime<Utc>,
+    ) -> Result<Vec<String>, Error> {
+        self.store.get_ids_with_due_refresh_override(now).await
+    }
+
+    async fn set_refresh_override(
+        &self,
+        id: &str,
+        interval_seconds: i64,
+        until_timestamp: i64,
+    ) -> Result<bool, Error> {
+        let changed = self
+            .store
+            .set_refresh_override(id, interval_seconds, until_timestamp)
+            .await?;
+
+        if changed {
+            self.publish_updated(id).await;
+        }
+
+        Ok(changed)
+    }
+
+    async fn clear_refresh_override(&self, id: &str) -> Result<bool, Error> {
+        let changed = self.store.clear_refresh_override(id).await?;
+
+        if changed {
+            self.publish_updated(id).await;
+        }
+
+        Ok(changed)
+    }
+
+    async fn get_classification_override(&self, id: &str) -> Result<Option<String>, Error> {
+        self.store.get_classification_override(id).await
+    }
+
+    async fn set_classification_override(
+        &self,
+        id: &str,
+        classification_override: &str,
+    ) -> Result<bool, Error> {
+        let changed = self
+            .store
+            .set_classification_override(id, classification_override)
+            .await?;
+
+        if changed {
+            self.publish_updated(id).await;
+        }
+
+        Ok(changed)
+    }
+
+    async fn clear_classification_override(&self, id: &str) -> Result<bool, Error> {
+        let changed = self.store.clear_classification_override(id).await?;
+
+        if changed {
+            self.publish_updated(id).await;
+        }
+
+        Ok(changed)
+    }
+
+    async fn get_language(&self, id: &str) -> Result<Option<String>, Error> {
+        self.store.get_language(id).await
+    }
+
+    async fn get_detected_language(&self, id: &str) -> Result<String, Error> {
+        self.store.get_detected_language(id).await
+    }
+
+    async fn delete(&self, id: &str) -> Result<(), Error> {
+        self.store.delete(id).await
+    }
+
+    async fn set_video_count_last_upload(
+        &self,
+        id: &str,
+        video_count: i64,
+        last_upload_timestamp: i64,
+    ) {
+        self.store
+            .set_video_count_last_upload(id, video_count, last_upload_timestamp)
+            .await;
+        self.publish_updated(id).await;
+    }
+
+    async fn set_scrape_schedule(
+        &self,
+        id: &str,
+        next_scrape_at: i64,
+        upload_interval_seconds: Option<i64>,
+    ) -> Result<(), Error> {
+        self.store
+            .set_scrape_schedule(id, next_scrape_at, upload_interval_seconds)
+            .await?;
+        self.publish_updated(id).await;
+
+        Ok(())
+    }
+
+    async fn set_scrape_failure(
+        &self,
+        id: &str,
+        failures: i64,
+        next_scrape_at: i64,
+    ) -> Result<(), Error> {
+        self.store
+            .set_scrape_failure(id, failures, next_scrape_at)
+            .await?;
+        self.publish_updated(id).await;
+
+        Ok(())
+    }
+
+    async fn set_about(
+        &self,
+        id: &str,
+        links: Vec<Document>,
+        contact_emails: Vec<String>,
+    ) -> Result<(), Error> {
+        self.store.set_about(id, links, contact_emails).await?;
+        self.publish_updated(id).await;
+
+        Ok(())
+    }
+
+    async fn set_page_hints(&self, id: &str, hints: &ChannelPageHints) -> Result<(), Error> {
+        self.store.set_page_hints(id, hints).await?;
+        self.publish_updated(id).await;
+
+        Ok(())
+    }
+
+    async fn set_link_checks(&self, id: &str, checks: &[LinkCheck]) -> Result<(), Error> {
+        self.store.set_link_checks(id, checks).await?;
+        self.publish_updated(id).await;
+
+        Ok(())
+    }
+
+    async fn set_verified_metadata(&self, id: &str, changes: Document) -> Result<(), Error> {
+        self.store.set_verified_metadata(id, changes).await?;
+        self.publish_updated(id).await;
+
+        Ok(())
+    }
+
+    async fn set_upload_pattern(&self, id: &str, pattern: &UploadPattern) -> Result<(), Error> {
+        self.store.set_upload_pattern(id, pattern).await?;
+        self.publish_updated(id).await;
+
+        Ok(())
+    }
+
+    async fn get_upload_pattern(&self, id: &str) -> Result<Option<UploadPattern>, Error> {
+        self.store.get_upload_pattern(id).await
+    }
+
+    async fn set_genre_tags(&self, id: &str, genres: &[GenreScore]) -> Result<(), Error> {
+        self.store.set_genre_tags(id, genres).await?;
+        self.publish_updated(id).await;
+
+        Ok(())
+    }
+
+    async fn set_gear(&self, id: &str, gear: &[GearCount]) -> Result<(), Error> {
+        self.store.set_gear(id, gear).await?;
+        self.publish_updated(id).await;
+
+        Ok(())
+    }
+
+    async fn set_lifecycle(
+        &self,
+        id: &str,
+        lifecycle: &str,
+        previous: Option<&str>,
+    ) -> Result<(), Error> {
+        self.store.set_lifecycle(id, lifecycle, previous).await?;
+        self.publish_updated(id).await;
+
+        Ok(())
+    }
+
+    async fn set_duplicate_of(
+        &self,
+        id: &str,
+        canonical_id: &str,
+        reason: &str,
+    ) -> Result<(), Error> {
+        self.store
+            .set_duplicate_of(id, canonical_id, reason)
+            .await?;
+        self.publish_updated(id).await;
+
+        Ok(())
+    }
+
+    async fn set_topic_drift(&self, id: &str, drift: Option<&TopicDrift>) -> Result<(), Error> {
+        self.store.set_topic_drift(id, drift).await?;
+        self.publish_updated(id).await;
+
+        Ok(())
+    }
+
+    async fn set_ban_evasion(&self, id: &str, ban_evasion: &BanEvasionMatch) -> Result<(), Error> {
+        self.store.set_ban_evasion(id, ban_evasion).await?;
+        self.publish_updated(id).await;
+
+        Ok(())
+    }
+
+    async fn set_avatar_hash(&self, id: &str, avatar_hash: u64) -> Result<(), Error> {
+        self.store.set_avatar_hash(id, avatar_hash).await?;
+        self.publish_updated(id).await;
+
+        Ok(())
+    }
+
+    async fn set_merged(&self, id: &str, canonical_id: &str) -> Result<(), Error> {
+        self.store.set_merged(id, canonical_id).await?;
+        self.publish_updated(id).await;
+
+        Ok(())
+    }
+
+    async fn set_scrape_error(&self, id: &str, error: String) {
+        self.store.set_scrape_error(id, error).await;
+        self.publish_updated(id).await;
+    }
+
+    async fn get_ids_reclassified_before(
+        &self,
+        reclassified_before: chrono::DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<String>, Error> {
+        self.store
+            .get_ids_reclassified_before(reclassified_before, limit)
+            .await
+    }
+
+    async fn set_reclassified(&self, id: &str) -> Result<(), Error> {
+        self.store.set_reclassified(id).await?;
+        self.publish_updated(id).await;
+
+        Ok(())
+    }
+
+    async fn deactivate(&self, id: &str, reason: &str) -> Result<(), Error> {
+        self.store.deactivate(id, reason).await?;
+
+        let event = EntityEvent::ChannelDeactivated {
+            channel_id: id.to_string(),
+            reason: reason.to_string(),
+            occurred_at: Utc::now().timestamp(),
+        };
+        self.publish(id, &event).await;
+
+        Ok(())
+    }
+
+    async fn get_redirect(&self, id: &str) -> Result<Option<String>, Error> {
+        self.store.get_redirect(id).await
+    }
+
+    async fn set_redirect(&self, id: &str, redirects_to: &str) -> Result<(), Error> {
+        self.store.set_redirect(id, redirects_to).await?;
+
+        let event = EntityEvent::ChannelRedirected {
+            channel_id: id.to_string(),
+            redirects_to: redirects_to.to_string(),
+            occurred_at: Utc::now().timestamp(),
+        };
+        self.publish(id, &event).await;
+
+        Ok(())
+    }
+
+    async fn get_feed_state(&self, id: &str) -> Result<FeedState, Error> {
+        self.store.get_feed_state(id).await
+    }
+
+    async fn set_feed_state(&self, id: &str, feed_state: &FeedState) -> Result<(), Error> {
+        self.store.set_feed_state(id, feed_state).await?;
+        self.publish_updated(id).await;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use mongodb::bson::doc;
+
+    use crate::events::{broadcast_publisher::BroadcastPublisher, entity_event::EntityEvent};
+    use crate::repos::channel_store::ChannelStore;
+    use crate::test_support::fake_stores::FakeChannelStore;
+
+    #[tokio::test]
+    async fn publishes_every_change() {
+        let publisher = Arc::new(BroadcastPublisher::new(8, None));
+        let mut receiver = publisher.subscribe();
+        let store = super::PublishingChannelStore::new(
+            Box::new(FakeChannelStore::default()),
+            publisher.clone(),
+        );
+
+        assert!(store.upsert("UC1", doc! {"title": "Guitar"}).await);
+        assert!(!store.upsert("UC1", doc! {"title": "Guitars"}).await);
+        store
+            .set_page_hints("UC1", &Default::default())
+            .await
+            .unwrap();
+
+        let types = (0..3)
+            .map(|_| match receiver.try_recv().unwrap() {
+                EntityEvent::ChannelDiscovered { .. } => "discovered",
+                EntityEvent::ChannelUpdated { .. } => "updated",
+                _ => "other",
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(types, vec!["discovered", "updated", "updated"]);
+    }
+}
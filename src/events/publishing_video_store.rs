@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use log::error;
+use mongodb::bson::Document;
+
+use crate::events::{entity_event::EntityEvent, event_publisher::EventPublisher};
+use crate::repos::video_store::VideoStore;
+use crate::utils::{sentiment_utils::CommentSentiment, song_utils::CoverOf};
+
+/// Wraps a video store and publishes a `VideoUpserted` event after every successful upsert.
+pub struct PublishingVideoStore {
+    store: Box<dyn VideoStore>,
+    publisher: Arc<dyn EventPublisher>,
+}
+
+impl PublishingVideoStore {
+    pub fn new(
+        store: Box<dyn VideoStore>,
+        publisher: Arc<dyn EventPublisher>,
+    ) -> PublishingVideoStore {
+        PublishingVideoStore { store, publisher }
+    }
+}
+
+#[async_trait]
+impl VideoStore for PublishingVideoStore {
+    async fn upsert(&self, id: &str, video_doc: Document) -> Result<(), Error> {
+        let channel_id = video_doc.get_str("channel").unwrap_or_default().to_string();
+
+        self.store.upsert(id, video_doc).await?;
+
+        let event = EntityEvent::VideoUpserted {
+            video_id: id.to_string(),
+            channel_id,
+            occurred_at: Utc::now().timestamp(),
+        };
+
+        if let Err(e) = self.publisher.publish(&event).await {
+            error!("Failed to publish event for video {}: {}", id, e);
+        }
+
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: &str) -> Result<Option<Document>, Error> {
+        self.store.find_by_id(id).await
+    }
+
+    async fn archive_by_channel(
+        &self,
+        channel_id: &str,
+        published_before: i64,
+        keep_latest: u64,
+    ) -> Result<u64, Error> {
+        self.store
+            .archive_by_channel(channel_id, published_before, keep_latest)
+            .await
+    }
+
+    async fn get_updated_lookup_by_ids(
+        &self,
+        channel_id: &str,
+        video_ids: &[String],
+    ) -> Result<HashMap<String, chrono::DateTime<Utc>>, Error> {
+        self.store
+            .get_updated_lookup_by_ids(channel_id, video_ids)
+            .await
+    }
+
+    async fn delete_all_by_channel(&self, channel_id: &str) -> Result<(), Error> {
+        self.store.delete_all_by_channel(channel_id).await
+    }
+
+    async fn count(&self, channel_id: &str) -> Result<u64, Error> {
+        self.store.count(channel_id).await
+    }
+
+    async fn get_ids_by_channel(&self, channel_id: &str) -> Result<Vec<String>, Error> {
+        self.store.get_ids_by_channel(channel_id).await
+    }
+
+    async fn get_views_by_ids(
+        &self,
+        channel_id: &str,
+        video_ids: &[String],
+    ) -> Result<HashMap<String, i64>, Error> {
+        self.store.get_views_by_ids(channel_id, video_ids).await
+    }
+
+    async fn get_ids_without_captions(
+        &self,
+        retry_before: i64,
+        excluded_channel_ids: &[String],
+        limit: i64,
+    ) -> Result<Vec<(String, String)>, Error> {
+        self.store
+            .get_ids_without_captions(retry_before, excluded_channel_ids, limit)
+            .await
+    }
+
+    async fn set_caption_keywords(
+        &self,
+        id: &str,
+        caption_keywords: Vec<String>,
+        caption_songs: &[CoverOf],
+    ) -> Result<(), Error> {
+        self.store
+            .set_caption_keywords(id, caption_keywords, caption_songs)
+            .await
+    }
+
+    async fn set_caption_retry(&self, id: &str, retry_at: i64) -> Result<(), Error> {
+        self.store.set_caption_retry(id, retry_at).await
+    }
+
+    async fn get_ids_comments_due(
+        &self,
+        published_after: i64,
+        crawled_before: i64,
+        limit: i64,
+    ) -> Result<Vec<(String, String)>, Error> {
+        self.store
+            .get_ids_comments_due(published_after, crawled_before, limit)
+            .await
+    }
+
+    async fn set_comments_crawled(&self, id: &str, crawled_at: i64) -> Result<(), Error> {
+        self.store.set_comments_crawled(id, crawled_at).await
+    }
+
+    async fn set_comment_sentiment(
+        &self,
+        id: &str,
+        sentiment: &CommentSentiment,
+    ) -> Result<(), Error> {
+        self.store.set_comment_sentiment(id, sentiment).await
+    }
+
+    async fn count_all(&self) -> Result<u64, Error> {
+        self.store.count_all().await
+    }
+
+    async fn get_page(
+        &self,
+        after_id: Option<&str>,
+        channel_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Document>, Error> {
+        self.store.get_page(after_id, channel_id, limit).await
+    }
+
+    async fn set_view_snapshot(&self, id: &str, field: &str, views: i64) -> Result<(), Error> {
+        self.store.set_view_snapshot(id, field, views).await
+    }
+
+    async fn get_published_timestamps(
+        &self,
+        channel_id: &str,
+        limit: i64,
+    ) -> Result<Vec<i64>, Error> {
+        self.store.get_published_timestamps(channel_id, limit).await
+    }
+
+    async fn get_tags(
+        &self,
+        channel_id: &str,
+        limit: i64,
+    ) -> Result<Vec<(Vec<String>, i64)>, Error> {
+        self.store.get_tags(channel_id, limit).await
+    }
+
+    async fn get_gear(&self, channel_id: &str, limit: i64) -> Result<Vec<Vec<String>>, Error> {
+        self.store.get_gear(channel_id, limit).await
+    }
+
+    async fn get_latest_texts(&self, channel_id: &str, limit: i64) -> Result<Vec<Document>, Error> {
+        self.store.get_latest_texts(channel_id, limit).await
+    }
+
+    async fn get_ids_availability_checked_before(
+        &self,
+        checked_before: i64,
+        limit: i64,
+    ) -> Result<Vec<(String, String)>, Error> {
+        self.store
+            .get_ids_availability_checked_before(checked_before, limit)
+            .await
+    }
+
+    async fn set_availability(&self, id: &str, availability: &str) -> Result<(), Error> {
+        self.store.set_availability(id, availability).await
+    }
+
+    async fn move_to_channel(
+        &self,
+        channel_id: &str,
+        target_channel_id: &str,
+    ) -> Result<u64, Error> {
+        self.store
+            .move_to_channel(channel_id, target_channel_id)
+            .await
+    }
+
+    async fn get_restricted_by_channel(
+        &self,
+        channel_id: &str,
+        limit: i64,
+    ) -> Result<Vec<Document>, Error> {
+        self.store
+            .get_restricted_by_channel(channel_id, limit)
+            .await
+    }
+
+    async fn get_covers(
+        &self,
+        song_key: &str,
+        artist_key: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Document>, Error> {
+        self.store.get_covers(song_key, artist_key, limit).await
+    }
+
+    async fn get_cover_songs(&self, limit: i64) -> Result<Vec<CoverOf>, Error> {
+        self.store.get_cover_songs(limit).await
+    }
+}
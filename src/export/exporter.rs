@@ -0,0 +1,234 @@
+use std::io::Write;
+
+use anyhow::Error;
+use clap::ValueEnum;
+use mongodb::bson::Document;
+use serde_json::Value;
+
+use crate::repos::{channel_store::ChannelStore, video_store::VideoStore};
+use crate::utils::document_utils::to_json;
+
+const PAGE_SIZE: i64 = 1000;
+
+const DEFAULT_CHANNEL_FIELDS: [&str; 5] = ["_id", "title", "subscribers", "country", "status"];
+const DEFAULT_VIDEO_FIELDS: [&str; 5] = ["_id", "channel", "title", "publishedAt", "views"];
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ExportFormat {
+    /// One JSON document per line
+    Ndjson,
+    Csv,
+}
+
+/// Streams channels and videos page by page into a writer, so exports never hold more than one
+/// page in memory.
+pub struct Exporter {
+    channel_repo: Box<dyn ChannelStore>,
+    video_repo: Box<dyn VideoStore>,
+}
+
+impl Exporter {
+    pub fn new(channel_repo: Box<dyn ChannelStore>, video_repo: Box<dyn VideoStore>) -> Exporter {
+        Exporter {
+            channel_repo,
+            video_repo,
+        }
+    }
+
+    /// Returns the number of exported channels.
+    pub async fn export_channels(
+        &self,
+        writer: &mut impl Write,
+        format: ExportFormat,
+        fields: &[String],
+        min_subscribers: Option<i64>,
+    ) -> Result<u64, Error> {
+        let fields = get_fields(format, fields, &DEFAULT_CHANNEL_FIELDS);
+        write_header(writer, format, &fields)?;
+
+        let mut count = 0;
+        let mut after_id = None;
+
+        loop {
+            let channels = self
+                .channel_repo
+                .get_page(after_id.as_deref(), min_subscribers, PAGE_SIZE)
+                .await?;
+
+            count += write_page(writer, format, &fields, &channels)?;
+
+            match get_last_id(&channels) {
+                Some(last_id) => after_id = Some(last_id),
+                None => break,
+            }
+        }
+
+        writer.flush()?;
+
+        Ok(count)
+    }
+
+    /// Returns the number of exported videos.
+    pub async fn export_videos(
+        &self,
+        writer: &mut impl Write,
+        format: ExportFormat,
+        fields: &[String],
+        channel_id: Option<&str>,
+    ) -> Result<u64, Error> {
+        let fields = get_fields(format, fields, &DEFAULT_VIDEO_FIELDS);
+        write_header(writer, format, &fields)?;
+
+        let mut count = 0;
+        let mut after_id = None;
+
+        loop {
+            let videos = self
+                .video_repo
+                .get_page(after_id.as_deref(), channel_id, PAGE_SIZE)
+                .await?;
+
+            count += write_page(writer, format, &fields, &videos)?;
+
+            match get_last_id(&videos) {
+                Some(last_id) => after_id = Some(last_id),
+                None => break,
+            }
+        }
+
+        writer.flush()?;
+
+        Ok(count)
+    }
+}
+
+/// CSV needs fixed columns, so it falls back to the default fields. NDJSON without fields
+/// exports the whole documents.
+fn get_fields(format: ExportFormat, fields: &[String], default_fields: &[&str]) -> Vec<String> {
+    if fields.is_empty() && format == ExportFormat::Csv {
+        return default_fields
+            .iter()
+            .map(|field| field.to_string())
+            .collect();
+    }
+
+    fields.to_vec()
+}
+
+fn get_last_id(documents: &[Document]) -> Option<String> {
+    documents
+        .last()
+        .and_then(|document| document.get_str("_id").ok())
+        .map(|id| id.to_string())
+}
+
+fn write_header(
+    writer: &mut impl Write,
+    format: ExportFormat,
+    fields: &[String],
+) -> Result<(), Error> {
+    if format == ExportFormat::Csv {
+        let header: Vec<String> = fields.iter().map(|field| escape_csv(field)).collect();
+        writeln!(writer, "{}", header.join(","))?;
+    }
+
+    Ok(())
+}
+
+fn write_page(
+    writer: &mut impl Write,
+    format: ExportFormat,
+    fields: &[String],
+    documents: &[Document],
+) -> Result<u64, Error> {
+    for document in documents {
+        writeln!(writer, "{}", format_row(format, fields, document))?;
+    }
+
+    Ok(documents.len() as u64)
+}
+
+fn format_row(format: ExportFormat, fields: &[String], document: &Document) -> String {
+    let json = to_json(document);
+
+    match format {
+        ExportFormat::Ndjson if fields.is_empty() => json.to_string(),
+        ExportFormat::Ndjson => {
+            let selected = fields
+                .iter()
+                .map(|field| (field.to_string(), get_field(&json, field)))
+                .collect::<serde_json::Map<String, Value>>();
+
+            Value::Object(selected).to_string()
+        }
+        ExportFormat::Csv => fields
+            .iter()
+            .map(|field| match get_field(&json, field) {
+                Value::Null => String::new(),
+                Value::String(text) => escape_csv(&text),
+                other => escape_csv(&other.to_string()),
+            })
+            .collect::<Vec<String>>()
+            .join(","),
+    }
+}
+
+/// Looks up a dotted path like `about.links`, missing fields are null.
+fn get_field(json: &Value, field: &str) -> Value {
+    field
+        .split('.')
+        .try_fold(json, |value, key| value.get(key))
+        .cloned()
+        .unwrap_or(Value::Null)
+}
+
+fn escape_csv(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        return format!("\"{}\"", value.replace('"', "\"\""));
+    }
+
+    value.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::doc;
+
+    use super::ExportFormat;
+
+    #[test]
+    fn formats_rows_with_selected_fields() {
+        let channel = doc! {
+            "_id": "channel",
+            "title": "Riffs, \"licks\" and more",
+            "subscribers": 12_000_i64,
+            "about": { "contactEmails": ["mail@example.com"] },
+        };
+        let fields = vec![
+            "_id".to_string(),
+            "title".to_string(),
+            "about.contactEmails".to_string(),
+            "country".to_string(),
+        ];
+
+        assert_eq!(
+            super::format_row(ExportFormat::Csv, &fields, &channel),
+            r#"channel,"Riffs, ""licks"" and more","[""mail@example.com""]","#
+        );
+        assert_eq!(
+            super::format_row(ExportFormat::Ndjson, &fields[..2], &channel),
+            r#"{"_id":"channel","title":"Riffs, \"licks\" and more"}"#
+        );
+    }
+
+    #[test]
+    fn csv_falls_back_to_default_fields() {
+        let defaults = ["_id", "title"];
+
+        assert_eq!(
+            super::get_fields(ExportFormat::Csv, &[], &defaults),
+            vec!["_id", "title"]
+        );
+        assert!(super::get_fields(ExportFormat::Ndjson, &[], &defaults).is_empty());
+    }
+}
@@ -0,0 +1,130 @@
+use anyhow::Error;
+use chrono::Utc;
+use log::{info, warn};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::{
+    notifications::{critical_alert::CriticalAlert, notification_service::NotificationService},
+    repos::{lock_store::LockStore, settings_store::SettingsStore},
+    utils::{
+        alert_utils::{get_stuck_queues, QueueProgress, QUOTA_ALERT_AFTER_SECONDS},
+        health::Health,
+        maintenance::Maintenance,
+    },
+};
+
+const COMPONENT_NAME: &str = "alertJob";
+
+/// Checks for conditions that need an operator: a quota pause lasting over an hour, a scraper
+/// queue that stopped draining and the database not answering. Found conditions are sent as
+/// critical alerts. Runs on every instance, so the database not answering is still noticed, but
+/// each alert takes a lease for its cooldown, so only one instance sends it.
+pub struct AlertJob {
+    settings_repo: Box<dyn SettingsStore>,
+    lock_repo: Box<dyn LockStore>,
+    notification_service: Arc<NotificationService>,
+    maintenance: Arc<Maintenance>,
+    interval_seconds: u64,
+    cooldown_seconds: u64,
+    health: Arc<Health>,
+}
+
+impl AlertJob {
+    pub fn new(
+        settings_repo: Box<dyn SettingsStore>,
+        lock_repo: Box<dyn LockStore>,
+        notification_service: Arc<NotificationService>,
+        maintenance: Arc<Maintenance>,
+        interval_seconds: u64,
+        cooldown_seconds: u64,
+        health: Arc<Health>,
+    ) -> AlertJob {
+        AlertJob {
+            settings_repo,
+            lock_repo,
+            notification_service,
+            maintenance,
+            interval_seconds,
+            cooldown_seconds,
+            health,
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Error> {
+        let mut queue_progress: HashMap<String, QueueProgress> = HashMap::new();
+        let mut database_unreachable_since: Option<i64> = None;
+
+        loop {
+            self.maintenance.checkpoint("alert job").await;
+
+            let now = Utc::now().timestamp();
+
+            if self.health.is_database_reachable().await {
+                database_unreachable_since = None;
+
+                match self.settings_repo.get_quota_pause().await {
+                    Ok(Some((paused_at, paused_until)))
+                        if paused_until > now && now - paused_at >= QUOTA_ALERT_AFTER_SECONDS =>
+                    {
+                        self.notify(&CriticalAlert::QuotaExhausted {
+                            paused_at,
+                            paused_until,
+                        })
+                        .await;
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("Failed to read the quota pause: {}", e),
+                }
+            } else {
+                let since = *database_unreachable_since.get_or_insert(now);
+
+                self.notify(&CriticalAlert::DatabaseUnreachable { since })
+                    .await;
+            }
+
+            let depths = self.health.get_queue_depths().await;
+            for (queue, progress) in get_stuck_queues(&mut queue_progress, &depths, now) {
+                self.notify(&CriticalAlert::QueueStuck {
+                    queue,
+                    depth: progress.depth,
+                    since: progress.since,
+                })
+                .await;
+            }
+
+            self.health.record_success(COMPONENT_NAME).await;
+
+            info!(
+                "Wait for {} seconds until next check",
+                self.interval_seconds
+            );
+
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+
+    /// The instance holding the lease of an alert keeps renewing it and sends it by the cooldown
+    /// of its notifiers. Without a readable lease, e.g. with the database unreachable, every
+    /// instance sends it.
+    async fn notify(&self, alert: &CriticalAlert) {
+        let lock_name = format!("alert:{}", alert.key());
+
+        match self
+            .lock_repo
+            .acquire(&lock_name, self.cooldown_seconds)
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => {
+                info!("Skip alert {}, another instance sends it", alert.key());
+                return;
+            }
+            Err(e) => warn!("Failed to acquire lock {}: {}", lock_name, e),
+        }
+
+        self.notification_service.notify_critical(alert).await;
+    }
+}
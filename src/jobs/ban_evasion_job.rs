@@ -0,0 +1,292 @@
+use anyhow::Error;
+use chrono::Utc;
+use log::{info, warn};
+use mongodb::bson::Document;
+use reqwest::Client;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::{
+    repos::{
+        blocklist_store::BlocklistStore, channel_store::ChannelStore, lock_store::LockStore,
+        review_queue_store::ReviewQueueStore, video_store::VideoStore,
+    },
+    utils::{
+        ban_evasion_utils::{
+            find_ban_evasion, get_avatar_hash, get_ban_evasion_review_document,
+            get_channel_fingerprint, get_fingerprint_document, parse_fingerprint,
+            ChannelFingerprint,
+        },
+        consts::{CHANNEL_STATUS_DEACTIVATED, DEACTIVATION_REASON_BAN_EVASION},
+        health::Health,
+        image_change_utils::{get_previous_avatar_hashes, parse_image_hash},
+        maintenance::Maintenance,
+    },
+};
+
+const PAGE_SIZE: i64 = 500;
+const VIDEO_TITLE_COUNT: i64 = 20;
+// Only channels first stored within this window are compared against the blocked channels
+const CANDIDATE_DAYS: i64 = 30;
+
+const AVATAR_TIMEOUT_SECONDS: u64 = 10;
+
+const LOCK_NAME: &str = "banEvasionJob";
+
+/// Compares recently stored channels against the fingerprints of blocked channels, including
+/// their video titles the channel scraper could not compare before accepting them. Likely
+/// reuploads or ban evasions get `banEvasion`, are deactivated and go to the review queue.
+pub struct BanEvasionJob {
+    channel_repo: Box<dyn ChannelStore>,
+    video_repo: Box<dyn VideoStore>,
+    blocklist_repo: Box<dyn BlocklistStore>,
+    review_queue_repo: Box<dyn ReviewQueueStore>,
+    http_client: Client,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    health: Arc<Health>,
+}
+
+impl BanEvasionJob {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        channel_repo: Box<dyn ChannelStore>,
+        video_repo: Box<dyn VideoStore>,
+        blocklist_repo: Box<dyn BlocklistStore>,
+        review_queue_repo: Box<dyn ReviewQueueStore>,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        health: Arc<Health>,
+    ) -> BanEvasionJob {
+        BanEvasionJob {
+            channel_repo,
+            video_repo,
+            blocklist_repo,
+            review_queue_repo,
+            http_client: Client::builder()
+                .timeout(Duration::from_secs(AVATAR_TIMEOUT_SECONDS))
+                .build()
+                .unwrap_or_default(),
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            health,
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Error> {
+        loop {
+            self.maintenance
+                .background_checkpoint("ban evasion job")
+                .await;
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
+            info!("Start ban evasion job");
+
+            let blocked = self.get_blocked_fingerprints().await?;
+            let match_count = if blocked.is_empty() {
+                0
+            } else {
+                self.detect_ban_evasions(&blocked).await?
+            };
+
+            info!(
+                "{} channels match one of {} blocked channels",
+                match_count,
+                blocked.len()
+            );
+
+            self.health.record_success(LOCK_NAME).await;
+
+            info!("Wait for {} seconds until next run", self.interval_seconds);
+
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+
+    /// Fingerprints blocked channels that are still stored once, on their blocklist entry.
+    async fn get_blocked_fingerprints(&self) -> Result<Vec<(String, ChannelFingerprint)>, Error> {
+        let mut fingerprints = vec![];
+
+        for entry in self.blocklist_repo.get_all().await? {
+            let blocked_channel = entry.get_str("_id")?;
+
+            if let Some(fingerprint) = entry
+                .get_document("fingerprint")
+                .ok()
+                .and_then(parse_fingerprint)
+            {
+                fingerprints.push((blocked_channel.to_string(), fingerprint));
+                continue;
+            }
+
+            let channel_id = if blocked_channel.starts_with('@') {
+                self.channel_repo.get_id_by_handle(blocked_channel).await?
+            } else {
+                Some(blocked_channel.to_string())
+            };
+            let channel = match channel_id {
+                Some(channel_id) => self.channel_repo.find_by_id(&channel_id).await?,
+                None => None,
+            };
+
+            if let Some(channel) = channel {
+                let fingerprint = self.get_fingerprint(&channel).await?;
+                self.blocklist_repo
+                    .set_fingerprint(blocked_channel, get_fingerprint_document(&fingerprint))
+                    .await?;
+                fingerprints.push((blocked_channel.to_string(), fingerprint));
+            }
+        }
+
+        Ok(fingerprints)
+    }
+
+    async fn detect_ban_evasions(
+        &self,
+        blocked: &[(String, ChannelFingerprint)],
+    ) -> Result<usize, Error> {
+        let blocked_channels = blocked
+            .iter()
+            .map(|(blocked_channel, _)| blocked_channel.as_str())
+            .collect::<HashSet<&str>>();
+        let created_after =
+            (Utc::now() - chrono::Duration::days(CANDIDATE_DAYS)).timestamp_millis();
+
+        let mut after_id: Option<String> = None;
+        let mut match_count = 0;
+
+        loop {
+            let channels = self
+                .channel_repo
+                .get_page(after_id.as_deref(), None, PAGE_SIZE)
+                .await?;
+
+            for channel in &channels {
+                let id = channel.get_str("_id")?;
+                let handle = channel.get_str("handle").unwrap_or_default();
+
+                if channel.get_str("status").ok() == Some(CHANNEL_STATUS_DEACTIVATED)
+                    || channel.get_document("banEvasion").is_ok()
+                    || blocked_channels.contains(id)
+                    || blocked_channels.contains(handle)
+                    || get_created_at_millis(channel).unwrap_or(0) < created_after
+                {
+                    continue;
+                }
+
+                let fingerprint = self.get_fingerprint(channel).await?;
+                let ban_evasion = match find_ban_evasion(&fingerprint, blocked) {
+                    Some(ban_evasion) => ban_evasion,
+                    None => continue,
+                };
+
+                info!(
+                    "Channel {} likely evades the ban of {} by {:?}",
+                    id, ban_evasion.blocked_channel, ban_evasion.signals
+                );
+
+                self.channel_repo.set_ban_evasion(id, &ban_evasion).await?;
+                self.channel_repo
+                    .deactivate(id, DEACTIVATION_REASON_BAN_EVASION)
+                    .await?;
+                self.review_queue_repo
+                    .reopen(
+                        id,
+                        get_ban_evasion_review_document(
+                            channel.get_str("title").unwrap_or(id),
+                            channel.get_str("description").unwrap_or_default(),
+                            &ban_evasion,
+                        ),
+                    )
+                    .await?;
+                match_count += 1;
+            }
+
+            match channels.last() {
+                Some(channel) if channels.len() as i64 == PAGE_SIZE => {
+                    after_id = Some(channel.get_str("_id")?.to_string());
+                }
+                _ => return Ok(match_count),
+            }
+        }
+    }
+
+    async fn get_fingerprint(&self, channel: &Document) -> Result<ChannelFingerprint, Error> {
+        let id = channel.get_str("_id")?;
+        let videos = self
+            .video_repo
+            .get_latest_texts(id, VIDEO_TITLE_COUNT)
+            .await?;
+        let video_titles = videos
+            .iter()
+            .filter_map(|video| video.get_str("title").ok())
+            .collect::<Vec<&str>>();
+
+        // The channel scraper keeps the hash of the current avatar, missing ones are stored once
+        let avatar_hash = match (
+            channel
+                .get_str("avatarHash")
+                .ok()
+                .and_then(parse_image_hash),
+            channel.get_str("thumbnail"),
+        ) {
+            (Some(avatar_hash), _) => Some(avatar_hash),
+            (None, Ok(url)) => {
+                let avatar_hash = self.get_avatar_hash(id, url).await;
+                if let Some(avatar_hash) = avatar_hash {
+                    self.channel_repo.set_avatar_hash(id, avatar_hash).await?;
+                }
+                avatar_hash
+            }
+            (None, Err(_)) => None,
+        };
+
+        Ok(ChannelFingerprint {
+            previous_avatar_hashes: get_previous_avatar_hashes(channel),
+            ..get_channel_fingerprint(
+                channel.get_str("title").unwrap_or_default(),
+                avatar_hash,
+                &video_titles,
+            )
+        })
+    }
+
+    /// An avatar that fails to load leaves the avatar out of the comparison.
+    async fn get_avatar_hash(&self, channel_id: &str, url: &str) -> Option<u64> {
+        let response = self
+            .http_client
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+
+        let bytes = match response {
+            Ok(response) => response.bytes().await.ok()?,
+            Err(e) => {
+                warn!("Failed to load avatar of channel {}: {}", channel_id, e);
+                return None;
+            }
+        };
+
+        get_avatar_hash(&bytes)
+    }
+}
+
+fn get_created_at_millis(channel: &Document) -> Option<i64> {
+    channel
+        .get_datetime("createdAt")
+        .ok()
+        .map(|date| date.timestamp_millis())
+}
@@ -0,0 +1,109 @@
+use anyhow::Error;
+use chrono::Utc;
+use log::info;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::{
+    repos::{channel_store::ChannelStore, lock_store::LockStore},
+    utils::{health::Health, maintenance::Maintenance, schedule_utils::compute_lifecycle},
+};
+
+const PAGE_SIZE: i64 = 500;
+
+const LOCK_NAME: &str = "channelLifecycleJob";
+
+/// Updates the lifecycle of all channels from their last upload and upload interval, recording
+/// each transition. The video scraper deprioritizes dormant channels on its own schedule.
+pub struct ChannelLifecycleJob {
+    channel_repo: Box<dyn ChannelStore>,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    health: Arc<Health>,
+}
+
+impl ChannelLifecycleJob {
+    pub fn new(
+        channel_repo: Box<dyn ChannelStore>,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        health: Arc<Health>,
+    ) -> ChannelLifecycleJob {
+        ChannelLifecycleJob {
+            channel_repo,
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            health,
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Error> {
+        loop {
+            self.maintenance
+                .background_checkpoint("channel lifecycle job")
+                .await;
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
+            info!("Start channel lifecycle job");
+
+            let transition_count = self.update_lifecycles().await?;
+
+            info!("{} channels changed their lifecycle", transition_count);
+
+            self.health.record_success(LOCK_NAME).await;
+
+            info!("Wait for {} seconds until next run", self.interval_seconds);
+
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+
+    async fn update_lifecycles(&self) -> Result<usize, Error> {
+        let now = Utc::now().timestamp();
+        let mut after_id: Option<String> = None;
+        let mut transition_count = 0;
+
+        loop {
+            let channels = self
+                .channel_repo
+                .get_page(after_id.as_deref(), None, PAGE_SIZE)
+                .await?;
+
+            for channel in &channels {
+                let id = channel.get_str("_id")?;
+                let previous = channel.get_str("lifecycle").ok();
+                let lifecycle = compute_lifecycle(
+                    now,
+                    channel.get_i64("lastUploadAt").ok(),
+                    channel.get_i64("uploadIntervalSeconds").ok(),
+                );
+
+                if let Some(lifecycle) = lifecycle.filter(|lifecycle| Some(*lifecycle) != previous)
+                {
+                    self.channel_repo
+                        .set_lifecycle(id, lifecycle, previous)
+                        .await?;
+                    transition_count += 1;
+                }
+            }
+
+            match channels.last() {
+                Some(channel) if channels.len() as i64 == PAGE_SIZE => {
+                    after_id = Some(channel.get_str("_id")?.to_string());
+                }
+                _ => return Ok(transition_count),
+            }
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use anyhow::Error;
+use log::info;
+use mongodb::bson::Document;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::{
+    repos::{channel_store::ChannelStore, lock_store::LockStore},
+    services::youtube_service::YoutubeService,
+    utils::{
+        channel_metadata_utils::get_metadata_changes, consts::CHANNEL_STATUS_DEACTIVATED,
+        health::Health, maintenance::Maintenance,
+    },
+};
+
+const PAGE_SIZE: i64 = 500;
+
+const LOCK_NAME: &str = "channelMetadataRefreshJob";
+
+/// Refreshes the title, description, avatar and handle of all tracked channels through
+/// `channels.list`, 50 channels per unit of quota. The channel update crawler only rescrapes
+/// channels that uploaded within the last year, this keeps the others current as well.
+pub struct ChannelMetadataRefreshJob {
+    channel_repo: Box<dyn ChannelStore>,
+    youtube_service: YoutubeService,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    health: Arc<Health>,
+}
+
+impl ChannelMetadataRefreshJob {
+    pub fn new(
+        channel_repo: Box<dyn ChannelStore>,
+        youtube_service: YoutubeService,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        health: Arc<Health>,
+    ) -> ChannelMetadataRefreshJob {
+        ChannelMetadataRefreshJob {
+            channel_repo,
+            youtube_service,
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            health,
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Error> {
+        loop {
+            self.maintenance
+                .background_checkpoint("channel metadata refresh job")
+                .await;
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
+            info!("Start channel metadata refresh job");
+
+            let (verified_count, changed_count) = self.refresh_metadata().await?;
+
+            info!(
+                "Verified the metadata of {} channels, {} changed",
+                verified_count, changed_count
+            );
+
+            self.health.record_success(LOCK_NAME).await;
+
+            info!("Wait for {} seconds until next run", self.interval_seconds);
+
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+
+    /// Returns the channels verified and the ones whose metadata changed.
+    async fn refresh_metadata(&self) -> Result<(usize, usize), Error> {
+        let mut after_id: Option<String> = None;
+        let mut verified_count = 0;
+        let mut changed_count = 0;
+
+        loop {
+            let channels = self
+                .channel_repo
+                .get_page(after_id.as_deref(), None, PAGE_SIZE)
+                .await?;
+
+            let stored = channels
+                .iter()
+                .filter(|channel| {
+                    channel.get_str("status").ok() != Some(CHANNEL_STATUS_DEACTIVATED)
+                })
+                .filter_map(|channel| Some((channel.get_str("_id").ok()?, channel)))
+                .collect::<HashMap<&str, &Document>>();
+            let channel_ids = stored
+                .keys()
+                .map(|id| id.to_string())
+                .collect::<Vec<String>>();
+
+            // Channels that were deleted or moved are left to the channel scraper
+            for item in self
+                .youtube_service
+                .get_channels_details(&channel_ids)
+                .await?
+            {
+                let channel = match stored.get(item.id.as_str()) {
+                    Some(channel) => channel,
+                    None => continue,
+                };
+
+                let changes = get_metadata_changes(channel, &item);
+                if !changes.is_empty() {
+                    info!(
+                        "Metadata of channel {} changed: {:?}",
+                        item.id,
+                        changes.keys().collect::<Vec<&String>>()
+                    );
+                    changed_count += 1;
+                }
+
+                self.channel_repo
+                    .set_verified_metadata(&item.id, changes)
+                    .await?;
+                verified_count += 1;
+            }
+
+            match channels.last() {
+                Some(channel) if channels.len() as i64 == PAGE_SIZE => {
+                    after_id = Some(channel.get_str("_id")?.to_string());
+                }
+                _ => return Ok((verified_count, changed_count)),
+            }
+        }
+    }
+}
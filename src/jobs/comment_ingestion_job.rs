@@ -0,0 +1,207 @@
+use anyhow::Error;
+use chrono::Utc;
+use log::{info, warn};
+use mongodb::bson::{doc, Document};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::{
+    models::youtube_comment_threads::CommentThread,
+    repos::{comment_store::CommentStore, lock_store::LockStore, video_store::VideoStore},
+    services::youtube_service::YoutubeService,
+    utils::{consts::ONE_DAYS_IN_SECONDS, health::Health, maintenance::Maintenance},
+};
+
+/// One unit per video, so a run costs at most this many units.
+const VIDEOS_PER_RUN: i64 = 500;
+
+/// Comments of older videos hardly change.
+const MAX_VIDEO_AGE_DAYS: i64 = 90;
+
+const RECRAWL_AFTER_DAYS: i64 = 7;
+
+const LOCK_NAME: &str = "commentIngestionJob";
+
+/// Stores the newest top-level comments of recent videos for the related channels and comment
+/// sentiment jobs.
+pub struct CommentIngestionJob {
+    video_repo: Box<dyn VideoStore>,
+    comment_repo: Box<dyn CommentStore>,
+    youtube_service: YoutubeService,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    health: Arc<Health>,
+}
+
+impl CommentIngestionJob {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        video_repo: Box<dyn VideoStore>,
+        comment_repo: Box<dyn CommentStore>,
+        youtube_service: YoutubeService,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        health: Arc<Health>,
+    ) -> CommentIngestionJob {
+        CommentIngestionJob {
+            video_repo,
+            comment_repo,
+            youtube_service,
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            health,
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Error> {
+        loop {
+            self.maintenance
+                .background_checkpoint("comment ingestion job")
+                .await;
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
+            info!("Start comment ingestion job");
+
+            let now = Utc::now().timestamp();
+            let videos = self
+                .video_repo
+                .get_ids_comments_due(
+                    now - MAX_VIDEO_AGE_DAYS * ONE_DAYS_IN_SECONDS as i64,
+                    now - RECRAWL_AFTER_DAYS * ONE_DAYS_IN_SECONDS as i64,
+                    VIDEOS_PER_RUN,
+                )
+                .await?;
+
+            let mut comment_count = 0;
+            for (video_id, channel_id) in &videos {
+                match self
+                    .youtube_service
+                    .get_comment_threads_page(video_id)
+                    .await
+                {
+                    Ok(threads) => {
+                        for thread in &threads.items {
+                            self.comment_repo
+                                .upsert(
+                                    &thread.snippet.top_level_comment.id,
+                                    get_comment_document(channel_id, thread),
+                                )
+                                .await?;
+                        }
+
+                        comment_count += threads.items.len();
+                    }
+                    // Videos with disabled comments fail the same way on the next run
+                    Err(e) if !e.is_retryable() => {
+                        info!("Skip comments of video {}: {}", video_id, e);
+                    }
+                    Err(e) => {
+                        warn!("Could not fetch comments of video {}: {}", video_id, e);
+                        continue;
+                    }
+                }
+
+                self.video_repo
+                    .set_comments_crawled(video_id, Utc::now().timestamp())
+                    .await?;
+            }
+
+            info!(
+                "Stored {} comments of {} videos",
+                comment_count,
+                videos.len()
+            );
+
+            self.health.record_success(LOCK_NAME).await;
+
+            info!("Wait for {} seconds until next run", self.interval_seconds);
+
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+}
+
+/// The comment as stored by the comment repository, `channel_id` is the channel of the video.
+fn get_comment_document(channel_id: &str, thread: &CommentThread) -> Document {
+    let snippet = &thread.snippet.top_level_comment.snippet;
+
+    let mut comment = doc! {
+        "videoId": &thread.snippet.video_id,
+        "channelId": channel_id,
+        "text": &snippet.text_original,
+        "publishedAt": snippet.published_at.map(|published_at| published_at.timestamp()),
+    };
+
+    if let Some(author_channel_id) = &snippet.author_channel_id {
+        comment.insert("authorChannelId", &author_channel_id.value);
+    }
+
+    comment
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use mongodb::bson::{doc, Bson};
+
+    use crate::models::youtube_comment_threads::{
+        AuthorChannelId, Comment, CommentSnippet, CommentThread, CommentThreadSnippet,
+    };
+
+    fn get_thread(author_channel_id: Option<&str>) -> CommentThread {
+        CommentThread {
+            id: "Ugthread".to_string(),
+            snippet: CommentThreadSnippet {
+                video_id: "video1".to_string(),
+                top_level_comment: Comment {
+                    id: "Ugcomment".to_string(),
+                    snippet: CommentSnippet {
+                        author_channel_id: author_channel_id.map(|value| AuthorChannelId {
+                            value: value.to_string(),
+                        }),
+                        text_original: "Great tone!".to_string(),
+                        published_at: Some(Utc.timestamp(1_600_000_000, 0)),
+                    },
+                },
+            },
+        }
+    }
+
+    #[test]
+    fn comment_document_has_the_fields_of_the_comment_repository() {
+        let comment = super::get_comment_document("UCguitar", &get_thread(Some("UCfan")));
+
+        assert_eq!(
+            comment,
+            doc! {
+                "videoId": "video1",
+                "channelId": "UCguitar",
+                "text": "Great tone!",
+                "publishedAt": 1_600_000_000_i64,
+                "authorChannelId": "UCfan",
+            }
+        );
+    }
+
+    #[test]
+    fn comment_document_leaves_out_missing_author() {
+        let comment = super::get_comment_document("UCguitar", &get_thread(None));
+
+        assert!(comment.get("authorChannelId").is_none());
+        assert_eq!(
+            comment.get("publishedAt"),
+            Some(&Bson::Int64(1_600_000_000))
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use anyhow::Error;
+use log::info;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::{
+    repos::lock_store::LockStore,
+    services::sentiment_service::SentimentService,
+    utils::{health::Health, maintenance::Maintenance},
+};
+
+const BATCH_SIZE: i64 = 500;
+
+const LOCK_NAME: &str = "commentSentimentJob";
+
+/// Scores the comments ingested since the last run in batches and updates the comment sentiment
+/// of their videos.
+pub struct CommentSentimentJob {
+    sentiment_service: SentimentService,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    health: Arc<Health>,
+}
+
+impl CommentSentimentJob {
+    pub fn new(
+        sentiment_service: SentimentService,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        health: Arc<Health>,
+    ) -> CommentSentimentJob {
+        CommentSentimentJob {
+            sentiment_service,
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            health,
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Error> {
+        loop {
+            self.maintenance
+                .background_checkpoint("comment sentiment job")
+                .await;
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
+            info!("Start comment sentiment job");
+
+            let mut scored_count = 0;
+            loop {
+                let scored = self.sentiment_service.score_batch(BATCH_SIZE).await?;
+                scored_count += scored;
+
+                if (scored as i64) < BATCH_SIZE {
+                    break;
+                }
+            }
+
+            info!("Scored the sentiment of {} comments", scored_count);
+
+            self.health.record_success(LOCK_NAME).await;
+
+            info!("Wait for {} seconds until next run", self.interval_seconds);
+
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+}
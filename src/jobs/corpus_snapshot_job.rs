@@ -0,0 +1,149 @@
+use anyhow::Error;
+use log::{error, info};
+use mongodb::bson::{doc, DateTime, Document};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::{
+    repos::{
+        channel_store::ChannelStore, corpus_snapshot_store::CorpusSnapshotStore,
+        lock_store::LockStore, video_store::VideoStore,
+    },
+    utils::{consts::CHANNEL_STATUS_ACTIVE, health::Health, maintenance::Maintenance},
+};
+
+const SIZED_COLLECTIONS: [&str; 5] = ["channels", "videos", "coldvideos", "views", "subscribers"];
+const UNKNOWN_LANGUAGE: &str = "unknown";
+
+const LOCK_NAME: &str = "corpusSnapshotJob";
+
+pub struct CorpusSnapshotJob {
+    channel_repo: Box<dyn ChannelStore>,
+    video_repo: Box<dyn VideoStore>,
+    corpus_snapshot_repo: Box<dyn CorpusSnapshotStore>,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    health: Arc<Health>,
+}
+
+impl CorpusSnapshotJob {
+    pub fn new(
+        channel_repo: Box<dyn ChannelStore>,
+        video_repo: Box<dyn VideoStore>,
+        corpus_snapshot_repo: Box<dyn CorpusSnapshotStore>,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        health: Arc<Health>,
+    ) -> CorpusSnapshotJob {
+        CorpusSnapshotJob {
+            channel_repo,
+            video_repo,
+            corpus_snapshot_repo,
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            health,
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Error> {
+        loop {
+            self.maintenance
+                .background_checkpoint("corpus snapshot job")
+                .await;
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
+            match self.take_snapshot().await {
+                Ok(()) => self.health.record_success(LOCK_NAME).await,
+                Err(e) => error!("Failed to take corpus snapshot: {}", e),
+            }
+
+            info!(
+                "Wait for {} seconds until next snapshot",
+                self.interval_seconds
+            );
+
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+
+    async fn take_snapshot(&self) -> Result<(), Error> {
+        let channels_by_status = self
+            .channel_repo
+            .count_grouped_by("status", CHANNEL_STATUS_ACTIVE)
+            .await?;
+        let channels_by_language = self
+            .channel_repo
+            .count_grouped_by("language", UNKNOWN_LANGUAGE)
+            .await?;
+        let total_channels: i64 = channels_by_status.iter().map(|(_, count)| count).sum();
+        let total_videos = self.video_repo.count_all().await? as i64;
+
+        let mut storage = Document::new();
+        let mut total_storage_size: i64 = 0;
+
+        for collection_name in SIZED_COLLECTIONS {
+            let (size, storage_size) = self
+                .corpus_snapshot_repo
+                .get_collection_size(collection_name)
+                .await?;
+
+            total_storage_size += storage_size;
+            storage.insert(
+                collection_name,
+                doc! { "size": size, "storageSize": storage_size },
+            );
+        }
+
+        info!(
+            "CORPUS: {} channels ({}), {} videos, languages: {}, storage: {} MB",
+            total_channels,
+            format_counts(&channels_by_status),
+            total_videos,
+            format_counts(&channels_by_language),
+            total_storage_size / (1024 * 1024)
+        );
+
+        let snapshot = doc! {
+            "createdAt": DateTime::now(),
+            "totalChannels": total_channels,
+            "totalVideos": total_videos,
+            "channelsByStatus": to_document(&channels_by_status),
+            "channelsByLanguage": to_document(&channels_by_language),
+            "storage": storage,
+            "totalStorageSize": total_storage_size,
+        };
+
+        self.corpus_snapshot_repo.insert(snapshot).await?;
+
+        Ok(())
+    }
+}
+
+fn format_counts(counts: &[(String, i64)]) -> String {
+    counts
+        .iter()
+        .map(|(key, count)| format!("{} {}", key, count))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn to_document(counts: &[(String, i64)]) -> Document {
+    let mut doc = Document::new();
+
+    for (key, count) in counts {
+        doc.insert(key, count);
+    }
+
+    doc
+}
@@ -0,0 +1,185 @@
+use anyhow::Error;
+use chrono::Utc;
+use log::{error, info};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::{
+    models::youtube_playlists::Playlist,
+    repos::{
+        channel_store::ChannelStore,
+        course_store::{CourseStore, PlaylistCourse},
+        lock_store::LockStore,
+    },
+    services::youtube_service::YoutubeService,
+    utils::{
+        course_utils::{detect_course, PlaylistLesson, MIN_COURSE_LESSONS},
+        date_utils::parse_timestamp,
+        health::Health,
+        maintenance::Maintenance,
+    },
+};
+
+const DETECT_AFTER_DAYS: i64 = 30;
+// Each channel costs one unit for its playlists and one per page of each playlist
+const CHANNELS_PER_RUN: usize = 50;
+const MAX_PLAYLISTS_PER_CHANNEL: usize = 20;
+// Longer playlists are collections, e.g. all uploads of a year, rather than courses
+const MAX_COURSE_LESSONS: i64 = 100;
+
+const LOCK_NAME: &str = "courseDetectionJob";
+
+/// Looks for playlists that are laid out as courses in the channels that uploaded last month and
+/// stores them with their lessons in course order, so the site can offer them as learning paths.
+/// Each channel is checked again after 30 days.
+pub struct CourseDetectionJob {
+    channel_repo: Box<dyn ChannelStore>,
+    course_repo: Box<dyn CourseStore>,
+    youtube_service: YoutubeService,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    health: Arc<Health>,
+}
+
+impl CourseDetectionJob {
+    pub fn new(
+        channel_repo: Box<dyn ChannelStore>,
+        course_repo: Box<dyn CourseStore>,
+        youtube_service: YoutubeService,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        health: Arc<Health>,
+    ) -> CourseDetectionJob {
+        CourseDetectionJob {
+            channel_repo,
+            course_repo,
+            youtube_service,
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            health,
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Error> {
+        loop {
+            self.maintenance
+                .background_checkpoint("course detection job")
+                .await;
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
+            info!("Start course detection job");
+
+            let detected_since = Utc::now() - chrono::Duration::days(DETECT_AFTER_DAYS);
+            let detected_ids = self
+                .course_repo
+                .get_ids_detected_since(detected_since)
+                .await?;
+            let channel_ids = self
+                .channel_repo
+                .get_ids_upload_last_month(0)
+                .await?
+                .into_iter()
+                .filter(|channel_id| !detected_ids.contains(channel_id))
+                .take(CHANNELS_PER_RUN)
+                .collect::<Vec<String>>();
+
+            let mut course_count = 0;
+
+            for channel_id in &channel_ids {
+                match self.detect_courses(channel_id).await {
+                    Ok(count) => course_count += count,
+                    Err(e) => error!("Failed to detect courses of channel {}: {}", channel_id, e),
+                }
+            }
+
+            info!(
+                "Detected {} courses in {} channels",
+                course_count,
+                channel_ids.len()
+            );
+
+            self.health.record_success(LOCK_NAME).await;
+
+            info!("Wait for {} seconds until next run", self.interval_seconds);
+
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+
+    /// Returns the number of courses found in the playlists of the channel.
+    async fn detect_courses(&self, channel_id: &str) -> Result<usize, Error> {
+        let playlists = self
+            .youtube_service
+            .get_channel_playlists_page(channel_id, None)
+            .await?
+            .items
+            .into_iter()
+            .filter(|playlist| {
+                let item_count = playlist.content_details.item_count;
+                item_count >= MIN_COURSE_LESSONS as i64 && item_count <= MAX_COURSE_LESSONS
+            })
+            .take(MAX_PLAYLISTS_PER_CHANNEL);
+
+        let mut courses = vec![];
+
+        for playlist in playlists {
+            let lessons = self.get_lessons(&playlist).await?;
+
+            if let Some(course) = detect_course(&playlist.snippet.title, &lessons) {
+                courses.push(PlaylistCourse {
+                    playlist_id: playlist.id,
+                    title: playlist.snippet.title,
+                    course,
+                });
+            }
+        }
+
+        self.course_repo.replace(channel_id, &courses).await?;
+
+        Ok(courses.len())
+    }
+
+    async fn get_lessons(&self, playlist: &Playlist) -> Result<Vec<PlaylistLesson>, Error> {
+        let now = Utc::now().timestamp();
+        let mut lessons = vec![];
+        let mut page_token: Option<String> = None;
+
+        loop {
+            let page = self
+                .youtube_service
+                .get_playlist_items_page(&playlist.id, page_token.as_deref())
+                .await?;
+
+            lessons.extend(page.items.into_iter().map(|item| {
+                PlaylistLesson {
+                    position: item.snippet.position.max(0) as usize,
+                    published_at: item
+                        .content_details
+                        .video_published_at
+                        .as_deref()
+                        .and_then(|published_at| parse_timestamp(published_at, now))
+                        .map(|published_at| published_at.timestamp()),
+                    video_id: item.content_details.video_id,
+                    title: item.snippet.title,
+                }
+            }));
+
+            page_token = page.next_page_token;
+
+            if page_token.is_none() || lessons.len() as i64 >= MAX_COURSE_LESSONS {
+                return Ok(lessons);
+            }
+        }
+    }
+}
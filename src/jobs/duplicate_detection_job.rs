@@ -0,0 +1,141 @@
+use anyhow::Error;
+use log::info;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::{
+    repos::{channel_store::ChannelStore, lock_store::LockStore},
+    services::channel_merge_service::ChannelMergeService,
+    utils::{
+        duplicate_utils::{find_duplicates, DUPLICATE_REASON_REDIRECT},
+        health::Health,
+        maintenance::Maintenance,
+    },
+};
+
+const PAGE_SIZE: i64 = 1000;
+
+const LOCK_NAME: &str = "duplicateDetectionJob";
+
+/// Flags channel entries that are likely duplicates of another entry with `duplicateOf`.
+/// Redirected channels are certain duplicates and get merged right away, the others are merged
+/// after review with `crawler channel merge`.
+pub struct DuplicateDetectionJob {
+    channel_repo: Box<dyn ChannelStore>,
+    merge_service: ChannelMergeService,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    health: Arc<Health>,
+}
+
+impl DuplicateDetectionJob {
+    pub fn new(
+        channel_repo: Box<dyn ChannelStore>,
+        merge_service: ChannelMergeService,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        health: Arc<Health>,
+    ) -> DuplicateDetectionJob {
+        DuplicateDetectionJob {
+            channel_repo,
+            merge_service,
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            health,
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Error> {
+        loop {
+            self.maintenance
+                .background_checkpoint("duplicate detection job")
+                .await;
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
+            info!("Start duplicate detection job");
+
+            let mut channels = vec![];
+            let mut after_id: Option<String> = None;
+
+            loop {
+                let page = self
+                    .channel_repo
+                    .get_page(after_id.as_deref(), None, PAGE_SIZE)
+                    .await?;
+                let is_last_page = (page.len() as i64) < PAGE_SIZE;
+
+                after_id = match page.last() {
+                    Some(channel) => Some(channel.get_str("_id")?.to_string()),
+                    None => None,
+                };
+                channels.extend(page);
+
+                if is_last_page || after_id.is_none() {
+                    break;
+                }
+            }
+
+            // Keeps the first detection time of channels that were flagged in an earlier run
+            let flagged = channels
+                .iter()
+                .filter_map(|channel| {
+                    Some((
+                        channel.get_str("_id").ok()?,
+                        channel.get_str("duplicateOf").ok()?,
+                    ))
+                })
+                .collect::<HashMap<&str, &str>>();
+
+            let mut flagged_count = 0;
+            let mut merged_count = 0;
+
+            for duplicate in find_duplicates(&channels) {
+                if duplicate.reason == DUPLICATE_REASON_REDIRECT {
+                    self.merge_service
+                        .merge(&duplicate.channel_id, &duplicate.canonical_id)
+                        .await?;
+                    merged_count += 1;
+                    continue;
+                }
+
+                if flagged.get(duplicate.channel_id.as_str())
+                    == Some(&duplicate.canonical_id.as_str())
+                {
+                    continue;
+                }
+
+                self.channel_repo
+                    .set_duplicate_of(
+                        &duplicate.channel_id,
+                        &duplicate.canonical_id,
+                        duplicate.reason,
+                    )
+                    .await?;
+                flagged_count += 1;
+            }
+
+            info!(
+                "Flagged {} duplicate channels, merged {} redirected channels",
+                flagged_count, merged_count
+            );
+
+            self.health.record_success(LOCK_NAME).await;
+
+            info!("Wait for {} seconds until next run", self.interval_seconds);
+
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use anyhow::Error;
+use log::{info, warn};
+use reqwest::Client;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::Sender;
+use tokio::time::sleep;
+
+use crate::{
+    commands::crawl_channel_command::CrawlChannelCommand,
+    errors::crawler_error::CrawlerError,
+    repos::{
+        blocklist_store::BlocklistStore, channel_store::ChannelStore,
+        end_screen_scan_store::EndScreenScanStore, lock_store::LockStore,
+        non_guitar_channel_store::NonGuitarChannelStore, opt_out_store::OptOutStore,
+        video_store::VideoStore,
+    },
+    services::youtube_service::YoutubeService,
+    utils::{
+        channel_metadata_utils::get_handle, consts::CHANNEL_SOURCE_END_SCREEN,
+        end_screen_utils::get_promoted_channel_ids, health::Health, maintenance::Maintenance,
+        throttle::Throttle,
+    },
+};
+
+// End screens are set up at upload, so the latest videos tell the current promotions
+const VIDEOS_PER_CHANNEL: i64 = 3;
+const MAX_PAGES_PER_RUN: usize = 500;
+const MAX_PROMOTIONS_PER_RUN: usize = 100;
+
+const WATCH_PAGE_TIMEOUT_SECONDS: u64 = 10;
+
+const LOCK_NAME: &str = "endScreenDiscoveryJob";
+
+/// Reads the watch pages of the latest videos of channels that uploaded last month and queues
+/// the unknown channels their end screens and cards promote for the channel scraper. Each video
+/// is read once, the pages cost no Data API quota. The `channels.list` details of a promoted
+/// channel tell its handle for the opt-out check and go along with the command, so the scraper
+/// does not load them again.
+pub struct EndScreenDiscoveryJob {
+    channel_repo: Box<dyn ChannelStore>,
+    video_repo: Box<dyn VideoStore>,
+    scan_repo: Box<dyn EndScreenScanStore>,
+    non_guitar_channel_repo: Box<dyn NonGuitarChannelStore>,
+    blocklist_repo: Box<dyn BlocklistStore>,
+    opt_out_repo: Box<dyn OptOutStore>,
+    youtube_service: YoutubeService,
+    sender: Sender<CrawlChannelCommand>,
+    page_throttle: Arc<Throttle>,
+    watch_page_base_url: String,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    health: Arc<Health>,
+    http_client: Client,
+}
+
+impl EndScreenDiscoveryJob {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        channel_repo: Box<dyn ChannelStore>,
+        video_repo: Box<dyn VideoStore>,
+        scan_repo: Box<dyn EndScreenScanStore>,
+        non_guitar_channel_repo: Box<dyn NonGuitarChannelStore>,
+        blocklist_repo: Box<dyn BlocklistStore>,
+        opt_out_repo: Box<dyn OptOutStore>,
+        youtube_service: YoutubeService,
+        sender: Sender<CrawlChannelCommand>,
+        page_throttle: Arc<Throttle>,
+        watch_page_base_url: String,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        health: Arc<Health>,
+    ) -> EndScreenDiscoveryJob {
+        EndScreenDiscoveryJob {
+            channel_repo,
+            video_repo,
+            scan_repo,
+            non_guitar_channel_repo,
+            blocklist_repo,
+            opt_out_repo,
+            youtube_service,
+            sender,
+            page_throttle,
+            watch_page_base_url,
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            health,
+            http_client: Client::builder()
+                .timeout(Duration::from_secs(WATCH_PAGE_TIMEOUT_SECONDS))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Error> {
+        loop {
+            self.maintenance
+                .background_checkpoint("end screen discovery job")
+                .await;
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
+            info!("Start end screen discovery job");
+
+            let (page_count, promoted_count) = self.scan_latest_videos().await?;
+
+            info!(
+                "Read {} watch pages and queued {} promoted channels",
+                page_count, promoted_count
+            );
+
+            self.health.record_success(LOCK_NAME).await;
+
+            info!("Wait for {} seconds until next run", self.interval_seconds);
+
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+
+    async fn scan_latest_videos(&self) -> Result<(usize, usize), Error> {
+        let mut page_count = 0;
+        let mut queued = HashSet::new();
+
+        for channel_id in self.channel_repo.get_ids_upload_last_month(0).await? {
+            let videos = self
+                .video_repo
+                .get_latest_texts(&channel_id, VIDEOS_PER_CHANNEL)
+                .await?;
+
+            for video in videos {
+                if page_count >= MAX_PAGES_PER_RUN || queued.len() >= MAX_PROMOTIONS_PER_RUN {
+                    return Ok((page_count, queued.len()));
+                }
+
+                let video_id = match video.get_str("_id") {
+                    Ok(video_id) => video_id,
+                    Err(_) => continue,
+                };
+
+                if self.scan_repo.is_scanned(video_id).await? {
+                    continue;
+                }
+
+                page_count += 1;
+
+                // The page layout changes without notice, a failed page is retried next run
+                let promoted_channel_ids =
+                    match self.load_promoted_channels(video_id, &channel_id).await {
+                        Ok(promoted_channel_ids) => promoted_channel_ids,
+                        Err(e) => {
+                            warn!("Failed to load watch page of {}: {}", video_id, e);
+                            continue;
+                        }
+                    };
+
+                self.scan_repo
+                    .insert(video_id, &channel_id, &promoted_channel_ids)
+                    .await?;
+
+                for promoted_channel_id in promoted_channel_ids {
+                    if queued.contains(&promoted_channel_id)
+                        || !self.is_unknown(&promoted_channel_id).await?
+                    {
+                        continue;
+                    }
+
+                    let mut cmd = CrawlChannelCommand::new(
+                        promoted_channel_id.clone(),
+                        false,
+                        Some(CHANNEL_SOURCE_END_SCREEN.to_string()),
+                    );
+
+                    // Without details the channel scraper checks the handle itself
+                    match self
+                        .youtube_service
+                        .get_channel_details(&promoted_channel_id)
+                        .await
+                    {
+                        Ok(details) => {
+                            if let Some(handle) = get_handle(&details) {
+                                if self.opt_out_repo.is_opted_out(handle).await? {
+                                    info!("Skip promoted channel {}, it opted out", handle);
+                                    continue;
+                                }
+                            }
+                            cmd.channel_details = Some(Box::new(details));
+                        }
+                        Err(e) => warn!(
+                            "Failed to load details of promoted channel {}: {}",
+                            promoted_channel_id, e
+                        ),
+                    }
+
+                    info!(
+                        "Send channel {} promoted by video {} of {} for crawling",
+                        promoted_channel_id, video_id, channel_id
+                    );
+
+                    self.sender.send(cmd).await?;
+
+                    queued.insert(promoted_channel_id);
+                }
+            }
+        }
+
+        Ok((page_count, queued.len()))
+    }
+
+    /// Rejected channels are listed as non guitar channels, so each is offered once.
+    async fn is_unknown(&self, channel_id: &str) -> Result<bool, Error> {
+        Ok(!(self.channel_repo.exists(channel_id).await?
+            || self.non_guitar_channel_repo.exists(channel_id).await?
+            || self.blocklist_repo.is_blocked(channel_id).await?
+            || self.opt_out_repo.is_opted_out(channel_id).await?))
+    }
+
+    async fn load_promoted_channels(
+        &self,
+        video_id: &str,
+        channel_id: &str,
+    ) -> Result<Vec<String>, CrawlerError> {
+        self.page_throttle.wait().await;
+
+        let url = format!("{}{}", self.watch_page_base_url, video_id);
+        let response = self
+            .http_client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| CrawlerError::FeedError(e.to_string()))?;
+
+        if response.status() != 200 {
+            return Err(CrawlerError::FeedError(format!(
+                "Youtube Watch Page Response Error: {}",
+                response.status()
+            )));
+        }
+
+        let html = response
+            .text()
+            .await
+            .map_err(|e| CrawlerError::FeedError(e.to_string()))?;
+
+        Ok(get_promoted_channel_ids(&html, channel_id))
+    }
+}
@@ -0,0 +1,125 @@
+use anyhow::Error;
+use log::info;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::{
+    classifiers::genre_classifier::GenreClassifier,
+    repos::{channel_store::ChannelStore, lock_store::LockStore, video_store::VideoStore},
+    utils::{
+        consts::{CHANNEL_STATUS_DEACTIVATED, CHANNEL_STATUS_PROBATION},
+        health::Health,
+        maintenance::Maintenance,
+    },
+};
+
+const PAGE_SIZE: i64 = 500;
+// Latest videos the genres are scored on, so channels changing styles get retagged
+const GENRE_VIDEO_COUNT: i64 = 50;
+
+const LOCK_NAME: &str = "genreTagJob";
+
+/// Assigns channels to genres and styles like blues, metal or gear reviews from the texts of
+/// their latest videos, and stores them as `genreTags` with the scores of the genre classifier.
+pub struct GenreTagJob {
+    channel_repo: Box<dyn ChannelStore>,
+    video_repo: Box<dyn VideoStore>,
+    genre_classifier: Arc<dyn GenreClassifier>,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    health: Arc<Health>,
+}
+
+impl GenreTagJob {
+    pub fn new(
+        channel_repo: Box<dyn ChannelStore>,
+        video_repo: Box<dyn VideoStore>,
+        genre_classifier: Arc<dyn GenreClassifier>,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        health: Arc<Health>,
+    ) -> GenreTagJob {
+        GenreTagJob {
+            channel_repo,
+            video_repo,
+            genre_classifier,
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            health,
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Error> {
+        loop {
+            self.maintenance
+                .background_checkpoint("genre tag job")
+                .await;
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
+            info!("Start genre tag job");
+
+            let tagged_count = self.update_genre_tags().await?;
+
+            info!("{} channels have genre tags", tagged_count);
+
+            self.health.record_success(LOCK_NAME).await;
+
+            info!("Wait for {} seconds until next run", self.interval_seconds);
+
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+
+    async fn update_genre_tags(&self) -> Result<usize, Error> {
+        let mut after_id: Option<String> = None;
+        let mut tagged_count = 0;
+
+        loop {
+            let channels = self
+                .channel_repo
+                .get_page(after_id.as_deref(), None, PAGE_SIZE)
+                .await?;
+
+            for channel in &channels {
+                if matches!(
+                    channel.get_str("status").ok(),
+                    Some(CHANNEL_STATUS_DEACTIVATED) | Some(CHANNEL_STATUS_PROBATION)
+                ) {
+                    continue;
+                }
+
+                let id = channel.get_str("_id")?;
+                let videos = self
+                    .video_repo
+                    .get_latest_texts(id, GENRE_VIDEO_COUNT)
+                    .await?;
+                let genres = self.genre_classifier.get_genre_tags(&videos);
+
+                // Tags of channels that left a genre are cleared as well
+                self.channel_repo.set_genre_tags(id, &genres).await?;
+
+                if !genres.is_empty() {
+                    tagged_count += 1;
+                }
+            }
+
+            match channels.last() {
+                Some(channel) if channels.len() as i64 == PAGE_SIZE => {
+                    after_id = Some(channel.get_str("_id")?.to_string());
+                }
+                _ => return Ok(tagged_count),
+            }
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use anyhow::Error;
+use chrono::Utc;
+use log::info;
+use mongodb::bson::Document;
+use reqwest::Client;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::{
+    repos::{channel_store::ChannelStore, lock_store::LockStore},
+    utils::{
+        consts::CHANNEL_STATUS_DEACTIVATED,
+        health::Health,
+        link_utils::{
+            classify_external_url, get_link_status, LinkCheck, LINK_STATUS_BLOCKED,
+            LINK_STATUS_DEAD, LINK_TYPE_WEBSITE,
+        },
+        maintenance::Maintenance,
+        url_guard::{is_blocked_error, UrlGuard},
+    },
+};
+
+const PAGE_SIZE: i64 = 500;
+// The links of a channel are checked again after this many days
+const RECHECK_DAYS: i64 = 7;
+const REQUEST_TIMEOUT_SECONDS: u64 = 10;
+const MAX_REDIRECTS: usize = 10;
+
+const LOCK_NAME: &str = "linkVerificationJob";
+
+/// Resolves the external links of the channel about pages with `HEAD` requests, following
+/// redirects, and stores their liveness and type next to the links, so the site can hide dead
+/// links. Links to private addresses are not requested.
+pub struct LinkVerificationJob {
+    channel_repo: Box<dyn ChannelStore>,
+    http_client: Client,
+    url_guard: UrlGuard,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    health: Arc<Health>,
+}
+
+impl LinkVerificationJob {
+    pub fn new(
+        channel_repo: Box<dyn ChannelStore>,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        health: Arc<Health>,
+    ) -> LinkVerificationJob {
+        let url_guard = UrlGuard::default();
+
+        LinkVerificationJob {
+            channel_repo,
+            http_client: url_guard
+                .build_client(Duration::from_secs(REQUEST_TIMEOUT_SECONDS), MAX_REDIRECTS),
+            url_guard,
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            health,
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Error> {
+        loop {
+            self.maintenance
+                .background_checkpoint("link verification job")
+                .await;
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
+            info!("Start link verification job");
+
+            let (channel_count, dead_count) = self.verify_links().await?;
+
+            info!(
+                "Checked the links of {} channels, {} links are dead",
+                channel_count, dead_count
+            );
+
+            self.health.record_success(LOCK_NAME).await;
+
+            info!("Wait for {} seconds until next run", self.interval_seconds);
+
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+
+    /// Returns the channels checked and the dead links found.
+    async fn verify_links(&self) -> Result<(usize, usize), Error> {
+        let checked_before = (Utc::now() - chrono::Duration::days(RECHECK_DAYS)).timestamp_millis();
+
+        let mut after_id: Option<String> = None;
+        let mut channel_count = 0;
+        let mut dead_count = 0;
+
+        loop {
+            let channels = self
+                .channel_repo
+                .get_page(after_id.as_deref(), None, PAGE_SIZE)
+                .await?;
+
+            for channel in &channels {
+                let urls = get_link_urls(channel);
+
+                if channel.get_str("status").ok() == Some(CHANNEL_STATUS_DEACTIVATED)
+                    || urls.is_empty()
+                    || get_checked_at_millis(channel).unwrap_or(0) >= checked_before
+                {
+                    continue;
+                }
+
+                let id = channel.get_str("_id")?;
+                let mut checks = vec![];
+                for url in urls {
+                    checks.push(check_link(&self.http_client, &self.url_guard, url).await);
+                }
+
+                dead_count += checks
+                    .iter()
+                    .filter(|check| check.status == LINK_STATUS_DEAD)
+                    .count();
+                channel_count += 1;
+
+                self.channel_repo.set_link_checks(id, &checks).await?;
+            }
+
+            match channels.last() {
+                Some(channel) if channels.len() as i64 == PAGE_SIZE => {
+                    after_id = Some(channel.get_str("_id")?.to_string());
+                }
+                _ => return Ok((channel_count, dead_count)),
+            }
+        }
+    }
+}
+
+/// Falls back to `GET` for servers that do not allow `HEAD`.
+async fn check_link(http_client: &Client, url_guard: &UrlGuard, url: &str) -> LinkCheck {
+    let mut result = if url_guard.is_blocked(url).await {
+        None
+    } else {
+        Some(http_client.head(url).send().await)
+    };
+
+    if let Some(Ok(response)) = &result {
+        if matches!(response.status().as_u16(), 405 | 501) {
+            result = Some(http_client.get(url).send().await);
+        }
+    }
+
+    let (http_status, final_url, status) = match result {
+        Some(Ok(response)) => {
+            let http_status = response.status().as_u16();
+
+            (
+                Some(http_status),
+                Some(response.url().to_string()),
+                get_link_status(Some(http_status), false),
+            )
+        }
+        Some(Err(e)) if !is_blocked_error(&e) => {
+            info!("Failed to resolve link {}: {}", url, e);
+            (None, None, get_link_status(None, e.is_connect()))
+        }
+        _ => {
+            info!("Skipped link {} to a private address", url);
+            (None, None, LINK_STATUS_BLOCKED)
+        }
+    };
+
+    // Short links and link pages are typed by where they redirect to
+    let link_type = final_url
+        .as_deref()
+        .and_then(classify_external_url)
+        .or_else(|| classify_external_url(url))
+        .map(|link| link.link_type)
+        .unwrap_or(LINK_TYPE_WEBSITE);
+
+    LinkCheck {
+        url: url.to_string(),
+        final_url,
+        link_type,
+        status,
+        http_status,
+    }
+}
+
+fn get_link_urls(channel: &Document) -> Vec<&str> {
+    channel
+        .get_array("links")
+        .map(|links| {
+            links
+                .iter()
+                .filter_map(|link| link.as_document()?.get_str("url").ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn get_checked_at_millis(channel: &Document) -> Option<i64> {
+    channel
+        .get_datetime("linksCheckedAt")
+        .ok()
+        .map(|date| date.timestamp_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+    use std::time::Duration;
+
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    use crate::utils::{link_utils::LINK_STATUS_BLOCKED, url_guard::UrlGuard};
+
+    #[tokio::test]
+    async fn blocks_loopback_links() {
+        let url_guard = UrlGuard::default();
+        let http_client = url_guard.build_client(Duration::from_secs(5), 10);
+
+        let check = super::check_link(&http_client, &url_guard, "http://127.0.0.1/").await;
+
+        assert_eq!(check.status, LINK_STATUS_BLOCKED);
+        assert_eq!(check.http_status, None);
+        assert_eq!(check.final_url, None);
+    }
+
+    #[tokio::test]
+    async fn blocks_redirects_to_private_addresses() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/links", listener.local_addr().unwrap());
+
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut connection = BufReader::new(stream);
+            let mut line = String::new();
+            while connection.read_line(&mut line).await.unwrap() > 2 {
+                line.clear();
+            }
+            connection
+                .get_mut()
+                .write_all(
+                    b"HTTP/1.1 301 Moved Permanently\r\nLocation: http://10.0.0.8/admin\r\nContent-Length: 0\r\n\r\n",
+                )
+                .await
+                .unwrap();
+        });
+
+        // Lets the test reach its own server on the loopback address
+        let url_guard = UrlGuard::allowing(|ip| *ip == "127.0.0.1".parse::<IpAddr>().unwrap());
+        let http_client = url_guard.build_client(Duration::from_secs(5), 10);
+
+        let check = super::check_link(&http_client, &url_guard, &url).await;
+
+        assert_eq!(check.status, LINK_STATUS_BLOCKED);
+        assert_eq!(check.http_status, None);
+    }
+}
@@ -0,0 +1,21 @@
+pub mod alert_job;
+pub mod ban_evasion_job;
+pub mod channel_lifecycle_job;
+pub mod channel_metadata_refresh_job;
+pub mod comment_ingestion_job;
+pub mod comment_sentiment_job;
+pub mod corpus_snapshot_job;
+pub mod course_detection_job;
+pub mod duplicate_detection_job;
+pub mod end_screen_discovery_job;
+pub mod genre_tag_job;
+pub mod link_verification_job;
+pub mod probation_job;
+pub mod reclassification_job;
+pub mod reconciliation_job;
+pub mod related_channels_job;
+pub mod reporting_job;
+pub mod stats_aggregation_job;
+pub mod topic_drift_job;
+pub mod upload_pattern_job;
+pub mod video_archive_job;
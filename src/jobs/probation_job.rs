@@ -0,0 +1,213 @@
+use anyhow::Error;
+use chrono::Utc;
+use log::{error, info};
+use mongodb::bson::doc;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::{
+    repos::{
+        channel_audit_store::{ChannelAuditStore, AUDIT_ACTION_DEACTIVATED},
+        channel_probation_store::{
+            ChannelProbationStore, PROBATION_OUTCOME_CONFIRMED, PROBATION_OUTCOME_REJECTED,
+        },
+        channel_store::ChannelStore,
+        lock_store::LockStore,
+        video_store::VideoStore,
+    },
+    services::guitar_terms_service::GuitarTermsService,
+    utils::{
+        consts::{
+            CHANNEL_STATUS_ACTIVE, CHANNEL_STATUS_DEACTIVATED, CLASSIFICATION_OVERRIDE_EXCLUDE,
+            CLASSIFICATION_OVERRIDE_INCLUDE, DEACTIVATION_REASON_PROBATION_FAILED,
+        },
+        health::Health,
+        maintenance::Maintenance,
+        probation_utils::{get_rejection_reason, PROBATION_VIDEOS},
+    },
+};
+
+const CHANNELS_PER_RUN: i64 = 500;
+
+const LOCK_NAME: &str = "probationJob";
+
+/// Decides channels whose probation is over: channels with mostly guitar videos and a recent
+/// upload become active, the others are deactivated.
+pub struct ProbationJob {
+    channel_repo: Box<dyn ChannelStore>,
+    video_repo: Box<dyn VideoStore>,
+    probation_repo: Box<dyn ChannelProbationStore>,
+    channel_audit_repo: Box<dyn ChannelAuditStore>,
+    guitar_terms_service: GuitarTermsService,
+    probation_days: i64,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    health: Arc<Health>,
+}
+
+impl ProbationJob {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        channel_repo: Box<dyn ChannelStore>,
+        video_repo: Box<dyn VideoStore>,
+        probation_repo: Box<dyn ChannelProbationStore>,
+        channel_audit_repo: Box<dyn ChannelAuditStore>,
+        guitar_terms_service: GuitarTermsService,
+        probation_days: i64,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        health: Arc<Health>,
+    ) -> ProbationJob {
+        ProbationJob {
+            channel_repo,
+            video_repo,
+            probation_repo,
+            channel_audit_repo,
+            guitar_terms_service,
+            probation_days,
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            health,
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Error> {
+        loop {
+            self.maintenance
+                .background_checkpoint("probation job")
+                .await;
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
+            info!("Start probation job");
+
+            let started_before = Utc::now() - chrono::Duration::days(self.probation_days);
+            let channel_ids = self
+                .probation_repo
+                .get_ids_due(started_before, CHANNELS_PER_RUN)
+                .await?;
+
+            let mut confirmed_count = 0;
+
+            for channel_id in &channel_ids {
+                match self.decide(channel_id).await {
+                    Ok(true) => confirmed_count += 1,
+                    Ok(false) => {}
+                    Err(e) => error!(
+                        "Failed to decide probation of channel {}: {}",
+                        channel_id, e
+                    ),
+                }
+            }
+
+            info!(
+                "Decided probation of {} channels, confirmed {}",
+                channel_ids.len(),
+                confirmed_count
+            );
+
+            self.health.record_success(LOCK_NAME).await;
+
+            info!("Wait for {} seconds until next run", self.interval_seconds);
+
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+
+    /// Returns true if the channel was confirmed. A manual override decides without looking at
+    /// the videos.
+    async fn decide(&self, channel_id: &str) -> Result<bool, Error> {
+        let channel = match self.channel_repo.find_by_id(channel_id).await? {
+            Some(channel) if channel.get_str("status").ok() != Some(CHANNEL_STATUS_DEACTIVATED) => {
+                channel
+            }
+            // Deleted, opted out or deactivated by another job in the meantime
+            _ => {
+                self.probation_repo
+                    .set_outcome(channel_id, PROBATION_OUTCOME_REJECTED, None)
+                    .await?;
+                return Ok(false);
+            }
+        };
+
+        let rejection_reason = match self
+            .channel_repo
+            .get_classification_override(channel_id)
+            .await?
+            .as_deref()
+        {
+            Some(CLASSIFICATION_OVERRIDE_INCLUDE) => None,
+            Some(CLASSIFICATION_OVERRIDE_EXCLUDE) => Some(CLASSIFICATION_OVERRIDE_EXCLUDE),
+            _ => {
+                let videos = self
+                    .video_repo
+                    .get_latest_texts(channel_id, PROBATION_VIDEOS)
+                    .await?;
+                let guitar_video_share = self.guitar_terms_service.get_video_score(&videos);
+                let rejection_reason = get_rejection_reason(
+                    videos.len(),
+                    guitar_video_share,
+                    channel.get_i64("lastUploadAt").ok(),
+                    Utc::now().timestamp(),
+                );
+
+                if let Some(reason) = rejection_reason {
+                    self.channel_audit_repo
+                        .insert(
+                            channel_id,
+                            AUDIT_ACTION_DEACTIVATED,
+                            doc! {
+                                "reason": DEACTIVATION_REASON_PROBATION_FAILED,
+                                "probationReason": reason,
+                                "videoCount": videos.len() as i64,
+                                "guitarVideoShare": guitar_video_share,
+                            },
+                        )
+                        .await?;
+                }
+
+                rejection_reason
+            }
+        };
+
+        match rejection_reason {
+            None => {
+                info!("Confirm channel {} after probation", channel_id);
+
+                self.channel_repo
+                    .upsert(channel_id, doc! {"status": CHANNEL_STATUS_ACTIVE})
+                    .await;
+                self.probation_repo
+                    .set_outcome(channel_id, PROBATION_OUTCOME_CONFIRMED, None)
+                    .await?;
+
+                Ok(true)
+            }
+            Some(reason) => {
+                info!(
+                    "Deactivate channel {} ({}: {})",
+                    channel_id, DEACTIVATION_REASON_PROBATION_FAILED, reason
+                );
+
+                self.channel_repo
+                    .deactivate(channel_id, DEACTIVATION_REASON_PROBATION_FAILED)
+                    .await?;
+                self.probation_repo
+                    .set_outcome(channel_id, PROBATION_OUTCOME_REJECTED, Some(reason))
+                    .await?;
+
+                Ok(false)
+            }
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use anyhow::Error;
+use chrono::Utc;
+use log::{error, info};
+use mongodb::bson::doc;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::{
+    repos::{
+        additional_channel_store::AdditionalChannelStore,
+        channel_audit_store::{ChannelAuditStore, AUDIT_ACTION_DEACTIVATED},
+        channel_store::ChannelStore,
+        lock_store::LockStore,
+    },
+    services::{
+        guitar_terms_service::GuitarTermsService, tag_analytics_service::TagAnalyticsService,
+        youtube_service::YoutubeService,
+    },
+    utils::{
+        consts::{
+            CLASSIFICATION_OVERRIDE_EXCLUDE, CLASSIFICATION_OVERRIDE_INCLUDE,
+            DEACTIVATION_REASON_CLASSIFICATION_OVERRIDE,
+        },
+        health::Health,
+        maintenance::Maintenance,
+    },
+};
+
+const RECLASSIFY_AFTER_DAYS: i64 = 30;
+// Each channel costs one unit of api quota
+const CHANNELS_PER_RUN: i64 = 500;
+
+// Channels whose videos are mostly tagged with guitar terms stay active without a guitar term
+// in the title or description
+const MIN_TAG_SCORE: f64 = 0.5;
+
+const REASON_BLACKLISTED: &str = "blacklisted";
+const REASON_NO_GUITAR_TERMS: &str = "noGuitarTerms";
+
+const LOCK_NAME: &str = "reclassificationJob";
+
+/// Re-checks stored channels against the current title and description on YouTube and
+/// deactivates the ones that no longer qualify as guitar channels.
+pub struct ReclassificationJob {
+    channel_repo: Box<dyn ChannelStore>,
+    channel_audit_repo: Box<dyn ChannelAuditStore>,
+    additional_channel_repo: Box<dyn AdditionalChannelStore>,
+    youtube_service: YoutubeService,
+    guitar_terms_service: GuitarTermsService,
+    tag_analytics_service: TagAnalyticsService,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    health: Arc<Health>,
+}
+
+impl ReclassificationJob {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        channel_repo: Box<dyn ChannelStore>,
+        channel_audit_repo: Box<dyn ChannelAuditStore>,
+        additional_channel_repo: Box<dyn AdditionalChannelStore>,
+        youtube_service: YoutubeService,
+        guitar_terms_service: GuitarTermsService,
+        tag_analytics_service: TagAnalyticsService,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        health: Arc<Health>,
+    ) -> ReclassificationJob {
+        ReclassificationJob {
+            channel_repo,
+            channel_audit_repo,
+            additional_channel_repo,
+            youtube_service,
+            guitar_terms_service,
+            tag_analytics_service,
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            health,
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Error> {
+        loop {
+            self.maintenance
+                .background_checkpoint("reclassification job")
+                .await;
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
+            info!("Start reclassification job");
+
+            let reclassified_before = Utc::now() - chrono::Duration::days(RECLASSIFY_AFTER_DAYS);
+            let channel_ids = self
+                .channel_repo
+                .get_ids_reclassified_before(reclassified_before, CHANNELS_PER_RUN)
+                .await?;
+
+            let mut deactivated_count = 0;
+
+            for channel_id in &channel_ids {
+                match self.reclassify(channel_id).await {
+                    Ok(true) => deactivated_count += 1,
+                    Ok(false) => {}
+                    Err(e) => {
+                        error!("Failed to reclassify channel {}: {}", channel_id, e);
+
+                        // Failing channels wait for the next round instead of blocking the run
+                        if let Err(e) = self.channel_repo.set_reclassified(channel_id).await {
+                            error!("Failed to set channel {} reclassified: {}", channel_id, e);
+                        }
+                    }
+                }
+            }
+
+            info!(
+                "Reclassified {} channels, deactivated {}",
+                channel_ids.len(),
+                deactivated_count
+            );
+
+            self.health.record_success(LOCK_NAME).await;
+
+            info!("Wait for {} seconds until next run", self.interval_seconds);
+
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+
+    /// Returns true if the channel was deactivated. Channels with a manual override are not
+    /// looked up on YouTube.
+    async fn reclassify(&self, channel_id: &str) -> Result<bool, Error> {
+        match self
+            .channel_repo
+            .get_classification_override(channel_id)
+            .await?
+            .as_deref()
+        {
+            Some(CLASSIFICATION_OVERRIDE_INCLUDE) => {
+                self.channel_repo.set_reclassified(channel_id).await?;
+                return Ok(false);
+            }
+            Some(CLASSIFICATION_OVERRIDE_EXCLUDE) => {
+                info!(
+                    "Deactivate channel {} ({})",
+                    channel_id, DEACTIVATION_REASON_CLASSIFICATION_OVERRIDE
+                );
+
+                self.channel_repo
+                    .deactivate(channel_id, DEACTIVATION_REASON_CLASSIFICATION_OVERRIDE)
+                    .await?;
+                return Ok(true);
+            }
+            _ => {}
+        }
+
+        let channel_details = self.youtube_service.get_channel_details(channel_id).await?;
+        let title = channel_details.snippet.title;
+        let description = channel_details.snippet.description.unwrap_or_default();
+        let ignore_guitar_terms = self
+            .additional_channel_repo
+            .is_ignoring_guitar_terms(channel_id)
+            .await?;
+
+        let guitar_term_result = self
+            .guitar_terms_service
+            .has_guitar_term(channel_id, &title, &description, ignore_guitar_terms)
+            .await;
+
+        let tag_score = match self.tag_analytics_service.get_profile(channel_id).await? {
+            Some(tag_profile) => self.guitar_terms_service.get_tag_score(&tag_profile),
+            None => 0.0,
+        };
+
+        let has_guitar_tags = !guitar_term_result.is_blacklisted && tag_score >= MIN_TAG_SCORE;
+
+        if guitar_term_result.has_guitar_term || has_guitar_tags {
+            self.channel_repo.set_reclassified(channel_id).await?;
+            return Ok(false);
+        }
+
+        let reason = if guitar_term_result.is_blacklisted {
+            REASON_BLACKLISTED
+        } else {
+            REASON_NO_GUITAR_TERMS
+        };
+
+        info!("Deactivate channel {} ({})", channel_id, reason);
+
+        self.channel_repo.deactivate(channel_id, reason).await?;
+        self.channel_audit_repo
+            .insert(
+                channel_id,
+                AUDIT_ACTION_DEACTIVATED,
+                doc! {
+                    "reason": reason,
+                    "title": &title,
+                    "description": &description,
+                    "partialTerms": &guitar_term_result.partial_terms,
+                    "partialScore": guitar_term_result.partial_score,
+                    "tagScore": tag_score,
+                },
+            )
+            .await?;
+
+        Ok(true)
+    }
+}
@@ -0,0 +1,175 @@
+use anyhow::Error;
+use chrono::Utc;
+use log::{error, info};
+use mongodb::bson::{doc, Document};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::{
+    repos::{
+        lock_store::LockStore, reconciliation_report_store::ReconciliationReportStore,
+        video_store::VideoStore,
+    },
+    services::youtube_service::YoutubeService,
+    utils::{availability_utils::get_availability, health::Health, maintenance::Maintenance},
+};
+
+const RECHECK_AFTER_DAYS: i64 = 7;
+// One api request checks up to 50 videos for one unit of quota
+const BATCH_SIZE: usize = 50;
+const VIDEOS_PER_RUN: i64 = 5000;
+
+const LOCK_NAME: &str = "reconciliationJob";
+
+/// Checks stored videos against `videos.list` and marks the ones that were deleted, made
+/// private or blocked in some regions on YouTube. Each run stores a report of its changes.
+pub struct ReconciliationJob {
+    video_repo: Box<dyn VideoStore>,
+    report_repo: Box<dyn ReconciliationReportStore>,
+    youtube_service: YoutubeService,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    health: Arc<Health>,
+}
+
+impl ReconciliationJob {
+    pub fn new(
+        video_repo: Box<dyn VideoStore>,
+        report_repo: Box<dyn ReconciliationReportStore>,
+        youtube_service: YoutubeService,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        health: Arc<Health>,
+    ) -> ReconciliationJob {
+        ReconciliationJob {
+            video_repo,
+            report_repo,
+            youtube_service,
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            health,
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Error> {
+        loop {
+            self.maintenance
+                .background_checkpoint("reconciliation job")
+                .await;
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
+            info!("Start reconciliation job");
+
+            let started_at = mongodb::bson::DateTime::now();
+            let checked_before =
+                (Utc::now() - chrono::Duration::days(RECHECK_AFTER_DAYS)).timestamp();
+            let videos = self
+                .video_repo
+                .get_ids_availability_checked_before(checked_before, VIDEOS_PER_RUN)
+                .await?;
+
+            let mut checked_count = 0;
+            let mut changes = vec![];
+
+            for batch in videos.chunks(BATCH_SIZE) {
+                match self.reconcile(batch).await {
+                    Ok(batch_changes) => {
+                        checked_count += batch.len();
+                        changes.extend(batch_changes);
+                    }
+                    Err(e) => {
+                        // Most likely out of quota, the remaining videos are checked next run
+                        error!("Failed to reconcile videos: {}", e);
+                        break;
+                    }
+                }
+            }
+
+            let mut change_counts = BTreeMap::new();
+            for change in &changes {
+                let availability = change.get_str("to").unwrap_or_default().to_string();
+                *change_counts.entry(availability).or_insert(0) += 1;
+            }
+
+            info!(
+                "Checked {} videos, {} changed availability",
+                checked_count,
+                changes.len()
+            );
+
+            let change_counts = change_counts
+                .into_iter()
+                .map(|(availability, count)| (availability, mongodb::bson::Bson::Int32(count)))
+                .collect::<Document>();
+
+            self.report_repo
+                .insert(doc! {
+                    "startedAt": started_at,
+                    "finishedAt": mongodb::bson::DateTime::now(),
+                    "checkedCount": checked_count as i64,
+                    "changeCounts": change_counts,
+                    "changes": changes,
+                })
+                .await?;
+
+            self.health.record_success(LOCK_NAME).await;
+
+            info!("Wait for {} seconds until next run", self.interval_seconds);
+
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+
+    /// Stores the current availability of each video and returns the changes.
+    async fn reconcile(&self, videos: &[(String, String)]) -> Result<Vec<Document>, Error> {
+        let video_ids = videos
+            .iter()
+            .map(|(video_id, _)| video_id.clone())
+            .collect::<Vec<String>>();
+
+        let items = self
+            .youtube_service
+            .get_video_details(&video_ids)
+            .await?
+            .into_iter()
+            .map(|item| (item.id.clone(), item))
+            .collect::<HashMap<_, _>>();
+
+        let mut changes = vec![];
+
+        for (video_id, previous_availability) in videos {
+            let availability = get_availability(items.get(video_id));
+
+            if availability != previous_availability {
+                info!(
+                    "Video {} changed from {} to {}",
+                    video_id, previous_availability, availability
+                );
+
+                changes.push(doc! {
+                    "videoId": video_id,
+                    "from": previous_availability,
+                    "to": availability,
+                });
+            }
+
+            self.video_repo
+                .set_availability(video_id, availability)
+                .await?;
+        }
+
+        Ok(changes)
+    }
+}
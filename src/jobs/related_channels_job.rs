@@ -0,0 +1,172 @@
+use anyhow::Error;
+use chrono::Utc;
+use log::info;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::Sender;
+use tokio::time::sleep;
+
+use crate::{
+    commands::crawl_channel_command::CrawlChannelCommand,
+    repos::{
+        blocklist_store::BlocklistStore, channel_store::ChannelStore, comment_store::CommentStore,
+        lock_store::LockStore, non_guitar_channel_store::NonGuitarChannelStore,
+        opt_out_store::OptOutStore, related_channel_store::RelatedChannelStore,
+    },
+    utils::{
+        consts::CHANNEL_SOURCE_COMMENTER,
+        health::Health,
+        maintenance::Maintenance,
+        similarity_utils::{get_connected_commenters, get_related_channels},
+    },
+};
+
+const COMMENT_WINDOW_DAYS: i64 = 90;
+const RELATED_CHANNELS_PER_CHANNEL: usize = 20;
+const MIN_SHARED_COMMENTERS: usize = 3;
+// Commenters active on this many known channels are likely creators of the same niche
+const MIN_COMMENTED_CHANNELS: usize = 3;
+const MAX_PROMOTIONS_PER_RUN: usize = 100;
+
+const LOCK_NAME: &str = "relatedChannelsJob";
+
+/// Relates channels by the overlap of their commenters and stores the closest ones per channel.
+/// Unknown commenter channels active on many known channels are queued for the channel scraper.
+pub struct RelatedChannelsJob {
+    channel_repo: Box<dyn ChannelStore>,
+    comment_repo: Box<dyn CommentStore>,
+    related_channel_repo: Box<dyn RelatedChannelStore>,
+    non_guitar_channel_repo: Box<dyn NonGuitarChannelStore>,
+    blocklist_repo: Box<dyn BlocklistStore>,
+    opt_out_repo: Box<dyn OptOutStore>,
+    sender: Sender<CrawlChannelCommand>,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    health: Arc<Health>,
+}
+
+impl RelatedChannelsJob {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        channel_repo: Box<dyn ChannelStore>,
+        comment_repo: Box<dyn CommentStore>,
+        related_channel_repo: Box<dyn RelatedChannelStore>,
+        non_guitar_channel_repo: Box<dyn NonGuitarChannelStore>,
+        blocklist_repo: Box<dyn BlocklistStore>,
+        opt_out_repo: Box<dyn OptOutStore>,
+        sender: Sender<CrawlChannelCommand>,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        health: Arc<Health>,
+    ) -> RelatedChannelsJob {
+        RelatedChannelsJob {
+            channel_repo,
+            comment_repo,
+            related_channel_repo,
+            non_guitar_channel_repo,
+            blocklist_repo,
+            opt_out_repo,
+            sender,
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            health,
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Error> {
+        loop {
+            self.maintenance
+                .background_checkpoint("related channels job")
+                .await;
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
+            info!("Start related channels job");
+
+            let published_after =
+                (Utc::now() - chrono::Duration::days(COMMENT_WINDOW_DAYS)).timestamp();
+            let commenters = self
+                .comment_repo
+                .get_commenters_by_channel(published_after)
+                .await?;
+
+            let related_channels = get_related_channels(
+                &commenters,
+                RELATED_CHANNELS_PER_CHANNEL,
+                MIN_SHARED_COMMENTERS,
+            );
+
+            for (channel_id, related) in &related_channels {
+                self.related_channel_repo
+                    .replace(channel_id, related)
+                    .await?;
+            }
+
+            info!(
+                "Stored related channels of {} channels",
+                related_channels.len()
+            );
+
+            let promoted_count = self.promote_commenters(&commenters).await?;
+
+            info!("Queued {} commenter channels", promoted_count);
+
+            self.health.record_success(LOCK_NAME).await;
+
+            info!("Wait for {} seconds until next run", self.interval_seconds);
+
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+
+    async fn promote_commenters(
+        &self,
+        commenters: &HashMap<String, HashSet<String>>,
+    ) -> Result<usize, Error> {
+        let mut promoted_count = 0;
+
+        for (commenter, channel_count) in
+            get_connected_commenters(commenters, MIN_COMMENTED_CHANNELS)
+        {
+            if promoted_count >= MAX_PROMOTIONS_PER_RUN {
+                break;
+            }
+
+            // Rejected channels are listed as non guitar channels, so each is offered once
+            if self.channel_repo.exists(&commenter).await?
+                || self.non_guitar_channel_repo.exists(&commenter).await?
+                || self.blocklist_repo.is_blocked(&commenter).await?
+                || self.opt_out_repo.is_opted_out(&commenter).await?
+            {
+                continue;
+            }
+
+            info!(
+                "Send commenter channel {} for crawling, commented on {} channels",
+                commenter, channel_count
+            );
+
+            self.sender
+                .send(CrawlChannelCommand::new(
+                    commenter,
+                    false,
+                    Some(CHANNEL_SOURCE_COMMENTER.to_string()),
+                ))
+                .await?;
+
+            promoted_count += 1;
+        }
+
+        Ok(promoted_count)
+    }
+}
@@ -0,0 +1,115 @@
+use anyhow::Error;
+use chrono::Utc;
+use log::info;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::{
+    models::daily_digest::DailyDigest,
+    notifications::notification_service::NotificationService,
+    repos::{
+        crawl_audit_store::CrawlAuditStore, digest_report_store::DigestReportStore,
+        discovery_provenance_store::DiscoveryProvenanceStore, lock_store::LockStore,
+    },
+    utils::{health::Health, maintenance::Maintenance},
+};
+
+const SLOWEST_CRAWLS_LIMIT: i64 = 10;
+
+const LOCK_NAME: &str = "reportingJob";
+
+/// Summarizes the crawling since the previous run into a digest, stores it in `digestreports`
+/// and sends it to the notification webhooks.
+pub struct ReportingJob {
+    crawl_audit_repo: Box<dyn CrawlAuditStore>,
+    provenance_repo: Box<dyn DiscoveryProvenanceStore>,
+    report_repo: Box<dyn DigestReportStore>,
+    notification_service: Arc<NotificationService>,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    health: Arc<Health>,
+}
+
+impl ReportingJob {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        crawl_audit_repo: Box<dyn CrawlAuditStore>,
+        provenance_repo: Box<dyn DiscoveryProvenanceStore>,
+        report_repo: Box<dyn DigestReportStore>,
+        notification_service: Arc<NotificationService>,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        health: Arc<Health>,
+    ) -> ReportingJob {
+        ReportingJob {
+            crawl_audit_repo,
+            provenance_repo,
+            report_repo,
+            notification_service,
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            health,
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Error> {
+        loop {
+            self.maintenance
+                .background_checkpoint("reporting job")
+                .await;
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
+            info!("Start reporting job");
+
+            let digest = self.build_digest().await?;
+            self.report_repo.insert(&digest).await?;
+            self.notification_service.notify_daily_digest(&digest).await;
+
+            info!(
+                "Reported {} videos ingested and {} failed crawls",
+                digest.videos_ingested,
+                digest.error_count()
+            );
+
+            self.health.record_success(LOCK_NAME).await;
+
+            info!("Wait for {} seconds until next run", self.interval_seconds);
+
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+
+    async fn build_digest(&self) -> Result<DailyDigest, Error> {
+        let to = Utc::now();
+        let from = to - chrono::Duration::seconds(self.interval_seconds as i64);
+
+        let sources = self.provenance_repo.get_report_since(from).await?;
+        let (videos_ingested, api_units) = self.crawl_audit_repo.get_totals(from).await?;
+
+        Ok(DailyDigest {
+            from: from.timestamp(),
+            to: to.timestamp(),
+            channels_discovered: sources.iter().map(|source| source.channels).sum(),
+            channels_accepted: sources.iter().map(|source| source.accepted).sum(),
+            channels_rejected: sources.iter().map(|source| source.rejected).sum(),
+            videos_ingested,
+            api_units,
+            errors_by_category: self.crawl_audit_repo.get_error_counts(from).await?,
+            slowest_crawls: self
+                .crawl_audit_repo
+                .get_slowest(from, SLOWEST_CRAWLS_LIMIT)
+                .await?,
+        })
+    }
+}
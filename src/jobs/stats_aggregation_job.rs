@@ -0,0 +1,147 @@
+use anyhow::Error;
+use chrono::Utc;
+use log::{error, info};
+use mongodb::bson::{doc, DateTime, Document};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::{
+    repos::{
+        channel_store::ChannelStore, lock_store::LockStore, site_stats_store::SiteStatsStore,
+        video_store::VideoStore,
+    },
+    utils::{consts::CHANNEL_STATUS_ACTIVE, health::Health, maintenance::Maintenance},
+};
+
+const NEW_CHANNEL_DAYS: i64 = 7;
+// Countries are ranked by their channels that uploaded within this window
+const ACTIVE_COUNTRY_DAYS: i64 = 30;
+const ACTIVE_COUNTRY_COUNT: i64 = 10;
+
+const LOCK_NAME: &str = "statsAggregationJob";
+
+/// Computes the site statistics into a new version of `site_stats` on each run, with the
+/// changes against the previous version.
+pub struct StatsAggregationJob {
+    channel_repo: Box<dyn ChannelStore>,
+    video_repo: Box<dyn VideoStore>,
+    site_stats_repo: Box<dyn SiteStatsStore>,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    health: Arc<Health>,
+}
+
+impl StatsAggregationJob {
+    pub fn new(
+        channel_repo: Box<dyn ChannelStore>,
+        video_repo: Box<dyn VideoStore>,
+        site_stats_repo: Box<dyn SiteStatsStore>,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        health: Arc<Health>,
+    ) -> StatsAggregationJob {
+        StatsAggregationJob {
+            channel_repo,
+            video_repo,
+            site_stats_repo,
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            health,
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Error> {
+        loop {
+            self.maintenance
+                .background_checkpoint("stats aggregation job")
+                .await;
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
+            match self.aggregate().await {
+                Ok(()) => self.health.record_success(LOCK_NAME).await,
+                Err(e) => error!("Failed to aggregate site stats: {}", e),
+            }
+
+            info!("Wait for {} seconds until next run", self.interval_seconds);
+
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+
+    async fn aggregate(&self) -> Result<(), Error> {
+        let now = Utc::now();
+        let previous = self.site_stats_repo.get_latest().await?;
+
+        let channels_by_status = self
+            .channel_repo
+            .count_grouped_by("status", CHANNEL_STATUS_ACTIVE)
+            .await?;
+        let total_channels: i64 = channels_by_status.iter().map(|(_, count)| count).sum();
+        let active_channels = channels_by_status
+            .iter()
+            .find(|(status, _)| status == CHANNEL_STATUS_ACTIVE)
+            .map(|(_, count)| *count)
+            .unwrap_or(0);
+        let total_videos = self.video_repo.count_all().await? as i64;
+        let new_channels = self
+            .channel_repo
+            .count_created_since(now - chrono::Duration::days(NEW_CHANNEL_DAYS))
+            .await?;
+        let active_countries = self
+            .channel_repo
+            .count_uploading_by_country(
+                (now - chrono::Duration::days(ACTIVE_COUNTRY_DAYS)).timestamp(),
+                ACTIVE_COUNTRY_COUNT,
+            )
+            .await?;
+
+        let version = previous
+            .as_ref()
+            .and_then(|stats| stats.get_i64("version").ok())
+            .unwrap_or(0)
+            + 1;
+        let get_previous = |key| {
+            previous
+                .as_ref()
+                .and_then(|stats| stats.get_i64(key).ok())
+                .unwrap_or(0)
+        };
+
+        info!(
+            "STATS: version {}, {} channels ({} active, {} new this week), {} videos",
+            version, total_channels, active_channels, new_channels, total_videos
+        );
+
+        let stats = doc! {
+            "version": version,
+            "createdAt": DateTime::now(),
+            "totalChannels": total_channels,
+            "activeChannels": active_channels,
+            "totalVideos": total_videos,
+            "newChannelsThisWeek": new_channels,
+            "mostActiveCountries": active_countries
+                .iter()
+                .map(|(country, channels)| doc! {"country": country, "channels": channels})
+                .collect::<Vec<Document>>(),
+            "changes": {
+                "totalChannels": total_channels - get_previous("totalChannels"),
+                "totalVideos": total_videos - get_previous("totalVideos"),
+            },
+        };
+
+        self.site_stats_repo.insert(stats).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+use anyhow::Error;
+use log::info;
+use mongodb::bson::doc;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::{
+    notifications::notification_service::NotificationService,
+    repos::{
+        channel_store::ChannelStore, lock_store::LockStore, review_queue_store::ReviewQueueStore,
+        video_store::VideoStore,
+    },
+    utils::{
+        consts::CHANNEL_STATUS_DEACTIVATED,
+        health::Health,
+        maintenance::Maintenance,
+        topic_drift_utils::{get_topic_drift, BASELINE_VIDEO_COUNT, RECENT_VIDEO_COUNT},
+    },
+};
+
+const PAGE_SIZE: i64 = 500;
+
+const LOCK_NAME: &str = "topicDriftJob";
+
+pub const REVIEW_REASON_TOPIC_DRIFT: &str = "topicDrift";
+
+/// Compares how often the latest videos of each channel mention guitar terms against its older
+/// videos. Channels that stopped posting guitar content get `topicDrift`, a notification and a
+/// review queue entry once; the flag is cleared when they return to guitar content.
+pub struct TopicDriftJob {
+    channel_repo: Box<dyn ChannelStore>,
+    video_repo: Box<dyn VideoStore>,
+    review_queue_repo: Box<dyn ReviewQueueStore>,
+    notification_service: Arc<NotificationService>,
+    guitar_terms: Vec<String>,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    health: Arc<Health>,
+}
+
+impl TopicDriftJob {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        channel_repo: Box<dyn ChannelStore>,
+        video_repo: Box<dyn VideoStore>,
+        review_queue_repo: Box<dyn ReviewQueueStore>,
+        notification_service: Arc<NotificationService>,
+        guitar_terms: Vec<String>,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        health: Arc<Health>,
+    ) -> TopicDriftJob {
+        TopicDriftJob {
+            channel_repo,
+            video_repo,
+            review_queue_repo,
+            notification_service,
+            guitar_terms,
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            health,
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Error> {
+        loop {
+            self.maintenance
+                .background_checkpoint("topic drift job")
+                .await;
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
+            info!("Start topic drift job");
+
+            let drift_count = self.detect_drifts().await?;
+
+            info!("{} channels drifted away from guitar content", drift_count);
+
+            self.health.record_success(LOCK_NAME).await;
+
+            info!("Wait for {} seconds until next run", self.interval_seconds);
+
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+
+    async fn detect_drifts(&self) -> Result<usize, Error> {
+        let mut after_id: Option<String> = None;
+        let mut drift_count = 0;
+
+        loop {
+            let channels = self
+                .channel_repo
+                .get_page(after_id.as_deref(), None, PAGE_SIZE)
+                .await?;
+
+            for channel in &channels {
+                if channel.get_str("status").ok() == Some(CHANNEL_STATUS_DEACTIVATED) {
+                    continue;
+                }
+
+                let id = channel.get_str("_id")?;
+                let was_drifting = channel.get_document("topicDrift").is_ok();
+                let videos = self
+                    .video_repo
+                    .get_latest_texts(id, (RECENT_VIDEO_COUNT + BASELINE_VIDEO_COUNT) as i64)
+                    .await?;
+
+                match get_topic_drift(&self.guitar_terms, &videos) {
+                    Some(drift) if !was_drifting => {
+                        let title = channel.get_str("title").unwrap_or(id);
+
+                        self.channel_repo.set_topic_drift(id, Some(&drift)).await?;
+                        self.review_queue_repo
+                            .reopen(
+                                id,
+                                doc! {
+                                    "title": title,
+                                    "description": channel.get_str("description").unwrap_or_default(),
+                                    "score": drift.baseline_density - drift.recent_density,
+                                    "evidence": {
+                                        "reason": REVIEW_REASON_TOPIC_DRIFT,
+                                        "recentDensity": drift.recent_density,
+                                        "baselineDensity": drift.baseline_density,
+                                    },
+                                },
+                            )
+                            .await?;
+                        self.notification_service
+                            .notify_topic_drift(
+                                id,
+                                title,
+                                drift.recent_density,
+                                drift.baseline_density,
+                            )
+                            .await;
+                        drift_count += 1;
+                    }
+                    None if was_drifting => {
+                        info!("Channel {} returned to guitar content", id);
+                        self.channel_repo.set_topic_drift(id, None).await?;
+                    }
+                    _ => {}
+                }
+            }
+
+            match channels.last() {
+                Some(channel) if channels.len() as i64 == PAGE_SIZE => {
+                    after_id = Some(channel.get_str("_id")?.to_string());
+                }
+                _ => return Ok(drift_count),
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use anyhow::Error;
+use log::info;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::{
+    repos::{channel_store::ChannelStore, lock_store::LockStore, video_store::VideoStore},
+    utils::{
+        consts::CHANNEL_STATUS_DEACTIVATED,
+        health::Health,
+        maintenance::Maintenance,
+        upload_pattern_utils::{compute_upload_pattern, get_country_timezone},
+    },
+};
+
+const PAGE_SIZE: i64 = 500;
+// Uploads the pattern is computed from, older habits do not matter
+const UPLOAD_HISTORY_SIZE: i64 = 100;
+
+const LOCK_NAME: &str = "uploadPatternJob";
+
+/// Computes on which days and hours each channel typically uploads, in the local time of its
+/// country, and stores the pattern for the site and for the video scraper to poll right after the
+/// usual upload slots.
+pub struct UploadPatternJob {
+    channel_repo: Box<dyn ChannelStore>,
+    video_repo: Box<dyn VideoStore>,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    health: Arc<Health>,
+}
+
+impl UploadPatternJob {
+    pub fn new(
+        channel_repo: Box<dyn ChannelStore>,
+        video_repo: Box<dyn VideoStore>,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        health: Arc<Health>,
+    ) -> UploadPatternJob {
+        UploadPatternJob {
+            channel_repo,
+            video_repo,
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            health,
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Error> {
+        loop {
+            self.maintenance
+                .background_checkpoint("upload pattern job")
+                .await;
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
+            info!("Start upload pattern job");
+
+            let pattern_count = self.update_upload_patterns().await?;
+
+            info!("Stored the upload patterns of {} channels", pattern_count);
+
+            self.health.record_success(LOCK_NAME).await;
+
+            info!("Wait for {} seconds until next run", self.interval_seconds);
+
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+
+    async fn update_upload_patterns(&self) -> Result<usize, Error> {
+        let mut after_id: Option<String> = None;
+        let mut pattern_count = 0;
+
+        loop {
+            let channels = self
+                .channel_repo
+                .get_page(after_id.as_deref(), None, PAGE_SIZE)
+                .await?;
+
+            for channel in &channels {
+                if channel.get_str("status").ok() == Some(CHANNEL_STATUS_DEACTIVATED) {
+                    continue;
+                }
+
+                let id = channel.get_str("_id")?;
+                let published_timestamps = self
+                    .video_repo
+                    .get_published_timestamps(id, UPLOAD_HISTORY_SIZE)
+                    .await?;
+                let timezone = get_country_timezone(channel.get_str("country").ok());
+
+                if let Some(pattern) = compute_upload_pattern(&published_timestamps, timezone) {
+                    self.channel_repo.set_upload_pattern(id, &pattern).await?;
+                    pattern_count += 1;
+                }
+            }
+
+            match channels.last() {
+                Some(channel) if channels.len() as i64 == PAGE_SIZE => {
+                    after_id = Some(channel.get_str("_id")?.to_string());
+                }
+                _ => return Ok(pattern_count),
+            }
+        }
+    }
+}
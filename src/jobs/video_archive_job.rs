@@ -0,0 +1,97 @@
+use anyhow::Error;
+use chrono::Utc;
+use log::{error, info};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::{
+    repos::{channel_store::ChannelStore, lock_store::LockStore, video_store::VideoStore},
+    utils::{health::Health, maintenance::Maintenance},
+};
+
+const DORMANT_AFTER_WEEKS: i64 = 52;
+const ARCHIVE_VIDEOS_OLDER_THAN_WEEKS: i64 = 104;
+// The feed returns the latest 15 videos, keeping them hot prevents re-inserting archived videos
+const KEEP_LATEST_VIDEOS: u64 = 15;
+
+const LOCK_NAME: &str = "videoArchiveJob";
+
+pub struct VideoArchiveJob {
+    channel_repo: Box<dyn ChannelStore>,
+    video_repo: Box<dyn VideoStore>,
+    maintenance: Arc<Maintenance>,
+    lock_repo: Box<dyn LockStore>,
+    interval_seconds: u64,
+    health: Arc<Health>,
+}
+
+impl VideoArchiveJob {
+    pub fn new(
+        channel_repo: Box<dyn ChannelStore>,
+        video_repo: Box<dyn VideoStore>,
+        maintenance: Arc<Maintenance>,
+        lock_repo: Box<dyn LockStore>,
+        interval_seconds: u64,
+        health: Arc<Health>,
+    ) -> VideoArchiveJob {
+        VideoArchiveJob {
+            channel_repo,
+            video_repo,
+            maintenance,
+            lock_repo,
+            interval_seconds,
+            health,
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Error> {
+        loop {
+            self.maintenance
+                .background_checkpoint("video archive job")
+                .await;
+
+            if !self
+                .lock_repo
+                .acquire_run(LOCK_NAME, self.interval_seconds)
+                .await?
+            {
+                continue;
+            }
+
+            info!("Start video archive job");
+
+            let dormant_since = Utc::now() - chrono::Duration::weeks(DORMANT_AFTER_WEEKS);
+            let published_before =
+                (Utc::now() - chrono::Duration::weeks(ARCHIVE_VIDEOS_OLDER_THAN_WEEKS)).timestamp();
+
+            let channel_ids = self
+                .channel_repo
+                .get_ids_last_upload_before(dormant_since)
+                .await?;
+
+            info!("Found {} dormant channels", channel_ids.len());
+
+            let mut archived_count = 0;
+
+            for channel_id in channel_ids {
+                match self
+                    .video_repo
+                    .archive_by_channel(&channel_id, published_before, KEEP_LATEST_VIDEOS)
+                    .await
+                {
+                    Ok(count) => archived_count += count,
+                    Err(e) => error!("Failed to archive videos of channel {}: {}", channel_id, e),
+                }
+            }
+
+            info!("Archived {} videos to cold storage", archived_count);
+
+            self.health.record_success(LOCK_NAME).await;
+
+            info!("Wait for {} seconds until next run", self.interval_seconds);
+
+            sleep(Duration::from_secs(self.interval_seconds)).await;
+        }
+    }
+}
@@ -1,55 +1,167 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 
+use api::{admin_api::AdminApi, grpc_api::GrpcApi};
+use chrono::{DateTime, Utc};
+use clap::Parser;
+use cli::cli_args::{
+    AdditionalCommand, BlocklistCommand, ChannelCommand, CliArgs, CliCommand, DiscoveryCommand,
+    ExportCommand, ExportOptions, ImportCommand, PurgeCommand,
+};
 use crawler::{
-    additional_channel_crawler::AdditionalChannelCrawler,
+    about_crawler::AboutCrawler, additional_channel_crawler::AdditionalChannelCrawler,
+    caption_crawler::CaptionCrawler, channel_backfill_crawler::ChannelBackfillCrawler,
     channel_discovery_crawler::ChannelDiscoveryCrawler,
+    crawl_request_listener::CrawlRequestListener,
+};
+use errors::crawler_error::CrawlerError;
+use events::{
+    broadcast_publisher::BroadcastPublisher,
+    change_sink::ChangeSink,
+    change_stream_publisher::ChangeStreamPublisher,
+    event_publisher::EventPublisher,
+    kafka_publisher::KafkaPublisher,
+    nats_change_sink::NatsChangeSink,
+    postgres_change_stream_publisher::{set_up_change_outbox, PostgresChangeStreamPublisher},
 };
-use figment::{
-    providers::{Env, Format, Json},
-    Figment,
+use jobs::{
+    alert_job::AlertJob, ban_evasion_job::BanEvasionJob,
+    channel_lifecycle_job::ChannelLifecycleJob,
+    channel_metadata_refresh_job::ChannelMetadataRefreshJob,
+    comment_ingestion_job::CommentIngestionJob, comment_sentiment_job::CommentSentimentJob,
+    corpus_snapshot_job::CorpusSnapshotJob, course_detection_job::CourseDetectionJob,
+    duplicate_detection_job::DuplicateDetectionJob,
+    end_screen_discovery_job::EndScreenDiscoveryJob, genre_tag_job::GenreTagJob,
+    link_verification_job::LinkVerificationJob, probation_job::ProbationJob,
+    reclassification_job::ReclassificationJob, reconciliation_job::ReconciliationJob,
+    related_channels_job::RelatedChannelsJob, reporting_job::ReportingJob,
+    stats_aggregation_job::StatsAggregationJob, topic_drift_job::TopicDriftJob,
+    upload_pattern_job::UploadPatternJob, video_archive_job::VideoArchiveJob,
 };
-use log::{debug, error, info, LevelFilter};
+use log::{debug, error, info, warn, LevelFilter};
 use mongodb::{options::ClientOptions, Client};
-use repos::additional_channel_repo::AdditionalChannelRepository;
-use repos::blacklist_repo::BlacklistRepository;
-use repos::guitar_term_repo::GuitarTermRepository;
+use queue::{
+    channel_command_queue::{ChannelCommandQueue, QueuedChannelCommand},
+    local_channel_command_queue::LocalChannelCommandQueue,
+    redis_channel_command_queue::RedisChannelCommandQueue,
+};
+use repos::crawl_audit_store::{CrawlAuditStore, CRAWL_KIND_CHANNEL, CRAWL_KIND_VIDEOS};
+use repos::discovery_provenance_store::{
+    DISCOVERY_OUTCOME_ACCEPTED, DISCOVERY_OUTCOME_FAILED, DISCOVERY_OUTCOME_REJECTED,
+};
 use simple_logger::SimpleLogger;
+use simulation::{
+    replay_server::ReplayServer,
+    simulation_seeder::{seed_replay_database, seed_simulation_database},
+    simulation_server::SimulationServer,
+    synthetic_corpus::SyntheticCorpus,
+};
+use tokio::signal::unix::{signal, SignalKind};
 use tokio::sync::mpsc::{channel, Receiver, Sender};
 use tokio::task::{self, JoinHandle};
+use tokio::time::sleep;
+use tokio_retry::{strategy::ExponentialBackoff, RetryIf};
 
+use crate::crawler::new_video_crawler::NewVideoCrawler;
+use crate::export::exporter::Exporter;
+use crate::metrics::{
+    db_load_monitor::DbLoadMonitor, metrics_registry::MetricsRegistry,
+    mongo_command_monitor::MongoCommandMonitor,
+};
+use crate::migrations::migration_runner::MigrationRunner;
+use crate::notifications::notification_service::NotificationService;
+use crate::notifications::smtp_client::SmtpClient;
+use crate::scraper::{
+    about_scraper::AboutScraper,
+    caption_scraper::{CaptionScraper, CAPTION_SONGS},
+    channel_scraper::ChannelScraper,
+    video_scraper::VideoScraper,
+};
 use crate::{
-    commands::crawl_channel_command::CrawlChannelCommand,
+    classifiers::{
+        genre_classifier::{build_genre_classifier, GenreClassifier},
+        video_type_classifier::{build_video_type_classifier, VideoTypeClassifier},
+    },
+    commands::{
+        crawl_about_command::CrawlAboutCommand, crawl_captions_command::CrawlCaptionsCommand,
+        crawl_channel_command::CrawlChannelCommand,
+    },
+    models::crawl_stats::CrawlStats,
     repos::{
-        settings_repo::SettingsRepository, subscriber_repo::SubscriberRepository,
-        view_repo::ViewRepository,
+        channel_probation_store::ChannelProbationStore,
+        settings_repo::SettingsRepository,
+        settings_store::{get_source_feature_flag, is_feature_enabled, SettingsStore},
+    },
+    services::{
+        channel_merge_service::ChannelMergeService, channel_purge_service::ChannelPurgeService,
+        channel_redirect_service::ChannelRedirectService,
+        collaboration_service::CollaborationService, crawl_request_service::CrawlRequestService,
+        gear_extraction_service::GearExtractionService, guitar_terms_service::GuitarTermsService,
+        opt_out_service::OptOutService, sentiment_service::SentimentService,
+        song_recognition_service::SongRecognitionService,
+        tag_analytics_service::TagAnalyticsService, url_resolver_service::UrlResolverService,
+        youtube_service::YoutubeService,
+    },
+    utils::{
+        api_scheduler::{
+            get_pacing_interval, get_source_priority, ApiCaller, ApiPriority, ApiScheduler,
+        },
+        config_utils::{load_config, validate_config},
+        consts::{
+            CHANGE_SINK_NATS, CHANNEL_COMMAND_MAX_ATTEMPTS, CHANNEL_SCRAPE_MAX_RETRIES,
+            CHANNEL_SCRAPE_RETRY_BASE_MILLIS, CHANNEL_SCRAPE_RETRY_FACTOR, CHANNEL_SOURCE_CLI,
+            DEFAULT_API_KEY_DAILY_QUOTA, DEFAULT_MONGODB_MAX_POOL_SIZE,
+            FEATURE_VIDEO_SCRAPE_ENABLED, IMPORT_PROGRESS_INTERVAL, QUEUE_BACKEND_REDIS,
+            QUEUE_RETRY_SECONDS, REPLAY_ENVIRONMENT, SCRAPER_QUEUE_CAPACITY,
+            SIMULATION_ENVIRONMENT, STORAGE_BACKEND_MONGODB,
+            STRICT_COMPLIANCE_REQUEST_INTERVAL_MILLIS,
+        },
+        crawl_budget::CrawlBudget,
+        health::Health,
+        import_utils::parse_channel_import,
+        maintenance::Maintenance,
+        proxy_pool::ProxyPool,
+        rate_limiter::RateLimiter,
+        throttle::Throttle,
+        youtube_url_utils::{parse_youtube_url, YoutubeResource},
     },
-    services::{guitar_terms_service::GuitarTermsService, youtube_service::YoutubeService},
 };
 use crate::{commands::crawl_videos_command::CrawlVideosCommand, models::config::Config};
 use crate::{
-    crawler::channel_update_crawler::ChannelUpdateCrawler, repos::video_repo::VideoRepository,
-};
-use crate::{crawler::new_video_crawler::NewVideoCrawler, repos::channel_repo::ChannelRepository};
-use crate::{repos::apikeys_repo::ApiKeyRepository, scraper::channel_scraper::ChannelScraper};
-use crate::{
-    repos::non_guitar_channel_repo::NonGuitarChannelRepository,
-    scraper::video_scraper::VideoScraper,
+    crawler::channel_update_crawler::ChannelUpdateCrawler, repos::store_factory::StoreFactory,
 };
 
+mod api;
+mod cache;
+mod classifiers;
+mod cli;
 mod commands;
 mod crawler;
+mod errors;
+mod events;
+mod export;
+mod jobs;
+mod metrics;
+mod migrations;
 mod models;
+mod notifications;
+mod queue;
 mod repos;
 mod scraper;
 mod services;
+mod simulation;
+#[cfg(test)]
+mod test_support;
 mod utils;
 
 #[tokio::main]
 pub async fn main() -> Result<(), anyhow::Error> {
-    let config: Config = Figment::new()
-        .merge(Json::file("config.json"))
-        .merge(Env::raw().only(&["MONGO_CONNECTION_STRING"]))
-        .extract()?;
+    let cli_args = CliArgs::parse();
+    let mut config = load_config()?;
+    validate_config(&config)?;
 
     debug!("{:?}", config);
     info!("Environment {}", config.environment);
@@ -58,281 +170,3016 @@ pub async fn main() -> Result<(), anyhow::Error> {
         .with_level(LevelFilter::from_str(&config.log_level).unwrap())
         .init()?;
 
-    info!("Start connection to mongodb");
+    let metrics = Arc::new(MetricsRegistry::new());
+    let maintenance = Arc::new(Maintenance::new(get_maintenance_reason(&config)));
 
-    let opts = ClientOptions::parse(&config.mongo_connection_string).await?;
-    let db_client = Client::with_options(opts)?;
+    if let Some(command) = cli_args.command {
+        let mongo_client = connect_mongodb(&config, &metrics, &maintenance).await?;
+        let stores =
+            StoreFactory::connect(mongo_client, &config, get_event_publisher(&config)?).await?;
 
-    info!("Connected to mongodb");
+        return run_cli_command(command, stores, config).await;
+    }
+
+    if config.simulation.enabled {
+        config.environment = SIMULATION_ENVIRONMENT.to_string();
+        config.youtube = SimulationServer::youtube_config(config.simulation.port);
+    }
+
+    if config.replay.enabled {
+        config.environment = REPLAY_ENVIRONMENT.to_string();
+        config.youtube = SimulationServer::youtube_config(config.replay.port);
+    }
+
+    let mongo_client = connect_mongodb(&config, &metrics, &maintenance).await?;
+    let (event_publisher, event_broadcast) = get_daemon_event_publisher(&config)?;
+    let stores = StoreFactory::connect(mongo_client, &config, event_publisher).await?;
 
     let mut tasks = vec![];
 
-    let (channel_scraper_tx, channel_scraper_rx) = channel::<CrawlChannelCommand>(usize::MAX >> 3);
-    let (video_scraper_tx, video_scraper_rx) = channel::<CrawlVideosCommand>(usize::MAX >> 3);
+    if config.simulation.enabled {
+        register_simulation_server(&mut tasks, stores.clone(), config.clone()).await?;
+    }
+
+    if config.replay.enabled {
+        register_replay_server(&mut tasks, stores.clone(), config.clone()).await?;
+    }
+
+    register_api_keys(&stores, &config).await?;
+    stores.ensure_indexes().await?;
+
+    if let Some(postgres_client) = stores.postgres_client() {
+        set_up_change_outbox(&postgres_client, config.change_stream.enabled).await?;
+    }
+
+    if config.strict_compliance {
+        info!(
+            "Strict compliance mode enabled, requests are spaced by {}ms",
+            STRICT_COMPLIANCE_REQUEST_INTERVAL_MILLIS
+        );
+    }
+
+    let api_scheduler = Arc::new(ApiScheduler::new(get_pacing_interval(
+        get_request_interval(&config),
+        config.youtube.daily_api_calls,
+    )));
+    let feed_throttle = Arc::new(Throttle::new(get_request_interval(&config)));
+    let health = Arc::new(Health::new(stores.clone(), &config.youtube.api_base_url));
+
+    let (channel_scraper_tx, channel_scraper_rx) =
+        channel::<CrawlChannelCommand>(SCRAPER_QUEUE_CAPACITY);
+    let (video_scraper_tx, video_scraper_rx) =
+        channel::<CrawlVideosCommand>(SCRAPER_QUEUE_CAPACITY);
+    let (caption_scraper_tx, caption_scraper_rx) =
+        channel::<CrawlCaptionsCommand>(SCRAPER_QUEUE_CAPACITY);
+    let (about_scraper_tx, about_scraper_rx) = channel::<CrawlAboutCommand>(SCRAPER_QUEUE_CAPACITY);
+
+    health
+        .register_queue(
+            "channelScraper",
+            channel_scraper_tx.clone(),
+            SCRAPER_QUEUE_CAPACITY,
+        )
+        .await;
+    health
+        .register_queue(
+            "videoScraper",
+            video_scraper_tx.clone(),
+            SCRAPER_QUEUE_CAPACITY,
+        )
+        .await;
+    health
+        .register_queue(
+            "captionScraper",
+            caption_scraper_tx.clone(),
+            SCRAPER_QUEUE_CAPACITY,
+        )
+        .await;
+    health
+        .register_queue(
+            "aboutScraper",
+            about_scraper_tx.clone(),
+            SCRAPER_QUEUE_CAPACITY,
+        )
+        .await;
+
+    let channel_scraper_queue =
+        get_channel_command_queue(&mut tasks, &config, channel_scraper_rx).await?;
+    let scrape_budget = Arc::new(CrawlBudget::new(config.budgets.scrape.clone()));
 
     register_channel_scraper(
         &mut tasks,
-        db_client.clone(),
+        stores.clone(),
         config.clone(),
-        channel_scraper_rx,
+        maintenance.clone(),
+        api_scheduler.clone(),
+        channel_scraper_queue,
     );
 
     register_video_scraper(
         &mut tasks,
-        db_client.clone(),
+        stores.clone(),
         config.clone(),
+        maintenance.clone(),
+        api_scheduler.clone(),
+        feed_throttle.clone(),
+        get_feed_rate_limiter(&config),
+        Arc::new(ProxyPool::new(&config.proxy.urls)?),
+        metrics.clone(),
+        scrape_budget.clone(),
         video_scraper_rx,
     );
 
+    register_caption_scraper(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
+        feed_throttle.clone(),
+        caption_scraper_rx,
+    );
+
+    register_about_scraper(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
+        api_scheduler.clone(),
+        feed_throttle.clone(),
+        about_scraper_rx,
+    );
+
     register_additional_channel_crawler(
         &mut tasks,
-        db_client.clone(),
+        stores.clone(),
         config.clone(),
+        maintenance.clone(),
+        health.clone(),
+        channel_scraper_tx.clone(),
+    );
+
+    let crawl_request_service = Arc::new(CrawlRequestService::new(
         channel_scraper_tx.clone(),
+        stores.channel_store(),
+        stores.additional_channel_store(),
+        stores.blocklist_store(),
+        stores.opt_out_store(),
+        Duration::from_secs(config.ingest.dedupe_seconds),
+    ));
+
+    register_crawl_request_listener(
+        &mut tasks,
+        config.clone(),
+        maintenance.clone(),
+        crawl_request_service.clone(),
+    );
+
+    register_change_stream_publisher(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
     );
 
     register_channel_discovery_crawler(
         &mut tasks,
-        db_client.clone(),
+        stores.clone(),
         config.clone(),
+        maintenance.clone(),
+        health.clone(),
+        api_scheduler.clone(),
         channel_scraper_tx.clone(),
     );
 
     register_channel_update_crawler(
         &mut tasks,
-        db_client.clone(),
+        stores.clone(),
         config.clone(),
+        maintenance.clone(),
+        health.clone(),
         channel_scraper_tx.clone(),
     );
 
     register_new_video_crawler(
         &mut tasks,
-        db_client.clone(),
+        stores.clone(),
         config.clone(),
+        maintenance.clone(),
+        health.clone(),
+        scrape_budget,
         video_scraper_tx.clone(),
     );
 
-    await_all(tasks).await?;
+    register_channel_backfill_crawler(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
+        health.clone(),
+        api_scheduler.clone(),
+    );
 
-    Ok(())
-}
+    register_caption_crawler(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
+        health.clone(),
+        caption_scraper_tx.clone(),
+    );
 
-async fn await_all(tasks: Vec<JoinHandle<()>>) -> Result<(), anyhow::Error> {
-    for task in tasks {
-        task.await?;
-    }
+    register_about_crawler(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
+        health.clone(),
+        about_scraper_tx.clone(),
+    );
 
-    Ok(())
-}
+    register_corpus_snapshot_job(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
+        health.clone(),
+    );
 
-fn register_additional_channel_crawler(
-    tasks: &mut Vec<JoinHandle<()>>,
-    mongo_client: Client,
-    config: Config,
-    tx: Sender<CrawlChannelCommand>,
-) {
-    if config.crawler.additional == false {
-        return;
-    }
+    register_video_archive_job(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
+        health.clone(),
+    );
 
-    let additional_channel_crawling_task = task::spawn(async move {
-        let additional_channel_repo =
-            AdditionalChannelRepository::new(&mongo_client, &config.environment);
-        let crawler = AdditionalChannelCrawler::new(tx, additional_channel_repo);
+    register_reclassification_job(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
+        health.clone(),
+        api_scheduler.clone(),
+    );
 
-        info!("CRAWLER: Start additional channel crawling");
-        crawler
-            .crawl()
-            .await
-            .expect("Panic in additional channel crawling");
-    });
+    register_course_detection_job(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
+        health.clone(),
+        api_scheduler.clone(),
+    );
 
-    tasks.push(additional_channel_crawling_task);
-}
+    register_probation_job(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
+        health.clone(),
+    );
 
-fn register_channel_discovery_crawler(
-    tasks: &mut Vec<JoinHandle<()>>,
-    mongo_client: Client,
-    config: Config,
-    tx: Sender<CrawlChannelCommand>,
-) {
-    if config.crawler.discovery == false {
-        return;
-    }
+    register_reconciliation_job(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
+        health.clone(),
+        api_scheduler.clone(),
+    );
 
-    let channel_discovery_crawling_task = task::spawn(async move {
-        let guitar_terms = get_guitar_terms(&mongo_client, &config.environment).await;
-        let blacklisted_channel_ids =
-            get_blacklisted_channels(&mongo_client, &config.environment).await;
-
-        let channel_repo = ChannelRepository::new(&mongo_client, &config.environment);
-        let settings_repo = SettingsRepository::new(&mongo_client, &config.environment);
-        let apikey_repo = ApiKeyRepository::new(&mongo_client, &config.environment);
-        let non_guitar_channel_repo =
-            NonGuitarChannelRepository::new(&mongo_client, &config.environment);
-        let additional_channel_repo =
-            AdditionalChannelRepository::new(&mongo_client, &config.environment);
-
-        let youtube_service = YoutubeService::new(apikey_repo);
-        let guitar_terms_service = GuitarTermsService::new(
-            guitar_terms,
-            blacklisted_channel_ids,
-            non_guitar_channel_repo,
-        );
+    register_related_channels_job(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
+        health.clone(),
+        channel_scraper_tx.clone(),
+    );
 
-        let crawler = ChannelDiscoveryCrawler::new(
-            tx,
-            channel_repo,
-            settings_repo,
-            youtube_service,
-            guitar_terms_service,
-            additional_channel_repo,
-        );
+    register_end_screen_discovery_job(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
+        health.clone(),
+        feed_throttle.clone(),
+        channel_scraper_tx.clone(),
+        api_scheduler.clone(),
+    );
 
-        info!("CRAWLER: Start channel discovery crawling");
-        crawler
-            .crawl()
-            .await
-            .expect("Panic in channel discovery crawling");
-    });
+    register_channel_lifecycle_job(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
+        health.clone(),
+    );
 
-    tasks.push(channel_discovery_crawling_task);
-}
+    register_duplicate_detection_job(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
+        health.clone(),
+    );
 
-fn register_channel_update_crawler(
-    tasks: &mut Vec<JoinHandle<()>>,
-    mongo_client: Client,
-    config: Config,
-    tx: Sender<CrawlChannelCommand>,
-) {
-    if config.crawler.channel == false {
-        return;
-    }
+    register_topic_drift_job(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
+        health.clone(),
+    );
 
-    let channel_update_crawling_task = task::spawn(async move {
-        let channel_repo = ChannelRepository::new(&mongo_client, &config.environment);
-        let crawler = ChannelUpdateCrawler::new(tx, channel_repo);
+    register_stats_aggregation_job(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
+        health.clone(),
+    );
 
-        info!("CRAWLER: Start channel update crawling");
-        let result = crawler.crawl().await;
+    register_comment_ingestion_job(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
+        health.clone(),
+        api_scheduler.clone(),
+    );
 
-        if let Err(e) = result {
-            error!("Error in channel update crawling: {}", e);
-        }
-    });
+    register_comment_sentiment_job(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
+        health.clone(),
+    );
 
-    tasks.push(channel_update_crawling_task);
-}
+    register_ban_evasion_job(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
+        health.clone(),
+    );
 
-fn register_new_video_crawler(
-    tasks: &mut Vec<JoinHandle<()>>,
-    mongo_client: Client,
-    config: Config,
-    tx: Sender<CrawlVideosCommand>,
-) {
-    if config.crawler.video == false {
-        return;
-    }
+    register_link_verification_job(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
+        health.clone(),
+    );
 
-    let new_video_crawling_task = task::spawn(async move {
-        let channel_repo = ChannelRepository::new(&mongo_client, &config.environment);
-        let crawler = NewVideoCrawler::new(tx, channel_repo);
+    register_upload_pattern_job(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
+        health.clone(),
+    );
 
-        info!("CRAWLER: Start new video crawling");
-        let result = crawler.crawl().await;
+    register_genre_tag_job(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
+        health.clone(),
+    );
 
-        if let Err(e) = result {
-            error!("Error in new video crawling: {}", e);
-        }
-    });
+    register_metadata_refresh_job(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
+        health.clone(),
+        api_scheduler.clone(),
+    );
 
-    tasks.push(new_video_crawling_task);
-}
+    register_reporting_job(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
+        health.clone(),
+    );
 
-fn register_channel_scraper(
-    tasks: &mut Vec<JoinHandle<()>>,
-    mongo_client: Client,
-    config: Config,
-    mut rx: Receiver<CrawlChannelCommand>,
-) {
-    let channel_scraper_task = task::spawn(async move {
-        info!("SCRAPER: Start channel scrape listener");
+    register_alert_job(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance.clone(),
+        health.clone(),
+    );
 
-        let channel_repo = ChannelRepository::new(&mongo_client, &config.environment);
-        let non_guitar_channel_repo =
-            NonGuitarChannelRepository::new(&mongo_client, &config.environment);
-        let view_repo = ViewRepository::new(&mongo_client, &config.environment);
-        let subscriber_repo = SubscriberRepository::new(&mongo_client, &config.environment);
-        let video_repo = VideoRepository::new(&mongo_client, &config.environment);
-        let apikey_repo = ApiKeyRepository::new(&mongo_client, &config.environment);
+    register_admin_api(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        maintenance,
+        health.clone(),
+        metrics,
+    );
 
-        let guitar_terms = get_guitar_terms(&mongo_client, &config.environment).await;
-        let blacklisted_channel_ids =
-            get_blacklisted_channels(&mongo_client, &config.environment).await;
+    register_grpc_api(
+        &mut tasks,
+        stores.clone(),
+        config.clone(),
+        health,
+        crawl_request_service,
+        event_broadcast,
+    );
 
-        let guitar_terms_service = GuitarTermsService::new(
-            guitar_terms,
-            blacklisted_channel_ids,
-            non_guitar_channel_repo,
-        );
+    let lock_repo = stores.lock_store();
 
-        let scraper = ChannelScraper::new(
-            channel_repo,
-            view_repo,
-            subscriber_repo,
-            video_repo,
-            apikey_repo,
-            guitar_terms_service,
-        );
+    tokio::select! {
+        result = await_all(tasks) => result?,
+        _ = shutdown_signal() => {
+            let released = lock_repo.release_all().await?;
+            info!("Shutting down, released {} locks", released);
+        }
+    }
 
-        while let Some(cmd) = rx.recv().await {
-            let result = scraper
-                .scrape(cmd.channel_id, cmd.ignore_guitar_terms)
-                .await;
+    Ok(())
+}
 
-            if let Err(e) = result {
-                error!("Error in channel scraping: {}", e);
-            }
+async fn shutdown_signal() {
+    let mut terminate = match signal(SignalKind::terminate()) {
+        Ok(terminate) => terminate,
+        Err(e) => {
+            warn!("Failed to listen for SIGTERM: {}", e);
+            let _ = tokio::signal::ctrl_c().await;
+            return;
         }
-    });
+    };
 
-    tasks.push(channel_scraper_task);
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate.recv() => {}
+    }
 }
 
-fn register_video_scraper(
-    tasks: &mut Vec<JoinHandle<()>>,
-    mongo_client: Client,
+async fn run_cli_command(
+    command: CliCommand,
+    stores: StoreFactory,
     config: Config,
-    mut rx: Receiver<CrawlVideosCommand>,
-) {
-    let video_scraper_task = task::spawn(async move {
-        info!("SCRAPER: Start video scrape listener");
+) -> Result<(), anyhow::Error> {
+    let api_scheduler = Arc::new(ApiScheduler::new(get_pacing_interval(
+        get_request_interval(&config),
+        config.youtube.daily_api_calls,
+    )));
+    let feed_throttle = Arc::new(Throttle::new(get_request_interval(&config)));
+    let maintenance = Arc::new(Maintenance::new(None));
+    let health = Arc::new(Health::new(stores.clone(), &config.youtube.api_base_url));
 
-        let video_repo = VideoRepository::new(&mongo_client, &config.environment);
-        let channel_repo = ChannelRepository::new(&mongo_client, &config.environment);
-        let scraper = VideoScraper::new(video_repo, channel_repo);
+    match command {
+        CliCommand::Channel {
+            command:
+                ChannelCommand::Add {
+                    channel: channel_argument,
+                    ignore_guitar_terms,
+                },
+        } => {
+            let channel_id = parse_channel_argument(&channel_argument)?;
+            let additional_channel_repo = stores.additional_channel_store();
 
-        while let Some(cmd) = rx.recv().await {
-            let result = scraper.scrape(cmd.channel_id).await;
+            additional_channel_repo
+                .insert(&channel_id, ignore_guitar_terms)
+                .await?;
 
-            if let Err(e) = result {
-                error!("Error in video scraper: {}", e);
-            }
+            println!("Channel {} queued for crawling", channel_id);
         }
-    });
+        CliCommand::Channel {
+            command:
+                ChannelCommand::Scrape {
+                    channel: channel_argument,
+                    ignore_guitar_terms,
+                },
+        } => {
+            let channel_id = parse_channel_argument(&channel_argument)?;
+
+            let mut tasks = vec![];
+            let (channel_scraper_tx, channel_scraper_rx) = channel::<CrawlChannelCommand>(1);
+            register_channel_scraper(
+                &mut tasks,
+                stores.clone(),
+                config.clone(),
+                maintenance.clone(),
+                api_scheduler.clone(),
+                Box::new(LocalChannelCommandQueue::new(channel_scraper_rx)),
+            );
+
+            channel_scraper_tx
+                .send(CrawlChannelCommand::new(
+                    channel_id.clone(),
+                    ignore_guitar_terms,
+                    Some(CHANNEL_SOURCE_CLI.to_string()),
+                ))
+                .await?;
+            drop(channel_scraper_tx);
+            await_all(tasks).await?;
+
+            let channel_repo = stores.channel_store();
+            let stored_channel_id = if channel_id.starts_with('@') {
+                channel_repo.get_id_by_handle(&channel_id).await?
+            } else {
+                Some(channel_id.clone())
+            };
+
+            let stored_channel_id = match stored_channel_id {
+                Some(id) => Some(channel_repo.get_redirect(&id).await?.unwrap_or(id)),
+                None => None,
+            };
+
+            let stored_channel_id = match stored_channel_id {
+                Some(id) if channel_repo.exists(&id).await? => id,
+                _ => {
+                    println!("Channel {} was not stored as a guitar channel", channel_id);
+                    return Ok(());
+                }
+            };
+
+            let feed_rate_limiter = get_feed_rate_limiter(&config);
+            let feed_proxy_pool = Arc::new(ProxyPool::new(&config.proxy.urls)?);
+            let scrape_budget = Arc::new(CrawlBudget::new(config.budgets.scrape.clone()));
+            let mut tasks = vec![];
+            let (video_scraper_tx, video_scraper_rx) = channel::<CrawlVideosCommand>(1);
+            register_video_scraper(
+                &mut tasks,
+                stores,
+                config,
+                maintenance,
+                api_scheduler,
+                feed_throttle,
+                feed_rate_limiter,
+                feed_proxy_pool,
+                Arc::new(MetricsRegistry::new()),
+                scrape_budget,
+                video_scraper_rx,
+            );
+
+            video_scraper_tx
+                .send(CrawlVideosCommand {
+                    channel_id: stored_channel_id.clone(),
+                })
+                .await?;
+            drop(video_scraper_tx);
+            await_all(tasks).await?;
+
+            println!("Channel {} scraped", stored_channel_id);
+        }
+        CliCommand::Channel {
+            command:
+                ChannelCommand::Merge {
+                    channel_id,
+                    canonical_id,
+                },
+        } => {
+            let channel_repo = stores.channel_store();
+
+            for id in [&channel_id, &canonical_id] {
+                if !channel_repo.exists(id).await? {
+                    return Err(anyhow::anyhow!("Channel {} is not stored", id));
+                }
+            }
+
+            get_channel_merge_service(&stores)
+                .merge(&channel_id, &canonical_id)
+                .await?;
+
+            println!("Channel {} merged into {}", channel_id, canonical_id);
+        }
+        CliCommand::Channel {
+            command: ChannelCommand::Crawls { channel_id, limit },
+        } => {
+            let crawl_audit_repo = stores.crawl_audit_store();
+
+            for run in crawl_audit_repo.get_latest(&channel_id, limit).await? {
+                println!(
+                    "{} {}: {} seen, {} updated, {} skipped, {} api units, {}ms{}",
+                    run.get_datetime("startedAt")?.to_chrono().to_rfc3339(),
+                    run.get_str("kind")?,
+                    run.get_i64("videosSeen")?,
+                    run.get_i64("videosUpdated")?,
+                    run.get_i64("videosSkipped")?,
+                    run.get_i64("apiUnits")?,
+                    run.get_i64("durationMillis")?,
+                    run.get_str("error")
+                        .map(|error| format!(", error: {}", error))
+                        .unwrap_or_default()
+                );
+            }
+        }
+        CliCommand::Backfill { channel_id } => {
+            let crawler = build_channel_backfill_crawler(
+                &stores,
+                &config,
+                maintenance,
+                health,
+                api_scheduler,
+            );
+
+            crawler.backfill_channel(&channel_id).await?;
+
+            println!("Backfill of channel {} finished", channel_id);
+        }
+        CliCommand::Blocklist { command } => {
+            let blocklist_repo = stores.blocklist_store();
+
+            match command {
+                BlocklistCommand::Add { channel, reason } => {
+                    let channel = parse_channel_argument(&channel)?;
+
+                    blocklist_repo.block(&channel, &reason).await?;
+                    stores
+                        .additional_channel_store()
+                        .delete_one(&channel)
+                        .await?;
+
+                    println!("Channel {} blocked", channel);
+                }
+                BlocklistCommand::Remove { channel } => {
+                    let channel = parse_channel_argument(&channel)?;
+
+                    if blocklist_repo.unblock(&channel).await? {
+                        println!("Channel {} unblocked", channel);
+                    } else {
+                        println!("Channel {} is not blocked", channel);
+                    }
+                }
+                BlocklistCommand::List => {
+                    for blocked in blocklist_repo.get_all().await? {
+                        println!(
+                            "{}: {}",
+                            blocked.get_str("_id")?,
+                            blocked.get_str("reason").unwrap_or_default()
+                        );
+                    }
+                }
+            }
+        }
+        CliCommand::Additional { command } => {
+            let additional_channel_repo = stores.additional_channel_store();
+
+            match command {
+                AdditionalCommand::List => {
+                    for additional_channel in additional_channel_repo.get_all().await? {
+                        println!(
+                            "{} (ignore guitar terms: {})",
+                            additional_channel.get_str("_id")?,
+                            additional_channel
+                                .get_bool("ignoreGuitarTerm")
+                                .unwrap_or(false)
+                        );
+                    }
+                }
+                AdditionalCommand::Remove { channel } => {
+                    additional_channel_repo.delete_one(&channel).await?;
+
+                    println!("Channel {} removed from the additional channels", channel);
+                }
+            }
+        }
+        CliCommand::Discovery {
+            command: DiscoveryCommand::RunOnce,
+        } => {
+            let mut tasks = vec![];
+            let (channel_scraper_tx, channel_scraper_rx) =
+                channel::<CrawlChannelCommand>(SCRAPER_QUEUE_CAPACITY);
+            register_channel_scraper(
+                &mut tasks,
+                stores.clone(),
+                config.clone(),
+                maintenance.clone(),
+                api_scheduler.clone(),
+                Box::new(LocalChannelCommandQueue::new(channel_scraper_rx)),
+            );
+
+            let crawler = build_channel_discovery_crawler(
+                &stores,
+                &config,
+                maintenance,
+                health,
+                api_scheduler,
+                channel_scraper_tx,
+            )
+            .await;
+
+            crawler.discover().await?;
+            drop(crawler);
+            await_all(tasks).await?;
+
+            println!("Channel discovery finished");
+        }
+        CliCommand::Discovery {
+            command: DiscoveryCommand::Report { days },
+        } => {
+            let provenance_repo = stores.discovery_provenance_store();
+
+            for report in provenance_repo.get_report(days).await? {
+                let units_per_accepted = report
+                    .api_units_per_accepted()
+                    .map(|units| format!("{:.1}", units))
+                    .unwrap_or_else(|| "-".to_string());
+
+                println!(
+                    "{}: {} channels, {} accepted ({:.1}%), {} rejected, {} failed, {} api units ({} per accepted channel)",
+                    report.source,
+                    report.channels,
+                    report.accepted,
+                    report.acceptance_rate() * 100.0,
+                    report.rejected,
+                    report.failed,
+                    report.api_units,
+                    units_per_accepted
+                );
+            }
+        }
+        CliCommand::Import {
+            command:
+                ImportCommand::Channels {
+                    file,
+                    source,
+                    ignore_guitar_terms,
+                },
+        } => {
+            let content = std::fs::read_to_string(&file)?;
+            let import = parse_channel_import(&content, file.ends_with(".json"))?;
+            let total = import.channels.len();
+
+            let mut tasks = vec![];
+            let (channel_scraper_tx, channel_scraper_rx) =
+                channel::<CrawlChannelCommand>(SCRAPER_QUEUE_CAPACITY);
+            register_channel_scraper(
+                &mut tasks,
+                stores,
+                config,
+                maintenance,
+                api_scheduler,
+                Box::new(LocalChannelCommandQueue::new(channel_scraper_rx)),
+            );
+
+            for (index, channel_id) in import.channels.into_iter().enumerate() {
+                channel_scraper_tx
+                    .send(CrawlChannelCommand::new(
+                        channel_id,
+                        ignore_guitar_terms,
+                        Some(source.clone()),
+                    ))
+                    .await?;
+
+                if (index + 1) % IMPORT_PROGRESS_INTERVAL == 0 {
+                    println!("Queued {}/{} channels", index + 1, total);
+                }
+            }
+            drop(channel_scraper_tx);
+            await_all(tasks).await?;
+
+            println!(
+                "Imported {} channels, skipped {} duplicates and {} invalid entries",
+                total,
+                import.duplicates.len(),
+                import.invalid.len()
+            );
+
+            for entry in import.invalid {
+                println!("  invalid: {}", entry);
+            }
+        }
+        CliCommand::Export { command } => {
+            let exporter = Exporter::new(stores.channel_store(), stores.video_store());
+
+            // The count goes to stderr so it does not end up in exports written to stdout
+            match command {
+                ExportCommand::Channels {
+                    options,
+                    min_subscribers,
+                } => {
+                    let mut writer = get_export_writer(&options)?;
+                    let count = exporter
+                        .export_channels(
+                            &mut writer,
+                            options.format,
+                            &options.fields,
+                            min_subscribers,
+                        )
+                        .await?;
+
+                    eprintln!("Exported {} channels", count);
+                }
+                ExportCommand::Videos {
+                    options,
+                    channel_id,
+                } => {
+                    let mut writer = get_export_writer(&options)?;
+                    let count = exporter
+                        .export_videos(
+                            &mut writer,
+                            options.format,
+                            &options.fields,
+                            channel_id.as_deref(),
+                        )
+                        .await?;
+
+                    eprintln!("Exported {} videos", count);
+                }
+            }
+        }
+        CliCommand::Stats => {
+            let channel_repo = stores.channel_store();
+            let video_repo = stores.video_store();
+
+            for (field, default_key) in [("status", "active"), ("language", "unknown")] {
+                println!("Channels by {}:", field);
+
+                for (key, count) in channel_repo.count_grouped_by(field, default_key).await? {
+                    println!("  {}: {}", key, count);
+                }
+            }
+
+            println!("Videos: {}", video_repo.count_all().await?);
+        }
+        CliCommand::Migrate { dry_run } => {
+            let runner = MigrationRunner::new(&stores);
+
+            let migrations = if dry_run {
+                runner.get_pending().await?
+            } else {
+                runner.apply_pending().await?
+            };
+
+            if migrations.is_empty() {
+                println!("No pending migrations");
+            }
+
+            for migration in migrations {
+                let state = if dry_run { "pending" } else { "applied" };
+                println!("{} {} {}", migration.version(), migration.name(), state);
+            }
+        }
+        CliCommand::Purge {
+            command:
+                PurgeCommand::Channel {
+                    channel_id,
+                    dry_run,
+                },
+        } => {
+            let channel_purge_service = get_channel_purge_service(&stores);
+
+            let counts = if dry_run {
+                channel_purge_service.preview(&channel_id).await?
+            } else {
+                let purge = channel_purge_service.purge(&channel_id).await?;
+                println!("Audit entries kept as {}", purge.anonymized_id);
+                purge.counts
+            };
+
+            let state = if dry_run { "affected" } else { "purged" };
+            for (collection, count) in counts {
+                println!("{}: {} {}", collection, count, state);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn get_channel_purge_service(stores: &StoreFactory) -> ChannelPurgeService {
+    ChannelPurgeService::new(
+        stores.channel_store(),
+        stores.video_store(),
+        stores.purge_store(),
+        stores.channel_audit_store(),
+    )
+}
+
+fn parse_channel_argument(channel: &str) -> Result<String, anyhow::Error> {
+    match parse_youtube_url(channel) {
+        Some(YoutubeResource::Channel(channel_id)) => Ok(channel_id),
+        Some(YoutubeResource::Handle(handle)) => Ok(handle),
+        _ => Err(anyhow::anyhow!(
+            "{} is not a channel id, handle or url",
+            channel
+        )),
+    }
+}
+
+fn get_export_writer(options: &ExportOptions) -> Result<BufWriter<Box<dyn Write>>, anyhow::Error> {
+    let writer: Box<dyn Write> = match &options.output {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(std::io::stdout()),
+    };
+
+    Ok(BufWriter::new(writer))
+}
+
+fn get_maintenance_reason(config: &Config) -> Option<String> {
+    if !config.maintenance.enabled {
+        return None;
+    }
+
+    info!(
+        "Starting in maintenance mode: {}",
+        config.maintenance.reason
+    );
+
+    Some(config.maintenance.reason.clone())
+}
+
+/// Only the mongodb storage backend needs MongoDB. Its client reports command latencies, and
+/// the pool usage when backpressure is enabled.
+async fn connect_mongodb(
+    config: &Config,
+    metrics: &Arc<MetricsRegistry>,
+    maintenance: &Arc<Maintenance>,
+) -> Result<Option<Client>, anyhow::Error> {
+    if config.storage.backend != STORAGE_BACKEND_MONGODB {
+        return Ok(None);
+    }
+
+    info!("Start connection to mongodb");
+
+    let mut opts = ClientOptions::parse(&config.mongo_connection_string).await?;
+    let db_load_monitor = if config.monitoring.backpressure {
+        let db_load_monitor = Arc::new(DbLoadMonitor::new(
+            maintenance.clone(),
+            metrics.clone(),
+            Duration::from_millis(config.monitoring.overload_latency_millis),
+            config.monitoring.overload_pool_usage,
+            opts.max_pool_size.unwrap_or(DEFAULT_MONGODB_MAX_POOL_SIZE),
+        ));
+        opts.cmap_event_handler = Some(db_load_monitor.clone());
+        Some(db_load_monitor)
+    } else {
+        None
+    };
+    opts.command_event_handler = Some(Arc::new(MongoCommandMonitor::new(
+        metrics.clone(),
+        Duration::from_millis(config.monitoring.slow_query_millis),
+        db_load_monitor,
+    )));
+    let client = Client::with_options(opts)?;
+
+    info!("Connected to mongodb");
+
+    Ok(Some(client))
+}
+
+async fn register_api_keys(stores: &StoreFactory, config: &Config) -> Result<(), anyhow::Error> {
+    let apikey_repo = stores.apikey_store();
+
+    for api_key in config.api_keys.iter() {
+        apikey_repo
+            .upsert(api_key, DEFAULT_API_KEY_DAILY_QUOTA)
+            .await?;
+    }
+
+    if !config.api_keys.is_empty() {
+        info!("Registered {} api keys from config", config.api_keys.len());
+    }
+
+    Ok(())
+}
+
+fn get_event_publisher(config: &Config) -> Result<Option<Arc<dyn EventPublisher>>, anyhow::Error> {
+    if !config.events.enabled {
+        return Ok(None);
+    }
+
+    info!(
+        "Publishing entity events to kafka topic {}",
+        config.events.topic
+    );
+
+    let publisher = KafkaPublisher::new(&config.events.kafka_brokers, config.events.topic.clone())?;
+
+    Ok(Some(Arc::new(publisher)))
+}
+
+/// With the gRPC API enabled, entity events are also broadcast to its event streams.
+#[allow(clippy::type_complexity)]
+fn get_daemon_event_publisher(
+    config: &Config,
+) -> Result<
+    (
+        Option<Arc<dyn EventPublisher>>,
+        Option<Arc<BroadcastPublisher>>,
+    ),
+    anyhow::Error,
+> {
+    let event_publisher = get_event_publisher(config)?;
+
+    if !config.grpc.enabled {
+        return Ok((event_publisher, None));
+    }
+
+    let event_broadcast = Arc::new(BroadcastPublisher::new(
+        config.grpc.event_buffer,
+        event_publisher,
+    ));
+
+    Ok((Some(event_broadcast.clone()), Some(event_broadcast)))
+}
+
+async fn await_all(tasks: Vec<JoinHandle<()>>) -> Result<(), anyhow::Error> {
+    for task in tasks {
+        task.await?;
+    }
+
+    Ok(())
+}
+
+fn register_additional_channel_crawler(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+    tx: Sender<CrawlChannelCommand>,
+) {
+    if !config.crawler.additional {
+        return;
+    }
+
+    let additional_channel_crawling_task = task::spawn(async move {
+        let additional_channel_repo = stores.additional_channel_store();
+        let lock_repo = stores.lock_store();
+        let crawler = AdditionalChannelCrawler::new(
+            tx,
+            additional_channel_repo,
+            maintenance,
+            lock_repo,
+            config.intervals.additional,
+            health,
+        );
+
+        info!("CRAWLER: Start additional channel crawling");
+        crawler
+            .crawl()
+            .await
+            .expect("Panic in additional channel crawling");
+    });
+
+    tasks.push(additional_channel_crawling_task);
+}
+
+fn register_change_stream_publisher(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+) {
+    if !config.change_stream.enabled {
+        return;
+    }
+
+    let change_stream_task = task::spawn(async move {
+        let sink = match get_change_sink(&config).await {
+            Ok(sink) => sink,
+            Err(e) => {
+                error!("Failed to create the change stream sink: {}", e);
+                return;
+            }
+        };
+        info!(
+            "EVENTS: Start change stream publisher to {} {}",
+            config.change_stream.sink, config.change_stream.topic
+        );
+
+        if let Some(postgres_client) = stores.postgres_client() {
+            PostgresChangeStreamPublisher::new(postgres_client, sink, maintenance)
+                .run()
+                .await;
+        } else if let Some(mongo_client) = stores.mongo_client() {
+            ChangeStreamPublisher::new(
+                mongo_client.clone(),
+                config.environment.clone(),
+                SettingsRepository::new(&mongo_client, &config.environment),
+                sink,
+                maintenance,
+            )
+            .run()
+            .await;
+        }
+    });
+
+    tasks.push(change_stream_task);
+}
+
+async fn get_change_sink(config: &Config) -> Result<Arc<dyn ChangeSink>, anyhow::Error> {
+    let topic = config.change_stream.topic.clone();
+
+    if config.change_stream.sink == CHANGE_SINK_NATS {
+        let client = async_nats::connect(&config.change_stream.nats_url).await?;
+
+        return Ok(Arc::new(NatsChangeSink::new(client, topic)));
+    }
+
+    Ok(Arc::new(KafkaPublisher::new(
+        &config.change_stream.kafka_brokers,
+        topic,
+    )?))
+}
+
+fn register_crawl_request_listener(
+    tasks: &mut Vec<JoinHandle<()>>,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    crawl_request_service: Arc<CrawlRequestService>,
+) {
+    if !config.ingest.enabled {
+        return;
+    }
+
+    let crawl_request_task = task::spawn(async move {
+        let client = match async_nats::connect(&config.ingest.nats_url).await {
+            Ok(client) => client,
+            Err(e) => {
+                error!(
+                    "Failed to connect to nats {}: {}",
+                    config.ingest.nats_url, e
+                );
+                return;
+            }
+        };
+        let listener = CrawlRequestListener::new(
+            client,
+            config.ingest.subject.clone(),
+            config.ingest.queue_group.clone(),
+            crawl_request_service,
+            maintenance,
+        );
+
+        info!("CRAWLER: Start crawl request listener");
+        let result = listener.listen().await;
+
+        if let Err(e) = result {
+            error!("Error in crawl request listener: {}", e);
+        }
+    });
+
+    tasks.push(crawl_request_task);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn register_channel_discovery_crawler(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+    api_scheduler: Arc<ApiScheduler>,
+    tx: Sender<CrawlChannelCommand>,
+) {
+    if !config.crawler.discovery {
+        return;
+    }
+
+    let channel_discovery_crawling_task = task::spawn(async move {
+        let crawler = build_channel_discovery_crawler(
+            &stores,
+            &config,
+            maintenance,
+            health,
+            api_scheduler,
+            tx,
+        )
+        .await;
+
+        info!("CRAWLER: Start channel discovery crawling");
+        crawler
+            .crawl()
+            .await
+            .expect("Panic in channel discovery crawling");
+    });
+
+    tasks.push(channel_discovery_crawling_task);
+}
+
+async fn build_channel_discovery_crawler(
+    stores: &StoreFactory,
+    config: &Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+    api_scheduler: Arc<ApiScheduler>,
+    tx: Sender<CrawlChannelCommand>,
+) -> ChannelDiscoveryCrawler {
+    let guitar_terms = get_guitar_terms(stores).await;
+    let blacklisted_channel_ids = get_blacklisted_channels(stores).await;
+
+    let channel_repo = stores.channel_store();
+    let settings_repo = stores.settings_store();
+    let apikey_repo = stores.apikey_store();
+    let non_guitar_channel_repo = stores.non_guitar_channel_store();
+    let additional_channel_repo = stores.additional_channel_store();
+    let review_queue_repo = stores.review_queue_store();
+
+    let quota_settings_repo = stores.quota_breaker_store();
+    let youtube_service = YoutubeService::new(
+        apikey_repo,
+        quota_settings_repo,
+        api_scheduler,
+        ApiCaller::new("channelDiscoveryCrawler", ApiPriority::Discovery),
+        config.youtube.api_base_url.clone(),
+        get_notification_service(config, stores),
+        stores.response_cache(),
+    );
+    let guitar_terms_service = GuitarTermsService::new(
+        guitar_terms,
+        blacklisted_channel_ids,
+        non_guitar_channel_repo,
+    );
+
+    let lock_repo = stores.lock_store();
+
+    ChannelDiscoveryCrawler::new(
+        tx,
+        channel_repo,
+        settings_repo,
+        youtube_service,
+        guitar_terms_service,
+        get_collaboration_service(stores),
+        additional_channel_repo,
+        review_queue_repo,
+        stores.blocklist_store(),
+        stores.opt_out_store(),
+        stores.policy_rejection_store(),
+        stores.crawl_audit_store(),
+        CrawlBudget::new(config.budgets.discovery.clone()),
+        maintenance,
+        lock_repo,
+        config.intervals.discovery,
+        health,
+    )
+}
+
+fn register_channel_update_crawler(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+    tx: Sender<CrawlChannelCommand>,
+) {
+    if !config.crawler.channel {
+        return;
+    }
+
+    let channel_update_crawling_task = task::spawn(async move {
+        let channel_repo = stores.channel_store();
+        let lock_repo = stores.lock_store();
+        let crawler = ChannelUpdateCrawler::new(
+            tx,
+            channel_repo,
+            stores.channel_popularity_store(),
+            maintenance,
+            lock_repo,
+            config.intervals.channel_update,
+            health,
+        );
+
+        info!("CRAWLER: Start channel update crawling");
+        let result = crawler.crawl().await;
+
+        if let Err(e) = result {
+            error!("Error in channel update crawling: {}", e);
+        }
+    });
+
+    tasks.push(channel_update_crawling_task);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn register_new_video_crawler(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+    budget: Arc<CrawlBudget>,
+    tx: Sender<CrawlVideosCommand>,
+) {
+    if !config.crawler.video {
+        return;
+    }
+
+    let new_video_crawling_task = task::spawn(async move {
+        let channel_repo = stores.channel_store();
+        let lock_repo = stores.lock_store();
+        let crawler = NewVideoCrawler::new(
+            tx,
+            channel_repo,
+            stores.channel_popularity_store(),
+            maintenance,
+            lock_repo,
+            config.intervals.new_video,
+            budget,
+            health,
+        );
+
+        info!("CRAWLER: Start new video crawling");
+        let result = crawler.crawl().await;
+
+        if let Err(e) = result {
+            error!("Error in new video crawling: {}", e);
+        }
+    });
+
+    tasks.push(new_video_crawling_task);
+}
+
+fn register_channel_backfill_crawler(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+    api_scheduler: Arc<ApiScheduler>,
+) {
+    if !config.crawler.backfill {
+        return;
+    }
+
+    let channel_backfill_crawling_task = task::spawn(async move {
+        let crawler =
+            build_channel_backfill_crawler(&stores, &config, maintenance, health, api_scheduler);
+
+        info!("CRAWLER: Start channel backfill crawling");
+        let result = crawler.crawl().await;
+
+        if let Err(e) = result {
+            error!("Error in channel backfill crawling: {}", e);
+        }
+    });
+
+    tasks.push(channel_backfill_crawling_task);
+}
+
+fn build_channel_backfill_crawler(
+    stores: &StoreFactory,
+    config: &Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+    api_scheduler: Arc<ApiScheduler>,
+) -> ChannelBackfillCrawler {
+    let channel_repo = stores.channel_store();
+    let video_repo = stores.video_store();
+    let backfill_repo = stores.backfill_store();
+    let apikey_repo = stores.apikey_store();
+    let quota_settings_repo = stores.quota_breaker_store();
+    let youtube_service = YoutubeService::new(
+        apikey_repo,
+        quota_settings_repo,
+        api_scheduler,
+        ApiCaller::new("channelBackfillCrawler", ApiPriority::Discovery),
+        config.youtube.api_base_url.clone(),
+        get_notification_service(config, stores),
+        stores.response_cache(),
+    );
+
+    let lock_repo = stores.lock_store();
+
+    ChannelBackfillCrawler::new(
+        channel_repo,
+        video_repo,
+        backfill_repo,
+        stores.settings_store(),
+        youtube_service,
+        get_song_recognition_service(config),
+        get_gear_extraction_service(stores),
+        get_video_type_classifier(config),
+        maintenance,
+        lock_repo,
+        config.intervals.backfill,
+        health,
+        get_probation_repo(stores, config),
+        get_url_resolver_service(stores, config),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn register_about_scraper(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    api_scheduler: Arc<ApiScheduler>,
+    feed_throttle: Arc<Throttle>,
+    mut rx: Receiver<CrawlAboutCommand>,
+) {
+    let about_scraper_task = task::spawn(async move {
+        info!("SCRAPER: Start about scrape listener");
+
+        let channel_repo = stores.channel_store();
+        let apikey_repo = stores.apikey_store();
+        let quota_settings_repo = stores.quota_breaker_store();
+        let youtube_service = YoutubeService::new(
+            apikey_repo,
+            quota_settings_repo,
+            api_scheduler,
+            ApiCaller::new("aboutScraper", ApiPriority::Freshness),
+            config.youtube.api_base_url.clone(),
+            get_notification_service(&config, &stores),
+            stores.response_cache(),
+        );
+
+        let scraper = AboutScraper::new(
+            channel_repo,
+            youtube_service,
+            feed_throttle,
+            config.youtube.channel_page_base_url.clone(),
+            config.strict_compliance,
+        );
+
+        while let Some(cmd) = rx.recv().await {
+            maintenance.checkpoint("about scraper").await;
+
+            let result = scraper.scrape(cmd.channel_id).await;
+
+            if let Err(e) = result {
+                error!("Error in about scraper: {}", e);
+            }
+        }
+    });
+
+    tasks.push(about_scraper_task);
+}
+
+fn register_about_crawler(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+    tx: Sender<CrawlAboutCommand>,
+) {
+    if !config.crawler.about {
+        return;
+    }
+
+    let about_crawling_task = task::spawn(async move {
+        let channel_repo = stores.channel_store();
+        let lock_repo = stores.lock_store();
+        let crawler = AboutCrawler::new(
+            tx,
+            channel_repo,
+            maintenance,
+            lock_repo,
+            config.intervals.about,
+            health,
+        );
+
+        info!("CRAWLER: Start about crawling");
+        let result = crawler.crawl().await;
+
+        if let Err(e) = result {
+            error!("Error in about crawling: {}", e);
+        }
+    });
+
+    tasks.push(about_crawling_task);
+}
+
+fn register_caption_crawler(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+    tx: Sender<CrawlCaptionsCommand>,
+) {
+    if !config.crawler.captions {
+        return;
+    }
+
+    if config.strict_compliance {
+        info!("CRAWLER: Caption crawling is disabled in strict compliance mode");
+        return;
+    }
+
+    let caption_crawling_task = task::spawn(async move {
+        let video_repo = stores.video_store();
+        let lock_repo = stores.lock_store();
+        let crawler = CaptionCrawler::new(
+            tx,
+            video_repo,
+            get_probation_repo(&stores, &config),
+            maintenance,
+            lock_repo,
+            config.intervals.captions,
+            health,
+        );
+
+        info!("CRAWLER: Start caption crawling");
+        let result = crawler.crawl().await;
+
+        if let Err(e) = result {
+            error!("Error in caption crawling: {}", e);
+        }
+    });
+
+    tasks.push(caption_crawling_task);
+}
+
+fn register_corpus_snapshot_job(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+) {
+    let corpus_snapshot_task = task::spawn(async move {
+        let channel_repo = stores.channel_store();
+        let video_repo = stores.video_store();
+        let corpus_snapshot_repo = stores.corpus_snapshot_store();
+        let lock_repo = stores.lock_store();
+        let job = CorpusSnapshotJob::new(
+            channel_repo,
+            video_repo,
+            corpus_snapshot_repo,
+            maintenance,
+            lock_repo,
+            config.intervals.corpus_snapshot,
+            health,
+        );
+
+        info!("JOB: Start corpus snapshot job");
+        let result = job.run().await;
+
+        if let Err(e) = result {
+            error!("Error in corpus snapshot job: {}", e);
+        }
+    });
+
+    tasks.push(corpus_snapshot_task);
+}
+
+fn register_video_archive_job(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+) {
+    if !config.crawler.archive {
+        return;
+    }
+
+    let video_archive_task = task::spawn(async move {
+        let channel_repo = stores.channel_store();
+        let video_repo = stores.video_store();
+        let lock_repo = stores.lock_store();
+        let job = VideoArchiveJob::new(
+            channel_repo,
+            video_repo,
+            maintenance,
+            lock_repo,
+            config.intervals.video_archive,
+            health,
+        );
+
+        info!("JOB: Start video archive job");
+        let result = job.run().await;
+
+        if let Err(e) = result {
+            error!("Error in video archive job: {}", e);
+        }
+    });
+
+    tasks.push(video_archive_task);
+}
+
+fn register_reclassification_job(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+    api_scheduler: Arc<ApiScheduler>,
+) {
+    if !config.crawler.reclassification {
+        return;
+    }
+
+    let reclassification_task = task::spawn(async move {
+        let guitar_terms = get_guitar_terms(&stores).await;
+        let blacklisted_channel_ids = get_blacklisted_channels(&stores).await;
+
+        let channel_repo = stores.channel_store();
+        let channel_audit_repo = stores.channel_audit_store();
+        let apikey_repo = stores.apikey_store();
+        let quota_settings_repo = stores.quota_breaker_store();
+        let youtube_service = YoutubeService::new(
+            apikey_repo,
+            quota_settings_repo,
+            api_scheduler,
+            ApiCaller::new("reclassificationJob", ApiPriority::Discovery),
+            config.youtube.api_base_url.clone(),
+            get_notification_service(&config, &stores),
+            stores.response_cache(),
+        );
+        let non_guitar_channel_repo = stores.non_guitar_channel_store();
+        let guitar_terms_service = GuitarTermsService::new(
+            guitar_terms,
+            blacklisted_channel_ids,
+            non_guitar_channel_repo,
+        );
+        let tag_analytics_service =
+            TagAnalyticsService::new(stores.video_store(), stores.tag_profile_store());
+        let lock_repo = stores.lock_store();
+        let job = ReclassificationJob::new(
+            channel_repo,
+            channel_audit_repo,
+            stores.additional_channel_store(),
+            youtube_service,
+            guitar_terms_service,
+            tag_analytics_service,
+            maintenance,
+            lock_repo,
+            config.intervals.reclassification,
+            health,
+        );
+
+        info!("JOB: Start reclassification job");
+        let result = job.run().await;
+
+        if let Err(e) = result {
+            error!("Error in reclassification job: {}", e);
+        }
+    });
+
+    tasks.push(reclassification_task);
+}
+
+fn register_course_detection_job(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+    api_scheduler: Arc<ApiScheduler>,
+) {
+    if !config.crawler.courses {
+        return;
+    }
+
+    let course_detection_task = task::spawn(async move {
+        let youtube_service = YoutubeService::new(
+            stores.apikey_store(),
+            stores.quota_breaker_store(),
+            api_scheduler,
+            ApiCaller::new("courseDetectionJob", ApiPriority::Discovery),
+            config.youtube.api_base_url.clone(),
+            get_notification_service(&config, &stores),
+            stores.response_cache(),
+        );
+        let job = CourseDetectionJob::new(
+            stores.channel_store(),
+            stores.course_store(),
+            youtube_service,
+            maintenance,
+            stores.lock_store(),
+            config.intervals.courses,
+            health,
+        );
+
+        info!("JOB: Start course detection job");
+        let result = job.run().await;
+
+        if let Err(e) = result {
+            error!("Error in course detection job: {}", e);
+        }
+    });
+
+    tasks.push(course_detection_task);
+}
+
+fn register_probation_job(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+) {
+    if !config.probation.enabled {
+        return;
+    }
+
+    let probation_task = task::spawn(async move {
+        let guitar_terms = get_guitar_terms(&stores).await;
+        let blacklisted_channel_ids = get_blacklisted_channels(&stores).await;
+
+        let guitar_terms_service = GuitarTermsService::new(
+            guitar_terms,
+            blacklisted_channel_ids,
+            stores.non_guitar_channel_store(),
+        );
+        let job = ProbationJob::new(
+            stores.channel_store(),
+            stores.video_store(),
+            stores.channel_probation_store(),
+            stores.channel_audit_store(),
+            guitar_terms_service,
+            config.probation.days,
+            maintenance,
+            stores.lock_store(),
+            config.intervals.probation,
+            health,
+        );
+
+        info!("JOB: Start probation job");
+        let result = job.run().await;
+
+        if let Err(e) = result {
+            error!("Error in probation job: {}", e);
+        }
+    });
+
+    tasks.push(probation_task);
+}
+
+fn register_reconciliation_job(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+    api_scheduler: Arc<ApiScheduler>,
+) {
+    if !config.crawler.reconciliation {
+        return;
+    }
+
+    let reconciliation_task = task::spawn(async move {
+        let video_repo = stores.video_store();
+        let report_repo = stores.reconciliation_report_store();
+        let apikey_repo = stores.apikey_store();
+        let quota_settings_repo = stores.quota_breaker_store();
+        let youtube_service = YoutubeService::new(
+            apikey_repo,
+            quota_settings_repo,
+            api_scheduler,
+            ApiCaller::new("reconciliationJob", ApiPriority::Freshness),
+            config.youtube.api_base_url.clone(),
+            get_notification_service(&config, &stores),
+            stores.response_cache(),
+        );
+        let lock_repo = stores.lock_store();
+        let job = ReconciliationJob::new(
+            video_repo,
+            report_repo,
+            youtube_service,
+            maintenance,
+            lock_repo,
+            config.intervals.reconciliation,
+            health,
+        );
+
+        info!("JOB: Start reconciliation job");
+        let result = job.run().await;
+
+        if let Err(e) = result {
+            error!("Error in reconciliation job: {}", e);
+        }
+    });
+
+    tasks.push(reconciliation_task);
+}
+
+fn register_related_channels_job(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+    tx: Sender<CrawlChannelCommand>,
+) {
+    if !config.crawler.related_channels {
+        return;
+    }
+
+    let related_channels_task = task::spawn(async move {
+        let job = RelatedChannelsJob::new(
+            stores.channel_store(),
+            stores.comment_store(),
+            stores.related_channel_store(),
+            stores.non_guitar_channel_store(),
+            stores.blocklist_store(),
+            stores.opt_out_store(),
+            tx,
+            maintenance,
+            stores.lock_store(),
+            config.intervals.related_channels,
+            health,
+        );
+
+        info!("JOB: Start related channels job");
+        let result = job.run().await;
+
+        if let Err(e) = result {
+            error!("Error in related channels job: {}", e);
+        }
+    });
+
+    tasks.push(related_channels_task);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn register_end_screen_discovery_job(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+    page_throttle: Arc<Throttle>,
+    tx: Sender<CrawlChannelCommand>,
+    api_scheduler: Arc<ApiScheduler>,
+) {
+    if !config.crawler.end_screens {
+        return;
+    }
+
+    if config.strict_compliance {
+        info!("CRAWLER: End screen discovery is disabled in strict compliance mode");
+        return;
+    }
+
+    let end_screen_task = task::spawn(async move {
+        let youtube_service = YoutubeService::new(
+            stores.apikey_store(),
+            stores.quota_breaker_store(),
+            api_scheduler,
+            ApiCaller::new("endScreenDiscoveryJob", ApiPriority::Discovery),
+            config.youtube.api_base_url.clone(),
+            get_notification_service(&config, &stores),
+            stores.response_cache(),
+        );
+        let job = EndScreenDiscoveryJob::new(
+            stores.channel_store(),
+            stores.video_store(),
+            stores.end_screen_scan_store(),
+            stores.non_guitar_channel_store(),
+            stores.blocklist_store(),
+            stores.opt_out_store(),
+            youtube_service,
+            tx,
+            page_throttle,
+            config.youtube.watch_page_base_url.clone(),
+            maintenance,
+            stores.lock_store(),
+            config.intervals.end_screens,
+            health,
+        );
+
+        info!("JOB: Start end screen discovery job");
+        let result = job.run().await;
+
+        if let Err(e) = result {
+            error!("Error in end screen discovery job: {}", e);
+        }
+    });
+
+    tasks.push(end_screen_task);
+}
+
+fn register_channel_lifecycle_job(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+) {
+    if !config.crawler.lifecycle {
+        return;
+    }
+
+    let channel_lifecycle_task = task::spawn(async move {
+        let job = ChannelLifecycleJob::new(
+            stores.channel_store(),
+            maintenance,
+            stores.lock_store(),
+            config.intervals.lifecycle,
+            health,
+        );
+
+        info!("JOB: Start channel lifecycle job");
+        let result = job.run().await;
+
+        if let Err(e) = result {
+            error!("Error in channel lifecycle job: {}", e);
+        }
+    });
+
+    tasks.push(channel_lifecycle_task);
+}
+
+fn register_duplicate_detection_job(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+) {
+    if !config.crawler.duplicates {
+        return;
+    }
+
+    let duplicate_detection_task = task::spawn(async move {
+        let job = DuplicateDetectionJob::new(
+            stores.channel_store(),
+            get_channel_merge_service(&stores),
+            maintenance,
+            stores.lock_store(),
+            config.intervals.duplicates,
+            health,
+        );
+
+        info!("JOB: Start duplicate detection job");
+        let result = job.run().await;
+
+        if let Err(e) = result {
+            error!("Error in duplicate detection job: {}", e);
+        }
+    });
+
+    tasks.push(duplicate_detection_task);
+}
+
+fn register_topic_drift_job(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+) {
+    if !config.crawler.topic_drift {
+        return;
+    }
+
+    let topic_drift_task = task::spawn(async move {
+        let guitar_terms = get_guitar_terms(&stores).await;
+        let job = TopicDriftJob::new(
+            stores.channel_store(),
+            stores.video_store(),
+            stores.review_queue_store(),
+            get_notification_service(&config, &stores),
+            guitar_terms,
+            maintenance,
+            stores.lock_store(),
+            config.intervals.topic_drift,
+            health,
+        );
+
+        info!("JOB: Start topic drift job");
+        let result = job.run().await;
+
+        if let Err(e) = result {
+            error!("Error in topic drift job: {}", e);
+        }
+    });
+
+    tasks.push(topic_drift_task);
+}
+
+fn register_ban_evasion_job(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+) {
+    if !config.crawler.ban_evasion {
+        return;
+    }
+
+    let ban_evasion_task = task::spawn(async move {
+        let job = BanEvasionJob::new(
+            stores.channel_store(),
+            stores.video_store(),
+            stores.blocklist_store(),
+            stores.review_queue_store(),
+            maintenance,
+            stores.lock_store(),
+            config.intervals.ban_evasion,
+            health,
+        );
+
+        info!("JOB: Start ban evasion job");
+        let result = job.run().await;
+
+        if let Err(e) = result {
+            error!("Error in ban evasion job: {}", e);
+        }
+    });
+
+    tasks.push(ban_evasion_task);
+}
+
+fn register_link_verification_job(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+) {
+    if !config.crawler.link_verification {
+        return;
+    }
+
+    let link_verification_task = task::spawn(async move {
+        let job = LinkVerificationJob::new(
+            stores.channel_store(),
+            maintenance,
+            stores.lock_store(),
+            config.intervals.link_verification,
+            health,
+        );
+
+        info!("JOB: Start link verification job");
+        let result = job.run().await;
+
+        if let Err(e) = result {
+            error!("Error in link verification job: {}", e);
+        }
+    });
+
+    tasks.push(link_verification_task);
+}
+
+fn register_upload_pattern_job(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+) {
+    if !config.crawler.upload_pattern {
+        return;
+    }
+
+    let upload_pattern_task = task::spawn(async move {
+        let job = UploadPatternJob::new(
+            stores.channel_store(),
+            stores.video_store(),
+            maintenance,
+            stores.lock_store(),
+            config.intervals.upload_pattern,
+            health,
+        );
+
+        info!("JOB: Start upload pattern job");
+        let result = job.run().await;
+
+        if let Err(e) = result {
+            error!("Error in upload pattern job: {}", e);
+        }
+    });
+
+    tasks.push(upload_pattern_task);
+}
+
+fn register_genre_tag_job(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+) {
+    if !config.crawler.genre_tags {
+        return;
+    }
+
+    let genre_tag_task = task::spawn(async move {
+        let job = GenreTagJob::new(
+            stores.channel_store(),
+            stores.video_store(),
+            get_genre_classifier(&config),
+            maintenance,
+            stores.lock_store(),
+            config.intervals.genre_tags,
+            health,
+        );
+
+        info!("JOB: Start genre tag job");
+        let result = job.run().await;
+
+        if let Err(e) = result {
+            error!("Error in genre tag job: {}", e);
+        }
+    });
+
+    tasks.push(genre_tag_task);
+}
+
+fn register_reporting_job(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+) {
+    if !config.crawler.reporting {
+        return;
+    }
+
+    let reporting_task = task::spawn(async move {
+        let job = ReportingJob::new(
+            stores.crawl_audit_store(),
+            stores.discovery_provenance_store(),
+            stores.digest_report_store(),
+            get_notification_service(&config, &stores),
+            maintenance,
+            stores.lock_store(),
+            config.intervals.reporting,
+            health,
+        );
+
+        info!("JOB: Start reporting job");
+        let result = job.run().await;
+
+        if let Err(e) = result {
+            error!("Error in reporting job: {}", e);
+        }
+    });
+
+    tasks.push(reporting_task);
+}
+
+fn register_alert_job(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+) {
+    if !config.crawler.alerts {
+        return;
+    }
+
+    let alert_task = task::spawn(async move {
+        let cooldown_seconds = config
+            .notifications
+            .email
+            .as_ref()
+            .map_or(config.intervals.alerts, |email| {
+                email.cooldown_seconds.max(0) as u64
+            });
+        let job = AlertJob::new(
+            stores.settings_store(),
+            stores.lock_store(),
+            get_notification_service(&config, &stores),
+            maintenance,
+            config.intervals.alerts,
+            cooldown_seconds,
+            health,
+        );
+
+        info!("JOB: Start alert job");
+        let result = job.run().await;
+
+        if let Err(e) = result {
+            error!("Error in alert job: {}", e);
+        }
+    });
+
+    tasks.push(alert_task);
+}
+
+fn register_metadata_refresh_job(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+    api_scheduler: Arc<ApiScheduler>,
+) {
+    if !config.crawler.metadata_refresh {
+        return;
+    }
+
+    let metadata_refresh_task = task::spawn(async move {
+        let apikey_repo = stores.apikey_store();
+        let quota_settings_repo = stores.quota_breaker_store();
+        let youtube_service = YoutubeService::new(
+            apikey_repo,
+            quota_settings_repo,
+            api_scheduler,
+            ApiCaller::new("channelMetadataRefreshJob", ApiPriority::Freshness),
+            config.youtube.api_base_url.clone(),
+            get_notification_service(&config, &stores),
+            stores.response_cache(),
+        );
+        let job = ChannelMetadataRefreshJob::new(
+            stores.channel_store(),
+            youtube_service,
+            maintenance,
+            stores.lock_store(),
+            config.intervals.metadata_refresh,
+            health,
+        );
+
+        info!("JOB: Start channel metadata refresh job");
+        let result = job.run().await;
+
+        if let Err(e) = result {
+            error!("Error in channel metadata refresh job: {}", e);
+        }
+    });
+
+    tasks.push(metadata_refresh_task);
+}
+
+fn register_stats_aggregation_job(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+) {
+    if !config.crawler.stats_aggregation {
+        return;
+    }
+
+    let stats_aggregation_task = task::spawn(async move {
+        let job = StatsAggregationJob::new(
+            stores.channel_store(),
+            stores.video_store(),
+            stores.site_stats_store(),
+            maintenance,
+            stores.lock_store(),
+            config.intervals.stats_aggregation,
+            health,
+        );
+
+        info!("JOB: Start stats aggregation job");
+        let result = job.run().await;
+
+        if let Err(e) = result {
+            error!("Error in stats aggregation job: {}", e);
+        }
+    });
+
+    tasks.push(stats_aggregation_task);
+}
+
+fn register_comment_ingestion_job(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+    api_scheduler: Arc<ApiScheduler>,
+) {
+    if !config.crawler.comments {
+        return;
+    }
+
+    let comment_ingestion_task = task::spawn(async move {
+        let youtube_service = YoutubeService::new(
+            stores.apikey_store(),
+            stores.quota_breaker_store(),
+            api_scheduler,
+            ApiCaller::new("commentIngestionJob", ApiPriority::Discovery),
+            config.youtube.api_base_url.clone(),
+            get_notification_service(&config, &stores),
+            stores.response_cache(),
+        );
+        let job = CommentIngestionJob::new(
+            stores.video_store(),
+            stores.comment_store(),
+            youtube_service,
+            maintenance,
+            stores.lock_store(),
+            config.intervals.comments,
+            health,
+        );
+
+        info!("JOB: Start comment ingestion job");
+        let result = job.run().await;
+
+        if let Err(e) = result {
+            error!("Error in comment ingestion job: {}", e);
+        }
+    });
+
+    tasks.push(comment_ingestion_task);
+}
+
+fn register_comment_sentiment_job(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+) {
+    if !config.crawler.comment_sentiment {
+        return;
+    }
+
+    let comment_sentiment_task = task::spawn(async move {
+        let job = CommentSentimentJob::new(
+            SentimentService::new(stores.comment_store(), stores.video_store()),
+            maintenance,
+            stores.lock_store(),
+            config.intervals.comment_sentiment,
+            health,
+        );
+
+        info!("JOB: Start comment sentiment job");
+        let result = job.run().await;
+
+        if let Err(e) = result {
+            error!("Error in comment sentiment job: {}", e);
+        }
+    });
+
+    tasks.push(comment_sentiment_task);
+}
+
+async fn register_replay_server(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+) -> Result<(), anyhow::Error> {
+    let server = ReplayServer::new(&config.replay.fixtures_dir);
+    let channel_ids = server.get_channel_ids()?;
+
+    info!(
+        "Replay mode enabled, replaying {} recorded channels from {}",
+        channel_ids.len(),
+        config.replay.fixtures_dir
+    );
+
+    seed_replay_database(&stores, &channel_ids).await?;
+
+    let port = config.replay.port;
+
+    tasks.push(task::spawn(async move {
+        if let Err(e) = server.serve(port).await {
+            error!("Replay server stopped: {}", e);
+        }
+    }));
+
+    Ok(())
+}
+
+async fn register_simulation_server(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+) -> Result<(), anyhow::Error> {
+    info!(
+        "Simulation mode enabled, generating {} channels with seed {}",
+        config.simulation.channels, config.simulation.seed
+    );
+
+    let corpus = SyntheticCorpus::generate(
+        config.simulation.seed,
+        config.simulation.channels,
+        config.simulation.videos_per_channel,
+    );
+
+    seed_simulation_database(&stores, &corpus).await?;
+
+    let server = SimulationServer::new(corpus);
+    let port = config.simulation.port;
+
+    tasks.push(task::spawn(async move {
+        if let Err(e) = server.serve(port).await {
+            error!("Simulation server stopped: {}", e);
+        }
+    }));
+
+    Ok(())
+}
+
+fn register_admin_api(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    health: Arc<Health>,
+    metrics: Arc<MetricsRegistry>,
+) {
+    if !config.admin_api.enabled {
+        return;
+    }
+
+    let admin_api_task = task::spawn(async move {
+        let channel_repo = stores.channel_store();
+        let video_repo = stores.video_store();
+        let review_queue_repo = stores.review_queue_store();
+        let additional_channel_repo = stores.additional_channel_store();
+        let non_guitar_channel_repo = stores.non_guitar_channel_store();
+
+        let tag_profile_repo = stores.tag_profile_store();
+        let settings_repo = stores.settings_store();
+        let crawl_audit_repo = stores.crawl_audit_store();
+        let site_stats_repo = stores.site_stats_store();
+        let opt_out_service = OptOutService::new(
+            stores.opt_out_store(),
+            stores.channel_store(),
+            get_channel_purge_service(&stores),
+            stores.additional_channel_store(),
+            stores.channel_audit_store(),
+            config.notifications.email.clone().map(SmtpClient::new),
+        );
+
+        let admin_api = AdminApi::new(
+            channel_repo,
+            video_repo,
+            review_queue_repo,
+            additional_channel_repo,
+            stores.blocklist_store(),
+            non_guitar_channel_repo,
+            tag_profile_repo,
+            settings_repo,
+            crawl_audit_repo,
+            site_stats_repo,
+            stores.digest_report_store(),
+            stores.channel_popularity_store(),
+            stores.apikey_store(),
+            opt_out_service,
+            maintenance,
+            health,
+            metrics,
+            config.admin_api.token.clone(),
+        );
+
+        info!("API: Start admin api");
+        let result = admin_api
+            .serve(&config.admin_api.host, config.admin_api.port)
+            .await;
+
+        if let Err(e) = result {
+            error!("Error in admin api: {}", e);
+        }
+    });
+
+    tasks.push(admin_api_task);
+}
+
+fn register_grpc_api(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    health: Arc<Health>,
+    crawl_request_service: Arc<CrawlRequestService>,
+    event_broadcast: Option<Arc<BroadcastPublisher>>,
+) {
+    let event_broadcast = match event_broadcast {
+        Some(event_broadcast) if config.grpc.enabled => event_broadcast,
+        _ => return,
+    };
+
+    let grpc_api_task = task::spawn(async move {
+        let grpc_api = GrpcApi::new(
+            stores.channel_store(),
+            crawl_request_service,
+            event_broadcast,
+            health,
+        );
+
+        info!("API: Start grpc api");
+        let result = grpc_api.serve(config.grpc.port).await;
+
+        if let Err(e) = result {
+            error!("Error in grpc api: {}", e);
+        }
+    });
+
+    tasks.push(grpc_api_task);
+}
+
+fn register_channel_scraper(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    api_scheduler: Arc<ApiScheduler>,
+    mut queue: Box<dyn ChannelCommandQueue>,
+) {
+    let channel_scraper_task = task::spawn(async move {
+        info!("SCRAPER: Start channel scrape listener");
+
+        let channel_repo = stores.channel_store();
+        let non_guitar_channel_repo = stores.non_guitar_channel_store();
+        let view_repo = stores.view_store();
+        let subscriber_repo = stores.subscriber_store();
+        let video_repo = stores.video_store();
+        let apikey_repo = stores.apikey_store();
+        let quota_settings_repo = stores.quota_breaker_store();
+        let notification_service = get_notification_service(&config, &stores);
+        let youtube_service = YoutubeService::new(
+            apikey_repo,
+            quota_settings_repo,
+            api_scheduler,
+            ApiCaller::new("channelScraper", ApiPriority::Freshness),
+            config.youtube.api_base_url.clone(),
+            notification_service.clone(),
+            stores.response_cache(),
+        );
+        let settings_repo = stores.settings_store();
+
+        let guitar_terms = get_guitar_terms(&stores).await;
+        let blacklisted_channel_ids = get_blacklisted_channels(&stores).await;
+
+        let guitar_terms_service = GuitarTermsService::new(
+            guitar_terms,
+            blacklisted_channel_ids,
+            non_guitar_channel_repo,
+        );
+
+        let channel_redirect_service = ChannelRedirectService::new(
+            stores.channel_store(),
+            stores.video_store(),
+            stores.additional_channel_store(),
+        );
+
+        let mut scraper = ChannelScraper::new(
+            channel_repo,
+            view_repo,
+            subscriber_repo,
+            video_repo,
+            youtube_service,
+            guitar_terms_service,
+            channel_redirect_service,
+            notification_service.clone(),
+            stores.blocklist_store(),
+            stores.opt_out_store(),
+            get_probation_repo(&stores, &config),
+            config
+                .crawler
+                .ban_evasion
+                .then(|| stores.review_queue_store()),
+        );
+        let crawl_audit_repo = stores.crawl_audit_store();
+        let provenance_repo = stores.discovery_provenance_store();
+        let opt_out_repo = stores.opt_out_store();
+
+        loop {
+            let queued = match queue.receive().await {
+                Ok(Some(queued)) => queued,
+                Ok(None) => break,
+                Err(e) => {
+                    error!("Failed to receive channel command: {}", e);
+                    sleep(Duration::from_secs(QUEUE_RETRY_SECONDS)).await;
+                    continue;
+                }
+            };
+            let cmd = &queued.command;
+
+            maintenance.checkpoint("channel scraper").await;
+
+            if !is_source_enabled(settings_repo.as_ref(), cmd.source.as_deref()).await {
+                info!(
+                    "Skip channel {}, its source {:?} is disabled",
+                    cmd.channel_id, cmd.source
+                );
+                ack_channel_command(queue.as_mut(), &queued).await;
+                continue;
+            }
+
+            match opt_out_repo.is_opted_out(&cmd.channel_id).await {
+                Ok(true) => {
+                    info!("Skip channel {}, it opted out", cmd.channel_id);
+                    ack_channel_command(queue.as_mut(), &queued).await;
+                    continue;
+                }
+                Ok(false) => {}
+                // Crawling an opted-out channel is worse than crawling it late
+                Err(e) => {
+                    error!(
+                        "Failed to check opt-out of channel {}: {}",
+                        cmd.channel_id, e
+                    );
+                    retry_channel_command(queue.as_mut(), queued).await;
+                    continue;
+                }
+            }
+
+            scraper.set_api_priority(get_source_priority(cmd.source.as_deref()));
+
+            let started_at = Utc::now();
+            let units_spent = scraper.api_units_spent();
+            let strategy = ExponentialBackoff::from_millis(CHANNEL_SCRAPE_RETRY_BASE_MILLIS)
+                .factor(CHANNEL_SCRAPE_RETRY_FACTOR)
+                .take(CHANNEL_SCRAPE_MAX_RETRIES);
+            let result = RetryIf::spawn(
+                strategy,
+                || {
+                    scraper.scrape(
+                        cmd.channel_id.clone(),
+                        cmd.ignore_guitar_terms,
+                        cmd.source.clone(),
+                        cmd.channel_details.clone(),
+                    )
+                },
+                CrawlerError::is_retryable,
+            )
+            .await;
+
+            let stats = CrawlStats {
+                api_units: (scraper.api_units_spent() - units_spent) as i64,
+                ..CrawlStats::default()
+            };
+            record_crawl_run(
+                crawl_audit_repo.as_ref(),
+                &cmd.channel_id,
+                CRAWL_KIND_CHANNEL,
+                started_at,
+                &stats,
+                result.as_ref().err(),
+            )
+            .await;
+
+            if let Some(source) = &cmd.source {
+                let outcome = match &result {
+                    Ok(true) => DISCOVERY_OUTCOME_ACCEPTED,
+                    Ok(false) => DISCOVERY_OUTCOME_REJECTED,
+                    Err(_) => DISCOVERY_OUTCOME_FAILED,
+                };
+
+                if let Err(e) = provenance_repo
+                    .record(&cmd.channel_id, source, outcome, stats.api_units)
+                    .await
+                {
+                    warn!(
+                        "Failed to record provenance of channel {}: {}",
+                        cmd.channel_id, e
+                    );
+                }
+            }
+
+            notification_service
+                .record_crawl_result("channel scraper", &result)
+                .await;
+
+            match &result {
+                Ok(_) => ack_channel_command(queue.as_mut(), &queued).await,
+                Err(e) if !e.is_retryable() => {
+                    warn!("Dropping channel {}: {}", cmd.channel_id, e);
+                    ack_channel_command(queue.as_mut(), &queued).await;
+                }
+                Err(e) => {
+                    error!("Error in channel scraping of {}: {}", cmd.channel_id, e);
+                    retry_channel_command(queue.as_mut(), queued).await;
+                }
+            }
+        }
+    });
+
+    tasks.push(channel_scraper_task);
+}
+
+/// The command was handled or dropped either way, a failed ack only means it may be scraped twice.
+async fn ack_channel_command(queue: &mut dyn ChannelCommandQueue, queued: &QueuedChannelCommand) {
+    if let Err(e) = queue.ack(&queued.id).await {
+        warn!(
+            "Failed to acknowledge command for channel {}: {}",
+            queued.command.channel_id, e
+        );
+    }
+}
+
+/// Commands that failed too often are dropped, the others queued again behind the waiting ones.
+async fn retry_channel_command(queue: &mut dyn ChannelCommandQueue, queued: QueuedChannelCommand) {
+    let channel_id = queued.command.channel_id.clone();
+
+    if queued.command.retries + 1 >= CHANNEL_COMMAND_MAX_ATTEMPTS {
+        warn!(
+            "Dropping channel {} after {} attempts",
+            channel_id, CHANNEL_COMMAND_MAX_ATTEMPTS
+        );
+        ack_channel_command(queue, &queued).await;
+        return;
+    }
+
+    info!("Queue channel {} again", channel_id);
+    // Left unacknowledged, the redis queue still hands the command out again once it idled
+    if let Err(e) = queue.retry(queued).await {
+        warn!("Failed to queue channel {} again: {}", channel_id, e);
+    }
+}
+
+/// Channel scrapers of the local backend read the crawlers of this instance directly. With the
+/// redis backend the crawlers' commands are forwarded to the shared stream instead.
+async fn get_channel_command_queue(
+    tasks: &mut Vec<JoinHandle<()>>,
+    config: &Config,
+    rx: Receiver<CrawlChannelCommand>,
+) -> Result<Box<dyn ChannelCommandQueue>, anyhow::Error> {
+    match config.queue.backend.as_str() {
+        QUEUE_BACKEND_REDIS => {
+            let redis_url = config
+                .queue
+                .redis_url
+                .as_ref()
+                .ok_or_else(|| anyhow::anyhow!("Redis queue needs a url"))?;
+            let queue = RedisChannelCommandQueue::connect(redis_url, &config.queue).await?;
+
+            tasks.push(queue.spawn_forwarder(rx));
+
+            Ok(Box::new(queue))
+        }
+        _ => Ok(Box::new(LocalChannelCommandQueue::new(rx))),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn register_video_scraper(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    api_scheduler: Arc<ApiScheduler>,
+    feed_throttle: Arc<Throttle>,
+    feed_rate_limiter: Arc<RateLimiter>,
+    feed_proxy_pool: Arc<ProxyPool>,
+    metrics: Arc<MetricsRegistry>,
+    scrape_budget: Arc<CrawlBudget>,
+    mut rx: Receiver<CrawlVideosCommand>,
+) {
+    let video_scraper_task = task::spawn(async move {
+        info!("SCRAPER: Start video scrape listener");
+
+        let video_repo = stores.video_store();
+        let channel_repo = stores.channel_store();
+        let apikey_repo = stores.apikey_store();
+        let quota_settings_repo = stores.quota_breaker_store();
+        let notification_service = get_notification_service(&config, &stores);
+        let youtube_service = YoutubeService::new(
+            apikey_repo,
+            quota_settings_repo,
+            api_scheduler,
+            ApiCaller::new("videoScraper", ApiPriority::Freshness),
+            config.youtube.api_base_url.clone(),
+            notification_service.clone(),
+            stores.response_cache(),
+        );
+        let settings_repo = stores.settings_store();
+        let tag_analytics_service =
+            TagAnalyticsService::new(stores.video_store(), stores.tag_profile_store());
+        let channel_redirect_service = ChannelRedirectService::new(
+            stores.channel_store(),
+            stores.video_store(),
+            stores.additional_channel_store(),
+        );
+        let scraper = VideoScraper::new(
+            video_repo,
+            channel_repo,
+            youtube_service,
+            tag_analytics_service,
+            stores.channel_summary_store(),
+            channel_redirect_service,
+            get_collaboration_service(&stores),
+            get_song_recognition_service(&config),
+            get_gear_extraction_service(&stores),
+            get_video_type_classifier(&config),
+            feed_throttle,
+            feed_rate_limiter,
+            feed_proxy_pool,
+            config.youtube.feed_base_url.clone(),
+            metrics,
+            config.scrape_policy.clone(),
+            get_url_resolver_service(&stores, &config),
+        );
+        let crawl_audit_repo = stores.crawl_audit_store();
+
+        while let Some(cmd) = rx.recv().await {
+            maintenance.checkpoint("video scraper").await;
+
+            if !is_feature_enabled(settings_repo.as_ref(), FEATURE_VIDEO_SCRAPE_ENABLED).await {
+                info!(
+                    "Skip videos of channel {}, video scraping is disabled",
+                    cmd.channel_id
+                );
+                continue;
+            }
+
+            // Skipped channels stay due, so the new video crawler sends them again next cycle
+            if let Some(reason) = scrape_budget.get_exhausted_reason() {
+                info!(
+                    "Skip videos of channel {}, the scrape budget is exhausted ({})",
+                    cmd.channel_id, reason
+                );
+                continue;
+            }
+
+            let started_at = Utc::now();
+            let result = scraper.scrape(cmd.channel_id.clone()).await;
+
+            let stats = match &result {
+                Ok(summary) => {
+                    info!(
+                        "Scraped videos of channel {}: {} updated, {} skipped, {} failed",
+                        cmd.channel_id,
+                        summary.updated,
+                        summary.skipped,
+                        summary.failed.len()
+                    );
+
+                    for (video_id, e) in &summary.failed {
+                        warn!("Failed to scrape video {}: {}", video_id, e);
+                    }
+
+                    CrawlStats::from(summary)
+                }
+                Err(e) => {
+                    error!("Error in video scraper: {}", e);
+                    CrawlStats::default()
+                }
+            };
+            scrape_budget.record(stats.api_units as u64, 1);
+
+            record_crawl_run(
+                crawl_audit_repo.as_ref(),
+                &cmd.channel_id,
+                CRAWL_KIND_VIDEOS,
+                started_at,
+                &stats,
+                result.as_ref().err(),
+            )
+            .await;
+
+            notification_service
+                .record_crawl_result("video scraper", &result)
+                .await;
+        }
+    });
 
     tasks.push(video_scraper_task);
 }
 
-async fn get_guitar_terms(mongo_client: &Client, environment: &str) -> Vec<String> {
-    let guitar_term_repo = GuitarTermRepository::new(&mongo_client, environment);
+/// A failing audit log should not stop the scrapers.
+async fn record_crawl_run(
+    crawl_audit_repo: &dyn CrawlAuditStore,
+    channel_id: &str,
+    kind: &str,
+    started_at: DateTime<Utc>,
+    stats: &CrawlStats,
+    error: Option<&CrawlerError>,
+) {
+    if let Err(e) = crawl_audit_repo
+        .insert(channel_id, kind, started_at, stats, error)
+        .await
+    {
+        warn!("Failed to record crawl of channel {}: {}", channel_id, e);
+    }
+}
+
+/// Keeps scraping when the flags cannot be read, a settings outage should not stop crawling.
+async fn is_source_enabled(settings_repo: &dyn SettingsStore, source: Option<&str>) -> bool {
+    match source.and_then(get_source_feature_flag) {
+        Some(flag) => is_feature_enabled(settings_repo, flag).await,
+        None => true,
+    }
+}
+
+fn get_collaboration_service(stores: &StoreFactory) -> CollaborationService {
+    CollaborationService::new(stores.collab_edge_store(), stores.channel_store())
+}
+
+fn get_gear_extraction_service(stores: &StoreFactory) -> GearExtractionService {
+    GearExtractionService::new(stores.video_store(), stores.channel_store())
+}
+
+/// The rules were validated with the config.
+fn get_song_recognition_service(config: &Config) -> Arc<SongRecognitionService> {
+    Arc::new(
+        SongRecognitionService::new(&config.song_recognition.rules)
+            .expect("Invalid song recognition rules"),
+    )
+}
+
+/// The classifier was validated with the config.
+fn get_video_type_classifier(config: &Config) -> Arc<dyn VideoTypeClassifier> {
+    build_video_type_classifier(&config.classification.video_type_classifier)
+        .expect("Invalid video type classifier")
+}
+
+/// The classifier was validated with the config.
+fn get_genre_classifier(config: &Config) -> Arc<dyn GenreClassifier> {
+    build_genre_classifier(&config.classification.genre_classifier)
+        .expect("Invalid genre classifier")
+}
+
+fn get_channel_merge_service(stores: &StoreFactory) -> ChannelMergeService {
+    ChannelMergeService::new(
+        stores.channel_store(),
+        stores.video_store(),
+        stores.view_store(),
+        stores.subscriber_store(),
+    )
+}
+
+fn get_notification_service(config: &Config, stores: &StoreFactory) -> Arc<NotificationService> {
+    Arc::new(NotificationService::new(
+        &config.notifications,
+        stores.event_publisher(),
+    ))
+}
+
+/// Only set with probation enabled, so channels left on probation when it is switched off are
+/// tracked fully.
+fn get_probation_repo(
+    stores: &StoreFactory,
+    config: &Config,
+) -> Option<Box<dyn ChannelProbationStore>> {
+    if !config.probation.enabled {
+        return None;
+    }
+
+    Some(stores.channel_probation_store())
+}
+
+fn get_url_resolver_service(
+    stores: &StoreFactory,
+    config: &Config,
+) -> Option<Arc<UrlResolverService>> {
+    if !config.url_resolver.enabled {
+        return None;
+    }
+
+    Some(Arc::new(UrlResolverService::new(
+        stores.resolved_url_store(),
+        Duration::from_millis(config.url_resolver.domain_interval_millis),
+    )))
+}
+
+fn get_feed_rate_limiter(config: &Config) -> Arc<RateLimiter> {
+    Arc::new(RateLimiter::new(
+        config.rate_limit.feed_requests_per_second,
+        config.rate_limit.feed_burst,
+    ))
+}
+
+fn get_request_interval(config: &Config) -> Duration {
+    if config.strict_compliance {
+        return Duration::from_millis(STRICT_COMPLIANCE_REQUEST_INTERVAL_MILLIS);
+    }
+
+    Duration::ZERO
+}
+
+fn register_caption_scraper(
+    tasks: &mut Vec<JoinHandle<()>>,
+    stores: StoreFactory,
+    config: Config,
+    maintenance: Arc<Maintenance>,
+    feed_throttle: Arc<Throttle>,
+    mut rx: Receiver<CrawlCaptionsCommand>,
+) {
+    let caption_scraper_task = task::spawn(async move {
+        info!("SCRAPER: Start caption scrape listener");
+
+        let caption_repo = stores.caption_store();
+        let video_repo = stores.video_store();
+        let channel_repo = stores.channel_store();
+        let guitar_terms = get_guitar_terms(&stores).await;
+        let songs = video_repo
+            .get_cover_songs(CAPTION_SONGS)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Failed to load cover songs for captions: {}", e);
+                vec![]
+            });
+
+        let scraper = CaptionScraper::new(
+            caption_repo,
+            video_repo,
+            channel_repo,
+            guitar_terms,
+            songs,
+            feed_throttle,
+            config.youtube.timed_text_base_url.clone(),
+        );
+
+        while let Some(cmd) = rx.recv().await {
+            maintenance.checkpoint("caption scraper").await;
+
+            let result = scraper.scrape(cmd.video_id, cmd.channel_id).await;
+
+            if let Err(e) = result {
+                error!("Error in caption scraper: {}", e);
+            }
+        }
+    });
+
+    tasks.push(caption_scraper_task);
+}
+
+async fn get_guitar_terms(stores: &StoreFactory) -> Vec<String> {
+    let guitar_term_repo = stores.guitar_term_store();
     let guitar_terms = guitar_term_repo.get_all().await.unwrap();
 
     guitar_terms
 }
 
-async fn get_blacklisted_channels(mongo_client: &Client, environment: &str) -> Vec<String> {
-    let blacklist_repo = BlacklistRepository::new(&mongo_client, environment);
+async fn get_blacklisted_channels(stores: &StoreFactory) -> Vec<String> {
+    let blacklist_repo = stores.blacklist_store();
     let blacklisted_channels = blacklist_repo.get_all().await.unwrap();
 
     blacklisted_channels
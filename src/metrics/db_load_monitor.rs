@@ -0,0 +1,170 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use log::{info, warn};
+use mongodb::event::cmap::{
+    CmapEventHandler, ConnectionCheckedInEvent, ConnectionCheckedOutEvent,
+    ConnectionCheckoutFailedEvent, ConnectionCheckoutStartedEvent,
+};
+
+use crate::{metrics::metrics_registry::MetricsRegistry, utils::maintenance::Maintenance};
+
+// Weight of the latest command in the average latency
+const LATENCY_SMOOTHING: f64 = 0.1;
+// The database counts as recovered once both signals fall below this share of their limit
+const RECOVERY_RATIO: f64 = 0.5;
+
+const OVERLOADED_METRIC: &str = "mongodb_overloaded";
+const LATENCY_METRIC: &str = "mongodb_command_latency_average_milliseconds";
+const POOL_USAGE_METRIC: &str = "mongodb_pool_usage";
+const TRANSITIONS_METRIC: &str = "mongodb_overload_transitions_total";
+
+#[derive(Default)]
+struct DbLoad {
+    average_latency_millis: f64,
+    checked_out: u64,
+    waiting: u64,
+    overloaded: bool,
+}
+
+/// Follows the command latency and the connection pool of the MongoDB client, and sets the
+/// overload flag of `Maintenance` while either stays above its limit.
+pub struct DbLoadMonitor {
+    maintenance: Arc<Maintenance>,
+    metrics: Arc<MetricsRegistry>,
+    max_latency_millis: f64,
+    max_pool_usage: f64,
+    max_pool_size: u32,
+    load: Mutex<DbLoad>,
+}
+
+impl DbLoadMonitor {
+    pub fn new(
+        maintenance: Arc<Maintenance>,
+        metrics: Arc<MetricsRegistry>,
+        max_latency: Duration,
+        max_pool_usage: f64,
+        max_pool_size: u32,
+    ) -> DbLoadMonitor {
+        DbLoadMonitor {
+            maintenance,
+            metrics,
+            max_latency_millis: max_latency.as_secs_f64() * 1000.0,
+            max_pool_usage,
+            max_pool_size: max_pool_size.max(1),
+            load: Mutex::new(DbLoad::default()),
+        }
+    }
+
+    /// Called by the `MongoCommandMonitor` for every finished command.
+    pub fn record_command(&self, duration: Duration) {
+        let latency_millis = duration.as_secs_f64() * 1000.0;
+
+        self.update(|load| {
+            load.average_latency_millis +=
+                LATENCY_SMOOTHING * (latency_millis - load.average_latency_millis);
+        });
+    }
+
+    fn update<F: FnOnce(&mut DbLoad)>(&self, change: F) {
+        let mut load = self.load.lock().unwrap();
+        change(&mut load);
+
+        let pool_usage = (load.checked_out + load.waiting) as f64 / self.max_pool_size as f64;
+        let average_latency_millis = load.average_latency_millis;
+        let was_overloaded = load.overloaded;
+        load.overloaded = is_overloaded(
+            was_overloaded,
+            average_latency_millis,
+            pool_usage,
+            self.max_latency_millis,
+            self.max_pool_usage,
+        );
+        let overloaded = load.overloaded;
+        drop(load);
+
+        self.metrics
+            .set_gauge(LATENCY_METRIC, &[], average_latency_millis);
+        self.metrics.set_gauge(POOL_USAGE_METRIC, &[], pool_usage);
+        self.metrics
+            .set_gauge(OVERLOADED_METRIC, &[], if overloaded { 1.0 } else { 0.0 });
+
+        if overloaded == was_overloaded {
+            return;
+        }
+
+        if overloaded {
+            warn!(
+                "MongoDB is overloaded (latency {:.0}ms, pool usage {:.2}), backing off",
+                average_latency_millis, pool_usage
+            );
+        } else {
+            info!(
+                "MongoDB recovered (latency {:.0}ms, pool usage {:.2}), resuming",
+                average_latency_millis, pool_usage
+            );
+        }
+
+        let state = if overloaded {
+            "overloaded"
+        } else {
+            "recovered"
+        };
+        self.metrics
+            .increment_counter(TRANSITIONS_METRIC, &[("state", state)]);
+        self.maintenance.set_overloaded(overloaded);
+    }
+}
+
+impl CmapEventHandler for DbLoadMonitor {
+    fn handle_connection_checkout_started_event(&self, _event: ConnectionCheckoutStartedEvent) {
+        self.update(|load| load.waiting += 1);
+    }
+
+    fn handle_connection_checkout_failed_event(&self, _event: ConnectionCheckoutFailedEvent) {
+        self.update(|load| load.waiting = load.waiting.saturating_sub(1));
+    }
+
+    fn handle_connection_checked_out_event(&self, _event: ConnectionCheckedOutEvent) {
+        self.update(|load| {
+            load.waiting = load.waiting.saturating_sub(1);
+            load.checked_out += 1;
+        });
+    }
+
+    fn handle_connection_checked_in_event(&self, _event: ConnectionCheckedInEvent) {
+        self.update(|load| load.checked_out = load.checked_out.saturating_sub(1));
+    }
+}
+
+/// Overloaded above either limit, and only recovered once both signals fell well below them, so
+/// the state does not flap around a limit.
+fn is_overloaded(
+    was_overloaded: bool,
+    average_latency_millis: f64,
+    pool_usage: f64,
+    max_latency_millis: f64,
+    max_pool_usage: f64,
+) -> bool {
+    if average_latency_millis >= max_latency_millis || pool_usage >= max_pool_usage {
+        return true;
+    }
+
+    was_overloaded
+        && (average_latency_millis >= max_latency_millis * RECOVERY_RATIO
+            || pool_usage >= max_pool_usage * RECOVERY_RATIO)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn recovers_below_half_of_the_limits() {
+        assert!(!super::is_overloaded(false, 200.0, 0.5, 250.0, 0.9));
+        assert!(super::is_overloaded(false, 300.0, 0.5, 250.0, 0.9));
+        assert!(super::is_overloaded(false, 10.0, 1.0, 250.0, 0.9));
+
+        assert!(super::is_overloaded(true, 200.0, 0.2, 250.0, 0.9));
+        assert!(super::is_overloaded(true, 50.0, 0.6, 250.0, 0.9));
+        assert!(!super::is_overloaded(true, 100.0, 0.2, 250.0, 0.9));
+    }
+}
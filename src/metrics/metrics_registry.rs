@@ -0,0 +1,221 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+const BUCKETS_MILLIS: [f64; 11] = [
+    1.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0,
+];
+// From a minute to a week, for delays like the one between an upload and its crawl
+const BUCKETS_SECONDS: [f64; 11] = [
+    60.0, 300.0, 900.0, 1800.0, 3600.0, 10800.0, 21600.0, 43200.0, 86400.0, 259200.0, 604800.0,
+];
+
+struct Histogram {
+    buckets: &'static [f64],
+    bucket_counts: Vec<u64>,
+    count: u64,
+    sum: f64,
+}
+
+/// In-process histograms, counters and gauges rendered in the Prometheus text format. Series are
+/// identified by metric name and label pairs.
+#[derive(Default)]
+pub struct MetricsRegistry {
+    histograms: Mutex<BTreeMap<(String, String), Histogram>>,
+    counters: Mutex<BTreeMap<(String, String), u64>>,
+    gauges: Mutex<BTreeMap<(String, String), f64>>,
+}
+
+impl MetricsRegistry {
+    pub fn new() -> MetricsRegistry {
+        MetricsRegistry::default()
+    }
+
+    pub fn observe_duration(&self, name: &str, labels: &[(&str, &str)], duration: Duration) {
+        self.observe(
+            name,
+            labels,
+            &BUCKETS_MILLIS,
+            duration.as_secs_f64() * 1000.0,
+        );
+    }
+
+    /// Like `observe_duration`, for delays of minutes to days.
+    pub fn observe_seconds(&self, name: &str, labels: &[(&str, &str)], seconds: f64) {
+        self.observe(name, labels, &BUCKETS_SECONDS, seconds);
+    }
+
+    fn observe(&self, name: &str, labels: &[(&str, &str)], buckets: &'static [f64], value: f64) {
+        let mut histograms = self.histograms.lock().unwrap();
+        let histogram = histograms
+            .entry((name.to_string(), format_labels(labels)))
+            .or_insert_with(|| Histogram {
+                buckets,
+                bucket_counts: vec![0; buckets.len()],
+                count: 0,
+                sum: 0.0,
+            });
+
+        for (index, bucket) in histogram.buckets.iter().enumerate() {
+            if value <= *bucket {
+                histogram.bucket_counts[index] += 1;
+            }
+        }
+        histogram.count += 1;
+        histogram.sum += value;
+    }
+
+    pub fn increment_counter(&self, name: &str, labels: &[(&str, &str)]) {
+        let mut counters = self.counters.lock().unwrap();
+
+        *counters
+            .entry((name.to_string(), format_labels(labels)))
+            .or_default() += 1;
+    }
+
+    pub fn set_gauge(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.gauges
+            .lock()
+            .unwrap()
+            .insert((name.to_string(), format_labels(labels)), value);
+    }
+
+    pub fn render(&self) -> String {
+        let histograms = self.histograms.lock().unwrap();
+        let mut output = String::new();
+        let mut last_name = "";
+
+        for ((name, labels), histogram) in histograms.iter() {
+            if name != last_name {
+                writeln!(output, "# TYPE {} histogram", name).unwrap();
+                last_name = name;
+            }
+
+            let separator = if labels.is_empty() { "" } else { "," };
+            for (index, bucket) in histogram.buckets.iter().enumerate() {
+                writeln!(
+                    output,
+                    "{}_bucket{{{}{}le=\"{}\"}} {}",
+                    name, labels, separator, bucket, histogram.bucket_counts[index]
+                )
+                .unwrap();
+            }
+            writeln!(
+                output,
+                "{}_bucket{{{}{}le=\"+Inf\"}} {}",
+                name, labels, separator, histogram.count
+            )
+            .unwrap();
+            writeln!(output, "{}_sum{{{}}} {}", name, labels, histogram.sum).unwrap();
+            writeln!(output, "{}_count{{{}}} {}", name, labels, histogram.count).unwrap();
+        }
+
+        let counters = self.counters.lock().unwrap();
+        let mut last_name = "";
+
+        for ((name, labels), count) in counters.iter() {
+            if name != last_name {
+                writeln!(output, "# TYPE {} counter", name).unwrap();
+                last_name = name;
+            }
+
+            writeln!(output, "{}{{{}}} {}", name, labels, count).unwrap();
+        }
+
+        let gauges = self.gauges.lock().unwrap();
+        let mut last_name = "";
+
+        for ((name, labels), value) in gauges.iter() {
+            if name != last_name {
+                writeln!(output, "# TYPE {} gauge", name).unwrap();
+                last_name = name;
+            }
+
+            writeln!(output, "{}{{{}}} {}", name, labels, value).unwrap();
+        }
+
+        output
+    }
+}
+
+fn format_labels(labels: &[(&str, &str)]) -> String {
+    labels
+        .iter()
+        .map(|(key, value)| format!("{}=\"{}\"", key, value.replace('"', "\\\"")))
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::MetricsRegistry;
+
+    #[test]
+    fn renders_cumulative_buckets() {
+        let metrics = MetricsRegistry::new();
+
+        metrics.observe_duration(
+            "latency",
+            &[("collection", "videos")],
+            Duration::from_millis(3),
+        );
+        metrics.observe_duration(
+            "latency",
+            &[("collection", "videos")],
+            Duration::from_millis(30),
+        );
+
+        let output = metrics.render();
+
+        assert!(output.contains("# TYPE latency histogram"));
+        assert!(output.contains("latency_bucket{collection=\"videos\",le=\"1\"} 0"));
+        assert!(output.contains("latency_bucket{collection=\"videos\",le=\"5\"} 1"));
+        assert!(output.contains("latency_bucket{collection=\"videos\",le=\"50\"} 2"));
+        assert!(output.contains("latency_bucket{collection=\"videos\",le=\"+Inf\"} 2"));
+        assert!(output.contains("latency_count{collection=\"videos\"} 2"));
+    }
+
+    #[test]
+    fn renders_second_buckets() {
+        let metrics = MetricsRegistry::new();
+
+        metrics.observe_seconds("delay_seconds", &[], 600.0);
+
+        let output = metrics.render();
+
+        assert!(output.contains("delay_seconds_bucket{le=\"300\"} 0"));
+        assert!(output.contains("delay_seconds_bucket{le=\"900\"} 1"));
+        assert!(output.contains("delay_seconds_sum{} 600"));
+    }
+
+    #[test]
+    fn renders_counters() {
+        let metrics = MetricsRegistry::new();
+
+        metrics.increment_counter("fallbacks_total", &[("reason", "error")]);
+        metrics.increment_counter("fallbacks_total", &[("reason", "error")]);
+        metrics.increment_counter("fallbacks_total", &[("reason", "empty")]);
+
+        let output = metrics.render();
+
+        assert!(output.contains("# TYPE fallbacks_total counter"));
+        assert!(output.contains("fallbacks_total{reason=\"error\"} 2"));
+        assert!(output.contains("fallbacks_total{reason=\"empty\"} 1"));
+    }
+
+    #[test]
+    fn renders_latest_gauge_value() {
+        let metrics = MetricsRegistry::new();
+
+        metrics.set_gauge("overloaded", &[], 1.0);
+        metrics.set_gauge("overloaded", &[], 0.0);
+
+        let output = metrics.render();
+
+        assert!(output.contains("# TYPE overloaded gauge"));
+        assert!(output.contains("overloaded{} 0"));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod db_load_monitor;
+pub mod metrics_registry;
+pub mod mongo_command_monitor;
@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use log::{debug, warn};
+use mongodb::bson::{Bson, Document};
+use mongodb::event::command::{
+    CommandEventHandler, CommandFailedEvent, CommandStartedEvent, CommandSucceededEvent,
+};
+
+use crate::metrics::{db_load_monitor::DbLoadMonitor, metrics_registry::MetricsRegistry};
+
+const COMMAND_DURATION_METRIC: &str = "mongodb_command_duration_milliseconds";
+
+/// Commands that take long however idle the server is
+const LONG_RUNNING_COMMANDS: [&str; 4] = ["createIndexes", "dropIndexes", "drop", "compact"];
+
+struct StartedCommand {
+    collection: String,
+    filter_shape: String,
+    /// Whether the duration tells how loaded the server is
+    measures_load: bool,
+}
+
+/// Times every command the MongoDB client sends, so all repositories are instrumented without
+/// wrapping each of their methods. Commands slower than the threshold are logged as warnings.
+pub struct MongoCommandMonitor {
+    metrics: Arc<MetricsRegistry>,
+    slow_query_threshold: Duration,
+    db_load_monitor: Option<Arc<DbLoadMonitor>>,
+    started_commands: Mutex<HashMap<i32, StartedCommand>>,
+}
+
+impl MongoCommandMonitor {
+    pub fn new(
+        metrics: Arc<MetricsRegistry>,
+        slow_query_threshold: Duration,
+        db_load_monitor: Option<Arc<DbLoadMonitor>>,
+    ) -> Self {
+        MongoCommandMonitor {
+            metrics,
+            slow_query_threshold,
+            db_load_monitor,
+            started_commands: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn finish(
+        &self,
+        request_id: i32,
+        operation: &str,
+        duration: Duration,
+        failure: Option<String>,
+    ) {
+        let started_command = match self.started_commands.lock().unwrap().remove(&request_id) {
+            Some(started_command) => started_command,
+            None => return,
+        };
+
+        self.metrics.observe_duration(
+            COMMAND_DURATION_METRIC,
+            &[
+                ("collection", &started_command.collection),
+                ("operation", operation),
+            ],
+            duration,
+        );
+
+        if !started_command.measures_load {
+            debug!(
+                "mongodb operation={} collection={} duration_ms={}",
+                operation,
+                started_command.collection,
+                duration.as_millis()
+            );
+            return;
+        }
+
+        if let Some(db_load_monitor) = &self.db_load_monitor {
+            db_load_monitor.record_command(duration);
+        }
+
+        let duration_millis = duration.as_millis();
+        if let Some(failure) = failure {
+            warn!(
+                "mongodb operation={} collection={} filter={} duration_ms={} error={}",
+                operation,
+                started_command.collection,
+                started_command.filter_shape,
+                duration_millis,
+                failure
+            );
+        } else if duration >= self.slow_query_threshold {
+            warn!(
+                "Slow mongodb operation={} collection={} filter={} duration_ms={}",
+                operation,
+                started_command.collection,
+                started_command.filter_shape,
+                duration_millis
+            );
+        } else {
+            debug!(
+                "mongodb operation={} collection={} filter={} duration_ms={}",
+                operation,
+                started_command.collection,
+                started_command.filter_shape,
+                duration_millis
+            );
+        }
+    }
+}
+
+impl CommandEventHandler for MongoCommandMonitor {
+    fn handle_command_started_event(&self, event: CommandStartedEvent) {
+        // Handshakes and pings are not addressed to a collection by name, cursors name it apart
+        let collection_field = match event.command_name.as_str() {
+            "getMore" => "collection",
+            command_name => command_name,
+        };
+        let collection = match event.command.get_str(collection_field) {
+            Ok(collection) => collection.to_string(),
+            Err(_) => return,
+        };
+
+        let filter_shape = get_filter(&event.command_name, &event.command)
+            .map(|filter| get_shape(&filter).to_string())
+            .unwrap_or_else(|| "{}".to_string());
+
+        self.started_commands.lock().unwrap().insert(
+            event.request_id,
+            StartedCommand {
+                collection,
+                filter_shape,
+                measures_load: !is_long_by_design(&event.command_name, &event.command),
+            },
+        );
+    }
+
+    fn handle_command_succeeded_event(&self, event: CommandSucceededEvent) {
+        self.finish(event.request_id, &event.command_name, event.duration, None);
+    }
+
+    fn handle_command_failed_event(&self, event: CommandFailedEvent) {
+        self.finish(
+            event.request_id,
+            &event.command_name,
+            event.duration,
+            Some(event.failure.to_string()),
+        );
+    }
+}
+
+/// Tailable await cursors and change streams wait for new data on purpose, their `getMore` sets
+/// the wait as `maxTimeMS`. Such commands would read as an overloaded server.
+fn is_long_by_design(command_name: &str, command: &Document) -> bool {
+    match command_name {
+        "getMore" => command.contains_key("maxTimeMS"),
+        "aggregate" => command
+            .get_array("pipeline")
+            .ok()
+            .and_then(|pipeline| pipeline.first())
+            .and_then(Bson::as_document)
+            .is_some_and(|stage| stage.contains_key("$changeStream")),
+        command_name => LONG_RUNNING_COMMANDS.contains(&command_name),
+    }
+}
+
+fn get_filter(command_name: &str, command: &Document) -> Option<Document> {
+    let first_statement = |field: &str| -> Option<Document> {
+        command
+            .get_array(field)
+            .ok()?
+            .first()?
+            .as_document()?
+            .get_document("q")
+            .ok()
+            .cloned()
+    };
+
+    match command_name {
+        "find" => command.get_document("filter").ok().cloned(),
+        "count" | "distinct" | "findAndModify" => command.get_document("query").ok().cloned(),
+        "update" => first_statement("updates"),
+        "delete" => first_statement("deletes"),
+        "aggregate" => command
+            .get_array("pipeline")
+            .ok()?
+            .first()?
+            .as_document()?
+            .get_document("$match")
+            .ok()
+            .cloned(),
+        _ => None,
+    }
+}
+
+/// Replaces all values with their type, so filters can be logged without the queried data.
+fn get_shape(filter: &Document) -> Document {
+    filter
+        .iter()
+        .map(|(key, value)| (key.clone(), get_value_shape(value)))
+        .collect()
+}
+
+fn get_value_shape(value: &Bson) -> Bson {
+    match value {
+        Bson::Document(document) => Bson::Document(get_shape(document)),
+        Bson::Array(values) => Bson::Array(values.iter().take(1).map(get_value_shape).collect()),
+        Bson::String(_) => Bson::String("string".to_string()),
+        Bson::Int32(_) | Bson::Int64(_) | Bson::Double(_) => Bson::String("number".to_string()),
+        Bson::Boolean(_) => Bson::String("bool".to_string()),
+        Bson::DateTime(_) => Bson::String("date".to_string()),
+        Bson::Null => Bson::Null,
+        _ => Bson::String("value".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::doc;
+
+    #[test]
+    fn awaiting_cursors_and_admin_commands_are_long_by_design() {
+        let change_stream = doc! {"aggregate": "channels", "pipeline": [{"$changeStream": {}}]};
+        let await_get_more = doc! {"getMore": 42_i64, "collection": "channels", "maxTimeMS": 1000};
+        let get_more = doc! {"getMore": 42_i64, "collection": "channels"};
+        let aggregate = doc! {"aggregate": "channels", "pipeline": [{"$match": {"_id": "UC1"}}]};
+
+        assert!(super::is_long_by_design("aggregate", &change_stream));
+        assert!(super::is_long_by_design("getMore", &await_get_more));
+        assert!(super::is_long_by_design(
+            "createIndexes",
+            &doc! {"createIndexes": "channels"}
+        ));
+        assert!(!super::is_long_by_design("getMore", &get_more));
+        assert!(!super::is_long_by_design("aggregate", &aggregate));
+        assert!(!super::is_long_by_design(
+            "find",
+            &doc! {"find": "channels"}
+        ));
+    }
+
+    #[test]
+    fn shape_hides_values() {
+        let command = doc! {
+            "update": "channels",
+            "updates": [{
+                "q": {"_id": "UC123", "views": {"$gte": 10}, "$or": [{"a": true}, {"b": 1}]},
+                "u": {"$set": {"views": 11}},
+            }],
+        };
+
+        let filter = super::get_filter("update", &command).unwrap();
+
+        assert_eq!(
+            super::get_shape(&filter),
+            doc! {"_id": "string", "views": {"$gte": "number"}, "$or": [{"a": "bool"}]}
+        );
+    }
+}
@@ -0,0 +1,22 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::Database;
+
+/// The database of the configured storage backend.
+pub enum MigrationDatabase {
+    MongoDb(Database),
+    Postgres(Arc<tokio_postgres::Client>),
+}
+
+/// A schema change of the database, applied once in the order of `version`.
+#[async_trait]
+pub trait Migration: Send + Sync {
+    fn version(&self) -> i64;
+
+    fn name(&self) -> &'static str;
+
+    /// Must be safe to run again if it fails halfway, the version is only recorded after it.
+    async fn up(&self, database: &MigrationDatabase) -> Result<(), Error>;
+}
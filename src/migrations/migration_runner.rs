@@ -0,0 +1,84 @@
+use anyhow::Error;
+use log::info;
+
+use crate::{
+    migrations::{
+        migration::{Migration, MigrationDatabase},
+        v0001_query_indexes::QueryIndexes,
+        v0002_comment_indexes::CommentIndexes,
+    },
+    repos::{schema_migrations_store::SchemaMigrationsStore, store_factory::StoreFactory},
+};
+
+/// All migrations, new ones are appended with the next version.
+pub fn get_migrations() -> Vec<Box<dyn Migration>> {
+    vec![Box::new(QueryIndexes), Box::new(CommentIndexes)]
+}
+
+/// Applies the migrations whose version is not in `schema_migrations` yet, in order.
+pub struct MigrationRunner {
+    database: MigrationDatabase,
+    schema_migrations_repo: Box<dyn SchemaMigrationsStore>,
+    migrations: Vec<Box<dyn Migration>>,
+}
+
+impl MigrationRunner {
+    pub fn new(stores: &StoreFactory) -> MigrationRunner {
+        MigrationRunner {
+            database: stores.migration_database(),
+            schema_migrations_repo: stores.schema_migrations_store(),
+            migrations: get_migrations(),
+        }
+    }
+
+    pub async fn get_pending(&self) -> Result<Vec<&dyn Migration>, Error> {
+        let applied = self.schema_migrations_repo.get_applied_versions().await?;
+
+        Ok(self
+            .migrations
+            .iter()
+            .map(|migration| migration.as_ref())
+            .filter(|migration| !applied.contains(&migration.version()))
+            .collect())
+    }
+
+    /// Stops at the first failing migration, the ones before it stay applied.
+    pub async fn apply_pending(&self) -> Result<Vec<&dyn Migration>, Error> {
+        let pending = self.get_pending().await?;
+
+        for migration in &pending {
+            info!(
+                "Apply migration {} {}",
+                migration.version(),
+                migration.name()
+            );
+
+            migration.up(&self.database).await.map_err(|e| {
+                anyhow::anyhow!(
+                    "Migration {} {} failed: {}",
+                    migration.version(),
+                    migration.name(),
+                    e
+                )
+            })?;
+            self.schema_migrations_repo
+                .insert(migration.version(), migration.name())
+                .await?;
+        }
+
+        Ok(pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn versions_ascend_without_gaps() {
+        let versions = super::get_migrations()
+            .iter()
+            .map(|migration| migration.version())
+            .collect::<Vec<i64>>();
+
+        assert_eq!(versions, (1..=versions.len() as i64).collect::<Vec<i64>>());
+    }
+}
@@ -0,0 +1,4 @@
+pub mod migration;
+pub mod migration_runner;
+pub mod v0001_query_indexes;
+pub mod v0002_comment_indexes;
@@ -0,0 +1,61 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, Document};
+use mongodb::IndexModel;
+
+use crate::migrations::migration::{Migration, MigrationDatabase};
+
+/// Indexes the fields the scrapers and jobs query channels and videos by.
+pub struct QueryIndexes;
+
+#[async_trait]
+impl Migration for QueryIndexes {
+    fn version(&self) -> i64 {
+        1
+    }
+
+    fn name(&self) -> &'static str {
+        "query_indexes"
+    }
+
+    async fn up(&self, database: &MigrationDatabase) -> Result<(), Error> {
+        let db = match database {
+            MigrationDatabase::MongoDb(db) => db,
+            // Same names as the required indexes, so the startup check finds them
+            MigrationDatabase::Postgres(client) => {
+                client
+                    .batch_execute(
+                        "CREATE INDEX IF NOT EXISTS channels_handle_idx
+                            ON channels ((doc->>'handle'));
+                        CREATE INDEX IF NOT EXISTS channels_next_scrape_at_idx
+                            ON channels (((doc->>'nextScrapeAt')::bigint));
+                        CREATE INDEX IF NOT EXISTS videos_channel_published_at_idx
+                            ON videos (channel, ((doc->>'publishedAt')::bigint) DESC);",
+                    )
+                    .await?;
+
+                return Ok(());
+            }
+        };
+
+        let channels = db.collection::<Document>("channels");
+        for keys in [doc! { "handle": 1 }, doc! { "nextScrapeAt": 1 }] {
+            channels
+                .create_index(IndexModel::builder().keys(keys).build(), None)
+                .await?;
+        }
+
+        for collection in ["videos", "coldvideos"] {
+            db.collection::<Document>(collection)
+                .create_index(
+                    IndexModel::builder()
+                        .keys(doc! { "channel": 1, "publishedAt": -1 })
+                        .build(),
+                    None,
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
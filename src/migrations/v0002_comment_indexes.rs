@@ -0,0 +1,47 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, Document};
+use mongodb::IndexModel;
+
+use crate::migrations::migration::{Migration, MigrationDatabase};
+
+/// Indexes the ingested comments by video for the comment sentiment and related channels jobs.
+pub struct CommentIndexes;
+
+#[async_trait]
+impl Migration for CommentIndexes {
+    fn version(&self) -> i64 {
+        2
+    }
+
+    fn name(&self) -> &'static str {
+        "comment_indexes"
+    }
+
+    async fn up(&self, database: &MigrationDatabase) -> Result<(), Error> {
+        let db = match database {
+            MigrationDatabase::MongoDb(db) => db,
+            MigrationDatabase::Postgres(client) => {
+                client
+                    .batch_execute(
+                        "CREATE INDEX IF NOT EXISTS comments_video_id_idx
+                            ON comments ((doc->>'videoId'));
+                        CREATE INDEX IF NOT EXISTS comments_published_at_idx
+                            ON comments (((doc->>'publishedAt')::bigint) DESC);",
+                    )
+                    .await?;
+
+                return Ok(());
+            }
+        };
+
+        let comments = db.collection::<Document>("comments");
+        for keys in [doc! { "videoId": 1 }, doc! { "publishedAt": -1 }] {
+            comments
+                .create_index(IndexModel::builder().keys(keys).build(), None)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
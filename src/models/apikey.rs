@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::utils::api_key_health_utils::API_KEY_HEALTHY;
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ApiKey {
     #[serde(rename(deserialize = "_id"))]
@@ -7,4 +9,22 @@ pub struct ApiKey {
     pub used_quota: i32,
     pub daily_quota: i32,
     pub pdt_day: i32,
+    /// One of `healthy`, `degraded` or `disabled`
+    #[serde(default = "default_health")]
+    pub health: String,
+    #[serde(default)]
+    pub consecutive_errors: i32,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    #[serde(default)]
+    pub last_error_at: Option<i64>,
+    #[serde(default)]
+    pub disabled_at: Option<i64>,
+    /// The key ran out of quota and is skipped until the next reset
+    #[serde(default)]
+    pub quota_exceeded_until: Option<i64>,
+}
+
+fn default_health() -> String {
+    API_KEY_HEALTHY.to_string()
 }
@@ -1,17 +1,691 @@
 use serde::Deserialize;
 
+use crate::notifications::{
+    smtp_client::SMTP_SECURITY_STARTTLS, webhook_notifier::WEBHOOK_FORMAT_JSON,
+};
+use crate::utils::consts::{
+    CACHE_BACKEND_MEMORY, CHANGE_SINK_KAFKA, GENRE_CLASSIFIER_RULES, ONE_DAYS_IN_SECONDS,
+    QUEUE_BACKEND_LOCAL, STORAGE_BACKEND_MONGODB, VIDEO_TYPE_CLASSIFIER_RULES,
+};
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct CrawlerConfig {
     pub additional: bool,
     pub discovery: bool,
     pub video: bool,
     pub channel: bool,
+    #[serde(default)]
+    pub captions: bool,
+    #[serde(default)]
+    pub backfill: bool,
+    #[serde(default)]
+    pub archive: bool,
+    #[serde(default)]
+    pub about: bool,
+    #[serde(default)]
+    pub reclassification: bool,
+    #[serde(default)]
+    pub reconciliation: bool,
+    #[serde(default)]
+    pub related_channels: bool,
+    #[serde(default)]
+    pub lifecycle: bool,
+    #[serde(default)]
+    pub duplicates: bool,
+    #[serde(default)]
+    pub topic_drift: bool,
+    #[serde(default)]
+    pub stats_aggregation: bool,
+    #[serde(default)]
+    pub comments: bool,
+    #[serde(default)]
+    pub comment_sentiment: bool,
+    #[serde(default)]
+    pub ban_evasion: bool,
+    #[serde(default)]
+    pub link_verification: bool,
+    #[serde(default)]
+    pub upload_pattern: bool,
+    #[serde(default)]
+    pub metadata_refresh: bool,
+    #[serde(default)]
+    pub genre_tags: bool,
+    #[serde(default)]
+    pub reporting: bool,
+    #[serde(default)]
+    pub alerts: bool,
+    #[serde(default)]
+    pub end_screens: bool,
+    #[serde(default)]
+    pub courses: bool,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct AdminApiConfig {
+    pub enabled: bool,
+    /// Address to bind, only reachable from the host by default
+    pub host: String,
+    pub port: u16,
+    /// Bearer token every request besides the health probes, status page and metrics must carry
+    pub token: Option<String>,
+}
+
+impl Default for AdminApiConfig {
+    fn default() -> Self {
+        AdminApiConfig {
+            enabled: false,
+            host: "127.0.0.1".to_string(),
+            port: 8080,
+            token: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct YoutubeConfig {
+    pub api_base_url: String,
+    pub feed_base_url: String,
+    pub timed_text_base_url: String,
+    /// Public channel pages, read for the verification badge and monetization hints
+    pub channel_page_base_url: String,
+    /// Public watch pages, read for the channels promoted by end screens and cards
+    pub watch_page_base_url: String,
+    /// Data API calls to spread evenly over a day, 0 does not pace calls
+    pub daily_api_calls: u64,
+}
+
+impl Default for YoutubeConfig {
+    fn default() -> Self {
+        YoutubeConfig {
+            api_base_url: "https://www.googleapis.com/youtube/v3/".to_string(),
+            feed_base_url: "https://www.youtube.com/feeds/videos.xml".to_string(),
+            timed_text_base_url: "https://www.youtube.com/api/timedtext".to_string(),
+            channel_page_base_url: "https://www.youtube.com/channel/".to_string(),
+            watch_page_base_url: "https://www.youtube.com/watch?v=".to_string(),
+            daily_api_calls: 0,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct SimulationConfig {
+    pub enabled: bool,
+    pub port: u16,
+    pub seed: u64,
+    pub channels: usize,
+    pub videos_per_channel: usize,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        SimulationConfig {
+            enabled: false,
+            port: 8090,
+            seed: 42,
+            channels: 1000,
+            videos_per_channel: 50,
+        }
+    }
+}
+
+/// Serves recorded responses from `fixtures_dir` in place of YouTube, see `ReplayServer`.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct ReplayConfig {
+    pub enabled: bool,
+    pub port: u16,
+    pub fixtures_dir: String,
+}
+
+impl Default for ReplayConfig {
+    fn default() -> Self {
+        ReplayConfig {
+            enabled: false,
+            port: 8091,
+            fixtures_dir: "fixtures".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct MaintenanceConfig {
+    pub enabled: bool,
+    pub reason: String,
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        MaintenanceConfig {
+            enabled: false,
+            reason: "Planned maintenance".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct StorageConfig {
+    /// `mongodb` or `postgres`, the backend of all stores
+    pub backend: String,
+    pub postgres_connection_string: Option<String>,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        StorageConfig {
+            backend: STORAGE_BACKEND_MONGODB.to_string(),
+            postgres_connection_string: None,
+        }
+    }
+}
+
+/// Youtube api responses are cached when enabled, so lookups repeated within `ttl_seconds` cost
+/// no quota.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct CacheConfig {
+    pub enabled: bool,
+    /// `memory` or `redis`
+    pub backend: String,
+    pub ttl_seconds: u64,
+    /// Only used by the memory backend
+    pub max_entries: usize,
+    pub redis_url: Option<String>,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfig {
+            enabled: false,
+            backend: CACHE_BACKEND_MEMORY.to_string(),
+            ttl_seconds: 6 * 60 * 60,
+            max_entries: 10_000,
+            redis_url: None,
+        }
+    }
+}
+
+/// Where the channel scraper takes its commands from. With `redis`, commands are added to a
+/// stream that the channel scrapers of all instances consume as one consumer group.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct QueueConfig {
+    /// `local` or `redis`
+    pub backend: String,
+    pub redis_url: Option<String>,
+    pub stream: String,
+    pub group: String,
+    /// Defaults to the host name, so a restarted instance picks up its unacknowledged commands
+    pub consumer: Option<String>,
+    /// Commands a consumer did not acknowledge for this long are handed to another consumer
+    pub claim_idle_seconds: u64,
+    /// Commands are rejected while the stream holds this many
+    pub max_length: u64,
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        QueueConfig {
+            backend: QUEUE_BACKEND_LOCAL.to_string(),
+            redis_url: None,
+            stream: "crawler:channel-commands".to_string(),
+            group: "channel-scrapers".to_string(),
+            consumer: None,
+            claim_idle_seconds: 10 * 60,
+            max_length: 100_000,
+        }
+    }
+}
+
+/// Crawl requests other services publish on a NATS subject. Instances subscribe as one queue
+/// group, so each request is handled once.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct IngestConfig {
+    pub enabled: bool,
+    pub nats_url: String,
+    pub subject: String,
+    pub queue_group: String,
+    /// Requests for a channel repeated within this window are skipped
+    pub dedupe_seconds: u64,
+}
+
+impl Default for IngestConfig {
+    fn default() -> Self {
+        IngestConfig {
+            enabled: false,
+            nats_url: "nats://localhost:4222".to_string(),
+            subject: "crawler.channels.crawl".to_string(),
+            queue_group: "crawler".to_string(),
+            dedupe_seconds: 60 * 60,
+        }
+    }
+}
+
+/// gRPC control plane for other services, see `proto/crawler.proto`.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct GrpcConfig {
+    pub enabled: bool,
+    pub port: u16,
+    /// Entity events kept for slow event stream subscribers
+    pub event_buffer: usize,
+}
+
+impl Default for GrpcConfig {
+    fn default() -> Self {
+        GrpcConfig {
+            enabled: false,
+            port: 50051,
+            event_buffer: 1024,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct EventsConfig {
+    pub enabled: bool,
+    pub kafka_brokers: String,
+    pub topic: String,
+}
+
+impl Default for EventsConfig {
+    fn default() -> Self {
+        EventsConfig {
+            enabled: false,
+            kafka_brokers: "localhost:9092".to_string(),
+            topic: "guitar-channels-events".to_string(),
+        }
+    }
+}
+
+/// Republishes the changes of channels and videos, see `ChangeStreamPublisher` and
+/// `PostgresChangeStreamPublisher`.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct ChangeStreamConfig {
+    pub enabled: bool,
+    /// `kafka` or `nats`
+    pub sink: String,
+    pub kafka_brokers: String,
+    pub nats_url: String,
+    /// Kafka topic, or NATS subject prefix
+    pub topic: String,
+}
+
+impl Default for ChangeStreamConfig {
+    fn default() -> Self {
+        ChangeStreamConfig {
+            enabled: false,
+            sink: CHANGE_SINK_KAFKA.to_string(),
+            kafka_brokers: "localhost:9092".to_string(),
+            nats_url: "nats://localhost:4222".to_string(),
+            topic: "guitar-channels-changes".to_string(),
+        }
+    }
+}
+
+/// Limits for requests outside the quota-metered YouTube api.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    pub feed_requests_per_second: f64,
+    pub feed_burst: u32,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        RateLimitConfig {
+            feed_requests_per_second: 5.0,
+            feed_burst: 10,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct MonitoringConfig {
+    /// MongoDB commands slower than this are logged as warnings
+    pub slow_query_millis: u64,
+    /// Slows down scrapers and pauses background jobs while MongoDB is overloaded
+    pub backpressure: bool,
+    /// The database counts as overloaded above this average command latency
+    pub overload_latency_millis: u64,
+    /// or above this share of the connection pool in use or waited for
+    pub overload_pool_usage: f64,
+}
+
+impl Default for MonitoringConfig {
+    fn default() -> Self {
+        MonitoringConfig {
+            slow_query_millis: 500,
+            backpressure: false,
+            overload_latency_millis: 250,
+            overload_pool_usage: 0.9,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ProxyConfig {
+    /// `http://`, `https://` or `socks5://` urls, feed fetches rotate over them
+    pub urls: Vec<String>,
+}
+
+/// Limits of a single discovery run or scrape cycle, 0 is unlimited. An exhausted run stops and
+/// resumes on the next cycle.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct CrawlBudgetConfig {
+    pub max_api_units: u64,
+    /// Feeds in a scrape cycle, subscription listings in a discovery run
+    pub max_feed_fetches: u64,
+    pub max_seconds: u64,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct BudgetsConfig {
+    pub discovery: CrawlBudgetConfig,
+    pub scrape: CrawlBudgetConfig,
+}
+
+/// Minimum seconds between two detail updates of a stored video. Young videos change the most,
+/// so the gap grows with the age of the video. Hot videos are updated more often at any age.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct ScrapePolicy {
+    pub new_video_seconds: i64,
+    pub week_old_video_seconds: i64,
+    pub month_old_video_seconds: i64,
+    pub half_year_old_video_seconds: i64,
+    /// Average views per hour since upload from which a video counts as hot
+    pub hot_views_per_hour: f64,
+    pub hot_video_seconds: i64,
+}
+
+impl Default for ScrapePolicy {
+    fn default() -> Self {
+        ScrapePolicy {
+            new_video_seconds: 3 * 60 * 60,
+            week_old_video_seconds: 24 * 60 * 60,
+            month_old_video_seconds: 7 * 24 * 60 * 60,
+            half_year_old_video_seconds: 4 * 7 * 24 * 60 * 60,
+            hot_views_per_hour: 1000.0,
+            hot_video_seconds: 60 * 60,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// `json`, `discord` or `slack`
+    #[serde(default = "default_webhook_format")]
+    pub format: String,
+    /// Signs the request bodies with HMAC-SHA256 when set
+    pub secret: Option<String>,
+}
+
+fn default_webhook_format() -> String {
+    WEBHOOK_FORMAT_JSON.to_string()
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct NotificationsConfig {
+    pub webhooks: Vec<WebhookConfig>,
+    /// Consecutive failures of a scraper after which the webhooks are notified
+    pub crawl_failure_threshold: u32,
+    /// Receives the critical alerts when set
+    pub email: Option<EmailConfig>,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        NotificationsConfig {
+            webhooks: vec![],
+            crawl_failure_threshold: 10,
+            email: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct EmailConfig {
+    pub smtp_host: String,
+    #[serde(default = "default_smtp_port")]
+    pub smtp_port: u16,
+    /// `starttls`, `tls` for implicit TLS or `none`
+    #[serde(default = "default_smtp_security")]
+    pub security: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+    /// An alert is mailed again at most once in this many seconds
+    #[serde(default = "default_email_cooldown_seconds")]
+    pub cooldown_seconds: i64,
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
+
+fn default_smtp_security() -> String {
+    SMTP_SECURITY_STARTTLS.to_string()
+}
+
+fn default_email_cooldown_seconds() -> i64 {
+    60 * 60
+}
+
+/// Regexes with `artist` and `song` groups that recognize covered songs in video titles
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct SongRecognitionConfig {
+    pub rules: Vec<String>,
+}
+
+impl Default for SongRecognitionConfig {
+    fn default() -> Self {
+        SongRecognitionConfig {
+            rules: vec![
+                // Metallica – Nothing Else Matters (Guitar Cover)
+                r"(?i)^\s*(?P<artist>[^|(\[–—\-]+?)\s+[–—\-]\s+(?P<song>[^|(\[–—\-]+?)\s*(?:[|(\[]|[–—\-]\s).*\b(?:cover|lesson|tabs?|tutorial|playthrough)\b".to_string(),
+                // How to play Wonderwall by Oasis - Guitar Lesson
+                r"(?i)^\s*(?:how to play\s+)?(?P<song>[^|(\[–—\-]+?)\s+by\s+(?P<artist>[^|(\[–—\-]+?)\s*(?:[|(\[]|[–—\-]\s).*\b(?:cover|lesson|tabs?|tutorial|playthrough)\b".to_string(),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct ClassificationConfig {
+    /// `rules`, the only classifier so far
+    pub video_type_classifier: String,
+    /// `rules`, the only classifier so far
+    pub genre_classifier: String,
+}
+
+impl Default for ClassificationConfig {
+    fn default() -> Self {
+        ClassificationConfig {
+            video_type_classifier: VIDEO_TYPE_CLASSIFIER_RULES.to_string(),
+            genre_classifier: GENRE_CLASSIFIER_RULES.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct ProbationConfig {
+    /// New channels are tracked with their latest videos only until the probation job confirms
+    /// them
+    pub enabled: bool,
+    pub days: i64,
+}
+
+impl Default for ProbationConfig {
+    fn default() -> Self {
+        ProbationConfig {
+            enabled: false,
+            days: 14,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct UrlResolverConfig {
+    /// Shortened links in video descriptions are expanded before links and gear are mined
+    pub enabled: bool,
+    /// Minimum milliseconds between two requests to the same shortener
+    pub domain_interval_millis: u64,
+}
+
+impl Default for UrlResolverConfig {
+    fn default() -> Self {
+        UrlResolverConfig {
+            enabled: false,
+            domain_interval_millis: 1000,
+        }
+    }
+}
+
+/// Seconds between two runs of each crawler and job. Locks are leased for three intervals.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct IntervalsConfig {
+    pub additional: u64,
+    pub discovery: u64,
+    pub channel_update: u64,
+    /// Channels are polled on their own `nextScrapeAt`, this only bounds how late a due channel
+    /// is picked up.
+    pub new_video: u64,
+    pub backfill: u64,
+    pub captions: u64,
+    pub about: u64,
+    pub corpus_snapshot: u64,
+    pub video_archive: u64,
+    pub reclassification: u64,
+    pub reconciliation: u64,
+    pub related_channels: u64,
+    pub lifecycle: u64,
+    pub duplicates: u64,
+    pub topic_drift: u64,
+    pub stats_aggregation: u64,
+    pub comments: u64,
+    pub comment_sentiment: u64,
+    pub ban_evasion: u64,
+    pub link_verification: u64,
+    pub upload_pattern: u64,
+    pub metadata_refresh: u64,
+    pub genre_tags: u64,
+    pub reporting: u64,
+    pub alerts: u64,
+    pub end_screens: u64,
+    pub probation: u64,
+    pub courses: u64,
+}
+
+impl Default for IntervalsConfig {
+    fn default() -> Self {
+        IntervalsConfig {
+            additional: 10 * 60,
+            discovery: ONE_DAYS_IN_SECONDS,
+            channel_update: 15 * 60,
+            new_video: 15 * 60,
+            backfill: 10 * 60,
+            captions: 60 * 60,
+            about: 60 * 60,
+            corpus_snapshot: ONE_DAYS_IN_SECONDS,
+            video_archive: ONE_DAYS_IN_SECONDS,
+            reclassification: ONE_DAYS_IN_SECONDS,
+            reconciliation: ONE_DAYS_IN_SECONDS,
+            related_channels: ONE_DAYS_IN_SECONDS,
+            lifecycle: ONE_DAYS_IN_SECONDS,
+            duplicates: ONE_DAYS_IN_SECONDS,
+            topic_drift: ONE_DAYS_IN_SECONDS,
+            stats_aggregation: ONE_DAYS_IN_SECONDS,
+            comments: ONE_DAYS_IN_SECONDS,
+            comment_sentiment: 60 * 60,
+            ban_evasion: ONE_DAYS_IN_SECONDS,
+            link_verification: ONE_DAYS_IN_SECONDS,
+            upload_pattern: ONE_DAYS_IN_SECONDS,
+            metadata_refresh: 7 * ONE_DAYS_IN_SECONDS,
+            genre_tags: ONE_DAYS_IN_SECONDS,
+            reporting: ONE_DAYS_IN_SECONDS,
+            alerts: 5 * 60,
+            end_screens: ONE_DAYS_IN_SECONDS,
+            probation: ONE_DAYS_IN_SECONDS,
+            courses: ONE_DAYS_IN_SECONDS,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Config {
+    /// Only used by the mongodb storage backend
+    #[serde(default)]
     pub mongo_connection_string: String,
     pub environment: String,
     pub log_level: String,
     pub crawler: CrawlerConfig,
+    #[serde(default)]
+    pub strict_compliance: bool,
+    #[serde(default)]
+    pub admin_api: AdminApiConfig,
+    #[serde(default)]
+    pub youtube: YoutubeConfig,
+    #[serde(default)]
+    pub simulation: SimulationConfig,
+    #[serde(default)]
+    pub replay: ReplayConfig,
+    #[serde(default)]
+    pub maintenance: MaintenanceConfig,
+    #[serde(default)]
+    pub storage: StorageConfig,
+    #[serde(default)]
+    pub cache: CacheConfig,
+    #[serde(default)]
+    pub queue: QueueConfig,
+    #[serde(default)]
+    pub ingest: IngestConfig,
+    #[serde(default)]
+    pub grpc: GrpcConfig,
+    #[serde(default)]
+    pub events: EventsConfig,
+    #[serde(default)]
+    pub change_stream: ChangeStreamConfig,
+    #[serde(default)]
+    pub intervals: IntervalsConfig,
+    #[serde(default)]
+    pub api_keys: Vec<String>,
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    #[serde(default)]
+    pub proxy: ProxyConfig,
+    #[serde(default)]
+    pub monitoring: MonitoringConfig,
+    #[serde(default)]
+    pub scrape_policy: ScrapePolicy,
+    #[serde(default)]
+    pub budgets: BudgetsConfig,
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+    #[serde(default)]
+    pub song_recognition: SongRecognitionConfig,
+    #[serde(default)]
+    pub classification: ClassificationConfig,
+    #[serde(default)]
+    pub probation: ProbationConfig,
+    #[serde(default)]
+    pub url_resolver: UrlResolverConfig,
 }
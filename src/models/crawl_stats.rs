@@ -0,0 +1,11 @@
+/// Counts of a single scrape or discovery run of a channel, kept in the crawl audit log.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CrawlStats {
+    pub videos_seen: i64,
+    pub videos_updated: i64,
+    pub videos_skipped: i64,
+    /// Data API requests that were not served from the response cache
+    pub api_units: i64,
+    /// Video id and error of each video that failed
+    pub failed_videos: Vec<(String, String)>,
+}
@@ -0,0 +1,38 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// A crawl run that took long, from the crawl audit log.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlowCrawl {
+    pub channel_id: String,
+    /// One of the `CRAWL_KIND_` consts
+    pub kind: String,
+    pub duration_millis: i64,
+}
+
+/// Operational summary of the crawling in a period, usually the last day.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyDigest {
+    /// Unix timestamps of the start and end of the period
+    pub from: i64,
+    pub to: i64,
+    /// Channels the channel scraper decided on, by outcome
+    pub channels_discovered: i64,
+    pub channels_accepted: i64,
+    pub channels_rejected: i64,
+    /// Videos the video scraper stored or updated
+    pub videos_ingested: i64,
+    pub api_units: i64,
+    /// Failed crawl runs by `CrawlerError::category`
+    pub errors_by_category: BTreeMap<String, i64>,
+    pub slowest_crawls: Vec<SlowCrawl>,
+}
+
+impl DailyDigest {
+    pub fn error_count(&self) -> i64 {
+        self.errors_by_category.values().sum()
+    }
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// The last channel whose subscriptions a discovery pass checked, so an interrupted pass resumes
+/// after it instead of starting over.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveryCursor {
+    pub channel_id: String,
+    /// Channels checked in the pass so far
+    pub position: i64,
+}
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// Thresholds a discovered channel has to meet before it is queued for the channel scraper.
+/// Unset thresholds are not checked, so the default policy lets every channel through.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DiscoveryPolicy {
+    pub min_subscribers: Option<i64>,
+    pub max_subscribers: Option<i64>,
+    pub min_videos: Option<i64>,
+    /// Days since the latest upload after which a channel counts as dead
+    pub max_inactive_days: Option<i64>,
+}
+
+impl DiscoveryPolicy {
+    pub fn is_empty(&self) -> bool {
+        self == &DiscoveryPolicy::default()
+    }
+}
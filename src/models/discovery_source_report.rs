@@ -0,0 +1,26 @@
+/// Outcomes and quota cost of the channels one source queued for the channel scraper.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DiscoverySourceReport {
+    pub source: String,
+    pub channels: i64,
+    pub accepted: i64,
+    pub rejected: i64,
+    pub failed: i64,
+    /// Quota units the channel scraper spent vetting the channels
+    pub api_units: i64,
+}
+
+impl DiscoverySourceReport {
+    pub fn acceptance_rate(&self) -> f64 {
+        if self.channels == 0 {
+            return 0.0;
+        }
+
+        self.accepted as f64 / self.channels as f64
+    }
+
+    /// Returns `None` while the source has no accepted channel.
+    pub fn api_units_per_accepted(&self) -> Option<f64> {
+        (self.accepted > 0).then(|| self.api_units as f64 / self.accepted as f64)
+    }
+}
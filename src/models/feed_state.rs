@@ -0,0 +1,11 @@
+/// What the video scraper saw in the last feed response of a channel: the HTTP validators sent
+/// back as conditional headers, and the entries it processed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FeedState {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub content_hash: Option<String>,
+    pub video_ids: Vec<String>,
+    /// When the uploads playlist was last found empty along with the feed.
+    pub empty_playlist_checked_at: Option<i64>,
+}
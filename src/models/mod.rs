@@ -1,5 +1,20 @@
 pub mod apikey;
 pub mod config;
+pub mod crawl_stats;
+pub mod daily_digest;
+pub mod discovery_cursor;
+pub mod discovery_policy;
+pub mod discovery_source_report;
+pub mod feed_state;
+pub mod scrape_summary;
+pub mod tag_profile;
+pub mod upload_pattern;
 pub mod youtube_channel_details;
 pub mod youtube_channel_subscriptions;
+pub mod youtube_comment_threads;
+pub mod youtube_playlist_items;
+pub mod youtube_playlists;
+pub mod youtube_serde;
+pub mod youtube_timed_text;
+pub mod youtube_video_details;
 pub mod youtube_video_feed_response;
@@ -0,0 +1,24 @@
+use crate::models::crawl_stats::CrawlStats;
+
+/// Outcome of a video scrape. Entries that fail are collected instead of aborting the scrape of
+/// the other entries.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScrapeSummary {
+    pub updated: usize,
+    pub skipped: usize,
+    /// Video id and error of each failed entry
+    pub failed: Vec<(String, String)>,
+    pub api_units: u64,
+}
+
+impl From<&ScrapeSummary> for CrawlStats {
+    fn from(summary: &ScrapeSummary) -> Self {
+        CrawlStats {
+            videos_seen: (summary.updated + summary.skipped + summary.failed.len()) as i64,
+            videos_updated: summary.updated as i64,
+            videos_skipped: summary.skipped as i64,
+            api_units: summary.api_units as i64,
+            failed_videos: summary.failed.clone(),
+        }
+    }
+}
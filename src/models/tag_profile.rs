@@ -0,0 +1,14 @@
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: i64,
+    pub last_used_at: i64,
+}
+
+/// Keywords of a channel aggregated from the tags of its latest videos.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagProfile {
+    pub keywords: Vec<TagCount>,
+    pub tagged_video_count: i64,
+    pub specializations: Vec<String>,
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+/// An hour of the week the channel usually uploads in, in its local time.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadSlot {
+    /// Days from Monday
+    pub weekday: u32,
+    pub hour: u32,
+}
+
+/// When a channel typically uploads, computed from the publish times of its latest videos.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadPattern {
+    /// IANA name of the timezone the days and hours are in
+    pub timezone: String,
+    pub uploads: i64,
+    /// Uploads per day of the week, starting on Monday
+    pub weekdays: Vec<i64>,
+    /// Uploads per hour of the day
+    pub hours: Vec<i64>,
+    pub slots: Vec<UploadSlot>,
+}
@@ -1,5 +1,8 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::models::youtube_serde::{optional_count, optional_timestamp};
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct YouTubeChannelDetails {
@@ -9,6 +12,18 @@ pub struct YouTubeChannelDetails {
     pub items: Option<Vec<YoutubeStatisticsItem>>,
 }
 
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YouTubeChannelIds {
+    pub items: Option<Vec<ChannelIdItem>>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelIdItem {
+    pub id: String,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PageInfo {
@@ -25,6 +40,13 @@ pub struct YoutubeStatisticsItem {
     pub snippet: Snippet,
     pub statistics: Statistics,
     pub branding_settings: BrandingSettings,
+    pub topic_details: Option<TopicDetails>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopicDetails {
+    pub topic_categories: Option<Vec<String>>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -33,7 +55,8 @@ pub struct Snippet {
     pub title: String,
     pub description: Option<String>,
     pub custom_url: Option<String>,
-    pub published_at: String,
+    #[serde(default, with = "optional_timestamp")]
+    pub published_at: Option<DateTime<Utc>>,
     pub thumbnails: Thumbnails,
     pub localized: Localized,
     pub country: Option<String>,
@@ -81,10 +104,14 @@ pub struct Localized {
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Statistics {
-    pub view_count: String,
-    pub subscriber_count: Option<String>,
+    #[serde(default, with = "optional_count")]
+    pub view_count: Option<i64>,
+    /// Missing for channels hiding their subscribers
+    #[serde(default, with = "optional_count")]
+    pub subscriber_count: Option<i64>,
     pub hidden_subscriber_count: bool,
-    pub video_count: String,
+    #[serde(default, with = "optional_count")]
+    pub video_count: Option<i64>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -111,3 +138,75 @@ pub struct Channel {
 pub struct Image {
     pub banner_external_url: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::YouTubeChannelDetails;
+
+    // A channels.list response as recorded, without the localizations
+    const CHANNELS_RESPONSE: &str = r#"{
+      "kind": "youtube#channelListResponse",
+      "etag": "vmk6kd4N0C5rJhPpGW0ZNn-BK54",
+      "pageInfo": {"totalResults": 1, "resultsPerPage": 5},
+      "items": [
+        {
+          "kind": "youtube#channel",
+          "etag": "z5a1Xk3NQ-HCewLG4Zuk4xrYm1o",
+          "id": "UCmnlTWVJysjWPFiZhQ5uudg",
+          "snippet": {
+            "title": "Andy Guitar",
+            "description": "Guitar lessons for beginners",
+            "customUrl": "@andyguitar",
+            "publishedAt": "2013-03-14T21:17:31.000Z",
+            "thumbnails": {
+              "default": {"url": "https://yt3.ggpht.com/default.jpg", "width": 88, "height": 88},
+              "medium": {"url": "https://yt3.ggpht.com/medium.jpg", "width": 240, "height": 240},
+              "high": {"url": "https://yt3.ggpht.com/high.jpg", "width": 800, "height": 800}
+            },
+            "localized": {"title": "Andy Guitar", "description": "Guitar lessons for beginners"},
+            "country": "GB"
+          },
+          "statistics": {
+            "viewCount": "321467876",
+            "subscriberCount": "2110000",
+            "hiddenSubscriberCount": false,
+            "videoCount": "1028"
+          },
+          "brandingSettings": {
+            "channel": {"title": "Andy Guitar", "country": "GB"}
+          }
+        }
+      ]
+    }"#;
+
+    #[test]
+    fn parses_recorded_channels_response() {
+        let response = serde_json::from_str::<YouTubeChannelDetails>(CHANNELS_RESPONSE).unwrap();
+        let item = &response.items.unwrap()[0];
+
+        assert_eq!(
+            item.snippet.published_at,
+            Some(Utc.ymd(2013, 3, 14).and_hms(21, 17, 31))
+        );
+        assert_eq!(item.statistics.view_count, Some(321467876));
+        assert_eq!(item.statistics.subscriber_count, Some(2110000));
+        assert_eq!(item.statistics.video_count, Some(1028));
+    }
+
+    #[test]
+    fn hidden_subscribers_are_missing() {
+        let hidden = CHANNELS_RESPONSE
+            .replace(r#""subscriberCount": "2110000","#, "")
+            .replace(
+                r#""hiddenSubscriberCount": false"#,
+                r#""hiddenSubscriberCount": true"#,
+            );
+        let response = serde_json::from_str::<YouTubeChannelDetails>(&hidden).unwrap();
+        let statistics = &response.items.unwrap()[0].statistics;
+
+        assert!(statistics.hidden_subscriber_count);
+        assert_eq!(statistics.subscriber_count, None);
+    }
+}
@@ -0,0 +1,116 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::youtube_serde::optional_timestamp;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YouTubeCommentThreads {
+    pub next_page_token: Option<String>,
+    #[serde(default)]
+    pub items: Vec<CommentThread>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentThread {
+    pub id: String,
+    pub snippet: CommentThreadSnippet,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentThreadSnippet {
+    pub video_id: String,
+    pub top_level_comment: Comment,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Comment {
+    pub id: String,
+    pub snippet: CommentSnippet,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentSnippet {
+    /// Missing for commenters without a channel
+    pub author_channel_id: Option<AuthorChannelId>,
+    #[serde(default)]
+    pub text_original: String,
+    #[serde(default, with = "optional_timestamp")]
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorChannelId {
+    pub value: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::YouTubeCommentThreads;
+
+    // A commentThreads.list response, shortened to one item
+    const COMMENT_THREADS_RESPONSE: &str = r#"{
+      "kind": "youtube#commentThreadListResponse",
+      "etag": "2Fq4vYQxN2ZJ0m7XlKx3WcBqXbE",
+      "nextPageToken": "QURTSl9pM",
+      "items": [
+        {
+          "kind": "youtube#commentThread",
+          "etag": "q2cJmNn2b3Vx3fLhQ5yXqkK7xYw",
+          "id": "UgyB8Jx0lU1yQ2Nn0hF4AaABAg",
+          "snippet": {
+            "channelId": "UCmnlTWVJysjWPFiZhQ5uudg",
+            "videoId": "8SbUC-UaAxE",
+            "topLevelComment": {
+              "kind": "youtube#comment",
+              "etag": "r7Qm2hK0cG1yHf3dJ8sPq9xWbZk",
+              "id": "UgyB8Jx0lU1yQ2Nn0hF4AaABAg",
+              "snippet": {
+                "videoId": "8SbUC-UaAxE",
+                "textDisplay": "Great lesson, the &quot;fade out&quot; riff finally clicked",
+                "textOriginal": "Great lesson, the \"fade out\" riff finally clicked",
+                "authorDisplayName": "@riffer",
+                "authorChannelId": { "value": "UCriffer" },
+                "likeCount": 3,
+                "publishedAt": "2022-03-02T08:15:00Z",
+                "updatedAt": "2022-03-02T08:15:00Z"
+              }
+            },
+            "canReply": true,
+            "totalReplyCount": 0,
+            "isPublic": true
+          }
+        }
+      ]
+    }"#;
+
+    #[test]
+    fn parses_comment_threads_response() {
+        let response =
+            serde_json::from_str::<YouTubeCommentThreads>(COMMENT_THREADS_RESPONSE).unwrap();
+        let thread = &response.items[0];
+        let comment = &thread.snippet.top_level_comment.snippet;
+
+        assert_eq!(response.next_page_token.as_deref(), Some("QURTSl9pM"));
+        assert_eq!(thread.snippet.video_id, "8SbUC-UaAxE");
+        assert_eq!(
+            comment.author_channel_id.as_ref().unwrap().value,
+            "UCriffer"
+        );
+        assert_eq!(
+            comment.text_original,
+            "Great lesson, the \"fade out\" riff finally clicked"
+        );
+        assert_eq!(
+            comment.published_at,
+            Some(Utc.ymd(2022, 3, 2).and_hms(8, 15, 0))
+        );
+    }
+}
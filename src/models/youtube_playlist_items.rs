@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YouTubePlaylistItems {
+    pub kind: String,
+    pub etag: String,
+    pub next_page_token: Option<String>,
+    #[serde(default)]
+    pub items: Vec<PlaylistItem>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistItem {
+    pub kind: String,
+    pub etag: String,
+    pub id: String,
+    pub snippet: PlaylistItemSnippet,
+    pub content_details: PlaylistItemContentDetails,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistItemSnippet {
+    pub published_at: String,
+    pub title: String,
+    pub description: String,
+    #[serde(default)]
+    pub position: i64,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistItemContentDetails {
+    pub video_id: String,
+    pub video_published_at: Option<String>,
+}
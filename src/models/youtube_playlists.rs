@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YouTubePlaylists {
+    pub kind: String,
+    pub etag: String,
+    pub next_page_token: Option<String>,
+    #[serde(default)]
+    pub items: Vec<Playlist>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Playlist {
+    pub kind: String,
+    pub etag: String,
+    pub id: String,
+    pub snippet: PlaylistSnippet,
+    pub content_details: PlaylistContentDetails,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistSnippet {
+    pub published_at: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistContentDetails {
+    pub item_count: i64,
+}
@@ -0,0 +1,147 @@
+//! Serde helpers for the Data API, which returns counts as strings, durations in ISO 8601 and
+//! timestamps in RFC 3339. Values serialize back the way the api sent them. Values that fail to
+//! parse are logged and read as missing, so one odd field does not fail the whole response.
+
+use log::warn;
+use serde::{Deserialize, Deserializer};
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Count {
+    String(String),
+    Number(i64),
+}
+
+fn parse_count(count: Count) -> Option<i64> {
+    match count {
+        Count::String(value) => {
+            let count = value.parse::<i64>().ok();
+            if count.is_none() {
+                warn!("Ignore invalid count {:?} from the api", value);
+            }
+            count
+        }
+        Count::Number(value) => Some(value),
+    }
+}
+
+/// For counts the api leaves out, e.g. hidden likes, or sends in an unexpected format.
+pub mod optional_count {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Option<i64>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.serialize_str(&value.to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<i64>, D::Error> {
+        Ok(Option::<super::Count>::deserialize(deserializer)?.and_then(super::parse_count))
+    }
+}
+
+/// `contentDetails.duration` like `PT1H2M3S`, `P0D` for upcoming live streams.
+pub mod optional_duration {
+    use chrono::Duration;
+    use serde::{Deserializer, Serializer};
+
+    use crate::utils::duration_utils::parse_iso8601_duration;
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.serialize_str(&format!("PT{}S", value.num_seconds())),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        super::deserialize_optional_string(deserializer, |value| {
+            parse_iso8601_duration(value).map(Duration::seconds)
+        })
+    }
+}
+
+pub mod optional_timestamp {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<DateTime<Utc>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.serialize_str(&value.to_rfc3339()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<DateTime<Utc>>, D::Error> {
+        super::deserialize_optional_string(deserializer, |value| {
+            DateTime::parse_from_rfc3339(value)
+                .ok()
+                .map(|timestamp| timestamp.with_timezone(&Utc))
+        })
+    }
+}
+
+fn deserialize_optional_string<'de, D, T, F>(
+    deserializer: D,
+    parse: F,
+) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    F: Fn(&str) -> Option<T>,
+{
+    Ok(
+        Option::<String>::deserialize(deserializer)?.and_then(|value| {
+            let parsed = parse(&value);
+            if parsed.is_none() {
+                warn!("Ignore invalid value {:?} from the api", value);
+            }
+            parsed
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration, Utc};
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Values {
+        #[serde(default, with = "super::optional_count")]
+        count: Option<i64>,
+        #[serde(default, with = "super::optional_duration")]
+        duration: Option<Duration>,
+        #[serde(default, with = "super::optional_timestamp")]
+        timestamp: Option<DateTime<Utc>>,
+    }
+
+    #[test]
+    fn reads_invalid_values_as_missing() {
+        let values: Values = serde_json::from_str(
+            r#"{"count": "12k", "duration": "one hour", "timestamp": "yesterday"}"#,
+        )
+        .unwrap();
+        assert_eq!(values.count, None);
+        assert_eq!(values.duration, None);
+        assert_eq!(values.timestamp, None);
+
+        let values: Values =
+            serde_json::from_str(r#"{"count": "12", "duration": "PT1M2S"}"#).unwrap();
+        assert_eq!(values.count, Some(12));
+        assert_eq!(values.duration, Some(Duration::seconds(62)));
+        assert_eq!(values.timestamp, None);
+    }
+}
@@ -0,0 +1,14 @@
+use serde::Deserialize;
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct YoutubeTimedText {
+    #[serde(rename = "text", default)]
+    pub texts: Vec<TimedTextLine>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TimedTextLine {
+    pub start: f64,
+    #[serde(rename = "$value", default)]
+    pub value: String,
+}
@@ -0,0 +1,164 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::youtube_serde::{optional_count, optional_duration, optional_timestamp};
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YouTubeVideoDetails {
+    pub kind: String,
+    pub etag: String,
+    #[serde(default)]
+    pub items: Vec<YouTubeVideoItem>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YouTubeVideoItem {
+    pub kind: String,
+    pub etag: String,
+    pub id: String,
+    pub snippet: Option<VideoSnippet>,
+    pub content_details: Option<ContentDetails>,
+    pub statistics: Option<VideoStatistics>,
+    pub status: Option<VideoStatus>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoSnippet {
+    #[serde(default, with = "optional_timestamp")]
+    pub published_at: Option<DateTime<Utc>>,
+    pub channel_id: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentDetails {
+    #[serde(default, with = "optional_duration")]
+    pub duration: Option<Duration>,
+    pub definition: String,
+    pub caption: String,
+    pub licensed_content: bool,
+    pub region_restriction: Option<RegionRestriction>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegionRestriction {
+    pub allowed: Option<Vec<String>>,
+    pub blocked: Option<Vec<String>>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoStatus {
+    pub upload_status: String,
+    pub privacy_status: String,
+    pub embeddable: Option<bool>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoStatistics {
+    #[serde(default, with = "optional_count")]
+    pub view_count: Option<i64>,
+    #[serde(default, with = "optional_count")]
+    pub like_count: Option<i64>,
+    #[serde(default, with = "optional_count")]
+    pub comment_count: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+
+    use super::YouTubeVideoDetails;
+
+    // A videos.list response as recorded, shortened to one item
+    const VIDEOS_RESPONSE: &str = r#"{
+      "kind": "youtube#videoListResponse",
+      "etag": "8XTQ3y5p0SV7eL9zd-C2_xh1FEg",
+      "items": [
+        {
+          "kind": "youtube#video",
+          "etag": "nNmEH8cW8uFmIt7OB2ijmrSpxXY",
+          "id": "8SbUC-UaAxE",
+          "snippet": {
+            "publishedAt": "2022-03-01T17:00:12Z",
+            "channelId": "UCmnlTWVJysjWPFiZhQ5uudg",
+            "title": "Street Spirit (Fade Out) - Radiohead | Guitar Lesson",
+            "tags": ["guitar lesson", "radiohead"],
+            "categoryId": "10",
+            "liveBroadcastContent": "none"
+          },
+          "contentDetails": {
+            "duration": "PT14M32S",
+            "dimension": "2d",
+            "definition": "hd",
+            "caption": "false",
+            "licensedContent": true,
+            "contentRating": {},
+            "projection": "rectangular"
+          },
+          "status": {
+            "uploadStatus": "processed",
+            "privacyStatus": "public",
+            "license": "youtube",
+            "embeddable": true,
+            "publicStatsViewable": true,
+            "madeForKids": false
+          },
+          "statistics": {
+            "viewCount": "184523",
+            "likeCount": "6120",
+            "favoriteCount": "0",
+            "commentCount": "402"
+          }
+        }
+      ],
+      "pageInfo": {"totalResults": 1, "resultsPerPage": 1}
+    }"#;
+
+    #[test]
+    fn parses_recorded_videos_response() {
+        let response = serde_json::from_str::<YouTubeVideoDetails>(VIDEOS_RESPONSE).unwrap();
+        let item = &response.items[0];
+
+        let snippet = item.snippet.as_ref().unwrap();
+        assert_eq!(
+            snippet.published_at,
+            Some(Utc.ymd(2022, 3, 1).and_hms(17, 0, 12))
+        );
+        assert_eq!(
+            item.content_details.as_ref().unwrap().duration,
+            Some(Duration::seconds(872))
+        );
+
+        assert_eq!(item.status.as_ref().unwrap().embeddable, Some(true));
+
+        let statistics = item.statistics.as_ref().unwrap();
+        assert_eq!(statistics.view_count, Some(184523));
+        assert_eq!(statistics.like_count, Some(6120));
+        assert_eq!(statistics.comment_count, Some(402));
+    }
+
+    #[test]
+    fn hidden_likes_and_invalid_counts() {
+        let hidden_likes = VIDEOS_RESPONSE.replace(r#""likeCount": "6120","#, "");
+        let response = serde_json::from_str::<YouTubeVideoDetails>(&hidden_likes).unwrap();
+        assert_eq!(
+            response.items[0].statistics.as_ref().unwrap().like_count,
+            None
+        );
+
+        // The rest of the response is still read
+        let invalid_views = VIDEOS_RESPONSE.replace("\"184523\"", "\"many\"");
+        let response = serde_json::from_str::<YouTubeVideoDetails>(&invalid_views).unwrap();
+        let statistics = response.items[0].statistics.as_ref().unwrap();
+        assert_eq!(statistics.view_count, None);
+        assert_eq!(statistics.like_count, Some(6120));
+    }
+}
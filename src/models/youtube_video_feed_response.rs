@@ -1,5 +1,6 @@
 use serde::Deserialize;
 
+/// Fields are matched by their local name, so the `yt:` and `media:` prefixes do not matter.
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct YoutubeVideoFeedResponse {
@@ -10,35 +11,35 @@ pub struct YoutubeVideoFeedResponse {
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Entry {
-    #[serde(rename = "ytvideoId")]
     pub video_id: String,
+    #[serde(default)]
+    pub channel_id: Option<String>,
     pub title: String,
     pub published: String,
     pub updated: String,
-    #[serde(rename = "mediagroup")]
     pub group: MediaGroup,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MediaGroup {
-    #[serde(rename = "mediatitle")]
     pub title: String,
-    #[serde(rename = "mediadescription")]
+    /// Missing for videos without a description
+    #[serde(default)]
     pub description: String,
-    #[serde(rename = "mediacommunity")]
     pub community: MediaCommunity,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MediaCommunity {
-    #[serde(rename = "mediastatistics")]
     pub statistics: MediaStatistics,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MediaStatistics {
-    pub views: i64,
+    /// Missing for playlist fallback entries without video details
+    #[serde(default)]
+    pub views: Option<i64>,
 }
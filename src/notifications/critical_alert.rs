@@ -0,0 +1,74 @@
+use chrono::{TimeZone, Utc};
+
+/// Conditions that need an operator, mailed by the `EmailNotifier`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CriticalAlert {
+    QuotaExhausted {
+        paused_at: i64,
+        paused_until: i64,
+    },
+    QueueStuck {
+        queue: String,
+        depth: usize,
+        since: i64,
+    },
+    DatabaseUnreachable {
+        since: i64,
+    },
+}
+
+impl CriticalAlert {
+    /// Alerts with the same key share their rate limit.
+    pub fn key(&self) -> String {
+        match self {
+            CriticalAlert::QuotaExhausted { .. } => "quotaExhausted".to_string(),
+            CriticalAlert::QueueStuck { queue, .. } => format!("queueStuck:{}", queue),
+            CriticalAlert::DatabaseUnreachable { .. } => "databaseUnreachable".to_string(),
+        }
+    }
+
+    pub fn subject(&self) -> String {
+        match self {
+            CriticalAlert::QuotaExhausted { .. } => {
+                "[crawler] YouTube api quota exhausted".to_string()
+            }
+            CriticalAlert::QueueStuck { queue, .. } => {
+                format!("[crawler] Queue {} is stuck", queue)
+            }
+            CriticalAlert::DatabaseUnreachable { .. } => {
+                "[crawler] The database is unreachable".to_string()
+            }
+        }
+    }
+
+    pub fn body(&self) -> String {
+        match self {
+            CriticalAlert::QuotaExhausted {
+                paused_at,
+                paused_until,
+            } => format!(
+                "The YouTube api quota ran out at {}, all api calls are paused until {}.\n\nCheck the api keys and the quota spent by each crawler.",
+                to_rfc3339(*paused_at),
+                to_rfc3339(*paused_until)
+            ),
+            CriticalAlert::QueueStuck {
+                queue,
+                depth,
+                since,
+            } => format!(
+                "The {} queue holds {} commands and has not drained since {}.\n\nCheck that the scraper is running and not blocked.",
+                queue,
+                depth,
+                to_rfc3339(*since)
+            ),
+            CriticalAlert::DatabaseUnreachable { since } => format!(
+                "The database has not answered a ping since {}.\n\nCrawlers and jobs fail until it is reachable again.",
+                to_rfc3339(*since)
+            ),
+        }
+    }
+}
+
+fn to_rfc3339(timestamp: i64) -> String {
+    Utc.timestamp(timestamp, 0).to_rfc3339()
+}
@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use log::{error, info};
+use tokio::sync::Mutex;
+
+use crate::{
+    models::config::EmailConfig,
+    notifications::{critical_alert::CriticalAlert, smtp_client::SmtpClient},
+};
+
+/// Mails critical alerts to `notifications.email.to`. Each kind of alert is mailed at most once
+/// per cooldown, so a lasting outage does not flood the inbox. Failed deliveries are logged and
+/// retried on the next alert.
+pub struct EmailNotifier {
+    client: SmtpClient,
+    cooldown_seconds: i64,
+    last_sent: Mutex<HashMap<String, i64>>,
+}
+
+impl EmailNotifier {
+    pub fn new(config: EmailConfig) -> EmailNotifier {
+        EmailNotifier {
+            cooldown_seconds: config.cooldown_seconds,
+            client: SmtpClient::new(config),
+            last_sent: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn notify(&self, alert: &CriticalAlert) {
+        let key = alert.key();
+
+        if !self.reserve(&key, Utc::now().timestamp()).await {
+            info!("Skip mail for {}, one was sent recently", key);
+            return;
+        }
+
+        if let Err(e) = self.client.send(&alert.subject(), &alert.body()).await {
+            error!("Failed to mail alert {}: {}", key, e);
+            self.last_sent.lock().await.remove(&key);
+        }
+    }
+
+    /// Records the alert as sent unless it was sent within the cooldown.
+    async fn reserve(&self, key: &str, now: i64) -> bool {
+        let mut last_sent = self.last_sent.lock().await;
+
+        match last_sent.get(key) {
+            Some(sent_at) if now - sent_at < self.cooldown_seconds => false,
+            _ => {
+                last_sent.insert(key.to_string(), now);
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::config::EmailConfig;
+
+    use super::EmailNotifier;
+
+    #[tokio::test]
+    async fn rate_limits_each_alert() {
+        let notifier = EmailNotifier::new(EmailConfig {
+            smtp_host: "localhost".to_string(),
+            smtp_port: 25,
+            security: "none".to_string(),
+            username: None,
+            password: None,
+            from: "crawler@example.com".to_string(),
+            to: vec!["ops@example.com".to_string()],
+            cooldown_seconds: 3600,
+        });
+
+        assert!(notifier.reserve("quotaExhausted", 1000).await);
+        assert!(!notifier.reserve("quotaExhausted", 4000).await);
+        assert!(notifier.reserve("databaseUnreachable", 4000).await);
+        assert!(notifier.reserve("quotaExhausted", 4600).await);
+    }
+}
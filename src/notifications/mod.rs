@@ -0,0 +1,6 @@
+pub mod critical_alert;
+pub mod email_notifier;
+pub mod notification;
+pub mod notification_service;
+pub mod smtp_client;
+pub mod webhook_notifier;
@@ -0,0 +1,41 @@
+use serde::Serialize;
+
+use crate::models::daily_digest::DailyDigest;
+
+/// Payload of the `json` webhook format.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all_fields = "camelCase")]
+pub enum Notification {
+    ChannelAccepted {
+        channel_id: String,
+        title: String,
+        occurred_at: i64,
+    },
+    MilestoneReached {
+        channel_id: String,
+        milestone: i64,
+        subscribers: i64,
+        occurred_at: i64,
+    },
+    TopicDriftDetected {
+        channel_id: String,
+        title: String,
+        recent_density: f64,
+        baseline_density: f64,
+        occurred_at: i64,
+    },
+    CrawlFailures {
+        component: String,
+        consecutive_failures: u32,
+        last_error: String,
+        occurred_at: i64,
+    },
+    QuotaExhausted {
+        paused_until: i64,
+        occurred_at: i64,
+    },
+    DailyDigest {
+        digest: DailyDigest,
+        occurred_at: i64,
+    },
+}
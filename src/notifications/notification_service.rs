@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::Arc;
+
+use chrono::{TimeZone, Utc};
+use log::{error, info, warn};
+use tokio::sync::Mutex;
+
+use crate::{
+    events::{entity_event::EntityEvent, event_publisher::EventPublisher},
+    models::{config::NotificationsConfig, daily_digest::DailyDigest},
+    notifications::{
+        critical_alert::CriticalAlert, email_notifier::EmailNotifier, notification::Notification,
+        webhook_notifier::WebhookNotifier,
+    },
+};
+
+/// Sends significant crawl results to the configured webhooks. Milestones and topic drifts are
+/// also published as `MilestoneReached` and `TopicDriftDetected` events when events are enabled. Failed deliveries are logged and never
+/// fail the crawl. Critical alerts are mailed when `notifications.email` is set.
+pub struct NotificationService {
+    notifier: WebhookNotifier,
+    email_notifier: Option<EmailNotifier>,
+    event_publisher: Option<Arc<dyn EventPublisher>>,
+    crawl_failure_threshold: u32,
+    consecutive_failures: Mutex<HashMap<String, u32>>,
+}
+
+impl NotificationService {
+    pub fn new(
+        config: &NotificationsConfig,
+        event_publisher: Option<Arc<dyn EventPublisher>>,
+    ) -> NotificationService {
+        NotificationService {
+            notifier: WebhookNotifier::new(config.webhooks.clone()),
+            email_notifier: config.email.clone().map(EmailNotifier::new),
+            event_publisher,
+            crawl_failure_threshold: config.crawl_failure_threshold,
+            consecutive_failures: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn notify_channel_accepted(&self, channel_id: &str, title: &str) {
+        let notification = Notification::ChannelAccepted {
+            channel_id: channel_id.to_string(),
+            title: title.to_string(),
+            occurred_at: Utc::now().timestamp(),
+        };
+        let text = format!(
+            "New guitar channel {}: https://www.youtube.com/channel/{}",
+            title, channel_id
+        );
+
+        self.notifier.notify(&notification, &text);
+    }
+
+    pub async fn notify_milestone(
+        &self,
+        channel_id: &str,
+        title: &str,
+        milestone: i64,
+        subscribers: i64,
+    ) {
+        info!("Channel {} reached {} subscribers", channel_id, milestone);
+
+        let occurred_at = Utc::now().timestamp();
+
+        if let Some(event_publisher) = &self.event_publisher {
+            let event = EntityEvent::MilestoneReached {
+                channel_id: channel_id.to_string(),
+                milestone,
+                subscribers,
+                occurred_at,
+            };
+
+            if let Err(e) = event_publisher.publish(&event).await {
+                error!("Failed to publish event for channel {}: {}", channel_id, e);
+            }
+        }
+
+        let notification = Notification::MilestoneReached {
+            channel_id: channel_id.to_string(),
+            milestone,
+            subscribers,
+            occurred_at,
+        };
+        let text = format!(
+            "{} reached {} subscribers: https://www.youtube.com/channel/{}",
+            title, milestone, channel_id
+        );
+
+        self.notifier.notify(&notification, &text);
+    }
+
+    pub async fn notify_topic_drift(
+        &self,
+        channel_id: &str,
+        title: &str,
+        recent_density: f64,
+        baseline_density: f64,
+    ) {
+        info!(
+            "Channel {} drifted away from guitar content, {:.0}% of recent videos against {:.0}% before",
+            channel_id,
+            recent_density * 100.0,
+            baseline_density * 100.0
+        );
+
+        let occurred_at = Utc::now().timestamp();
+
+        if let Some(event_publisher) = &self.event_publisher {
+            let event = EntityEvent::TopicDriftDetected {
+                channel_id: channel_id.to_string(),
+                recent_density,
+                baseline_density,
+                occurred_at,
+            };
+
+            if let Err(e) = event_publisher.publish(&event).await {
+                error!("Failed to publish event for channel {}: {}", channel_id, e);
+            }
+        }
+
+        let notification = Notification::TopicDriftDetected {
+            channel_id: channel_id.to_string(),
+            title: title.to_string(),
+            recent_density,
+            baseline_density,
+            occurred_at,
+        };
+        let text = format!(
+            "{} may have stopped posting guitar content, {:.0}% of its recent videos mention guitar terms against {:.0}% before: https://www.youtube.com/channel/{}",
+            title,
+            recent_density * 100.0,
+            baseline_density * 100.0,
+            channel_id
+        );
+
+        self.notifier.notify(&notification, &text);
+    }
+
+    /// Counts consecutive failures of a component and notifies once when they reach the
+    /// threshold. A success resets the count.
+    pub async fn record_crawl_result<T, E: Display>(&self, component: &str, result: &Result<T, E>) {
+        let consecutive_failures = {
+            let mut failures = self.consecutive_failures.lock().await;
+
+            if result.is_ok() {
+                failures.remove(component);
+                return;
+            }
+
+            let count = failures.entry(component.to_string()).or_insert(0);
+            *count += 1;
+            *count
+        };
+
+        if consecutive_failures != self.crawl_failure_threshold {
+            return;
+        }
+
+        let last_error = result
+            .as_ref()
+            .err()
+            .map(|e| e.to_string())
+            .unwrap_or_default();
+        let notification = Notification::CrawlFailures {
+            component: component.to_string(),
+            consecutive_failures,
+            last_error: last_error.clone(),
+            occurred_at: Utc::now().timestamp(),
+        };
+        let text = format!(
+            "{} failed {} times in a row, last error: {}",
+            component, consecutive_failures, last_error
+        );
+
+        self.notifier.notify(&notification, &text);
+    }
+
+    pub async fn notify_quota_exhausted(&self, paused_until: i64) {
+        let notification = Notification::QuotaExhausted {
+            paused_until,
+            occurred_at: Utc::now().timestamp(),
+        };
+        let text = format!(
+            "YouTube api quota exhausted, crawling is paused until {}",
+            Utc.timestamp(paused_until, 0).to_rfc3339()
+        );
+
+        self.notifier.notify(&notification, &text);
+    }
+
+    pub async fn notify_daily_digest(&self, digest: &DailyDigest) {
+        let notification = Notification::DailyDigest {
+            digest: digest.clone(),
+            occurred_at: Utc::now().timestamp(),
+        };
+
+        let mut text = format!(
+            "Crawler digest since {}: {} channels discovered, {} accepted, {} rejected, {} videos ingested, {} api units spent, {} failed crawls",
+            Utc.timestamp(digest.from, 0).to_rfc3339(),
+            digest.channels_discovered,
+            digest.channels_accepted,
+            digest.channels_rejected,
+            digest.videos_ingested,
+            digest.api_units,
+            digest.error_count()
+        );
+        if !digest.errors_by_category.is_empty() {
+            let errors = digest
+                .errors_by_category
+                .iter()
+                .map(|(category, count)| format!("{} {}", category, count))
+                .collect::<Vec<String>>();
+            text.push_str(&format!(" ({})", errors.join(", ")));
+        }
+        if let Some(slowest) = digest.slowest_crawls.first() {
+            text.push_str(&format!(
+                ", slowest crawl {} of channel {} in {:.1}s",
+                slowest.kind,
+                slowest.channel_id,
+                slowest.duration_millis as f64 / 1000.0
+            ));
+        }
+
+        self.notifier.notify(&notification, &text);
+    }
+
+    pub async fn notify_critical(&self, alert: &CriticalAlert) {
+        warn!("Critical alert: {}", alert.subject());
+
+        if let Some(email_notifier) = &self.email_notifier {
+            email_notifier.notify(alert).await;
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use std::time::Duration;
+
+use anyhow::Error;
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::{
+        authentication::Credentials,
+        client::{Tls, TlsParameters},
+        extension::ClientId,
+    },
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+
+use crate::models::config::EmailConfig;
+
+pub const SMTP_SECURITY_STARTTLS: &str = "starttls";
+pub const SMTP_SECURITY_TLS: &str = "tls";
+pub const SMTP_SECURITY_NONE: &str = "none";
+
+const SEND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Sends plain text mails to the configured or given recipients over SMTP, with STARTTLS or
+/// implicit TLS and authentication when credentials are set.
+pub struct SmtpClient {
+    config: EmailConfig,
+}
+
+impl SmtpClient {
+    pub fn new(config: EmailConfig) -> SmtpClient {
+        SmtpClient { config }
+    }
+
+    pub async fn send(&self, subject: &str, body: &str) -> Result<(), Error> {
+        self.send_to(&self.config.to, subject, body).await
+    }
+
+    pub async fn send_to(&self, to: &[String], subject: &str, body: &str) -> Result<(), Error> {
+        let mut message = Message::builder()
+            .from(self.config.from.parse::<Mailbox>()?)
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN);
+        for recipient in to {
+            message = message.to(recipient.parse::<Mailbox>()?);
+        }
+        let message = message.body(body.to_string())?;
+
+        let host = &self.config.smtp_host;
+        let tls = match self.config.security.as_str() {
+            SMTP_SECURITY_TLS => Tls::Wrapper(TlsParameters::new(host.clone())?),
+            SMTP_SECURITY_STARTTLS => Tls::Required(TlsParameters::new(host.clone())?),
+            _ => Tls::None,
+        };
+
+        let mut transport = AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host)
+            .port(self.config.smtp_port)
+            .tls(tls)
+            .hello_name(ClientId::Domain(self.hostname().to_string()))
+            .timeout(Some(SEND_TIMEOUT));
+        if let (Some(username), Some(password)) = (&self.config.username, &self.config.password) {
+            transport = transport.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        transport.build().send(message).await?;
+
+        Ok(())
+    }
+
+    /// Domain of the sender, to greet the server with.
+    fn hostname(&self) -> &str {
+        self.config
+            .from
+            .rsplit_once('@')
+            .map(|(_, domain)| domain)
+            .unwrap_or("localhost")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    use crate::models::config::EmailConfig;
+
+    use super::{SmtpClient, SMTP_SECURITY_NONE};
+
+    #[tokio::test]
+    async fn sends_mail_with_auth() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut connection = BufReader::new(stream);
+            let mut received = vec![];
+            let mut in_data = false;
+
+            connection
+                .get_mut()
+                .write_all(b"220 smtp.test ready\r\n")
+                .await
+                .unwrap();
+
+            loop {
+                let mut line = String::new();
+                if connection.read_line(&mut line).await.unwrap() == 0 {
+                    break;
+                }
+                let line = line.trim_end().to_string();
+
+                if in_data {
+                    if line == "." {
+                        in_data = false;
+                        connection
+                            .get_mut()
+                            .write_all(b"250 queued\r\n")
+                            .await
+                            .unwrap();
+                    } else {
+                        received.push(line);
+                    }
+                    continue;
+                }
+
+                let reply: &[u8] = if line.starts_with("EHLO") {
+                    b"250-smtp.test\r\n250 AUTH PLAIN\r\n"
+                } else if line.starts_with("AUTH") {
+                    b"235 ok\r\n"
+                } else if line == "DATA" {
+                    in_data = true;
+                    b"354 go ahead\r\n"
+                } else if line == "QUIT" {
+                    b"221 bye\r\n"
+                } else {
+                    b"250 ok\r\n"
+                };
+                connection.get_mut().write_all(reply).await.unwrap();
+                received.push(line);
+            }
+
+            received
+        });
+
+        let client = SmtpClient::new(EmailConfig {
+            smtp_host: "127.0.0.1".to_string(),
+            smtp_port: port,
+            security: SMTP_SECURITY_NONE.to_string(),
+            username: Some("user".to_string()),
+            password: Some("secret".to_string()),
+            from: "crawler@example.com".to_string(),
+            to: vec!["ops@example.com".to_string()],
+            cooldown_seconds: 3600,
+        });
+
+        client
+            .send("Kontingent erschöpft", "First\n.hidden")
+            .await
+            .unwrap();
+
+        let received = server.await.unwrap();
+        assert_eq!(received[0], "EHLO example.com");
+        assert_eq!(received[1], "AUTH PLAIN AHVzZXIAc2VjcmV0");
+        assert_eq!(received[2], "MAIL FROM:<crawler@example.com>");
+        assert_eq!(received[3], "RCPT TO:<ops@example.com>");
+        assert_eq!(received[4], "DATA");
+        // Non-ascii subjects are encoded words
+        assert!(received.contains(&"Subject: Kontingent =?utf-8?b?ZXJzY2jDtnBmdA==?=".to_string()));
+        assert!(received.contains(&"..hidden".to_string()));
+        assert_eq!(received.last().unwrap(), "QUIT");
+    }
+}
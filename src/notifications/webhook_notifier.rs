@@ -0,0 +1,167 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Error};
+use hmac::{Hmac, Mac};
+use log::error;
+use reqwest::Client;
+use serde_json::{json, Value};
+use sha2::Sha256;
+use tokio_retry::{strategy::ExponentialBackoff, Retry};
+
+use crate::{models::config::WebhookConfig, notifications::notification::Notification};
+
+pub const WEBHOOK_FORMAT_JSON: &str = "json";
+pub const WEBHOOK_FORMAT_DISCORD: &str = "discord";
+pub const WEBHOOK_FORMAT_SLACK: &str = "slack";
+
+pub const SIGNATURE_HEADER: &str = "X-Signature-256";
+
+// Waits 1s, 2s and 4s between the attempts
+const RETRY_BASE_MILLIS: u64 = 2;
+const RETRY_FACTOR: u64 = 500;
+const MAX_RETRIES: usize = 3;
+const REQUEST_TIMEOUT_SECONDS: u64 = 10;
+
+/// Posts notifications to the configured webhooks. Discord and Slack webhooks get the message
+/// text, plain JSON webhooks get the notification itself. Bodies of webhooks with a secret are
+/// signed with HMAC-SHA256 in `X-Signature-256`. Deliveries run in the background, so a slow
+/// webhook never holds up the caller. Failed deliveries are retried, then logged.
+pub struct WebhookNotifier {
+    client: Client,
+    webhooks: Arc<Vec<WebhookConfig>>,
+}
+
+impl WebhookNotifier {
+    pub fn new(webhooks: Vec<WebhookConfig>) -> WebhookNotifier {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECONDS))
+            .build()
+            .unwrap_or_default();
+
+        WebhookNotifier {
+            client,
+            webhooks: Arc::new(webhooks),
+        }
+    }
+
+    pub fn notify(&self, notification: &Notification, text: &str) {
+        if self.webhooks.is_empty() {
+            return;
+        }
+
+        let client = self.client.clone();
+        let webhooks = self.webhooks.clone();
+        let notification = notification.clone();
+        let text = text.to_string();
+
+        tokio::spawn(async move { deliver(&client, &webhooks, &notification, &text).await });
+    }
+}
+
+async fn deliver(
+    client: &Client,
+    webhooks: &[WebhookConfig],
+    notification: &Notification,
+    text: &str,
+) {
+    for webhook in webhooks {
+        let body = match build_body(&webhook.format, notification, text) {
+            Ok(body) => body.to_string(),
+            Err(e) => {
+                error!("Failed to build notification for {}: {}", webhook.url, e);
+                continue;
+            }
+        };
+
+        let strategy = ExponentialBackoff::from_millis(RETRY_BASE_MILLIS)
+            .factor(RETRY_FACTOR)
+            .take(MAX_RETRIES);
+        let result = Retry::spawn(strategy, || send(client, webhook, &body)).await;
+
+        if let Err(e) = result {
+            error!("Failed to send notification to {}: {}", webhook.url, e);
+        }
+    }
+}
+
+async fn send(client: &Client, webhook: &WebhookConfig, body: &str) -> Result<(), Error> {
+    let mut request = client
+        .post(&webhook.url)
+        .header("Content-Type", "application/json")
+        .body(body.to_string());
+
+    if let Some(secret) = &webhook.secret {
+        request = request.header(SIGNATURE_HEADER, sign(secret, body)?);
+    }
+
+    let response = request.send().await?;
+
+    if !response.status().is_success() {
+        return Err(anyhow!("Webhook responded with {}", response.status()));
+    }
+
+    Ok(())
+}
+
+fn build_body(format: &str, notification: &Notification, text: &str) -> Result<Value, Error> {
+    match format {
+        WEBHOOK_FORMAT_DISCORD => Ok(json!({ "content": text })),
+        WEBHOOK_FORMAT_SLACK => Ok(json!({ "text": text })),
+        _ => Ok(serde_json::to_value(notification)?),
+    }
+}
+
+fn sign(secret: &str, body: &str) -> Result<String, Error> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())?;
+    mac.update(body.as_bytes());
+
+    Ok(format!(
+        "sha256={}",
+        hex::encode(mac.finalize().into_bytes())
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::notifications::notification::Notification;
+
+    #[test]
+    fn body_by_format() {
+        let notification = Notification::MilestoneReached {
+            channel_id: "channel".to_string(),
+            milestone: 10_000,
+            subscribers: 10_020,
+            occurred_at: 1650000000,
+        };
+
+        assert_eq!(
+            super::build_body("discord", &notification, "text").unwrap(),
+            json!({"content": "text"})
+        );
+        assert_eq!(
+            super::build_body("slack", &notification, "text").unwrap(),
+            json!({"text": "text"})
+        );
+        assert_eq!(
+            super::build_body("json", &notification, "text").unwrap(),
+            json!({
+                "type": "MilestoneReached",
+                "channelId": "channel",
+                "milestone": 10_000,
+                "subscribers": 10_020,
+                "occurredAt": 1650000000,
+            })
+        );
+    }
+
+    #[test]
+    fn signs_body_with_hmac_sha256() {
+        assert_eq!(
+            super::sign("key", "The quick brown fox jumps over the lazy dog").unwrap(),
+            "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+}
@@ -0,0 +1,25 @@
+use anyhow::Error;
+use async_trait::async_trait;
+
+use crate::commands::crawl_channel_command::CrawlChannelCommand;
+
+/// A command taken from the queue, to be acknowledged by its id once it is handled.
+#[derive(Debug)]
+pub struct QueuedChannelCommand {
+    pub id: String,
+    pub command: CrawlChannelCommand,
+}
+
+/// The commands the channel scraper works through.
+#[async_trait]
+pub trait ChannelCommandQueue: Send {
+    /// Waits for the next command. Returns `None` once no command can arrive anymore.
+    async fn receive(&mut self) -> Result<Option<QueuedChannelCommand>, Error>;
+
+    /// Commands that are received but never acknowledged may be delivered again.
+    async fn ack(&mut self, id: &str) -> Result<(), Error>;
+
+    /// Hands a received command back, to be received again after the commands queued before it.
+    /// Its idempotency key does not keep it out.
+    async fn retry(&mut self, queued: QueuedChannelCommand) -> Result<(), Error>;
+}
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+/// Commands of a channel are deduplicated within buckets of this length, and a key is dropped
+/// for this long after the first command with it.
+pub const IDEMPOTENCY_WINDOW_SECONDS: i64 = 60 * 60;
+
+/// The key of a crawl command from its channel, the options that change how it is crawled and the
+/// time bucket it is sent in. The source is left out, so a channel several crawlers find is
+/// crawled once.
+pub fn get_idempotency_key(channel_id: &str, ignore_guitar_terms: bool, now: i64) -> String {
+    format!(
+        "{}/{}/{}",
+        channel_id,
+        if ignore_guitar_terms {
+            "ignoreGuitarTerms"
+        } else {
+            "guitarTerms"
+        },
+        now.div_euclid(IDEMPOTENCY_WINDOW_SECONDS)
+    )
+}
+
+/// Remembers the idempotency keys of the commands an in-process queue passed on.
+#[derive(Default)]
+pub struct IdempotencyFilter {
+    seen: HashMap<String, i64>,
+}
+
+impl IdempotencyFilter {
+    pub fn new() -> IdempotencyFilter {
+        IdempotencyFilter::default()
+    }
+
+    /// Returns `false` for a key that passed before. Commands without a key always pass.
+    pub fn pass(&mut self, key: &str, now: i64) -> bool {
+        if key.is_empty() {
+            return true;
+        }
+
+        self.seen
+            .retain(|_, seen_at| now - *seen_at < IDEMPOTENCY_WINDOW_SECONDS);
+
+        if self.seen.contains_key(key) {
+            return false;
+        }
+
+        self.seen.insert(key.to_string(), now);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_idempotency_key, IdempotencyFilter, IDEMPOTENCY_WINDOW_SECONDS};
+
+    #[test]
+    fn key_by_channel_options_and_time_bucket() {
+        assert_eq!(get_idempotency_key("UC1", false, 7210), "UC1/guitarTerms/2");
+        assert_eq!(
+            get_idempotency_key("UC1", true, 7210),
+            "UC1/ignoreGuitarTerms/2"
+        );
+        assert_eq!(
+            get_idempotency_key("UC1", false, 7200),
+            get_idempotency_key("UC1", false, 7200 + IDEMPOTENCY_WINDOW_SECONDS - 1)
+        );
+        assert_ne!(
+            get_idempotency_key("UC1", false, 7199),
+            get_idempotency_key("UC1", false, 7200)
+        );
+    }
+
+    #[test]
+    fn filter_passes_keys_once() {
+        let mut filter = IdempotencyFilter::new();
+
+        assert!(filter.pass("UC1", 7190));
+        assert!(!filter.pass("UC1", 7210));
+        assert!(filter.pass("UC2", 7210));
+        assert!(filter.pass("", 7210));
+        assert!(filter.pass("", 7210));
+        assert!(!filter.pass("UC1", 7190 + IDEMPOTENCY_WINDOW_SECONDS - 1));
+        assert!(filter.pass("UC1", 7190 + IDEMPOTENCY_WINDOW_SECONDS));
+    }
+}
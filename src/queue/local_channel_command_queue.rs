@@ -0,0 +1,204 @@
+use std::collections::VecDeque;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use log::info;
+use tokio::sync::mpsc::Receiver;
+
+use crate::{
+    commands::crawl_channel_command::CrawlChannelCommand,
+    queue::{
+        channel_command_queue::{ChannelCommandQueue, QueuedChannelCommand},
+        idempotency_filter::IdempotencyFilter,
+    },
+};
+
+/// The in-process channel the crawlers of this instance send their commands to. Commands are
+/// lost when the process stops, so acknowledging does nothing. Commands with an idempotency key
+/// received before are dropped, unless they were handed back to be retried.
+pub struct LocalChannelCommandQueue {
+    rx: Receiver<CrawlChannelCommand>,
+    /// Commands taken from `rx` to queue a retried command after them
+    pending: VecDeque<CrawlChannelCommand>,
+    idempotency_filter: IdempotencyFilter,
+}
+
+impl LocalChannelCommandQueue {
+    pub fn new(rx: Receiver<CrawlChannelCommand>) -> LocalChannelCommandQueue {
+        LocalChannelCommandQueue {
+            rx,
+            pending: VecDeque::new(),
+            idempotency_filter: IdempotencyFilter::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl ChannelCommandQueue for LocalChannelCommandQueue {
+    async fn receive(&mut self) -> Result<Option<QueuedChannelCommand>, Error> {
+        loop {
+            let command = match self.pending.pop_front() {
+                Some(command) => command,
+                None => match self.rx.recv().await {
+                    Some(command) => command,
+                    None => return Ok(None),
+                },
+            };
+
+            if command.retries == 0
+                && !self
+                    .idempotency_filter
+                    .pass(&command.idempotency_key, Utc::now().timestamp())
+            {
+                info!(
+                    "Skip channel {}, already queued as {}",
+                    command.channel_id, command.idempotency_key
+                );
+                continue;
+            }
+
+            return Ok(Some(QueuedChannelCommand {
+                id: String::new(),
+                command,
+            }));
+        }
+    }
+
+    async fn ack(&mut self, _id: &str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    async fn retry(&mut self, mut queued: QueuedChannelCommand) -> Result<(), Error> {
+        while let Ok(command) = self.rx.try_recv() {
+            self.pending.push_back(command);
+        }
+
+        queued.command.retries += 1;
+        self.pending.push_back(queued.command);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc::channel;
+
+    use super::LocalChannelCommandQueue;
+    use crate::{
+        commands::crawl_channel_command::CrawlChannelCommand,
+        queue::channel_command_queue::ChannelCommandQueue,
+    };
+
+    #[tokio::test]
+    async fn receive_until_senders_are_dropped() {
+        let (tx, rx) = channel(1);
+        let mut queue = LocalChannelCommandQueue::new(rx);
+
+        tx.send(CrawlChannelCommand::new("UC1".to_string(), false, None))
+            .await
+            .unwrap();
+        drop(tx);
+
+        let queued = queue.receive().await.unwrap().unwrap();
+        assert_eq!(queued.command.channel_id, "UC1");
+        assert!(queue.ack(&queued.id).await.is_ok());
+        assert!(queue.receive().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn drop_commands_with_a_received_idempotency_key() {
+        let (tx, rx) = channel(4);
+        let mut queue = LocalChannelCommandQueue::new(rx);
+
+        for (channel_id, ignore_guitar_terms) in [
+            ("UC1", false),
+            ("UC1", false),
+            ("UC1", true),
+            ("UC2", false),
+        ] {
+            tx.send(CrawlChannelCommand::new(
+                channel_id.to_string(),
+                ignore_guitar_terms,
+                None,
+            ))
+            .await
+            .unwrap();
+        }
+        drop(tx);
+
+        let first = queue.receive().await.unwrap().unwrap().command;
+        assert_eq!(first.channel_id, "UC1");
+        assert!(!first.ignore_guitar_terms);
+        // The same channel with other options is another request
+        assert!(
+            queue
+                .receive()
+                .await
+                .unwrap()
+                .unwrap()
+                .command
+                .ignore_guitar_terms
+        );
+        assert_eq!(
+            queue.receive().await.unwrap().unwrap().command.channel_id,
+            "UC2"
+        );
+        assert!(queue.receive().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn receive_retried_command_after_the_queued_ones() {
+        let (tx, rx) = channel(4);
+        let mut queue = LocalChannelCommandQueue::new(rx);
+
+        for channel_id in ["UC1", "UC2"] {
+            tx.send(CrawlChannelCommand::new(
+                channel_id.to_string(),
+                false,
+                None,
+            ))
+            .await
+            .unwrap();
+        }
+
+        let queued = queue.receive().await.unwrap().unwrap();
+        queue.retry(queued).await.unwrap();
+        tx.send(CrawlChannelCommand::new("UC1".to_string(), false, None))
+            .await
+            .unwrap();
+        drop(tx);
+
+        assert_eq!(
+            queue.receive().await.unwrap().unwrap().command.channel_id,
+            "UC2"
+        );
+        let retried = queue.receive().await.unwrap().unwrap().command;
+        assert_eq!(retried.channel_id, "UC1");
+        assert_eq!(retried.retries, 1);
+        // Sent again within the window, the channel is still dropped
+        assert!(queue.receive().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn drop_a_channel_sent_again_by_another_source() {
+        let (tx, rx) = channel(2);
+        let mut queue = LocalChannelCommandQueue::new(rx);
+
+        for source in ["discovery", "import"] {
+            tx.send(CrawlChannelCommand::new(
+                "UC1".to_string(),
+                false,
+                Some(source.to_string()),
+            ))
+            .await
+            .unwrap();
+        }
+        drop(tx);
+
+        let first = queue.receive().await.unwrap().unwrap().command;
+        assert_eq!(first.source.as_deref(), Some("discovery"));
+        assert!(queue.receive().await.unwrap().is_none());
+    }
+}
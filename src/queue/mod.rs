@@ -0,0 +1,4 @@
+pub mod channel_command_queue;
+pub mod idempotency_filter;
+pub mod local_channel_command_queue;
+pub mod redis_channel_command_queue;
@@ -0,0 +1,314 @@
+use std::collections::VecDeque;
+
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
+use log::{error, info, warn};
+use redis::{
+    aio::ConnectionManager,
+    streams::{StreamId, StreamRangeReply, StreamReadReply},
+    FromRedisValue, Value,
+};
+use tokio::{sync::mpsc::Receiver, task, task::JoinHandle};
+
+use crate::{
+    commands::crawl_channel_command::CrawlChannelCommand,
+    models::config::QueueConfig,
+    queue::{
+        channel_command_queue::{ChannelCommandQueue, QueuedChannelCommand},
+        idempotency_filter::IDEMPOTENCY_WINDOW_SECONDS,
+    },
+};
+
+const COMMAND_FIELD: &str = "command";
+const READ_COUNT: usize = 10;
+const BLOCK_MILLIS: usize = 5000;
+
+/// Shares channel commands between crawler instances through a Redis stream. The channel
+/// scrapers read it as one consumer group, so each command is scraped once, and acknowledge a
+/// command after scraping it, which deletes it from the stream. Commands of a consumer that
+/// stopped before acknowledging them are claimed by another consumer once they idled for
+/// `claim_idle_seconds`. Commands with an idempotency key that any instance added before are not
+/// added again. A retried command is added to the end of the stream as a new entry. Once the
+/// stream holds `max_length` commands, new ones are rejected instead of trimming commands nobody
+/// acknowledged yet.
+pub struct RedisChannelCommandQueue {
+    connection: ConnectionManager,
+    stream: String,
+    max_length: u64,
+    group: String,
+    consumer: String,
+    claim_idle_millis: u64,
+    received: VecDeque<QueuedChannelCommand>,
+    has_read_pending: bool,
+}
+
+impl RedisChannelCommandQueue {
+    pub async fn connect(
+        url: &str,
+        config: &QueueConfig,
+    ) -> Result<RedisChannelCommandQueue, Error> {
+        let client = redis::Client::open(url)?;
+        let mut connection = ConnectionManager::new(client).await?;
+
+        let created: redis::RedisResult<()> = redis::cmd("XGROUP")
+            .arg("CREATE")
+            .arg(&config.stream)
+            .arg(&config.group)
+            .arg("$")
+            .arg("MKSTREAM")
+            .query_async(&mut connection)
+            .await;
+
+        match created {
+            Err(e) if e.code() != Some("BUSYGROUP") => return Err(e.into()),
+            _ => {}
+        }
+
+        let consumer = config
+            .consumer
+            .clone()
+            .or_else(|| std::env::var("HOSTNAME").ok())
+            .unwrap_or_else(|| format!("crawler-{}", std::process::id()));
+
+        info!(
+            "Consume channel commands from redis stream {} as {} in group {}",
+            config.stream, consumer, config.group
+        );
+
+        Ok(RedisChannelCommandQueue {
+            connection,
+            stream: config.stream.clone(),
+            max_length: config.max_length,
+            group: config.group.clone(),
+            consumer,
+            claim_idle_millis: config.claim_idle_seconds * 1000,
+            received: VecDeque::new(),
+            has_read_pending: false,
+        })
+    }
+
+    /// Adds the commands the crawlers of this instance send to the stream, until all senders
+    /// are dropped.
+    pub fn spawn_forwarder(&self, mut rx: Receiver<CrawlChannelCommand>) -> JoinHandle<()> {
+        let mut connection = self.connection.clone();
+        let stream = self.stream.clone();
+        let max_length = self.max_length;
+
+        task::spawn(async move {
+            while let Some(command) = rx.recv().await {
+                if let Err(e) = add_command(&mut connection, &stream, max_length, &command).await {
+                    error!(
+                        "Failed to add channel {} to redis stream {}: {}",
+                        command.channel_id, stream, e
+                    );
+                }
+            }
+        })
+    }
+
+    /// Reads new commands with `>`, or the commands delivered to this consumer before with `0`.
+    async fn read_group(&mut self, id: &str) -> Result<Vec<StreamId>, Error> {
+        let mut cmd = redis::cmd("XREADGROUP");
+        cmd.arg("GROUP")
+            .arg(&self.group)
+            .arg(&self.consumer)
+            .arg("COUNT")
+            .arg(READ_COUNT);
+
+        if id == ">" {
+            cmd.arg("BLOCK").arg(BLOCK_MILLIS);
+        }
+
+        let reply: Option<StreamReadReply> = cmd
+            .arg("STREAMS")
+            .arg(&self.stream)
+            .arg(id)
+            .query_async(&mut self.connection)
+            .await?;
+
+        Ok(reply
+            .map(|reply| reply.keys.into_iter().flat_map(|key| key.ids).collect())
+            .unwrap_or_default())
+    }
+
+    async fn claim_idle(&mut self) -> Result<Vec<StreamId>, Error> {
+        let reply: Value = redis::cmd("XAUTOCLAIM")
+            .arg(&self.stream)
+            .arg(&self.group)
+            .arg(&self.consumer)
+            .arg(self.claim_idle_millis)
+            .arg("0-0")
+            .arg("COUNT")
+            .arg(READ_COUNT)
+            .query_async(&mut self.connection)
+            .await?;
+
+        // The reply holds the cursor to continue from, then the claimed entries
+        match reply {
+            Value::Bulk(items) if items.len() > 1 => {
+                Ok(StreamRangeReply::from_redis_value(&items[1])?.ids)
+            }
+            _ => Ok(vec![]),
+        }
+    }
+
+    async fn parse_entries(&mut self, entries: Vec<StreamId>) -> Result<(), Error> {
+        for entry in entries {
+            let command = entry
+                .get::<String>(COMMAND_FIELD)
+                .map(|json| serde_json::from_str::<CrawlChannelCommand>(&json));
+
+            match command {
+                Some(Ok(command)) => self.received.push_back(QueuedChannelCommand {
+                    id: entry.id,
+                    command,
+                }),
+                _ => {
+                    warn!(
+                        "Drop invalid channel command {} from redis stream",
+                        entry.id
+                    );
+                    self.ack(&entry.id).await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ChannelCommandQueue for RedisChannelCommandQueue {
+    /// The stream stays open, so this only returns commands.
+    async fn receive(&mut self) -> Result<Option<QueuedChannelCommand>, Error> {
+        loop {
+            if let Some(command) = self.received.pop_front() {
+                return Ok(Some(command));
+            }
+
+            let entries = if !self.has_read_pending {
+                let entries = self.read_group("0").await?;
+                self.has_read_pending = entries.is_empty();
+                entries
+            } else {
+                let claimed = self.claim_idle().await?;
+                if claimed.is_empty() {
+                    self.read_group(">").await?
+                } else {
+                    claimed
+                }
+            };
+
+            self.parse_entries(entries).await?;
+        }
+    }
+
+    /// Acknowledged commands are not read again by the group, so they are deleted right away.
+    async fn ack(&mut self, id: &str) -> Result<(), Error> {
+        redis::pipe()
+            .atomic()
+            .cmd("XACK")
+            .arg(&self.stream)
+            .arg(&self.group)
+            .arg(id)
+            .ignore()
+            .cmd("XDEL")
+            .arg(&self.stream)
+            .arg(id)
+            .ignore()
+            .query_async::<_, ()>(&mut self.connection)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn retry(&mut self, mut queued: QueuedChannelCommand) -> Result<(), Error> {
+        queued.command.retries += 1;
+
+        redis::pipe()
+            .atomic()
+            .cmd("XADD")
+            .arg(&self.stream)
+            .arg("*")
+            .arg(COMMAND_FIELD)
+            .arg(serde_json::to_string(&queued.command)?)
+            .ignore()
+            .cmd("XACK")
+            .arg(&self.stream)
+            .arg(&self.group)
+            .arg(&queued.id)
+            .ignore()
+            .cmd("XDEL")
+            .arg(&self.stream)
+            .arg(&queued.id)
+            .ignore()
+            .query_async::<_, ()>(&mut self.connection)
+            .await?;
+
+        Ok(())
+    }
+}
+
+async fn add_command(
+    connection: &mut ConnectionManager,
+    stream: &str,
+    max_length: u64,
+    command: &CrawlChannelCommand,
+) -> Result<(), Error> {
+    // Acknowledged commands are deleted, so every command in the stream still has to be scraped
+    let length: u64 = redis::cmd("XLEN")
+        .arg(stream)
+        .query_async(connection)
+        .await?;
+    if length >= max_length {
+        return Err(anyhow!("stream is full with {} commands", length));
+    }
+
+    let idempotency_key = format!("{}:idempotency:{}", stream, command.idempotency_key);
+    if !command.idempotency_key.is_empty() {
+        let claimed: Option<String> = redis::cmd("SET")
+            .arg(&idempotency_key)
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg(IDEMPOTENCY_WINDOW_SECONDS)
+            .query_async(connection)
+            .await?;
+
+        if claimed.is_none() {
+            info!(
+                "Skip channel {}, already queued as {}",
+                command.channel_id, command.idempotency_key
+            );
+            return Ok(());
+        }
+    }
+
+    let added = redis::cmd("XADD")
+        .arg(stream)
+        .arg("*")
+        .arg(COMMAND_FIELD)
+        .arg(serde_json::to_string(command)?)
+        .query_async::<_, String>(connection)
+        .await;
+
+    // A claimed key without its command would keep the channel out for the whole window
+    if let Err(e) = added {
+        if !command.idempotency_key.is_empty() {
+            if let Err(del_error) = redis::cmd("DEL")
+                .arg(&idempotency_key)
+                .query_async::<_, ()>(connection)
+                .await
+            {
+                warn!(
+                    "Failed to release idempotency key {}: {}",
+                    idempotency_key, del_error
+                );
+            }
+        }
+
+        return Err(e.into());
+    }
+
+    Ok(())
+}
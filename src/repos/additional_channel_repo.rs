@@ -1,8 +1,10 @@
 use anyhow::Error;
+use async_trait::async_trait;
 use futures::stream::TryStreamExt;
 use mongodb::bson::{doc, Document};
 use mongodb::{Client, Collection};
 
+use crate::repos::additional_channel_store::AdditionalChannelStore;
 use crate::utils::db::get_db_name;
 
 pub struct AdditionalChannelRepository {
@@ -11,13 +13,16 @@ pub struct AdditionalChannelRepository {
 
 impl AdditionalChannelRepository {
     pub fn new(client: &Client, environment: &str) -> AdditionalChannelRepository {
-        let db = client.database(&get_db_name(&environment));
+        let db = client.database(&get_db_name(environment));
         let feeds = db.collection::<Document>("additional");
 
         AdditionalChannelRepository { collection: feeds }
     }
+}
 
-    pub async fn exists(&self, channel_id: &str) -> Result<bool, Error> {
+#[async_trait]
+impl AdditionalChannelStore for AdditionalChannelRepository {
+    async fn exists(&self, channel_id: &str) -> Result<bool, Error> {
         let result = self
             .collection
             .count_documents(doc! { "_id": channel_id }, None)
@@ -26,14 +31,41 @@ impl AdditionalChannelRepository {
         Ok(result > 0)
     }
 
-    pub async fn get_all(&self) -> Result<Vec<Document>, Error> {
+    async fn is_ignoring_guitar_terms(&self, channel_id: &str) -> Result<bool, Error> {
+        let additional_channel = self
+            .collection
+            .find_one(doc! { "_id": channel_id }, None)
+            .await?;
+
+        Ok(additional_channel
+            .and_then(|channel| channel.get_bool("ignoreGuitarTerm").ok())
+            .unwrap_or(false))
+    }
+
+    async fn get_all(&self) -> Result<Vec<Document>, Error> {
         let cursor = self.collection.find(None, None).await?;
         let additional_channels: Vec<Document> = cursor.try_collect().await?;
 
         Ok(additional_channels)
     }
 
-    pub async fn delete_one(&self, id: &str) -> Result<(), Error> {
+    async fn insert(&self, channel_id: &str, ignore_guitar_terms: bool) -> Result<(), Error> {
+        let update_options = mongodb::options::UpdateOptions::builder()
+            .upsert(true)
+            .build();
+
+        self.collection
+            .update_one(
+                doc! {"_id": channel_id},
+                doc! {"$set": {"ignoreGuitarTerm": ignore_guitar_terms}},
+                update_options,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_one(&self, id: &str) -> Result<(), Error> {
         let filter = doc! {"_id": id};
         self.collection.delete_one(filter, None).await?;
 
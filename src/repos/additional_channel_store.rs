@@ -0,0 +1,20 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::Document;
+
+/// Channels added for crawling by hand or by the discovery sources that feed the additional channel
+/// crawler. Implemented for MongoDB by `AdditionalChannelRepository` and for PostgreSQL by
+/// `PostgresAdditionalChannelStore`.
+#[async_trait]
+pub trait AdditionalChannelStore: Send + Sync {
+    async fn exists(&self, channel_id: &str) -> Result<bool, Error>;
+
+    /// Channels added with `ignoreGuitarTerm` stay guitar channels without a guitar term.
+    async fn is_ignoring_guitar_terms(&self, channel_id: &str) -> Result<bool, Error>;
+
+    async fn get_all(&self) -> Result<Vec<Document>, Error>;
+
+    async fn insert(&self, channel_id: &str, ignore_guitar_terms: bool) -> Result<(), Error>;
+
+    async fn delete_one(&self, id: &str) -> Result<(), Error>;
+}
@@ -0,0 +1,36 @@
+use anyhow::Error;
+use async_trait::async_trait;
+
+use crate::{errors::crawler_error::ApiErrorReason, models::apikey::ApiKey};
+
+/// The api keys of the YouTube service, implemented for MongoDB by `ApiKeyRepository` and for
+/// PostgreSQL by `PostgresApiKeyStore`.
+#[async_trait]
+pub trait ApiKeyStore: Send + Sync {
+    async fn get_all(&self) -> Result<Vec<ApiKey>, Error>;
+
+    /// Registers a key, a known key only gets the new daily quota.
+    async fn upsert(&self, key: &str, daily_quota: i32) -> Result<(), Error>;
+
+    /// Degraded keys are only used when no healthy key is left, disabled keys never. Keys out
+    /// of quota come last, calls with them wait for the quota breaker.
+    async fn get_least_used_api_key(&self) -> Result<ApiKey, Error>;
+
+    /// Counts the enabled keys with quota left.
+    async fn count_with_quota(&self, now: i64) -> Result<u64, Error>;
+
+    async fn set_quota_exceeded(&self, api_key: &ApiKey, until: i64) -> Result<(), Error>;
+
+    /// Makes a key that failed before healthy again, a disabled key stays disabled.
+    async fn record_success(&self, api_key: &ApiKey) -> Result<(), Error>;
+
+    /// Returns the health of the key after the failed call.
+    async fn record_error(
+        &self,
+        api_key: &ApiKey,
+        reason: ApiErrorReason,
+        now: i64,
+    ) -> Result<&'static str, Error>;
+
+    async fn update_usage(&self, api_key: &ApiKey) -> Result<(), Error>;
+}
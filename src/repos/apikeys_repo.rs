@@ -1,12 +1,21 @@
-use anyhow::Error;
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use chrono_tz::{Tz, US::Pacific};
-use mongodb::bson::doc;
-use mongodb::options::FindOneOptions;
+use futures::stream::TryStreamExt;
+use mongodb::bson::{doc, Bson, Document};
+use mongodb::options::{FindOneOptions, FindOptions};
 use mongodb::{Client, Collection};
 
+use crate::errors::crawler_error::ApiErrorReason;
 use crate::models::apikey::ApiKey;
-use crate::utils::db::get_db_name;
+use crate::repos::apikey_store::ApiKeyStore;
+use crate::utils::{
+    api_key_health_utils::{
+        get_health_after_error, API_KEY_DEGRADED, API_KEY_DISABLED, API_KEY_HEALTHY,
+    },
+    db::get_db_name,
+};
 
 pub struct ApiKeyRepository {
     collection: Collection<ApiKey>,
@@ -14,29 +23,140 @@ pub struct ApiKeyRepository {
 
 impl ApiKeyRepository {
     pub fn new(client: &Client, environment: &str) -> ApiKeyRepository {
-        let db = client.database(&get_db_name(&environment));
+        let db = client.database(&get_db_name(environment));
         let channels = db.collection::<ApiKey>("apikeys");
 
         ApiKeyRepository {
             collection: channels,
         }
     }
+}
+
+#[async_trait]
+impl ApiKeyStore for ApiKeyRepository {
+    async fn get_all(&self) -> Result<Vec<ApiKey>, Error> {
+        let find_options = FindOptions::builder().sort(doc! {"_id": 1}).build();
+        let cursor = self.collection.find(doc! {}, find_options).await?;
+
+        Ok(cursor.try_collect().await?)
+    }
+
+    async fn upsert(&self, key: &str, daily_quota: i32) -> Result<(), Error> {
+        let update_options = mongodb::options::UpdateOptions::builder()
+            .upsert(true)
+            .build();
+
+        self.collection
+            .update_one(
+                doc! {"_id": key},
+                doc! {
+                    "$set": {"daily_quota": daily_quota},
+                    "$setOnInsert": {
+                        "used_quota": 0,
+                        "pdt_day": 0,
+                        "health": API_KEY_HEALTHY,
+                        "consecutive_errors": 0,
+                    },
+                },
+                update_options,
+            )
+            .await?;
+
+        Ok(())
+    }
 
-    pub async fn get_least_used_api_key(&self) -> Result<ApiKey, Error> {
+    async fn get_least_used_api_key(&self) -> Result<ApiKey, Error> {
         let find_options = FindOneOptions::builder()
             .sort(doc! { "pdt_day": 1, "used_quota": 1 })
             .build();
+        let with_quota = get_with_quota_filter(Utc::now().timestamp());
+
+        for filter in [
+            doc! {"health": {"$nin": [API_KEY_DEGRADED, API_KEY_DISABLED]}, "$or": with_quota.clone()},
+            doc! {"health": API_KEY_DEGRADED, "$or": with_quota},
+            doc! {"health": {"$ne": API_KEY_DISABLED}},
+        ] {
+            if let Some(api_key) = self
+                .collection
+                .find_one(filter, find_options.clone())
+                .await?
+            {
+                return Ok(api_key);
+            }
+        }
+
+        Err(anyhow!("No enabled api key left"))
+    }
 
-        let doc = self
+    async fn count_with_quota(&self, now: i64) -> Result<u64, Error> {
+        let count = self
             .collection
-            .find_one(doc! {}, find_options)
-            .await?
-            .unwrap();
+            .count_documents(
+                doc! {"health": {"$ne": API_KEY_DISABLED}, "$or": get_with_quota_filter(now)},
+                None,
+            )
+            .await?;
+
+        Ok(count)
+    }
+
+    async fn set_quota_exceeded(&self, api_key: &ApiKey, until: i64) -> Result<(), Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": &api_key.key},
+                doc! {"$set": {"quota_exceeded_until": until}},
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn record_success(&self, api_key: &ApiKey) -> Result<(), Error> {
+        if api_key.consecutive_errors == 0 && api_key.health == API_KEY_HEALTHY {
+            return Ok(());
+        }
+
+        self.collection
+            .update_one(
+                doc! {"_id": &api_key.key, "health": {"$ne": API_KEY_DISABLED}},
+                doc! {"$set": {"health": API_KEY_HEALTHY, "consecutive_errors": 0}},
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn record_error(
+        &self,
+        api_key: &ApiKey,
+        reason: ApiErrorReason,
+        now: i64,
+    ) -> Result<&'static str, Error> {
+        let health = get_health_after_error(reason, api_key.consecutive_errors + 1);
+
+        let mut set = doc! {
+            "health": health,
+            "last_error": reason.name(),
+            "last_error_at": now,
+        };
+        if health == API_KEY_DISABLED {
+            set.insert("disabled_at", now);
+        }
 
-        Ok(doc)
+        self.collection
+            .update_one(
+                doc! {"_id": &api_key.key, "health": {"$ne": API_KEY_DISABLED}},
+                doc! {"$set": set, "$inc": {"consecutive_errors": 1}},
+                None,
+            )
+            .await?;
+
+        Ok(health)
     }
 
-    pub async fn update_usage(&self, api_key: &ApiKey) -> Result<(), Error> {
+    async fn update_usage(&self, api_key: &ApiKey) -> Result<(), Error> {
         let pacific_now: DateTime<Tz> = Utc::now().with_timezone(&Pacific);
         let pacific_date = pacific_now
             .format("%Y%m%d")
@@ -66,3 +186,10 @@ impl ApiKeyRepository {
         Ok(())
     }
 }
+
+fn get_with_quota_filter(now: i64) -> Vec<Document> {
+    vec![
+        doc! {"quota_exceeded_until": Bson::Null},
+        doc! {"quota_exceeded_until": {"$lte": now}},
+    ]
+}
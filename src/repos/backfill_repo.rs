@@ -0,0 +1,125 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use futures::stream::TryStreamExt;
+use mongodb::bson::{doc, DateTime, Document};
+use mongodb::options::{FindOneOptions, FindOptions, UpdateOptions};
+use mongodb::{Client, Collection};
+
+use crate::repos::backfill_store::BackfillStore;
+use crate::utils::db::get_db_name;
+
+pub struct BackfillRepository {
+    collection: Collection<Document>,
+}
+
+impl BackfillRepository {
+    pub fn new(client: &Client, environment: &str) -> BackfillRepository {
+        let db = client.database(&get_db_name(environment));
+        let backfills = db.collection::<Document>("backfills");
+
+        BackfillRepository {
+            collection: backfills,
+        }
+    }
+}
+
+#[async_trait]
+impl BackfillStore for BackfillRepository {
+    async fn get_completed_ids(&self) -> Result<Vec<String>, Error> {
+        let find_options = FindOptions::builder().projection(doc! { "_id": 1 }).build();
+        let cursor = self
+            .collection
+            .find(doc! { "completedAt": { "$exists": true } }, find_options)
+            .await?;
+        let backfills: Vec<Document> = cursor.try_collect().await?;
+
+        let channel_ids = backfills
+            .iter()
+            .map(|doc| doc.get_str("_id").unwrap().to_string())
+            .collect();
+
+        Ok(channel_ids)
+    }
+
+    async fn get_page_token(&self, channel_id: &str) -> Result<Option<String>, Error> {
+        let find_one_options = FindOneOptions::builder()
+            .projection(doc! {"pageToken": 1})
+            .build();
+
+        let backfill = self
+            .collection
+            .find_one(doc! {"_id": channel_id}, find_one_options)
+            .await?;
+
+        let page_token = backfill.and_then(|b| b.get_str("pageToken").ok().map(|t| t.to_string()));
+
+        Ok(page_token)
+    }
+
+    async fn set_page_token(
+        &self,
+        channel_id: &str,
+        page_token: &str,
+        videos_ingested: i64,
+    ) -> Result<(), Error> {
+        let update_options = UpdateOptions::builder().upsert(true).build();
+
+        self.collection
+            .update_one(
+                doc! {"_id": channel_id},
+                doc! {
+                    "$set": { "pageToken": page_token, "updatedAt": DateTime::now() },
+                    "$inc": { "videosIngested": videos_ingested },
+                },
+                update_options,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_error(&self, channel_id: &str, error: &str) -> Result<(), Error> {
+        let update_options = UpdateOptions::builder().upsert(true).build();
+
+        self.collection
+            .update_one(
+                doc! {"_id": channel_id},
+                doc! {
+                    "$set": { "error": error, "updatedAt": DateTime::now() },
+                    "$inc": { "errorCount": 1 },
+                },
+                update_options,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_completed(
+        &self,
+        channel_id: &str,
+        videos_ingested: i64,
+        error: Option<String>,
+    ) -> Result<(), Error> {
+        let update_options = UpdateOptions::builder().upsert(true).build();
+
+        let mut set = doc! { "completedAt": DateTime::now(), "updatedAt": DateTime::now() };
+        if let Some(error) = error {
+            set.insert("error", error);
+        }
+
+        self.collection
+            .update_one(
+                doc! {"_id": channel_id},
+                doc! {
+                    "$set": set,
+                    "$unset": { "pageToken": "" },
+                    "$inc": { "videosIngested": videos_ingested },
+                },
+                update_options,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
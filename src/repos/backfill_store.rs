@@ -0,0 +1,28 @@
+use anyhow::Error;
+use async_trait::async_trait;
+
+/// Progress of the upload history backfill per channel, resumed from the stored page token.
+/// Implemented for MongoDB by `BackfillRepository` and for PostgreSQL by `PostgresBackfillStore`.
+#[async_trait]
+pub trait BackfillStore: Send + Sync {
+    async fn get_completed_ids(&self) -> Result<Vec<String>, Error>;
+
+    async fn get_page_token(&self, channel_id: &str) -> Result<Option<String>, Error>;
+
+    async fn set_page_token(
+        &self,
+        channel_id: &str,
+        page_token: &str,
+        videos_ingested: i64,
+    ) -> Result<(), Error>;
+
+    /// Records a failed page, the backfill resumes from its page token on the next crawl.
+    async fn set_error(&self, channel_id: &str, error: &str) -> Result<(), Error>;
+
+    async fn set_completed(
+        &self,
+        channel_id: &str,
+        videos_ingested: i64,
+        error: Option<String>,
+    ) -> Result<(), Error>;
+}
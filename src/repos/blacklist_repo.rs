@@ -1,8 +1,10 @@
 use anyhow::Error;
+use async_trait::async_trait;
 use futures::stream::TryStreamExt;
 use mongodb::bson::{doc, Document};
 use mongodb::{Client, Collection};
 
+use crate::repos::blacklist_store::BlacklistStore;
 use crate::utils::db::get_db_name;
 
 pub struct BlacklistRepository {
@@ -11,13 +13,16 @@ pub struct BlacklistRepository {
 
 impl BlacklistRepository {
     pub fn new(client: &Client, environment: &str) -> BlacklistRepository {
-        let db = client.database(&get_db_name(&environment));
+        let db = client.database(&get_db_name(environment));
         let feeds = db.collection::<Document>("blacklist");
 
         BlacklistRepository { collection: feeds }
     }
+}
 
-    pub async fn get_all(&self) -> Result<Vec<String>, Error> {
+#[async_trait]
+impl BlacklistStore for BlacklistRepository {
+    async fn get_all(&self) -> Result<Vec<String>, Error> {
         let find_options = mongodb::options::FindOptions::builder()
             .projection(doc! {"_id": 1})
             .build();
@@ -0,0 +1,9 @@
+use anyhow::Error;
+use async_trait::async_trait;
+
+/// Channel ids that are never scraped. Implemented for MongoDB by `BlacklistRepository` and for
+/// PostgreSQL by `PostgresBlacklistStore`.
+#[async_trait]
+pub trait BlacklistStore: Send + Sync {
+    async fn get_all(&self) -> Result<Vec<String>, Error>;
+}
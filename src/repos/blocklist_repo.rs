@@ -0,0 +1,105 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use futures::stream::TryStreamExt;
+use mongodb::bson::{doc, DateTime, Document};
+use mongodb::options::{FindOptions, UpdateOptions};
+use mongodb::{Client, Collection};
+
+use crate::repos::blocklist_store::{get_blocklist_key, BlocklistStore};
+use crate::utils::{
+    ban_evasion_utils::{parse_fingerprint, ChannelFingerprint},
+    db::get_db_name,
+};
+
+pub struct BlocklistRepository {
+    collection: Collection<Document>,
+}
+
+impl BlocklistRepository {
+    pub fn new(client: &Client, environment: &str) -> BlocklistRepository {
+        let db = client.database(&get_db_name(environment));
+        let blocklist = db.collection::<Document>("blocklist");
+
+        BlocklistRepository {
+            collection: blocklist,
+        }
+    }
+}
+
+#[async_trait]
+impl BlocklistStore for BlocklistRepository {
+    async fn is_blocked(&self, channel: &str) -> Result<bool, Error> {
+        let result = self
+            .collection
+            .count_documents(doc! { "_id": get_blocklist_key(channel) }, None)
+            .await?;
+
+        Ok(result > 0)
+    }
+
+    async fn get_all(&self) -> Result<Vec<Document>, Error> {
+        let find_options = FindOptions::builder()
+            .sort(doc! { "blockedAt": -1 })
+            .build();
+
+        let cursor = self.collection.find(None, find_options).await?;
+        let blocked: Vec<Document> = cursor.try_collect().await?;
+
+        Ok(blocked)
+    }
+
+    async fn block(&self, channel: &str, reason: &str) -> Result<(), Error> {
+        let update_options = UpdateOptions::builder().upsert(true).build();
+
+        self.collection
+            .update_one(
+                doc! {"_id": get_blocklist_key(channel)},
+                doc! {
+                    "$set": {"reason": reason},
+                    "$setOnInsert": {"blockedAt": DateTime::now()},
+                },
+                update_options,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_fingerprint(&self, channel: &str, fingerprint: Document) -> Result<(), Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": get_blocklist_key(channel)},
+                doc! {"$set": {"fingerprint": fingerprint}},
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_fingerprints(&self) -> Result<Vec<(String, ChannelFingerprint)>, Error> {
+        let cursor = self
+            .collection
+            .find(doc! {"fingerprint": {"$exists": true}}, None)
+            .await?;
+        let blocked: Vec<Document> = cursor.try_collect().await?;
+
+        Ok(blocked
+            .iter()
+            .filter_map(|entry| {
+                let fingerprint = parse_fingerprint(entry.get_document("fingerprint").ok()?)?;
+
+                Some((entry.get_str("_id").ok()?.to_string(), fingerprint))
+            })
+            .collect())
+    }
+
+    async fn unblock(&self, channel: &str) -> Result<bool, Error> {
+        let result = self
+            .collection
+            .delete_one(doc! {"_id": get_blocklist_key(channel)}, None)
+            .await?;
+
+        Ok(result.deleted_count > 0)
+    }
+}
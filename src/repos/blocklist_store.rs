@@ -0,0 +1,34 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::Document;
+
+use crate::utils::ban_evasion_utils::ChannelFingerprint;
+
+/// Channel ids and handles that discovery never queues again, with the reason they were blocked.
+/// Handles are stored lowercase like the `handle` of channels. Implemented for MongoDB by
+/// `BlocklistRepository` and for PostgreSQL by `PostgresBlocklistStore`.
+#[async_trait]
+pub trait BlocklistStore: Send + Sync {
+    async fn is_blocked(&self, channel: &str) -> Result<bool, Error>;
+
+    async fn get_all(&self) -> Result<Vec<Document>, Error>;
+
+    async fn block(&self, channel: &str, reason: &str) -> Result<(), Error>;
+
+    /// Stores what the blocked channel is recognized by, see `ban_evasion_utils`.
+    async fn set_fingerprint(&self, channel: &str, fingerprint: Document) -> Result<(), Error>;
+
+    /// The blocked channels that have a fingerprint stored, by their blocklist key.
+    async fn get_fingerprints(&self) -> Result<Vec<(String, ChannelFingerprint)>, Error>;
+
+    /// Returns whether the channel was blocked.
+    async fn unblock(&self, channel: &str) -> Result<bool, Error>;
+}
+
+pub fn get_blocklist_key(channel: &str) -> String {
+    if channel.starts_with('@') {
+        channel.to_lowercase()
+    } else {
+        channel.to_string()
+    }
+}
@@ -0,0 +1,41 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, Document};
+use mongodb::{Client, Collection};
+
+use crate::repos::caption_store::CaptionStore;
+use crate::utils::db::get_db_name;
+
+pub struct CaptionRepository {
+    collection: Collection<Document>,
+}
+
+impl CaptionRepository {
+    pub fn new(client: &Client, environment: &str) -> CaptionRepository {
+        let db = client.database(&get_db_name(environment));
+        let captions = db.collection::<Document>("captions");
+
+        CaptionRepository {
+            collection: captions,
+        }
+    }
+}
+
+#[async_trait]
+impl CaptionStore for CaptionRepository {
+    async fn upsert(&self, video_id: &str, caption: Document) -> Result<(), Error> {
+        let update_options = mongodb::options::UpdateOptions::builder()
+            .upsert(true)
+            .build();
+
+        self.collection
+            .update_one(
+                doc! {"_id": video_id},
+                doc! {"$set": caption},
+                update_options,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
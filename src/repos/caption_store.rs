@@ -0,0 +1,10 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::Document;
+
+/// Captions of videos, keyed by video id. Implemented for MongoDB by `CaptionRepository` and for
+/// PostgreSQL by `PostgresCaptionStore`.
+#[async_trait]
+pub trait CaptionStore: Send + Sync {
+    async fn upsert(&self, video_id: &str, caption: Document) -> Result<(), Error>;
+}
@@ -0,0 +1,41 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, DateTime, Document};
+use mongodb::{Client, Collection};
+
+use crate::repos::channel_audit_store::ChannelAuditStore;
+use crate::utils::db::get_db_name;
+
+pub struct ChannelAuditRepository {
+    collection: Collection<Document>,
+}
+
+impl ChannelAuditRepository {
+    pub fn new(client: &Client, environment: &str) -> ChannelAuditRepository {
+        let db = client.database(&get_db_name(environment));
+        let audit = db.collection::<Document>("channelaudit");
+
+        ChannelAuditRepository { collection: audit }
+    }
+}
+
+#[async_trait]
+impl ChannelAuditStore for ChannelAuditRepository {
+    async fn insert(
+        &self,
+        channel_id: &str,
+        action: &str,
+        evidence: Document,
+    ) -> Result<(), Error> {
+        let entry = doc! {
+            "channel": channel_id,
+            "action": action,
+            "evidence": evidence,
+            "createdAt": DateTime::now(),
+        };
+
+        self.collection.insert_one(entry, None).await?;
+
+        Ok(())
+    }
+}
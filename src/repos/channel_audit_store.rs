@@ -0,0 +1,16 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::Document;
+
+pub const AUDIT_ACTION_DEACTIVATED: &str = "deactivated";
+pub const AUDIT_ACTION_OPTED_OUT: &str = "optedOut";
+pub const AUDIT_ACTION_PURGED: &str = "purged";
+
+/// Append-only log of automated decisions about channels and the evidence they were based on.
+/// Implemented for MongoDB by `ChannelAuditRepository` and for PostgreSQL by
+/// `PostgresChannelAuditStore`.
+#[async_trait]
+pub trait ChannelAuditStore: Send + Sync {
+    async fn insert(&self, channel_id: &str, action: &str, evidence: Document)
+        -> Result<(), Error>;
+}
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::stream::TryStreamExt;
+use mongodb::bson::{doc, DateTime, Document};
+use mongodb::options::{UpdateModifications, UpdateOptions};
+use mongodb::{Client, Collection};
+
+use crate::repos::channel_popularity_store::ChannelPopularityStore;
+use crate::utils::{
+    db::get_db_name,
+    popularity_utils::{decay_popularity, POPULARITY_HALF_LIFE_SECONDS},
+};
+
+pub struct ChannelPopularityRepository {
+    collection: Collection<Document>,
+}
+
+impl ChannelPopularityRepository {
+    pub fn new(client: &Client, environment: &str) -> ChannelPopularityRepository {
+        let db = client.database(&get_db_name(environment));
+        let popularity = db.collection::<Document>("channelpopularity");
+
+        ChannelPopularityRepository {
+            collection: popularity,
+        }
+    }
+}
+
+#[async_trait]
+impl ChannelPopularityStore for ChannelPopularityRepository {
+    async fn record_views(&self, views: &HashMap<String, i64>) -> Result<(), Error> {
+        let now = DateTime::now();
+        let update_options = UpdateOptions::builder().upsert(true).build();
+
+        for (channel_id, count) in views {
+            let elapsed_millis = doc! { "$subtract": [now, { "$ifNull": ["$updatedAt", now] }] };
+            let decay = doc! {
+                "$pow": [0.5, { "$divide": [elapsed_millis, POPULARITY_HALF_LIFE_SECONDS * 1000] }]
+            };
+
+            let pipeline = vec![doc! {
+                "$set": {
+                    "score": {
+                        "$add": [{ "$multiply": [{ "$ifNull": ["$score", 0.0] }, decay] }, *count as f64]
+                    },
+                    "views": { "$add": [{ "$ifNull": ["$views", 0i64] }, *count] },
+                    "updatedAt": now,
+                }
+            }];
+
+            self.collection
+                .update_one(
+                    doc! {"_id": channel_id},
+                    UpdateModifications::Pipeline(pipeline),
+                    update_options.clone(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn get_scores(&self, channel_ids: &[String]) -> Result<HashMap<String, f64>, Error> {
+        let now = Utc::now().timestamp();
+        let cursor = self
+            .collection
+            .find(doc! {"_id": {"$in": channel_ids}}, None)
+            .await?;
+        let entries: Vec<Document> = cursor.try_collect().await?;
+
+        Ok(entries
+            .iter()
+            .filter_map(|entry| {
+                let updated_at = entry.get_datetime("updatedAt").ok()?.timestamp_millis() / 1000;
+
+                Some((
+                    entry.get_str("_id").ok()?.to_string(),
+                    decay_popularity(entry.get_f64("score").ok()?, updated_at, now),
+                ))
+            })
+            .collect())
+    }
+}
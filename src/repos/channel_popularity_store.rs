@@ -0,0 +1,17 @@
+use std::collections::HashMap;
+
+use anyhow::Error;
+use async_trait::async_trait;
+
+/// Page views the website reports per channel, kept as a `score` that halves every week without
+/// views. The scrape crawlers queue popular channels first. Implemented for MongoDB by
+/// `ChannelPopularityRepository` and for PostgreSQL by `PostgresChannelPopularityStore`.
+#[async_trait]
+pub trait ChannelPopularityStore: Send + Sync {
+    /// Decays the score of each channel to now and adds its views, in a single update so
+    /// concurrent reports are not lost.
+    async fn record_views(&self, views: &HashMap<String, i64>) -> Result<(), Error>;
+
+    /// Scores of the channels that have any, decayed to now.
+    async fn get_scores(&self, channel_ids: &[String]) -> Result<HashMap<String, f64>, Error>;
+}
@@ -0,0 +1,99 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::stream::TryStreamExt;
+use mongodb::bson::{doc, DateTime, Document};
+use mongodb::options::{FindOptions, UpdateOptions};
+use mongodb::{Client, Collection};
+
+use crate::repos::channel_probation_store::ChannelProbationStore;
+use crate::utils::db::get_db_name;
+
+pub struct ChannelProbationRepository {
+    collection: Collection<Document>,
+}
+
+impl ChannelProbationRepository {
+    pub fn new(client: &Client, environment: &str) -> ChannelProbationRepository {
+        let db = client.database(&get_db_name(environment));
+        let probations = db.collection::<Document>("channelprobations");
+
+        ChannelProbationRepository {
+            collection: probations,
+        }
+    }
+
+    async fn get_ids(&self, filter: Document, limit: Option<i64>) -> Result<Vec<String>, Error> {
+        let find_options = FindOptions::builder()
+            .projection(doc! {"_id": 1})
+            .sort(doc! {"startedAt": 1})
+            .limit(limit)
+            .build();
+
+        let cursor = self.collection.find(filter, find_options).await?;
+        let probations: Vec<Document> = cursor.try_collect().await?;
+
+        Ok(probations
+            .iter()
+            .filter_map(|probation| probation.get_str("_id").ok().map(|id| id.to_string()))
+            .collect())
+    }
+}
+
+#[async_trait]
+impl ChannelProbationStore for ChannelProbationRepository {
+    async fn start(&self, channel_id: &str) -> Result<(), Error> {
+        let update_options = UpdateOptions::builder().upsert(true).build();
+
+        self.collection
+            .update_one(
+                doc! {"_id": channel_id},
+                doc! {"$setOnInsert": {"startedAt": DateTime::now()}},
+                update_options,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_undecided_ids(&self) -> Result<Vec<String>, Error> {
+        self.get_ids(doc! {"outcome": {"$exists": false}}, None)
+            .await
+    }
+
+    async fn get_ids_due(
+        &self,
+        started_before: chrono::DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<String>, Error> {
+        self.get_ids(
+            doc! {
+                "outcome": {"$exists": false},
+                "startedAt": {"$lt": DateTime::from_chrono(started_before)},
+            },
+            Some(limit),
+        )
+        .await
+    }
+
+    async fn set_outcome(
+        &self,
+        channel_id: &str,
+        outcome: &str,
+        reason: Option<&str>,
+    ) -> Result<(), Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": channel_id},
+                doc! {"$set": {
+                    "outcome": outcome,
+                    "reason": reason,
+                    "decidedAt": DateTime::now(),
+                }},
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
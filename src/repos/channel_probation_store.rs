@@ -0,0 +1,31 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+
+pub const PROBATION_OUTCOME_CONFIRMED: &str = "confirmed";
+pub const PROBATION_OUTCOME_REJECTED: &str = "rejected";
+
+/// Channels accepted on probation, with the outcome once the probation job decided them.
+/// Implemented for MongoDB by `ChannelProbationRepository` and for PostgreSQL by
+/// `PostgresChannelProbationStore`.
+#[async_trait]
+pub trait ChannelProbationStore: Send + Sync {
+    /// Keeps the start of a channel already on probation.
+    async fn start(&self, channel_id: &str) -> Result<(), Error>;
+
+    async fn get_undecided_ids(&self) -> Result<Vec<String>, Error>;
+
+    /// Undecided channels that went on probation before `started_before`, oldest first.
+    async fn get_ids_due(
+        &self,
+        started_before: chrono::DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<String>, Error>;
+
+    async fn set_outcome(
+        &self,
+        channel_id: &str,
+        outcome: &str,
+        reason: Option<&str>,
+    ) -> Result<(), Error>;
+}
@@ -1,12 +1,27 @@
 use anyhow::anyhow;
 use anyhow::Error;
+use async_trait::async_trait;
 use chrono::Utc;
 use futures::stream::TryStreamExt;
-use mongodb::bson::{doc, Document};
+use mongodb::bson::{doc, from_document, to_document, Document};
 use mongodb::options::{FindOneOptions, FindOptions};
 use mongodb::{Client, Collection};
 
+use crate::classifiers::genre_classifier::{get_genre_tags_document, GenreScore};
+use crate::models::feed_state::FeedState;
+use crate::models::upload_pattern::UploadPattern;
+use crate::repos::channel_store::ChannelStore;
+use crate::utils::ban_evasion_utils::{get_ban_evasion_document, BanEvasionMatch};
+use crate::utils::channel_page_utils::ChannelPageHints;
+use crate::utils::consts::{
+    CHANNEL_STATUS_ACTIVE, CHANNEL_STATUS_DEACTIVATED, CHANNEL_STATUS_PROBATION,
+    DEACTIVATION_REASON_MERGED, DEACTIVATION_REASON_REDIRECTED,
+};
 use crate::utils::db::get_db_name;
+use crate::utils::gear_utils::{get_gear_documents, GearCount};
+use crate::utils::image_change_utils::format_image_hash;
+use crate::utils::link_utils::{get_link_check_document, LinkCheck};
+use crate::utils::topic_drift_utils::{get_topic_drift_document, TopicDrift};
 
 pub struct ChannelRepository {
     collection: Collection<Document>,
@@ -14,15 +29,18 @@ pub struct ChannelRepository {
 
 impl ChannelRepository {
     pub fn new(client: &Client, environment: &str) -> ChannelRepository {
-        let db = client.database(&get_db_name(&environment));
+        let db = client.database(&get_db_name(environment));
         let channels = db.collection::<Document>("channels");
 
         ChannelRepository {
             collection: channels,
         }
     }
+}
 
-    pub async fn exists(&self, channel_id: &str) -> Result<bool, Error> {
+#[async_trait]
+impl ChannelStore for ChannelRepository {
+    async fn exists(&self, channel_id: &str) -> Result<bool, Error> {
         let result = self
             .collection
             .count_documents(doc! { "_id": channel_id }, None)
@@ -31,7 +49,26 @@ impl ChannelRepository {
         Ok(result > 0)
     }
 
-    pub async fn get_all_ids(&self) -> Result<Vec<String>, Error> {
+    async fn get_id_by_handle(&self, handle: &str) -> Result<Option<String>, Error> {
+        let find_one_options = FindOneOptions::builder()
+            .projection(doc! {"_id": 1})
+            .build();
+
+        let channel = self
+            .collection
+            .find_one(doc! {"handle": handle.to_lowercase()}, find_one_options)
+            .await?;
+
+        let channel_id = channel.and_then(|c| c.get_str("_id").ok().map(|id| id.to_string()));
+
+        Ok(channel_id)
+    }
+
+    async fn find_by_id(&self, id: &str) -> Result<Option<Document>, Error> {
+        Ok(self.collection.find_one(doc! {"_id": id}, None).await?)
+    }
+
+    async fn get_all_ids(&self) -> Result<Vec<String>, Error> {
         let find_options = FindOptions::builder().projection(doc! { "_id": 1 }).build();
         let cursor = self.collection.find(None, find_options).await?;
         let channels: Vec<Document> = cursor.try_collect().await?;
@@ -44,7 +81,34 @@ impl ChannelRepository {
         Ok(channel_ids)
     }
 
-    pub async fn get_ids_upload_last_month(
+    async fn get_page(
+        &self,
+        after_id: Option<&str>,
+        min_subscribers: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<Document>, Error> {
+        let find_options = FindOptions::builder()
+            .sort(doc! { "_id": 1 })
+            .limit(limit)
+            .build();
+
+        let mut query = doc! {};
+
+        if let Some(after_id) = after_id {
+            query.insert("_id", doc! { "$gt": after_id });
+        }
+
+        if let Some(min_subscribers) = min_subscribers {
+            query.insert("subscribers", doc! { "$gte": min_subscribers });
+        }
+
+        let cursor = self.collection.find(query, find_options).await?;
+        let channels = cursor.try_collect().await?;
+
+        Ok(channels)
+    }
+
+    async fn get_ids_upload_last_month(
         &self,
         min_subscribers_count: i64,
     ) -> Result<Vec<String>, Error> {
@@ -58,6 +122,58 @@ impl ChannelRepository {
             },
             "subscribers": {
                 "$gte": min_subscribers_count
+            },
+            "status": { "$nin": [CHANNEL_STATUS_DEACTIVATED, CHANNEL_STATUS_PROBATION] },
+        };
+
+        let cursor = self.collection.find(query, find_options).await?;
+        let channels: Vec<Document> = cursor.try_collect().await?;
+
+        let channel_ids = channels
+            .iter()
+            .map(|doc| doc.get_str("_id").unwrap().to_string())
+            .collect();
+
+        Ok(channel_ids)
+    }
+
+    async fn get_ids_by_country_and_topic(
+        &self,
+        country: Option<&str>,
+        topic: Option<&str>,
+    ) -> Result<Vec<String>, Error> {
+        let find_options = FindOptions::builder().projection(doc! { "_id": 1 }).build();
+
+        let mut query = doc! {};
+
+        if let Some(country) = country {
+            query.insert("country", country.to_lowercase());
+        }
+
+        if let Some(topic) = topic {
+            query.insert("topics", topic.to_lowercase());
+        }
+
+        let cursor = self.collection.find(query, find_options).await?;
+        let channels: Vec<Document> = cursor.try_collect().await?;
+
+        let channel_ids = channels
+            .iter()
+            .map(|doc| doc.get_str("_id").unwrap().to_string())
+            .collect();
+
+        Ok(channel_ids)
+    }
+
+    async fn get_ids_last_upload_before(
+        &self,
+        last_upload_before: chrono::DateTime<Utc>,
+    ) -> Result<Vec<String>, Error> {
+        let find_options = FindOptions::builder().projection(doc! { "_id": 1 }).build();
+
+        let query = doc! {
+            "lastUploadAt": {
+                "$lt": last_upload_before.timestamp()
             }
         };
 
@@ -72,7 +188,121 @@ impl ChannelRepository {
         Ok(channel_ids)
     }
 
-    pub async fn get_ids_last_crawled_before(
+    async fn get_ids_due_for_scrape(
+        &self,
+        now: chrono::DateTime<Utc>,
+    ) -> Result<Vec<String>, Error> {
+        let find_options = FindOptions::builder().projection(doc! { "_id": 1 }).build();
+
+        let query = doc! {
+            "$or": [
+                { "nextScrapeAt": { "$exists": false } },
+                { "nextScrapeAt": { "$lte": now.timestamp() } },
+            ],
+            "status": { "$ne": CHANNEL_STATUS_DEACTIVATED },
+        };
+
+        let cursor = self.collection.find(query, find_options).await?;
+        let channels: Vec<Document> = cursor.try_collect().await?;
+
+        let channel_ids = channels
+            .iter()
+            .map(|doc| doc.get_str("_id").unwrap().to_string())
+            .collect();
+
+        Ok(channel_ids)
+    }
+
+    async fn get_ids_about_crawled_before(
+        &self,
+        crawled_before: chrono::DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<String>, Error> {
+        let find_options = FindOptions::builder()
+            .projection(doc! { "_id": 1 })
+            .limit(limit)
+            .build();
+
+        let query = doc! {
+            "$or": [
+                { "aboutCrawledAt": { "$exists": false } },
+                { "aboutCrawledAt": {
+                    "$lt": mongodb::bson::DateTime::from_millis(crawled_before.timestamp_millis())
+                } },
+            ]
+        };
+
+        let cursor = self.collection.find(query, find_options).await?;
+        let channels: Vec<Document> = cursor.try_collect().await?;
+
+        let channel_ids = channels
+            .iter()
+            .map(|doc| doc.get_str("_id").unwrap().to_string())
+            .collect();
+
+        Ok(channel_ids)
+    }
+
+    async fn get_ids_reclassified_before(
+        &self,
+        reclassified_before: chrono::DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<String>, Error> {
+        let find_options = FindOptions::builder()
+            .projection(doc! { "_id": 1 })
+            .limit(limit)
+            .build();
+
+        let query = doc! {
+            "$or": [
+                { "reclassifiedAt": { "$exists": false } },
+                { "reclassifiedAt": {
+                    "$lt": mongodb::bson::DateTime::from_millis(reclassified_before.timestamp_millis())
+                } },
+            ],
+            "status": { "$ne": CHANNEL_STATUS_DEACTIVATED },
+        };
+
+        let cursor = self.collection.find(query, find_options).await?;
+        let channels: Vec<Document> = cursor.try_collect().await?;
+
+        let channel_ids = channels
+            .iter()
+            .map(|doc| doc.get_str("_id").unwrap().to_string())
+            .collect();
+
+        Ok(channel_ids)
+    }
+
+    async fn get_ids_rebranded_since(
+        &self,
+        since: chrono::DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<String>, Error> {
+        let find_options = FindOptions::builder()
+            .projection(doc! { "_id": 1 })
+            .sort(doc! { "rebrandedAt": -1 })
+            .limit(limit)
+            .build();
+
+        let query = doc! {
+            "rebrandedAt": {
+                "$gte": mongodb::bson::DateTime::from_millis(since.timestamp_millis())
+            },
+        };
+
+        let cursor = self.collection.find(query, find_options).await?;
+        let channels: Vec<Document> = cursor.try_collect().await?;
+
+        let channel_ids = channels
+            .iter()
+            .map(|doc| doc.get_str("_id").unwrap().to_string())
+            .collect();
+
+        Ok(channel_ids)
+    }
+
+    async fn get_ids_last_crawled_before(
         &self,
         last_crawl_before: chrono::DateTime<Utc>,
         last_upload_after: chrono::DateTime<Utc>,
@@ -108,79 +338,716 @@ impl ChannelRepository {
         Ok(channel_ids)
     }
 
-    pub async fn get_detected_language(&self, id: &str) -> Result<String, Error> {
-        let find_one_options = FindOneOptions::builder()
-            .projection(doc! {"detectedLanguage": 1})
-            .build();
+    async fn count_grouped_by(
+        &self,
+        field: &str,
+        default_key: &str,
+    ) -> Result<Vec<(String, i64)>, Error> {
+        let pipeline = vec![
+            doc! { "$group": { "_id": format!("${}", field), "count": { "$sum": 1 } } },
+            doc! { "$sort": { "count": -1 } },
+        ];
 
-        let channel = self
+        let cursor = self.collection.aggregate(pipeline, None).await?;
+        let groups: Vec<Document> = cursor.try_collect().await?;
+
+        let counts = groups
+            .iter()
+            .map(|doc| {
+                let key = doc.get_str("_id").unwrap_or(default_key).to_string();
+                let count = doc.get_i32("count").map(i64::from).unwrap_or(0);
+
+                (key, count)
+            })
+            .collect();
+
+        Ok(counts)
+    }
+
+    async fn count_created_since(&self, since: chrono::DateTime<Utc>) -> Result<i64, Error> {
+        let count = self
             .collection
-            .find_one(doc! {"_id": id}, find_one_options)
+            .count_documents(
+                doc! {"createdAt": {"$gte": mongodb::bson::DateTime::from_chrono(since)}},
+                None,
+            )
             .await?;
 
-        match channel {
-            Some(c) => {
-                let detected_language = c.get_str("detectedLanguage")?;
-                Ok(detected_language.to_string())
-            }
-            None => Err(anyhow!("No channel found for id {}!", id)),
-        }
+        Ok(count as i64)
     }
 
-    pub async fn delete(&self, id: &str) -> Result<(), anyhow::Error> {
-        self.collection.delete_one(doc! {"_id": id}, None).await?;
+    async fn count_uploading_by_country(
+        &self,
+        uploaded_since: i64,
+        limit: i64,
+    ) -> Result<Vec<(String, i64)>, Error> {
+        let pipeline = vec![
+            doc! { "$match": {
+                "status": CHANNEL_STATUS_ACTIVE,
+                "lastUploadAt": { "$gte": uploaded_since },
+                "country": { "$type": "string" },
+            } },
+            doc! { "$group": { "_id": "$country", "count": { "$sum": 1 } } },
+            doc! { "$sort": { "count": -1, "_id": 1 } },
+            doc! { "$limit": limit },
+        ];
 
-        Ok(())
+        let cursor = self.collection.aggregate(pipeline, None).await?;
+        let groups: Vec<Document> = cursor.try_collect().await?;
+
+        let counts = groups
+            .iter()
+            .filter_map(|doc| {
+                let country = doc.get_str("_id").ok()?.to_string();
+                let count = doc.get_i32("count").map(i64::from).unwrap_or(0);
+
+                Some((country, count))
+            })
+            .collect();
+
+        Ok(counts)
     }
 
-    pub async fn upsert(&self, id: &str, channel: Document) {
-        let update_options = mongodb::options::UpdateOptions::builder()
-            .upsert(true)
+    async fn get_ids_with_due_refresh_override(
+        &self,
+        now: chrono::DateTime<Utc>,
+    ) -> Result<Vec<String>, Error> {
+        let find_options = FindOptions::builder()
+            .projection(doc! { "_id": 1 })
+            .sort(doc! { "lastCrawl": 1 })
             .build();
 
-        self.collection
-            .update_one(doc! {"_id": id}, doc! {"$set": channel}, update_options)
-            .await
-            .unwrap();
+        let until_after = now.timestamp();
+        let now = mongodb::bson::DateTime::from_millis(now.timestamp_millis());
+
+        let query = doc! {
+            "refreshOverride.until": { "$gt": until_after },
+            "$expr": {
+                "$lt": [
+                    "$lastCrawl",
+                    { "$subtract": [now, { "$multiply": ["$refreshOverride.intervalSeconds", 1000] }] }
+                ]
+            }
+        };
+
+        let cursor = self.collection.find(query, find_options).await?;
+        let channels: Vec<Document> = cursor.try_collect().await?;
+
+        let channel_ids = channels
+            .iter()
+            .map(|doc| doc.get_str("_id").unwrap().to_string())
+            .collect();
+
+        Ok(channel_ids)
     }
 
-    pub async fn set_video_count_last_upload(
+    async fn set_refresh_override(
         &self,
         id: &str,
-        video_count: i64,
-        last_upload_timestamp: i64,
-    ) {
-        self.collection
+        interval_seconds: i64,
+        until_timestamp: i64,
+    ) -> Result<bool, Error> {
+        let result = self
+            .collection
             .update_one(
                 doc! {"_id": id},
                 doc! {
                     "$set": {
-                        "videoCount": video_count,
-                        "lastUploadAt": last_upload_timestamp,
-                        "lastVideoCrawl": mongodb::bson::DateTime::now()
+                        "refreshOverride": {
+                            "intervalSeconds": interval_seconds,
+                            "until": until_timestamp,
+                        }
                     }
                 },
                 None,
             )
-            .await
-            .unwrap();
+            .await?;
+
+        Ok(result.matched_count > 0)
     }
 
-    pub async fn set_scrape_error(&self, id: &str, error: String) {
-        self.collection
+    async fn clear_refresh_override(&self, id: &str) -> Result<bool, Error> {
+        let result = self
+            .collection
             .update_one(
                 doc! {"_id": id},
-                doc! {
-                    "$set": {
-                        "scrapeError": {
-                            "at": mongodb::bson::DateTime::now(),
-                            "error": error
-                        }
-                    }
-                },
+                doc! {"$unset": {"refreshOverride": ""}},
                 None,
             )
-            .await
-            .unwrap();
+            .await?;
+
+        Ok(result.matched_count > 0)
+    }
+
+    async fn get_classification_override(&self, id: &str) -> Result<Option<String>, Error> {
+        let find_one_options = FindOneOptions::builder()
+            .projection(doc! {"classificationOverride": 1})
+            .build();
+
+        let channel = self
+            .collection
+            .find_one(doc! {"_id": id}, find_one_options)
+            .await?;
+
+        let classification_override = channel.and_then(|c| {
+            c.get_str("classificationOverride")
+                .ok()
+                .map(|classification_override| classification_override.to_string())
+        });
+
+        Ok(classification_override)
+    }
+
+    async fn set_classification_override(
+        &self,
+        id: &str,
+        classification_override: &str,
+    ) -> Result<bool, Error> {
+        let result = self
+            .collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {"$set": {"classificationOverride": classification_override}},
+                None,
+            )
+            .await?;
+
+        Ok(result.matched_count > 0)
+    }
+
+    async fn clear_classification_override(&self, id: &str) -> Result<bool, Error> {
+        let result = self
+            .collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {"$unset": {"classificationOverride": ""}},
+                None,
+            )
+            .await?;
+
+        Ok(result.matched_count > 0)
+    }
+
+    async fn get_language(&self, id: &str) -> Result<Option<String>, Error> {
+        let find_one_options = FindOneOptions::builder()
+            .projection(doc! {"language": 1})
+            .build();
+
+        let channel = self
+            .collection
+            .find_one(doc! {"_id": id}, find_one_options)
+            .await?;
+
+        let language = channel.and_then(|c| {
+            c.get_str("language")
+                .ok()
+                .map(|language| language.to_string())
+        });
+
+        Ok(language)
+    }
+
+    async fn get_detected_language(&self, id: &str) -> Result<String, Error> {
+        let find_one_options = FindOneOptions::builder()
+            .projection(doc! {"detectedLanguage": 1})
+            .build();
+
+        let channel = self
+            .collection
+            .find_one(doc! {"_id": id}, find_one_options)
+            .await?;
+
+        match channel {
+            Some(c) => {
+                let detected_language = c.get_str("detectedLanguage")?;
+                Ok(detected_language.to_string())
+            }
+            None => Err(anyhow!("No channel found for id {}!", id)),
+        }
+    }
+
+    async fn delete(&self, id: &str) -> Result<(), anyhow::Error> {
+        self.collection.delete_one(doc! {"_id": id}, None).await?;
+
+        Ok(())
+    }
+
+    async fn upsert(&self, id: &str, channel: Document) -> bool {
+        let update_options = mongodb::options::UpdateOptions::builder()
+            .upsert(true)
+            .build();
+
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {
+                    "$set": channel,
+                    "$setOnInsert": {"createdAt": mongodb::bson::DateTime::now()},
+                },
+                update_options,
+            )
+            .await
+            .unwrap()
+            .upserted_id
+            .is_some()
+    }
+
+    async fn set_video_count_last_upload(
+        &self,
+        id: &str,
+        video_count: i64,
+        last_upload_timestamp: i64,
+    ) {
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {
+                    "$set": {
+                        "videoCount": video_count,
+                        "lastUploadAt": last_upload_timestamp,
+                        "lastVideoCrawl": mongodb::bson::DateTime::now()
+                    }
+                },
+                None,
+            )
+            .await
+            .unwrap();
+    }
+
+    async fn set_scrape_schedule(
+        &self,
+        id: &str,
+        next_scrape_at: i64,
+        upload_interval_seconds: Option<i64>,
+    ) -> Result<(), Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {
+                    "$set": {
+                        "nextScrapeAt": next_scrape_at,
+                        "uploadIntervalSeconds": upload_interval_seconds,
+                        "scrapeFailures": 0_i64,
+                    }
+                },
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_scrape_failure(
+        &self,
+        id: &str,
+        failures: i64,
+        next_scrape_at: i64,
+    ) -> Result<(), Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {
+                    "$set": {
+                        "nextScrapeAt": next_scrape_at,
+                        "scrapeFailures": failures,
+                    }
+                },
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_about(
+        &self,
+        id: &str,
+        links: Vec<Document>,
+        contact_emails: Vec<String>,
+    ) -> Result<(), Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {
+                    "$set": {
+                        "links": links,
+                        "contactEmails": contact_emails,
+                        "aboutCrawledAt": mongodb::bson::DateTime::now(),
+                    }
+                },
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_page_hints(&self, id: &str, hints: &ChannelPageHints) -> Result<(), Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {
+                    "$set": {
+                        "isVerified": hints.is_verified,
+                        "hasMemberships": hints.has_memberships,
+                        "isMonetized": hints.is_monetized,
+                        "pageCrawledAt": mongodb::bson::DateTime::now(),
+                    }
+                },
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_link_checks(&self, id: &str, checks: &[LinkCheck]) -> Result<(), Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {
+                    "$set": {
+                        "linkChecks": checks.iter().map(get_link_check_document).collect::<Vec<Document>>(),
+                        "linksCheckedAt": mongodb::bson::DateTime::now(),
+                    }
+                },
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_verified_metadata(&self, id: &str, mut changes: Document) -> Result<(), Error> {
+        changes.insert("metadataVerifiedAt", mongodb::bson::DateTime::now());
+
+        self.collection
+            .update_one(doc! {"_id": id}, doc! {"$set": changes}, None)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_upload_pattern(&self, id: &str, pattern: &UploadPattern) -> Result<(), Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {
+                    "$set": {
+                        "uploadPattern": to_document(pattern)?,
+                        "uploadPatternUpdatedAt": mongodb::bson::DateTime::now(),
+                    }
+                },
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_upload_pattern(&self, id: &str) -> Result<Option<UploadPattern>, Error> {
+        let find_one_options = FindOneOptions::builder()
+            .projection(doc! {"uploadPattern": 1})
+            .build();
+
+        let channel = self
+            .collection
+            .find_one(doc! {"_id": id}, find_one_options)
+            .await?;
+
+        match channel
+            .as_ref()
+            .and_then(|c| c.get_document("uploadPattern").ok())
+        {
+            Some(pattern) => Ok(Some(from_document(pattern.clone())?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn set_genre_tags(&self, id: &str, genres: &[GenreScore]) -> Result<(), Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {"$set": get_genre_tags_document(genres)},
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_gear(&self, id: &str, gear: &[GearCount]) -> Result<(), Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {
+                    "$set": {
+                        "gear": get_gear_documents(gear),
+                        "gearUpdatedAt": mongodb::bson::DateTime::now(),
+                    }
+                },
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_lifecycle(
+        &self,
+        id: &str,
+        lifecycle: &str,
+        previous: Option<&str>,
+    ) -> Result<(), Error> {
+        let now = mongodb::bson::DateTime::now();
+
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {
+                    "$set": {
+                        "lifecycle": lifecycle,
+                        "lifecycleChangedAt": now,
+                    },
+                    "$push": {
+                        "lifecycleHistory": {"from": previous, "to": lifecycle, "at": now}
+                    }
+                },
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_duplicate_of(
+        &self,
+        id: &str,
+        canonical_id: &str,
+        reason: &str,
+    ) -> Result<(), Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {
+                    "$set": {
+                        "duplicateOf": canonical_id,
+                        "duplicateReason": reason,
+                        "duplicateDetectedAt": mongodb::bson::DateTime::now(),
+                    }
+                },
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_topic_drift(&self, id: &str, drift: Option<&TopicDrift>) -> Result<(), Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {"$set": {"topicDrift": drift.map(get_topic_drift_document)}},
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_ban_evasion(&self, id: &str, ban_evasion: &BanEvasionMatch) -> Result<(), Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {"$set": {"banEvasion": get_ban_evasion_document(ban_evasion)}},
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_avatar_hash(&self, id: &str, avatar_hash: u64) -> Result<(), Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {"$set": {"avatarHash": format_image_hash(avatar_hash)}},
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_merged(&self, id: &str, canonical_id: &str) -> Result<(), Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {
+                    "$set": {
+                        "status": CHANNEL_STATUS_DEACTIVATED,
+                        "deactivationReason": DEACTIVATION_REASON_MERGED,
+                        "redirectsTo": canonical_id,
+                        "mergedInto": canonical_id,
+                        "mergedAt": mongodb::bson::DateTime::now(),
+                    }
+                },
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_scrape_error(&self, id: &str, error: String) {
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {
+                    "$set": {
+                        "scrapeError": {
+                            "at": mongodb::bson::DateTime::now(),
+                            "error": error
+                        }
+                    }
+                },
+                None,
+            )
+            .await
+            .unwrap();
+    }
+
+    async fn set_reclassified(&self, id: &str) -> Result<(), Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {"$set": {"reclassifiedAt": mongodb::bson::DateTime::now()}},
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn deactivate(&self, id: &str, reason: &str) -> Result<(), Error> {
+        let now = mongodb::bson::DateTime::now();
+
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {
+                    "$set": {
+                        "status": CHANNEL_STATUS_DEACTIVATED,
+                        "deactivationReason": reason,
+                        "deactivatedAt": now,
+                        "reclassifiedAt": now,
+                    }
+                },
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_redirect(&self, id: &str) -> Result<Option<String>, Error> {
+        let find_one_options = FindOneOptions::builder()
+            .projection(doc! {"redirectsTo": 1})
+            .build();
+
+        let channel = self
+            .collection
+            .find_one(doc! {"_id": id}, find_one_options)
+            .await?;
+
+        let redirects_to = channel.and_then(|c| {
+            c.get_str("redirectsTo")
+                .ok()
+                .map(|redirects_to| redirects_to.to_string())
+        });
+
+        Ok(redirects_to)
+    }
+
+    async fn set_redirect(&self, id: &str, redirects_to: &str) -> Result<(), Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {
+                    "$set": {
+                        "status": CHANNEL_STATUS_DEACTIVATED,
+                        "deactivationReason": DEACTIVATION_REASON_REDIRECTED,
+                        "deactivatedAt": mongodb::bson::DateTime::now(),
+                        "redirectsTo": redirects_to,
+                    }
+                },
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_feed_state(&self, id: &str) -> Result<FeedState, Error> {
+        let find_one_options = FindOneOptions::builder()
+            .projection(doc! {
+                "feedEtag": 1,
+                "feedLastModified": 1,
+                "feedContentHash": 1,
+                "feedVideoIds": 1,
+                "feedEmptyPlaylistCheckedAt": 1,
+            })
+            .build();
+
+        let channel = self
+            .collection
+            .find_one(doc! {"_id": id}, find_one_options)
+            .await?;
+
+        let feed_state = channel
+            .map(|c| FeedState {
+                etag: c.get_str("feedEtag").ok().map(|etag| etag.to_string()),
+                last_modified: c
+                    .get_str("feedLastModified")
+                    .ok()
+                    .map(|last_modified| last_modified.to_string()),
+                content_hash: c
+                    .get_str("feedContentHash")
+                    .ok()
+                    .map(|content_hash| content_hash.to_string()),
+                video_ids: c
+                    .get_array("feedVideoIds")
+                    .map(|video_ids| {
+                        video_ids
+                            .iter()
+                            .filter_map(|video_id| video_id.as_str().map(|id| id.to_string()))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                empty_playlist_checked_at: c.get_i64("feedEmptyPlaylistCheckedAt").ok(),
+            })
+            .unwrap_or_default();
+
+        Ok(feed_state)
+    }
+
+    async fn set_feed_state(&self, id: &str, feed_state: &FeedState) -> Result<(), Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {
+                    "$set": {
+                        "feedEtag": &feed_state.etag,
+                        "feedLastModified": &feed_state.last_modified,
+                        "feedContentHash": &feed_state.content_hash,
+                        "feedVideoIds": &feed_state.video_ids,
+                        "feedEmptyPlaylistCheckedAt": feed_state.empty_playlist_checked_at,
+                    }
+                },
+                None,
+            )
+            .await?;
+
+        Ok(())
     }
 }
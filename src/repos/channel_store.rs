@@ -0,0 +1,235 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use mongodb::bson::Document;
+
+use crate::{
+    classifiers::genre_classifier::GenreScore,
+    models::{feed_state::FeedState, upload_pattern::UploadPattern},
+    utils::{
+        ban_evasion_utils::BanEvasionMatch, channel_page_utils::ChannelPageHints,
+        gear_utils::GearCount, link_utils::LinkCheck, topic_drift_utils::TopicDrift,
+    },
+};
+
+/// Storage of channel documents, implemented for MongoDB by `ChannelRepository` and for
+/// PostgreSQL by `PostgresChannelStore`.
+#[async_trait]
+pub trait ChannelStore: Send + Sync {
+    async fn exists(&self, channel_id: &str) -> Result<bool, Error>;
+
+    async fn get_id_by_handle(&self, handle: &str) -> Result<Option<String>, Error>;
+
+    async fn find_by_id(&self, id: &str) -> Result<Option<Document>, Error>;
+
+    async fn get_all_ids(&self) -> Result<Vec<String>, Error>;
+
+    /// Returns up to `limit` channels ordered by id, starting after `after_id`.
+    async fn get_page(
+        &self,
+        after_id: Option<&str>,
+        min_subscribers: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<Document>, Error>;
+
+    /// Channels on probation are left out like deactivated ones, they do not seed further work.
+    async fn get_ids_upload_last_month(
+        &self,
+        min_subscribers_count: i64,
+    ) -> Result<Vec<String>, Error>;
+
+    /// Filters channels by lowercase country code and/or topic name as stored by the channel scraper.
+    async fn get_ids_by_country_and_topic(
+        &self,
+        country: Option<&str>,
+        topic: Option<&str>,
+    ) -> Result<Vec<String>, Error>;
+
+    async fn get_ids_last_upload_before(
+        &self,
+        last_upload_before: chrono::DateTime<Utc>,
+    ) -> Result<Vec<String>, Error>;
+
+    /// Channels without a `nextScrapeAt` have not been scheduled yet and are always due.
+    /// Deactivated channels are never due.
+    async fn get_ids_due_for_scrape(
+        &self,
+        now: chrono::DateTime<Utc>,
+    ) -> Result<Vec<String>, Error>;
+
+    async fn get_ids_about_crawled_before(
+        &self,
+        crawled_before: chrono::DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<String>, Error>;
+
+    /// Deactivated channels are skipped, only the channel scraper brings them back.
+    async fn get_ids_reclassified_before(
+        &self,
+        reclassified_before: chrono::DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<String>, Error>;
+
+    /// Channels whose avatar or banner changed at or after `since`, the latest change first.
+    async fn get_ids_rebranded_since(
+        &self,
+        since: chrono::DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<String>, Error>;
+
+    async fn get_ids_last_crawled_before(
+        &self,
+        last_crawl_before: chrono::DateTime<Utc>,
+        last_upload_after: chrono::DateTime<Utc>,
+    ) -> Result<Vec<String>, Error>;
+
+    async fn count_grouped_by(
+        &self,
+        field: &str,
+        default_key: &str,
+    ) -> Result<Vec<(String, i64)>, Error>;
+
+    /// Counts channels first stored at or after `since`.
+    async fn count_created_since(&self, since: chrono::DateTime<Utc>) -> Result<i64, Error>;
+
+    /// Counts active channels by country that uploaded at or after `uploaded_since`, most first.
+    async fn count_uploading_by_country(
+        &self,
+        uploaded_since: i64,
+        limit: i64,
+    ) -> Result<Vec<(String, i64)>, Error>;
+
+    async fn get_ids_with_due_refresh_override(
+        &self,
+        now: chrono::DateTime<Utc>,
+    ) -> Result<Vec<String>, Error>;
+
+    /// Returns false if no channel exists for the id.
+    async fn set_refresh_override(
+        &self,
+        id: &str,
+        interval_seconds: i64,
+        until_timestamp: i64,
+    ) -> Result<bool, Error>;
+
+    async fn clear_refresh_override(&self, id: &str) -> Result<bool, Error>;
+
+    /// Returns the manual `classificationOverride`, which wins over the guitar terms.
+    async fn get_classification_override(&self, id: &str) -> Result<Option<String>, Error>;
+
+    /// Returns false if no channel exists for the id.
+    async fn set_classification_override(
+        &self,
+        id: &str,
+        classification_override: &str,
+    ) -> Result<bool, Error>;
+
+    async fn clear_classification_override(&self, id: &str) -> Result<bool, Error>;
+
+    async fn get_language(&self, id: &str) -> Result<Option<String>, Error>;
+
+    async fn get_detected_language(&self, id: &str) -> Result<String, Error>;
+
+    async fn delete(&self, id: &str) -> Result<(), Error>;
+
+    /// Sets the fields of the channel, `createdAt` is added when it is new. Returns whether it
+    /// was new.
+    async fn upsert(&self, id: &str, channel: Document) -> bool;
+
+    async fn set_video_count_last_upload(
+        &self,
+        id: &str,
+        video_count: i64,
+        last_upload_timestamp: i64,
+    );
+
+    /// Schedules the next scrape after a successful one and resets `scrapeFailures`.
+    async fn set_scrape_schedule(
+        &self,
+        id: &str,
+        next_scrape_at: i64,
+        upload_interval_seconds: Option<i64>,
+    ) -> Result<(), Error>;
+
+    /// Stores the failed scrapes in a row and when to try again.
+    async fn set_scrape_failure(
+        &self,
+        id: &str,
+        failures: i64,
+        next_scrape_at: i64,
+    ) -> Result<(), Error>;
+
+    async fn set_about(
+        &self,
+        id: &str,
+        links: Vec<Document>,
+        contact_emails: Vec<String>,
+    ) -> Result<(), Error>;
+
+    /// Stores the verification badge and monetization hints found on the channel page.
+    async fn set_page_hints(&self, id: &str, hints: &ChannelPageHints) -> Result<(), Error>;
+
+    /// Stores the changed metadata fields of the channel and sets `metadataVerifiedAt`.
+    async fn set_verified_metadata(&self, id: &str, changes: Document) -> Result<(), Error>;
+
+    /// Stores the liveness of the about links in `linkChecks`, next to `links` so a new about
+    /// crawl keeps the checks of its links.
+    async fn set_link_checks(&self, id: &str, checks: &[LinkCheck]) -> Result<(), Error>;
+
+    /// Stores when the channel typically uploads as `uploadPattern`.
+    async fn set_upload_pattern(&self, id: &str, pattern: &UploadPattern) -> Result<(), Error>;
+
+    async fn get_upload_pattern(&self, id: &str) -> Result<Option<UploadPattern>, Error>;
+
+    /// Stores the genre tags and their scores, replacing the previous ones.
+    async fn set_genre_tags(&self, id: &str, genres: &[GenreScore]) -> Result<(), Error>;
+
+    /// Stores the gear aggregated from the videos of the channel.
+    async fn set_gear(&self, id: &str, gear: &[GearCount]) -> Result<(), Error>;
+
+    /// Sets the lifecycle and records the transition from the previous one in
+    /// `lifecycleHistory`.
+    async fn set_lifecycle(
+        &self,
+        id: &str,
+        lifecycle: &str,
+        previous: Option<&str>,
+    ) -> Result<(), Error>;
+
+    /// Flags the channel as a likely duplicate of the canonical channel.
+    async fn set_duplicate_of(
+        &self,
+        id: &str,
+        canonical_id: &str,
+        reason: &str,
+    ) -> Result<(), Error>;
+
+    /// Stores the detected drift away from guitar content in `topicDrift`, `None` clears it.
+    async fn set_topic_drift(&self, id: &str, drift: Option<&TopicDrift>) -> Result<(), Error>;
+
+    /// Stores the blocked channel the channel likely reuploads or evades a ban of in
+    /// `banEvasion`.
+    async fn set_ban_evasion(&self, id: &str, ban_evasion: &BanEvasionMatch) -> Result<(), Error>;
+
+    /// Stores the avatar hash as `avatarHash`, see `image_change_utils`.
+    async fn set_avatar_hash(&self, id: &str, avatar_hash: u64) -> Result<(), Error>;
+
+    /// Deactivates a channel merged into the canonical channel and redirects to it.
+    async fn set_merged(&self, id: &str, canonical_id: &str) -> Result<(), Error>;
+
+    async fn set_scrape_error(&self, id: &str, error: String);
+
+    async fn set_reclassified(&self, id: &str) -> Result<(), Error>;
+
+    async fn deactivate(&self, id: &str, reason: &str) -> Result<(), Error>;
+
+    /// Returns the channel id stored in `redirectsTo`, if the channel moved to another id.
+    async fn get_redirect(&self, id: &str) -> Result<Option<String>, Error>;
+
+    /// Deactivates the channel and points it to the id it moved to.
+    async fn set_redirect(&self, id: &str, redirects_to: &str) -> Result<(), Error>;
+
+    async fn get_feed_state(&self, id: &str) -> Result<FeedState, Error>;
+
+    async fn set_feed_state(&self, id: &str, feed_state: &FeedState) -> Result<(), Error>;
+}
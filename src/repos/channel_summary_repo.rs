@@ -0,0 +1,85 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, DateTime, Document};
+use mongodb::options::UpdateOptions;
+use mongodb::{Client, Collection};
+
+use crate::repos::channel_summary_store::ChannelSummaryStore;
+use crate::utils::db::get_db_name;
+
+pub struct ChannelSummaryRepository {
+    collection: Collection<Document>,
+}
+
+impl ChannelSummaryRepository {
+    pub fn new(client: &Client, environment: &str) -> ChannelSummaryRepository {
+        let db = client.database(&get_db_name(environment));
+        let channel_summaries = db.collection::<Document>("channel_summary");
+
+        ChannelSummaryRepository {
+            collection: channel_summaries,
+        }
+    }
+}
+
+#[async_trait]
+impl ChannelSummaryStore for ChannelSummaryRepository {
+    async fn update(&self, channel_id: &str, mut fields: Document) -> Result<(), Error> {
+        let update_options = UpdateOptions::builder().upsert(true).build();
+        fields.insert("updatedAt", DateTime::now());
+
+        self.collection
+            .update_one(
+                doc! {"_id": channel_id},
+                doc! {"$set": fields},
+                update_options,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn update_with_views(
+        &self,
+        channel_id: &str,
+        mut fields: Document,
+        views_delta: i64,
+    ) -> Result<(), Error> {
+        let update_options = UpdateOptions::builder().upsert(true).build();
+        fields.insert("updatedAt", DateTime::now());
+
+        // A pipeline, so the average is taken from the total after the change
+        let pipeline = vec![
+            doc! {"$set": fields},
+            doc! {"$set": {"totalViews": {"$add": [{"$ifNull": ["$totalViews", 0]}, views_delta]}}},
+            doc! {"$set": {"averageViews": {"$cond": [
+                {"$gt": ["$videoCount", 0]},
+                {"$toLong": {"$floor": {"$divide": ["$totalViews", "$videoCount"]}}},
+                0,
+            ]}}},
+        ];
+
+        self.collection
+            .update_one(doc! {"_id": channel_id}, pipeline, update_options)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn record_index_latency(&self, channel_id: &str, seconds: i64) -> Result<(), Error> {
+        let update_options = UpdateOptions::builder().upsert(true).build();
+
+        self.collection
+            .update_one(
+                doc! {"_id": channel_id},
+                doc! {
+                    "$set": {"indexLatency.lastSeconds": seconds, "updatedAt": DateTime::now()},
+                    "$inc": {"indexLatency.sumSeconds": seconds, "indexLatency.count": 1},
+                },
+                update_options,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::Document;
+
+/// Channel cards for the website, kept up to date by the video scraper so they can be read without
+/// aggregating the videos of a channel. Implemented for MongoDB by `ChannelSummaryRepository` and
+/// for PostgreSQL by `PostgresChannelSummaryStore`.
+#[async_trait]
+pub trait ChannelSummaryStore: Send + Sync {
+    /// Sets the given fields of the summary, the others keep their values.
+    async fn update(&self, channel_id: &str, fields: Document) -> Result<(), Error>;
+
+    /// Sets the given fields and adds `views_delta` to `totalViews`, `averageViews` follows from
+    /// the new total and `videoCount`.
+    async fn update_with_views(
+        &self,
+        channel_id: &str,
+        fields: Document,
+        views_delta: i64,
+    ) -> Result<(), Error>;
+
+    /// Adds the seconds between the upload of a video and its first crawl, so the average index
+    /// latency of the channel is `sumSeconds / count`.
+    async fn record_index_latency(&self, channel_id: &str, seconds: i64) -> Result<(), Error>;
+}
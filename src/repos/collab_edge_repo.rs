@@ -0,0 +1,109 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use futures::stream::TryStreamExt;
+use mongodb::bson::{doc, Document};
+use mongodb::options::UpdateOptions;
+use mongodb::{Client, Collection};
+
+use crate::repos::collab_edge_store::CollabEdgeStore;
+use crate::utils::db::get_db_name;
+
+pub struct CollabEdgeRepository {
+    collection: Collection<Document>,
+}
+
+impl CollabEdgeRepository {
+    pub fn new(client: &Client, environment: &str) -> CollabEdgeRepository {
+        let db = client.database(&get_db_name(environment));
+        let collab_edges = db.collection::<Document>("collab_edges");
+
+        CollabEdgeRepository {
+            collection: collab_edges,
+        }
+    }
+}
+
+#[async_trait]
+impl CollabEdgeStore for CollabEdgeRepository {
+    async fn add_mention(
+        &self,
+        from: &str,
+        to: &str,
+        kind: &str,
+        video_id: &str,
+    ) -> Result<(), Error> {
+        let now = mongodb::bson::DateTime::now();
+        let update = doc! {
+            "$set": {
+                "from": from,
+                "to": to,
+                "lastSeenAt": now,
+            },
+            "$setOnInsert": {
+                "firstSeenAt": now,
+            },
+            "$addToSet": {
+                "kinds": kind,
+                "videoIds": video_id,
+            }
+        };
+
+        let update_options = UpdateOptions::builder().upsert(true).build();
+
+        self.collection
+            .update_one(
+                doc! {"_id": format!("{}:{}", from, to)},
+                update,
+                update_options,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_strong_targets(
+        &self,
+        min_channels: i32,
+        min_videos: i32,
+    ) -> Result<Vec<String>, Error> {
+        let pipeline = vec![
+            doc! { "$match": { "queuedAt": { "$exists": false } } },
+            doc! {
+                "$group": {
+                    "_id": "$to",
+                    "channels": { "$sum": 1 },
+                    "videos": { "$sum": { "$size": "$videoIds" } },
+                }
+            },
+            doc! {
+                "$match": {
+                    "channels": { "$gte": min_channels },
+                    "videos": { "$gte": min_videos },
+                }
+            },
+            doc! { "$sort": { "channels": -1, "videos": -1 } },
+        ];
+
+        let cursor = self.collection.aggregate(pipeline, None).await?;
+        let targets: Vec<Document> = cursor.try_collect().await?;
+
+        let target_ids = targets
+            .iter()
+            .filter_map(|doc| doc.get_str("_id").ok().map(|id| id.to_string()))
+            .collect();
+
+        Ok(target_ids)
+    }
+
+    async fn mark_queued(&self, to: &str) -> Result<(), Error> {
+        self.collection
+            .update_many(
+                doc! {"to": to},
+                doc! {"$set": {"queuedAt": mongodb::bson::DateTime::now()}},
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+use anyhow::Error;
+use async_trait::async_trait;
+
+/// Directed edges from a channel to the channels mentioned in its videos, with the videos and kinds
+/// of the mentions. Implemented for MongoDB by `CollabEdgeRepository` and for PostgreSQL by
+/// `PostgresCollabEdgeStore`.
+#[async_trait]
+pub trait CollabEdgeStore: Send + Sync {
+    /// Recording the same video twice does not count it twice.
+    async fn add_mention(
+        &self,
+        from: &str,
+        to: &str,
+        kind: &str,
+        video_id: &str,
+    ) -> Result<(), Error>;
+
+    /// Returns the not yet queued targets mentioned by at least `min_channels` channels and in at
+    /// least `min_videos` videos, most mentioned first. A single channel mentioning its own side
+    /// channel in every upload doesn't make it a collaboration.
+    async fn get_strong_targets(
+        &self,
+        min_channels: i32,
+        min_videos: i32,
+    ) -> Result<Vec<String>, Error>;
+
+    async fn mark_queued(&self, to: &str) -> Result<(), Error>;
+}
@@ -0,0 +1,142 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Error;
+use async_trait::async_trait;
+use futures::stream::TryStreamExt;
+use mongodb::bson::{doc, Bson, Document};
+use mongodb::options::{FindOptions, UpdateOptions};
+use mongodb::{Client, Collection};
+
+use crate::repos::comment_store::CommentStore;
+use crate::utils::db::get_db_name;
+
+pub struct CommentRepository {
+    collection: Collection<Document>,
+}
+
+impl CommentRepository {
+    pub fn new(client: &Client, environment: &str) -> CommentRepository {
+        let db = client.database(&get_db_name(environment));
+        let comments = db.collection::<Document>("comments");
+
+        CommentRepository {
+            collection: comments,
+        }
+    }
+}
+
+#[async_trait]
+impl CommentStore for CommentRepository {
+    async fn upsert(&self, id: &str, comment: Document) -> Result<(), Error> {
+        let update_options = UpdateOptions::builder().upsert(true).build();
+
+        self.collection
+            .update_one(doc! {"_id": id}, doc! {"$set": comment}, update_options)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_commenters_by_channel(
+        &self,
+        published_after: i64,
+    ) -> Result<HashMap<String, HashSet<String>>, Error> {
+        let pipeline = vec![
+            doc! {
+                "$match": {
+                    "publishedAt": { "$gte": published_after },
+                    "authorChannelId": { "$exists": true },
+                }
+            },
+            doc! {
+                "$group": {
+                    "_id": "$channelId",
+                    "commenters": { "$addToSet": "$authorChannelId" },
+                }
+            },
+        ];
+
+        let cursor = self.collection.aggregate(pipeline, None).await?;
+        let groups: Vec<Document> = cursor.try_collect().await?;
+
+        let commenters = groups
+            .iter()
+            .filter_map(|doc| {
+                let channel_id = doc.get_str("_id").ok()?.to_string();
+                let commenters = doc
+                    .get_array("commenters")
+                    .ok()?
+                    .iter()
+                    .filter_map(|commenter| commenter.as_str().map(|c| c.to_string()))
+                    .collect();
+
+                Some((channel_id, commenters))
+            })
+            .collect();
+
+        Ok(commenters)
+    }
+
+    async fn get_unscored(&self, limit: i64) -> Result<Vec<(Bson, String, String)>, Error> {
+        let find_options = FindOptions::builder()
+            .projection(doc! { "_id": 1, "videoId": 1, "text": 1 })
+            .limit(limit)
+            .build();
+
+        let cursor = self
+            .collection
+            .find(
+                doc! {
+                    "sentimentScore": { "$exists": false },
+                    "videoId": { "$type": "string" },
+                    "text": { "$type": "string" },
+                },
+                find_options,
+            )
+            .await?;
+        let comments: Vec<Document> = cursor.try_collect().await?;
+
+        Ok(comments
+            .into_iter()
+            .filter_map(|comment| {
+                Some((
+                    comment.get("_id")?.clone(),
+                    comment.get_str("videoId").ok()?.to_string(),
+                    comment.get_str("text").ok()?.to_string(),
+                ))
+            })
+            .collect())
+    }
+
+    async fn set_sentiment_score(&self, id: &Bson, score: Option<f64>) -> Result<(), Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {"$set": {"sentimentScore": score}},
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_sentiment_scores(&self, video_id: &str) -> Result<Vec<f64>, Error> {
+        let find_options = FindOptions::builder()
+            .projection(doc! { "sentimentScore": 1 })
+            .build();
+
+        let cursor = self
+            .collection
+            .find(
+                doc! {"videoId": video_id, "sentimentScore": { "$type": "double" }},
+                find_options,
+            )
+            .await?;
+        let comments: Vec<Document> = cursor.try_collect().await?;
+
+        Ok(comments
+            .iter()
+            .filter_map(|comment| comment.get_f64("sentimentScore").ok())
+            .collect())
+    }
+}
@@ -0,0 +1,29 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{Bson, Document};
+
+/// Comments of stored videos, one document per comment with `videoId`, `channelId` of the video,
+/// `authorChannelId` of the commenter, `text` and `publishedAt`. Implemented for MongoDB by
+/// `CommentRepository` and for PostgreSQL by `PostgresCommentStore`.
+#[async_trait]
+pub trait CommentStore: Send + Sync {
+    /// Comment fields are replaced, a stored `sentimentScore` is kept.
+    async fn upsert(&self, id: &str, comment: Document) -> Result<(), Error>;
+
+    /// Returns the distinct commenters of each channel since the given timestamp.
+    async fn get_commenters_by_channel(
+        &self,
+        published_after: i64,
+    ) -> Result<HashMap<String, HashSet<String>>, Error>;
+
+    /// Returns up to `limit` comments with a text and without `sentimentScore` as
+    /// `(_id, videoId, text)`.
+    async fn get_unscored(&self, limit: i64) -> Result<Vec<(Bson, String, String)>, Error>;
+
+    /// Comments without sentiment terms get a null score, so they are not read again.
+    async fn set_sentiment_score(&self, id: &Bson, score: Option<f64>) -> Result<(), Error>;
+
+    async fn get_sentiment_scores(&self, video_id: &str) -> Result<Vec<f64>, Error>;
+}
@@ -0,0 +1,54 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, Bson, Document};
+use mongodb::{Client, Collection, Database};
+
+use crate::repos::corpus_snapshot_store::CorpusSnapshotStore;
+use crate::utils::db::get_db_name;
+
+pub struct CorpusSnapshotRepository {
+    db: Database,
+    collection: Collection<Document>,
+}
+
+impl CorpusSnapshotRepository {
+    pub fn new(client: &Client, environment: &str) -> CorpusSnapshotRepository {
+        let db = client.database(&get_db_name(environment));
+        let snapshots = db.collection::<Document>("corpussnapshots");
+
+        CorpusSnapshotRepository {
+            db,
+            collection: snapshots,
+        }
+    }
+}
+
+#[async_trait]
+impl CorpusSnapshotStore for CorpusSnapshotRepository {
+    async fn insert(&self, snapshot: Document) -> Result<(), Error> {
+        self.collection.insert_one(snapshot, None).await?;
+
+        Ok(())
+    }
+
+    async fn get_collection_size(&self, collection_name: &str) -> Result<(i64, i64), Error> {
+        let stats = self
+            .db
+            .run_command(doc! { "collStats": collection_name }, None)
+            .await?;
+
+        Ok((
+            get_number(&stats, "size"),
+            get_number(&stats, "storageSize"),
+        ))
+    }
+}
+
+fn get_number(doc: &Document, key: &str) -> i64 {
+    match doc.get(key) {
+        Some(Bson::Int32(value)) => *value as i64,
+        Some(Bson::Int64(value)) => *value,
+        Some(Bson::Double(value)) => *value as i64,
+        _ => 0,
+    }
+}
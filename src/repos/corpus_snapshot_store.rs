@@ -0,0 +1,13 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::Document;
+
+/// Snapshots of the corpus size taken by the corpus snapshot job. Implemented for MongoDB by
+/// `CorpusSnapshotRepository` and for PostgreSQL by `PostgresCorpusSnapshotStore`.
+#[async_trait]
+pub trait CorpusSnapshotStore: Send + Sync {
+    async fn insert(&self, snapshot: Document) -> Result<(), Error>;
+
+    /// Returns the uncompressed data size and the allocated storage size of a collection in bytes.
+    async fn get_collection_size(&self, collection_name: &str) -> Result<(i64, i64), Error>;
+}
@@ -0,0 +1,69 @@
+use std::collections::HashSet;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::stream::TryStreamExt;
+use mongodb::bson::{doc, DateTime, Document};
+use mongodb::options::{FindOptions, ReplaceOptions};
+use mongodb::{Client, Collection};
+
+use crate::repos::course_store::{get_course_documents, CourseStore, PlaylistCourse};
+use crate::utils::db::get_db_name;
+
+pub struct CourseRepository {
+    collection: Collection<Document>,
+}
+
+impl CourseRepository {
+    pub fn new(client: &Client, environment: &str) -> CourseRepository {
+        let db = client.database(&get_db_name(environment));
+        let courses = db.collection::<Document>("courses");
+
+        CourseRepository {
+            collection: courses,
+        }
+    }
+}
+
+#[async_trait]
+impl CourseStore for CourseRepository {
+    async fn get_ids_detected_since(
+        &self,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<HashSet<String>, Error> {
+        let find_options = FindOptions::builder().projection(doc! {"_id": 1}).build();
+        let cursor = self
+            .collection
+            .find(
+                doc! {"detectedAt": {"$gte": DateTime::from_chrono(since)}},
+                find_options,
+            )
+            .await?;
+        let entries: Vec<Document> = cursor.try_collect().await?;
+
+        Ok(entries
+            .iter()
+            .filter_map(|entry| entry.get_str("_id").ok().map(|id| id.to_string()))
+            .collect())
+    }
+
+    async fn replace(&self, channel_id: &str, courses: &[PlaylistCourse]) -> Result<(), Error> {
+        let courses = get_course_documents(courses);
+        let replace_options = ReplaceOptions::builder().upsert(true).build();
+
+        self.collection
+            .replace_one(
+                doc! {"_id": channel_id},
+                doc! {
+                    "_id": channel_id,
+                    "courses": courses,
+                    "detectedAt": DateTime::now(),
+                },
+                replace_options,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use std::collections::HashSet;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use mongodb::bson::{doc, Document};
+
+use crate::utils::course_utils::Course;
+
+/// A course detected in a playlist of a channel.
+pub struct PlaylistCourse {
+    pub playlist_id: String,
+    pub title: String,
+    pub course: Course,
+}
+
+/// The playlists of each channel that look like courses, with their lessons in course order.
+/// Channels without courses are kept too, so they are not scanned again before they are due.
+/// Implemented for MongoDB by `CourseRepository` and for PostgreSQL by `PostgresCourseStore`.
+#[async_trait]
+pub trait CourseStore: Send + Sync {
+    async fn get_ids_detected_since(
+        &self,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<HashSet<String>, Error>;
+
+    async fn replace(&self, channel_id: &str, courses: &[PlaylistCourse]) -> Result<(), Error>;
+}
+
+/// The stored form of the courses of a channel, the same for every backend.
+pub fn get_course_documents(courses: &[PlaylistCourse]) -> Vec<Document> {
+    courses
+        .iter()
+        .map(|playlist_course| {
+            let lessons = playlist_course
+                .course
+                .lessons
+                .iter()
+                .map(|lesson| {
+                    doc! {
+                        "video": &lesson.video_id,
+                        "title": &lesson.title,
+                        "order": lesson.order as i64,
+                        "number": lesson.number.map(i64::from),
+                    }
+                })
+                .collect::<Vec<Document>>();
+
+            doc! {
+                "playlist": &playlist_course.playlist_id,
+                "title": &playlist_course.title,
+                "lessonCount": lessons.len() as i64,
+                "lessons": lessons,
+                "signals": &playlist_course.course.signals,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,187 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::stream::TryStreamExt;
+use mongodb::bson::{doc, DateTime, Document};
+use mongodb::options::{FindOptions, IndexOptions};
+use mongodb::{Client, Collection, IndexModel};
+
+use crate::errors::crawler_error::CrawlerError;
+use crate::models::{crawl_stats::CrawlStats, daily_digest::SlowCrawl};
+use crate::repos::crawl_audit_store::{
+    get_crawl_audit_entry, CrawlAuditStore, CRAWL_AUDIT_RETENTION, CRAWL_KIND_VIDEOS,
+};
+use crate::utils::db::get_db_name;
+
+pub struct CrawlAuditRepository {
+    collection: Collection<Document>,
+    has_ttl_index: AtomicBool,
+}
+
+impl CrawlAuditRepository {
+    pub fn new(client: &Client, environment: &str) -> CrawlAuditRepository {
+        let db = client.database(&get_db_name(environment));
+        let crawl_audit = db.collection::<Document>("crawlaudit");
+
+        CrawlAuditRepository {
+            collection: crawl_audit,
+            has_ttl_index: AtomicBool::new(false),
+        }
+    }
+
+    async fn ensure_ttl_index(&self) -> Result<(), Error> {
+        let index = IndexModel::builder()
+            .keys(doc! { "finishedAt": 1 })
+            .options(
+                IndexOptions::builder()
+                    .expire_after(CRAWL_AUDIT_RETENTION)
+                    .build(),
+            )
+            .build();
+
+        self.collection.create_index(index, None).await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CrawlAuditStore for CrawlAuditRepository {
+    async fn insert(
+        &self,
+        channel_id: &str,
+        kind: &str,
+        started_at: chrono::DateTime<Utc>,
+        stats: &CrawlStats,
+        error: Option<&CrawlerError>,
+    ) -> Result<(), Error> {
+        if !self.has_ttl_index.load(Ordering::Relaxed) {
+            self.ensure_ttl_index().await?;
+            self.has_ttl_index.store(true, Ordering::Relaxed);
+        }
+
+        let entry = get_crawl_audit_entry(channel_id, kind, started_at, stats, error);
+
+        self.collection.insert_one(entry, None).await?;
+
+        Ok(())
+    }
+
+    async fn get_latest(&self, channel_id: &str, limit: i64) -> Result<Vec<Document>, Error> {
+        let find_options = FindOptions::builder()
+            .sort(doc! { "startedAt": -1 })
+            .limit(limit)
+            .projection(doc! { "_id": 0 })
+            .build();
+
+        let cursor = self
+            .collection
+            .find(doc! { "channel": channel_id }, find_options)
+            .await?;
+        let runs: Vec<Document> = cursor.try_collect().await?;
+
+        Ok(runs)
+    }
+
+    async fn get_totals(&self, since: chrono::DateTime<Utc>) -> Result<(i64, i64), Error> {
+        let pipeline = vec![
+            doc! { "$match": { "startedAt": { "$gte": DateTime::from_chrono(since) } } },
+            doc! {
+                "$group": {
+                    "_id": null,
+                    "videosUpdated": {
+                        "$sum": { "$cond": [{ "$eq": ["$kind", CRAWL_KIND_VIDEOS] }, "$videosUpdated", 0] }
+                    },
+                    "apiUnits": { "$sum": "$apiUnits" },
+                }
+            },
+        ];
+
+        let cursor = self.collection.aggregate(pipeline, None).await?;
+        let groups: Vec<Document> = cursor.try_collect().await?;
+
+        Ok(groups
+            .first()
+            .map(|group| {
+                (
+                    get_count(group, "videosUpdated"),
+                    get_count(group, "apiUnits"),
+                )
+            })
+            .unwrap_or((0, 0)))
+    }
+
+    async fn get_error_counts(
+        &self,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<BTreeMap<String, i64>, Error> {
+        let pipeline = vec![
+            doc! {
+                "$match": {
+                    "startedAt": { "$gte": DateTime::from_chrono(since) },
+                    "error": { "$ne": null },
+                }
+            },
+            doc! {
+                "$group": {
+                    "_id": { "$ifNull": ["$errorCategory", "other"] },
+                    "count": { "$sum": 1 },
+                }
+            },
+        ];
+
+        let cursor = self.collection.aggregate(pipeline, None).await?;
+        let groups: Vec<Document> = cursor.try_collect().await?;
+
+        Ok(groups
+            .iter()
+            .map(|group| {
+                (
+                    group.get_str("_id").unwrap_or_default().to_string(),
+                    get_count(group, "count"),
+                )
+            })
+            .collect())
+    }
+
+    async fn get_slowest(
+        &self,
+        since: chrono::DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<SlowCrawl>, Error> {
+        let find_options = FindOptions::builder()
+            .sort(doc! { "durationMillis": -1 })
+            .limit(limit)
+            .projection(doc! { "channel": 1, "kind": 1, "durationMillis": 1 })
+            .build();
+
+        let cursor = self
+            .collection
+            .find(
+                doc! { "startedAt": { "$gte": DateTime::from_chrono(since) } },
+                find_options,
+            )
+            .await?;
+        let runs: Vec<Document> = cursor.try_collect().await?;
+
+        Ok(runs
+            .iter()
+            .map(|run| SlowCrawl {
+                channel_id: run.get_str("channel").unwrap_or_default().to_string(),
+                kind: run.get_str("kind").unwrap_or_default().to_string(),
+                duration_millis: get_count(run, "durationMillis"),
+            })
+            .collect())
+    }
+}
+
+// Sums come back as int32 or int64 depending on their size
+fn get_count(document: &Document, key: &str) -> i64 {
+    document
+        .get_i64(key)
+        .or_else(|_| document.get_i32(key).map(i64::from))
+        .unwrap_or(0)
+}
@@ -0,0 +1,80 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use mongodb::bson::{doc, Bson, DateTime, Document};
+
+use crate::errors::crawler_error::CrawlerError;
+use crate::models::{crawl_stats::CrawlStats, daily_digest::SlowCrawl};
+
+pub const CRAWL_KIND_VIDEOS: &str = "videos";
+pub const CRAWL_KIND_CHANNEL: &str = "channel";
+pub const CRAWL_KIND_DISCOVERY: &str = "discovery";
+
+pub const CRAWL_AUDIT_RETENTION: Duration = Duration::from_secs(90 * 24 * 60 * 60);
+
+/// Log of every scrape and discovery run per channel, to debug gaps in the ingestion. Runs are kept
+/// for 90 days. Implemented for MongoDB by `CrawlAuditRepository` and for PostgreSQL by
+/// `PostgresCrawlAuditStore`.
+#[async_trait]
+pub trait CrawlAuditStore: Send + Sync {
+    async fn insert(
+        &self,
+        channel_id: &str,
+        kind: &str,
+        started_at: chrono::DateTime<Utc>,
+        stats: &CrawlStats,
+        error: Option<&CrawlerError>,
+    ) -> Result<(), Error>;
+
+    /// Latest runs of a channel first.
+    async fn get_latest(&self, channel_id: &str, limit: i64) -> Result<Vec<Document>, Error>;
+
+    /// Videos updated by the video scraper and api units spent by all runs started since.
+    async fn get_totals(&self, since: chrono::DateTime<Utc>) -> Result<(i64, i64), Error>;
+
+    /// Failed runs started since by error category. Runs logged before categories were kept
+    /// count as `other`.
+    async fn get_error_counts(
+        &self,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<BTreeMap<String, i64>, Error>;
+
+    /// Longest runs started since, slowest first.
+    async fn get_slowest(
+        &self,
+        since: chrono::DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<SlowCrawl>, Error>;
+}
+
+pub fn get_crawl_audit_entry(
+    channel_id: &str,
+    kind: &str,
+    started_at: chrono::DateTime<Utc>,
+    stats: &CrawlStats,
+    error: Option<&CrawlerError>,
+) -> Document {
+    let finished_at = Utc::now();
+
+    doc! {
+        "channel": channel_id,
+        "kind": kind,
+        "startedAt": DateTime::from_chrono(started_at),
+        "finishedAt": DateTime::from_chrono(finished_at),
+        "durationMillis": (finished_at - started_at).num_milliseconds(),
+        "videosSeen": stats.videos_seen,
+        "videosUpdated": stats.videos_updated,
+        "videosSkipped": stats.videos_skipped,
+        "apiUnits": stats.api_units,
+        "failedVideos": stats
+            .failed_videos
+            .iter()
+            .map(|(video_id, error)| doc! {"video": video_id, "error": error})
+            .collect::<Vec<Document>>(),
+        "error": error.map(|e| Bson::String(e.to_string())).unwrap_or(Bson::Null),
+        "errorCategory": error.map(|e| Bson::String(e.category().to_string())).unwrap_or(Bson::Null),
+    }
+}
@@ -0,0 +1,52 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use futures::stream::TryStreamExt;
+use mongodb::bson::{doc, Document};
+use mongodb::options::FindOptions;
+use mongodb::{Client, Collection};
+
+use crate::models::daily_digest::DailyDigest;
+use crate::repos::digest_report_store::DigestReportStore;
+use crate::utils::db::get_db_name;
+
+pub struct DigestReportRepository {
+    collection: Collection<Document>,
+}
+
+impl DigestReportRepository {
+    pub fn new(client: &Client, environment: &str) -> DigestReportRepository {
+        let db = client.database(&get_db_name(environment));
+        let reports = db.collection::<Document>("digestreports");
+
+        DigestReportRepository {
+            collection: reports,
+        }
+    }
+}
+
+#[async_trait]
+impl DigestReportStore for DigestReportRepository {
+    async fn insert(&self, digest: &DailyDigest) -> Result<(), Error> {
+        self.collection
+            .insert_one(mongodb::bson::to_document(digest)?, None)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_latest(&self, limit: i64) -> Result<Vec<DailyDigest>, Error> {
+        let find_options = FindOptions::builder()
+            .sort(doc! { "to": -1 })
+            .limit(limit)
+            .projection(doc! { "_id": 0 })
+            .build();
+
+        let cursor = self.collection.find(None, find_options).await?;
+        let reports: Vec<Document> = cursor.try_collect().await?;
+
+        Ok(reports
+            .into_iter()
+            .map(mongodb::bson::from_document)
+            .collect::<Result<Vec<DailyDigest>, _>>()?)
+    }
+}
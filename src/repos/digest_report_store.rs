@@ -0,0 +1,14 @@
+use anyhow::Error;
+use async_trait::async_trait;
+
+use crate::models::daily_digest::DailyDigest;
+
+/// The digests of the reporting job, one per run. Implemented for MongoDB by
+/// `DigestReportRepository` and for PostgreSQL by `PostgresDigestReportStore`.
+#[async_trait]
+pub trait DigestReportStore: Send + Sync {
+    async fn insert(&self, digest: &DailyDigest) -> Result<(), Error>;
+
+    /// Latest digests first.
+    async fn get_latest(&self, limit: i64) -> Result<Vec<DailyDigest>, Error>;
+}
@@ -0,0 +1,105 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::stream::TryStreamExt;
+use mongodb::bson::{doc, DateTime, Document};
+use mongodb::options::UpdateOptions;
+use mongodb::{Client, Collection};
+
+use crate::models::discovery_source_report::DiscoverySourceReport;
+use crate::repos::discovery_provenance_store::{
+    DiscoveryProvenanceStore, DISCOVERY_OUTCOME_ACCEPTED, DISCOVERY_OUTCOME_FAILED,
+    DISCOVERY_OUTCOME_REJECTED,
+};
+use crate::utils::db::get_db_name;
+
+pub struct DiscoveryProvenanceRepository {
+    collection: Collection<Document>,
+}
+
+impl DiscoveryProvenanceRepository {
+    pub fn new(client: &Client, environment: &str) -> DiscoveryProvenanceRepository {
+        let db = client.database(&get_db_name(environment));
+        let provenance = db.collection::<Document>("discoveryprovenance");
+
+        DiscoveryProvenanceRepository {
+            collection: provenance,
+        }
+    }
+}
+
+#[async_trait]
+impl DiscoveryProvenanceStore for DiscoveryProvenanceRepository {
+    async fn record(
+        &self,
+        channel_id: &str,
+        source: &str,
+        outcome: &str,
+        api_units: i64,
+    ) -> Result<(), Error> {
+        let now = DateTime::now();
+        let update_options = UpdateOptions::builder().upsert(true).build();
+
+        self.collection
+            .update_one(
+                doc! {"_id": channel_id},
+                doc! {
+                    "$setOnInsert": {"source": source, "discoveredAt": now},
+                    "$set": {"outcome": outcome, "decidedAt": now},
+                    "$inc": {"apiUnits": api_units, "attempts": 1},
+                },
+                update_options,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_report_since(
+        &self,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<Vec<DiscoverySourceReport>, Error> {
+        let decided_after = DateTime::from_chrono(since);
+        let count_outcome = |outcome: &str| {
+            doc! { "$sum": { "$cond": [{ "$eq": ["$outcome", outcome] }, 1, 0] } }
+        };
+
+        let pipeline = vec![
+            doc! { "$match": { "decidedAt": { "$gte": decided_after } } },
+            doc! {
+                "$group": {
+                    "_id": "$source",
+                    "channels": { "$sum": 1 },
+                    "accepted": count_outcome(DISCOVERY_OUTCOME_ACCEPTED),
+                    "rejected": count_outcome(DISCOVERY_OUTCOME_REJECTED),
+                    "failed": count_outcome(DISCOVERY_OUTCOME_FAILED),
+                    "apiUnits": { "$sum": "$apiUnits" },
+                }
+            },
+            doc! { "$sort": { "channels": -1 } },
+        ];
+
+        let cursor = self.collection.aggregate(pipeline, None).await?;
+        let groups: Vec<Document> = cursor.try_collect().await?;
+
+        // Sums come back as int32 or int64 depending on their size
+        let get_count = |group: &Document, key: &str| {
+            group
+                .get_i64(key)
+                .or_else(|_| group.get_i32(key).map(i64::from))
+                .unwrap_or(0)
+        };
+
+        Ok(groups
+            .iter()
+            .map(|group| DiscoverySourceReport {
+                source: group.get_str("_id").unwrap_or_default().to_string(),
+                channels: get_count(group, "channels"),
+                accepted: get_count(group, "accepted"),
+                rejected: get_count(group, "rejected"),
+                failed: get_count(group, "failed"),
+                api_units: get_count(group, "apiUnits"),
+            })
+            .collect())
+    }
+}
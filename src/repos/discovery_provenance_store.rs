@@ -0,0 +1,36 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+
+use crate::models::discovery_source_report::DiscoverySourceReport;
+
+pub const DISCOVERY_OUTCOME_ACCEPTED: &str = "accepted";
+pub const DISCOVERY_OUTCOME_REJECTED: &str = "rejected";
+pub const DISCOVERY_OUTCOME_FAILED: &str = "failed";
+
+/// The source that first queued each channel for the channel scraper, with the latest outcome of
+/// its scrape and the quota spent on it. Rejected channels are kept too, unlike in `channels`.
+/// Implemented for MongoDB by `DiscoveryProvenanceRepository` and for PostgreSQL by
+/// `PostgresDiscoveryProvenanceStore`.
+#[async_trait]
+pub trait DiscoveryProvenanceStore: Send + Sync {
+    async fn record(
+        &self,
+        channel_id: &str,
+        source: &str,
+        outcome: &str,
+        api_units: i64,
+    ) -> Result<(), Error>;
+
+    /// Summarizes the channels decided in the last `days` days per source, most channels first.
+    async fn get_report(&self, days: i64) -> Result<Vec<DiscoverySourceReport>, Error> {
+        self.get_report_since(Utc::now() - Duration::days(days))
+            .await
+    }
+
+    /// Summarizes the channels decided since the given time per source, most channels first.
+    async fn get_report_since(
+        &self,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<Vec<DiscoverySourceReport>, Error>;
+}
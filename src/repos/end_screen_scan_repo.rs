@@ -0,0 +1,56 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, DateTime, Document};
+use mongodb::options::UpdateOptions;
+use mongodb::{Client, Collection};
+
+use crate::repos::end_screen_scan_store::EndScreenScanStore;
+use crate::utils::db::get_db_name;
+
+pub struct EndScreenScanRepository {
+    collection: Collection<Document>,
+}
+
+impl EndScreenScanRepository {
+    pub fn new(client: &Client, environment: &str) -> EndScreenScanRepository {
+        let db = client.database(&get_db_name(environment));
+        let scans = db.collection::<Document>("endscreenscans");
+
+        EndScreenScanRepository { collection: scans }
+    }
+}
+
+#[async_trait]
+impl EndScreenScanStore for EndScreenScanRepository {
+    async fn is_scanned(&self, video_id: &str) -> Result<bool, Error> {
+        let count = self
+            .collection
+            .count_documents(doc! {"_id": video_id}, None)
+            .await?;
+
+        Ok(count > 0)
+    }
+
+    async fn insert(
+        &self,
+        video_id: &str,
+        channel_id: &str,
+        promoted_channel_ids: &[String],
+    ) -> Result<(), Error> {
+        let update_options = UpdateOptions::builder().upsert(true).build();
+
+        self.collection
+            .update_one(
+                doc! {"_id": video_id},
+                doc! {"$set": {
+                    "channel": channel_id,
+                    "promotedChannels": promoted_channel_ids,
+                    "scannedAt": DateTime::now(),
+                }},
+                update_options,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
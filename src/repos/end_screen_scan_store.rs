@@ -0,0 +1,17 @@
+use anyhow::Error;
+use async_trait::async_trait;
+
+/// Videos whose watch page was read for end screens and cards, with the channels they promote.
+/// Implemented for MongoDB by `EndScreenScanRepository` and for PostgreSQL by
+/// `PostgresEndScreenScanStore`.
+#[async_trait]
+pub trait EndScreenScanStore: Send + Sync {
+    async fn is_scanned(&self, video_id: &str) -> Result<bool, Error>;
+
+    async fn insert(
+        &self,
+        video_id: &str,
+        channel_id: &str,
+        promoted_channel_ids: &[String],
+    ) -> Result<(), Error>;
+}
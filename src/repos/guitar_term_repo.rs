@@ -1,8 +1,10 @@
 use anyhow::Error;
+use async_trait::async_trait;
 use futures::stream::TryStreamExt;
 use mongodb::bson::{doc, Document};
 use mongodb::{Client, Collection};
 
+use crate::repos::guitar_term_store::GuitarTermStore;
 use crate::utils::db::get_db_name;
 
 pub struct GuitarTermRepository {
@@ -11,13 +13,16 @@ pub struct GuitarTermRepository {
 
 impl GuitarTermRepository {
     pub fn new(client: &Client, environment: &str) -> GuitarTermRepository {
-        let db = client.database(&get_db_name(&environment));
+        let db = client.database(&get_db_name(environment));
         let feeds = db.collection::<Document>("guitarterms");
 
         GuitarTermRepository { collection: feeds }
     }
+}
 
-    pub async fn get_all(&self) -> Result<Vec<String>, Error> {
+#[async_trait]
+impl GuitarTermStore for GuitarTermRepository {
+    async fn get_all(&self) -> Result<Vec<String>, Error> {
         let find_options = mongodb::options::FindOptions::builder()
             .projection(doc! {"_id": 1})
             .build();
@@ -32,4 +37,20 @@ impl GuitarTermRepository {
 
         Ok(ids)
     }
+
+    async fn insert(&self, term: &str) -> Result<(), Error> {
+        let update_options = mongodb::options::UpdateOptions::builder()
+            .upsert(true)
+            .build();
+
+        self.collection
+            .update_one(
+                doc! {"_id": term},
+                doc! {"$setOnInsert": {"_id": term}},
+                update_options,
+            )
+            .await?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,11 @@
+use anyhow::Error;
+use async_trait::async_trait;
+
+/// The terms that make a channel a guitar channel. Implemented for MongoDB by
+/// `GuitarTermRepository` and for PostgreSQL by `PostgresGuitarTermStore`.
+#[async_trait]
+pub trait GuitarTermStore: Send + Sync {
+    async fn get_all(&self) -> Result<Vec<String>, Error>;
+
+    async fn insert(&self, term: &str) -> Result<(), Error>;
+}
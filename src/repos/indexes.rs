@@ -0,0 +1,138 @@
+use anyhow::Error;
+use futures::stream::TryStreamExt;
+use log::{info, warn};
+use mongodb::bson::{doc, Document};
+use mongodb::{Client, IndexModel};
+
+use crate::utils::db::get_db_name;
+
+struct RequiredIndex {
+    collection: &'static str,
+    keys: Document,
+}
+
+/// An index of the postgres backend, on the same expressions its queries use. Found by name, so
+/// migrations creating the same index use the same name.
+struct RequiredPostgresIndex {
+    name: &'static str,
+    table: &'static str,
+    columns: &'static str,
+}
+
+fn get_required_indexes() -> Vec<RequiredIndex> {
+    let index = |collection, keys| RequiredIndex { collection, keys };
+
+    vec![
+        index("videos", doc! { "channel": 1, "publishedAt": -1 }),
+        index("videos", doc! { "channel": 1, "updatedAt": 1 }),
+        index("coldvideos", doc! { "channel": 1, "publishedAt": -1 }),
+        index("channels", doc! { "lastUploadAt": -1 }),
+        index("channels", doc! { "handle": 1 }),
+        index("channels", doc! { "nextScrapeAt": 1 }),
+        index("channels", doc! { "rebrandedAt": -1 }),
+        index("comments", doc! { "videoId": 1 }),
+    ]
+}
+
+fn get_required_postgres_indexes() -> Vec<RequiredPostgresIndex> {
+    let index = |name, table, columns| RequiredPostgresIndex {
+        name,
+        table,
+        columns,
+    };
+
+    vec![
+        index(
+            "videos_channel_published_at_idx",
+            "videos",
+            "channel, ((doc->>'publishedAt')::bigint) DESC",
+        ),
+        index(
+            "channels_last_upload_at_idx",
+            "channels",
+            "((doc->>'lastUploadAt')::bigint) DESC",
+        ),
+        index("channels_handle_idx", "channels", "(doc->>'handle')"),
+        index(
+            "channels_next_scrape_at_idx",
+            "channels",
+            "((doc->>'nextScrapeAt')::bigint)",
+        ),
+        index(
+            "channels_rebranded_at_idx",
+            "channels",
+            "((doc->'rebrandedAt'->>'$date')::bigint) DESC",
+        ),
+        index("comments_video_id_idx", "comments", "(doc->>'videoId')"),
+    ]
+}
+
+/// Creates the indexes the crawler queries rely on if no index with the same keys exists yet,
+/// whatever its name. Missing indexes are logged, so slow scans on large collections show up.
+pub async fn ensure_indexes(client: &Client, environment: &str) -> Result<(), Error> {
+    let db = client.database(&get_db_name(environment));
+    let mut created_count = 0;
+
+    for required in get_required_indexes() {
+        let collection = db.collection::<Document>(required.collection);
+        let existing: Vec<IndexModel> = match collection.list_indexes(None).await {
+            Ok(cursor) => cursor.try_collect().await?,
+            // Collections that do not exist yet have no indexes
+            Err(_) => vec![],
+        };
+
+        if existing.iter().any(|index| index.keys == required.keys) {
+            continue;
+        }
+
+        warn!(
+            "Missing index {} on {}, creating it",
+            required.keys, required.collection
+        );
+        // Default names, so migrations creating the same index find it
+        collection
+            .create_index(IndexModel::builder().keys(required.keys).build(), None)
+            .await?;
+        created_count += 1;
+    }
+
+    info!("Indexes verified, {} created", created_count);
+
+    Ok(())
+}
+
+/// Like `ensure_indexes` for the tables of the postgres backend, in the schema of the connection.
+pub async fn ensure_postgres_indexes(client: &tokio_postgres::Client) -> Result<(), Error> {
+    let existing: Vec<String> = client
+        .query(
+            "SELECT indexname::text FROM pg_indexes WHERE schemaname = current_schema()",
+            &[],
+        )
+        .await?
+        .iter()
+        .map(|row| row.get(0))
+        .collect();
+    let mut created_count = 0;
+
+    for required in get_required_postgres_indexes() {
+        if existing.iter().any(|name| name == required.name) {
+            continue;
+        }
+
+        warn!(
+            "Missing index {} on {}, creating it",
+            required.name, required.table
+        );
+        client
+            .batch_execute(&format!(
+                "CREATE INDEX IF NOT EXISTS {} ON {} ({})",
+                required.name, required.table, required.columns
+            ))
+            .await?;
+        created_count += 1;
+    }
+
+    info!("Indexes verified, {} created", created_count);
+
+    Ok(())
+}
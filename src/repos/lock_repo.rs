@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use mongodb::bson::{doc, DateTime, Document};
+use mongodb::error::{ErrorKind, WriteFailure};
+use mongodb::options::{IndexOptions, UpdateOptions};
+use mongodb::{Client, Collection, IndexModel};
+
+use crate::repos::lock_store::{get_lock_owner, LockStore};
+use crate::utils::db::get_db_name;
+
+const DUPLICATE_KEY_ERROR_CODE: i32 = 11000;
+
+/// Expired leases are removed by a TTL index.
+pub struct LockRepository {
+    collection: Collection<Document>,
+    owner: String,
+}
+
+impl LockRepository {
+    pub fn new(client: &Client, environment: &str) -> LockRepository {
+        let db = client.database(&get_db_name(environment));
+        let locks = db.collection::<Document>("locks");
+
+        LockRepository {
+            collection: locks,
+            owner: get_lock_owner(),
+        }
+    }
+
+    /// Created once on startup.
+    pub async fn ensure_ttl_index(&self) -> Result<(), Error> {
+        let index = IndexModel::builder()
+            .keys(doc! { "expiresAt": 1 })
+            .options(
+                IndexOptions::builder()
+                    .expire_after(Duration::from_secs(0))
+                    .build(),
+            )
+            .build();
+
+        self.collection.create_index(index, None).await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LockStore for LockRepository {
+    async fn acquire(&self, name: &str, lease_seconds: u64) -> Result<bool, Error> {
+        let now = Utc::now();
+        let expires_at = now + chrono::Duration::seconds(lease_seconds as i64);
+
+        let filter = doc! {
+            "_id": name,
+            "$or": [
+                { "owner": &self.owner },
+                { "expiresAt": { "$lt": DateTime::from_millis(now.timestamp_millis()) } },
+            ]
+        };
+        let update = doc! {
+            "$set": {
+                "owner": &self.owner,
+                "expiresAt": DateTime::from_millis(expires_at.timestamp_millis()),
+            }
+        };
+        let update_options = UpdateOptions::builder().upsert(true).build();
+
+        match self
+            .collection
+            .update_one(filter, update, update_options)
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(e) => match *e.kind {
+                // The upsert collides with the lease of another owner
+                ErrorKind::Write(WriteFailure::WriteError(ref write_error))
+                    if write_error.code == DUPLICATE_KEY_ERROR_CODE =>
+                {
+                    Ok(false)
+                }
+                _ => Err(e.into()),
+            },
+        }
+    }
+
+    async fn release_all(&self) -> Result<u64, Error> {
+        let result = self
+            .collection
+            .delete_many(doc! {"owner": &self.owner}, None)
+            .await?;
+
+        Ok(result.deleted_count)
+    }
+}
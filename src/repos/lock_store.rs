@@ -0,0 +1,54 @@
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use log::info;
+use tokio::time::sleep;
+
+// Leases of periodic runs outlive a few runs, another instance only takes over once the owner
+// has missed them
+const LEASE_RUNS: u64 = 3;
+
+static OWNER: OnceLock<String> = OnceLock::new();
+
+/// Named leases shared by all crawler instances. A lease is held by one owner, the process, until
+/// it expires and the owner renews it on every run. The leases of a process that shuts down are
+/// released. Implemented for MongoDB by `LockRepository` and for PostgreSQL by
+/// `PostgresLockStore`.
+#[async_trait]
+pub trait LockStore: Send + Sync {
+    /// Acquires or renews the lease of a run repeated every `interval_seconds`. If another
+    /// instance holds it, waits for one interval and returns false, so the caller skips the run.
+    async fn acquire_run(&self, name: &str, interval_seconds: u64) -> Result<bool, Error> {
+        if self.acquire(name, LEASE_RUNS * interval_seconds).await? {
+            return Ok(true);
+        }
+
+        info!("Lock {} is held by another instance, skipping run", name);
+        sleep(Duration::from_secs(interval_seconds)).await;
+
+        Ok(false)
+    }
+
+    /// Acquires or renews the named lease, returns false if another instance holds it.
+    async fn acquire(&self, name: &str, lease_seconds: u64) -> Result<bool, Error>;
+
+    /// Releases all leases of this process, e.g. on shutdown.
+    async fn release_all(&self) -> Result<u64, Error>;
+}
+
+/// The owner of the leases of this process, shared by all its lock stores.
+pub fn get_lock_owner() -> String {
+    OWNER
+        .get_or_init(|| {
+            let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "crawler".to_string());
+            format!(
+                "{}-{}-{:08x}",
+                host,
+                std::process::id(),
+                rand::random::<u32>()
+            )
+        })
+        .clone()
+}
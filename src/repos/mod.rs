@@ -1,10 +1,112 @@
 pub mod additional_channel_repo;
+pub mod additional_channel_store;
+pub mod apikey_store;
 pub mod apikeys_repo;
+pub mod backfill_repo;
+pub mod backfill_store;
 pub mod blacklist_repo;
+pub mod blacklist_store;
+pub mod blocklist_repo;
+pub mod blocklist_store;
+pub mod caption_repo;
+pub mod caption_store;
+pub mod channel_audit_repo;
+pub mod channel_audit_store;
+pub mod channel_popularity_repo;
+pub mod channel_popularity_store;
+pub mod channel_probation_repo;
+pub mod channel_probation_store;
 pub mod channel_repo;
+pub mod channel_store;
+pub mod channel_summary_repo;
+pub mod channel_summary_store;
+pub mod collab_edge_repo;
+pub mod collab_edge_store;
+pub mod comment_repo;
+pub mod comment_store;
+pub mod corpus_snapshot_repo;
+pub mod corpus_snapshot_store;
+pub mod course_repo;
+pub mod course_store;
+pub mod crawl_audit_repo;
+pub mod crawl_audit_store;
+pub mod digest_report_repo;
+pub mod digest_report_store;
+pub mod discovery_provenance_repo;
+pub mod discovery_provenance_store;
+pub mod end_screen_scan_repo;
+pub mod end_screen_scan_store;
 pub mod guitar_term_repo;
+pub mod guitar_term_store;
+pub mod indexes;
+pub mod lock_repo;
+pub mod lock_store;
 pub mod non_guitar_channel_repo;
+pub mod non_guitar_channel_store;
+pub mod opt_out_repo;
+pub mod opt_out_store;
+pub mod policy_rejection_repo;
+pub mod policy_rejection_store;
+pub mod postgres_additional_channel_store;
+pub mod postgres_apikey_store;
+pub mod postgres_backfill_store;
+pub mod postgres_blacklist_store;
+pub mod postgres_blocklist_store;
+pub mod postgres_caption_store;
+pub mod postgres_channel_audit_store;
+pub mod postgres_channel_popularity_store;
+pub mod postgres_channel_probation_store;
+pub mod postgres_channel_store;
+pub mod postgres_channel_summary_store;
+pub mod postgres_collab_edge_store;
+pub mod postgres_comment_store;
+pub mod postgres_corpus_snapshot_store;
+pub mod postgres_course_store;
+pub mod postgres_crawl_audit_store;
+pub mod postgres_digest_report_store;
+pub mod postgres_discovery_provenance_store;
+pub mod postgres_documents;
+pub mod postgres_end_screen_scan_store;
+pub mod postgres_guitar_term_store;
+pub mod postgres_lock_store;
+pub mod postgres_non_guitar_channel_store;
+pub mod postgres_opt_out_store;
+pub mod postgres_policy_rejection_store;
+pub mod postgres_purge_store;
+pub mod postgres_reconciliation_report_store;
+pub mod postgres_related_channel_store;
+pub mod postgres_resolved_url_store;
+pub mod postgres_review_queue_store;
+pub mod postgres_schema_migrations_store;
+pub mod postgres_settings_store;
+pub mod postgres_site_stats_store;
+pub mod postgres_subscriber_store;
+pub mod postgres_tag_profile_store;
+pub mod postgres_video_store;
+pub mod postgres_view_store;
+pub mod purge_repo;
+pub mod purge_store;
+pub mod quota_breaker_store;
+pub mod reconciliation_report_repo;
+pub mod reconciliation_report_store;
+pub mod related_channel_repo;
+pub mod related_channel_store;
+pub mod resolved_url_repo;
+pub mod resolved_url_store;
+pub mod review_queue_repo;
+pub mod review_queue_store;
+pub mod schema_migrations_repo;
+pub mod schema_migrations_store;
 pub mod settings_repo;
+pub mod settings_store;
+pub mod site_stats_repo;
+pub mod site_stats_store;
+pub mod store_factory;
 pub mod subscriber_repo;
+pub mod subscriber_store;
+pub mod tag_profile_repo;
+pub mod tag_profile_store;
 pub mod video_repo;
+pub mod video_store;
 pub mod view_repo;
+pub mod view_store;
@@ -1,7 +1,9 @@
 use anyhow::Error;
+use async_trait::async_trait;
 use mongodb::bson::{doc, DateTime, Document};
 use mongodb::{Client, Collection};
 
+use crate::repos::non_guitar_channel_store::NonGuitarChannelStore;
 use crate::utils::db::get_db_name;
 
 pub struct NonGuitarChannelRepository {
@@ -10,15 +12,18 @@ pub struct NonGuitarChannelRepository {
 
 impl NonGuitarChannelRepository {
     pub fn new(client: &Client, environment: &str) -> NonGuitarChannelRepository {
-        let db = client.database(&get_db_name(&environment));
+        let db = client.database(&get_db_name(environment));
         let channels = db.collection::<Document>("nonguitarchannels");
 
         NonGuitarChannelRepository {
             collection: channels,
         }
     }
+}
 
-    pub async fn exists(&self, channel_id: &str) -> Result<bool, Error> {
+#[async_trait]
+impl NonGuitarChannelStore for NonGuitarChannelRepository {
+    async fn exists(&self, channel_id: &str) -> Result<bool, Error> {
         let result = self
             .collection
             .count_documents(doc! { "_id": channel_id }, None)
@@ -27,7 +32,7 @@ impl NonGuitarChannelRepository {
         Ok(result > 0)
     }
 
-    pub async fn upsert(&self, channel_id: &str) {
+    async fn upsert(&self, channel_id: &str) {
         let update_options = mongodb::options::UpdateOptions::builder()
             .upsert(true)
             .build();
@@ -41,4 +46,12 @@ impl NonGuitarChannelRepository {
             .await
             .unwrap();
     }
+
+    async fn delete(&self, channel_id: &str) -> Result<(), Error> {
+        self.collection
+            .delete_one(doc! {"_id": channel_id}, None)
+            .await?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,13 @@
+use anyhow::Error;
+use async_trait::async_trait;
+
+/// Channels that were scraped and found not to be guitar channels. Implemented for MongoDB by
+/// `NonGuitarChannelRepository` and for PostgreSQL by `PostgresNonGuitarChannelStore`.
+#[async_trait]
+pub trait NonGuitarChannelStore: Send + Sync {
+    async fn exists(&self, channel_id: &str) -> Result<bool, Error>;
+
+    async fn upsert(&self, channel_id: &str);
+
+    async fn delete(&self, channel_id: &str) -> Result<(), Error>;
+}
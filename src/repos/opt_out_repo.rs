@@ -0,0 +1,117 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use futures::stream::TryStreamExt;
+use mongodb::bson::{doc, Bson, DateTime, Document};
+use mongodb::options::{FindOptions, UpdateOptions};
+use mongodb::{Client, Collection};
+
+use crate::repos::opt_out_store::{get_opt_out_key, OptOutStore};
+use crate::utils::db::get_db_name;
+
+pub struct OptOutRepository {
+    collection: Collection<Document>,
+}
+
+impl OptOutRepository {
+    pub fn new(client: &Client, environment: &str) -> OptOutRepository {
+        let db = client.database(&get_db_name(environment));
+        let opt_outs = db.collection::<Document>("optouts");
+
+        OptOutRepository {
+            collection: opt_outs,
+        }
+    }
+}
+
+#[async_trait]
+impl OptOutStore for OptOutRepository {
+    async fn is_opted_out(&self, channel: &str) -> Result<bool, Error> {
+        let key = get_opt_out_key(channel);
+        let result = self
+            .collection
+            .count_documents(
+                doc! {
+                    "$or": [{"_id": &key}, {"handle": &key}],
+                    "confirmedAt": {"$exists": true},
+                },
+                None,
+            )
+            .await?;
+
+        Ok(result > 0)
+    }
+
+    async fn get_all(&self) -> Result<Vec<Document>, Error> {
+        let find_options = FindOptions::builder()
+            .sort(doc! { "requestedAt": -1 })
+            .projection(doc! { "confirmationCode": 0 })
+            .build();
+
+        let cursor = self.collection.find(None, find_options).await?;
+        let opt_outs: Vec<Document> = cursor.try_collect().await?;
+
+        Ok(opt_outs)
+    }
+
+    async fn insert(
+        &self,
+        channel: &str,
+        handle: Option<&str>,
+        email: &str,
+        reason: &str,
+        confirmation_code: &str,
+    ) -> Result<(), Error> {
+        let update_options = UpdateOptions::builder().upsert(true).build();
+
+        self.collection
+            .update_one(
+                doc! {"_id": get_opt_out_key(channel)},
+                doc! {
+                    "$set": {
+                        "handle": handle.map(get_opt_out_key).map(Bson::String).unwrap_or(Bson::Null),
+                        "email": email,
+                        "reason": reason,
+                        "confirmationCode": confirmation_code,
+                    },
+                    "$setOnInsert": {"requestedAt": DateTime::now()},
+                },
+                update_options,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn confirm(&self, channel: &str, confirmation_code: &str) -> Result<bool, Error> {
+        let key = get_opt_out_key(channel);
+        let result = self
+            .collection
+            .update_one(
+                doc! {
+                    "$or": [{"_id": &key}, {"handle": &key}],
+                    "confirmationCode": confirmation_code,
+                },
+                doc! {
+                    "$set": {"confirmedAt": DateTime::now()},
+                    "$unset": {"confirmationCode": ""},
+                },
+                None,
+            )
+            .await?;
+
+        Ok(result.matched_count > 0)
+    }
+
+    async fn set_purged(&self, channel: &str) -> Result<(), Error> {
+        let key = get_opt_out_key(channel);
+        self.collection
+            .update_one(
+                doc! {"$or": [{"_id": &key}, {"handle": &key}]},
+                doc! {"$set": {"purgedAt": DateTime::now()}},
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
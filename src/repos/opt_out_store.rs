@@ -0,0 +1,41 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::Document;
+
+/// Channels whose owners asked to be removed, with their contact email. Requests take effect once
+/// confirmed with their confirmation code, then the channels are never crawled or discovered again.
+/// Entries are keyed by channel id, or by the lowercase handle while the channel is unknown.
+/// Implemented for MongoDB by `OptOutRepository` and for PostgreSQL by `PostgresOptOutStore`.
+#[async_trait]
+pub trait OptOutStore: Send + Sync {
+    /// Takes a channel id or `@handle`.
+    async fn is_opted_out(&self, channel: &str) -> Result<bool, Error>;
+
+    async fn get_all(&self) -> Result<Vec<Document>, Error>;
+
+    /// Registers a request waiting for confirmation. Repeated requests replace the confirmation
+    /// code, a confirmed opt-out stays confirmed.
+    async fn insert(
+        &self,
+        channel: &str,
+        handle: Option<&str>,
+        email: &str,
+        reason: &str,
+        confirmation_code: &str,
+    ) -> Result<(), Error>;
+
+    /// Takes a channel id or `@handle`. Returns false when no request carries the code, each code
+    /// confirms once.
+    async fn confirm(&self, channel: &str, confirmation_code: &str) -> Result<bool, Error>;
+
+    /// Takes a channel id or `@handle`.
+    async fn set_purged(&self, channel: &str) -> Result<(), Error>;
+}
+
+pub fn get_opt_out_key(channel: &str) -> String {
+    if channel.starts_with('@') {
+        channel.to_lowercase()
+    } else {
+        channel.to_string()
+    }
+}
@@ -0,0 +1,73 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, DateTime, Document};
+use mongodb::options::{IndexOptions, UpdateOptions};
+use mongodb::{Client, Collection, IndexModel};
+
+use crate::repos::policy_rejection_store::{PolicyRejectionStore, POLICY_REJECTION_RETENTION};
+use crate::utils::db::get_db_name;
+
+pub struct PolicyRejectionRepository {
+    collection: Collection<Document>,
+    has_ttl_index: AtomicBool,
+}
+
+impl PolicyRejectionRepository {
+    pub fn new(client: &Client, environment: &str) -> PolicyRejectionRepository {
+        let db = client.database(&get_db_name(environment));
+        let policy_rejections = db.collection::<Document>("policyrejections");
+
+        PolicyRejectionRepository {
+            collection: policy_rejections,
+            has_ttl_index: AtomicBool::new(false),
+        }
+    }
+
+    async fn ensure_ttl_index(&self) -> Result<(), Error> {
+        let index = IndexModel::builder()
+            .keys(doc! { "rejectedAt": 1 })
+            .options(
+                IndexOptions::builder()
+                    .expire_after(POLICY_REJECTION_RETENTION)
+                    .build(),
+            )
+            .build();
+
+        self.collection.create_index(index, None).await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PolicyRejectionStore for PolicyRejectionRepository {
+    async fn is_rejected(&self, channel: &str) -> Result<bool, Error> {
+        let result = self
+            .collection
+            .count_documents(doc! { "_id": channel }, None)
+            .await?;
+
+        Ok(result > 0)
+    }
+
+    async fn reject(&self, channel: &str, reason: &str) -> Result<(), Error> {
+        if !self.has_ttl_index.load(Ordering::Relaxed) {
+            self.ensure_ttl_index().await?;
+            self.has_ttl_index.store(true, Ordering::Relaxed);
+        }
+
+        let update_options = UpdateOptions::builder().upsert(true).build();
+
+        self.collection
+            .update_one(
+                doc! {"_id": channel},
+                doc! {"$set": {"reason": reason, "rejectedAt": DateTime::now()}},
+                update_options,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
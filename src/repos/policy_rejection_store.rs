@@ -0,0 +1,16 @@
+use std::time::Duration;
+
+use anyhow::Error;
+use async_trait::async_trait;
+
+pub const POLICY_REJECTION_RETENTION: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Channels that failed the discovery policy, so discovery does not spend quota on them again.
+/// Rejections expire after 30 days, when the channel may have grown into the policy. Implemented
+/// for MongoDB by `PolicyRejectionRepository` and for PostgreSQL by `PostgresPolicyRejectionStore`.
+#[async_trait]
+pub trait PolicyRejectionStore: Send + Sync {
+    async fn is_rejected(&self, channel: &str) -> Result<bool, Error>;
+
+    async fn reject(&self, channel: &str, reason: &str) -> Result<(), Error>;
+}
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, Document};
+use tokio_postgres::Client;
+
+use crate::repos::additional_channel_store::AdditionalChannelStore;
+use crate::repos::postgres_documents::{
+    create_document_table, delete, exists, find_by_id, set_fields, to_document,
+};
+
+const TABLE: &str = "additional";
+
+pub struct PostgresAdditionalChannelStore {
+    client: Arc<Client>,
+}
+
+impl PostgresAdditionalChannelStore {
+    pub fn new(client: Arc<Client>) -> PostgresAdditionalChannelStore {
+        PostgresAdditionalChannelStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_document_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl AdditionalChannelStore for PostgresAdditionalChannelStore {
+    async fn exists(&self, channel_id: &str) -> Result<bool, Error> {
+        exists(&self.client, TABLE, channel_id).await
+    }
+
+    async fn is_ignoring_guitar_terms(&self, channel_id: &str) -> Result<bool, Error> {
+        let additional_channel = find_by_id(&self.client, TABLE, channel_id).await?;
+
+        Ok(additional_channel
+            .and_then(|channel| channel.get_bool("ignoreGuitarTerm").ok())
+            .unwrap_or(false))
+    }
+
+    async fn get_all(&self) -> Result<Vec<Document>, Error> {
+        let rows = self
+            .client
+            .query(&format!("SELECT id, doc FROM {}", TABLE), &[])
+            .await?;
+
+        Ok(rows.iter().map(to_document).collect())
+    }
+
+    async fn insert(&self, channel_id: &str, ignore_guitar_terms: bool) -> Result<(), Error> {
+        set_fields(
+            &self.client,
+            TABLE,
+            channel_id,
+            &doc! {"ignoreGuitarTerm": ignore_guitar_terms},
+        )
+        .await
+    }
+
+    async fn delete_one(&self, id: &str) -> Result<(), Error> {
+        delete(&self.client, TABLE, id).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,215 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use chrono_tz::{Tz, US::Pacific};
+use mongodb::bson::{doc, from_document};
+use tokio_postgres::{Client, Row};
+
+use crate::errors::crawler_error::ApiErrorReason;
+use crate::models::apikey::ApiKey;
+use crate::repos::apikey_store::ApiKeyStore;
+use crate::repos::postgres_documents::{
+    create_document_table, insert_missing, set_fields, to_document, update_fields,
+};
+use crate::utils::api_key_health_utils::{
+    get_health_after_error, API_KEY_DEGRADED, API_KEY_DISABLED, API_KEY_HEALTHY,
+};
+use crate::utils::document_utils::to_json;
+
+const TABLE: &str = "apikeys";
+
+const WITH_QUOTA: &str = "AND (NOT doc ? 'quota_exceeded_until'
+    OR jsonb_typeof(doc->'quota_exceeded_until') = 'null'
+    OR (doc->>'quota_exceeded_until')::bigint <= $1)";
+
+pub struct PostgresApiKeyStore {
+    client: Arc<Client>,
+}
+
+impl PostgresApiKeyStore {
+    pub fn new(client: Arc<Client>) -> PostgresApiKeyStore {
+        PostgresApiKeyStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_document_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl ApiKeyStore for PostgresApiKeyStore {
+    async fn get_all(&self) -> Result<Vec<ApiKey>, Error> {
+        let rows = self
+            .client
+            .query("SELECT id, doc FROM apikeys ORDER BY id", &[])
+            .await?;
+
+        rows.iter().map(to_api_key).collect()
+    }
+
+    async fn upsert(&self, key: &str, daily_quota: i32) -> Result<(), Error> {
+        insert_missing(
+            &self.client,
+            TABLE,
+            key,
+            &doc! {
+                "used_quota": 0,
+                "pdt_day": 0,
+                "health": API_KEY_HEALTHY,
+                "consecutive_errors": 0,
+            },
+        )
+        .await?;
+        set_fields(&self.client, TABLE, key, &doc! {"daily_quota": daily_quota}).await
+    }
+
+    async fn get_least_used_api_key(&self) -> Result<ApiKey, Error> {
+        let now = Utc::now().timestamp();
+
+        for (health_filter, with_quota) in [
+            (
+                format!(
+                    "doc->>'health' NOT IN ('{}', '{}')",
+                    API_KEY_DEGRADED, API_KEY_DISABLED
+                ),
+                true,
+            ),
+            (format!("doc->>'health' = '{}'", API_KEY_DEGRADED), true),
+            (
+                format!("doc->>'health' IS DISTINCT FROM '{}'", API_KEY_DISABLED),
+                false,
+            ),
+        ] {
+            let query = format!(
+                "SELECT id, doc FROM apikeys WHERE {} {}
+                ORDER BY (doc->>'pdt_day')::bigint, (doc->>'used_quota')::bigint
+                LIMIT 1",
+                health_filter,
+                if with_quota { WITH_QUOTA } else { "" }
+            );
+            let row = match with_quota {
+                true => self.client.query_opt(&query, &[&now]).await?,
+                false => self.client.query_opt(&query, &[]).await?,
+            };
+
+            if let Some(row) = row {
+                return to_api_key(&row);
+            }
+        }
+
+        Err(anyhow!("No enabled api key left"))
+    }
+
+    async fn count_with_quota(&self, now: i64) -> Result<u64, Error> {
+        let row = self
+            .client
+            .query_one(
+                &format!(
+                    "SELECT COUNT(*) FROM apikeys
+                    WHERE doc->>'health' IS DISTINCT FROM '{}' {}",
+                    API_KEY_DISABLED, WITH_QUOTA
+                ),
+                &[&now],
+            )
+            .await?;
+
+        Ok(row.get::<_, i64>(0) as u64)
+    }
+
+    async fn set_quota_exceeded(&self, api_key: &ApiKey, until: i64) -> Result<(), Error> {
+        update_fields(
+            &self.client,
+            TABLE,
+            &api_key.key,
+            &doc! {"quota_exceeded_until": until},
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn record_success(&self, api_key: &ApiKey) -> Result<(), Error> {
+        if api_key.consecutive_errors == 0 && api_key.health == API_KEY_HEALTHY {
+            return Ok(());
+        }
+
+        self.client
+            .execute(
+                "UPDATE apikeys SET doc = doc || $2::jsonb
+                WHERE id = $1 AND doc->>'health' IS DISTINCT FROM $3",
+                &[
+                    &api_key.key,
+                    &to_json(&doc! {"health": API_KEY_HEALTHY, "consecutive_errors": 0}),
+                    &API_KEY_DISABLED,
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn record_error(
+        &self,
+        api_key: &ApiKey,
+        reason: ApiErrorReason,
+        now: i64,
+    ) -> Result<&'static str, Error> {
+        let health = get_health_after_error(reason, api_key.consecutive_errors + 1);
+
+        let mut set = doc! {
+            "health": health,
+            "last_error": reason.name(),
+            "last_error_at": now,
+        };
+        if health == API_KEY_DISABLED {
+            set.insert("disabled_at", now);
+        }
+
+        self.client
+            .execute(
+                "UPDATE apikeys SET doc = doc || $2::jsonb || jsonb_build_object(
+                    'consecutive_errors', COALESCE((doc->>'consecutive_errors')::bigint, 0) + 1)
+                WHERE id = $1 AND doc->>'health' IS DISTINCT FROM $3",
+                &[&api_key.key, &to_json(&set), &API_KEY_DISABLED],
+            )
+            .await?;
+
+        Ok(health)
+    }
+
+    async fn update_usage(&self, api_key: &ApiKey) -> Result<(), Error> {
+        let pacific_now: DateTime<Tz> = Utc::now().with_timezone(&Pacific);
+        let pacific_date = pacific_now
+            .format("%Y%m%d")
+            .to_string()
+            .parse::<i32>()
+            .unwrap();
+
+        if pacific_date > api_key.pdt_day {
+            update_fields(
+                &self.client,
+                TABLE,
+                &api_key.key,
+                &doc! {"used_quota": 1, "pdt_day": pacific_date},
+            )
+            .await?;
+        } else {
+            self.client
+                .execute(
+                    "UPDATE apikeys SET doc = jsonb_set(doc, '{used_quota}',
+                        to_jsonb(COALESCE((doc->>'used_quota')::bigint, 0) + 1))
+                    WHERE id = $1",
+                    &[&api_key.key],
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+fn to_api_key(row: &Row) -> Result<ApiKey, Error> {
+    Ok(from_document(to_document(row))?)
+}
@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, DateTime};
+use tokio_postgres::Client;
+
+use crate::repos::backfill_store::BackfillStore;
+use crate::repos::postgres_documents::{
+    create_document_table, find_by_id, set_and_increment, to_ids, unset_fields,
+};
+
+const TABLE: &str = "backfills";
+
+pub struct PostgresBackfillStore {
+    client: Arc<Client>,
+}
+
+impl PostgresBackfillStore {
+    pub fn new(client: Arc<Client>) -> PostgresBackfillStore {
+        PostgresBackfillStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_document_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl BackfillStore for PostgresBackfillStore {
+    async fn get_completed_ids(&self) -> Result<Vec<String>, Error> {
+        let rows = self
+            .client
+            .query("SELECT id FROM backfills WHERE doc ? 'completedAt'", &[])
+            .await?;
+
+        Ok(to_ids(&rows))
+    }
+
+    async fn get_page_token(&self, channel_id: &str) -> Result<Option<String>, Error> {
+        let backfill = find_by_id(&self.client, TABLE, channel_id).await?;
+
+        let page_token = backfill.and_then(|b| b.get_str("pageToken").ok().map(|t| t.to_string()));
+
+        Ok(page_token)
+    }
+
+    async fn set_page_token(
+        &self,
+        channel_id: &str,
+        page_token: &str,
+        videos_ingested: i64,
+    ) -> Result<(), Error> {
+        set_and_increment(
+            &self.client,
+            TABLE,
+            channel_id,
+            &doc! { "pageToken": page_token, "updatedAt": DateTime::now() },
+            "videosIngested",
+            videos_ingested,
+        )
+        .await
+    }
+
+    async fn set_error(&self, channel_id: &str, error: &str) -> Result<(), Error> {
+        set_and_increment(
+            &self.client,
+            TABLE,
+            channel_id,
+            &doc! { "error": error, "updatedAt": DateTime::now() },
+            "errorCount",
+            1,
+        )
+        .await
+    }
+
+    async fn set_completed(
+        &self,
+        channel_id: &str,
+        videos_ingested: i64,
+        error: Option<String>,
+    ) -> Result<(), Error> {
+        let mut set = doc! { "completedAt": DateTime::now(), "updatedAt": DateTime::now() };
+        if let Some(error) = error {
+            set.insert("error", error);
+        }
+
+        set_and_increment(
+            &self.client,
+            TABLE,
+            channel_id,
+            &set,
+            "videosIngested",
+            videos_ingested,
+        )
+        .await?;
+        unset_fields(&self.client, TABLE, channel_id, &["pageToken"]).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use tokio_postgres::Client;
+
+use crate::repos::blacklist_store::BlacklistStore;
+use crate::repos::postgres_documents::{create_document_table, to_ids};
+
+const TABLE: &str = "blacklist";
+
+pub struct PostgresBlacklistStore {
+    client: Arc<Client>,
+}
+
+impl PostgresBlacklistStore {
+    pub fn new(client: Arc<Client>) -> PostgresBlacklistStore {
+        PostgresBlacklistStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_document_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl BlacklistStore for PostgresBlacklistStore {
+    async fn get_all(&self) -> Result<Vec<String>, Error> {
+        let rows = self.client.query("SELECT id FROM blacklist", &[]).await?;
+
+        Ok(to_ids(&rows))
+    }
+}
@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, DateTime, Document};
+use tokio_postgres::Client;
+
+use crate::repos::blocklist_store::{get_blocklist_key, BlocklistStore};
+use crate::repos::postgres_documents::{
+    create_document_table, delete, exists, insert_missing, set_fields, to_document, update_fields,
+};
+use crate::utils::ban_evasion_utils::{parse_fingerprint, ChannelFingerprint};
+
+const TABLE: &str = "blocklist";
+
+pub struct PostgresBlocklistStore {
+    client: Arc<Client>,
+}
+
+impl PostgresBlocklistStore {
+    pub fn new(client: Arc<Client>) -> PostgresBlocklistStore {
+        PostgresBlocklistStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_document_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl BlocklistStore for PostgresBlocklistStore {
+    async fn is_blocked(&self, channel: &str) -> Result<bool, Error> {
+        exists(&self.client, TABLE, &get_blocklist_key(channel)).await
+    }
+
+    async fn get_all(&self) -> Result<Vec<Document>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id, doc FROM blocklist ORDER BY (doc->'blockedAt'->>'$date')::bigint DESC",
+                &[],
+            )
+            .await?;
+
+        Ok(rows.iter().map(to_document).collect())
+    }
+
+    async fn block(&self, channel: &str, reason: &str) -> Result<(), Error> {
+        let key = get_blocklist_key(channel);
+
+        insert_missing(
+            &self.client,
+            TABLE,
+            &key,
+            &doc! {"blockedAt": DateTime::now()},
+        )
+        .await?;
+        set_fields(&self.client, TABLE, &key, &doc! {"reason": reason}).await
+    }
+
+    async fn set_fingerprint(&self, channel: &str, fingerprint: Document) -> Result<(), Error> {
+        update_fields(
+            &self.client,
+            TABLE,
+            &get_blocklist_key(channel),
+            &doc! {"fingerprint": fingerprint},
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_fingerprints(&self) -> Result<Vec<(String, ChannelFingerprint)>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id, doc FROM blocklist WHERE doc ? 'fingerprint'",
+                &[],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(to_document)
+            .filter_map(|entry| {
+                let fingerprint = parse_fingerprint(entry.get_document("fingerprint").ok()?)?;
+
+                Some((entry.get_str("_id").ok()?.to_string(), fingerprint))
+            })
+            .collect())
+    }
+
+    async fn unblock(&self, channel: &str) -> Result<bool, Error> {
+        let deleted = delete(&self.client, TABLE, &get_blocklist_key(channel)).await?;
+
+        Ok(deleted > 0)
+    }
+}
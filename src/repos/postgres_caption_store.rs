@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::Document;
+use tokio_postgres::Client;
+
+use crate::repos::caption_store::CaptionStore;
+use crate::repos::postgres_documents::{create_document_table, set_fields};
+
+const TABLE: &str = "captions";
+
+pub struct PostgresCaptionStore {
+    client: Arc<Client>,
+}
+
+impl PostgresCaptionStore {
+    pub fn new(client: Arc<Client>) -> PostgresCaptionStore {
+        PostgresCaptionStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_document_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl CaptionStore for PostgresCaptionStore {
+    async fn upsert(&self, video_id: &str, caption: Document) -> Result<(), Error> {
+        set_fields(&self.client, TABLE, video_id, &caption).await
+    }
+}
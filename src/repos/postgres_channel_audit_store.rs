@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, DateTime, Document};
+use tokio_postgres::Client;
+
+use crate::repos::channel_audit_store::ChannelAuditStore;
+use crate::repos::postgres_documents::{create_entry_table, insert_entry};
+
+const TABLE: &str = "channelaudit";
+
+pub struct PostgresChannelAuditStore {
+    client: Arc<Client>,
+}
+
+impl PostgresChannelAuditStore {
+    pub fn new(client: Arc<Client>) -> PostgresChannelAuditStore {
+        PostgresChannelAuditStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_entry_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl ChannelAuditStore for PostgresChannelAuditStore {
+    async fn insert(
+        &self,
+        channel_id: &str,
+        action: &str,
+        evidence: Document,
+    ) -> Result<(), Error> {
+        let entry = doc! {
+            "channel": channel_id,
+            "action": action,
+            "evidence": evidence,
+            "createdAt": DateTime::now(),
+        };
+
+        insert_entry(&self.client, TABLE, &entry).await
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use tokio_postgres::Client;
+
+use crate::repos::channel_popularity_store::ChannelPopularityStore;
+use crate::repos::postgres_documents::create_document_table;
+use crate::utils::popularity_utils::{decay_popularity, POPULARITY_HALF_LIFE_SECONDS};
+
+const TABLE: &str = "channelpopularity";
+
+pub struct PostgresChannelPopularityStore {
+    client: Arc<Client>,
+}
+
+impl PostgresChannelPopularityStore {
+    pub fn new(client: Arc<Client>) -> PostgresChannelPopularityStore {
+        PostgresChannelPopularityStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_document_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl ChannelPopularityStore for PostgresChannelPopularityStore {
+    async fn record_views(&self, views: &HashMap<String, i64>) -> Result<(), Error> {
+        let now = Utc::now().timestamp_millis();
+
+        for (channel_id, count) in views {
+            self.client
+                .execute(
+                    "INSERT INTO channelpopularity (id, doc) VALUES ($1, jsonb_build_object(
+                        'score', $2::bigint::float8,
+                        'views', $2::bigint,
+                        'updatedAt', jsonb_build_object('$date', $3::bigint)))
+                    ON CONFLICT (id) DO UPDATE SET doc = channelpopularity.doc || jsonb_build_object(
+                        'score', COALESCE((channelpopularity.doc->>'score')::float8, 0)
+                            * power(0.5, ($3 - COALESCE(
+                                (channelpopularity.doc->'updatedAt'->>'$date')::bigint, $3
+                            ))::float8 / ($4::bigint * 1000))
+                            + $2::bigint,
+                        'views', COALESCE((channelpopularity.doc->>'views')::bigint, 0) + $2,
+                        'updatedAt', jsonb_build_object('$date', $3::bigint))",
+                    &[channel_id, count, &now, &POPULARITY_HALF_LIFE_SECONDS],
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn get_scores(&self, channel_ids: &[String]) -> Result<HashMap<String, f64>, Error> {
+        let now = Utc::now().timestamp();
+        // Whole scores read back from JSON as integers, so they are read as numbers here
+        let rows = self
+            .client
+            .query(
+                "SELECT id, (doc->>'score')::float8, (doc->'updatedAt'->>'$date')::bigint
+                FROM channelpopularity
+                WHERE id = ANY($1) AND doc ? 'score' AND doc ? 'updatedAt'",
+                &[&channel_ids],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let updated_at = row.get::<_, i64>(2) / 1000;
+
+                (row.get(0), decay_popularity(row.get(1), updated_at, now))
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use mongodb::bson::{doc, Bson, DateTime};
+use tokio_postgres::Client;
+
+use crate::repos::channel_probation_store::ChannelProbationStore;
+use crate::repos::postgres_documents::{
+    create_document_table, insert_missing, to_ids, update_fields,
+};
+
+const TABLE: &str = "channelprobations";
+
+pub struct PostgresChannelProbationStore {
+    client: Arc<Client>,
+}
+
+impl PostgresChannelProbationStore {
+    pub fn new(client: Arc<Client>) -> PostgresChannelProbationStore {
+        PostgresChannelProbationStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_document_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl ChannelProbationStore for PostgresChannelProbationStore {
+    async fn start(&self, channel_id: &str) -> Result<(), Error> {
+        insert_missing(
+            &self.client,
+            TABLE,
+            channel_id,
+            &doc! {"startedAt": DateTime::now()},
+        )
+        .await
+    }
+
+    async fn get_undecided_ids(&self) -> Result<Vec<String>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id FROM channelprobations WHERE NOT doc ? 'outcome'
+                ORDER BY (doc->'startedAt'->>'$date')::bigint",
+                &[],
+            )
+            .await?;
+
+        Ok(to_ids(&rows))
+    }
+
+    async fn get_ids_due(
+        &self,
+        started_before: chrono::DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<String>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id FROM channelprobations
+                WHERE NOT doc ? 'outcome' AND (doc->'startedAt'->>'$date')::bigint < $1
+                ORDER BY (doc->'startedAt'->>'$date')::bigint
+                LIMIT $2",
+                &[&started_before.timestamp_millis(), &limit],
+            )
+            .await?;
+
+        Ok(to_ids(&rows))
+    }
+
+    async fn set_outcome(
+        &self,
+        channel_id: &str,
+        outcome: &str,
+        reason: Option<&str>,
+    ) -> Result<(), Error> {
+        update_fields(
+            &self.client,
+            TABLE,
+            channel_id,
+            &doc! {
+                "outcome": outcome,
+                "reason": reason.map_or(Bson::Null, Bson::from),
+                "decidedAt": DateTime::now(),
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+}
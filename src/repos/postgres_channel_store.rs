@@ -0,0 +1,845 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use mongodb::bson::{doc, Document};
+use serde_json::Value;
+use tokio_postgres::{Client, Row};
+
+use crate::classifiers::genre_classifier::{get_genre_tags_document, GenreScore};
+use crate::models::feed_state::FeedState;
+use crate::models::upload_pattern::UploadPattern;
+use crate::repos::channel_store::ChannelStore;
+use crate::utils::ban_evasion_utils::{get_ban_evasion_document, BanEvasionMatch};
+use crate::utils::channel_page_utils::ChannelPageHints;
+use crate::utils::consts::{
+    CHANNEL_STATUS_ACTIVE, CHANNEL_STATUS_DEACTIVATED, CHANNEL_STATUS_PROBATION,
+    DEACTIVATION_REASON_MERGED, DEACTIVATION_REASON_REDIRECTED,
+};
+use crate::utils::document_utils::{from_json, to_json};
+use crate::utils::gear_utils::{get_gear_documents, GearCount};
+use crate::utils::image_change_utils::format_image_hash;
+use crate::utils::link_utils::{get_link_check_document, LinkCheck};
+use crate::utils::topic_drift_utils::{get_topic_drift_document, TopicDrift};
+
+/// Channels are kept as one JSONB document per row, so the same documents the scrapers build
+/// for Mongo can be stored without a column per field.
+pub struct PostgresChannelStore {
+    client: Arc<Client>,
+}
+
+impl PostgresChannelStore {
+    pub fn new(client: Arc<Client>) -> PostgresChannelStore {
+        PostgresChannelStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        client
+            .batch_execute(
+                "CREATE TABLE IF NOT EXISTS channels (
+                    id TEXT PRIMARY KEY,
+                    doc JSONB NOT NULL DEFAULT '{}'
+                );
+                CREATE INDEX IF NOT EXISTS channels_handle_idx ON channels ((doc->>'handle'));",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_fields(&self, id: &str, fields: Document) -> Result<u64, Error> {
+        let updated = self
+            .client
+            .execute(
+                "UPDATE channels SET doc = doc || $2::jsonb WHERE id = $1",
+                &[&id, &to_json(&fields)],
+            )
+            .await?;
+
+        Ok(updated)
+    }
+}
+
+#[async_trait]
+impl ChannelStore for PostgresChannelStore {
+    async fn exists(&self, channel_id: &str) -> Result<bool, Error> {
+        let row = self
+            .client
+            .query_one(
+                "SELECT EXISTS(SELECT 1 FROM channels WHERE id = $1)",
+                &[&channel_id],
+            )
+            .await?;
+
+        Ok(row.get(0))
+    }
+
+    async fn get_id_by_handle(&self, handle: &str) -> Result<Option<String>, Error> {
+        let row = self
+            .client
+            .query_opt(
+                "SELECT id FROM channels WHERE doc->>'handle' = $1 LIMIT 1",
+                &[&handle.to_lowercase()],
+            )
+            .await?;
+
+        Ok(row.map(|row| row.get(0)))
+    }
+
+    async fn find_by_id(&self, id: &str) -> Result<Option<Document>, Error> {
+        let row = self
+            .client
+            .query_opt("SELECT id, doc FROM channels WHERE id = $1", &[&id])
+            .await?;
+
+        Ok(row.as_ref().map(to_document))
+    }
+
+    async fn get_all_ids(&self) -> Result<Vec<String>, Error> {
+        let rows = self.client.query("SELECT id FROM channels", &[]).await?;
+
+        Ok(to_ids(rows))
+    }
+
+    async fn get_page(
+        &self,
+        after_id: Option<&str>,
+        min_subscribers: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<Document>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id, doc FROM channels
+                WHERE ($1::text IS NULL OR id > $1)
+                    AND ($2::bigint IS NULL OR (doc->>'subscribers')::bigint >= $2)
+                ORDER BY id
+                LIMIT $3",
+                &[&after_id, &min_subscribers, &limit],
+            )
+            .await?;
+
+        Ok(rows.iter().map(to_document).collect())
+    }
+
+    async fn get_ids_upload_last_month(
+        &self,
+        min_subscribers_count: i64,
+    ) -> Result<Vec<String>, Error> {
+        let one_month_ago = Utc::now() - chrono::Duration::weeks(4);
+
+        let rows = self
+            .client
+            .query(
+                "SELECT id FROM channels
+                WHERE (doc->>'lastUploadAt')::bigint >= $1
+                AND (doc->>'subscribers')::bigint >= $2
+                AND doc->>'status' IS DISTINCT FROM $3
+                AND doc->>'status' IS DISTINCT FROM $4",
+                &[
+                    &one_month_ago.timestamp(),
+                    &min_subscribers_count,
+                    &CHANNEL_STATUS_DEACTIVATED,
+                    &CHANNEL_STATUS_PROBATION,
+                ],
+            )
+            .await?;
+
+        Ok(to_ids(rows))
+    }
+
+    async fn get_ids_by_country_and_topic(
+        &self,
+        country: Option<&str>,
+        topic: Option<&str>,
+    ) -> Result<Vec<String>, Error> {
+        let country = country.map(|country| country.to_lowercase());
+        let topic = topic.map(|topic| topic.to_lowercase());
+
+        let rows = self
+            .client
+            .query(
+                "SELECT id FROM channels
+                WHERE ($1::text IS NULL OR doc->>'country' = $1)
+                AND ($2::text IS NULL OR doc->'topics' ? $2)",
+                &[&country, &topic],
+            )
+            .await?;
+
+        Ok(to_ids(rows))
+    }
+
+    async fn get_ids_last_upload_before(
+        &self,
+        last_upload_before: chrono::DateTime<Utc>,
+    ) -> Result<Vec<String>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id FROM channels WHERE (doc->>'lastUploadAt')::bigint < $1",
+                &[&last_upload_before.timestamp()],
+            )
+            .await?;
+
+        Ok(to_ids(rows))
+    }
+
+    async fn get_ids_due_for_scrape(
+        &self,
+        now: chrono::DateTime<Utc>,
+    ) -> Result<Vec<String>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id FROM channels
+                WHERE (NOT doc ? 'nextScrapeAt' OR (doc->>'nextScrapeAt')::bigint <= $1)
+                AND doc->>'status' IS DISTINCT FROM $2",
+                &[&now.timestamp(), &CHANNEL_STATUS_DEACTIVATED],
+            )
+            .await?;
+
+        Ok(to_ids(rows))
+    }
+
+    async fn get_ids_about_crawled_before(
+        &self,
+        crawled_before: chrono::DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<String>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id FROM channels
+                WHERE NOT doc ? 'aboutCrawledAt'
+                OR (doc->'aboutCrawledAt'->>'$date')::bigint < $1
+                LIMIT $2",
+                &[&crawled_before.timestamp_millis(), &limit],
+            )
+            .await?;
+
+        Ok(to_ids(rows))
+    }
+
+    async fn get_ids_reclassified_before(
+        &self,
+        reclassified_before: chrono::DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<String>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id FROM channels
+                WHERE (NOT doc ? 'reclassifiedAt' OR (doc->'reclassifiedAt'->>'$date')::bigint < $1)
+                AND doc->>'status' IS DISTINCT FROM $2
+                LIMIT $3",
+                &[
+                    &reclassified_before.timestamp_millis(),
+                    &CHANNEL_STATUS_DEACTIVATED,
+                    &limit,
+                ],
+            )
+            .await?;
+
+        Ok(to_ids(rows))
+    }
+
+    async fn get_ids_rebranded_since(
+        &self,
+        since: chrono::DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<String>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id FROM channels
+                WHERE (doc->'rebrandedAt'->>'$date')::bigint >= $1
+                ORDER BY (doc->'rebrandedAt'->>'$date')::bigint DESC
+                LIMIT $2",
+                &[&since.timestamp_millis(), &limit],
+            )
+            .await?;
+
+        Ok(to_ids(rows))
+    }
+
+    async fn get_ids_last_crawled_before(
+        &self,
+        last_crawl_before: chrono::DateTime<Utc>,
+        last_upload_after: chrono::DateTime<Utc>,
+    ) -> Result<Vec<String>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id FROM channels
+                WHERE (doc->'lastCrawl'->>'$date')::bigint < $1
+                AND (doc->>'lastUploadAt')::bigint >= $2
+                ORDER BY (doc->'lastCrawl'->>'$date')::bigint
+                LIMIT 100",
+                &[
+                    &last_crawl_before.timestamp_millis(),
+                    &last_upload_after.timestamp(),
+                ],
+            )
+            .await?;
+
+        Ok(to_ids(rows))
+    }
+
+    async fn count_grouped_by(
+        &self,
+        field: &str,
+        default_key: &str,
+    ) -> Result<Vec<(String, i64)>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT doc->>$1 AS key, COUNT(*) AS count FROM channels
+                GROUP BY key
+                ORDER BY count DESC",
+                &[&field],
+            )
+            .await?;
+
+        let counts = rows
+            .iter()
+            .map(|row| {
+                let key: Option<String> = row.get(0);
+
+                (key.unwrap_or_else(|| default_key.to_string()), row.get(1))
+            })
+            .collect();
+
+        Ok(counts)
+    }
+
+    async fn count_created_since(&self, since: chrono::DateTime<Utc>) -> Result<i64, Error> {
+        let row = self
+            .client
+            .query_one(
+                "SELECT COUNT(*) FROM channels WHERE (doc->'createdAt'->>'$date')::bigint >= $1",
+                &[&since.timestamp_millis()],
+            )
+            .await?;
+
+        Ok(row.get(0))
+    }
+
+    async fn count_uploading_by_country(
+        &self,
+        uploaded_since: i64,
+        limit: i64,
+    ) -> Result<Vec<(String, i64)>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT doc->>'country' AS country, COUNT(*) AS count FROM channels
+                WHERE doc->>'status' = $1
+                    AND (doc->>'lastUploadAt')::bigint >= $2
+                    AND doc->>'country' IS NOT NULL
+                GROUP BY country
+                ORDER BY count DESC, country
+                LIMIT $3",
+                &[&CHANNEL_STATUS_ACTIVE, &uploaded_since, &limit],
+            )
+            .await?;
+
+        Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
+    }
+
+    async fn get_ids_with_due_refresh_override(
+        &self,
+        now: chrono::DateTime<Utc>,
+    ) -> Result<Vec<String>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id FROM channels
+                WHERE (doc->'refreshOverride'->>'until')::bigint > $1
+                AND (doc->'lastCrawl'->>'$date')::bigint
+                    < $2::bigint - (doc->'refreshOverride'->>'intervalSeconds')::bigint * 1000
+                ORDER BY (doc->'lastCrawl'->>'$date')::bigint",
+                &[&now.timestamp(), &now.timestamp_millis()],
+            )
+            .await?;
+
+        Ok(to_ids(rows))
+    }
+
+    async fn set_refresh_override(
+        &self,
+        id: &str,
+        interval_seconds: i64,
+        until_timestamp: i64,
+    ) -> Result<bool, Error> {
+        let updated = self
+            .set_fields(
+                id,
+                doc! {
+                    "refreshOverride": {
+                        "intervalSeconds": interval_seconds,
+                        "until": until_timestamp,
+                    }
+                },
+            )
+            .await?;
+
+        Ok(updated > 0)
+    }
+
+    async fn clear_refresh_override(&self, id: &str) -> Result<bool, Error> {
+        let updated = self
+            .client
+            .execute(
+                "UPDATE channels SET doc = doc - 'refreshOverride' WHERE id = $1",
+                &[&id],
+            )
+            .await?;
+
+        Ok(updated > 0)
+    }
+
+    async fn get_classification_override(&self, id: &str) -> Result<Option<String>, Error> {
+        let row = self
+            .client
+            .query_opt(
+                "SELECT doc->>'classificationOverride' FROM channels WHERE id = $1",
+                &[&id],
+            )
+            .await?;
+
+        Ok(row.and_then(|row| row.get(0)))
+    }
+
+    async fn set_classification_override(
+        &self,
+        id: &str,
+        classification_override: &str,
+    ) -> Result<bool, Error> {
+        let updated = self
+            .set_fields(id, doc! {"classificationOverride": classification_override})
+            .await?;
+
+        Ok(updated > 0)
+    }
+
+    async fn clear_classification_override(&self, id: &str) -> Result<bool, Error> {
+        let updated = self
+            .client
+            .execute(
+                "UPDATE channels SET doc = doc - 'classificationOverride' WHERE id = $1",
+                &[&id],
+            )
+            .await?;
+
+        Ok(updated > 0)
+    }
+
+    async fn get_language(&self, id: &str) -> Result<Option<String>, Error> {
+        let row = self
+            .client
+            .query_opt(
+                "SELECT doc->>'language' FROM channels WHERE id = $1",
+                &[&id],
+            )
+            .await?;
+
+        Ok(row.and_then(|row| row.get(0)))
+    }
+
+    async fn get_detected_language(&self, id: &str) -> Result<String, Error> {
+        let row = self
+            .client
+            .query_opt(
+                "SELECT doc->'detectedLanguage' FROM channels WHERE id = $1",
+                &[&id],
+            )
+            .await?;
+
+        match row.map(|row| row.get::<_, Option<Value>>(0)) {
+            Some(Some(Value::String(detected_language))) => Ok(detected_language),
+            Some(_) => Err(anyhow!("No detected language for channel {}!", id)),
+            None => Err(anyhow!("No channel found for id {}!", id)),
+        }
+    }
+
+    async fn delete(&self, id: &str) -> Result<(), anyhow::Error> {
+        self.client
+            .execute("DELETE FROM channels WHERE id = $1", &[&id])
+            .await?;
+
+        Ok(())
+    }
+
+    async fn upsert(&self, id: &str, channel: Document) -> bool {
+        // `xmax` is only zero for rows this statement inserted
+        self.client
+            .query_one(
+                "INSERT INTO channels (id, doc)
+                VALUES ($1, $2 || jsonb_build_object('createdAt', jsonb_build_object('$date', $3::bigint)))
+                ON CONFLICT (id) DO UPDATE SET doc = channels.doc || (EXCLUDED.doc - 'createdAt')
+                RETURNING xmax = 0",
+                &[&id, &to_json(&channel), &Utc::now().timestamp_millis()],
+            )
+            .await
+            .unwrap()
+            .get(0)
+    }
+
+    async fn set_video_count_last_upload(
+        &self,
+        id: &str,
+        video_count: i64,
+        last_upload_timestamp: i64,
+    ) {
+        self.set_fields(
+            id,
+            doc! {
+                "videoCount": video_count,
+                "lastUploadAt": last_upload_timestamp,
+                "lastVideoCrawl": mongodb::bson::DateTime::now()
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    async fn set_scrape_schedule(
+        &self,
+        id: &str,
+        next_scrape_at: i64,
+        upload_interval_seconds: Option<i64>,
+    ) -> Result<(), Error> {
+        self.set_fields(
+            id,
+            doc! {
+                "nextScrapeAt": next_scrape_at,
+                "uploadIntervalSeconds": upload_interval_seconds,
+                "scrapeFailures": 0_i64,
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn set_scrape_failure(
+        &self,
+        id: &str,
+        failures: i64,
+        next_scrape_at: i64,
+    ) -> Result<(), Error> {
+        self.set_fields(
+            id,
+            doc! {"nextScrapeAt": next_scrape_at, "scrapeFailures": failures},
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn set_about(
+        &self,
+        id: &str,
+        links: Vec<Document>,
+        contact_emails: Vec<String>,
+    ) -> Result<(), Error> {
+        self.set_fields(
+            id,
+            doc! {
+                "links": links,
+                "contactEmails": contact_emails,
+                "aboutCrawledAt": mongodb::bson::DateTime::now(),
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn set_page_hints(&self, id: &str, hints: &ChannelPageHints) -> Result<(), Error> {
+        self.set_fields(
+            id,
+            doc! {
+                "isVerified": hints.is_verified,
+                "hasMemberships": hints.has_memberships,
+                "isMonetized": hints.is_monetized,
+                "pageCrawledAt": mongodb::bson::DateTime::now(),
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn set_link_checks(&self, id: &str, checks: &[LinkCheck]) -> Result<(), Error> {
+        self.set_fields(
+            id,
+            doc! {
+                "linkChecks": checks.iter().map(get_link_check_document).collect::<Vec<Document>>(),
+                "linksCheckedAt": mongodb::bson::DateTime::now(),
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn set_verified_metadata(&self, id: &str, mut changes: Document) -> Result<(), Error> {
+        changes.insert("metadataVerifiedAt", mongodb::bson::DateTime::now());
+
+        self.set_fields(id, changes).await?;
+
+        Ok(())
+    }
+
+    async fn set_upload_pattern(&self, id: &str, pattern: &UploadPattern) -> Result<(), Error> {
+        self.set_fields(
+            id,
+            doc! {
+                "uploadPattern": mongodb::bson::to_document(pattern)?,
+                "uploadPatternUpdatedAt": mongodb::bson::DateTime::now(),
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_upload_pattern(&self, id: &str) -> Result<Option<UploadPattern>, Error> {
+        let row = self
+            .client
+            .query_opt(
+                "SELECT doc->'uploadPattern' FROM channels WHERE id = $1",
+                &[&id],
+            )
+            .await?;
+
+        match row.and_then(|row| row.get::<_, Option<Value>>(0)) {
+            Some(pattern) => Ok(Some(serde_json::from_value(pattern)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn set_genre_tags(&self, id: &str, genres: &[GenreScore]) -> Result<(), Error> {
+        self.set_fields(id, get_genre_tags_document(genres)).await?;
+
+        Ok(())
+    }
+
+    async fn set_gear(&self, id: &str, gear: &[GearCount]) -> Result<(), Error> {
+        self.set_fields(
+            id,
+            doc! {
+                "gear": get_gear_documents(gear),
+                "gearUpdatedAt": mongodb::bson::DateTime::now(),
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn set_lifecycle(
+        &self,
+        id: &str,
+        lifecycle: &str,
+        previous: Option<&str>,
+    ) -> Result<(), Error> {
+        // Dates are stored as `{"$date": millis}`, like `to_json` does
+        let now = mongodb::bson::DateTime::now().timestamp_millis();
+
+        self.client
+            .execute(
+                "UPDATE channels SET doc = doc || jsonb_build_object(
+                    'lifecycle', $2::text,
+                    'lifecycleChangedAt', jsonb_build_object('$date', $4::bigint),
+                    'lifecycleHistory', COALESCE(doc->'lifecycleHistory', '[]'::jsonb)
+                        || jsonb_build_array(jsonb_build_object(
+                            'from', $3::text, 'to', $2::text,
+                            'at', jsonb_build_object('$date', $4::bigint))))
+                WHERE id = $1",
+                &[&id, &lifecycle, &previous, &now],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_duplicate_of(
+        &self,
+        id: &str,
+        canonical_id: &str,
+        reason: &str,
+    ) -> Result<(), Error> {
+        self.set_fields(
+            id,
+            doc! {
+                "duplicateOf": canonical_id,
+                "duplicateReason": reason,
+                "duplicateDetectedAt": mongodb::bson::DateTime::now(),
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn set_topic_drift(&self, id: &str, drift: Option<&TopicDrift>) -> Result<(), Error> {
+        self.set_fields(
+            id,
+            doc! { "topicDrift": drift.map(get_topic_drift_document) },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn set_ban_evasion(&self, id: &str, ban_evasion: &BanEvasionMatch) -> Result<(), Error> {
+        self.set_fields(
+            id,
+            doc! { "banEvasion": get_ban_evasion_document(ban_evasion) },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn set_avatar_hash(&self, id: &str, avatar_hash: u64) -> Result<(), Error> {
+        self.set_fields(id, doc! { "avatarHash": format_image_hash(avatar_hash) })
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_merged(&self, id: &str, canonical_id: &str) -> Result<(), Error> {
+        self.set_fields(
+            id,
+            doc! {
+                "status": CHANNEL_STATUS_DEACTIVATED,
+                "deactivationReason": DEACTIVATION_REASON_MERGED,
+                "redirectsTo": canonical_id,
+                "mergedInto": canonical_id,
+                "mergedAt": mongodb::bson::DateTime::now(),
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn set_scrape_error(&self, id: &str, error: String) {
+        self.set_fields(
+            id,
+            doc! {
+                "scrapeError": {
+                    "at": mongodb::bson::DateTime::now(),
+                    "error": error
+                }
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    async fn set_reclassified(&self, id: &str) -> Result<(), Error> {
+        self.set_fields(id, doc! {"reclassifiedAt": mongodb::bson::DateTime::now()})
+            .await?;
+
+        Ok(())
+    }
+
+    async fn deactivate(&self, id: &str, reason: &str) -> Result<(), Error> {
+        let now = mongodb::bson::DateTime::now();
+
+        self.set_fields(
+            id,
+            doc! {
+                "status": CHANNEL_STATUS_DEACTIVATED,
+                "deactivationReason": reason,
+                "deactivatedAt": now,
+                "reclassifiedAt": now,
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_redirect(&self, id: &str) -> Result<Option<String>, Error> {
+        let row = self
+            .client
+            .query_opt(
+                "SELECT doc->>'redirectsTo' FROM channels WHERE id = $1",
+                &[&id],
+            )
+            .await?;
+
+        Ok(row.and_then(|row| row.get(0)))
+    }
+
+    async fn set_redirect(&self, id: &str, redirects_to: &str) -> Result<(), Error> {
+        self.set_fields(
+            id,
+            doc! {
+                "status": CHANNEL_STATUS_DEACTIVATED,
+                "deactivationReason": DEACTIVATION_REASON_REDIRECTED,
+                "deactivatedAt": mongodb::bson::DateTime::now(),
+                "redirectsTo": redirects_to,
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_feed_state(&self, id: &str) -> Result<FeedState, Error> {
+        let row = self
+            .client
+            .query_opt(
+                "SELECT doc->>'feedEtag', doc->>'feedLastModified', doc->>'feedContentHash',
+                COALESCE(doc->'feedVideoIds', '[]'::jsonb),
+                (doc->>'feedEmptyPlaylistCheckedAt')::bigint
+                FROM channels WHERE id = $1",
+                &[&id],
+            )
+            .await?;
+
+        Ok(row
+            .map(|row| FeedState {
+                etag: row.get(0),
+                last_modified: row.get(1),
+                content_hash: row.get(2),
+                video_ids: serde_json::from_value(row.get::<_, Value>(3)).unwrap_or_default(),
+                empty_playlist_checked_at: row.get(4),
+            })
+            .unwrap_or_default())
+    }
+
+    async fn set_feed_state(&self, id: &str, feed_state: &FeedState) -> Result<(), Error> {
+        self.set_fields(
+            id,
+            doc! {
+                "feedEtag": &feed_state.etag,
+                "feedLastModified": &feed_state.last_modified,
+                "feedContentHash": &feed_state.content_hash,
+                "feedVideoIds": &feed_state.video_ids,
+                "feedEmptyPlaylistCheckedAt": feed_state.empty_playlist_checked_at,
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+}
+
+fn to_ids(rows: Vec<Row>) -> Vec<String> {
+    rows.iter().map(|row| row.get(0)).collect()
+}
+
+fn to_document(row: &Row) -> Document {
+    let mut channel = from_json(row.get::<_, Value>(1));
+    channel.insert("_id", row.get::<_, String>(0));
+    channel
+}
@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, DateTime, Document};
+use tokio_postgres::Client;
+
+use crate::repos::channel_summary_store::ChannelSummaryStore;
+use crate::repos::postgres_documents::{create_document_table, set_fields};
+use crate::utils::document_utils::to_json;
+
+const TABLE: &str = "channel_summary";
+
+pub struct PostgresChannelSummaryStore {
+    client: Arc<Client>,
+}
+
+impl PostgresChannelSummaryStore {
+    pub fn new(client: Arc<Client>) -> PostgresChannelSummaryStore {
+        PostgresChannelSummaryStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_document_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl ChannelSummaryStore for PostgresChannelSummaryStore {
+    async fn update(&self, channel_id: &str, mut fields: Document) -> Result<(), Error> {
+        fields.insert("updatedAt", DateTime::now());
+
+        set_fields(&self.client, TABLE, channel_id, &fields).await
+    }
+
+    async fn update_with_views(
+        &self,
+        channel_id: &str,
+        fields: Document,
+        views_delta: i64,
+    ) -> Result<(), Error> {
+        self.update(channel_id, fields).await?;
+
+        // After the fields, so the average is taken from the new video count
+        self.client
+            .execute(
+                "UPDATE channel_summary SET doc = doc || jsonb_build_object(
+                    'totalViews', COALESCE((doc->>'totalViews')::bigint, 0) + $2,
+                    'averageViews', CASE
+                        WHEN COALESCE((doc->>'videoCount')::bigint, 0) > 0
+                        THEN (COALESCE((doc->>'totalViews')::bigint, 0) + $2)
+                            / (doc->>'videoCount')::bigint
+                        ELSE 0
+                    END)
+                WHERE id = $1",
+                &[&channel_id, &views_delta],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn record_index_latency(&self, channel_id: &str, seconds: i64) -> Result<(), Error> {
+        let updated_at = to_json(&doc! {"updatedAt": DateTime::now()});
+
+        self.client
+            .execute(
+                "INSERT INTO channel_summary (id, doc) VALUES ($1, $3::jsonb || jsonb_build_object(
+                    'indexLatency',
+                    jsonb_build_object('lastSeconds', $2::bigint, 'sumSeconds', $2::bigint, 'count', 1)))
+                ON CONFLICT (id) DO UPDATE SET doc = channel_summary.doc || $3::jsonb
+                    || jsonb_build_object('indexLatency',
+                        COALESCE(channel_summary.doc->'indexLatency', '{}') || jsonb_build_object(
+                            'lastSeconds', $2::bigint,
+                            'sumSeconds',
+                            COALESCE((channel_summary.doc->'indexLatency'->>'sumSeconds')::bigint, 0) + $2,
+                            'count',
+                            COALESCE((channel_summary.doc->'indexLatency'->>'count')::bigint, 0) + 1))",
+                &[&channel_id, &seconds, &updated_at],
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, DateTime};
+use serde_json::Value;
+use tokio_postgres::Client;
+
+use crate::repos::collab_edge_store::CollabEdgeStore;
+use crate::repos::postgres_documents::{add_to_set_sql, create_document_table, to_ids};
+use crate::utils::document_utils::to_json;
+
+const TABLE: &str = "collab_edges";
+
+pub struct PostgresCollabEdgeStore {
+    client: Arc<Client>,
+}
+
+impl PostgresCollabEdgeStore {
+    pub fn new(client: Arc<Client>) -> PostgresCollabEdgeStore {
+        PostgresCollabEdgeStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_document_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl CollabEdgeStore for PostgresCollabEdgeStore {
+    async fn add_mention(
+        &self,
+        from: &str,
+        to: &str,
+        kind: &str,
+        video_id: &str,
+    ) -> Result<(), Error> {
+        let now = DateTime::now();
+        let set = to_json(&doc! {"from": from, "to": to, "lastSeenAt": now});
+        let inserted = to_json(&doc! {
+            "firstSeenAt": now,
+            "kinds": [kind],
+            "videoIds": [video_id],
+        });
+
+        self.client
+            .execute(
+                &format!(
+                    "INSERT INTO collab_edges (id, doc) VALUES ($1, $3::jsonb || $2::jsonb)
+                    ON CONFLICT (id) DO UPDATE SET doc = collab_edges.doc || $2::jsonb
+                        || jsonb_build_object('kinds', {}, 'videoIds', {})",
+                    add_to_set_sql("collab_edges.doc", "kinds", "$4"),
+                    add_to_set_sql("collab_edges.doc", "videoIds", "$5"),
+                ),
+                &[
+                    &format!("{}:{}", from, to),
+                    &set,
+                    &inserted,
+                    &Value::from(kind),
+                    &Value::from(video_id),
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_strong_targets(
+        &self,
+        min_channels: i32,
+        min_videos: i32,
+    ) -> Result<Vec<String>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT doc->>'to' AS target FROM collab_edges
+                WHERE NOT doc ? 'queuedAt'
+                GROUP BY target
+                HAVING COUNT(*) >= $1
+                    AND SUM(jsonb_array_length(COALESCE(doc->'videoIds', '[]'))) >= $2
+                ORDER BY COUNT(*) DESC, SUM(jsonb_array_length(COALESCE(doc->'videoIds', '[]'))) DESC",
+                &[&i64::from(min_channels), &i64::from(min_videos)],
+            )
+            .await?;
+
+        Ok(to_ids(&rows))
+    }
+
+    async fn mark_queued(&self, to: &str) -> Result<(), Error> {
+        self.client
+            .execute(
+                "UPDATE collab_edges SET doc = doc || $2::jsonb WHERE doc->>'to' = $1",
+                &[&to, &to_json(&doc! {"queuedAt": DateTime::now()})],
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, Bson, Document};
+use tokio_postgres::Client;
+
+use crate::repos::comment_store::CommentStore;
+use crate::repos::postgres_documents::{create_document_table, set_fields, update_fields};
+
+const TABLE: &str = "comments";
+
+pub struct PostgresCommentStore {
+    client: Arc<Client>,
+}
+
+impl PostgresCommentStore {
+    pub fn new(client: Arc<Client>) -> PostgresCommentStore {
+        PostgresCommentStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_document_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl CommentStore for PostgresCommentStore {
+    async fn upsert(&self, id: &str, comment: Document) -> Result<(), Error> {
+        set_fields(&self.client, TABLE, id, &comment).await
+    }
+
+    async fn get_commenters_by_channel(
+        &self,
+        published_after: i64,
+    ) -> Result<HashMap<String, HashSet<String>>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT DISTINCT doc->>'channelId', doc->>'authorChannelId' FROM comments
+                WHERE (doc->>'publishedAt')::bigint >= $1
+                AND doc->>'channelId' IS NOT NULL AND doc->>'authorChannelId' IS NOT NULL",
+                &[&published_after],
+            )
+            .await?;
+
+        let mut commenters: HashMap<String, HashSet<String>> = HashMap::new();
+        for row in rows {
+            commenters.entry(row.get(0)).or_default().insert(row.get(1));
+        }
+
+        Ok(commenters)
+    }
+
+    async fn get_unscored(&self, limit: i64) -> Result<Vec<(Bson, String, String)>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id, doc->>'videoId', doc->>'text' FROM comments
+                WHERE NOT doc ? 'sentimentScore'
+                AND jsonb_typeof(doc->'videoId') = 'string'
+                AND jsonb_typeof(doc->'text') = 'string'
+                LIMIT $1",
+                &[&limit],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (Bson::String(row.get(0)), row.get(1), row.get(2)))
+            .collect())
+    }
+
+    async fn set_sentiment_score(&self, id: &Bson, score: Option<f64>) -> Result<(), Error> {
+        if let Some(id) = id.as_str() {
+            update_fields(&self.client, TABLE, id, &doc! {"sentimentScore": score}).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn get_sentiment_scores(&self, video_id: &str) -> Result<Vec<f64>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT (doc->>'sentimentScore')::float8 FROM comments
+                WHERE doc->>'videoId' = $1 AND jsonb_typeof(doc->'sentimentScore') = 'number'",
+                &[&video_id],
+            )
+            .await?;
+
+        Ok(rows.iter().map(|row| row.get(0)).collect())
+    }
+}
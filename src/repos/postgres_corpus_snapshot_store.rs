@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::Document;
+use tokio_postgres::Client;
+
+use crate::repos::corpus_snapshot_store::CorpusSnapshotStore;
+use crate::repos::postgres_documents::{create_entry_table, insert_entry};
+
+const TABLE: &str = "corpussnapshots";
+
+pub struct PostgresCorpusSnapshotStore {
+    client: Arc<Client>,
+}
+
+impl PostgresCorpusSnapshotStore {
+    pub fn new(client: Arc<Client>) -> PostgresCorpusSnapshotStore {
+        PostgresCorpusSnapshotStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_entry_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl CorpusSnapshotStore for PostgresCorpusSnapshotStore {
+    async fn insert(&self, snapshot: Document) -> Result<(), Error> {
+        insert_entry(&self.client, TABLE, &snapshot).await
+    }
+
+    /// Tables have no uncompressed size, the data size is the size of the table without its
+    /// indexes.
+    async fn get_collection_size(&self, collection_name: &str) -> Result<(i64, i64), Error> {
+        let row = self
+            .client
+            .query_one(
+                "SELECT COALESCE(pg_table_size(to_regclass($1)), 0),
+                    COALESCE(pg_total_relation_size(to_regclass($1)), 0)",
+                &[&collection_name],
+            )
+            .await?;
+
+        Ok((row.get(0), row.get(1)))
+    }
+}
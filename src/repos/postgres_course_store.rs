@@ -0,0 +1,58 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use mongodb::bson::{doc, DateTime};
+use tokio_postgres::Client;
+
+use crate::repos::course_store::{get_course_documents, CourseStore, PlaylistCourse};
+use crate::repos::postgres_documents::{create_document_table, replace};
+
+const TABLE: &str = "courses";
+
+pub struct PostgresCourseStore {
+    client: Arc<Client>,
+}
+
+impl PostgresCourseStore {
+    pub fn new(client: Arc<Client>) -> PostgresCourseStore {
+        PostgresCourseStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_document_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl CourseStore for PostgresCourseStore {
+    async fn get_ids_detected_since(
+        &self,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<HashSet<String>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id FROM courses WHERE (doc->'detectedAt'->>'$date')::bigint >= $1",
+                &[&since.timestamp_millis()],
+            )
+            .await?;
+
+        Ok(rows.iter().map(|row| row.get(0)).collect())
+    }
+
+    async fn replace(&self, channel_id: &str, courses: &[PlaylistCourse]) -> Result<(), Error> {
+        replace(
+            &self.client,
+            TABLE,
+            channel_id,
+            &doc! {
+                "courses": get_course_documents(courses),
+                "detectedAt": DateTime::now(),
+            },
+        )
+        .await
+    }
+}
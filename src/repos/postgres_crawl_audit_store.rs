@@ -0,0 +1,149 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use mongodb::bson::Document;
+use tokio_postgres::Client;
+
+use crate::errors::crawler_error::CrawlerError;
+use crate::models::{crawl_stats::CrawlStats, daily_digest::SlowCrawl};
+use crate::repos::crawl_audit_store::{
+    get_crawl_audit_entry, CrawlAuditStore, CRAWL_AUDIT_RETENTION, CRAWL_KIND_VIDEOS,
+};
+use crate::repos::postgres_documents::{create_entry_table, insert_entry, to_entry};
+
+const TABLE: &str = "crawlaudit";
+
+// Expired runs are deleted on insert, at most once per interval
+const EXPIRE_INTERVAL_SECONDS: i64 = 60 * 60;
+
+pub struct PostgresCrawlAuditStore {
+    client: Arc<Client>,
+    expired_at: AtomicI64,
+}
+
+impl PostgresCrawlAuditStore {
+    pub fn new(client: Arc<Client>) -> PostgresCrawlAuditStore {
+        PostgresCrawlAuditStore {
+            client,
+            expired_at: AtomicI64::new(0),
+        }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_entry_table(client, TABLE).await
+    }
+
+    async fn delete_expired(&self) -> Result<(), Error> {
+        let now = Utc::now();
+        if now.timestamp() - self.expired_at.load(Ordering::Relaxed) < EXPIRE_INTERVAL_SECONDS {
+            return Ok(());
+        }
+
+        let finished_before = now.timestamp_millis() - CRAWL_AUDIT_RETENTION.as_millis() as i64;
+        self.client
+            .execute(
+                "DELETE FROM crawlaudit WHERE (doc->'finishedAt'->>'$date')::bigint < $1",
+                &[&finished_before],
+            )
+            .await?;
+        self.expired_at.store(now.timestamp(), Ordering::Relaxed);
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CrawlAuditStore for PostgresCrawlAuditStore {
+    async fn insert(
+        &self,
+        channel_id: &str,
+        kind: &str,
+        started_at: chrono::DateTime<Utc>,
+        stats: &CrawlStats,
+        error: Option<&CrawlerError>,
+    ) -> Result<(), Error> {
+        self.delete_expired().await?;
+
+        let entry = get_crawl_audit_entry(channel_id, kind, started_at, stats, error);
+
+        insert_entry(&self.client, TABLE, &entry).await
+    }
+
+    async fn get_latest(&self, channel_id: &str, limit: i64) -> Result<Vec<Document>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT doc FROM crawlaudit WHERE doc->>'channel' = $1
+                ORDER BY (doc->'startedAt'->>'$date')::bigint DESC
+                LIMIT $2",
+                &[&channel_id, &limit],
+            )
+            .await?;
+
+        Ok(rows.iter().map(to_entry).collect())
+    }
+
+    async fn get_totals(&self, since: chrono::DateTime<Utc>) -> Result<(i64, i64), Error> {
+        let row = self
+            .client
+            .query_one(
+                "SELECT
+                    COALESCE(SUM((doc->>'videosUpdated')::bigint) FILTER (WHERE doc->>'kind' = $2), 0)::bigint,
+                    COALESCE(SUM((doc->>'apiUnits')::bigint), 0)::bigint
+                FROM crawlaudit WHERE (doc->'startedAt'->>'$date')::bigint >= $1",
+                &[&since.timestamp_millis(), &CRAWL_KIND_VIDEOS],
+            )
+            .await?;
+
+        Ok((row.get(0), row.get(1)))
+    }
+
+    async fn get_error_counts(
+        &self,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<BTreeMap<String, i64>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT COALESCE(doc->>'errorCategory', 'other') AS category, COUNT(*)
+                FROM crawlaudit
+                WHERE (doc->'startedAt'->>'$date')::bigint >= $1 AND doc->>'error' IS NOT NULL
+                GROUP BY category",
+                &[&since.timestamp_millis()],
+            )
+            .await?;
+
+        Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
+    }
+
+    async fn get_slowest(
+        &self,
+        since: chrono::DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<SlowCrawl>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT COALESCE(doc->>'channel', ''), COALESCE(doc->>'kind', ''),
+                    COALESCE((doc->>'durationMillis')::bigint, 0) AS duration
+                FROM crawlaudit WHERE (doc->'startedAt'->>'$date')::bigint >= $1
+                ORDER BY duration DESC
+                LIMIT $2",
+                &[&since.timestamp_millis(), &limit],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| SlowCrawl {
+                channel_id: row.get(0),
+                kind: row.get(1),
+                duration_millis: row.get(2),
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use tokio_postgres::Client;
+
+use crate::models::daily_digest::DailyDigest;
+use crate::repos::digest_report_store::DigestReportStore;
+use crate::repos::postgres_documents::{create_entry_table, insert_entry, to_entry};
+
+const TABLE: &str = "digestreports";
+
+pub struct PostgresDigestReportStore {
+    client: Arc<Client>,
+}
+
+impl PostgresDigestReportStore {
+    pub fn new(client: Arc<Client>) -> PostgresDigestReportStore {
+        PostgresDigestReportStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_entry_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl DigestReportStore for PostgresDigestReportStore {
+    async fn insert(&self, digest: &DailyDigest) -> Result<(), Error> {
+        insert_entry(&self.client, TABLE, &mongodb::bson::to_document(digest)?).await
+    }
+
+    async fn get_latest(&self, limit: i64) -> Result<Vec<DailyDigest>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT doc FROM digestreports ORDER BY (doc->>'to')::bigint DESC LIMIT $1",
+                &[&limit],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| mongodb::bson::from_document(to_entry(row)))
+            .collect::<Result<Vec<DailyDigest>, _>>()?)
+    }
+}
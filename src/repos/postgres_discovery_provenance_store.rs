@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use mongodb::bson::{doc, DateTime};
+use tokio_postgres::Client;
+
+use crate::models::discovery_source_report::DiscoverySourceReport;
+use crate::repos::discovery_provenance_store::{
+    DiscoveryProvenanceStore, DISCOVERY_OUTCOME_ACCEPTED, DISCOVERY_OUTCOME_FAILED,
+    DISCOVERY_OUTCOME_REJECTED,
+};
+use crate::repos::postgres_documents::create_document_table;
+use crate::utils::document_utils::to_json;
+
+const TABLE: &str = "discoveryprovenance";
+
+pub struct PostgresDiscoveryProvenanceStore {
+    client: Arc<Client>,
+}
+
+impl PostgresDiscoveryProvenanceStore {
+    pub fn new(client: Arc<Client>) -> PostgresDiscoveryProvenanceStore {
+        PostgresDiscoveryProvenanceStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_document_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl DiscoveryProvenanceStore for PostgresDiscoveryProvenanceStore {
+    async fn record(
+        &self,
+        channel_id: &str,
+        source: &str,
+        outcome: &str,
+        api_units: i64,
+    ) -> Result<(), Error> {
+        let now = DateTime::now();
+        let inserted = to_json(&doc! {"source": source, "discoveredAt": now});
+        let set = to_json(&doc! {"outcome": outcome, "decidedAt": now});
+
+        self.client
+            .execute(
+                "INSERT INTO discoveryprovenance (id, doc)
+                VALUES ($1, $2::jsonb || $3::jsonb
+                    || jsonb_build_object('apiUnits', $4::bigint, 'attempts', 1))
+                ON CONFLICT (id) DO UPDATE SET doc = discoveryprovenance.doc || $3::jsonb
+                    || jsonb_build_object(
+                        'apiUnits', COALESCE((discoveryprovenance.doc->>'apiUnits')::bigint, 0) + $4,
+                        'attempts', COALESCE((discoveryprovenance.doc->>'attempts')::bigint, 0) + 1)",
+                &[&channel_id, &inserted, &set, &api_units],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_report_since(
+        &self,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<Vec<DiscoverySourceReport>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT COALESCE(doc->>'source', '') AS source,
+                    COUNT(*) AS channels,
+                    COUNT(*) FILTER (WHERE doc->>'outcome' = $2),
+                    COUNT(*) FILTER (WHERE doc->>'outcome' = $3),
+                    COUNT(*) FILTER (WHERE doc->>'outcome' = $4),
+                    COALESCE(SUM((doc->>'apiUnits')::bigint), 0)::bigint
+                FROM discoveryprovenance
+                WHERE (doc->'decidedAt'->>'$date')::bigint >= $1
+                GROUP BY source
+                ORDER BY channels DESC",
+                &[
+                    &since.timestamp_millis(),
+                    &DISCOVERY_OUTCOME_ACCEPTED,
+                    &DISCOVERY_OUTCOME_REJECTED,
+                    &DISCOVERY_OUTCOME_FAILED,
+                ],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DiscoverySourceReport {
+                source: row.get(0),
+                channels: row.get(1),
+                accepted: row.get(2),
+                rejected: row.get(3),
+                failed: row.get(4),
+                api_units: row.get(5),
+            })
+            .collect())
+    }
+}
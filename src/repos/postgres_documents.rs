@@ -0,0 +1,316 @@
+//! Shared by the Postgres stores of the collections besides channels and videos. Like those,
+//! each collection is a table with one JSONB document per row, keyed by the Mongo `_id`, so the
+//! stores build the same documents for both backends. Table names are the collection names and
+//! always come from constants.
+
+use anyhow::Error;
+use mongodb::bson::Document;
+use serde_json::Value;
+use tokio_postgres::{Client, Row};
+
+use crate::utils::document_utils::{from_json, to_json};
+
+/// Creates a table of documents keyed by id.
+pub async fn create_document_table(client: &Client, table: &str) -> Result<(), Error> {
+    client
+        .batch_execute(&format!(
+            "CREATE TABLE IF NOT EXISTS {} (
+                id TEXT PRIMARY KEY,
+                doc JSONB NOT NULL DEFAULT '{{}}'
+            );",
+            table
+        ))
+        .await?;
+
+    Ok(())
+}
+
+/// Creates a table of documents without a natural key, like log entries and reports.
+pub async fn create_entry_table(client: &Client, table: &str) -> Result<(), Error> {
+    client
+        .batch_execute(&format!(
+            "CREATE TABLE IF NOT EXISTS {} (
+                id BIGSERIAL PRIMARY KEY,
+                doc JSONB NOT NULL DEFAULT '{{}}'
+            );",
+            table
+        ))
+        .await?;
+
+    Ok(())
+}
+
+pub async fn exists(client: &Client, table: &str, id: &str) -> Result<bool, Error> {
+    let row = client
+        .query_one(
+            &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = $1)", table),
+            &[&id],
+        )
+        .await?;
+
+    Ok(row.get(0))
+}
+
+/// Creates a table of daily counts keyed by `{channel, date}` documents, like the Mongo `_id` of
+/// views and subscribers.
+pub async fn create_daily_count_table(client: &Client, table: &str) -> Result<(), Error> {
+    client
+        .batch_execute(&format!(
+            "CREATE TABLE IF NOT EXISTS {0} (
+                id JSONB PRIMARY KEY,
+                doc JSONB NOT NULL DEFAULT '{{}}'
+            );
+            CREATE INDEX IF NOT EXISTS {0}_channel_idx ON {0} ((id->>'channel'));",
+            table
+        ))
+        .await?;
+
+    Ok(())
+}
+
+/// Sets the fields of the daily count, see `set_fields`.
+pub async fn set_daily_count_fields(
+    client: &Client,
+    table: &str,
+    id: &Document,
+    fields: &Document,
+) -> Result<(), Error> {
+    client
+        .execute(
+            &format!(
+                "INSERT INTO {0} (id, doc) VALUES ($1::jsonb, $2::jsonb)
+                ON CONFLICT (id) DO UPDATE SET doc = {0}.doc || EXCLUDED.doc",
+                table
+            ),
+            &[&to_json(id), &to_json(fields)],
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub async fn delete_daily_counts(
+    client: &Client,
+    table: &str,
+    channel_id: &str,
+) -> Result<(), Error> {
+    client
+        .execute(
+            &format!("DELETE FROM {} WHERE id->>'channel' = $1", table),
+            &[&channel_id],
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Moves the daily counts of a channel to another channel, days the target channel already has
+/// keep its count. Returns the number of moved days.
+pub async fn move_daily_counts(
+    client: &Client,
+    table: &str,
+    channel_id: &str,
+    target_channel_id: &str,
+) -> Result<u64, Error> {
+    client
+        .execute(
+            &format!(
+                "INSERT INTO {0} (id, doc)
+                SELECT jsonb_set(id, '{{channel}}', to_jsonb($2::text)), doc FROM {0}
+                WHERE id->>'channel' = $1
+                ON CONFLICT (id) DO NOTHING",
+                table
+            ),
+            &[&channel_id, &target_channel_id],
+        )
+        .await?;
+
+    let moved = client
+        .execute(
+            &format!("DELETE FROM {} WHERE id->>'channel' = $1", table),
+            &[&channel_id],
+        )
+        .await?;
+
+    Ok(moved)
+}
+
+/// Returns the document with its `_id`.
+pub async fn find_by_id(client: &Client, table: &str, id: &str) -> Result<Option<Document>, Error> {
+    let row = client
+        .query_opt(
+            &format!("SELECT id, doc FROM {} WHERE id = $1", table),
+            &[&id],
+        )
+        .await?;
+
+    Ok(row.as_ref().map(to_document))
+}
+
+/// Like a Mongo `$set` upsert: the fields replace the top-level fields they name, the others keep
+/// their values.
+pub async fn set_fields(
+    client: &Client,
+    table: &str,
+    id: &str,
+    fields: &Document,
+) -> Result<(), Error> {
+    client
+        .execute(
+            &format!(
+                "INSERT INTO {0} (id, doc) VALUES ($1, $2::jsonb)
+                ON CONFLICT (id) DO UPDATE SET doc = {0}.doc || EXCLUDED.doc",
+                table
+            ),
+            &[&id, &to_json(fields)],
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Sets the fields of an existing document only, returns the number of updated documents.
+pub async fn update_fields(
+    client: &Client,
+    table: &str,
+    id: &str,
+    fields: &Document,
+) -> Result<u64, Error> {
+    let updated = client
+        .execute(
+            &format!("UPDATE {} SET doc = doc || $2::jsonb WHERE id = $1", table),
+            &[&id, &to_json(fields)],
+        )
+        .await?;
+
+    Ok(updated)
+}
+
+/// Like a Mongo upsert with `$set` and `$inc`, a missing counter starts at zero.
+pub async fn set_and_increment(
+    client: &Client,
+    table: &str,
+    id: &str,
+    fields: &Document,
+    counter: &str,
+    by: i64,
+) -> Result<(), Error> {
+    client
+        .execute(
+            &format!(
+                "INSERT INTO {0} (id, doc) VALUES ($1, $2::jsonb || jsonb_build_object($3::text, $4::bigint))
+                ON CONFLICT (id) DO UPDATE SET doc = {0}.doc || EXCLUDED.doc
+                    || jsonb_build_object($3::text, COALESCE(({0}.doc->>$3)::bigint, 0) + $4)",
+                table
+            ),
+            &[&id, &to_json(fields), &counter, &by],
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Like a Mongo `$unset`, returns the number of updated documents.
+pub async fn unset_fields(
+    client: &Client,
+    table: &str,
+    id: &str,
+    fields: &[&str],
+) -> Result<u64, Error> {
+    let updated = client
+        .execute(
+            &format!("UPDATE {} SET doc = doc - $2::text[] WHERE id = $1", table),
+            &[&id, &fields],
+        )
+        .await?;
+
+    Ok(updated)
+}
+
+/// Like a Mongo `$setOnInsert` upsert: an existing document stays as it is.
+pub async fn insert_missing(
+    client: &Client,
+    table: &str,
+    id: &str,
+    document: &Document,
+) -> Result<(), Error> {
+    client
+        .execute(
+            &format!(
+                "INSERT INTO {} (id, doc) VALUES ($1, $2::jsonb) ON CONFLICT (id) DO NOTHING",
+                table
+            ),
+            &[&id, &to_json(document)],
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub async fn replace(
+    client: &Client,
+    table: &str,
+    id: &str,
+    document: &Document,
+) -> Result<(), Error> {
+    client
+        .execute(
+            &format!(
+                "INSERT INTO {} (id, doc) VALUES ($1, $2::jsonb)
+                ON CONFLICT (id) DO UPDATE SET doc = EXCLUDED.doc",
+                table
+            ),
+            &[&id, &to_json(document)],
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Returns the number of deleted documents.
+pub async fn delete(client: &Client, table: &str, id: &str) -> Result<u64, Error> {
+    let deleted = client
+        .execute(&format!("DELETE FROM {} WHERE id = $1", table), &[&id])
+        .await?;
+
+    Ok(deleted)
+}
+
+/// Appends a document to a table created with `create_entry_table`.
+pub async fn insert_entry(client: &Client, table: &str, document: &Document) -> Result<(), Error> {
+    client
+        .execute(
+            &format!("INSERT INTO {} (doc) VALUES ($1::jsonb)", table),
+            &[&to_json(document)],
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// The SQL of a Mongo `$addToSet` of one value to the array `field` of the document `doc`, the
+/// value is a JSONB parameter.
+pub fn add_to_set_sql(doc: &str, field: &str, value: &str) -> String {
+    format!(
+        "CASE WHEN COALESCE({0}->'{1}', '[]') @> jsonb_build_array({2}::jsonb)
+            THEN {0}->'{1}'
+            ELSE COALESCE({0}->'{1}', '[]') || jsonb_build_array({2}::jsonb) END",
+        doc, field, value
+    )
+}
+
+/// Reads rows of `id, doc` into documents with their `_id`.
+pub fn to_document(row: &Row) -> Document {
+    let mut document = from_json(row.get::<_, Value>(1));
+    document.insert("_id", row.get::<_, String>(0));
+    document
+}
+
+/// Reads rows of `doc` only, like documents read without their `_id`.
+pub fn to_entry(row: &Row) -> Document {
+    from_json(row.get::<_, Value>(0))
+}
+
+pub fn to_ids(rows: &[Row]) -> Vec<String> {
+    rows.iter().map(|row| row.get(0)).collect()
+}
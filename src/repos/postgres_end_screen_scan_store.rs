@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, DateTime};
+use tokio_postgres::Client;
+
+use crate::repos::end_screen_scan_store::EndScreenScanStore;
+use crate::repos::postgres_documents::{create_document_table, exists, set_fields};
+
+const TABLE: &str = "endscreenscans";
+
+pub struct PostgresEndScreenScanStore {
+    client: Arc<Client>,
+}
+
+impl PostgresEndScreenScanStore {
+    pub fn new(client: Arc<Client>) -> PostgresEndScreenScanStore {
+        PostgresEndScreenScanStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_document_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl EndScreenScanStore for PostgresEndScreenScanStore {
+    async fn is_scanned(&self, video_id: &str) -> Result<bool, Error> {
+        exists(&self.client, TABLE, video_id).await
+    }
+
+    async fn insert(
+        &self,
+        video_id: &str,
+        channel_id: &str,
+        promoted_channel_ids: &[String],
+    ) -> Result<(), Error> {
+        set_fields(
+            &self.client,
+            TABLE,
+            video_id,
+            &doc! {
+                "channel": channel_id,
+                "promotedChannels": promoted_channel_ids,
+                "scannedAt": DateTime::now(),
+            },
+        )
+        .await
+    }
+}
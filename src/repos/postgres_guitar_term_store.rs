@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::Document;
+use tokio_postgres::Client;
+
+use crate::repos::guitar_term_store::GuitarTermStore;
+use crate::repos::postgres_documents::{create_document_table, insert_missing, to_ids};
+
+const TABLE: &str = "guitarterms";
+
+pub struct PostgresGuitarTermStore {
+    client: Arc<Client>,
+}
+
+impl PostgresGuitarTermStore {
+    pub fn new(client: Arc<Client>) -> PostgresGuitarTermStore {
+        PostgresGuitarTermStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_document_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl GuitarTermStore for PostgresGuitarTermStore {
+    async fn get_all(&self) -> Result<Vec<String>, Error> {
+        let rows = self.client.query("SELECT id FROM guitarterms", &[]).await?;
+
+        Ok(to_ids(&rows))
+    }
+
+    async fn insert(&self, term: &str) -> Result<(), Error> {
+        insert_missing(&self.client, TABLE, term, &Document::new()).await
+    }
+}
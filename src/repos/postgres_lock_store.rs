@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use tokio_postgres::Client;
+
+use crate::repos::lock_store::{get_lock_owner, LockStore};
+
+/// Expired leases stay in the table until another owner takes them over.
+pub struct PostgresLockStore {
+    client: Arc<Client>,
+    owner: String,
+}
+
+impl PostgresLockStore {
+    pub fn new(client: Arc<Client>) -> PostgresLockStore {
+        PostgresLockStore {
+            client,
+            owner: get_lock_owner(),
+        }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        client
+            .batch_execute(
+                "CREATE TABLE IF NOT EXISTS locks (
+                    id TEXT PRIMARY KEY,
+                    owner TEXT NOT NULL,
+                    expires_at BIGINT NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS locks_owner_idx ON locks (owner);",
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LockStore for PostgresLockStore {
+    async fn acquire(&self, name: &str, lease_seconds: u64) -> Result<bool, Error> {
+        let now = Utc::now().timestamp_millis();
+        let expires_at = now + lease_seconds as i64 * 1000;
+
+        // No row comes back when another owner holds an unexpired lease
+        let row = self
+            .client
+            .query_opt(
+                "INSERT INTO locks (id, owner, expires_at) VALUES ($1, $2, $3)
+                ON CONFLICT (id) DO UPDATE SET owner = EXCLUDED.owner, expires_at = EXCLUDED.expires_at
+                WHERE locks.owner = EXCLUDED.owner OR locks.expires_at < $4
+                RETURNING id",
+                &[&name, &self.owner, &expires_at, &now],
+            )
+            .await?;
+
+        Ok(row.is_some())
+    }
+
+    async fn release_all(&self) -> Result<u64, Error> {
+        let deleted = self
+            .client
+            .execute("DELETE FROM locks WHERE owner = $1", &[&self.owner])
+            .await?;
+
+        Ok(deleted)
+    }
+}
@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, DateTime};
+use tokio_postgres::Client;
+
+use crate::repos::non_guitar_channel_store::NonGuitarChannelStore;
+use crate::repos::postgres_documents::{create_document_table, delete, exists, set_fields};
+
+const TABLE: &str = "nonguitarchannels";
+
+pub struct PostgresNonGuitarChannelStore {
+    client: Arc<Client>,
+}
+
+impl PostgresNonGuitarChannelStore {
+    pub fn new(client: Arc<Client>) -> PostgresNonGuitarChannelStore {
+        PostgresNonGuitarChannelStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_document_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl NonGuitarChannelStore for PostgresNonGuitarChannelStore {
+    async fn exists(&self, channel_id: &str) -> Result<bool, Error> {
+        exists(&self.client, TABLE, channel_id).await
+    }
+
+    async fn upsert(&self, channel_id: &str) {
+        set_fields(
+            &self.client,
+            TABLE,
+            channel_id,
+            &doc! {"decisionMadeAt": DateTime::now()},
+        )
+        .await
+        .unwrap();
+    }
+
+    async fn delete(&self, channel_id: &str) -> Result<(), Error> {
+        delete(&self.client, TABLE, channel_id).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, Bson, DateTime, Document};
+use tokio_postgres::Client;
+
+use crate::repos::opt_out_store::{get_opt_out_key, OptOutStore};
+use crate::repos::postgres_documents::{
+    create_document_table, insert_missing, set_fields, to_document,
+};
+use crate::utils::document_utils::to_json;
+
+const TABLE: &str = "optouts";
+
+pub struct PostgresOptOutStore {
+    client: Arc<Client>,
+}
+
+impl PostgresOptOutStore {
+    pub fn new(client: Arc<Client>) -> PostgresOptOutStore {
+        PostgresOptOutStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_document_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl OptOutStore for PostgresOptOutStore {
+    async fn is_opted_out(&self, channel: &str) -> Result<bool, Error> {
+        let row = self
+            .client
+            .query_one(
+                "SELECT EXISTS(SELECT 1 FROM optouts
+                    WHERE (id = $1 OR doc->>'handle' = $1) AND doc ? 'confirmedAt')",
+                &[&get_opt_out_key(channel)],
+            )
+            .await?;
+
+        Ok(row.get(0))
+    }
+
+    async fn get_all(&self) -> Result<Vec<Document>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id, doc - 'confirmationCode' FROM optouts
+                ORDER BY (doc->'requestedAt'->>'$date')::bigint DESC",
+                &[],
+            )
+            .await?;
+
+        Ok(rows.iter().map(to_document).collect())
+    }
+
+    async fn insert(
+        &self,
+        channel: &str,
+        handle: Option<&str>,
+        email: &str,
+        reason: &str,
+        confirmation_code: &str,
+    ) -> Result<(), Error> {
+        let key = get_opt_out_key(channel);
+
+        insert_missing(
+            &self.client,
+            TABLE,
+            &key,
+            &doc! {"requestedAt": DateTime::now()},
+        )
+        .await?;
+        set_fields(
+            &self.client,
+            TABLE,
+            &key,
+            &doc! {
+                "handle": handle.map(get_opt_out_key).map(Bson::String).unwrap_or(Bson::Null),
+                "email": email,
+                "reason": reason,
+                "confirmationCode": confirmation_code,
+            },
+        )
+        .await
+    }
+
+    async fn confirm(&self, channel: &str, confirmation_code: &str) -> Result<bool, Error> {
+        let updated = self
+            .client
+            .execute(
+                "UPDATE optouts SET doc = (doc - 'confirmationCode') || $3::jsonb
+                WHERE id = (SELECT id FROM optouts
+                    WHERE (id = $1 OR doc->>'handle' = $1) AND doc->>'confirmationCode' = $2
+                    LIMIT 1)",
+                &[
+                    &get_opt_out_key(channel),
+                    &confirmation_code,
+                    &to_json(&doc! {"confirmedAt": DateTime::now()}),
+                ],
+            )
+            .await?;
+
+        Ok(updated > 0)
+    }
+
+    async fn set_purged(&self, channel: &str) -> Result<(), Error> {
+        self.client
+            .execute(
+                "UPDATE optouts SET doc = doc || $2::jsonb
+                WHERE id = (SELECT id FROM optouts WHERE id = $1 OR doc->>'handle' = $1 LIMIT 1)",
+                &[
+                    &get_opt_out_key(channel),
+                    &to_json(&doc! {"purgedAt": DateTime::now()}),
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use mongodb::bson::{doc, DateTime};
+use tokio_postgres::Client;
+
+use crate::repos::policy_rejection_store::{PolicyRejectionStore, POLICY_REJECTION_RETENTION};
+use crate::repos::postgres_documents::{create_document_table, set_fields};
+
+const TABLE: &str = "policyrejections";
+
+pub struct PostgresPolicyRejectionStore {
+    client: Arc<Client>,
+}
+
+impl PostgresPolicyRejectionStore {
+    pub fn new(client: Arc<Client>) -> PostgresPolicyRejectionStore {
+        PostgresPolicyRejectionStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_document_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl PolicyRejectionStore for PostgresPolicyRejectionStore {
+    /// Expired rejections are kept until the channel is rejected again.
+    async fn is_rejected(&self, channel: &str) -> Result<bool, Error> {
+        let rejected_after =
+            Utc::now().timestamp_millis() - POLICY_REJECTION_RETENTION.as_millis() as i64;
+        let row = self
+            .client
+            .query_one(
+                "SELECT EXISTS(SELECT 1 FROM policyrejections
+                    WHERE id = $1 AND (doc->'rejectedAt'->>'$date')::bigint >= $2)",
+                &[&channel, &rejected_after],
+            )
+            .await?;
+
+        Ok(row.get(0))
+    }
+
+    async fn reject(&self, channel: &str, reason: &str) -> Result<(), Error> {
+        set_fields(
+            &self.client,
+            TABLE,
+            channel,
+            &doc! {"reason": reason, "rejectedAt": DateTime::now()},
+        )
+        .await
+    }
+}
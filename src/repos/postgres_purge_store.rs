@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use tokio_postgres::Client;
+
+use crate::repos::purge_store::PurgeStore;
+use crate::utils::purge_utils::get_postgres_channel_conditions;
+
+// The reports of `purge_utils::get_report_pulls`, with the condition selecting them and the
+// update pulling the channel or its videos out of their lists
+const DIGEST_CONDITION: &str =
+    "doc->'slowestCrawls' @> jsonb_build_array(jsonb_build_object('channelId', $1::text))";
+const DIGEST_PULL: &str = "UPDATE digestreports SET doc = jsonb_set(doc, '{slowestCrawls}',
+    COALESCE((SELECT jsonb_agg(crawl) FROM jsonb_array_elements(doc->'slowestCrawls') crawl
+        WHERE crawl->>'channelId' IS DISTINCT FROM $1), '[]'))";
+const RECONCILIATION_CONDITION: &str = "EXISTS(SELECT 1 FROM jsonb_array_elements(
+    CASE jsonb_typeof(doc->'changes') WHEN 'array' THEN doc->'changes' ELSE '[]' END) change
+    WHERE change->>'videoId' = ANY($1))";
+const RECONCILIATION_PULL: &str =
+    "UPDATE reconciliationreports SET doc = jsonb_set(doc, '{changes}',
+    COALESCE((SELECT jsonb_agg(change) FROM jsonb_array_elements(doc->'changes') change
+        WHERE NOT change->>'videoId' = ANY($1)), '[]'))";
+
+/// Counts and deletes what the PostgreSQL tables besides the channel and video stores hold about
+/// a channel and its videos, see `purge_utils::get_postgres_channel_conditions`.
+pub struct PostgresPurgeStore {
+    client: Arc<Client>,
+}
+
+impl PostgresPurgeStore {
+    pub fn new(client: Arc<Client>) -> PostgresPurgeStore {
+        PostgresPurgeStore { client }
+    }
+
+    async fn count(&self, table: &str, condition: &str, param: &str) -> Result<u64, Error> {
+        let row = self
+            .client
+            .query_one(
+                &format!("SELECT COUNT(*) FROM {} WHERE {}", table, condition),
+                &[&param],
+            )
+            .await?;
+
+        Ok(row.get::<_, i64>(0) as u64)
+    }
+}
+
+#[async_trait]
+impl PurgeStore for PostgresPurgeStore {
+    async fn count_by_channel(
+        &self,
+        channel_id: &str,
+        video_ids: &[String],
+    ) -> Result<Vec<(String, u64)>, Error> {
+        let mut counts = vec![];
+
+        for (table, condition) in get_postgres_channel_conditions() {
+            counts.push((
+                table.to_string(),
+                self.count(table, condition, channel_id).await?,
+            ));
+        }
+
+        counts.push((
+            "digestreports".to_string(),
+            self.count("digestreports", DIGEST_CONDITION, channel_id)
+                .await?,
+        ));
+
+        let row = self
+            .client
+            .query_one(
+                &format!(
+                    "SELECT COUNT(*) FROM reconciliationreports WHERE {}",
+                    RECONCILIATION_CONDITION
+                ),
+                &[&video_ids],
+            )
+            .await?;
+        counts.push((
+            "reconciliationreports".to_string(),
+            row.get::<_, i64>(0) as u64,
+        ));
+
+        Ok(counts)
+    }
+
+    async fn delete_by_channel(
+        &self,
+        channel_id: &str,
+        video_ids: &[String],
+    ) -> Result<Vec<(String, u64)>, Error> {
+        let mut counts = vec![];
+
+        for (table, condition) in get_postgres_channel_conditions() {
+            let deleted = self
+                .client
+                .execute(
+                    &format!("DELETE FROM {} WHERE {}", table, condition),
+                    &[&channel_id],
+                )
+                .await?;
+            counts.push((table.to_string(), deleted));
+        }
+
+        let updated = self
+            .client
+            .execute(
+                &format!("{} WHERE {}", DIGEST_PULL, DIGEST_CONDITION),
+                &[&channel_id],
+            )
+            .await?;
+        counts.push(("digestreports".to_string(), updated));
+
+        let updated = self
+            .client
+            .execute(
+                &format!("{} WHERE {}", RECONCILIATION_PULL, RECONCILIATION_CONDITION),
+                &[&video_ids],
+            )
+            .await?;
+        counts.push(("reconciliationreports".to_string(), updated));
+
+        Ok(counts)
+    }
+
+    async fn count_audit_entries(&self, channel_id: &str) -> Result<u64, Error> {
+        self.count("channelaudit", "doc->>'channel' = $1", channel_id)
+            .await
+    }
+
+    async fn anonymize_audit_entries(
+        &self,
+        channel_id: &str,
+        anonymized_id: &str,
+    ) -> Result<u64, Error> {
+        let updated = self
+            .client
+            .execute(
+                "UPDATE channelaudit
+                SET doc = (doc - 'evidence') || jsonb_build_object('channel', $2::text)
+                WHERE doc->>'channel' = $1",
+                &[&channel_id, &anonymized_id],
+            )
+            .await?;
+
+        Ok(updated)
+    }
+}
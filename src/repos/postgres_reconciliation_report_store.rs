@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::Document;
+use tokio_postgres::Client;
+
+use crate::repos::postgres_documents::{create_entry_table, insert_entry};
+use crate::repos::reconciliation_report_store::ReconciliationReportStore;
+
+const TABLE: &str = "reconciliationreports";
+
+pub struct PostgresReconciliationReportStore {
+    client: Arc<Client>,
+}
+
+impl PostgresReconciliationReportStore {
+    pub fn new(client: Arc<Client>) -> PostgresReconciliationReportStore {
+        PostgresReconciliationReportStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_entry_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl ReconciliationReportStore for PostgresReconciliationReportStore {
+    async fn insert(&self, report: Document) -> Result<(), Error> {
+        insert_entry(&self.client, TABLE, &report).await
+    }
+}
@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, DateTime};
+use tokio_postgres::Client;
+
+use crate::repos::postgres_documents::{create_document_table, replace};
+use crate::repos::related_channel_store::{get_related_documents, RelatedChannelStore};
+use crate::utils::similarity_utils::RelatedChannel;
+
+const TABLE: &str = "relatedchannels";
+
+pub struct PostgresRelatedChannelStore {
+    client: Arc<Client>,
+}
+
+impl PostgresRelatedChannelStore {
+    pub fn new(client: Arc<Client>) -> PostgresRelatedChannelStore {
+        PostgresRelatedChannelStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_document_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl RelatedChannelStore for PostgresRelatedChannelStore {
+    async fn replace(&self, channel_id: &str, related: &[RelatedChannel]) -> Result<(), Error> {
+        replace(
+            &self.client,
+            TABLE,
+            channel_id,
+            &doc! {
+                "related": get_related_documents(related),
+                "computedAt": DateTime::now(),
+            },
+        )
+        .await
+    }
+}
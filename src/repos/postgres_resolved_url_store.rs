@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, DateTime};
+use tokio_postgres::Client;
+
+use crate::repos::postgres_documents::{create_document_table, find_by_id, set_fields};
+use crate::repos::resolved_url_store::ResolvedUrlStore;
+
+const TABLE: &str = "resolvedurls";
+
+pub struct PostgresResolvedUrlStore {
+    client: Arc<Client>,
+}
+
+impl PostgresResolvedUrlStore {
+    pub fn new(client: Arc<Client>) -> PostgresResolvedUrlStore {
+        PostgresResolvedUrlStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_document_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl ResolvedUrlStore for PostgresResolvedUrlStore {
+    async fn get(&self, url: &str) -> Result<Option<String>, Error> {
+        let resolved_url = find_by_id(&self.client, TABLE, url)
+            .await?
+            .and_then(|entry| entry.get_str("resolvedUrl").ok().map(|url| url.to_string()));
+
+        Ok(resolved_url)
+    }
+
+    async fn insert(&self, url: &str, resolved_url: &str) -> Result<(), Error> {
+        set_fields(
+            &self.client,
+            TABLE,
+            url,
+            &doc! {
+                "resolvedUrl": resolved_url,
+                "resolvedAt": DateTime::now(),
+            },
+        )
+        .await
+    }
+}
@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, DateTime, Document};
+use tokio_postgres::Client;
+
+use crate::repos::postgres_documents::{
+    create_document_table, exists, find_by_id, insert_missing, to_document,
+};
+use crate::repos::review_queue_store::{ReviewQueueStore, REVIEW_STATUS_PENDING};
+use crate::utils::document_utils::to_json;
+
+const TABLE: &str = "reviewqueue";
+
+pub struct PostgresReviewQueueStore {
+    client: Arc<Client>,
+}
+
+impl PostgresReviewQueueStore {
+    pub fn new(client: Arc<Client>) -> PostgresReviewQueueStore {
+        PostgresReviewQueueStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_document_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl ReviewQueueStore for PostgresReviewQueueStore {
+    async fn exists(&self, channel_id: &str) -> Result<bool, Error> {
+        exists(&self.client, TABLE, channel_id).await
+    }
+
+    async fn get_status(&self, channel_id: &str) -> Result<Option<String>, Error> {
+        let review = find_by_id(&self.client, TABLE, channel_id).await?;
+
+        Ok(review.and_then(|review| review.get_str("status").ok().map(|s| s.to_string())))
+    }
+
+    async fn get_pending(&self) -> Result<Vec<Document>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id, doc FROM reviewqueue WHERE doc->>'status' = $1
+                ORDER BY (doc->>'score')::float8 DESC NULLS LAST",
+                &[&REVIEW_STATUS_PENDING],
+            )
+            .await?;
+
+        Ok(rows.iter().map(to_document).collect())
+    }
+
+    async fn insert_pending(&self, channel_id: &str, candidate: Document) -> Result<(), Error> {
+        let mut candidate = candidate;
+        candidate.insert("status", REVIEW_STATUS_PENDING);
+        candidate.insert("createdAt", DateTime::now());
+
+        insert_missing(&self.client, TABLE, channel_id, &candidate).await
+    }
+
+    async fn reopen(&self, channel_id: &str, candidate: Document) -> Result<(), Error> {
+        let mut candidate = candidate;
+        candidate.insert("status", REVIEW_STATUS_PENDING);
+        candidate.insert("createdAt", DateTime::now());
+
+        self.client
+            .execute(
+                "INSERT INTO reviewqueue (id, doc) VALUES ($1, $2::jsonb)
+                ON CONFLICT (id) DO UPDATE
+                SET doc = (reviewqueue.doc - 'decisionMadeAt') || EXCLUDED.doc",
+                &[&channel_id, &to_json(&candidate)],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_decision(&self, channel_id: &str, status: &str) -> Result<bool, Error> {
+        let updated = self
+            .client
+            .execute(
+                "UPDATE reviewqueue SET doc = doc || $3::jsonb
+                WHERE id = $1 AND doc->>'status' = $2",
+                &[
+                    &channel_id,
+                    &REVIEW_STATUS_PENDING,
+                    &to_json(&doc! {"status": status, "decisionMadeAt": DateTime::now()}),
+                ],
+            )
+            .await?;
+
+        Ok(updated > 0)
+    }
+}
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use tokio_postgres::Client;
+
+use crate::repos::schema_migrations_store::SchemaMigrationsStore;
+
+pub struct PostgresSchemaMigrationsStore {
+    client: Arc<Client>,
+}
+
+impl PostgresSchemaMigrationsStore {
+    pub fn new(client: Arc<Client>) -> PostgresSchemaMigrationsStore {
+        PostgresSchemaMigrationsStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        client
+            .batch_execute(
+                "CREATE TABLE IF NOT EXISTS schema_migrations (
+                    version BIGINT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    applied_at BIGINT NOT NULL
+                );",
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SchemaMigrationsStore for PostgresSchemaMigrationsStore {
+    async fn get_applied_versions(&self) -> Result<Vec<i64>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT version FROM schema_migrations ORDER BY version",
+                &[],
+            )
+            .await?;
+
+        Ok(rows.iter().map(|row| row.get(0)).collect())
+    }
+
+    async fn insert(&self, version: i64, name: &str) -> Result<(), Error> {
+        self.client
+            .execute(
+                "INSERT INTO schema_migrations (version, name, applied_at) VALUES ($1, $2, $3)",
+                &[&version, &name, &Utc::now().timestamp_millis()],
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use mongodb::bson::{doc, from_document, to_document};
+use tokio_postgres::Client;
+
+use crate::models::{discovery_cursor::DiscoveryCursor, discovery_policy::DiscoveryPolicy};
+use crate::repos::postgres_documents::{
+    create_document_table, delete, find_by_id, set_and_increment, set_fields, unset_fields,
+};
+use crate::repos::quota_breaker_store::QuotaBreakerStore;
+use crate::repos::settings_store::SettingsStore;
+use crate::utils::consts::{FEATURE_FLAGS, ONE_DAYS_IN_SECONDS};
+
+const TABLE: &str = "settings";
+
+pub struct PostgresSettingsStore {
+    client: Arc<Client>,
+}
+
+impl PostgresSettingsStore {
+    pub fn new(client: Arc<Client>) -> PostgresSettingsStore {
+        PostgresSettingsStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_document_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl SettingsStore for PostgresSettingsStore {
+    async fn get_last_discovery_crawl(&self) -> Result<i64, Error> {
+        let doc = find_by_id(&self.client, TABLE, "lastDiscoveryCrawl").await?;
+
+        let default_value = Utc::now().timestamp() - ((ONE_DAYS_IN_SECONDS + 1) as i64);
+
+        Ok(doc
+            .and_then(|d| d.get_i64("value").ok())
+            .unwrap_or(default_value))
+    }
+
+    async fn set_last_discovery_crawl(&self, last_crawl: i64) {
+        set_fields(
+            &self.client,
+            TABLE,
+            "lastDiscoveryCrawl",
+            &doc! {"value": last_crawl},
+        )
+        .await
+        .unwrap();
+    }
+
+    async fn get_discovery_policy(&self) -> Result<DiscoveryPolicy, Error> {
+        match find_by_id(&self.client, TABLE, "discoveryPolicy").await? {
+            Some(mut d) => {
+                d.remove("_id");
+                Ok(from_document::<DiscoveryPolicy>(d)?)
+            }
+            None => Ok(DiscoveryPolicy::default()),
+        }
+    }
+
+    async fn set_discovery_policy(&self, policy: &DiscoveryPolicy) -> Result<(), Error> {
+        set_fields(
+            &self.client,
+            TABLE,
+            "discoveryPolicy",
+            &to_document(policy)?,
+        )
+        .await
+    }
+
+    async fn get_discovery_cursor(&self) -> Result<Option<DiscoveryCursor>, Error> {
+        match find_by_id(&self.client, TABLE, "discoveryCursor").await? {
+            Some(mut d) => {
+                d.remove("_id");
+                Ok(Some(from_document::<DiscoveryCursor>(d)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn set_discovery_cursor(&self, cursor: &DiscoveryCursor) -> Result<(), Error> {
+        set_fields(
+            &self.client,
+            TABLE,
+            "discoveryCursor",
+            &to_document(cursor)?,
+        )
+        .await
+    }
+
+    async fn clear_discovery_cursor(&self) -> Result<(), Error> {
+        delete(&self.client, TABLE, "discoveryCursor").await?;
+
+        Ok(())
+    }
+
+    async fn get_quota_pause(&self) -> Result<Option<(i64, i64)>, Error> {
+        let doc = find_by_id(&self.client, TABLE, "quotaPausedUntil").await?;
+
+        Ok(doc.and_then(|d| Some((d.get_i64("pausedAt").ok()?, d.get_i64("value").ok()?))))
+    }
+
+    async fn get_feature_flags(&self) -> Result<Vec<(String, bool)>, Error> {
+        let doc = find_by_id(&self.client, TABLE, "featureFlags")
+            .await?
+            .unwrap_or_default();
+
+        let flags = FEATURE_FLAGS
+            .iter()
+            .map(|flag| (flag.to_string(), doc.get_bool(flag).unwrap_or(true)))
+            .collect();
+
+        Ok(flags)
+    }
+
+    async fn is_feature_enabled(&self, flag: &str) -> Result<bool, Error> {
+        let doc = find_by_id(&self.client, TABLE, "featureFlags").await?;
+
+        Ok(doc.and_then(|d| d.get_bool(flag).ok()).unwrap_or(true))
+    }
+
+    async fn set_feature_enabled(&self, flag: &str, enabled: bool) -> Result<(), Error> {
+        set_fields(&self.client, TABLE, "featureFlags", &doc! {flag: enabled}).await
+    }
+}
+
+#[async_trait]
+impl QuotaBreakerStore for PostgresSettingsStore {
+    async fn get_quota_paused_until(&self) -> Result<Option<i64>, Error> {
+        let doc = find_by_id(&self.client, TABLE, "quotaPausedUntil").await?;
+
+        Ok(doc.and_then(|d| d.get_i64("value").ok()))
+    }
+
+    async fn set_quota_paused_until(&self, paused_until: i64) -> Result<(), Error> {
+        set_and_increment(
+            &self.client,
+            TABLE,
+            "quotaPausedUntil",
+            &doc! {
+                "value": paused_until,
+                "pausedAt": Utc::now().timestamp(),
+            },
+            "pauseCount",
+            1,
+        )
+        .await
+    }
+
+    async fn clear_quota_paused_until(&self) -> Result<(), Error> {
+        unset_fields(&self.client, TABLE, "quotaPausedUntil", &["value"]).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::Document;
+use tokio_postgres::Client;
+
+use crate::repos::postgres_documents::{create_entry_table, insert_entry, to_entry};
+use crate::repos::site_stats_store::SiteStatsStore;
+
+const TABLE: &str = "site_stats";
+
+pub struct PostgresSiteStatsStore {
+    client: Arc<Client>,
+}
+
+impl PostgresSiteStatsStore {
+    pub fn new(client: Arc<Client>) -> PostgresSiteStatsStore {
+        PostgresSiteStatsStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_entry_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl SiteStatsStore for PostgresSiteStatsStore {
+    async fn get_latest(&self) -> Result<Option<Document>, Error> {
+        let row = self
+            .client
+            .query_opt(
+                "SELECT doc FROM site_stats ORDER BY (doc->>'version')::bigint DESC LIMIT 1",
+                &[],
+            )
+            .await?;
+
+        Ok(row.as_ref().map(to_entry))
+    }
+
+    async fn get_by_version(&self, version: i64) -> Result<Option<Document>, Error> {
+        let row = self
+            .client
+            .query_opt(
+                "SELECT doc FROM site_stats WHERE (doc->>'version')::bigint = $1 LIMIT 1",
+                &[&version],
+            )
+            .await?;
+
+        Ok(row.as_ref().map(to_entry))
+    }
+
+    async fn insert(&self, stats: Document) -> Result<(), Error> {
+        insert_entry(&self.client, TABLE, &stats).await
+    }
+}
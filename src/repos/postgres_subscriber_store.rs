@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::Document;
+use tokio_postgres::Client;
+
+use crate::repos::postgres_documents::{
+    create_daily_count_table, delete_daily_counts, move_daily_counts, set_daily_count_fields,
+};
+use crate::repos::subscriber_store::SubscriberStore;
+
+const TABLE: &str = "subscribers";
+
+pub struct PostgresSubscriberStore {
+    client: Arc<Client>,
+}
+
+impl PostgresSubscriberStore {
+    pub fn new(client: Arc<Client>) -> PostgresSubscriberStore {
+        PostgresSubscriberStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_daily_count_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl SubscriberStore for PostgresSubscriberStore {
+    async fn delete_by_channel(&self, channel_id: &str) -> Result<(), Error> {
+        delete_daily_counts(&self.client, TABLE, channel_id).await
+    }
+
+    async fn get_latest_estimate(&self, channel_id: &str) -> Result<Option<i64>, Error> {
+        let row = self
+            .client
+            .query_opt(
+                "SELECT COALESCE((doc->>'subscribersReconciled')::bigint, (doc->>'subscribers')::bigint)
+                FROM subscribers WHERE id->>'channel' = $1
+                ORDER BY id->'date' DESC
+                LIMIT 1",
+                &[&channel_id],
+            )
+            .await?;
+
+        Ok(row.and_then(|row| row.get(0)))
+    }
+
+    async fn upsert(&self, id: Document, view: Document) -> Result<(), Error> {
+        set_daily_count_fields(&self.client, TABLE, &id, &view).await
+    }
+
+    async fn move_to_channel(
+        &self,
+        channel_id: &str,
+        target_channel_id: &str,
+    ) -> Result<u64, Error> {
+        move_daily_counts(&self.client, TABLE, channel_id, target_channel_id).await
+    }
+}
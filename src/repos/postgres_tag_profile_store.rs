@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::Document;
+use tokio_postgres::Client;
+
+use crate::repos::postgres_documents::{create_document_table, find_by_id, set_fields};
+use crate::repos::tag_profile_store::TagProfileStore;
+
+const TABLE: &str = "tagprofiles";
+
+pub struct PostgresTagProfileStore {
+    client: Arc<Client>,
+}
+
+impl PostgresTagProfileStore {
+    pub fn new(client: Arc<Client>) -> PostgresTagProfileStore {
+        PostgresTagProfileStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_document_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl TagProfileStore for PostgresTagProfileStore {
+    async fn get(&self, channel_id: &str) -> Result<Option<Document>, Error> {
+        find_by_id(&self.client, TABLE, channel_id).await
+    }
+
+    async fn upsert(&self, channel_id: &str, profile: Document) -> Result<(), Error> {
+        set_fields(&self.client, TABLE, channel_id, &profile).await
+    }
+}
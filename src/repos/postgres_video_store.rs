@@ -0,0 +1,556 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::{TimeZone, Utc};
+use mongodb::bson::{doc, Document};
+use serde_json::Value;
+use tokio_postgres::Client;
+
+use crate::repos::video_store::VideoStore;
+use crate::utils::consts::VIDEO_AVAILABILITY_AVAILABLE;
+use crate::utils::document_utils::{from_json, to_json};
+use crate::utils::edit_history_utils::append_edit_history;
+use crate::utils::sentiment_utils::{get_comment_sentiment_document, CommentSentiment};
+use crate::utils::song_utils::{get_song_document, CoverOf};
+use crate::utils::stat_anomaly_utils::append_anomalies;
+
+/// Hot and cold videos share one table; archiving flips the `cold` flag instead of moving rows,
+/// and an upsert clears it again.
+pub struct PostgresVideoStore {
+    client: Arc<Client>,
+}
+
+impl PostgresVideoStore {
+    pub fn new(client: Arc<Client>) -> PostgresVideoStore {
+        PostgresVideoStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        client
+            .batch_execute(
+                "CREATE TABLE IF NOT EXISTS videos (
+                    id TEXT PRIMARY KEY,
+                    channel TEXT,
+                    cold BOOLEAN NOT NULL DEFAULT FALSE,
+                    doc JSONB NOT NULL DEFAULT '{}'
+                );
+                CREATE INDEX IF NOT EXISTS videos_channel_idx ON videos (channel);",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_fields(&self, id: &str, fields: Document) -> Result<(), Error> {
+        self.client
+            .execute(
+                "UPDATE videos SET doc = doc || $2::jsonb WHERE id = $1",
+                &[&id, &to_json(&fields)],
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl VideoStore for PostgresVideoStore {
+    async fn find_by_id(&self, id: &str) -> Result<Option<Document>, Error> {
+        let row = self
+            .client
+            .query_opt("SELECT doc FROM videos WHERE id = $1", &[&id])
+            .await?;
+
+        Ok(row.map(|row| from_json(row.get::<_, Value>(0))))
+    }
+
+    async fn archive_by_channel(
+        &self,
+        channel_id: &str,
+        published_before: i64,
+        keep_latest: u64,
+    ) -> Result<u64, Error> {
+        let oldest_kept = self
+            .client
+            .query_opt(
+                "SELECT (doc->>'publishedAt')::bigint AS published_at FROM videos
+                WHERE channel = $1 AND NOT cold AND doc ? 'publishedAt'
+                ORDER BY published_at DESC
+                OFFSET $2
+                LIMIT 1",
+                &[&channel_id, &(keep_latest as i64)],
+            )
+            .await?;
+
+        let max_published_at = match oldest_kept {
+            Some(row) => row.get::<_, i64>(0).min(published_before),
+            None => return Ok(0),
+        };
+
+        let archived = self
+            .client
+            .execute(
+                "UPDATE videos SET cold = TRUE
+                WHERE channel = $1 AND NOT cold AND (doc->>'publishedAt')::bigint <= $2",
+                &[&channel_id, &max_published_at],
+            )
+            .await?;
+
+        Ok(archived)
+    }
+
+    async fn get_updated_lookup_by_ids(
+        &self,
+        channel_id: &str,
+        video_ids: &[String],
+    ) -> Result<HashMap<String, chrono::DateTime<Utc>>, Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id, (doc->>'updatedAt')::bigint FROM videos
+                WHERE channel = $1 AND id = ANY($2)
+                AND jsonb_typeof(doc->'updatedAt') = 'number'",
+                &[&channel_id, &video_ids],
+            )
+            .await?;
+
+        let video_updated_lookup = rows
+            .iter()
+            .map(|row| (row.get(0), Utc.timestamp(row.get(1), 0)))
+            .collect::<HashMap<String, chrono::DateTime<Utc>>>();
+
+        Ok(video_updated_lookup)
+    }
+
+    async fn delete_all_by_channel(&self, channel_id: &str) -> Result<(), anyhow::Error> {
+        self.client
+            .execute("DELETE FROM videos WHERE channel = $1", &[&channel_id])
+            .await?;
+
+        Ok(())
+    }
+
+    async fn upsert(&self, id: &str, mut video_doc: Document) -> Result<(), anyhow::Error> {
+        if let Some(previous) = self.find_by_id(id).await? {
+            let now = Utc::now().timestamp();
+            append_edit_history(&previous, &mut video_doc, now);
+            append_anomalies(&previous, &mut video_doc, now);
+        }
+
+        let channel_id = video_doc.get_str("channel").ok();
+
+        self.client
+            .execute(
+                "INSERT INTO videos (id, channel, doc) VALUES ($1, $2, $3)
+                ON CONFLICT (id) DO UPDATE SET
+                    channel = COALESCE(EXCLUDED.channel, videos.channel),
+                    doc = videos.doc || EXCLUDED.doc,
+                    cold = FALSE",
+                &[&id, &channel_id, &to_json(&video_doc)],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn count(&self, channel_id: &str) -> Result<u64, anyhow::Error> {
+        let row = self
+            .client
+            .query_one(
+                "SELECT COUNT(*) FROM videos WHERE channel = $1",
+                &[&channel_id],
+            )
+            .await?;
+
+        Ok(row.get::<_, i64>(0) as u64)
+    }
+
+    async fn get_ids_by_channel(&self, channel_id: &str) -> Result<Vec<String>, anyhow::Error> {
+        let rows = self
+            .client
+            .query("SELECT id FROM videos WHERE channel = $1", &[&channel_id])
+            .await?;
+
+        Ok(rows.iter().map(|row| row.get::<_, String>(0)).collect())
+    }
+
+    async fn get_views_by_ids(
+        &self,
+        channel_id: &str,
+        video_ids: &[String],
+    ) -> Result<HashMap<String, i64>, anyhow::Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id, (doc->>'views')::bigint FROM videos
+                WHERE channel = $1 AND id = ANY($2)
+                AND jsonb_typeof(doc->'views') = 'number'",
+                &[&channel_id, &video_ids],
+            )
+            .await?;
+
+        Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
+    }
+
+    async fn get_ids_without_captions(
+        &self,
+        retry_before: i64,
+        excluded_channel_ids: &[String],
+        limit: i64,
+    ) -> Result<Vec<(String, String)>, anyhow::Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id, channel FROM videos
+                WHERE NOT cold AND channel IS NOT NULL AND NOT doc ? 'captionsCrawledAt'
+                    AND COALESCE((doc->>'captionsRetryAt')::bigint <= $1, TRUE)
+                    AND NOT channel = ANY($3)
+                ORDER BY (doc->>'publishedAt')::bigint DESC NULLS LAST
+                LIMIT $2",
+                &[&retry_before, &limit, &excluded_channel_ids],
+            )
+            .await?;
+
+        Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
+    }
+
+    async fn set_caption_keywords(
+        &self,
+        id: &str,
+        caption_keywords: Vec<String>,
+        caption_songs: &[CoverOf],
+    ) -> Result<(), anyhow::Error> {
+        self.set_fields(
+            id,
+            doc! {
+                "captionKeywords": caption_keywords,
+                "captionSongs": caption_songs.iter().map(get_song_document).collect::<Vec<Document>>(),
+                "captionsCrawledAt": mongodb::bson::DateTime::now(),
+            },
+        )
+        .await
+    }
+
+    async fn set_caption_retry(&self, id: &str, retry_at: i64) -> Result<(), anyhow::Error> {
+        self.set_fields(id, doc! {"captionsRetryAt": retry_at})
+            .await
+    }
+
+    async fn get_ids_comments_due(
+        &self,
+        published_after: i64,
+        crawled_before: i64,
+        limit: i64,
+    ) -> Result<Vec<(String, String)>, anyhow::Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id, channel FROM videos
+                WHERE NOT cold AND channel IS NOT NULL
+                    AND (doc->>'publishedAt')::bigint >= $1
+                    AND COALESCE((doc->>'commentsCrawledAt')::bigint < $2, TRUE)
+                ORDER BY (doc->>'publishedAt')::bigint DESC
+                LIMIT $3",
+                &[&published_after, &crawled_before, &limit],
+            )
+            .await?;
+
+        Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
+    }
+
+    async fn set_comments_crawled(&self, id: &str, crawled_at: i64) -> Result<(), anyhow::Error> {
+        self.set_fields(id, doc! {"commentsCrawledAt": crawled_at})
+            .await
+    }
+
+    async fn set_comment_sentiment(
+        &self,
+        id: &str,
+        sentiment: &CommentSentiment,
+    ) -> Result<(), anyhow::Error> {
+        self.set_fields(
+            id,
+            doc! {"commentSentiment": get_comment_sentiment_document(sentiment)},
+        )
+        .await
+    }
+
+    async fn count_all(&self) -> Result<u64, anyhow::Error> {
+        let row = self
+            .client
+            .query_one("SELECT COUNT(*) FROM videos", &[])
+            .await?;
+
+        Ok(row.get::<_, i64>(0) as u64)
+    }
+
+    async fn get_page(
+        &self,
+        after_id: Option<&str>,
+        channel_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Document>, anyhow::Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id, doc FROM videos
+                WHERE NOT cold
+                    AND ($1::text IS NULL OR id > $1)
+                    AND ($2::text IS NULL OR channel = $2)
+                ORDER BY id
+                LIMIT $3",
+                &[&after_id, &channel_id, &limit],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let mut video = from_json(row.get::<_, Value>(1));
+                video.insert("_id", row.get::<_, String>(0));
+                video
+            })
+            .collect())
+    }
+
+    async fn set_view_snapshot(
+        &self,
+        id: &str,
+        field: &str,
+        views: i64,
+    ) -> Result<(), anyhow::Error> {
+        self.client
+            .execute(
+                "UPDATE videos SET doc = doc || jsonb_build_object($2::text, $3::bigint)
+                WHERE id = $1 AND NOT cold AND NOT doc ? $2",
+                &[&id, &field, &views],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_published_timestamps(
+        &self,
+        channel_id: &str,
+        limit: i64,
+    ) -> Result<Vec<i64>, anyhow::Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT (doc->>'publishedAt')::bigint AS published_at FROM videos
+                WHERE channel = $1 AND NOT cold AND doc ? 'publishedAt'
+                ORDER BY published_at DESC
+                LIMIT $2",
+                &[&channel_id, &limit],
+            )
+            .await?;
+
+        Ok(rows.iter().map(|row| row.get(0)).collect())
+    }
+
+    async fn get_tags(
+        &self,
+        channel_id: &str,
+        limit: i64,
+    ) -> Result<Vec<(Vec<String>, i64)>, anyhow::Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT COALESCE(doc->'tags', '[]'::jsonb), (doc->>'publishedAt')::bigint AS published_at
+                FROM videos
+                WHERE channel = $1 AND NOT cold AND doc ? 'publishedAt'
+                ORDER BY published_at DESC
+                LIMIT $2",
+                &[&channel_id, &limit],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let tags = serde_json::from_value(row.get::<_, Value>(0)).unwrap_or_default();
+                (tags, row.get(1))
+            })
+            .collect())
+    }
+
+    async fn get_gear(
+        &self,
+        channel_id: &str,
+        limit: i64,
+    ) -> Result<Vec<Vec<String>>, anyhow::Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT COALESCE(doc->'gear', '[]'::jsonb) FROM videos
+                WHERE channel = $1 AND NOT cold
+                ORDER BY (doc->>'publishedAt')::bigint DESC NULLS LAST
+                LIMIT $2",
+                &[&channel_id, &limit],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| serde_json::from_value(row.get::<_, Value>(0)).unwrap_or_default())
+            .collect())
+    }
+
+    async fn get_latest_texts(
+        &self,
+        channel_id: &str,
+        limit: i64,
+    ) -> Result<Vec<Document>, anyhow::Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT jsonb_build_object(
+                    '_id', id,
+                    'title', doc->'title',
+                    'description', doc->'description',
+                    'tags', doc->'tags'
+                ) FROM videos
+                WHERE channel = $1 AND NOT cold
+                ORDER BY (doc->>'publishedAt')::bigint DESC NULLS LAST
+                LIMIT $2",
+                &[&channel_id, &limit],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| from_json(row.get::<_, Value>(0)))
+            .collect())
+    }
+
+    async fn get_ids_availability_checked_before(
+        &self,
+        checked_before: i64,
+        limit: i64,
+    ) -> Result<Vec<(String, String)>, anyhow::Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id, COALESCE(doc->>'availability', $3) FROM videos
+                WHERE NOT cold AND (NOT doc ? 'availabilityCheckedAt'
+                    OR (doc->>'availabilityCheckedAt')::bigint < $1)
+                ORDER BY (doc->>'availabilityCheckedAt')::bigint ASC NULLS FIRST
+                LIMIT $2",
+                &[&checked_before, &limit, &VIDEO_AVAILABILITY_AVAILABLE],
+            )
+            .await?;
+
+        Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
+    }
+
+    async fn set_availability(&self, id: &str, availability: &str) -> Result<(), anyhow::Error> {
+        self.set_fields(
+            id,
+            doc! {
+                "availability": availability,
+                "availabilityCheckedAt": Utc::now().timestamp(),
+            },
+        )
+        .await
+    }
+
+    async fn move_to_channel(
+        &self,
+        channel_id: &str,
+        target_channel_id: &str,
+    ) -> Result<u64, anyhow::Error> {
+        let moved = self
+            .client
+            .execute(
+                "UPDATE videos SET channel = $2,
+                    doc = jsonb_set(doc, '{channel}', to_jsonb($2::text))
+                WHERE channel = $1",
+                &[&channel_id, &target_channel_id],
+            )
+            .await?;
+
+        Ok(moved)
+    }
+
+    async fn get_restricted_by_channel(
+        &self,
+        channel_id: &str,
+        limit: i64,
+    ) -> Result<Vec<Document>, anyhow::Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id, doc FROM videos
+                WHERE channel = $1 AND NOT cold
+                    AND (doc->>'embeddable' = 'false'
+                        OR jsonb_typeof(doc->'regionRestriction') = 'object')
+                ORDER BY (doc->>'publishedAt')::bigint DESC NULLS LAST
+                LIMIT $2",
+                &[&channel_id, &limit],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let mut video = from_json(row.get::<_, Value>(1));
+                video.insert("_id", row.get::<_, String>(0));
+                video
+            })
+            .collect())
+    }
+
+    async fn get_covers(
+        &self,
+        song_key: &str,
+        artist_key: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Document>, anyhow::Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id, doc FROM videos
+                WHERE NOT cold
+                    AND doc->'coverOf'->>'songKey' = $1
+                    AND ($2::text IS NULL OR doc->'coverOf'->>'artistKey' = $2)
+                ORDER BY (doc->>'views')::bigint DESC NULLS LAST
+                LIMIT $3",
+                &[&song_key, &artist_key, &limit],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let mut video = from_json(row.get::<_, Value>(1));
+                video.insert("_id", row.get::<_, String>(0));
+                video
+            })
+            .collect())
+    }
+
+    async fn get_cover_songs(&self, limit: i64) -> Result<Vec<CoverOf>, anyhow::Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT MIN(doc->'coverOf'->>'artist'), MIN(doc->'coverOf'->>'song') FROM videos
+                WHERE NOT cold AND doc->'coverOf' ? 'songKey'
+                GROUP BY doc->'coverOf'->>'artistKey', doc->'coverOf'->>'songKey'
+                ORDER BY COUNT(*) DESC
+                LIMIT $1",
+                &[&limit],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| CoverOf {
+                artist: row.get(0),
+                song: row.get(1),
+            })
+            .collect())
+    }
+}
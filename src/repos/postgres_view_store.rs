@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::Document;
+use tokio_postgres::Client;
+
+use crate::repos::postgres_documents::{
+    create_daily_count_table, delete_daily_counts, move_daily_counts, set_daily_count_fields,
+};
+use crate::repos::view_store::ViewStore;
+
+const TABLE: &str = "views";
+
+pub struct PostgresViewStore {
+    client: Arc<Client>,
+}
+
+impl PostgresViewStore {
+    pub fn new(client: Arc<Client>) -> PostgresViewStore {
+        PostgresViewStore { client }
+    }
+
+    pub async fn create_table(client: &Client) -> Result<(), Error> {
+        create_daily_count_table(client, TABLE).await
+    }
+}
+
+#[async_trait]
+impl ViewStore for PostgresViewStore {
+    async fn delete_by_channel(&self, channel_id: &str) -> Result<(), Error> {
+        delete_daily_counts(&self.client, TABLE, channel_id).await
+    }
+
+    async fn upsert(&self, id: Document, view: Document) -> Result<(), Error> {
+        set_daily_count_fields(&self.client, TABLE, &id, &view).await
+    }
+
+    async fn move_to_channel(
+        &self,
+        channel_id: &str,
+        target_channel_id: &str,
+    ) -> Result<u64, Error> {
+        move_daily_counts(&self.client, TABLE, channel_id, target_channel_id).await
+    }
+}
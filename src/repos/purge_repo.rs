@@ -0,0 +1,115 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, Document};
+use mongodb::{Client, Database};
+
+use crate::repos::purge_store::PurgeStore;
+use crate::utils::{
+    db::get_db_name,
+    purge_utils::{get_channel_filters, get_report_pulls},
+};
+
+/// Counts and deletes what the MongoDB collections besides the channel and video stores hold
+/// about a channel and its videos, see `purge_utils::get_channel_filters` and
+/// `purge_utils::get_report_pulls`.
+pub struct PurgeRepository {
+    db: Database,
+}
+
+impl PurgeRepository {
+    pub fn new(client: &Client, environment: &str) -> PurgeRepository {
+        PurgeRepository {
+            db: client.database(&get_db_name(environment)),
+        }
+    }
+}
+
+#[async_trait]
+impl PurgeStore for PurgeRepository {
+    async fn count_by_channel(
+        &self,
+        channel_id: &str,
+        video_ids: &[String],
+    ) -> Result<Vec<(String, u64)>, Error> {
+        let mut counts = vec![];
+
+        for (name, filter) in get_channel_filters(channel_id) {
+            let count = self
+                .db
+                .collection::<Document>(name)
+                .count_documents(filter, None)
+                .await?;
+            counts.push((name.to_string(), count));
+        }
+
+        for (name, filter, _) in get_report_pulls(channel_id, video_ids) {
+            let count = self
+                .db
+                .collection::<Document>(name)
+                .count_documents(filter, None)
+                .await?;
+            counts.push((name.to_string(), count));
+        }
+
+        Ok(counts)
+    }
+
+    async fn delete_by_channel(
+        &self,
+        channel_id: &str,
+        video_ids: &[String],
+    ) -> Result<Vec<(String, u64)>, Error> {
+        let mut counts = vec![];
+
+        for (name, filter) in get_channel_filters(channel_id) {
+            let result = self
+                .db
+                .collection::<Document>(name)
+                .delete_many(filter, None)
+                .await?;
+            counts.push((name.to_string(), result.deleted_count));
+        }
+
+        for (name, filter, update) in get_report_pulls(channel_id, video_ids) {
+            let result = self
+                .db
+                .collection::<Document>(name)
+                .update_many(filter, update, None)
+                .await?;
+            counts.push((name.to_string(), result.modified_count));
+        }
+
+        Ok(counts)
+    }
+
+    async fn count_audit_entries(&self, channel_id: &str) -> Result<u64, Error> {
+        let count = self
+            .db
+            .collection::<Document>("channelaudit")
+            .count_documents(doc! {"channel": channel_id}, None)
+            .await?;
+
+        Ok(count)
+    }
+
+    async fn anonymize_audit_entries(
+        &self,
+        channel_id: &str,
+        anonymized_id: &str,
+    ) -> Result<u64, Error> {
+        let result = self
+            .db
+            .collection::<Document>("channelaudit")
+            .update_many(
+                doc! {"channel": channel_id},
+                doc! {
+                    "$set": {"channel": anonymized_id},
+                    "$unset": {"evidence": ""},
+                },
+                None,
+            )
+            .await?;
+
+        Ok(result.modified_count)
+    }
+}
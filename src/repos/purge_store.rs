@@ -0,0 +1,31 @@
+use anyhow::Error;
+use async_trait::async_trait;
+
+/// Counts and deletes what the collections besides the channel and video stores hold about a
+/// channel and its videos. Implemented for MongoDB by `PurgeRepository` and for PostgreSQL by
+/// `PostgresPurgeStore`.
+#[async_trait]
+pub trait PurgeStore: Send + Sync {
+    /// Returns the matching documents per collection.
+    async fn count_by_channel(
+        &self,
+        channel_id: &str,
+        video_ids: &[String],
+    ) -> Result<Vec<(String, u64)>, Error>;
+
+    /// Returns the deleted documents, or for reports the updated ones, per collection.
+    async fn delete_by_channel(
+        &self,
+        channel_id: &str,
+        video_ids: &[String],
+    ) -> Result<Vec<(String, u64)>, Error>;
+
+    async fn count_audit_entries(&self, channel_id: &str) -> Result<u64, Error>;
+
+    /// Keeps the audit entries of the channel under the anonymized id, without their evidence.
+    async fn anonymize_audit_entries(
+        &self,
+        channel_id: &str,
+        anonymized_id: &str,
+    ) -> Result<u64, Error>;
+}
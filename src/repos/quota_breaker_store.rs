@@ -0,0 +1,14 @@
+use anyhow::Error;
+use async_trait::async_trait;
+
+/// The quota circuit breaker shared by all instances, implemented for MongoDB by the
+/// `SettingsRepository` and for PostgreSQL by the `PostgresSettingsStore`.
+#[async_trait]
+pub trait QuotaBreakerStore: Send + Sync {
+    async fn get_quota_paused_until(&self) -> Result<Option<i64>, Error>;
+
+    /// Stores the pause window and counts pauses so quota exhaustion can be tracked over time.
+    async fn set_quota_paused_until(&self, paused_until: i64) -> Result<(), Error>;
+
+    async fn clear_quota_paused_until(&self) -> Result<(), Error>;
+}
@@ -0,0 +1,31 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::Document;
+use mongodb::{Client, Collection};
+
+use crate::repos::reconciliation_report_store::ReconciliationReportStore;
+use crate::utils::db::get_db_name;
+
+pub struct ReconciliationReportRepository {
+    collection: Collection<Document>,
+}
+
+impl ReconciliationReportRepository {
+    pub fn new(client: &Client, environment: &str) -> ReconciliationReportRepository {
+        let db = client.database(&get_db_name(environment));
+        let reports = db.collection::<Document>("reconciliationreports");
+
+        ReconciliationReportRepository {
+            collection: reports,
+        }
+    }
+}
+
+#[async_trait]
+impl ReconciliationReportStore for ReconciliationReportRepository {
+    async fn insert(&self, report: Document) -> Result<(), Error> {
+        self.collection.insert_one(report, None).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,10 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::Document;
+
+/// The reports of the reconciliation job, one per run. Implemented for MongoDB by
+/// `ReconciliationReportRepository` and for PostgreSQL by `PostgresReconciliationReportStore`.
+#[async_trait]
+pub trait ReconciliationReportStore: Send + Sync {
+    async fn insert(&self, report: Document) -> Result<(), Error>;
+}
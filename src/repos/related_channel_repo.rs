@@ -0,0 +1,46 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, Document};
+use mongodb::options::ReplaceOptions;
+use mongodb::{Client, Collection};
+
+use crate::repos::related_channel_store::{get_related_documents, RelatedChannelStore};
+use crate::utils::{db::get_db_name, similarity_utils::RelatedChannel};
+
+pub struct RelatedChannelRepository {
+    collection: Collection<Document>,
+}
+
+impl RelatedChannelRepository {
+    pub fn new(client: &Client, environment: &str) -> RelatedChannelRepository {
+        let db = client.database(&get_db_name(environment));
+        let related_channels = db.collection::<Document>("relatedchannels");
+
+        RelatedChannelRepository {
+            collection: related_channels,
+        }
+    }
+}
+
+#[async_trait]
+impl RelatedChannelStore for RelatedChannelRepository {
+    async fn replace(&self, channel_id: &str, related: &[RelatedChannel]) -> Result<(), Error> {
+        let related = get_related_documents(related);
+
+        let replace_options = ReplaceOptions::builder().upsert(true).build();
+
+        self.collection
+            .replace_one(
+                doc! {"_id": channel_id},
+                doc! {
+                    "_id": channel_id,
+                    "related": related,
+                    "computedAt": mongodb::bson::DateTime::now(),
+                },
+                replace_options,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, Document};
+
+use crate::utils::similarity_utils::RelatedChannel;
+
+/// The channels related to each channel by shared commenters. Implemented for MongoDB by
+/// `RelatedChannelRepository` and for PostgreSQL by `PostgresRelatedChannelStore`.
+#[async_trait]
+pub trait RelatedChannelStore: Send + Sync {
+    async fn replace(&self, channel_id: &str, related: &[RelatedChannel]) -> Result<(), Error>;
+}
+
+/// The stored form of the related channels of a channel, the same for every backend.
+pub fn get_related_documents(related: &[RelatedChannel]) -> Vec<Document> {
+    related
+        .iter()
+        .map(|related_channel| {
+            doc! {
+                "channel": &related_channel.channel_id,
+                "score": related_channel.score,
+                "sharedCommenters": related_channel.shared_commenters as i64,
+            }
+        })
+        .collect()
+}
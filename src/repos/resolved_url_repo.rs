@@ -0,0 +1,53 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, DateTime, Document};
+use mongodb::options::UpdateOptions;
+use mongodb::{Client, Collection};
+
+use crate::repos::resolved_url_store::ResolvedUrlStore;
+use crate::utils::db::get_db_name;
+
+pub struct ResolvedUrlRepository {
+    collection: Collection<Document>,
+}
+
+impl ResolvedUrlRepository {
+    pub fn new(client: &Client, environment: &str) -> ResolvedUrlRepository {
+        let db = client.database(&get_db_name(environment));
+        let resolved_urls = db.collection::<Document>("resolvedurls");
+
+        ResolvedUrlRepository {
+            collection: resolved_urls,
+        }
+    }
+}
+
+#[async_trait]
+impl ResolvedUrlStore for ResolvedUrlRepository {
+    async fn get(&self, url: &str) -> Result<Option<String>, Error> {
+        let resolved_url = self
+            .collection
+            .find_one(doc! {"_id": url}, None)
+            .await?
+            .and_then(|entry| entry.get_str("resolvedUrl").ok().map(|url| url.to_string()));
+
+        Ok(resolved_url)
+    }
+
+    async fn insert(&self, url: &str, resolved_url: &str) -> Result<(), Error> {
+        let update_options = UpdateOptions::builder().upsert(true).build();
+
+        self.collection
+            .update_one(
+                doc! {"_id": url},
+                doc! {"$set": {
+                    "resolvedUrl": resolved_url,
+                    "resolvedAt": DateTime::now(),
+                }},
+                update_options,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,11 @@
+use anyhow::Error;
+use async_trait::async_trait;
+
+/// Shortened links found in descriptions and the canonical URL they redirect to. Implemented for
+/// MongoDB by `ResolvedUrlRepository` and for PostgreSQL by `PostgresResolvedUrlStore`.
+#[async_trait]
+pub trait ResolvedUrlStore: Send + Sync {
+    async fn get(&self, url: &str) -> Result<Option<String>, Error>;
+
+    async fn insert(&self, url: &str, resolved_url: &str) -> Result<(), Error>;
+}
@@ -0,0 +1,106 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use futures::stream::TryStreamExt;
+use mongodb::bson::{doc, DateTime, Document};
+use mongodb::options::{FindOptions, UpdateOptions};
+use mongodb::{Client, Collection};
+
+use crate::repos::review_queue_store::{ReviewQueueStore, REVIEW_STATUS_PENDING};
+use crate::utils::db::get_db_name;
+
+pub struct ReviewQueueRepository {
+    collection: Collection<Document>,
+}
+
+impl ReviewQueueRepository {
+    pub fn new(client: &Client, environment: &str) -> ReviewQueueRepository {
+        let db = client.database(&get_db_name(environment));
+        let review_queue = db.collection::<Document>("reviewqueue");
+
+        ReviewQueueRepository {
+            collection: review_queue,
+        }
+    }
+}
+
+#[async_trait]
+impl ReviewQueueStore for ReviewQueueRepository {
+    async fn exists(&self, channel_id: &str) -> Result<bool, Error> {
+        let result = self
+            .collection
+            .count_documents(doc! { "_id": channel_id }, None)
+            .await?;
+
+        Ok(result > 0)
+    }
+
+    async fn get_status(&self, channel_id: &str) -> Result<Option<String>, Error> {
+        let review = self
+            .collection
+            .find_one(doc! { "_id": channel_id }, None)
+            .await?;
+
+        Ok(review.and_then(|review| review.get_str("status").ok().map(|s| s.to_string())))
+    }
+
+    async fn get_pending(&self) -> Result<Vec<Document>, Error> {
+        let find_options = FindOptions::builder().sort(doc! { "score": -1 }).build();
+
+        let cursor = self
+            .collection
+            .find(doc! { "status": REVIEW_STATUS_PENDING }, find_options)
+            .await?;
+        let pending: Vec<Document> = cursor.try_collect().await?;
+
+        Ok(pending)
+    }
+
+    async fn insert_pending(&self, channel_id: &str, candidate: Document) -> Result<(), Error> {
+        let update_options = UpdateOptions::builder().upsert(true).build();
+
+        let mut candidate = candidate;
+        candidate.insert("status", REVIEW_STATUS_PENDING);
+        candidate.insert("createdAt", DateTime::now());
+
+        self.collection
+            .update_one(
+                doc! {"_id": channel_id},
+                doc! {"$setOnInsert": candidate},
+                update_options,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn reopen(&self, channel_id: &str, candidate: Document) -> Result<(), Error> {
+        let update_options = UpdateOptions::builder().upsert(true).build();
+
+        let mut candidate = candidate;
+        candidate.insert("status", REVIEW_STATUS_PENDING);
+        candidate.insert("createdAt", DateTime::now());
+
+        self.collection
+            .update_one(
+                doc! {"_id": channel_id},
+                doc! {"$set": candidate, "$unset": {"decisionMadeAt": ""}},
+                update_options,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_decision(&self, channel_id: &str, status: &str) -> Result<bool, Error> {
+        let result = self
+            .collection
+            .update_one(
+                doc! {"_id": channel_id, "status": REVIEW_STATUS_PENDING},
+                doc! {"$set": {"status": status, "decisionMadeAt": DateTime::now()}},
+                None,
+            )
+            .await?;
+
+        Ok(result.matched_count > 0)
+    }
+}
@@ -0,0 +1,27 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::Document;
+
+pub const REVIEW_STATUS_PENDING: &str = "pending";
+pub const REVIEW_STATUS_APPROVED: &str = "approved";
+pub const REVIEW_STATUS_REJECTED: &str = "rejected";
+
+/// Discovered channels waiting for a manual review, with the decision once reviewed. Implemented
+/// for MongoDB by `ReviewQueueRepository` and for PostgreSQL by `PostgresReviewQueueStore`.
+#[async_trait]
+pub trait ReviewQueueStore: Send + Sync {
+    async fn exists(&self, channel_id: &str) -> Result<bool, Error>;
+
+    /// The review status of the channel, none for channels that were never queued.
+    async fn get_status(&self, channel_id: &str) -> Result<Option<String>, Error>;
+
+    async fn get_pending(&self) -> Result<Vec<Document>, Error>;
+
+    async fn insert_pending(&self, channel_id: &str, candidate: Document) -> Result<(), Error>;
+
+    /// Puts the channel back to pending review, also when it was decided before.
+    async fn reopen(&self, channel_id: &str, candidate: Document) -> Result<(), Error>;
+
+    /// Returns false if the channel was not pending review.
+    async fn set_decision(&self, channel_id: &str, status: &str) -> Result<bool, Error>;
+}
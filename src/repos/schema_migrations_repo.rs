@@ -0,0 +1,50 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use futures::stream::TryStreamExt;
+use mongodb::bson::{doc, DateTime, Document};
+use mongodb::options::FindOptions;
+use mongodb::{Client, Collection};
+
+use crate::repos::schema_migrations_store::SchemaMigrationsStore;
+use crate::utils::db::get_db_name;
+
+pub struct SchemaMigrationsRepository {
+    collection: Collection<Document>,
+}
+
+impl SchemaMigrationsRepository {
+    pub fn new(client: &Client, environment: &str) -> SchemaMigrationsRepository {
+        let db = client.database(&get_db_name(environment));
+        let schema_migrations = db.collection::<Document>("schema_migrations");
+
+        SchemaMigrationsRepository {
+            collection: schema_migrations,
+        }
+    }
+}
+
+#[async_trait]
+impl SchemaMigrationsStore for SchemaMigrationsRepository {
+    async fn get_applied_versions(&self) -> Result<Vec<i64>, Error> {
+        let find_options = FindOptions::builder().sort(doc! { "_id": 1 }).build();
+
+        let cursor = self.collection.find(None, find_options).await?;
+        let applied: Vec<Document> = cursor.try_collect().await?;
+
+        Ok(applied
+            .iter()
+            .filter_map(|migration| migration.get_i64("_id").ok())
+            .collect())
+    }
+
+    async fn insert(&self, version: i64, name: &str) -> Result<(), Error> {
+        self.collection
+            .insert_one(
+                doc! {"_id": version, "name": name, "appliedAt": DateTime::now()},
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
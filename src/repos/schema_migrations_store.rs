@@ -0,0 +1,11 @@
+use anyhow::Error;
+use async_trait::async_trait;
+
+/// The versions of the applied migrations, keyed by version. Implemented for MongoDB by
+/// `SchemaMigrationsRepository` and for PostgreSQL by `PostgresSchemaMigrationsStore`.
+#[async_trait]
+pub trait SchemaMigrationsStore: Send + Sync {
+    async fn get_applied_versions(&self) -> Result<Vec<i64>, Error>;
+
+    async fn insert(&self, version: i64, name: &str) -> Result<(), Error>;
+}
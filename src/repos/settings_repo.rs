@@ -1,12 +1,20 @@
 use anyhow::Error;
+use async_trait::async_trait;
 use chrono::Utc;
 use mongodb::{
-    bson::{doc, Document},
+    bson::{doc, from_bson, from_document, to_bson, to_document, Document},
+    change_stream::event::ResumeToken,
     options::UpdateOptions,
     Client, Collection,
 };
 
-use crate::utils::{consts::ONE_DAYS_IN_SECONDS, db::get_db_name};
+use crate::models::{discovery_cursor::DiscoveryCursor, discovery_policy::DiscoveryPolicy};
+use crate::repos::quota_breaker_store::QuotaBreakerStore;
+use crate::repos::settings_store::SettingsStore;
+use crate::utils::{
+    consts::{FEATURE_FLAGS, ONE_DAYS_IN_SECONDS},
+    db::get_db_name,
+};
 
 pub struct SettingsRepository {
     collection: Collection<Document>,
@@ -14,7 +22,7 @@ pub struct SettingsRepository {
 
 impl SettingsRepository {
     pub fn new(client: &Client, environment: &str) -> SettingsRepository {
-        let db = client.database(&get_db_name(&environment));
+        let db = client.database(&get_db_name(environment));
         let settings = db.collection::<Document>("settings");
 
         SettingsRepository {
@@ -22,7 +30,36 @@ impl SettingsRepository {
         }
     }
 
-    pub async fn get_last_discovery_crawl(&self) -> Result<i64, Error> {
+    pub async fn get_change_stream_token(&self) -> Result<Option<ResumeToken>, Error> {
+        let doc = self
+            .collection
+            .find_one(doc! {"_id": "changeStreamToken"}, None)
+            .await?;
+
+        match doc.and_then(|mut d| d.remove("token")) {
+            Some(token) => Ok(Some(from_bson::<ResumeToken>(token)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn set_change_stream_token(&self, token: &ResumeToken) -> Result<(), Error> {
+        let update = doc! {
+            "$set": {"token": to_bson(token)?},
+        };
+
+        let update_options = UpdateOptions::builder().upsert(true).build();
+
+        self.collection
+            .update_one(doc! {"_id": "changeStreamToken"}, update, update_options)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SettingsStore for SettingsRepository {
+    async fn get_last_discovery_crawl(&self) -> Result<i64, Error> {
         let doc = self
             .collection
             .find_one(doc! {"_id": "lastDiscoveryCrawl"}, None)
@@ -39,7 +76,7 @@ impl SettingsRepository {
         }
     }
 
-    pub async fn set_last_discovery_crawl(&self, last_crawl: i64) {
+    async fn set_last_discovery_crawl(&self, last_crawl: i64) {
         let update = doc! {
             "$set": {
                 "value": last_crawl,
@@ -53,4 +90,163 @@ impl SettingsRepository {
             .await
             .unwrap();
     }
+
+    async fn get_discovery_policy(&self) -> Result<DiscoveryPolicy, Error> {
+        let doc = self
+            .collection
+            .find_one(doc! {"_id": "discoveryPolicy"}, None)
+            .await?;
+
+        match doc {
+            Some(mut d) => {
+                d.remove("_id");
+                Ok(from_document::<DiscoveryPolicy>(d)?)
+            }
+            None => Ok(DiscoveryPolicy::default()),
+        }
+    }
+
+    async fn set_discovery_policy(&self, policy: &DiscoveryPolicy) -> Result<(), Error> {
+        let update = doc! {
+            "$set": to_document(policy)?,
+        };
+
+        let update_options = UpdateOptions::builder().upsert(true).build();
+
+        self.collection
+            .update_one(doc! {"_id": "discoveryPolicy"}, update, update_options)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_discovery_cursor(&self) -> Result<Option<DiscoveryCursor>, Error> {
+        let doc = self
+            .collection
+            .find_one(doc! {"_id": "discoveryCursor"}, None)
+            .await?;
+
+        match doc {
+            Some(mut d) => {
+                d.remove("_id");
+                Ok(Some(from_document::<DiscoveryCursor>(d)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn set_discovery_cursor(&self, cursor: &DiscoveryCursor) -> Result<(), Error> {
+        let update = doc! {
+            "$set": to_document(cursor)?,
+        };
+
+        let update_options = UpdateOptions::builder().upsert(true).build();
+
+        self.collection
+            .update_one(doc! {"_id": "discoveryCursor"}, update, update_options)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn clear_discovery_cursor(&self) -> Result<(), Error> {
+        self.collection
+            .delete_one(doc! {"_id": "discoveryCursor"}, None)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_quota_pause(&self) -> Result<Option<(i64, i64)>, Error> {
+        let doc = self
+            .collection
+            .find_one(doc! {"_id": "quotaPausedUntil"}, None)
+            .await?;
+
+        Ok(doc.and_then(|d| Some((d.get_i64("pausedAt").ok()?, d.get_i64("value").ok()?))))
+    }
+
+    async fn get_feature_flags(&self) -> Result<Vec<(String, bool)>, Error> {
+        let doc = self
+            .collection
+            .find_one(doc! {"_id": "featureFlags"}, None)
+            .await?
+            .unwrap_or_default();
+
+        let flags = FEATURE_FLAGS
+            .iter()
+            .map(|flag| (flag.to_string(), doc.get_bool(flag).unwrap_or(true)))
+            .collect();
+
+        Ok(flags)
+    }
+
+    async fn is_feature_enabled(&self, flag: &str) -> Result<bool, Error> {
+        let doc = self
+            .collection
+            .find_one(doc! {"_id": "featureFlags"}, None)
+            .await?;
+
+        Ok(doc.and_then(|d| d.get_bool(flag).ok()).unwrap_or(true))
+    }
+
+    async fn set_feature_enabled(&self, flag: &str, enabled: bool) -> Result<(), Error> {
+        let update = doc! {
+            "$set": {
+                flag: enabled,
+            }
+        };
+
+        let update_options = UpdateOptions::builder().upsert(true).build();
+
+        self.collection
+            .update_one(doc! {"_id": "featureFlags"}, update, update_options)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl QuotaBreakerStore for SettingsRepository {
+    async fn get_quota_paused_until(&self) -> Result<Option<i64>, Error> {
+        let doc = self
+            .collection
+            .find_one(doc! {"_id": "quotaPausedUntil"}, None)
+            .await?;
+
+        Ok(doc.and_then(|d| d.get_i64("value").ok()))
+    }
+
+    async fn set_quota_paused_until(&self, paused_until: i64) -> Result<(), Error> {
+        let update = doc! {
+            "$set": {
+                "value": paused_until,
+                "pausedAt": Utc::now().timestamp(),
+            },
+            "$inc": {
+                "pauseCount": 1,
+            }
+        };
+
+        let update_options = UpdateOptions::builder().upsert(true).build();
+
+        self.collection
+            .update_one(doc! {"_id": "quotaPausedUntil"}, update, update_options)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn clear_quota_paused_until(&self) -> Result<(), Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": "quotaPausedUntil"},
+                doc! {"$unset": {"value": ""}},
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,68 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use log::error;
+
+use crate::models::{discovery_cursor::DiscoveryCursor, discovery_policy::DiscoveryPolicy};
+use crate::utils::consts::{
+    CHANNEL_SOURCE_ADDITIONAL, CHANNEL_SOURCE_COLLABORATION, CHANNEL_SOURCE_COMMENTER,
+    CHANNEL_SOURCE_END_SCREEN, CHANNEL_SOURCE_IMPORT, CHANNEL_SOURCE_INGEST,
+    FEATURE_ADDITIONAL_SOURCE_ENABLED, FEATURE_COLLABORATION_SOURCE_ENABLED,
+    FEATURE_COMMENTER_SOURCE_ENABLED, FEATURE_END_SCREEN_SOURCE_ENABLED,
+    FEATURE_IMPORT_SOURCE_ENABLED, FEATURE_INGEST_SOURCE_ENABLED,
+};
+
+/// Settings shared by all crawler instances, like the discovery policy and the feature flags.
+/// Implemented for MongoDB by `SettingsRepository` and for PostgreSQL by `PostgresSettingsStore`.
+#[async_trait]
+pub trait SettingsStore: Send + Sync {
+    async fn get_last_discovery_crawl(&self) -> Result<i64, Error>;
+
+    async fn set_last_discovery_crawl(&self, last_crawl: i64);
+
+    /// Without a stored policy every discovered channel is queued.
+    async fn get_discovery_policy(&self) -> Result<DiscoveryPolicy, Error>;
+
+    async fn set_discovery_policy(&self, policy: &DiscoveryPolicy) -> Result<(), Error>;
+
+    async fn get_discovery_cursor(&self) -> Result<Option<DiscoveryCursor>, Error>;
+
+    async fn set_discovery_cursor(&self, cursor: &DiscoveryCursor) -> Result<(), Error>;
+
+    async fn clear_discovery_cursor(&self) -> Result<(), Error>;
+
+    /// When the ongoing quota pause started and until when it lasts.
+    async fn get_quota_pause(&self) -> Result<Option<(i64, i64)>, Error>;
+
+    /// Returns all known feature flags. Flags that were never set are enabled.
+    async fn get_feature_flags(&self) -> Result<Vec<(String, bool)>, Error>;
+
+    async fn is_feature_enabled(&self, flag: &str) -> Result<bool, Error>;
+
+    async fn set_feature_enabled(&self, flag: &str, enabled: bool) -> Result<(), Error>;
+}
+
+/// Reads a feature flag, failing open: flags that can't be read count as enabled, so a settings
+/// outage doesn't stop the crawlers.
+pub async fn is_feature_enabled(settings_repo: &dyn SettingsStore, flag: &str) -> bool {
+    match settings_repo.is_feature_enabled(flag).await {
+        Ok(enabled) => enabled,
+        Err(e) => {
+            error!("Failed to read feature flag {}: {}", flag, e);
+            true
+        }
+    }
+}
+
+/// Returns the flag that toggles scraping of channels queued by a source. Sources without a
+/// flag are always scraped.
+pub fn get_source_feature_flag(source: &str) -> Option<&'static str> {
+    match source {
+        CHANNEL_SOURCE_ADDITIONAL => Some(FEATURE_ADDITIONAL_SOURCE_ENABLED),
+        CHANNEL_SOURCE_IMPORT => Some(FEATURE_IMPORT_SOURCE_ENABLED),
+        CHANNEL_SOURCE_COLLABORATION => Some(FEATURE_COLLABORATION_SOURCE_ENABLED),
+        CHANNEL_SOURCE_COMMENTER => Some(FEATURE_COMMENTER_SOURCE_ENABLED),
+        CHANNEL_SOURCE_INGEST => Some(FEATURE_INGEST_SOURCE_ENABLED),
+        CHANNEL_SOURCE_END_SCREEN => Some(FEATURE_END_SCREEN_SOURCE_ENABLED),
+        _ => None,
+    }
+}
@@ -0,0 +1,45 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, Document};
+use mongodb::options::FindOneOptions;
+use mongodb::{Client, Collection};
+
+use crate::repos::site_stats_store::SiteStatsStore;
+use crate::utils::db::get_db_name;
+
+pub struct SiteStatsRepository {
+    collection: Collection<Document>,
+}
+
+impl SiteStatsRepository {
+    pub fn new(client: &Client, environment: &str) -> SiteStatsRepository {
+        let db = client.database(&get_db_name(environment));
+        let site_stats = db.collection::<Document>("site_stats");
+
+        SiteStatsRepository {
+            collection: site_stats,
+        }
+    }
+}
+
+#[async_trait]
+impl SiteStatsStore for SiteStatsRepository {
+    async fn get_latest(&self) -> Result<Option<Document>, Error> {
+        let find_one_options = FindOneOptions::builder().sort(doc! {"version": -1}).build();
+
+        Ok(self.collection.find_one(None, find_one_options).await?)
+    }
+
+    async fn get_by_version(&self, version: i64) -> Result<Option<Document>, Error> {
+        Ok(self
+            .collection
+            .find_one(doc! {"version": version}, None)
+            .await?)
+    }
+
+    async fn insert(&self, stats: Document) -> Result<(), Error> {
+        self.collection.insert_one(stats, None).await?;
+
+        Ok(())
+    }
+}
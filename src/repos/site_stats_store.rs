@@ -0,0 +1,14 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::Document;
+
+/// Versioned snapshots of the site statistics, one per run of the stats aggregation job.
+/// Implemented for MongoDB by `SiteStatsRepository` and for PostgreSQL by `PostgresSiteStatsStore`.
+#[async_trait]
+pub trait SiteStatsStore: Send + Sync {
+    async fn get_latest(&self) -> Result<Option<Document>, Error>;
+
+    async fn get_by_version(&self, version: i64) -> Result<Option<Document>, Error>;
+
+    async fn insert(&self, stats: Document) -> Result<(), Error>;
+}
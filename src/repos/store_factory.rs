@@ -0,0 +1,676 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Error};
+use log::{error, info};
+use mongodb::bson::doc;
+use mongodb::Client;
+use tokio_postgres::NoTls;
+
+use crate::cache::{
+    memory_cache::MemoryCache, redis_cache::RedisCache, response_cache::ResponseCache,
+};
+use crate::events::{
+    event_publisher::EventPublisher, publishing_channel_store::PublishingChannelStore,
+    publishing_video_store::PublishingVideoStore,
+};
+use crate::migrations::migration::MigrationDatabase;
+use crate::models::config::Config;
+use crate::repos::{
+    additional_channel_repo::AdditionalChannelRepository,
+    additional_channel_store::AdditionalChannelStore, apikey_store::ApiKeyStore,
+    apikeys_repo::ApiKeyRepository, backfill_repo::BackfillRepository,
+    backfill_store::BackfillStore, blacklist_repo::BlacklistRepository,
+    blacklist_store::BlacklistStore, blocklist_repo::BlocklistRepository,
+    blocklist_store::BlocklistStore, caption_repo::CaptionRepository, caption_store::CaptionStore,
+    channel_audit_repo::ChannelAuditRepository, channel_audit_store::ChannelAuditStore,
+    channel_popularity_repo::ChannelPopularityRepository,
+    channel_popularity_store::ChannelPopularityStore,
+    channel_probation_repo::ChannelProbationRepository,
+    channel_probation_store::ChannelProbationStore, channel_repo::ChannelRepository,
+    channel_store::ChannelStore, channel_summary_repo::ChannelSummaryRepository,
+    channel_summary_store::ChannelSummaryStore, collab_edge_repo::CollabEdgeRepository,
+    collab_edge_store::CollabEdgeStore, comment_repo::CommentRepository,
+    comment_store::CommentStore, corpus_snapshot_repo::CorpusSnapshotRepository,
+    corpus_snapshot_store::CorpusSnapshotStore, course_repo::CourseRepository,
+    course_store::CourseStore, crawl_audit_repo::CrawlAuditRepository,
+    crawl_audit_store::CrawlAuditStore, digest_report_repo::DigestReportRepository,
+    digest_report_store::DigestReportStore,
+    discovery_provenance_repo::DiscoveryProvenanceRepository,
+    discovery_provenance_store::DiscoveryProvenanceStore,
+    end_screen_scan_repo::EndScreenScanRepository, end_screen_scan_store::EndScreenScanStore,
+    guitar_term_repo::GuitarTermRepository, guitar_term_store::GuitarTermStore, indexes,
+    lock_repo::LockRepository, lock_store::LockStore,
+    non_guitar_channel_repo::NonGuitarChannelRepository,
+    non_guitar_channel_store::NonGuitarChannelStore, opt_out_repo::OptOutRepository,
+    opt_out_store::OptOutStore, policy_rejection_repo::PolicyRejectionRepository,
+    policy_rejection_store::PolicyRejectionStore,
+    postgres_additional_channel_store::PostgresAdditionalChannelStore,
+    postgres_apikey_store::PostgresApiKeyStore, postgres_backfill_store::PostgresBackfillStore,
+    postgres_blacklist_store::PostgresBlacklistStore,
+    postgres_blocklist_store::PostgresBlocklistStore, postgres_caption_store::PostgresCaptionStore,
+    postgres_channel_audit_store::PostgresChannelAuditStore,
+    postgres_channel_popularity_store::PostgresChannelPopularityStore,
+    postgres_channel_probation_store::PostgresChannelProbationStore,
+    postgres_channel_store::PostgresChannelStore,
+    postgres_channel_summary_store::PostgresChannelSummaryStore,
+    postgres_collab_edge_store::PostgresCollabEdgeStore,
+    postgres_comment_store::PostgresCommentStore,
+    postgres_corpus_snapshot_store::PostgresCorpusSnapshotStore,
+    postgres_course_store::PostgresCourseStore,
+    postgres_crawl_audit_store::PostgresCrawlAuditStore,
+    postgres_digest_report_store::PostgresDigestReportStore,
+    postgres_discovery_provenance_store::PostgresDiscoveryProvenanceStore,
+    postgres_end_screen_scan_store::PostgresEndScreenScanStore,
+    postgres_guitar_term_store::PostgresGuitarTermStore, postgres_lock_store::PostgresLockStore,
+    postgres_non_guitar_channel_store::PostgresNonGuitarChannelStore,
+    postgres_opt_out_store::PostgresOptOutStore,
+    postgres_policy_rejection_store::PostgresPolicyRejectionStore,
+    postgres_purge_store::PostgresPurgeStore,
+    postgres_reconciliation_report_store::PostgresReconciliationReportStore,
+    postgres_related_channel_store::PostgresRelatedChannelStore,
+    postgres_resolved_url_store::PostgresResolvedUrlStore,
+    postgres_review_queue_store::PostgresReviewQueueStore,
+    postgres_schema_migrations_store::PostgresSchemaMigrationsStore,
+    postgres_settings_store::PostgresSettingsStore,
+    postgres_site_stats_store::PostgresSiteStatsStore,
+    postgres_subscriber_store::PostgresSubscriberStore,
+    postgres_tag_profile_store::PostgresTagProfileStore, postgres_video_store::PostgresVideoStore,
+    postgres_view_store::PostgresViewStore, purge_repo::PurgeRepository, purge_store::PurgeStore,
+    quota_breaker_store::QuotaBreakerStore,
+    reconciliation_report_repo::ReconciliationReportRepository,
+    reconciliation_report_store::ReconciliationReportStore,
+    related_channel_repo::RelatedChannelRepository, related_channel_store::RelatedChannelStore,
+    resolved_url_repo::ResolvedUrlRepository, resolved_url_store::ResolvedUrlStore,
+    review_queue_repo::ReviewQueueRepository, review_queue_store::ReviewQueueStore,
+    schema_migrations_repo::SchemaMigrationsRepository,
+    schema_migrations_store::SchemaMigrationsStore, settings_repo::SettingsRepository,
+    settings_store::SettingsStore, site_stats_repo::SiteStatsRepository,
+    site_stats_store::SiteStatsStore, subscriber_repo::SubscriberRepository,
+    subscriber_store::SubscriberStore, tag_profile_repo::TagProfileRepository,
+    tag_profile_store::TagProfileStore, video_repo::VideoRepository, video_store::VideoStore,
+    view_repo::ViewRepository, view_store::ViewStore,
+};
+use crate::utils::consts::{
+    CACHE_BACKEND_MEMORY, CACHE_BACKEND_REDIS, STORAGE_BACKEND_MONGODB, STORAGE_BACKEND_POSTGRES,
+};
+use crate::utils::db::{get_db_name, get_postgres_schema};
+
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The database of the configured storage backend, all stores live in the same one.
+#[derive(Clone)]
+enum Storage {
+    MongoDb(Client),
+    Postgres(Arc<tokio_postgres::Client>),
+}
+
+/// Hands out the stores of the configured storage backend, channel and video stores wrapped to
+/// publish entity events when a publisher is set. Deployments on the postgres backend need no
+/// MongoDB. Also holds the api response cache shared by all youtube services.
+#[derive(Clone)]
+pub struct StoreFactory {
+    storage: Storage,
+    environment: String,
+    event_publisher: Option<Arc<dyn EventPublisher>>,
+    response_cache: Option<Arc<dyn ResponseCache>>,
+}
+
+impl StoreFactory {
+    /// The MongoDB client is only used, and only needed, with the mongodb backend.
+    pub async fn connect(
+        mongo_client: Option<Client>,
+        config: &Config,
+        event_publisher: Option<Arc<dyn EventPublisher>>,
+    ) -> Result<StoreFactory, Error> {
+        let storage = match config.storage.backend.as_str() {
+            STORAGE_BACKEND_MONGODB => Storage::MongoDb(
+                mongo_client.ok_or_else(|| anyhow!("MongoDB storage needs a client"))?,
+            ),
+            STORAGE_BACKEND_POSTGRES => {
+                let connection_string = config
+                    .storage
+                    .postgres_connection_string
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("Postgres storage needs a connection string"))?;
+
+                Storage::Postgres(Arc::new(
+                    connect_postgres(connection_string, &config.environment).await?,
+                ))
+            }
+            backend => return Err(anyhow!("Unknown storage backend {}", backend)),
+        };
+
+        Ok(StoreFactory {
+            storage,
+            environment: config.environment.clone(),
+            event_publisher,
+            response_cache: connect_response_cache(config).await?,
+        })
+    }
+
+    /// Stores on a MongoDB client, without events and cache.
+    #[cfg(test)]
+    pub fn mongodb(mongo_client: Client, environment: &str) -> StoreFactory {
+        StoreFactory {
+            storage: Storage::MongoDb(mongo_client),
+            environment: environment.to_string(),
+            event_publisher: None,
+            response_cache: None,
+        }
+    }
+
+    pub fn response_cache(&self) -> Option<Arc<dyn ResponseCache>> {
+        self.response_cache.clone()
+    }
+
+    pub fn event_publisher(&self) -> Option<Arc<dyn EventPublisher>> {
+        self.event_publisher.clone()
+    }
+
+    pub fn environment(&self) -> &str {
+        &self.environment
+    }
+
+    /// The MongoDB client of the mongodb backend, for the code that talks to MongoDB directly,
+    /// like its change streams.
+    pub fn mongo_client(&self) -> Option<Client> {
+        match &self.storage {
+            Storage::MongoDb(client) => Some(client.clone()),
+            Storage::Postgres(_) => None,
+        }
+    }
+
+    /// The Postgres client of the postgres backend, see `mongo_client`.
+    pub fn postgres_client(&self) -> Option<Arc<tokio_postgres::Client>> {
+        match &self.storage {
+            Storage::MongoDb(_) => None,
+            Storage::Postgres(client) => Some(client.clone()),
+        }
+    }
+
+    pub fn migration_database(&self) -> MigrationDatabase {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                MigrationDatabase::MongoDb(client.database(&get_db_name(&self.environment)))
+            }
+            Storage::Postgres(client) => MigrationDatabase::Postgres(client.clone()),
+        }
+    }
+
+    /// Creates the indexes the crawler queries rely on if they are missing, see
+    /// `indexes::ensure_indexes`.
+    pub async fn ensure_indexes(&self) -> Result<(), Error> {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                indexes::ensure_indexes(client, &self.environment).await?;
+                LockRepository::new(client, &self.environment)
+                    .ensure_ttl_index()
+                    .await
+            }
+            Storage::Postgres(client) => indexes::ensure_postgres_indexes(client).await,
+        }
+    }
+
+    /// Whether the database answers within a few seconds, for the readiness probe.
+    pub async fn is_reachable(&self) -> bool {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                let db = client.database(&get_db_name(&self.environment));
+
+                matches!(
+                    tokio::time::timeout(PING_TIMEOUT, db.run_command(doc! {"ping": 1}, None))
+                        .await,
+                    Ok(Ok(_))
+                )
+            }
+            Storage::Postgres(client) => matches!(
+                tokio::time::timeout(PING_TIMEOUT, client.simple_query("SELECT 1")).await,
+                Ok(Ok(_))
+            ),
+        }
+    }
+
+    pub fn channel_store(&self) -> Box<dyn ChannelStore> {
+        let store = self.backend_channel_store();
+
+        match &self.event_publisher {
+            Some(publisher) => Box::new(PublishingChannelStore::new(store, publisher.clone())),
+            None => store,
+        }
+    }
+
+    pub fn video_store(&self) -> Box<dyn VideoStore> {
+        let store = self.backend_video_store();
+
+        match &self.event_publisher {
+            Some(publisher) => Box::new(PublishingVideoStore::new(store, publisher.clone())),
+            None => store,
+        }
+    }
+
+    pub fn additional_channel_store(&self) -> Box<dyn AdditionalChannelStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                Box::new(AdditionalChannelRepository::new(client, &self.environment))
+            }
+            Storage::Postgres(client) => {
+                Box::new(PostgresAdditionalChannelStore::new(client.clone()))
+            }
+        }
+    }
+
+    pub fn apikey_store(&self) -> Box<dyn ApiKeyStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => Box::new(ApiKeyRepository::new(client, &self.environment)),
+            Storage::Postgres(client) => Box::new(PostgresApiKeyStore::new(client.clone())),
+        }
+    }
+
+    pub fn backfill_store(&self) -> Box<dyn BackfillStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                Box::new(BackfillRepository::new(client, &self.environment))
+            }
+            Storage::Postgres(client) => Box::new(PostgresBackfillStore::new(client.clone())),
+        }
+    }
+
+    pub fn blacklist_store(&self) -> Box<dyn BlacklistStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                Box::new(BlacklistRepository::new(client, &self.environment))
+            }
+            Storage::Postgres(client) => Box::new(PostgresBlacklistStore::new(client.clone())),
+        }
+    }
+
+    pub fn blocklist_store(&self) -> Box<dyn BlocklistStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                Box::new(BlocklistRepository::new(client, &self.environment))
+            }
+            Storage::Postgres(client) => Box::new(PostgresBlocklistStore::new(client.clone())),
+        }
+    }
+
+    pub fn caption_store(&self) -> Box<dyn CaptionStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => Box::new(CaptionRepository::new(client, &self.environment)),
+            Storage::Postgres(client) => Box::new(PostgresCaptionStore::new(client.clone())),
+        }
+    }
+
+    pub fn channel_audit_store(&self) -> Box<dyn ChannelAuditStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                Box::new(ChannelAuditRepository::new(client, &self.environment))
+            }
+            Storage::Postgres(client) => Box::new(PostgresChannelAuditStore::new(client.clone())),
+        }
+    }
+
+    pub fn channel_popularity_store(&self) -> Box<dyn ChannelPopularityStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                Box::new(ChannelPopularityRepository::new(client, &self.environment))
+            }
+            Storage::Postgres(client) => {
+                Box::new(PostgresChannelPopularityStore::new(client.clone()))
+            }
+        }
+    }
+
+    pub fn channel_probation_store(&self) -> Box<dyn ChannelProbationStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                Box::new(ChannelProbationRepository::new(client, &self.environment))
+            }
+            Storage::Postgres(client) => {
+                Box::new(PostgresChannelProbationStore::new(client.clone()))
+            }
+        }
+    }
+
+    pub fn channel_summary_store(&self) -> Box<dyn ChannelSummaryStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                Box::new(ChannelSummaryRepository::new(client, &self.environment))
+            }
+            Storage::Postgres(client) => Box::new(PostgresChannelSummaryStore::new(client.clone())),
+        }
+    }
+
+    pub fn collab_edge_store(&self) -> Box<dyn CollabEdgeStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                Box::new(CollabEdgeRepository::new(client, &self.environment))
+            }
+            Storage::Postgres(client) => Box::new(PostgresCollabEdgeStore::new(client.clone())),
+        }
+    }
+
+    pub fn comment_store(&self) -> Box<dyn CommentStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => Box::new(CommentRepository::new(client, &self.environment)),
+            Storage::Postgres(client) => Box::new(PostgresCommentStore::new(client.clone())),
+        }
+    }
+
+    pub fn corpus_snapshot_store(&self) -> Box<dyn CorpusSnapshotStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                Box::new(CorpusSnapshotRepository::new(client, &self.environment))
+            }
+            Storage::Postgres(client) => Box::new(PostgresCorpusSnapshotStore::new(client.clone())),
+        }
+    }
+
+    pub fn course_store(&self) -> Box<dyn CourseStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => Box::new(CourseRepository::new(client, &self.environment)),
+            Storage::Postgres(client) => Box::new(PostgresCourseStore::new(client.clone())),
+        }
+    }
+
+    pub fn crawl_audit_store(&self) -> Box<dyn CrawlAuditStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                Box::new(CrawlAuditRepository::new(client, &self.environment))
+            }
+            Storage::Postgres(client) => Box::new(PostgresCrawlAuditStore::new(client.clone())),
+        }
+    }
+
+    pub fn digest_report_store(&self) -> Box<dyn DigestReportStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                Box::new(DigestReportRepository::new(client, &self.environment))
+            }
+            Storage::Postgres(client) => Box::new(PostgresDigestReportStore::new(client.clone())),
+        }
+    }
+
+    pub fn discovery_provenance_store(&self) -> Box<dyn DiscoveryProvenanceStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => Box::new(DiscoveryProvenanceRepository::new(
+                client,
+                &self.environment,
+            )),
+            Storage::Postgres(client) => {
+                Box::new(PostgresDiscoveryProvenanceStore::new(client.clone()))
+            }
+        }
+    }
+
+    pub fn end_screen_scan_store(&self) -> Box<dyn EndScreenScanStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                Box::new(EndScreenScanRepository::new(client, &self.environment))
+            }
+            Storage::Postgres(client) => Box::new(PostgresEndScreenScanStore::new(client.clone())),
+        }
+    }
+
+    pub fn guitar_term_store(&self) -> Box<dyn GuitarTermStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                Box::new(GuitarTermRepository::new(client, &self.environment))
+            }
+            Storage::Postgres(client) => Box::new(PostgresGuitarTermStore::new(client.clone())),
+        }
+    }
+
+    pub fn lock_store(&self) -> Box<dyn LockStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => Box::new(LockRepository::new(client, &self.environment)),
+            Storage::Postgres(client) => Box::new(PostgresLockStore::new(client.clone())),
+        }
+    }
+
+    pub fn non_guitar_channel_store(&self) -> Box<dyn NonGuitarChannelStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                Box::new(NonGuitarChannelRepository::new(client, &self.environment))
+            }
+            Storage::Postgres(client) => {
+                Box::new(PostgresNonGuitarChannelStore::new(client.clone()))
+            }
+        }
+    }
+
+    pub fn opt_out_store(&self) -> Box<dyn OptOutStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => Box::new(OptOutRepository::new(client, &self.environment)),
+            Storage::Postgres(client) => Box::new(PostgresOptOutStore::new(client.clone())),
+        }
+    }
+
+    pub fn policy_rejection_store(&self) -> Box<dyn PolicyRejectionStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                Box::new(PolicyRejectionRepository::new(client, &self.environment))
+            }
+            Storage::Postgres(client) => {
+                Box::new(PostgresPolicyRejectionStore::new(client.clone()))
+            }
+        }
+    }
+
+    pub fn purge_store(&self) -> Box<dyn PurgeStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => Box::new(PurgeRepository::new(client, &self.environment)),
+            Storage::Postgres(client) => Box::new(PostgresPurgeStore::new(client.clone())),
+        }
+    }
+
+    pub fn quota_breaker_store(&self) -> Box<dyn QuotaBreakerStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                Box::new(SettingsRepository::new(client, &self.environment))
+            }
+            Storage::Postgres(client) => Box::new(PostgresSettingsStore::new(client.clone())),
+        }
+    }
+
+    pub fn reconciliation_report_store(&self) -> Box<dyn ReconciliationReportStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => Box::new(ReconciliationReportRepository::new(
+                client,
+                &self.environment,
+            )),
+            Storage::Postgres(client) => {
+                Box::new(PostgresReconciliationReportStore::new(client.clone()))
+            }
+        }
+    }
+
+    pub fn related_channel_store(&self) -> Box<dyn RelatedChannelStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                Box::new(RelatedChannelRepository::new(client, &self.environment))
+            }
+            Storage::Postgres(client) => Box::new(PostgresRelatedChannelStore::new(client.clone())),
+        }
+    }
+
+    pub fn resolved_url_store(&self) -> Box<dyn ResolvedUrlStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                Box::new(ResolvedUrlRepository::new(client, &self.environment))
+            }
+            Storage::Postgres(client) => Box::new(PostgresResolvedUrlStore::new(client.clone())),
+        }
+    }
+
+    pub fn review_queue_store(&self) -> Box<dyn ReviewQueueStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                Box::new(ReviewQueueRepository::new(client, &self.environment))
+            }
+            Storage::Postgres(client) => Box::new(PostgresReviewQueueStore::new(client.clone())),
+        }
+    }
+
+    pub fn schema_migrations_store(&self) -> Box<dyn SchemaMigrationsStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                Box::new(SchemaMigrationsRepository::new(client, &self.environment))
+            }
+            Storage::Postgres(client) => {
+                Box::new(PostgresSchemaMigrationsStore::new(client.clone()))
+            }
+        }
+    }
+
+    pub fn settings_store(&self) -> Box<dyn SettingsStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                Box::new(SettingsRepository::new(client, &self.environment))
+            }
+            Storage::Postgres(client) => Box::new(PostgresSettingsStore::new(client.clone())),
+        }
+    }
+
+    pub fn site_stats_store(&self) -> Box<dyn SiteStatsStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                Box::new(SiteStatsRepository::new(client, &self.environment))
+            }
+            Storage::Postgres(client) => Box::new(PostgresSiteStatsStore::new(client.clone())),
+        }
+    }
+
+    pub fn subscriber_store(&self) -> Box<dyn SubscriberStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                Box::new(SubscriberRepository::new(client, &self.environment))
+            }
+            Storage::Postgres(client) => Box::new(PostgresSubscriberStore::new(client.clone())),
+        }
+    }
+
+    pub fn tag_profile_store(&self) -> Box<dyn TagProfileStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => {
+                Box::new(TagProfileRepository::new(client, &self.environment))
+            }
+            Storage::Postgres(client) => Box::new(PostgresTagProfileStore::new(client.clone())),
+        }
+    }
+
+    pub fn view_store(&self) -> Box<dyn ViewStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => Box::new(ViewRepository::new(client, &self.environment)),
+            Storage::Postgres(client) => Box::new(PostgresViewStore::new(client.clone())),
+        }
+    }
+
+    fn backend_channel_store(&self) -> Box<dyn ChannelStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => Box::new(ChannelRepository::new(client, &self.environment)),
+            Storage::Postgres(client) => Box::new(PostgresChannelStore::new(client.clone())),
+        }
+    }
+
+    fn backend_video_store(&self) -> Box<dyn VideoStore> {
+        match &self.storage {
+            Storage::MongoDb(client) => Box::new(VideoRepository::new(client, &self.environment)),
+            Storage::Postgres(client) => Box::new(PostgresVideoStore::new(client.clone())),
+        }
+    }
+}
+
+async fn connect_response_cache(config: &Config) -> Result<Option<Arc<dyn ResponseCache>>, Error> {
+    if !config.cache.enabled {
+        return Ok(None);
+    }
+
+    let ttl_seconds = config.cache.ttl_seconds;
+
+    let cache: Arc<dyn ResponseCache> = match config.cache.backend.as_str() {
+        CACHE_BACKEND_MEMORY => Arc::new(MemoryCache::new(
+            Duration::from_secs(ttl_seconds),
+            config.cache.max_entries,
+        )),
+        CACHE_BACKEND_REDIS => {
+            let redis_url = config
+                .cache
+                .redis_url
+                .as_ref()
+                .ok_or_else(|| anyhow!("Redis cache needs a url"))?;
+
+            Arc::new(RedisCache::connect(redis_url, ttl_seconds as usize).await?)
+        }
+        backend => return Err(anyhow!("Unknown cache backend {}", backend)),
+    };
+
+    info!("Caching youtube api responses in {}", config.cache.backend);
+
+    Ok(Some(cache))
+}
+
+/// Creates the tables of all stores in the schema of the environment, like the MongoDB database
+/// of `get_db_name`.
+async fn connect_postgres(
+    connection_string: &str,
+    environment: &str,
+) -> Result<tokio_postgres::Client, Error> {
+    info!("Start connection to postgres");
+
+    let (client, connection) = tokio_postgres::connect(connection_string, NoTls).await?;
+
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            error!("Postgres connection closed: {}", e);
+        }
+    });
+
+    let schema = get_postgres_schema(environment);
+    client
+        .batch_execute(&format!(
+            "CREATE SCHEMA IF NOT EXISTS {0}; SET search_path TO {0};",
+            schema
+        ))
+        .await?;
+
+    PostgresChannelStore::create_table(&client).await?;
+    PostgresVideoStore::create_table(&client).await?;
+    PostgresAdditionalChannelStore::create_table(&client).await?;
+    PostgresApiKeyStore::create_table(&client).await?;
+    PostgresBackfillStore::create_table(&client).await?;
+    PostgresBlacklistStore::create_table(&client).await?;
+    PostgresBlocklistStore::create_table(&client).await?;
+    PostgresCaptionStore::create_table(&client).await?;
+    PostgresChannelAuditStore::create_table(&client).await?;
+    PostgresChannelPopularityStore::create_table(&client).await?;
+    PostgresChannelProbationStore::create_table(&client).await?;
+    PostgresChannelSummaryStore::create_table(&client).await?;
+    PostgresCollabEdgeStore::create_table(&client).await?;
+    PostgresCommentStore::create_table(&client).await?;
+    PostgresCorpusSnapshotStore::create_table(&client).await?;
+    PostgresCourseStore::create_table(&client).await?;
+    PostgresCrawlAuditStore::create_table(&client).await?;
+    PostgresDigestReportStore::create_table(&client).await?;
+    PostgresDiscoveryProvenanceStore::create_table(&client).await?;
+    PostgresEndScreenScanStore::create_table(&client).await?;
+    PostgresGuitarTermStore::create_table(&client).await?;
+    PostgresLockStore::create_table(&client).await?;
+    PostgresNonGuitarChannelStore::create_table(&client).await?;
+    PostgresOptOutStore::create_table(&client).await?;
+    PostgresPolicyRejectionStore::create_table(&client).await?;
+    PostgresReconciliationReportStore::create_table(&client).await?;
+    PostgresRelatedChannelStore::create_table(&client).await?;
+    PostgresResolvedUrlStore::create_table(&client).await?;
+    PostgresReviewQueueStore::create_table(&client).await?;
+    PostgresSchemaMigrationsStore::create_table(&client).await?;
+    PostgresSettingsStore::create_table(&client).await?;
+    PostgresSiteStatsStore::create_table(&client).await?;
+    PostgresSubscriberStore::create_table(&client).await?;
+    PostgresTagProfileStore::create_table(&client).await?;
+    PostgresViewStore::create_table(&client).await?;
+
+    info!("Connected to postgres");
+
+    Ok(client)
+}
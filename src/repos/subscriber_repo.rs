@@ -1,6 +1,10 @@
+use async_trait::async_trait;
+use futures::stream::TryStreamExt;
 use mongodb::bson::{doc, Document};
+use mongodb::options::FindOneOptions;
 use mongodb::{Client, Collection};
 
+use crate::repos::subscriber_store::SubscriberStore;
 use crate::utils::db::get_db_name;
 
 pub struct SubscriberRepository {
@@ -9,15 +13,18 @@ pub struct SubscriberRepository {
 
 impl SubscriberRepository {
     pub fn new(client: &Client, environment: &str) -> SubscriberRepository {
-        let db = client.database(&get_db_name(&environment));
+        let db = client.database(&get_db_name(environment));
         let channels = db.collection::<Document>("subscribers");
 
         SubscriberRepository {
             collection: channels,
         }
     }
+}
 
-    pub async fn delete_by_channel(&self, channel_id: &str) -> Result<(), anyhow::Error> {
+#[async_trait]
+impl SubscriberStore for SubscriberRepository {
+    async fn delete_by_channel(&self, channel_id: &str) -> Result<(), anyhow::Error> {
         self.collection
             .delete_many(doc! {"_id": {"channel": channel_id}}, None)
             .await?;
@@ -25,7 +32,26 @@ impl SubscriberRepository {
         Ok(())
     }
 
-    pub async fn upsert(&self, id: Document, view: Document) -> Result<(), anyhow::Error> {
+    async fn get_latest_estimate(&self, channel_id: &str) -> Result<Option<i64>, anyhow::Error> {
+        let find_options = FindOneOptions::builder()
+            .sort(doc! { "_id.date": -1 })
+            .build();
+
+        let latest = self
+            .collection
+            .find_one(doc! {"_id.channel": channel_id}, find_options)
+            .await?;
+
+        let estimate = latest.and_then(|doc| {
+            doc.get_i64("subscribersReconciled")
+                .or_else(|_| doc.get_i64("subscribers"))
+                .ok()
+        });
+
+        Ok(estimate)
+    }
+
+    async fn upsert(&self, id: Document, view: Document) -> Result<(), anyhow::Error> {
         let update_options = mongodb::options::UpdateOptions::builder()
             .upsert(true)
             .build();
@@ -36,4 +62,39 @@ impl SubscriberRepository {
 
         Ok(())
     }
+
+    async fn move_to_channel(
+        &self,
+        channel_id: &str,
+        target_channel_id: &str,
+    ) -> Result<u64, anyhow::Error> {
+        let update_options = mongodb::options::UpdateOptions::builder()
+            .upsert(true)
+            .build();
+        let mut cursor = self
+            .collection
+            .find(doc! {"_id.channel": channel_id}, None)
+            .await?;
+        let mut moved_count = 0;
+
+        while let Some(mut count) = cursor.try_next().await? {
+            let date = count.get_document("_id")?.get("date").cloned();
+            count.remove("_id");
+
+            self.collection
+                .update_one(
+                    doc! {"_id": {"channel": target_channel_id, "date": date}},
+                    doc! {"$setOnInsert": count},
+                    update_options.clone(),
+                )
+                .await?;
+            moved_count += 1;
+        }
+
+        self.collection
+            .delete_many(doc! {"_id.channel": channel_id}, None)
+            .await?;
+
+        Ok(moved_count)
+    }
 }
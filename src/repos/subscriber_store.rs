@@ -0,0 +1,24 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::Document;
+
+/// Daily subscriber counts per channel, keyed by channel and date. Implemented for MongoDB by
+/// `SubscriberRepository` and for PostgreSQL by `PostgresSubscriberStore`.
+#[async_trait]
+pub trait SubscriberStore: Send + Sync {
+    async fn delete_by_channel(&self, channel_id: &str) -> Result<(), Error>;
+
+    /// Returns the latest reconciled subscriber count, falling back to the raw count for
+    /// entries stored before reconciliation existed.
+    async fn get_latest_estimate(&self, channel_id: &str) -> Result<Option<i64>, Error>;
+
+    async fn upsert(&self, id: Document, view: Document) -> Result<(), Error>;
+
+    /// Moves the daily counts of a channel to another channel id. Days the target channel
+    /// already has keep its own count.
+    async fn move_to_channel(
+        &self,
+        channel_id: &str,
+        target_channel_id: &str,
+    ) -> Result<u64, Error>;
+}
@@ -0,0 +1,49 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::{doc, Document};
+use mongodb::options::UpdateOptions;
+use mongodb::{Client, Collection};
+
+use crate::repos::tag_profile_store::TagProfileStore;
+use crate::utils::db::get_db_name;
+
+pub struct TagProfileRepository {
+    collection: Collection<Document>,
+}
+
+impl TagProfileRepository {
+    pub fn new(client: &Client, environment: &str) -> TagProfileRepository {
+        let db = client.database(&get_db_name(environment));
+        let tag_profiles = db.collection::<Document>("tagprofiles");
+
+        TagProfileRepository {
+            collection: tag_profiles,
+        }
+    }
+}
+
+#[async_trait]
+impl TagProfileStore for TagProfileRepository {
+    async fn get(&self, channel_id: &str) -> Result<Option<Document>, Error> {
+        let profile = self
+            .collection
+            .find_one(doc! {"_id": channel_id}, None)
+            .await?;
+
+        Ok(profile)
+    }
+
+    async fn upsert(&self, channel_id: &str, profile: Document) -> Result<(), Error> {
+        let update_options = UpdateOptions::builder().upsert(true).build();
+
+        self.collection
+            .update_one(
+                doc! {"_id": channel_id},
+                doc! {"$set": profile},
+                update_options,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
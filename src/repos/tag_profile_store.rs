@@ -0,0 +1,12 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::Document;
+
+/// The tag profile of each channel, built from the tags of its videos. Implemented for MongoDB by
+/// `TagProfileRepository` and for PostgreSQL by `PostgresTagProfileStore`.
+#[async_trait]
+pub trait TagProfileStore: Send + Sync {
+    async fn get(&self, channel_id: &str) -> Result<Option<Document>, Error>;
+
+    async fn upsert(&self, channel_id: &str, profile: Document) -> Result<(), Error>;
+}
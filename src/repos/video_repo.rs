@@ -1,31 +1,173 @@
 use std::collections::HashMap;
 
 use anyhow::Error;
+use async_trait::async_trait;
 use chrono::{TimeZone, Utc};
 use futures::stream::TryStreamExt;
-use mongodb::bson::{doc, Document};
-use mongodb::options::FindOptions;
+use mongodb::bson::{doc, Bson, Document};
+use mongodb::options::{FindOneOptions, FindOptions, ReplaceOptions};
 use mongodb::{Client, Collection};
 
+use crate::repos::video_store::VideoStore;
 use crate::utils::db::get_db_name;
+use crate::utils::{
+    consts::VIDEO_AVAILABILITY_AVAILABLE,
+    edit_history_utils::{get_edits, EDIT_HISTORY_FIELDS, MAX_EDIT_HISTORY},
+    gear_utils::get_gear_names,
+    sentiment_utils::{get_comment_sentiment_document, CommentSentiment},
+    song_utils::{get_song_document, CoverOf},
+    stat_anomaly_utils::{get_anomalies, MAX_ANOMALIES, STAT_ANOMALY_FIELDS},
+};
 
+/// Videos live in the hot `videos` collection; old videos of dormant channels are moved to
+/// `coldvideos`. Reads by id and counts fall back to or include the cold collection, and an
+/// archived video that is upserted moves back to the hot collection.
 pub struct VideoRepository {
     collection: Collection<Document>,
+    cold_collection: Collection<Document>,
 }
 
 impl VideoRepository {
     pub fn new(client: &Client, environment: &str) -> VideoRepository {
-        let db = client.database(&get_db_name(&environment));
+        let db = client.database(&get_db_name(environment));
         let channels = db.collection::<Document>("videos");
+        let cold_videos = db.collection::<Document>("coldvideos");
 
         VideoRepository {
             collection: channels,
+            cold_collection: cold_videos,
         }
     }
+}
+
+impl VideoRepository {
+    /// Cold videos come first, so the hot copy of a video wins when both are collected by id.
+    async fn find_hot_and_cold(
+        &self,
+        query: Document,
+        find_options: FindOptions,
+    ) -> Result<Vec<Document>, Error> {
+        let cursor = self
+            .cold_collection
+            .find(query.clone(), find_options.clone())
+            .await?;
+        let mut videos: Vec<Document> = cursor.try_collect().await?;
+
+        let cursor = self.collection.find(query, find_options).await?;
+        let hot_videos: Vec<Document> = cursor.try_collect().await?;
+        videos.extend(hot_videos);
+
+        Ok(videos)
+    }
+
+    /// Moves an archived video back to the hot collection, copying before deleting like
+    /// `archive_by_channel`.
+    async fn unarchive(&self, id: &str) -> Result<Option<Document>, Error> {
+        let video = match self
+            .cold_collection
+            .find_one(doc! {"_id": id}, None)
+            .await?
+        {
+            Some(video) => video,
+            None => return Ok(None),
+        };
+
+        let replace_options = ReplaceOptions::builder().upsert(true).build();
+        self.collection
+            .replace_one(doc! {"_id": id}, video.clone(), replace_options)
+            .await?;
+        self.cold_collection
+            .delete_one(doc! {"_id": id}, None)
+            .await?;
+
+        Ok(Some(video))
+    }
+}
+
+#[async_trait]
+impl VideoStore for VideoRepository {
+    async fn find_by_id(&self, id: &str) -> Result<Option<Document>, Error> {
+        let video = self.collection.find_one(doc! {"_id": id}, None).await?;
+
+        if video.is_some() {
+            return Ok(video);
+        }
+
+        let cold_video = self
+            .cold_collection
+            .find_one(doc! {"_id": id}, None)
+            .await?;
+
+        Ok(cold_video)
+    }
+
+    async fn archive_by_channel(
+        &self,
+        channel_id: &str,
+        published_before: i64,
+        keep_latest: u64,
+    ) -> Result<u64, Error> {
+        let latest_options = FindOneOptions::builder()
+            .projection(doc! { "publishedAt": 1 })
+            .sort(doc! { "publishedAt": -1 })
+            .skip(keep_latest)
+            .build();
+
+        let oldest_kept = self
+            .collection
+            .find_one(doc! {"channel": channel_id}, latest_options)
+            .await?;
+
+        let max_published_at = match oldest_kept.and_then(|doc| doc.get_i64("publishedAt").ok()) {
+            Some(published_at) => published_at.min(published_before),
+            None => return Ok(0),
+        };
+
+        let query = doc! {
+            "channel": channel_id,
+            "publishedAt": { "$lte": max_published_at },
+        };
 
-    pub async fn get_updated_lookup(
+        let cursor = self.collection.find(query, None).await?;
+        let videos: Vec<Document> = cursor.try_collect().await?;
+        let replace_options = ReplaceOptions::builder().upsert(true).build();
+
+        // Copies are upserts, so a run that failed halfway is repeated without duplicates, and
+        // only the videos that made it into the cold collection are deleted
+        let mut copied_ids = vec![];
+        let mut copy_error = None;
+
+        for video in videos.iter() {
+            let id = video.get_str("_id")?;
+
+            match self
+                .cold_collection
+                .replace_one(doc! {"_id": id}, video.clone(), replace_options.clone())
+                .await
+            {
+                Ok(_) => copied_ids.push(id),
+                Err(e) => {
+                    copy_error = Some(e);
+                    break;
+                }
+            }
+        }
+
+        let result = self
+            .collection
+            .delete_many(doc! {"_id": {"$in": copied_ids}}, None)
+            .await?;
+
+        match copy_error {
+            Some(e) => Err(e.into()),
+            None => Ok(result.deleted_count),
+        }
+    }
+
+    async fn get_updated_lookup_by_ids(
         &self,
         channel_id: &str,
+        video_ids: &[String],
     ) -> Result<HashMap<String, chrono::DateTime<Utc>>, Error> {
         let find_options = FindOptions::builder()
             .projection(doc! {
@@ -34,10 +176,12 @@ impl VideoRepository {
             })
             .build();
 
-        let query = doc! {"channel": channel_id};
-
-        let cursor = self.collection.find(query, find_options).await?;
-        let videos: Vec<Document> = cursor.try_collect().await?;
+        let videos = self
+            .find_hot_and_cold(
+                doc! {"channel": channel_id, "_id": {"$in": video_ids}},
+                find_options,
+            )
+            .await?;
 
         let video_updated_lookup = videos
             .iter()
@@ -46,39 +190,582 @@ impl VideoRepository {
                 let id = doc.get_str("_id").unwrap().to_string();
                 let updated_at = doc.get_i64("updatedAt").unwrap();
 
-                (id, Utc.timestamp(updated_at as i64, 0))
+                (id, Utc.timestamp(updated_at, 0))
             })
             .collect::<HashMap<String, chrono::DateTime<Utc>>>();
 
         Ok(video_updated_lookup)
     }
 
-    pub async fn delete_all_by_channel(&self, channel_id: &str) -> Result<(), anyhow::Error> {
+    async fn delete_all_by_channel(&self, channel_id: &str) -> Result<(), anyhow::Error> {
         self.collection
             .delete_many(doc! {"channel": channel_id}, None)
             .await?;
+        self.cold_collection
+            .delete_many(doc! {"channel": channel_id}, None)
+            .await?;
 
         Ok(())
     }
 
-    pub async fn upsert(&self, id: &str, video_doc: Document) -> Result<(), anyhow::Error> {
+    async fn upsert(&self, id: &str, video_doc: Document) -> Result<(), anyhow::Error> {
         let update_options = mongodb::options::UpdateOptions::builder()
             .upsert(true)
             .build();
+        let find_options = FindOneOptions::builder()
+            .projection(
+                EDIT_HISTORY_FIELDS
+                    .iter()
+                    .chain(STAT_ANOMALY_FIELDS.iter())
+                    .map(|field| (field.to_string(), Bson::Int32(1)))
+                    .collect::<Document>(),
+            )
+            .build();
+
+        let previous = match self
+            .collection
+            .find_one(doc! {"_id": id}, find_options)
+            .await?
+        {
+            Some(previous) => Some(previous),
+            None => self.unarchive(id).await?,
+        };
+
+        let now = Utc::now().timestamp();
+        let (edits, anomalies) = match previous {
+            Some(previous) => (
+                get_edits(&previous, &video_doc, now),
+                get_anomalies(&previous, &video_doc, now),
+            ),
+            None => (vec![], vec![]),
+        };
+
+        let mut update = doc! {"$set": video_doc};
+        let mut push = Document::new();
+        if !edits.is_empty() {
+            push.insert(
+                "editHistory",
+                doc! {"$each": edits, "$slice": -(MAX_EDIT_HISTORY as i32)},
+            );
+        }
+        if !anomalies.is_empty() {
+            push.insert(
+                "anomalies",
+                doc! {"$each": anomalies, "$slice": -(MAX_ANOMALIES as i32)},
+            );
+        }
+        if !push.is_empty() {
+            update.insert("$push", push);
+        }
 
         self.collection
-            .update_one(doc! {"_id": id}, doc! {"$set": video_doc}, update_options)
+            .update_one(doc! {"_id": id}, update, update_options)
             .await?;
 
         Ok(())
     }
 
-    pub async fn count(&self, channel_id: &str) -> Result<u64, anyhow::Error> {
+    async fn count(&self, channel_id: &str) -> Result<u64, anyhow::Error> {
         let count = self
             .collection
             .count_documents(doc! {"channel": channel_id}, None)
             .await?;
+        let cold_count = self
+            .cold_collection
+            .count_documents(doc! {"channel": channel_id}, None)
+            .await?;
+
+        Ok(count + cold_count)
+    }
+
+    async fn get_ids_by_channel(&self, channel_id: &str) -> Result<Vec<String>, anyhow::Error> {
+        let find_options = FindOptions::builder().projection(doc! { "_id": 1 }).build();
+
+        let videos = self
+            .find_hot_and_cold(doc! {"channel": channel_id}, find_options)
+            .await?;
+
+        Ok(videos
+            .iter()
+            .filter_map(|video| video.get_str("_id").ok())
+            .map(|id| id.to_string())
+            .collect())
+    }
+
+    async fn get_views_by_ids(
+        &self,
+        channel_id: &str,
+        video_ids: &[String],
+    ) -> Result<HashMap<String, i64>, anyhow::Error> {
+        let find_options = FindOptions::builder()
+            .projection(doc! { "_id": 1, "views": 1 })
+            .build();
+
+        let videos = self
+            .find_hot_and_cold(
+                doc! {"channel": channel_id, "_id": {"$in": video_ids}},
+                find_options,
+            )
+            .await?;
+
+        Ok(videos
+            .iter()
+            .filter_map(|doc| {
+                Some((
+                    doc.get_str("_id").ok()?.to_string(),
+                    doc.get_i64("views").ok()?,
+                ))
+            })
+            .collect())
+    }
+
+    async fn get_ids_without_captions(
+        &self,
+        retry_before: i64,
+        excluded_channel_ids: &[String],
+        limit: i64,
+    ) -> Result<Vec<(String, String)>, anyhow::Error> {
+        let find_options = FindOptions::builder()
+            .projection(doc! { "_id": 1, "channel": 1 })
+            .sort(doc! { "publishedAt": -1 })
+            .limit(limit)
+            .build();
+
+        let query = doc! {
+            "captionsCrawledAt": { "$exists": false },
+            "channel": { "$nin": excluded_channel_ids },
+            "$or": [
+                { "captionsRetryAt": { "$exists": false } },
+                { "captionsRetryAt": { "$lte": retry_before } },
+            ],
+        };
+
+        let cursor = self.collection.find(query, find_options).await?;
+        let videos: Vec<Document> = cursor.try_collect().await?;
+
+        let ids = videos
+            .iter()
+            .filter_map(|doc| {
+                let id = doc.get_str("_id").ok()?.to_string();
+                let channel_id = doc.get_str("channel").ok()?.to_string();
+
+                Some((id, channel_id))
+            })
+            .collect();
+
+        Ok(ids)
+    }
+
+    async fn set_caption_keywords(
+        &self,
+        id: &str,
+        caption_keywords: Vec<String>,
+        caption_songs: &[CoverOf],
+    ) -> Result<(), anyhow::Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {
+                    "$set": {
+                        "captionKeywords": caption_keywords,
+                        "captionSongs": caption_songs.iter().map(get_song_document).collect::<Vec<Document>>(),
+                        "captionsCrawledAt": mongodb::bson::DateTime::now(),
+                    }
+                },
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_caption_retry(&self, id: &str, retry_at: i64) -> Result<(), anyhow::Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {"$set": {"captionsRetryAt": retry_at}},
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_ids_comments_due(
+        &self,
+        published_after: i64,
+        crawled_before: i64,
+        limit: i64,
+    ) -> Result<Vec<(String, String)>, anyhow::Error> {
+        let find_options = FindOptions::builder()
+            .projection(doc! { "_id": 1, "channel": 1 })
+            .sort(doc! { "publishedAt": -1 })
+            .limit(limit)
+            .build();
+
+        let query = doc! {
+            "publishedAt": { "$gte": published_after },
+            "$or": [
+                { "commentsCrawledAt": { "$exists": false } },
+                { "commentsCrawledAt": { "$lt": crawled_before } },
+            ],
+        };
+
+        let cursor = self.collection.find(query, find_options).await?;
+        let videos: Vec<Document> = cursor.try_collect().await?;
+
+        let ids = videos
+            .iter()
+            .filter_map(|doc| {
+                let id = doc.get_str("_id").ok()?.to_string();
+                let channel_id = doc.get_str("channel").ok()?.to_string();
+
+                Some((id, channel_id))
+            })
+            .collect();
+
+        Ok(ids)
+    }
+
+    async fn set_comments_crawled(&self, id: &str, crawled_at: i64) -> Result<(), anyhow::Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {"$set": {"commentsCrawledAt": crawled_at}},
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_comment_sentiment(
+        &self,
+        id: &str,
+        sentiment: &CommentSentiment,
+    ) -> Result<(), anyhow::Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {"$set": {"commentSentiment": get_comment_sentiment_document(sentiment)}},
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn count_all(&self) -> Result<u64, anyhow::Error> {
+        let count = self.collection.estimated_document_count(None).await?;
+        let cold_count = self.cold_collection.estimated_document_count(None).await?;
+
+        Ok(count + cold_count)
+    }
+
+    async fn get_page(
+        &self,
+        after_id: Option<&str>,
+        channel_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Document>, anyhow::Error> {
+        let find_options = FindOptions::builder()
+            .sort(doc! { "_id": 1 })
+            .limit(limit)
+            .build();
+
+        let mut query = doc! {};
+
+        if let Some(after_id) = after_id {
+            query.insert("_id", doc! { "$gt": after_id });
+        }
+
+        if let Some(channel_id) = channel_id {
+            query.insert("channel", channel_id);
+        }
+
+        let cursor = self.collection.find(query, find_options).await?;
+        let videos = cursor.try_collect().await?;
+
+        Ok(videos)
+    }
+
+    async fn set_view_snapshot(
+        &self,
+        id: &str,
+        field: &str,
+        views: i64,
+    ) -> Result<(), anyhow::Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": id, field: {"$exists": false}},
+                doc! {"$set": {field: views}},
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_published_timestamps(
+        &self,
+        channel_id: &str,
+        limit: i64,
+    ) -> Result<Vec<i64>, anyhow::Error> {
+        let find_options = FindOptions::builder()
+            .projection(doc! { "publishedAt": 1 })
+            .sort(doc! { "publishedAt": -1 })
+            .limit(limit)
+            .build();
+
+        let cursor = self
+            .collection
+            .find(doc! {"channel": channel_id}, find_options)
+            .await?;
+        let videos: Vec<Document> = cursor.try_collect().await?;
+
+        let timestamps = videos
+            .iter()
+            .filter_map(|doc| doc.get_i64("publishedAt").ok())
+            .collect();
+
+        Ok(timestamps)
+    }
+
+    async fn get_tags(
+        &self,
+        channel_id: &str,
+        limit: i64,
+    ) -> Result<Vec<(Vec<String>, i64)>, anyhow::Error> {
+        let find_options = FindOptions::builder()
+            .projection(doc! { "tags": 1, "publishedAt": 1 })
+            .sort(doc! { "publishedAt": -1 })
+            .limit(limit)
+            .build();
+
+        let cursor = self
+            .collection
+            .find(doc! {"channel": channel_id}, find_options)
+            .await?;
+        let videos: Vec<Document> = cursor.try_collect().await?;
+
+        let tags = videos
+            .iter()
+            .filter_map(|doc| {
+                let published_at = doc.get_i64("publishedAt").ok()?;
+                let tags = doc
+                    .get_array("tags")
+                    .map(|tags| {
+                        tags.iter()
+                            .filter_map(|tag| tag.as_str().map(|tag| tag.to_string()))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                Some((tags, published_at))
+            })
+            .collect();
+
+        Ok(tags)
+    }
+
+    async fn get_gear(
+        &self,
+        channel_id: &str,
+        limit: i64,
+    ) -> Result<Vec<Vec<String>>, anyhow::Error> {
+        let find_options = FindOptions::builder()
+            .projection(doc! { "gear": 1 })
+            .sort(doc! { "publishedAt": -1 })
+            .limit(limit)
+            .build();
+
+        let cursor = self
+            .collection
+            .find(doc! {"channel": channel_id}, find_options)
+            .await?;
+        let videos: Vec<Document> = cursor.try_collect().await?;
+
+        Ok(videos.iter().map(get_gear_names).collect())
+    }
+
+    async fn get_latest_texts(
+        &self,
+        channel_id: &str,
+        limit: i64,
+    ) -> Result<Vec<Document>, anyhow::Error> {
+        let find_options = FindOptions::builder()
+            .projection(doc! { "title": 1, "description": 1, "tags": 1 })
+            .sort(doc! { "publishedAt": -1 })
+            .limit(limit)
+            .build();
+
+        let cursor = self
+            .collection
+            .find(doc! {"channel": channel_id}, find_options)
+            .await?;
+
+        Ok(cursor.try_collect().await?)
+    }
+
+    async fn get_ids_availability_checked_before(
+        &self,
+        checked_before: i64,
+        limit: i64,
+    ) -> Result<Vec<(String, String)>, anyhow::Error> {
+        let find_options = FindOptions::builder()
+            .projection(doc! { "_id": 1, "availability": 1 })
+            .sort(doc! { "availabilityCheckedAt": 1 })
+            .limit(limit)
+            .build();
+
+        let query = doc! {
+            "$or": [
+                { "availabilityCheckedAt": { "$exists": false } },
+                { "availabilityCheckedAt": { "$lt": checked_before } },
+            ]
+        };
+
+        let cursor = self.collection.find(query, find_options).await?;
+        let videos: Vec<Document> = cursor.try_collect().await?;
+
+        let ids = videos
+            .iter()
+            .filter_map(|doc| {
+                let id = doc.get_str("_id").ok()?.to_string();
+                let availability = doc
+                    .get_str("availability")
+                    .unwrap_or(VIDEO_AVAILABILITY_AVAILABLE)
+                    .to_string();
+
+                Some((id, availability))
+            })
+            .collect();
+
+        Ok(ids)
+    }
+
+    async fn set_availability(&self, id: &str, availability: &str) -> Result<(), anyhow::Error> {
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {
+                    "$set": {
+                        "availability": availability,
+                        "availabilityCheckedAt": Utc::now().timestamp(),
+                    }
+                },
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn move_to_channel(
+        &self,
+        channel_id: &str,
+        target_channel_id: &str,
+    ) -> Result<u64, anyhow::Error> {
+        let filter = doc! {"channel": channel_id};
+        let update = doc! {"$set": {"channel": target_channel_id}};
+
+        let hot = self
+            .collection
+            .update_many(filter.clone(), update.clone(), None)
+            .await?;
+        let cold = self
+            .cold_collection
+            .update_many(filter, update, None)
+            .await?;
 
-        Ok(count)
+        Ok(hot.modified_count + cold.modified_count)
+    }
+
+    async fn get_restricted_by_channel(
+        &self,
+        channel_id: &str,
+        limit: i64,
+    ) -> Result<Vec<Document>, anyhow::Error> {
+        let find_options = FindOptions::builder()
+            .projection(doc! {
+                "title": 1,
+                "publishedAt": 1,
+                "embeddable": 1,
+                "regionRestriction": 1,
+            })
+            .sort(doc! { "publishedAt": -1 })
+            .limit(limit)
+            .build();
+
+        let cursor = self
+            .collection
+            .find(
+                doc! {
+                    "channel": channel_id,
+                    "$or": [
+                        {"embeddable": false},
+                        {"regionRestriction": {"$type": "object"}},
+                    ],
+                },
+                find_options,
+            )
+            .await?;
+        let videos = cursor.try_collect().await?;
+
+        Ok(videos)
+    }
+
+    async fn get_covers(
+        &self,
+        song_key: &str,
+        artist_key: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Document>, anyhow::Error> {
+        let find_options = FindOptions::builder()
+            .sort(doc! { "views": -1 })
+            .limit(limit)
+            .build();
+
+        let mut query = doc! {"coverOf.songKey": song_key};
+
+        if let Some(artist_key) = artist_key {
+            query.insert("coverOf.artistKey", artist_key);
+        }
+
+        let cursor = self.collection.find(query, find_options).await?;
+        let videos = cursor.try_collect().await?;
+
+        Ok(videos)
+    }
+
+    async fn get_cover_songs(&self, limit: i64) -> Result<Vec<CoverOf>, anyhow::Error> {
+        let pipeline = vec![
+            doc! {"$match": {"coverOf.songKey": {"$exists": true}}},
+            doc! {"$group": {
+                "_id": {"artistKey": "$coverOf.artistKey", "songKey": "$coverOf.songKey"},
+                "artist": {"$first": "$coverOf.artist"},
+                "song": {"$first": "$coverOf.song"},
+                "count": {"$sum": 1},
+            }},
+            doc! {"$sort": {"count": -1}},
+            doc! {"$limit": limit},
+        ];
+
+        let results: Vec<Document> = self
+            .collection
+            .aggregate(pipeline, None)
+            .await?
+            .try_collect()
+            .await?;
+
+        Ok(results
+            .iter()
+            .filter_map(|result| {
+                Some(CoverOf {
+                    artist: result.get_str("artist").ok()?.to_string(),
+                    song: result.get_str("song").ok()?.to_string(),
+                })
+            })
+            .collect())
     }
 }
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use mongodb::bson::Document;
+
+use crate::utils::{sentiment_utils::CommentSentiment, song_utils::CoverOf};
+
+/// Storage of video documents, split into hot and cold (archived) videos. Implemented for
+/// MongoDB by `VideoRepository` and for PostgreSQL by `PostgresVideoStore`.
+#[async_trait]
+pub trait VideoStore: Send + Sync {
+    async fn find_by_id(&self, id: &str) -> Result<Option<Document>, Error>;
+
+    /// Moves all but the `keep_latest` most recent videos published before the given timestamp
+    /// into the cold collection and returns the number of archived videos.
+    async fn archive_by_channel(
+        &self,
+        channel_id: &str,
+        published_before: i64,
+        keep_latest: u64,
+    ) -> Result<u64, Error>;
+
+    /// Reads the update times of the given videos of the channel only, e.g. the ones in its feed.
+    async fn get_updated_lookup_by_ids(
+        &self,
+        channel_id: &str,
+        video_ids: &[String],
+    ) -> Result<HashMap<String, chrono::DateTime<Utc>>, Error>;
+
+    async fn delete_all_by_channel(&self, channel_id: &str) -> Result<(), Error>;
+
+    async fn upsert(&self, id: &str, video_doc: Document) -> Result<(), Error>;
+
+    async fn count(&self, channel_id: &str) -> Result<u64, Error>;
+
+    /// Ids of the hot and cold videos of a channel.
+    async fn get_ids_by_channel(&self, channel_id: &str) -> Result<Vec<String>, Error>;
+
+    /// Returns the stored views of the given hot videos of a channel.
+    async fn get_views_by_ids(
+        &self,
+        channel_id: &str,
+        video_ids: &[String],
+    ) -> Result<HashMap<String, i64>, Error>;
+
+    /// Returns the newest videos without captions, leaving out the ones whose last caption fetch
+    /// failed and that are not due for a retry at `retry_before` and the videos of the excluded
+    /// channels.
+    async fn get_ids_without_captions(
+        &self,
+        retry_before: i64,
+        excluded_channel_ids: &[String],
+        limit: i64,
+    ) -> Result<Vec<(String, String)>, Error>;
+
+    async fn set_caption_keywords(
+        &self,
+        id: &str,
+        caption_keywords: Vec<String>,
+        caption_songs: &[CoverOf],
+    ) -> Result<(), Error>;
+
+    /// Marks a failed caption fetch, the video is left out until the retry time.
+    async fn set_caption_retry(&self, id: &str, retry_at: i64) -> Result<(), Error>;
+
+    /// Returns the newest videos published since `published_after` whose comments were not
+    /// fetched since `crawled_before`.
+    async fn get_ids_comments_due(
+        &self,
+        published_after: i64,
+        crawled_before: i64,
+        limit: i64,
+    ) -> Result<Vec<(String, String)>, Error>;
+
+    async fn set_comments_crawled(&self, id: &str, crawled_at: i64) -> Result<(), Error>;
+
+    async fn set_comment_sentiment(
+        &self,
+        id: &str,
+        sentiment: &CommentSentiment,
+    ) -> Result<(), Error>;
+
+    async fn count_all(&self) -> Result<u64, Error>;
+
+    /// Returns up to `limit` hot videos ordered by id, starting after `after_id`.
+    async fn get_page(
+        &self,
+        after_id: Option<&str>,
+        channel_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Document>, Error>;
+
+    /// Stores a view snapshot unless the video already has one for this field.
+    async fn set_view_snapshot(&self, id: &str, field: &str, views: i64) -> Result<(), Error>;
+
+    async fn get_published_timestamps(
+        &self,
+        channel_id: &str,
+        limit: i64,
+    ) -> Result<Vec<i64>, Error>;
+
+    /// Returns the tags and published timestamp of the latest videos of a channel.
+    async fn get_tags(
+        &self,
+        channel_id: &str,
+        limit: i64,
+    ) -> Result<Vec<(Vec<String>, i64)>, Error>;
+
+    /// Returns the gear names of the latest videos of a channel.
+    async fn get_gear(&self, channel_id: &str, limit: i64) -> Result<Vec<Vec<String>>, Error>;
+
+    /// Returns the ids, titles, descriptions and tags of the latest videos of a channel, newest
+    /// first.
+    async fn get_latest_texts(&self, channel_id: &str, limit: i64) -> Result<Vec<Document>, Error>;
+
+    /// Returns the ids and stored availability of hot videos whose availability was last checked
+    /// before the given timestamp, never checked videos first.
+    async fn get_ids_availability_checked_before(
+        &self,
+        checked_before: i64,
+        limit: i64,
+    ) -> Result<Vec<(String, String)>, Error>;
+
+    async fn set_availability(&self, id: &str, availability: &str) -> Result<(), Error>;
+
+    /// Moves all hot and cold videos of a channel to another channel id and returns the number
+    /// of moved videos.
+    async fn move_to_channel(
+        &self,
+        channel_id: &str,
+        target_channel_id: &str,
+    ) -> Result<u64, Error>;
+
+    /// Returns the hot videos of a channel that are not embeddable or restricted to some
+    /// regions, newest first.
+    async fn get_restricted_by_channel(
+        &self,
+        channel_id: &str,
+        limit: i64,
+    ) -> Result<Vec<Document>, Error>;
+
+    /// Returns the hot videos covering a song, most viewed first. The keys come from
+    /// `get_song_key`.
+    async fn get_covers(
+        &self,
+        song_key: &str,
+        artist_key: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Document>, Error>;
+
+    /// Returns the songs with the most covers among the hot videos.
+    async fn get_cover_songs(&self, limit: i64) -> Result<Vec<CoverOf>, Error>;
+}
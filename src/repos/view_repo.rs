@@ -1,6 +1,9 @@
+use async_trait::async_trait;
+use futures::stream::TryStreamExt;
 use mongodb::bson::{doc, Document};
 use mongodb::{Client, Collection};
 
+use crate::repos::view_store::ViewStore;
 use crate::utils::db::get_db_name;
 
 pub struct ViewRepository {
@@ -9,15 +12,18 @@ pub struct ViewRepository {
 
 impl ViewRepository {
     pub fn new(client: &Client, environment: &str) -> ViewRepository {
-        let db = client.database(&get_db_name(&environment));
+        let db = client.database(&get_db_name(environment));
         let channels = db.collection::<Document>("views");
 
         ViewRepository {
             collection: channels,
         }
     }
+}
 
-    pub async fn delete_by_channel(&self, channel_id: &str) -> Result<(), anyhow::Error> {
+#[async_trait]
+impl ViewStore for ViewRepository {
+    async fn delete_by_channel(&self, channel_id: &str) -> Result<(), anyhow::Error> {
         self.collection
             .delete_many(doc! {"_id": {"channel": channel_id}}, None)
             .await?;
@@ -25,7 +31,7 @@ impl ViewRepository {
         Ok(())
     }
 
-    pub async fn upsert(&self, id: Document, view: Document) -> Result<(), anyhow::Error> {
+    async fn upsert(&self, id: Document, view: Document) -> Result<(), anyhow::Error> {
         let update_options = mongodb::options::UpdateOptions::builder()
             .upsert(true)
             .build();
@@ -36,4 +42,39 @@ impl ViewRepository {
 
         Ok(())
     }
+
+    async fn move_to_channel(
+        &self,
+        channel_id: &str,
+        target_channel_id: &str,
+    ) -> Result<u64, anyhow::Error> {
+        let update_options = mongodb::options::UpdateOptions::builder()
+            .upsert(true)
+            .build();
+        let mut cursor = self
+            .collection
+            .find(doc! {"_id.channel": channel_id}, None)
+            .await?;
+        let mut moved_count = 0;
+
+        while let Some(mut count) = cursor.try_next().await? {
+            let date = count.get_document("_id")?.get("date").cloned();
+            count.remove("_id");
+
+            self.collection
+                .update_one(
+                    doc! {"_id": {"channel": target_channel_id, "date": date}},
+                    doc! {"$setOnInsert": count},
+                    update_options.clone(),
+                )
+                .await?;
+            moved_count += 1;
+        }
+
+        self.collection
+            .delete_many(doc! {"_id.channel": channel_id}, None)
+            .await?;
+
+        Ok(moved_count)
+    }
 }
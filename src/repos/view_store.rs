@@ -0,0 +1,20 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use mongodb::bson::Document;
+
+/// Daily view counts per channel, keyed by channel and date. Implemented for MongoDB by
+/// `ViewRepository` and for PostgreSQL by `PostgresViewStore`.
+#[async_trait]
+pub trait ViewStore: Send + Sync {
+    async fn delete_by_channel(&self, channel_id: &str) -> Result<(), Error>;
+
+    async fn upsert(&self, id: Document, view: Document) -> Result<(), Error>;
+
+    /// Moves the daily counts of a channel to another channel id. Days the target channel
+    /// already has keep its own count.
+    async fn move_to_channel(
+        &self,
+        channel_id: &str,
+        target_channel_id: &str,
+    ) -> Result<u64, Error>;
+}
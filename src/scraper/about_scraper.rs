@@ -0,0 +1,147 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{info, warn};
+use mongodb::bson::{doc, Document};
+use reqwest::Client;
+
+use crate::{
+    errors::crawler_error::CrawlerError,
+    repos::channel_store::ChannelStore,
+    services::youtube_service::YoutubeService,
+    utils::{
+        channel_page_utils::{parse_channel_page, ChannelPageHints},
+        link_utils::{extract_emails, extract_external_links},
+        throttle::Throttle,
+    },
+};
+
+const CHANNEL_PAGE_TIMEOUT_SECONDS: u64 = 10;
+
+/// Collects external links and contact emails from the channel's about texts, the channel
+/// description from `brandingSettings` and the localized snippet description. The verification
+/// badge and monetization hints come from the public channel page, which is not read in strict
+/// compliance mode.
+pub struct AboutScraper {
+    channel_repo: Box<dyn ChannelStore>,
+    youtube_service: YoutubeService,
+    page_throttle: Arc<Throttle>,
+    channel_page_base_url: String,
+    strict_compliance: bool,
+    http_client: Client,
+}
+
+impl AboutScraper {
+    pub fn new(
+        channel_repo: Box<dyn ChannelStore>,
+        youtube_service: YoutubeService,
+        page_throttle: Arc<Throttle>,
+        channel_page_base_url: String,
+        strict_compliance: bool,
+    ) -> Self {
+        Self {
+            channel_repo,
+            youtube_service,
+            page_throttle,
+            channel_page_base_url,
+            strict_compliance,
+            http_client: Client::builder()
+                .timeout(Duration::from_secs(CHANNEL_PAGE_TIMEOUT_SECONDS))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    pub async fn scrape(&self, channel_id: String) -> Result<(), CrawlerError> {
+        let channel_details = self
+            .youtube_service
+            .get_channel_details(&channel_id)
+            .await?;
+
+        let about_text = [
+            channel_details.branding_settings.channel.description,
+            channel_details.snippet.description,
+            channel_details.snippet.localized.description,
+        ]
+        .iter()
+        .flatten()
+        .cloned()
+        .collect::<Vec<String>>()
+        .join("\n");
+
+        let links = extract_external_links(&about_text)
+            .iter()
+            .map(|link| {
+                let mut link_doc = doc! {"type": link.link_type, "url": &link.url};
+
+                if let Some(handle) = &link.handle {
+                    link_doc.insert("handle", handle);
+                }
+
+                link_doc
+            })
+            .collect::<Vec<Document>>();
+        let emails = extract_emails(&about_text);
+
+        info!(
+            "Found {} links and {} emails for channel {}",
+            links.len(),
+            emails.len(),
+            channel_id
+        );
+
+        self.channel_repo
+            .set_about(&channel_id, links, emails)
+            .await?;
+
+        if self.strict_compliance {
+            return Ok(());
+        }
+
+        // The page layout changes without notice, a failed page must not lose the about links
+        match self.load_page_hints(&channel_id).await {
+            Ok(Some(hints)) => {
+                info!("Found page hints {:?} for channel {}", hints, channel_id);
+                self.channel_repo
+                    .set_page_hints(&channel_id, &hints)
+                    .await?;
+            }
+            Ok(None) => warn!(
+                "Channel page of {} has no initial data, keeping its page hints",
+                channel_id
+            ),
+            Err(e) => warn!("Failed to load channel page of {}: {}", channel_id, e),
+        }
+
+        Ok(())
+    }
+
+    async fn load_page_hints(
+        &self,
+        channel_id: &str,
+    ) -> Result<Option<ChannelPageHints>, CrawlerError> {
+        self.page_throttle.wait().await;
+
+        let url = format!("{}{}", self.channel_page_base_url, channel_id);
+        let response = self
+            .http_client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| CrawlerError::FeedError(e.to_string()))?;
+
+        if response.status() != 200 {
+            return Err(CrawlerError::FeedError(format!(
+                "Youtube Channel Page Response Error: {}",
+                response.status()
+            )));
+        }
+
+        let html = response
+            .text()
+            .await
+            .map_err(|e| CrawlerError::FeedError(e.to_string()))?;
+
+        Ok(parse_channel_page(&html))
+    }
+}
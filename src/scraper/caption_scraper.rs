@@ -0,0 +1,229 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use log::{info, warn};
+use mongodb::bson::doc;
+use quick_xml::de::from_str;
+
+use crate::{
+    errors::crawler_error::CrawlerError,
+    models::youtube_timed_text::YoutubeTimedText,
+    repos::{caption_store::CaptionStore, channel_store::ChannelStore, video_store::VideoStore},
+    utils::{
+        keyword_utils::extract_known_keywords,
+        song_utils::{find_song_mentions, CoverOf},
+        throttle::Throttle,
+    },
+};
+
+const DEFAULT_CAPTION_LANGUAGE: &str = "en";
+// Failed fetches are retried later, so they do not hold back the videos after them
+const CAPTION_RETRY_SECONDS: i64 = 7 * 24 * 60 * 60;
+// The most covered songs are looked for in captions, less known titles are too often common words
+pub const CAPTION_SONGS: i64 = 5_000;
+const TECHNIQUE_TERMS: [&str; 20] = [
+    "alternate picking",
+    "sweep picking",
+    "economy picking",
+    "hybrid picking",
+    "travis picking",
+    "fingerstyle",
+    "tapping",
+    "legato",
+    "vibrato",
+    "bending",
+    "palm muting",
+    "slide",
+    "harmonics",
+    "pentatonic",
+    "arpeggio",
+    "chord progression",
+    "barre chord",
+    "power chord",
+    "strumming",
+    "open tuning",
+];
+
+pub struct CaptionScraper {
+    caption_repo: Box<dyn CaptionStore>,
+    video_repo: Box<dyn VideoStore>,
+    channel_repo: Box<dyn ChannelStore>,
+    vocabulary: Vec<String>,
+    songs: Vec<CoverOf>,
+    throttle: Arc<Throttle>,
+    timed_text_base_url: String,
+}
+
+impl CaptionScraper {
+    pub fn new(
+        caption_repo: Box<dyn CaptionStore>,
+        video_repo: Box<dyn VideoStore>,
+        channel_repo: Box<dyn ChannelStore>,
+        guitar_terms: Vec<String>,
+        songs: Vec<CoverOf>,
+        throttle: Arc<Throttle>,
+        timed_text_base_url: String,
+    ) -> CaptionScraper {
+        let mut vocabulary = guitar_terms;
+        vocabulary.extend(TECHNIQUE_TERMS.iter().map(|term| term.to_string()));
+
+        CaptionScraper {
+            caption_repo,
+            video_repo,
+            channel_repo,
+            vocabulary,
+            songs,
+            throttle,
+            timed_text_base_url,
+        }
+    }
+
+    /// Stores the captions of a video with the techniques, guitar terms and known songs they
+    /// mention. A failed fetch is retried after a week.
+    pub async fn scrape(&self, video_id: String, channel_id: String) -> Result<(), CrawlerError> {
+        let result = self.scrape_captions(&video_id, &channel_id).await;
+
+        if result.is_err() {
+            let retry_at = Utc::now().timestamp() + CAPTION_RETRY_SECONDS;
+
+            if let Err(e) = self.video_repo.set_caption_retry(&video_id, retry_at).await {
+                warn!("Failed to set caption retry of video {}: {}", video_id, e);
+            }
+        }
+
+        result
+    }
+
+    async fn scrape_captions(&self, video_id: &str, channel_id: &str) -> Result<(), CrawlerError> {
+        let language = self
+            .channel_repo
+            .get_language(channel_id)
+            .await?
+            .unwrap_or_else(|| DEFAULT_CAPTION_LANGUAGE.to_string());
+
+        let mut kind = "manual";
+        let mut timed_text = self.load_timed_text(video_id, &language, None).await?;
+
+        if timed_text.texts.is_empty() {
+            kind = "asr";
+            timed_text = self
+                .load_timed_text(video_id, &language, Some(kind))
+                .await?;
+        }
+
+        if timed_text.texts.is_empty() {
+            info!("No captions available for video {}", video_id);
+            self.video_repo
+                .set_caption_keywords(video_id, vec![], &[])
+                .await?;
+
+            return Ok(());
+        }
+
+        let text = timed_text
+            .texts
+            .iter()
+            .map(|line| unescape_caption(&line.value))
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        let caption = doc! {
+            "channel": channel_id,
+            "language": &language,
+            "kind": kind,
+            "text": &text,
+            "updatedAt": mongodb::bson::DateTime::now(),
+        };
+
+        self.caption_repo.upsert(video_id, caption).await?;
+
+        let keywords = extract_known_keywords(&text, &self.vocabulary);
+        let songs = find_song_mentions(&text, &self.songs);
+
+        info!(
+            "Found {} caption keywords and {} songs for video {}",
+            keywords.len(),
+            songs.len(),
+            video_id
+        );
+        self.video_repo
+            .set_caption_keywords(video_id, keywords, &songs)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn load_timed_text(
+        &self,
+        video_id: &str,
+        language: &str,
+        kind: Option<&str>,
+    ) -> Result<YoutubeTimedText, CrawlerError> {
+        let mut url = format!(
+            "{}?v={}&lang={}",
+            self.timed_text_base_url, video_id, language
+        );
+
+        if let Some(kind) = kind {
+            url = format!("{}&kind={}", url, kind);
+        }
+
+        self.throttle.wait().await;
+
+        let response = reqwest::get(&url)
+            .await
+            .map_err(|e| CrawlerError::FeedError(e.to_string()))?;
+
+        if response.status() != 200 {
+            return Err(CrawlerError::FeedError(format!(
+                "Youtube Timed Text Response Error: {}",
+                response.status()
+            )));
+        }
+
+        let body = response
+            .text()
+            .await
+            .map_err(|e| CrawlerError::FeedError(e.to_string()))?;
+
+        parse_timed_text(&body)
+    }
+}
+
+fn parse_timed_text(xml: &str) -> Result<YoutubeTimedText, CrawlerError> {
+    if xml.trim().is_empty() {
+        return Ok(YoutubeTimedText { texts: vec![] });
+    }
+
+    let timed_text = from_str::<YoutubeTimedText>(xml)?;
+
+    Ok(timed_text)
+}
+
+fn unescape_caption(text: &str) -> String {
+    text.replace("&#39;", "'")
+        .replace("&quot;", "\"")
+        .replace("&amp;", "&")
+        .replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn parse_timed_text_lines() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8" ?><transcript><text start="0.5" dur="2.1">let&amp;#39;s play a pentatonic lick</text><text start="2.6" dur="1">with vibrato</text></transcript>"#;
+        let timed_text = super::parse_timed_text(xml).unwrap();
+
+        assert_eq!(timed_text.texts.len(), 2);
+        assert_eq!(
+            super::unescape_caption(&timed_text.texts[0].value),
+            "let's play a pentatonic lick"
+        );
+    }
+
+    #[test]
+    fn parse_empty_timed_text() {
+        let timed_text = super::parse_timed_text("").unwrap();
+        assert!(timed_text.texts.is_empty());
+    }
+}
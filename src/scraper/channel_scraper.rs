@@ -1,88 +1,232 @@
-use anyhow::Error;
-use chrono::{DateTime, Datelike, Utc};
-use log::{error, info, warn};
-use mongodb::bson::doc;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{Datelike, Utc};
+use log::{info, warn};
+use mongodb::bson::{doc, Document};
+use reqwest::Client;
 use whatlang::detect;
 
 use crate::{
+    errors::crawler_error::CrawlerError,
     models::youtube_channel_details::YoutubeStatisticsItem,
+    notifications::notification_service::NotificationService,
     repos::{
-        apikeys_repo::ApiKeyRepository, channel_repo::ChannelRepository,
-        subscriber_repo::SubscriberRepository, video_repo::VideoRepository,
-        view_repo::ViewRepository,
+        blocklist_store::BlocklistStore,
+        channel_probation_store::ChannelProbationStore,
+        channel_store::ChannelStore,
+        opt_out_store::OptOutStore,
+        review_queue_store::{ReviewQueueStore, REVIEW_STATUS_APPROVED},
+        subscriber_store::SubscriberStore,
+        video_store::VideoStore,
+        view_store::ViewStore,
+    },
+    services::{
+        channel_redirect_service::ChannelRedirectService, guitar_terms_service::GuitarTermsService,
+        youtube_service::YoutubeService,
+    },
+    utils::{
+        api_scheduler::ApiPriority,
+        ban_evasion_utils::{
+            find_ban_evasion, get_avatar_hash, get_ban_evasion_review_document,
+            get_channel_fingerprint,
+        },
+        channel_metadata_utils::get_handle,
+        consts::{
+            CHANNEL_STATUS_ACTIVE, CHANNEL_STATUS_PROBATION, CLASSIFICATION_OVERRIDE_INCLUDE,
+            DATA_SOURCE_YOUTUBE_DATA_API,
+        },
+        image_change_utils::add_image_hashes,
+        keyword_utils,
+        subscriber_utils::{get_crossed_milestones, reconcile_subscriber_count},
     },
-    services::{guitar_terms_service::GuitarTermsService, youtube_service::YoutubeService},
-    utils::keyword_utils,
 };
 
+const IMAGE_TIMEOUT_SECONDS: u64 = 10;
+
 pub struct ChannelScraper {
-    channel_repo: ChannelRepository,
-    view_repo: ViewRepository,
-    subscriber_repo: SubscriberRepository,
-    video_repo: VideoRepository,
+    channel_repo: Box<dyn ChannelStore>,
+    view_repo: Box<dyn ViewStore>,
+    subscriber_repo: Box<dyn SubscriberStore>,
+    video_repo: Box<dyn VideoStore>,
     youtube_service: YoutubeService,
     guitar_terms_service: GuitarTermsService,
+    channel_redirect_service: ChannelRedirectService,
+    notification_service: Arc<NotificationService>,
+    blocklist_repo: Box<dyn BlocklistStore>,
+    opt_out_repo: Box<dyn OptOutStore>,
+    /// Set when new channels start on probation
+    probation_repo: Option<Box<dyn ChannelProbationStore>>,
+    /// Set when new channels are held back for review if they look like a blocked channel
+    review_queue_repo: Option<Box<dyn ReviewQueueStore>>,
+    http_client: Client,
 }
 
 impl ChannelScraper {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
-        channel_repo: ChannelRepository,
-        view_repo: ViewRepository,
-        subscriber_repo: SubscriberRepository,
-        video_repo: VideoRepository,
-        apikey_repo: ApiKeyRepository,
+        channel_repo: Box<dyn ChannelStore>,
+        view_repo: Box<dyn ViewStore>,
+        subscriber_repo: Box<dyn SubscriberStore>,
+        video_repo: Box<dyn VideoStore>,
+        youtube_service: YoutubeService,
         guitar_terms_service: GuitarTermsService,
+        channel_redirect_service: ChannelRedirectService,
+        notification_service: Arc<NotificationService>,
+        blocklist_repo: Box<dyn BlocklistStore>,
+        opt_out_repo: Box<dyn OptOutStore>,
+        probation_repo: Option<Box<dyn ChannelProbationStore>>,
+        review_queue_repo: Option<Box<dyn ReviewQueueStore>>,
     ) -> ChannelScraper {
         ChannelScraper {
             channel_repo,
             view_repo,
             subscriber_repo,
             video_repo,
-            youtube_service: YoutubeService::new(apikey_repo),
+            youtube_service,
             guitar_terms_service,
+            channel_redirect_service,
+            notification_service,
+            blocklist_repo,
+            opt_out_repo,
+            probation_repo,
+            review_queue_repo,
+            http_client: Client::builder()
+                .timeout(Duration::from_secs(IMAGE_TIMEOUT_SECONDS))
+                .build()
+                .unwrap_or_default(),
         }
     }
 
-    pub async fn scrape(&self, channel_id: String, ignore_guitar_terms: bool) -> Result<(), Error> {
+    /// The api class of the next scrapes, see `get_source_priority`.
+    pub fn set_api_priority(&mut self, priority: ApiPriority) {
+        self.youtube_service.set_priority(priority);
+    }
+
+    /// Quota units spent by the scraper so far.
+    pub fn api_units_spent(&self) -> u64 {
+        self.youtube_service.units_spent()
+    }
+
+    /// Returns whether the channel was stored as a guitar channel. Channels blocked or opted out
+    /// by id or handle are never stored, a failed opt-out check fails the scrape. `known_details`
+    /// are used instead of loading the channel again.
+    pub async fn scrape(
+        &self,
+        channel_id: String,
+        ignore_guitar_terms: bool,
+        source: Option<String>,
+        known_details: Option<Box<YoutubeStatisticsItem>>,
+    ) -> Result<bool, CrawlerError> {
         info!("Start scraping channel {}", channel_id);
 
-        let channel_details = match self.load_channel_details(&channel_id).await {
-            Ok(value) => value,
-            Err(value) => return value,
+        if self.blocklist_repo.is_blocked(&channel_id).await? {
+            info!("Skip channel {}, it is blocked", channel_id);
+            return Ok(false);
+        }
+
+        let channel_details = match known_details {
+            Some(channel_details) => *channel_details,
+            None => {
+                let channel_id = self.resolve_channel_id(channel_id).await?;
+
+                let channel_details = self.load_channel_details(&channel_id).await?;
+
+                // The api answers with the current id of channels that moved
+                if channel_details.id != channel_id {
+                    self.channel_redirect_service
+                        .redirect(&channel_id, &channel_details.id, false)
+                        .await?;
+                }
+
+                channel_details
+            }
         };
+        let channel_id = channel_details.id.clone();
+
+        // Blocks by handle only match once the api told the handle of the channel
+        if self.blocklist_repo.is_blocked(&channel_id).await?
+            || self.is_handle_blocked(&channel_details).await?
+        {
+            info!("Skip channel {}, it is blocked", channel_id);
+            return Ok(false);
+        }
+
+        // Commands by id are only checked against opt-outs by id before
+        if self.opt_out_repo.is_opted_out(&channel_id).await?
+            || self.is_handle_opted_out(&channel_details).await?
+        {
+            info!("Skip channel {}, it opted out", channel_id);
+            return Ok(false);
+        }
 
         let description = channel_details.snippet.description.unwrap_or_default();
 
-        let guitar_term_result = self
+        let classification_override = self
+            .channel_repo
+            .get_classification_override(&channel_id)
+            .await?;
+
+        // Force-included channels do not end up in the non guitar channels
+        let mut guitar_term_result = self
             .guitar_terms_service
             .has_guitar_term(
                 &channel_id,
                 &channel_details.snippet.title,
                 &description,
-                ignore_guitar_terms,
+                ignore_guitar_terms
+                    || classification_override.as_deref() == Some(CLASSIFICATION_OVERRIDE_INCLUDE),
             )
             .await;
+        guitar_term_result.apply_override(classification_override.as_deref());
 
         if guitar_term_result.is_blacklisted {
             self.delete_channel(&channel_id).await?;
         }
 
-        let view_count = channel_details
-            .statistics
-            .view_count
-            .parse::<i64>()
-            .unwrap_or(0);
+        // An unreadable view count keeps the stored views
+        let view_count = channel_details.statistics.view_count;
 
-        if guitar_term_result.has_guitar_term == false || view_count == 0 {
-            return Ok(());
+        if !guitar_term_result.has_guitar_term || view_count == Some(0) {
+            return Ok(false);
         }
 
-        let subscriber_count = match channel_details.statistics.subscriber_count {
-            Some(subscriber_count) => subscriber_count.parse::<i64>()?,
-            None => 0,
-        };
+        let subscriber_count = channel_details.statistics.subscriber_count.unwrap_or(0);
+
+        let previous_estimate = self
+            .subscriber_repo
+            .get_latest_estimate(&channel_id)
+            .await?;
+        let reconciled_subscriber_count =
+            reconcile_subscriber_count(subscriber_count, previous_estimate);
 
-        let published_date = DateTime::parse_from_rfc3339(&channel_details.snippet.published_at)?;
+        let published_date = channel_details.snippet.published_at.ok_or_else(|| {
+            CrawlerError::ParseError(format!("Channel {} has no publishedAt", channel_id))
+        })?;
+
+        let stored_channel = self.channel_repo.find_by_id(&channel_id).await?;
+        let is_new_channel = stored_channel.is_none();
+
+        let avatar_hash = self
+            .get_image_hash(&channel_id, &channel_details.snippet.thumbnails.default.url)
+            .await;
+
+        if is_new_channel
+            && self
+                .is_held_for_review(
+                    &channel_id,
+                    &channel_details.snippet.title,
+                    &description,
+                    avatar_hash,
+                )
+                .await?
+        {
+            return Ok(false);
+        }
+
+        let on_probation = self
+            .is_on_probation(&channel_id, stored_channel.as_ref())
+            .await?;
 
         let mut channel = doc! {
             "_id": channel_id.to_string(),
@@ -91,16 +235,27 @@ impl ChannelScraper {
             "publishedAt": published_date.timestamp(),
             "thumbnail": channel_details.snippet.thumbnails.default.url.to_string(),
             "subscribers": subscriber_count,
-            "views": view_count,
+            "subscribersReconciled": reconciled_subscriber_count,
             "subscribersHidden": channel_details.statistics.hidden_subscriber_count,
             "lastCrawl": mongodb::bson::DateTime::now(),
+            "dataSource": DATA_SOURCE_YOUTUBE_DATA_API,
+            "status": if on_probation { CHANNEL_STATUS_PROBATION } else { CHANNEL_STATUS_ACTIVE },
         };
 
-        if channel_details.snippet.country.is_some() {
-            channel.insert(
-                "country",
-                channel_details.snippet.country.unwrap().to_lowercase(),
-            );
+        if let Some(view_count) = view_count {
+            channel.insert("views", view_count);
+        }
+
+        if let Some(custom_url) = channel_details.snippet.custom_url {
+            if custom_url.starts_with('@') {
+                channel.insert("handle", custom_url.to_lowercase());
+            }
+
+            channel.insert("customUrl", custom_url);
+        }
+
+        if let Some(country) = channel_details.snippet.country {
+            channel.insert("country", country.to_lowercase());
         }
 
         let keywords = keyword_utils::parse_keywords(
@@ -111,10 +266,25 @@ impl ChannelScraper {
                 .unwrap_or_default(),
         );
 
-        if keywords.len() > 0 {
+        if !keywords.is_empty() {
             channel.insert("keywords", keywords);
         }
 
+        let topic_categories = channel_details
+            .topic_details
+            .and_then(|topic_details| topic_details.topic_categories)
+            .unwrap_or_default();
+
+        if !topic_categories.is_empty() {
+            let topics = topic_categories
+                .iter()
+                .filter_map(|topic_url| keyword_utils::parse_topic_category(topic_url))
+                .collect::<Vec<String>>();
+
+            channel.insert("topicCategories", topic_categories);
+            channel.insert("topics", topics);
+        }
+
         let language_option = self.detect_language(&channel_id, &description).await;
         match language_option {
             Some(language) => {
@@ -126,37 +296,219 @@ impl ChannelScraper {
             }
         }
 
-        self.store_view_count(&channel_id, view_count).await;
-        self.store_subscriber_count(&channel_id, subscriber_count)
-            .await;
+        // The stats history starts once a channel passed its probation
+        if !on_probation {
+            if let Some(view_count) = view_count {
+                self.store_view_count(&channel_id, view_count).await;
+            }
+            self.store_subscriber_count(&channel_id, subscriber_count, reconciled_subscriber_count)
+                .await;
+        }
+
+        let banner_url = channel_details
+            .branding_settings
+            .image
+            .map(|image| image.banner_external_url);
+        let banner_hash = match &banner_url {
+            Some(banner_url) => self.get_image_hash(&channel_id, banner_url).await,
+            None => None,
+        };
+        if let Some(banner_url) = banner_url {
+            channel.insert("banner", banner_url);
+        }
+
+        add_image_hashes(
+            &mut channel,
+            stored_channel.as_ref(),
+            avatar_hash,
+            banner_hash,
+            mongodb::bson::DateTime::now(),
+        );
+
+        if let (true, Some(source)) = (is_new_channel, source) {
+            channel.insert("source", source);
+        }
 
         self.channel_repo.upsert(&channel_id, channel).await;
 
-        Ok(())
+        if is_new_channel {
+            self.notification_service
+                .notify_channel_accepted(&channel_id, &channel_details.snippet.title)
+                .await;
+        }
+
+        // Channels seen for the first time have no previous count to compare with
+        if let Some(previous_estimate) = previous_estimate {
+            for milestone in get_crossed_milestones(previous_estimate, reconciled_subscriber_count)
+            {
+                self.notification_service
+                    .notify_milestone(
+                        &channel_id,
+                        &channel_details.snippet.title,
+                        milestone,
+                        reconciled_subscriber_count,
+                    )
+                    .await;
+            }
+        }
+
+        Ok(true)
     }
 
-    async fn load_channel_details(
+    async fn is_handle_blocked(
         &self,
-        channel_id: &String,
-    ) -> Result<YoutubeStatisticsItem, Result<(), Error>> {
-        let channel_details_result = self.youtube_service.get_channel_details(channel_id).await;
-        let channel_details = match channel_details_result {
-            Ok(channel_details) => channel_details,
-            Err(err) => {
-                error!("Failed to get channel details for {}: {}", channel_id, err);
+        channel: &YoutubeStatisticsItem,
+    ) -> Result<bool, CrawlerError> {
+        match get_handle(channel) {
+            Some(handle) => Ok(self.blocklist_repo.is_blocked(handle).await?),
+            None => Ok(false),
+        }
+    }
 
-                self.channel_repo
-                    .set_scrape_error(channel_id, err.to_string())
-                    .await;
+    async fn is_handle_opted_out(
+        &self,
+        channel: &YoutubeStatisticsItem,
+    ) -> Result<bool, CrawlerError> {
+        match get_handle(channel) {
+            Some(handle) => Ok(self.opt_out_repo.is_opted_out(handle).await?),
+            None => Ok(false),
+        }
+    }
+
+    /// New channels looking like a blocked channel go to the review queue instead of being
+    /// stored, and are accepted once the review is approved. The video titles are left to the
+    /// ban evasion job, new channels have no videos stored yet.
+    async fn is_held_for_review(
+        &self,
+        channel_id: &str,
+        title: &str,
+        description: &str,
+        avatar_hash: Option<u64>,
+    ) -> Result<bool, CrawlerError> {
+        let review_queue_repo = match &self.review_queue_repo {
+            Some(review_queue_repo) => review_queue_repo,
+            None => return Ok(false),
+        };
+
+        match review_queue_repo.get_status(channel_id).await?.as_deref() {
+            Some(REVIEW_STATUS_APPROVED) => return Ok(false),
+            Some(status) => {
+                info!("Skip channel {}, its review is {}", channel_id, status);
+                return Ok(true);
+            }
+            None => {}
+        }
+
+        let blocked = self.blocklist_repo.get_fingerprints().await?;
+        let fingerprint = get_channel_fingerprint(title, avatar_hash, &[]);
+        let ban_evasion = match find_ban_evasion(&fingerprint, &blocked) {
+            Some(ban_evasion) => ban_evasion,
+            None => return Ok(false),
+        };
+
+        info!(
+            "Hold back channel {} for review, it likely evades the ban of {} by {:?}",
+            channel_id, ban_evasion.blocked_channel, ban_evasion.signals
+        );
+        review_queue_repo
+            .insert_pending(
+                channel_id,
+                get_ban_evasion_review_document(title, description, &ban_evasion),
+            )
+            .await?;
+
+        Ok(true)
+    }
+
+    /// New channels start on probation, stored ones stay on it until the probation job decides
+    /// them.
+    async fn is_on_probation(
+        &self,
+        channel_id: &str,
+        stored_channel: Option<&Document>,
+    ) -> Result<bool, CrawlerError> {
+        let probation_repo = match &self.probation_repo {
+            Some(probation_repo) => probation_repo,
+            None => return Ok(false),
+        };
 
-                return Err(Ok(()));
+        match stored_channel {
+            Some(stored_channel) => {
+                Ok(stored_channel.get_str("status").ok() == Some(CHANNEL_STATUS_PROBATION))
+            }
+            None => {
+                probation_repo.start(channel_id).await?;
+                Ok(true)
+            }
+        }
+    }
+
+    /// An image that fails to load keeps its stored hash.
+    async fn get_image_hash(&self, channel_id: &str, url: &str) -> Option<u64> {
+        let response = self
+            .http_client
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+
+        let bytes = match response {
+            Ok(response) => response.bytes().await.ok()?,
+            Err(e) => {
+                warn!(
+                    "Failed to load image {} of channel {}: {}",
+                    url, channel_id, e
+                );
+                return None;
             }
         };
 
-        Ok(channel_details)
+        get_avatar_hash(&bytes)
+    }
+
+    async fn resolve_channel_id(&self, channel_id: String) -> Result<String, CrawlerError> {
+        if !channel_id.starts_with('@') {
+            return self.channel_redirect_service.resolve(&channel_id).await;
+        }
+
+        if let Some(known_channel_id) = self.channel_repo.get_id_by_handle(&channel_id).await? {
+            return self
+                .channel_redirect_service
+                .resolve(&known_channel_id)
+                .await;
+        }
+
+        match self.youtube_service.resolve_handle(&channel_id).await? {
+            Some(resolved_channel_id) => {
+                info!("Resolved handle {} to {}", channel_id, resolved_channel_id);
+                Ok(self
+                    .channel_redirect_service
+                    .resolve(&resolved_channel_id)
+                    .await?)
+            }
+            None => Err(CrawlerError::not_found(format!(
+                "No channel found for handle {}",
+                channel_id
+            ))),
+        }
     }
 
-    async fn delete_channel(&self, channel_id: &str) -> Result<(), Error> {
+    async fn load_channel_details(
+        &self,
+        channel_id: &str,
+    ) -> Result<YoutubeStatisticsItem, CrawlerError> {
+        let channel_details_result = self.youtube_service.get_channel_details(channel_id).await;
+
+        if let Err(err) = &channel_details_result {
+            self.channel_repo
+                .set_scrape_error(channel_id, err.to_string())
+                .await;
+        }
+
+        channel_details_result
+    }
+
+    async fn delete_channel(&self, channel_id: &str) -> Result<(), CrawlerError> {
         self.channel_repo.delete(channel_id).await?;
         self.view_repo.delete_by_channel(channel_id).await?;
         self.subscriber_repo.delete_by_channel(channel_id).await?;
@@ -174,7 +526,7 @@ impl ChannelScraper {
             "tr",
         ];
 
-        if language_detected == false && text.len() > 0 {
+        if !language_detected && !text.is_empty() {
             match detect(text) {
                 Some(language) => {
                     let lang_code = &language.lang().code()[..2];
@@ -210,7 +562,7 @@ impl ChannelScraper {
                     "month": now.month(),
                     "day": now.day(),
                     "date": mongodb::bson::DateTime::from_millis(
-                        now.timestamp_millis() as i64
+                        now.timestamp_millis()
                     ),
                     "views": view_count
                 },
@@ -219,7 +571,12 @@ impl ChannelScraper {
             .expect("Failed to upsert view count");
     }
 
-    async fn store_subscriber_count(&self, channel_id: &str, subscriber_count: i64) {
+    async fn store_subscriber_count(
+        &self,
+        channel_id: &str,
+        subscriber_count: i64,
+        reconciled_subscriber_count: i64,
+    ) {
         let now = Utc::now();
 
         self.subscriber_repo
@@ -233,9 +590,10 @@ impl ChannelScraper {
                     "month": now.month(),
                     "day": now.day(),
                     "date": mongodb::bson::DateTime::from_millis(
-                        now.timestamp_millis() as i64
+                        now.timestamp_millis()
                     ),
-                    "subscribers": subscriber_count
+                    "subscribers": subscriber_count,
+                    "subscribersReconciled": reconciled_subscriber_count
                 },
             )
             .await
@@ -1,2 +1,4 @@
+pub mod about_scraper;
+pub mod caption_scraper;
 pub mod channel_scraper;
 pub mod video_scraper;
@@ -1,79 +1,802 @@
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 
-use anyhow::{anyhow, Error};
 use chrono::{DateTime, FixedOffset, Utc};
-use log::info;
+use log::{info, warn};
 use mongodb::bson::{doc, Document};
-use quick_xml::de::from_str;
+use reqwest::{
+    header::{
+        HeaderMap, HeaderValue, InvalidHeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH,
+        LAST_MODIFIED, RETRY_AFTER,
+    },
+    Response, StatusCode,
+};
 
 use crate::{
-    models::youtube_video_feed_response::{Entry, YoutubeVideoFeedResponse},
-    repos::{channel_repo::ChannelRepository, video_repo::VideoRepository},
+    classifiers::video_type_classifier::VideoTypeClassifier,
+    errors::crawler_error::CrawlerError,
+    metrics::metrics_registry::MetricsRegistry,
+    models::{
+        config::ScrapePolicy,
+        feed_state::FeedState,
+        scrape_summary::ScrapeSummary,
+        youtube_video_details::YouTubeVideoItem,
+        youtube_video_feed_response::{Entry, YoutubeVideoFeedResponse},
+    },
+    repos::{
+        channel_store::ChannelStore, channel_summary_store::ChannelSummaryStore,
+        video_store::VideoStore,
+    },
+    services::{
+        channel_redirect_service::ChannelRedirectService,
+        collaboration_service::CollaborationService,
+        gear_extraction_service::GearExtractionService,
+        song_recognition_service::SongRecognitionService,
+        tag_analytics_service::TagAnalyticsService, url_resolver_service::UrlResolverService,
+        youtube_service::YoutubeService,
+    },
+    utils::{
+        channel_summary_utils::{
+            get_channel_summary_document, get_latest_video_ids, get_views_delta, TOP_TAG_COUNT,
+        },
+        chapter_parser::ChapterParser,
+        consts::{
+            CHANNEL_LIFECYCLE_ACTIVE, CHANNEL_STATUS_PROBATION, DATA_SOURCE_YOUTUBE_DATA_API,
+            DATA_SOURCE_YOUTUBE_FEED,
+        },
+        date_utils::parse_first_timestamp,
+        feed_utils::{
+            get_canonical_channel_id, get_feed_from_playlist_items, hash_feed_entries,
+            parse_video_feed,
+        },
+        link_utils::{detect_resource_links, expand_urls, RESOURCE_TYPE_TAB},
+        proxy_pool::ProxyPool,
+        rate_limiter::RateLimiter,
+        schedule_utils::{
+            compute_failure_backoff, compute_lifecycle, compute_next_scrape_at,
+            compute_upload_interval, deprioritize_by_lifecycle, due_view_snapshots,
+            get_video_update_threshold, next_view_snapshot_at,
+        },
+        throttle::Throttle,
+        upload_pattern_utils::next_upload_slot_poll_at,
+    },
 };
 
-const YOUTUBE_VIDEO_FEED_BASE_URL: &str = "https://www.youtube.com/feeds/videos.xml";
-const ONE_HOUR_IN_SECONDS: i64 = 3600;
-const ONE_DAY_IN_SECONDS: i64 = 86400;
-const ONE_WEEK_IN_SECONDS: i64 = 604800;
+// Number of recent uploads the upload cadence is computed from
+const UPLOAD_HISTORY_SIZE: i64 = 20;
+const FEED_MAX_ATTEMPTS: u32 = 5;
+
+const FEED_FALLBACK_REASON_ERROR: &str = "error";
+const FEED_FALLBACK_REASON_EMPTY: &str = "empty";
+
+/// Bounds the time shortened links can hold up a scrape, the others are resolved by later scrapes
+const MAX_URL_REQUESTS_PER_SCRAPE: usize = 5;
+
+/// Channels on probation only get as many latest videos as the RSS feed lists.
+const PROBATION_VIDEO_COUNT: usize = 15;
+
+/// Channels without uploads keep an empty feed, their playlist is checked again after a week.
+const EMPTY_PLAYLIST_RECHECK_SECONDS: i64 = 7 * 24 * 3600;
+
+const PUBLISHED_SOURCE_FEED: &str = "published";
+const PUBLISHED_SOURCE_FEED_UPDATED: &str = "updated";
+const PUBLISHED_SOURCE_API: &str = "api";
 
 pub struct VideoScraper {
-    video_repo: VideoRepository,
-    channel_repo: ChannelRepository,
+    video_repo: Box<dyn VideoStore>,
+    channel_repo: Box<dyn ChannelStore>,
+    youtube_service: YoutubeService,
+    tag_analytics_service: TagAnalyticsService,
+    channel_summary_repo: Box<dyn ChannelSummaryStore>,
+    channel_redirect_service: ChannelRedirectService,
+    collaboration_service: CollaborationService,
+    song_recognition_service: Arc<SongRecognitionService>,
+    gear_extraction_service: GearExtractionService,
+    video_type_classifier: Arc<dyn VideoTypeClassifier>,
+    feed_throttle: Arc<Throttle>,
+    feed_rate_limiter: Arc<RateLimiter>,
+    feed_proxy_pool: Arc<ProxyPool>,
+    feed_base_url: String,
+    metrics: Arc<MetricsRegistry>,
+    scrape_policy: ScrapePolicy,
+    url_resolver_service: Option<Arc<UrlResolverService>>,
 }
 
 impl VideoScraper {
-    pub fn new(video_repo: VideoRepository, channel_repo: ChannelRepository) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        video_repo: Box<dyn VideoStore>,
+        channel_repo: Box<dyn ChannelStore>,
+        youtube_service: YoutubeService,
+        tag_analytics_service: TagAnalyticsService,
+        channel_summary_repo: Box<dyn ChannelSummaryStore>,
+        channel_redirect_service: ChannelRedirectService,
+        collaboration_service: CollaborationService,
+        song_recognition_service: Arc<SongRecognitionService>,
+        gear_extraction_service: GearExtractionService,
+        video_type_classifier: Arc<dyn VideoTypeClassifier>,
+        feed_throttle: Arc<Throttle>,
+        feed_rate_limiter: Arc<RateLimiter>,
+        feed_proxy_pool: Arc<ProxyPool>,
+        feed_base_url: String,
+        metrics: Arc<MetricsRegistry>,
+        scrape_policy: ScrapePolicy,
+        url_resolver_service: Option<Arc<UrlResolverService>>,
+    ) -> Self {
         Self {
             video_repo,
             channel_repo,
+            youtube_service,
+            tag_analytics_service,
+            channel_summary_repo,
+            channel_redirect_service,
+            collaboration_service,
+            song_recognition_service,
+            gear_extraction_service,
+            video_type_classifier,
+            feed_throttle,
+            feed_rate_limiter,
+            feed_proxy_pool,
+            feed_base_url,
+            metrics,
+            scrape_policy,
+            url_resolver_service,
+        }
+    }
+
+    /// Entries that fail end up in the summary, only errors of the whole channel abort the scrape.
+    /// Channels whose scrapes fail are tried again later with every failure in a row.
+    pub async fn scrape(&self, channel_id: String) -> Result<ScrapeSummary, CrawlerError> {
+        let result = self.scrape_channel(&channel_id).await;
+
+        if let Err(e) = &result {
+            if let Err(schedule_error) = self.back_off_scrape(&channel_id).await {
+                warn!(
+                    "Failed to back off scrape of channel {} after {}: {}",
+                    channel_id, e, schedule_error
+                );
+            }
+        }
+
+        result
+    }
+
+    async fn is_on_probation(&self, channel_id: &str) -> Result<bool, CrawlerError> {
+        Ok(self
+            .channel_repo
+            .find_by_id(channel_id)
+            .await?
+            .is_some_and(|channel| {
+                channel.get_str("status").ok() == Some(CHANNEL_STATUS_PROBATION)
+            }))
+    }
+
+    async fn back_off_scrape(&self, channel_id: &str) -> Result<(), CrawlerError> {
+        let failures = self
+            .channel_repo
+            .find_by_id(channel_id)
+            .await?
+            .and_then(|channel| channel.get_i64("scrapeFailures").ok())
+            .unwrap_or(0)
+            + 1;
+        let next_scrape_at = compute_failure_backoff(Utc::now().timestamp(), failures);
+
+        self.channel_repo
+            .set_scrape_failure(channel_id, failures, next_scrape_at)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn scrape_channel(&self, channel_id: &str) -> Result<ScrapeSummary, CrawlerError> {
+        let channel_id = channel_id.to_string();
+        self.feed_throttle.wait().await;
+
+        let units_spent = self.youtube_service.units_spent();
+        let mut summary = ScrapeSummary::default();
+
+        let feed_state = self.channel_repo.get_feed_state(&channel_id).await?;
+        let mut prefetched_details = HashMap::new();
+        let feed = match load_and_parse_video_feed(
+            &self.feed_rate_limiter,
+            &self.feed_proxy_pool,
+            &self.feed_base_url,
+            &channel_id,
+            &feed_state,
+        )
+        .await
+        {
+            Ok(Some((channel_feed, new_feed_state)))
+                if channel_feed.entries.is_empty()
+                    && is_empty_playlist_known(&feed_state, Utc::now().timestamp()) =>
+            {
+                info!(
+                    "Feed and uploads playlist of channel {} are empty",
+                    channel_id
+                );
+                Some((
+                    channel_feed,
+                    FeedState {
+                        empty_playlist_checked_at: feed_state.empty_playlist_checked_at,
+                        ..new_feed_state
+                    },
+                ))
+            }
+            Ok(Some((channel_feed, _))) if channel_feed.entries.is_empty() => {
+                info!("Feed of channel {} is empty", channel_id);
+                let (feed, details_lookup) = self
+                    .load_uploads_playlist_feed(&channel_id, FEED_FALLBACK_REASON_EMPTY)
+                    .await?;
+                prefetched_details = details_lookup;
+                Some(feed)
+            }
+            Ok(feed) => feed,
+            Err(e) => {
+                warn!("Failed to load feed of channel {}: {}", channel_id, e);
+                let (feed, details_lookup) = self
+                    .load_uploads_playlist_feed(&channel_id, FEED_FALLBACK_REASON_ERROR)
+                    .await?;
+                prefetched_details = details_lookup;
+                Some(feed)
+            }
+        };
+        self.metrics
+            .increment_counter("video_feed_scrapes_total", &[]);
+
+        let (mut channel_feed, mut new_feed_state) = match feed {
+            Some(feed) => feed,
+            None => {
+                info!("Feed of channel {} not modified", channel_id);
+                self.update_scrape_schedule(&channel_id, 0).await?;
+
+                return Ok(summary);
+            }
+        };
+
+        if let Some(canonical_channel_id) =
+            get_canonical_channel_id(&channel_id, &channel_feed.entries)
+        {
+            self.channel_redirect_service
+                .redirect(&channel_id, &canonical_channel_id, true)
+                .await?;
+
+            return Ok(summary);
+        }
+
+        let on_probation = self.is_on_probation(&channel_id).await?;
+        if on_probation {
+            channel_feed.entries.truncate(PROBATION_VIDEO_COUNT);
+        }
+
+        new_feed_state.content_hash = Some(hash_feed_entries(&channel_feed.entries));
+        new_feed_state.video_ids = channel_feed
+            .entries
+            .iter()
+            .map(|entry| entry.video_id.clone())
+            .collect();
+
+        // Unchanged entries only get the refreshes due by the scrape policy
+        let unchanged = new_feed_state.content_hash == feed_state.content_hash;
+        let entries = if unchanged {
+            info!("Feed entries of channel {} unchanged", channel_id);
+            get_feed_dated_entries(&channel_feed.entries, &mut summary)
+        } else {
+            let new_video_count = new_feed_state
+                .video_ids
+                .iter()
+                .filter(|video_id| !feed_state.video_ids.contains(video_id))
+                .count();
+            info!(
+                "Feed of channel {} changed, {} new videos",
+                channel_id, new_video_count
+            );
+            self.get_dated_entries(&channel_feed.entries, &mut summary)
+                .await
+        };
+
+        let max_last_upload_timestamp = entries
+            .iter()
+            .map(|(_, published)| published.timestamp())
+            .max()
+            .unwrap_or(0);
+
+        let views_delta = self
+            .update_videos(&channel_id, &entries, prefetched_details, &mut summary)
+            .await?;
+
+        // The view history starts once a channel passed its probation
+        if !on_probation {
+            self.store_view_snapshots(&entries).await;
+        }
+
+        if unchanged && summary.updated == 0 {
+            self.update_scrape_schedule(&channel_id, max_last_upload_timestamp)
+                .await?;
+        } else {
+            let video_count = self
+                .update_channel_video_stats(&channel_id, max_last_upload_timestamp)
+                .await?;
+
+            let upload_interval = self
+                .update_scrape_schedule(&channel_id, max_last_upload_timestamp)
+                .await?;
+
+            self.update_channel_summary(
+                &channel_id,
+                &entries,
+                video_count,
+                upload_interval,
+                views_delta,
+            )
+            .await;
+        }
+
+        // Without the hash the next scrape retries the failed entries
+        if !summary.failed.is_empty() {
+            new_feed_state.content_hash = None;
+        }
+
+        self.channel_repo
+            .set_feed_state(&channel_id, &new_feed_state)
+            .await?;
+
+        summary.api_units = self.youtube_service.units_spent() - units_spent;
+
+        Ok(summary)
+    }
+
+    /// Loads the latest page of the uploads playlist in place of the RSS feed, along with the
+    /// details of its videos. The feed state starts over, so the next scrape requests the RSS
+    /// feed unconditionally again.
+    async fn load_uploads_playlist_feed(
+        &self,
+        channel_id: &str,
+        reason: &str,
+    ) -> Result<
+        (
+            (YoutubeVideoFeedResponse, FeedState),
+            HashMap<String, YouTubeVideoItem>,
+        ),
+        CrawlerError,
+    > {
+        self.metrics
+            .increment_counter("video_feed_fallbacks_total", &[("reason", reason)]);
+
+        let uploads_playlist_id = match channel_id.strip_prefix("UC") {
+            Some(channel_suffix) => format!("UU{}", channel_suffix),
+            None => {
+                return Err(CrawlerError::FeedError(format!(
+                    "No uploads playlist for channel id {}",
+                    channel_id
+                )))
+            }
+        };
+
+        info!(
+            "Load uploads playlist of channel {} in place of the feed",
+            channel_id
+        );
+        let page = self
+            .youtube_service
+            .get_playlist_items_page(&uploads_playlist_id, None)
+            .await?;
+
+        if page.items.is_empty() {
+            let feed_state = FeedState {
+                empty_playlist_checked_at: Some(Utc::now().timestamp()),
+                ..FeedState::default()
+            };
+
+            return Ok((
+                (
+                    get_feed_from_playlist_items(&[], &HashMap::new()),
+                    feed_state,
+                ),
+                HashMap::new(),
+            ));
         }
+
+        let video_ids = page
+            .items
+            .iter()
+            .map(|item| item.content_details.video_id.clone())
+            .collect::<Vec<String>>();
+        let details_lookup = self
+            .youtube_service
+            .get_video_details(&video_ids)
+            .await?
+            .into_iter()
+            .map(|item| (item.id.clone(), item))
+            .collect::<HashMap<String, YouTubeVideoItem>>();
+
+        Ok((
+            (
+                get_feed_from_playlist_items(&page.items, &details_lookup),
+                FeedState::default(),
+            ),
+            details_lookup,
+        ))
     }
 
-    pub async fn scrape(&self, channel_id: String) -> Result<(), Error> {
-        let channel_feed = load_and_parse_video_feed(&channel_id).await?;
-        let updated_lookup = self.video_repo.get_updated_lookup(&channel_id).await?;
+    /// Pairs the entries with their published date, falling back to the `updated` date of the
+    /// entry and then to `publishedAt` from the api. Entries without any valid date fail.
+    async fn get_dated_entries<'a>(
+        &self,
+        entries: &'a [Entry],
+        summary: &mut ScrapeSummary,
+    ) -> Vec<(&'a Entry, DateTime<FixedOffset>)> {
+        let now = Utc::now().timestamp();
+
+        let mut dated_entries = entries
+            .iter()
+            .map(|entry| {
+                let candidates = [
+                    (PUBLISHED_SOURCE_FEED, entry.published.as_str()),
+                    (PUBLISHED_SOURCE_FEED_UPDATED, entry.updated.as_str()),
+                ];
+                (entry, parse_first_timestamp(&candidates, now))
+            })
+            .collect::<Vec<_>>();
+
+        let undated_video_ids = dated_entries
+            .iter()
+            .filter(|(_, published)| published.is_none())
+            .map(|(entry, _)| entry.video_id.clone())
+            .collect::<Vec<String>>();
 
-        let mut max_last_upload_timestamp: i64 = 0;
+        if !undated_video_ids.is_empty() {
+            let api_published_lookup = match self
+                .youtube_service
+                .get_video_details(&undated_video_ids)
+                .await
+            {
+                Ok(items) => items
+                    .into_iter()
+                    .filter_map(|item| Some((item.id, item.snippet?.published_at?)))
+                    .collect::<HashMap<String, DateTime<Utc>>>(),
+                Err(e) => {
+                    warn!("Failed to load published dates: {}", e);
+                    HashMap::new()
+                }
+            };
 
-        for entry in channel_feed.entries.iter() {
-            let published = DateTime::parse_from_rfc3339(&entry.published)?;
-            if published.timestamp() > max_last_upload_timestamp {
-                max_last_upload_timestamp = published.timestamp();
+            for (entry, published) in dated_entries.iter_mut() {
+                if published.is_none() {
+                    *published = api_published_lookup
+                        .get(&entry.video_id)
+                        .map(|api_published| {
+                            (DateTime::from(*api_published), PUBLISHED_SOURCE_API)
+                        });
+                }
             }
+        }
+
+        let mut result = vec![];
+
+        for (entry, published) in dated_entries {
+            match published {
+                Some((published, PUBLISHED_SOURCE_FEED)) => result.push((entry, published)),
+                Some((published, source)) => {
+                    warn!(
+                        "Invalid published date {:?} of video {}, using the {} date",
+                        entry.published, entry.video_id, source
+                    );
+                    self.metrics.increment_counter(
+                        "video_published_fallbacks_total",
+                        &[("source", source)],
+                    );
+                    result.push((entry, published));
+                }
+                None => summary.failed.push((
+                    entry.video_id.clone(),
+                    format!("Invalid published date {:?}", entry.published),
+                )),
+            }
+        }
+
+        result
+    }
+
+    /// Returns the change of the channel's total views by the updated videos, none when their
+    /// previous views could not be read. Details in `prefetched_details` are not loaded again.
+    async fn update_videos(
+        &self,
+        channel_id: &str,
+        entries: &[(&Entry, DateTime<FixedOffset>)],
+        prefetched_details: HashMap<String, YouTubeVideoItem>,
+        summary: &mut ScrapeSummary,
+    ) -> Result<Option<i64>, CrawlerError> {
+        let video_ids = entries
+            .iter()
+            .map(|(entry, _)| entry.video_id.clone())
+            .collect::<Vec<String>>();
+        let updated_lookup = self
+            .video_repo
+            .get_updated_lookup_by_ids(channel_id, &video_ids)
+            .await?;
 
-            let should_update = should_update_video(&updated_lookup, entry, published);
+        let mut entries_to_update = vec![];
+
+        for (entry, published) in entries.iter().copied() {
+            let should_update =
+                should_update_video(&self.scrape_policy, &updated_lookup, entry, published);
             if !should_update {
+                summary.skipped += 1;
                 continue;
             }
 
-            let vid = self.build_video_document(&channel_id, &entry, published);
+            entries_to_update.push((entry, published));
+        }
+
+        let ids_to_update = entries_to_update
+            .iter()
+            .map(|(entry, _)| entry.video_id.clone())
+            .collect::<Vec<String>>();
+        let previous_views = match self
+            .video_repo
+            .get_views_by_ids(channel_id, &ids_to_update)
+            .await
+        {
+            Ok(previous_views) => Some(previous_views),
+            Err(e) => {
+                warn!("Failed to get views of channel {}: {}", channel_id, e);
+                None
+            }
+        };
+        let mut stored_views = vec![];
+
+        let entries_without_details = entries_to_update
+            .iter()
+            .copied()
+            .filter(|(entry, _)| !prefetched_details.contains_key(&entry.video_id))
+            .collect::<Vec<_>>();
+        let mut details_lookup = self.load_video_details(&entries_without_details).await;
+        details_lookup.extend(prefetched_details);
+
+        let mut url_request_budget = MAX_URL_REQUESTS_PER_SCRAPE;
+        for (entry, published) in entries_to_update {
+            let details = details_lookup.get(&entry.video_id);
+            let resolved_urls = match &self.url_resolver_service {
+                Some(url_resolver_service) => {
+                    url_resolver_service
+                        .resolve_all_within(&entry.group.description, &mut url_request_budget)
+                        .await
+                }
+                None => HashMap::new(),
+            };
+            let vid =
+                self.build_video_document(channel_id, entry, published, details, &resolved_urls);
 
             info!("Updating video {}", entry.video_id);
-            self.video_repo.upsert(&entry.video_id, vid).await?;
+            if let Err(e) = self.video_repo.upsert(&entry.video_id, vid).await {
+                summary.failed.push((entry.video_id.clone(), e.to_string()));
+                continue;
+            }
+            summary.updated += 1;
+            if let Some(views) = entry.group.community.statistics.views {
+                stored_views.push((entry.video_id.clone(), views));
+            }
+
+            // The first crawl of a channel stores its old uploads, which would skew the latency
+            if !updated_lookup.is_empty() && !updated_lookup.contains_key(&entry.video_id) {
+                self.record_index_latency(channel_id, published).await;
+            }
+
+            if let Err(e) = self
+                .collaboration_service
+                .record_mentions(
+                    channel_id,
+                    &entry.video_id,
+                    &entry.title,
+                    &entry.group.description,
+                )
+                .await
+            {
+                warn!(
+                    "Failed to record mentions of video {}: {}",
+                    entry.video_id, e
+                );
+            }
         }
 
-        self.update_channel_video_stats(&channel_id, max_last_upload_timestamp)
+        if summary.updated > 0 {
+            match self.tag_analytics_service.update_profile(channel_id).await {
+                Ok(profile) => {
+                    let top_tags = profile
+                        .keywords
+                        .iter()
+                        .take(TOP_TAG_COUNT)
+                        .map(|keyword| keyword.tag.clone())
+                        .collect::<Vec<String>>();
+
+                    if let Err(e) = self
+                        .channel_summary_repo
+                        .update(channel_id, doc! {"topTags": top_tags})
+                        .await
+                    {
+                        warn!(
+                            "Failed to update top tags of channel summary {}: {}",
+                            channel_id, e
+                        );
+                    }
+                }
+                Err(e) => warn!(
+                    "Failed to update tag profile of channel {}: {}",
+                    channel_id, e
+                ),
+            }
+
+            if let Err(e) = self
+                .gear_extraction_service
+                .update_channel_gear(channel_id)
+                .await
+            {
+                warn!("Failed to update gear of channel {}: {}", channel_id, e);
+            }
+        }
+
+        Ok(previous_views.map(|previous_views| get_views_delta(&previous_views, &stored_views)))
+    }
+
+    /// A latency that fails to store is missing from the channel summary only.
+    async fn record_index_latency(&self, channel_id: &str, published: DateTime<FixedOffset>) {
+        let seconds = (Utc::now().timestamp() - published.timestamp()).max(0);
+
+        self.metrics
+            .observe_seconds("video_index_latency_seconds", &[], seconds as f64);
+
+        if let Err(e) = self
+            .channel_summary_repo
+            .record_index_latency(channel_id, seconds)
+            .await
+        {
+            warn!(
+                "Failed to record index latency of channel {}: {}",
+                channel_id, e
+            );
+        }
+    }
+
+    /// A snapshot that fails to store is missing from the view history of its video only.
+    async fn store_view_snapshots(&self, entries: &[(&Entry, DateTime<FixedOffset>)]) {
+        let now = Utc::now().timestamp();
+
+        for (entry, published) in entries {
+            let views = match entry.group.community.statistics.views {
+                Some(views) => views,
+                None => continue,
+            };
+
+            for field in due_view_snapshots(now, published.timestamp()) {
+                if let Err(e) = self
+                    .video_repo
+                    .set_view_snapshot(&entry.video_id, field, views)
+                    .await
+                {
+                    warn!(
+                        "Failed to store view snapshot {} of video {}: {}",
+                        field, entry.video_id, e
+                    );
+                }
+            }
+        }
+    }
+
+    async fn update_scrape_schedule(
+        &self,
+        channel_id: &str,
+        last_upload_timestamp: i64,
+    ) -> Result<Option<i64>, CrawlerError> {
+        let published_timestamps = self
+            .video_repo
+            .get_published_timestamps(channel_id, UPLOAD_HISTORY_SIZE)
             .await?;
 
-        Ok(())
+        let now = Utc::now().timestamp();
+        // An unchanged feed has no upload newer than the stored videos
+        let last_upload_timestamp =
+            last_upload_timestamp.max(published_timestamps.first().copied().unwrap_or(0));
+        let upload_interval = compute_upload_interval(&published_timestamps);
+        let lifecycle = compute_lifecycle(now, Some(last_upload_timestamp), upload_interval);
+        let mut next_scrape_at = deprioritize_by_lifecycle(
+            now,
+            compute_next_scrape_at(now, last_upload_timestamp, upload_interval),
+            lifecycle,
+        );
+
+        // Patterns of channels that stopped uploading are stale
+        if lifecycle == Some(CHANNEL_LIFECYCLE_ACTIVE) {
+            if let Some(pattern) = self.channel_repo.get_upload_pattern(channel_id).await? {
+                if let Some(slot_poll_at) = next_upload_slot_poll_at(now, &pattern) {
+                    next_scrape_at = next_scrape_at.min(slot_poll_at);
+                }
+            }
+        }
+
+        if let Some(snapshot_at) = next_view_snapshot_at(now, &published_timestamps) {
+            next_scrape_at = next_scrape_at.min(snapshot_at);
+        }
+
+        self.channel_repo
+            .set_scrape_schedule(channel_id, next_scrape_at, upload_interval)
+            .await?;
+
+        Ok(upload_interval)
     }
 
     async fn update_channel_video_stats(
         &self,
         channel_id: &str,
         max_last_upload_timestamp: i64,
-    ) -> Result<(), Error> {
-        let videos_per_channel = self.video_repo.count(&channel_id).await?;
+    ) -> Result<i64, CrawlerError> {
+        let videos_per_channel = self.video_repo.count(channel_id).await? as i64;
 
         self.channel_repo
-            .set_video_count_last_upload(
-                &channel_id,
-                videos_per_channel as i64,
-                max_last_upload_timestamp,
-            )
+            .set_video_count_last_upload(channel_id, videos_per_channel, max_last_upload_timestamp)
             .await;
 
-        Ok(())
+        Ok(videos_per_channel)
+    }
+
+    /// A summary that fails to store stays at its previous state until the next scrape. Without
+    /// a views delta the views keep their previous totals.
+    async fn update_channel_summary(
+        &self,
+        channel_id: &str,
+        entries: &[(&Entry, DateTime<FixedOffset>)],
+        video_count: i64,
+        upload_interval: Option<i64>,
+        views_delta: Option<i64>,
+    ) {
+        let latest_videos = entries
+            .iter()
+            .map(|(entry, published)| (entry.video_id.clone(), published.timestamp()))
+            .collect::<Vec<(String, i64)>>();
+
+        let summary = get_channel_summary_document(
+            video_count,
+            upload_interval,
+            get_latest_video_ids(&latest_videos),
+        );
+
+        let result = match views_delta {
+            Some(views_delta) => {
+                self.channel_summary_repo
+                    .update_with_views(channel_id, summary, views_delta)
+                    .await
+            }
+            None => self.channel_summary_repo.update(channel_id, summary).await,
+        };
+
+        if let Err(e) = result {
+            warn!("Failed to update summary of channel {}: {}", channel_id, e);
+        }
+    }
+
+    async fn load_video_details(
+        &self,
+        entries: &[(&Entry, DateTime<FixedOffset>)],
+    ) -> HashMap<String, YouTubeVideoItem> {
+        if entries.is_empty() {
+            return HashMap::new();
+        }
+
+        let video_ids = entries
+            .iter()
+            .map(|(entry, _)| entry.video_id.clone())
+            .collect::<Vec<String>>();
+
+        match self.youtube_service.get_video_details(&video_ids).await {
+            Ok(items) => items
+                .into_iter()
+                .map(|item| (item.id.clone(), item))
+                .collect(),
+            Err(e) => {
+                warn!("Failed to load video details: {}", e);
+                HashMap::new()
+            }
+        }
     }
 
     fn build_video_document(
@@ -81,22 +804,135 @@ impl VideoScraper {
         channel_id: &str,
         entry: &Entry,
         published: DateTime<FixedOffset>,
+        details: Option<&YouTubeVideoItem>,
+        resolved_urls: &HashMap<String, String>,
     ) -> Document {
-        let vid = doc! {
+        let mut vid = doc! {
             "_id": entry.video_id.clone(),
             "title": entry.title.clone(),
             "description": entry.group.description.clone(),
             "publishedAt": published.timestamp(),
             "updatedAt": Utc::now().timestamp(),
-            "views": entry.group.community.statistics.views,
-            "channel": channel_id.clone(),
+            "channel": channel_id,
+            "dataSource": DATA_SOURCE_YOUTUBE_FEED,
         };
 
+        // Unknown views keep the stored count
+        if let Some(views) = entry.group.community.statistics.views {
+            vid.insert("views", views);
+        }
+
+        append_video_details(&mut vid, &entry.group.description, details, resolved_urls);
+        self.song_recognition_service
+            .append_cover_of(&mut vid, &entry.title);
+        self.gear_extraction_service.append_gear(&mut vid);
+        self.video_type_classifier.append_video_type(&mut vid);
+
         vid
     }
 }
 
+/// Adds the fields derived from the description and the Data API video details. Links are mined
+/// from the description with its shortened links expanded to `resolved_urls`.
+pub fn append_video_details(
+    vid: &mut Document,
+    description: &str,
+    details: Option<&YouTubeVideoItem>,
+    resolved_urls: &HashMap<String, String>,
+) {
+    let mut resolved_links = resolved_urls
+        .iter()
+        .map(|(url, resolved_url)| doc! {"url": url, "resolvedUrl": resolved_url})
+        .collect::<Vec<Document>>();
+    resolved_links.sort_by_key(|link| link.get_str("url").unwrap_or_default().to_string());
+    vid.insert("resolvedLinks", resolved_links);
+
+    let resources = detect_resource_links(&expand_urls(description, resolved_urls));
+    let has_tabs = resources
+        .iter()
+        .any(|resource| resource.resource_type == RESOURCE_TYPE_TAB);
+
+    vid.insert(
+        "resources",
+        resources
+            .iter()
+            .map(|resource| doc! {"type": resource.resource_type, "url": &resource.url})
+            .collect::<Vec<Document>>(),
+    );
+    vid.insert("hasTabs", has_tabs);
+
+    let chapters = ChapterParser::new()
+        .parse(description)
+        .iter()
+        .map(|chapter| doc! {"startSeconds": chapter.start_seconds, "title": &chapter.title})
+        .collect::<Vec<Document>>();
+    vid.insert("chapters", chapters);
+
+    if let Some(snippet) = details.and_then(|d| d.snippet.as_ref()) {
+        vid.insert("tags", &snippet.tags);
+    }
+
+    if let Some(content_details) = details.and_then(|d| d.content_details.as_ref()) {
+        if let Some(duration) = content_details.duration {
+            vid.insert("durationSeconds", duration.num_seconds());
+        }
+
+        vid.insert("definition", content_details.definition.to_string());
+        vid.insert("hasCaption", content_details.caption == "true");
+        vid.insert("licensedContent", content_details.licensed_content);
+        // Null once a restriction is lifted, so the site embeds the video again
+        vid.insert(
+            "regionRestriction",
+            content_details
+                .region_restriction
+                .as_ref()
+                .map(|restriction| {
+                    doc! {"allowed": &restriction.allowed, "blocked": &restriction.blocked}
+                }),
+        );
+        vid.insert("detailsDataSource", DATA_SOURCE_YOUTUBE_DATA_API);
+    }
+
+    if let Some(likes) = details
+        .and_then(|d| d.statistics.as_ref())
+        .and_then(|statistics| statistics.like_count)
+    {
+        vid.insert("likes", likes);
+    }
+
+    if let Some(embeddable) = details
+        .and_then(|d| d.status.as_ref())
+        .and_then(|status| status.embeddable)
+    {
+        vid.insert("embeddable", embeddable);
+    }
+}
+
+/// Pairs the entries of an unchanged feed with their feed dates. Entries without one were dated
+/// by the api when the feed changed and are skipped instead of looking them up again.
+fn get_feed_dated_entries<'a>(
+    entries: &'a [Entry],
+    summary: &mut ScrapeSummary,
+) -> Vec<(&'a Entry, DateTime<FixedOffset>)> {
+    let now = Utc::now().timestamp();
+    let mut result = vec![];
+
+    for entry in entries {
+        let candidates = [
+            (PUBLISHED_SOURCE_FEED, entry.published.as_str()),
+            (PUBLISHED_SOURCE_FEED_UPDATED, entry.updated.as_str()),
+        ];
+        match parse_first_timestamp(&candidates, now) {
+            Some((published, _)) => result.push((entry, published)),
+            None => summary.skipped += 1,
+        }
+    }
+
+    result
+}
+
 fn should_update_video(
+    policy: &ScrapePolicy,
     updated_lookup: &HashMap<String, DateTime<Utc>>,
     entry: &Entry,
     published_at: DateTime<FixedOffset>,
@@ -104,55 +940,498 @@ fn should_update_video(
     let should_update = if !updated_lookup.contains_key(&entry.video_id) {
         true
     } else {
-        let mut uploaded_later_than_threshold = ONE_HOUR_IN_SECONDS * 3;
         let published_since_seconds = (Utc::now().timestamp() - published_at.timestamp()).abs();
-
-        if published_since_seconds >= ONE_WEEK_IN_SECONDS {
-            uploaded_later_than_threshold = ONE_DAY_IN_SECONDS;
-        }
-
-        if published_since_seconds >= 4 * ONE_WEEK_IN_SECONDS {
-            uploaded_later_than_threshold = ONE_WEEK_IN_SECONDS;
-        }
-
-        if published_since_seconds >= 6 * 4 * ONE_WEEK_IN_SECONDS {
-            uploaded_later_than_threshold = 4 * ONE_WEEK_IN_SECONDS;
-        }
+        let uploaded_later_than_threshold = get_video_update_threshold(
+            policy,
+            published_since_seconds,
+            entry.group.community.statistics.views.unwrap_or(0),
+        );
 
         let updated_at = updated_lookup.get(&entry.video_id).unwrap();
         let updated_time_diff = (Utc::now().timestamp() - updated_at.timestamp()).abs();
-        let should_update_video = updated_time_diff >= uploaded_later_than_threshold;
 
-        should_update_video
+        updated_time_diff >= uploaded_later_than_threshold
     };
 
     should_update
 }
 
-async fn load_and_parse_video_feed(channel_id: &str) -> Result<YoutubeVideoFeedResponse, Error> {
-    let feed_url = format!("{}?channel_id={}", YOUTUBE_VIDEO_FEED_BASE_URL, channel_id);
+async fn load_and_parse_video_feed(
+    rate_limiter: &RateLimiter,
+    proxy_pool: &ProxyPool,
+    feed_base_url: &str,
+    channel_id: &str,
+    feed_state: &FeedState,
+) -> Result<Option<(YoutubeVideoFeedResponse, FeedState)>, CrawlerError> {
+    let feed_url = format!("{}?channel_id={}", feed_base_url, channel_id);
+
+    let mut headers = HeaderMap::new();
+    if let Some(etag) = &feed_state.etag {
+        headers.insert(IF_NONE_MATCH, parse_header_value(etag)?);
+    }
+    if let Some(last_modified) = &feed_state.last_modified {
+        headers.insert(IF_MODIFIED_SINCE, parse_header_value(last_modified)?);
+    }
+
+    let response = get_rate_limited(rate_limiter, proxy_pool, &feed_url, headers).await?;
 
-    let response = reqwest::get(&feed_url).await?;
+    if response.status() == StatusCode::NOT_MODIFIED {
+        return Ok(None);
+    }
 
     if response.status() != 200 {
         println!("{}", feed_url);
-        return Err(anyhow!(
+        return Err(CrawlerError::FeedError(format!(
             "Youtube Video Feed Response Error: {}",
             response.status()
-        ));
+        )));
     }
 
+    let get_header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string())
+    };
+    let feed_state = FeedState {
+        etag: get_header(ETAG),
+        last_modified: get_header(LAST_MODIFIED),
+        ..FeedState::default()
+    };
+
     let xml = response
         .text()
-        .await?
-        .replace("yt:", "yt")
-        .replace("media:", "media");
+        .await
+        .map_err(|e| CrawlerError::FeedError(e.to_string()))?;
+
+    let channel_feed = parse_video_feed(&xml).map_err(|e| {
+        CrawlerError::ParseError(format!(
+            "{}, xml string length {}: {}",
+            &feed_url,
+            xml.len(),
+            e
+        ))
+    })?;
+
+    Ok(Some((channel_feed, feed_state)))
+}
+
+/// Whether the uploads playlist was found empty recently enough to skip the fallback.
+fn is_empty_playlist_known(feed_state: &FeedState, now: i64) -> bool {
+    feed_state
+        .empty_playlist_checked_at
+        .is_some_and(|checked_at| now - checked_at < EMPTY_PLAYLIST_RECHECK_SECONDS)
+}
+
+async fn get_rate_limited(
+    rate_limiter: &RateLimiter,
+    proxy_pool: &ProxyPool,
+    url: &str,
+    headers: HeaderMap,
+) -> Result<Response, CrawlerError> {
+    for attempt in 1..=FEED_MAX_ATTEMPTS {
+        rate_limiter.acquire().await;
+
+        let response = proxy_pool
+            .get(url, headers.clone())
+            .await
+            .map_err(|e| CrawlerError::FeedError(e.to_string()))?;
+
+        if response.status() != StatusCode::TOO_MANY_REQUESTS {
+            rate_limiter.reset_backoff().await;
+            return Ok(response);
+        }
+
+        let pause = rate_limiter.back_off(get_retry_after(&response)).await;
+        warn!(
+            "Feed requests are rate limited, pause for {}s (attempt {} of {})",
+            pause.as_secs(),
+            attempt,
+            FEED_MAX_ATTEMPTS
+        );
+    }
+
+    Err(CrawlerError::FeedError(format!(
+        "Youtube Video Feed still rate limited after {} attempts",
+        FEED_MAX_ATTEMPTS
+    )))
+}
+
+fn parse_header_value(value: &str) -> Result<HeaderValue, CrawlerError> {
+    value
+        .parse()
+        .map_err(|e: InvalidHeaderValue| CrawlerError::ParseError(e.to_string()))
+}
+
+fn get_retry_after(response: &Response) -> Option<Duration> {
+    let seconds = response.headers().get(RETRY_AFTER)?.to_str().ok()?;
+
+    seconds.parse().ok().map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use chrono::{Duration as ChronoDuration, Utc};
+    use mongodb::bson::doc;
+
+    use crate::{
+        classifiers::rule_based_video_type_classifier::RuleBasedVideoTypeClassifier,
+        metrics::metrics_registry::MetricsRegistry,
+        models::{
+            config::{NotificationsConfig, ScrapePolicy, SongRecognitionConfig},
+            youtube_playlist_items::YouTubePlaylistItems,
+        },
+        notifications::notification_service::NotificationService,
+        repos::store_factory::StoreFactory,
+        services::{
+            channel_redirect_service::ChannelRedirectService,
+            collaboration_service::CollaborationService,
+            gear_extraction_service::GearExtractionService,
+            song_recognition_service::SongRecognitionService,
+            tag_analytics_service::TagAnalyticsService, youtube_service::YoutubeService,
+        },
+        test_support::{
+            fake_stores::{
+                unreachable_mongo_client, FakeApiKeyStore, FakeChannelStore, FakeQuotaBreakerStore,
+                FakeVideoStore,
+            },
+            mock_youtube::{FeedVideo, MockYoutube},
+        },
+        utils::{
+            api_scheduler::{ApiCaller, ApiPriority, ApiScheduler},
+            consts::CHANNEL_STATUS_PROBATION,
+            proxy_pool::ProxyPool,
+            rate_limiter::RateLimiter,
+            throttle::Throttle,
+        },
+    };
+
+    use super::VideoScraper;
+
+    const CHANNEL_ID: &str = "UCguitar";
+
+    fn build_scraper(
+        youtube: &MockYoutube,
+        channel_store: &FakeChannelStore,
+        video_store: &FakeVideoStore,
+    ) -> VideoScraper {
+        let stores = StoreFactory::mongodb(unreachable_mongo_client(), "test");
+        let youtube_service = YoutubeService::new(
+            Box::new(FakeApiKeyStore::default()),
+            Box::new(FakeQuotaBreakerStore::default()),
+            Arc::new(ApiScheduler::new(Duration::ZERO)),
+            ApiCaller::new("videoScraper", ApiPriority::Freshness),
+            youtube.api_base_url(),
+            Arc::new(NotificationService::new(
+                &NotificationsConfig::default(),
+                None,
+            )),
+            None,
+        );
+
+        VideoScraper::new(
+            Box::new(video_store.clone()),
+            Box::new(channel_store.clone()),
+            youtube_service,
+            TagAnalyticsService::new(Box::new(video_store.clone()), stores.tag_profile_store()),
+            stores.channel_summary_store(),
+            ChannelRedirectService::new(
+                Box::new(channel_store.clone()),
+                Box::new(video_store.clone()),
+                stores.additional_channel_store(),
+            ),
+            CollaborationService::new(stores.collab_edge_store(), Box::new(channel_store.clone())),
+            Arc::new(SongRecognitionService::new(&SongRecognitionConfig::default().rules).unwrap()),
+            GearExtractionService::new(
+                Box::new(video_store.clone()),
+                Box::new(channel_store.clone()),
+            ),
+            Arc::new(RuleBasedVideoTypeClassifier::new()),
+            Arc::new(Throttle::new(Duration::ZERO)),
+            Arc::new(RateLimiter::new(100.0, 10)),
+            Arc::new(ProxyPool::new(&[]).unwrap()),
+            youtube.feed_base_url(),
+            Arc::new(MetricsRegistry::new()),
+            ScrapePolicy::default(),
+            None,
+        )
+    }
+
+    fn feed_videos() -> Vec<FeedVideo> {
+        let now = Utc::now();
+
+        vec![
+            FeedVideo::new("video1", "Blues lick lesson", now - ChronoDuration::days(1)),
+            FeedVideo::new("video2", "Jazz chords", now - ChronoDuration::days(8)),
+        ]
+    }
+
+    #[tokio::test]
+    async fn scrape_stores_feed_videos() {
+        let youtube = MockYoutube::start().await;
+        youtube
+            .mount_feed(CHANNEL_ID, &feed_videos(), "etag1")
+            .await;
+        let channel_store = FakeChannelStore::with_channels(vec![doc! {"_id": CHANNEL_ID}]);
+        let video_store = FakeVideoStore::default();
+
+        let summary = build_scraper(&youtube, &channel_store, &video_store)
+            .scrape(CHANNEL_ID.to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(summary.updated, 2);
+        assert_eq!(summary.skipped, 0);
+        assert!(summary.failed.is_empty());
+
+        let video = video_store.get("video1").unwrap();
+        assert_eq!(video.get_str("channel").unwrap(), CHANNEL_ID);
+        assert_eq!(video.get_str("title").unwrap(), "Blues lick lesson");
+        assert_eq!(video.get_str("videoType").unwrap(), "lesson");
+        assert!(video_store.get("video2").is_some());
+
+        let channel = channel_store.get(CHANNEL_ID).unwrap();
+        assert_eq!(channel.get_i64("videoCount").unwrap(), 2);
+        assert!(channel.get_i64("nextScrapeAt").is_ok());
+
+        let feed_state = channel_store.feed_states.lock().unwrap()[CHANNEL_ID].clone();
+        assert_eq!(feed_state.etag.as_deref(), Some("etag1"));
+        assert_eq!(feed_state.video_ids, vec!["video1", "video2"]);
+    }
+
+    #[tokio::test]
+    async fn scrape_of_probation_channel_keeps_latest_videos_without_view_history() {
+        let youtube = MockYoutube::start().await;
+        let now = Utc::now();
+        let videos = (0..17)
+            .map(|index| {
+                FeedVideo::new(
+                    &format!("video{}", index),
+                    "Blues lick lesson",
+                    now - ChronoDuration::hours(25 + index),
+                )
+            })
+            .collect::<Vec<FeedVideo>>();
+        youtube.mount_feed(CHANNEL_ID, &videos, "etag1").await;
+        let channel_store = FakeChannelStore::with_channels(vec![
+            doc! {"_id": CHANNEL_ID, "status": CHANNEL_STATUS_PROBATION},
+        ]);
+        let video_store = FakeVideoStore::default();
+
+        let summary = build_scraper(&youtube, &channel_store, &video_store)
+            .scrape(CHANNEL_ID.to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(summary.updated, 15);
+        assert!(video_store.get("video15").is_none());
+        assert!(video_store.get("video0").unwrap().get("views24h").is_none());
+    }
+
+    #[tokio::test]
+    async fn scrape_continues_after_failed_entry() {
+        let youtube = MockYoutube::start().await;
+        let mut videos = feed_videos();
+        videos[0].published = "yesterday".to_string();
+        videos[0].updated = "yesterday".to_string();
+        youtube.mount_feed(CHANNEL_ID, &videos, "etag1").await;
+        let channel_store = FakeChannelStore::with_channels(vec![doc! {"_id": CHANNEL_ID}]);
+        let video_store = FakeVideoStore::default();
+
+        let summary = build_scraper(&youtube, &channel_store, &video_store)
+            .scrape(CHANNEL_ID.to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(summary.updated, 1);
+        assert_eq!(summary.failed.len(), 1);
+        assert_eq!(summary.failed[0].0, "video1");
+        assert!(video_store.get("video1").is_none());
+        assert!(video_store.get("video2").is_some());
+
+        let feed_state = channel_store.feed_states.lock().unwrap()[CHANNEL_ID].clone();
+        assert_eq!(feed_state.content_hash, None);
+    }
+
+    #[tokio::test]
+    async fn scrape_falls_back_to_updated_date() {
+        let youtube = MockYoutube::start().await;
+        let mut videos = feed_videos();
+        let updated = Utc::now() - ChronoDuration::hours(2);
+        videos[0].published = "0000-00-00T00:00:00+00:00".to_string();
+        videos[0].updated = updated.to_rfc3339();
+        youtube.mount_feed(CHANNEL_ID, &videos, "etag1").await;
+        let channel_store = FakeChannelStore::with_channels(vec![doc! {"_id": CHANNEL_ID}]);
+        let video_store = FakeVideoStore::default();
+
+        let summary = build_scraper(&youtube, &channel_store, &video_store)
+            .scrape(CHANNEL_ID.to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(summary.updated, 2);
+        assert!(summary.failed.is_empty());
+        assert_eq!(
+            video_store
+                .get("video1")
+                .unwrap()
+                .get_i64("publishedAt")
+                .unwrap(),
+            updated.timestamp()
+        );
+    }
+
+    #[tokio::test]
+    async fn scrape_skips_unmodified_feed() {
+        let youtube = MockYoutube::start().await;
+        youtube.mount_feed_not_modified(CHANNEL_ID, "etag1").await;
+        youtube
+            .mount_feed(CHANNEL_ID, &feed_videos(), "etag1")
+            .await;
+        let channel_store = FakeChannelStore::with_channels(vec![doc! {"_id": CHANNEL_ID}]);
+        let video_store = FakeVideoStore::default();
+
+        let scraper = build_scraper(&youtube, &channel_store, &video_store);
+        scraper.scrape(CHANNEL_ID.to_string()).await.unwrap();
+        video_store.videos.lock().unwrap().clear();
+        scraper.scrape(CHANNEL_ID.to_string()).await.unwrap();
+
+        assert!(video_store.get("video1").is_none());
+        assert_eq!(youtube.received_feed_requests().await, 2);
+    }
+
+    #[tokio::test]
+    async fn scrape_refreshes_due_videos_of_unchanged_feed() {
+        let youtube = MockYoutube::start().await;
+        youtube
+            .mount_feed(CHANNEL_ID, &feed_videos(), "etag1")
+            .await;
+        let channel_store = FakeChannelStore::with_channels(vec![doc! {"_id": CHANNEL_ID}]);
+        let video_store = FakeVideoStore::default();
+
+        let scraper = build_scraper(&youtube, &channel_store, &video_store);
+        scraper.scrape(CHANNEL_ID.to_string()).await.unwrap();
+        let stale = (Utc::now() - ChronoDuration::days(30)).timestamp();
+        video_store
+            .videos
+            .lock()
+            .unwrap()
+            .get_mut("video1")
+            .unwrap()
+            .insert("updatedAt", stale);
+        let summary = scraper.scrape(CHANNEL_ID.to_string()).await.unwrap();
+
+        assert_eq!(summary.updated, 1);
+        assert_eq!(summary.skipped, 1);
+        assert!(
+            video_store
+                .get("video1")
+                .unwrap()
+                .get_i64("updatedAt")
+                .unwrap()
+                > stale
+        );
+    }
+
+    #[tokio::test]
+    async fn scrape_of_unchanged_feed_without_due_videos_keeps_channel_stats() {
+        let youtube = MockYoutube::start().await;
+        youtube
+            .mount_feed(CHANNEL_ID, &feed_videos(), "etag1")
+            .await;
+        let channel_store = FakeChannelStore::with_channels(vec![doc! {"_id": CHANNEL_ID}]);
+        let video_store = FakeVideoStore::default();
 
-    let channel_feed = from_str::<YoutubeVideoFeedResponse>(&xml).expect(&format!(
-        "{}, xml string length {}",
-        &feed_url,
-        xml.len()
-    ));
+        let scraper = build_scraper(&youtube, &channel_store, &video_store);
+        scraper.scrape(CHANNEL_ID.to_string()).await.unwrap();
+        channel_store
+            .channels
+            .lock()
+            .unwrap()
+            .get_mut(CHANNEL_ID)
+            .unwrap()
+            .remove("videoCount");
+        let video_requests = youtube.received_api_requests("videos").await;
+        let summary = scraper.scrape(CHANNEL_ID.to_string()).await.unwrap();
 
-    Ok(channel_feed)
+        assert_eq!(summary.updated, 0);
+        assert_eq!(summary.skipped, 2);
+        assert_eq!(
+            youtube.received_api_requests("videos").await,
+            video_requests
+        );
+        assert!(channel_store
+            .get(CHANNEL_ID)
+            .unwrap()
+            .get("videoCount")
+            .is_none());
+        assert!(channel_store
+            .get(CHANNEL_ID)
+            .unwrap()
+            .get("nextScrapeAt")
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn failed_scrape_backs_off_schedule() {
+        // Neither the feed nor the uploads playlist fallback are reachable
+        let youtube = MockYoutube::start().await;
+        let channel_store = FakeChannelStore::with_channels(vec![
+            doc! {"_id": CHANNEL_ID, "scrapeFailures": 2_i64},
+        ]);
+        let video_store = FakeVideoStore::default();
+
+        let result = build_scraper(&youtube, &channel_store, &video_store)
+            .scrape(CHANNEL_ID.to_string())
+            .await;
+
+        assert!(result.is_err());
+        let channel = channel_store.get(CHANNEL_ID).unwrap();
+        assert_eq!(channel.get_i64("scrapeFailures").unwrap(), 3);
+        assert!(channel.get_i64("nextScrapeAt").unwrap() >= Utc::now().timestamp() + 3 * 3600);
+    }
+
+    #[tokio::test]
+    async fn scrape_remembers_empty_uploads_playlist() {
+        let youtube = MockYoutube::start().await;
+        youtube.mount_feed(CHANNEL_ID, &[], "etag1").await;
+        youtube
+            .mount_uploads(CHANNEL_ID, &YouTubePlaylistItems::default())
+            .await;
+        let channel_store = FakeChannelStore::with_channels(vec![doc! {"_id": CHANNEL_ID}]);
+        let video_store = FakeVideoStore::default();
+        let scraper = build_scraper(&youtube, &channel_store, &video_store);
+
+        for _ in 0..2 {
+            scraper.scrape(CHANNEL_ID.to_string()).await.unwrap();
+        }
+
+        assert_eq!(youtube.received_api_requests("playlistItems").await, 1);
+        assert_eq!(youtube.received_api_requests("videos").await, 0);
+        let feed_state = channel_store.feed_states.lock().unwrap()[CHANNEL_ID].clone();
+        assert!(feed_state.empty_playlist_checked_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn scrape_retries_rate_limited_feed() {
+        let youtube = MockYoutube::start().await;
+        youtube.mount_feed_rate_limited(CHANNEL_ID, 2).await;
+        youtube
+            .mount_feed(CHANNEL_ID, &feed_videos(), "etag1")
+            .await;
+        let channel_store = FakeChannelStore::with_channels(vec![doc! {"_id": CHANNEL_ID}]);
+        let video_store = FakeVideoStore::default();
+
+        build_scraper(&youtube, &channel_store, &video_store)
+            .scrape(CHANNEL_ID.to_string())
+            .await
+            .unwrap();
+
+        assert!(video_store.get("video1").is_some());
+        assert_eq!(youtube.received_feed_requests().await, 3);
+    }
 }
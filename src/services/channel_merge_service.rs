@@ -0,0 +1,62 @@
+use log::info;
+
+use crate::errors::crawler_error::CrawlerError;
+use crate::repos::{
+    channel_store::ChannelStore, subscriber_store::SubscriberStore, video_store::VideoStore,
+    view_store::ViewStore,
+};
+
+/// Consolidates a duplicate channel entry into its canonical id: videos, view and subscriber
+/// history move over, and the duplicate stays as a deactivated redirect to the canonical id.
+pub struct ChannelMergeService {
+    channel_repo: Box<dyn ChannelStore>,
+    video_repo: Box<dyn VideoStore>,
+    view_repo: Box<dyn ViewStore>,
+    subscriber_repo: Box<dyn SubscriberStore>,
+}
+
+impl ChannelMergeService {
+    pub fn new(
+        channel_repo: Box<dyn ChannelStore>,
+        video_repo: Box<dyn VideoStore>,
+        view_repo: Box<dyn ViewStore>,
+        subscriber_repo: Box<dyn SubscriberStore>,
+    ) -> ChannelMergeService {
+        ChannelMergeService {
+            channel_repo,
+            video_repo,
+            view_repo,
+            subscriber_repo,
+        }
+    }
+
+    pub async fn merge(&self, channel_id: &str, canonical_id: &str) -> Result<(), CrawlerError> {
+        if channel_id == canonical_id {
+            return Ok(());
+        }
+
+        let moved_video_count = self
+            .video_repo
+            .move_to_channel(channel_id, canonical_id)
+            .await?;
+        let moved_view_count = self
+            .view_repo
+            .move_to_channel(channel_id, canonical_id)
+            .await?;
+        let moved_subscriber_count = self
+            .subscriber_repo
+            .move_to_channel(channel_id, canonical_id)
+            .await?;
+
+        self.channel_repo
+            .set_merged(channel_id, canonical_id)
+            .await?;
+
+        info!(
+            "Merged channel {} into {}, moved {} videos, {} view and {} subscriber counts",
+            channel_id, canonical_id, moved_video_count, moved_view_count, moved_subscriber_count
+        );
+
+        Ok(())
+    }
+}
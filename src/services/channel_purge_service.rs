@@ -0,0 +1,102 @@
+use log::info;
+
+use crate::{
+    errors::crawler_error::CrawlerError,
+    repos::{
+        channel_audit_store::{ChannelAuditStore, AUDIT_ACTION_PURGED},
+        channel_store::ChannelStore,
+        purge_store::PurgeStore,
+        video_store::VideoStore,
+    },
+    utils::purge_utils::{get_anonymized_channel_id, get_counts_document},
+};
+
+/// The outcome of a purge.
+pub struct Purge {
+    /// Replaces the channel id in its audit entries
+    pub anonymized_id: String,
+    /// The documents per collection as counted right before deleting them
+    pub counts: Vec<(String, u64)>,
+}
+
+/// Deletes all stored data of a channel and anonymizes its audit entries. The channel itself is
+/// deleted last, so an interrupted purge leaves it in place and can simply be run again.
+pub struct ChannelPurgeService {
+    channel_repo: Box<dyn ChannelStore>,
+    video_repo: Box<dyn VideoStore>,
+    purge_repo: Box<dyn PurgeStore>,
+    channel_audit_repo: Box<dyn ChannelAuditStore>,
+}
+
+impl ChannelPurgeService {
+    pub fn new(
+        channel_repo: Box<dyn ChannelStore>,
+        video_repo: Box<dyn VideoStore>,
+        purge_repo: Box<dyn PurgeStore>,
+        channel_audit_repo: Box<dyn ChannelAuditStore>,
+    ) -> ChannelPurgeService {
+        ChannelPurgeService {
+            channel_repo,
+            video_repo,
+            purge_repo,
+            channel_audit_repo,
+        }
+    }
+
+    /// Returns the documents a purge would delete or anonymize per collection.
+    pub async fn preview(&self, channel_id: &str) -> Result<Vec<(String, u64)>, CrawlerError> {
+        let mut counts = vec![
+            (
+                "channels".to_string(),
+                self.channel_repo.exists(channel_id).await? as u64,
+            ),
+            (
+                "videos".to_string(),
+                self.video_repo.count(channel_id).await?,
+            ),
+        ];
+        let video_ids = self.video_repo.get_ids_by_channel(channel_id).await?;
+        counts.extend(
+            self.purge_repo
+                .count_by_channel(channel_id, &video_ids)
+                .await?,
+        );
+        counts.push((
+            "channelaudit".to_string(),
+            self.purge_repo.count_audit_entries(channel_id).await?,
+        ));
+
+        Ok(counts)
+    }
+
+    pub async fn purge(&self, channel_id: &str) -> Result<Purge, CrawlerError> {
+        let counts = self.preview(channel_id).await?;
+        let anonymized_id = get_anonymized_channel_id();
+        // Read before the videos are deleted, the reports only know the videos by id
+        let video_ids = self.video_repo.get_ids_by_channel(channel_id).await?;
+
+        self.purge_repo
+            .delete_by_channel(channel_id, &video_ids)
+            .await?;
+        self.video_repo.delete_all_by_channel(channel_id).await?;
+        self.purge_repo
+            .anonymize_audit_entries(channel_id, &anonymized_id)
+            .await?;
+        self.channel_repo.delete(channel_id).await?;
+
+        self.channel_audit_repo
+            .insert(
+                &anonymized_id,
+                AUDIT_ACTION_PURGED,
+                get_counts_document(&counts),
+            )
+            .await?;
+
+        info!("Channel {} purged as {}", channel_id, anonymized_id);
+
+        Ok(Purge {
+            anonymized_id,
+            counts,
+        })
+    }
+}
@@ -0,0 +1,89 @@
+use log::{info, warn};
+
+use crate::errors::crawler_error::CrawlerError;
+use crate::repos::{
+    additional_channel_store::AdditionalChannelStore, channel_store::ChannelStore,
+    video_store::VideoStore,
+};
+
+// Bounds the lookups if redirects ever form a cycle
+const MAX_REDIRECT_HOPS: usize = 5;
+
+/// Handles channels that moved to another id. The old channel document stays as a deactivated
+/// redirect, so discovery keeps treating the old id as known and never queues it again.
+pub struct ChannelRedirectService {
+    channel_repo: Box<dyn ChannelStore>,
+    video_repo: Box<dyn VideoStore>,
+    additional_channel_repo: Box<dyn AdditionalChannelStore>,
+}
+
+impl ChannelRedirectService {
+    pub fn new(
+        channel_repo: Box<dyn ChannelStore>,
+        video_repo: Box<dyn VideoStore>,
+        additional_channel_repo: Box<dyn AdditionalChannelStore>,
+    ) -> ChannelRedirectService {
+        ChannelRedirectService {
+            channel_repo,
+            video_repo,
+            additional_channel_repo,
+        }
+    }
+
+    /// Follows stored redirects to the current id of a channel.
+    pub async fn resolve(&self, channel_id: &str) -> Result<String, CrawlerError> {
+        let mut current_id = channel_id.to_string();
+
+        for _ in 0..MAX_REDIRECT_HOPS {
+            match self.channel_repo.get_redirect(&current_id).await? {
+                Some(redirects_to) if redirects_to != current_id => current_id = redirects_to,
+                _ => return Ok(current_id),
+            }
+        }
+
+        warn!("Too many redirects for channel {}", channel_id);
+
+        Ok(current_id)
+    }
+
+    /// Points the old channel to its new id and moves its videos. With `queue_target` an unknown
+    /// target channel is added to the additional channels, so it gets scraped on the next run.
+    pub async fn redirect(
+        &self,
+        channel_id: &str,
+        target_channel_id: &str,
+        queue_target: bool,
+    ) -> Result<(), CrawlerError> {
+        if channel_id == target_channel_id {
+            return Ok(());
+        }
+
+        self.channel_repo
+            .set_redirect(channel_id, target_channel_id)
+            .await?;
+
+        let moved_video_count = self
+            .video_repo
+            .move_to_channel(channel_id, target_channel_id)
+            .await?;
+
+        info!(
+            "Channel {} redirects to {}, moved {} videos",
+            channel_id, target_channel_id, moved_video_count
+        );
+
+        if queue_target
+            && !self.channel_repo.exists(target_channel_id).await?
+            && !self
+                .additional_channel_repo
+                .exists(target_channel_id)
+                .await?
+        {
+            self.additional_channel_repo
+                .insert(target_channel_id, false)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+use log::info;
+
+use crate::{
+    errors::crawler_error::CrawlerError,
+    repos::{channel_store::ChannelStore, collab_edge_store::CollabEdgeStore},
+    utils::collaboration_utils::detect_mentions,
+};
+
+// A target counts as strongly connected when enough known channels and videos mention it
+const MIN_MENTIONING_CHANNELS: i32 = 2;
+const MIN_MENTIONING_VIDEOS: i32 = 3;
+
+/// Builds the collaboration graph from the mentions in video titles and descriptions and offers
+/// strongly connected unknown channels to the discovery.
+pub struct CollaborationService {
+    collab_edge_repo: Box<dyn CollabEdgeStore>,
+    channel_repo: Box<dyn ChannelStore>,
+}
+
+impl CollaborationService {
+    pub fn new(
+        collab_edge_repo: Box<dyn CollabEdgeStore>,
+        channel_repo: Box<dyn ChannelStore>,
+    ) -> CollaborationService {
+        CollaborationService {
+            collab_edge_repo,
+            channel_repo,
+        }
+    }
+
+    /// Handles of known channels are stored as their channel id.
+    pub async fn record_mentions(
+        &self,
+        channel_id: &str,
+        video_id: &str,
+        title: &str,
+        description: &str,
+    ) -> Result<(), CrawlerError> {
+        for mention in detect_mentions(title, description) {
+            let target = if mention.channel.starts_with('@') {
+                self.channel_repo
+                    .get_id_by_handle(&mention.channel)
+                    .await?
+                    .unwrap_or(mention.channel)
+            } else {
+                mention.channel
+            };
+
+            if target == channel_id {
+                continue;
+            }
+
+            self.collab_edge_repo
+                .add_mention(channel_id, &target, mention.kind, video_id)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns strongly connected channels that are not stored yet. Known ones are marked as
+    /// queued right away, candidates once the discovery queued them, so each is queued once.
+    pub async fn take_discovery_candidates(&self) -> Result<Vec<String>, CrawlerError> {
+        let targets = self
+            .collab_edge_repo
+            .get_strong_targets(MIN_MENTIONING_CHANNELS, MIN_MENTIONING_VIDEOS)
+            .await?;
+
+        let mut candidates = vec![];
+
+        for target in targets {
+            let is_known = if target.starts_with('@') {
+                self.channel_repo.get_id_by_handle(&target).await?.is_some()
+            } else {
+                self.channel_repo.exists(&target).await?
+            };
+
+            if is_known {
+                self.collab_edge_repo.mark_queued(&target).await?;
+            } else {
+                candidates.push(target);
+            }
+        }
+
+        info!("Found {} collaboration candidates", candidates.len());
+
+        Ok(candidates)
+    }
+
+    pub async fn mark_queued(&self, candidate: &str) -> Result<(), CrawlerError> {
+        Ok(self.collab_edge_repo.mark_queued(candidate).await?)
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use log::info;
+use tokio::sync::{mpsc::Sender, Mutex};
+
+use crate::{
+    commands::crawl_channel_command::CrawlChannelCommand,
+    errors::crawler_error::CrawlerError,
+    repos::{
+        additional_channel_store::AdditionalChannelStore, blocklist_store::BlocklistStore,
+        channel_store::ChannelStore, opt_out_store::OptOutStore,
+    },
+    utils::consts::CHANNEL_SOURCE_INGEST,
+};
+
+pub const CRAWL_REQUEST_STATUS_QUEUED: &str = "queued";
+pub const CRAWL_REQUEST_STATUS_KNOWN: &str = "known";
+pub const CRAWL_REQUEST_STATUS_DUPLICATE: &str = "duplicate";
+pub const CRAWL_REQUEST_STATUS_BLOCKED: &str = "blocked";
+pub const CRAWL_REQUEST_STATUS_INVALID: &str = "invalid";
+pub const CRAWL_REQUEST_STATUS_FAILED: &str = "failed";
+
+// Bounds the memory of requested channels, older entries are pruned first
+const MAX_RECENT_REQUESTS: usize = 10_000;
+
+/// Queues channels other services ask to crawl for the channel scraper, with the source
+/// `ingest`. Known, blocked, opted-out and recently requested channels are skipped.
+pub struct CrawlRequestService {
+    sender: Sender<CrawlChannelCommand>,
+    channel_repo: Box<dyn ChannelStore>,
+    additional_channel_repo: Box<dyn AdditionalChannelStore>,
+    blocklist_repo: Box<dyn BlocklistStore>,
+    opt_out_repo: Box<dyn OptOutStore>,
+    dedupe_window: Duration,
+    recent_requests: Mutex<HashMap<String, Instant>>,
+}
+
+impl CrawlRequestService {
+    pub fn new(
+        sender: Sender<CrawlChannelCommand>,
+        channel_repo: Box<dyn ChannelStore>,
+        additional_channel_repo: Box<dyn AdditionalChannelStore>,
+        blocklist_repo: Box<dyn BlocklistStore>,
+        opt_out_repo: Box<dyn OptOutStore>,
+        dedupe_window: Duration,
+    ) -> CrawlRequestService {
+        CrawlRequestService {
+            sender,
+            channel_repo,
+            additional_channel_repo,
+            blocklist_repo,
+            opt_out_repo,
+            dedupe_window,
+            recent_requests: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the status of the request for a channel id or `@handle`.
+    pub async fn request(&self, channel: &str) -> Result<&'static str, CrawlerError> {
+        let now = Instant::now();
+
+        if self
+            .recent_requests
+            .lock()
+            .await
+            .get(channel)
+            .is_some_and(|requested_at| now.duration_since(*requested_at) < self.dedupe_window)
+        {
+            return Ok(CRAWL_REQUEST_STATUS_DUPLICATE);
+        }
+
+        if self.blocklist_repo.is_blocked(channel).await? {
+            info!("Skip crawl request for blocked channel {}", channel);
+            return Ok(CRAWL_REQUEST_STATUS_BLOCKED);
+        }
+
+        if self.opt_out_repo.is_opted_out(channel).await? {
+            info!("Skip crawl request for opted-out channel {}", channel);
+            return Ok(CRAWL_REQUEST_STATUS_BLOCKED);
+        }
+
+        let is_known = if channel.starts_with('@') {
+            self.channel_repo.get_id_by_handle(channel).await?.is_some()
+        } else {
+            self.channel_repo.exists(channel).await?
+                || self.additional_channel_repo.exists(channel).await?
+        };
+
+        if is_known {
+            return Ok(CRAWL_REQUEST_STATUS_KNOWN);
+        }
+
+        {
+            let mut recent_requests = self.recent_requests.lock().await;
+
+            if recent_requests.len() >= MAX_RECENT_REQUESTS {
+                recent_requests.retain(|_, requested_at| {
+                    now.duration_since(*requested_at) < self.dedupe_window
+                });
+            }
+            recent_requests.insert(channel.to_string(), now);
+        }
+
+        info!("Send requested channel for crawling: {}", channel);
+
+        let cmd = CrawlChannelCommand::new(
+            channel.to_string(),
+            false,
+            Some(CHANNEL_SOURCE_INGEST.to_string()),
+        );
+
+        self.sender.send(cmd).await?;
+
+        Ok(CRAWL_REQUEST_STATUS_QUEUED)
+    }
+}
@@ -0,0 +1,179 @@
+use mongodb::bson::Document;
+use regex::Regex;
+
+use crate::{
+    errors::crawler_error::CrawlerError,
+    repos::{channel_store::ChannelStore, video_store::VideoStore},
+    utils::{
+        document_utils::get_strings,
+        gear_utils::{count_gear, GearCount, GEAR_DICTIONARY},
+    },
+};
+
+// The channel gear follows what a channel plays now, older uploads are left out
+const CHANNEL_GEAR_VIDEO_COUNT: i64 = 200;
+
+/// Finds the guitars, amps, modelers and pedals of the gear dictionary mentioned by videos and
+/// aggregates them per channel.
+pub struct GearExtractionService {
+    video_repo: Box<dyn VideoStore>,
+    channel_repo: Box<dyn ChannelStore>,
+    matchers: Vec<(&'static str, Regex)>,
+}
+
+impl GearExtractionService {
+    pub fn new(
+        video_repo: Box<dyn VideoStore>,
+        channel_repo: Box<dyn ChannelStore>,
+    ) -> GearExtractionService {
+        let matchers = GEAR_DICTIONARY
+            .iter()
+            .map(|item| {
+                let aliases = item
+                    .aliases
+                    .iter()
+                    .map(|alias| regex::escape(alias))
+                    .collect::<Vec<String>>()
+                    .join("|");
+
+                (
+                    item.name,
+                    Regex::new(&format!(r"(?i)\b(?:{})\b", aliases)).unwrap(),
+                )
+            })
+            .collect();
+
+        GearExtractionService {
+            video_repo,
+            channel_repo,
+            matchers,
+        }
+    }
+
+    /// Returns the names of the gear mentioned in the title, description or tags.
+    pub fn extract(&self, title: &str, description: &str, tags: &[String]) -> Vec<String> {
+        let text = format!("{}\n{}\n{}", title, description, tags.join("\n"));
+
+        self.matchers
+            .iter()
+            .filter(|(_, regex)| regex.is_match(&text))
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+
+    /// Stores the gear mentioned by a video document, after its tags and resolved links were
+    /// added. Shop links name the product in their path, e.g. amzn.to links.
+    pub fn append_gear(&self, video: &mut Document) {
+        let mut texts = get_strings(video, "tags");
+        if let Ok(resolved_links) = video.get_array("resolvedLinks") {
+            texts.extend(resolved_links.iter().filter_map(|link| {
+                let resolved_url = link.as_document()?.get_str("resolvedUrl").ok()?;
+                Some(resolved_url.replace(&['-', '_', '/', '+'][..], " "))
+            }));
+        }
+
+        let gear = self.extract(
+            video.get_str("title").unwrap_or_default(),
+            video.get_str("description").unwrap_or_default(),
+            &texts,
+        );
+
+        video.insert("gear", gear);
+    }
+
+    /// Rebuilds the gear of a channel from the gear of its latest videos.
+    pub async fn update_channel_gear(
+        &self,
+        channel_id: &str,
+    ) -> Result<Vec<GearCount>, CrawlerError> {
+        let videos = self
+            .video_repo
+            .get_gear(channel_id, CHANNEL_GEAR_VIDEO_COUNT)
+            .await?;
+        let gear = count_gear(&videos);
+
+        self.channel_repo.set_gear(channel_id, &gear).await?;
+
+        Ok(gear)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::doc;
+
+    use crate::test_support::fake_stores::{FakeChannelStore, FakeVideoStore};
+
+    use super::GearExtractionService;
+
+    fn build_service(
+        channel_store: &FakeChannelStore,
+        video_store: &FakeVideoStore,
+    ) -> GearExtractionService {
+        GearExtractionService::new(
+            Box::new(video_store.clone()),
+            Box::new(channel_store.clone()),
+        )
+    }
+
+    #[test]
+    fn extract_gear() {
+        let service = build_service(&FakeChannelStore::default(), &FakeVideoStore::default());
+
+        assert_eq!(
+            service.extract(
+                "Strat through a Helix",
+                "Played on my Paul Reed Smith, no more television",
+                &["axe-fx iii".to_string()]
+            ),
+            vec![
+                "Fender Stratocaster",
+                "PRS",
+                "Line 6 Helix",
+                "Fractal Axe-Fx"
+            ]
+        );
+        assert!(service.extract("Stratosphere", "", &[]).is_empty());
+
+        let mut video = doc! {
+            "title": "My rig",
+            "description": "Amp: https://amzn.to/abc",
+            "resolvedLinks": [{
+                "url": "https://amzn.to/abc",
+                "resolvedUrl": "https://amazon.com/Line-6-Helix-Floor/dp/B00",
+            }],
+        };
+        service.append_gear(&mut video);
+        assert_eq!(
+            video.get_array("gear").unwrap(),
+            &vec![mongodb::bson::Bson::from("Line 6 Helix")]
+        );
+    }
+
+    #[tokio::test]
+    async fn update_channel_gear() {
+        let channel_store = FakeChannelStore::with_channels(vec![doc! {"_id": "UCgear"}]);
+        let video_store = FakeVideoStore::default();
+        let service = build_service(&channel_store, &video_store);
+
+        for (id, title) in [("v1", "Les Paul vs Strat"), ("v2", "Les Paul tone")] {
+            let mut video = doc! {"_id": id, "channel": "UCgear", "title": title, "publishedAt": 1};
+            service.append_gear(&mut video);
+            video_store
+                .videos
+                .lock()
+                .unwrap()
+                .insert(id.to_string(), video);
+        }
+
+        service.update_channel_gear("UCgear").await.unwrap();
+
+        let gear = channel_store.get("UCgear").unwrap();
+        let gear = gear.get_array("gear").unwrap();
+        let top = gear[0].as_document().unwrap();
+        assert_eq!(gear.len(), 2);
+        assert_eq!(top.get_str("name").unwrap(), "Gibson Les Paul");
+        assert_eq!(top.get_str("brand").unwrap(), "Gibson");
+        assert_eq!(top.get_i64("videoCount").unwrap(), 2);
+    }
+}
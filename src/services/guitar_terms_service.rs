@@ -1,21 +1,39 @@
-use crate::repos::non_guitar_channel_repo::NonGuitarChannelRepository;
+use mongodb::bson::Document;
+
+use crate::models::tag_profile::TagProfile;
+use crate::repos::non_guitar_channel_store::NonGuitarChannelStore;
+use crate::utils::consts::{CLASSIFICATION_OVERRIDE_EXCLUDE, CLASSIFICATION_OVERRIDE_INCLUDE};
+use crate::utils::probation_utils::get_guitar_video_share;
 
 pub struct GuitarTermResult {
     pub has_guitar_term: bool,
     pub is_blacklisted: bool,
+    pub partial_terms: Vec<String>,
+    pub partial_score: f64,
+}
+
+impl GuitarTermResult {
+    /// The manual override of a channel wins over its guitar terms, but not over the blacklist.
+    pub fn apply_override(&mut self, classification_override: Option<&str>) {
+        match classification_override {
+            Some(CLASSIFICATION_OVERRIDE_INCLUDE) => self.has_guitar_term = !self.is_blacklisted,
+            Some(CLASSIFICATION_OVERRIDE_EXCLUDE) => self.has_guitar_term = false,
+            _ => {}
+        }
+    }
 }
 
 pub struct GuitarTermsService {
     guitar_terms: Vec<String>,
     blacklisted_channel_ids: Vec<String>,
-    non_guitar_channel_repo: NonGuitarChannelRepository,
+    non_guitar_channel_repo: Box<dyn NonGuitarChannelStore>,
 }
 
 impl GuitarTermsService {
     pub fn new(
         guitar_terms: Vec<String>,
         blacklisted_channel_ids: Vec<String>,
-        non_guitar_channel_repo: NonGuitarChannelRepository,
+        non_guitar_channel_repo: Box<dyn NonGuitarChannelStore>,
     ) -> GuitarTermsService {
         GuitarTermsService {
             guitar_terms,
@@ -53,11 +71,17 @@ impl GuitarTermsService {
             }
         }
 
-        if has_guitar_term == false && ignore_guitar_terms == false {
-            self.non_guitar_channel_repo.upsert(&channel_id).await;
+        let (partial_terms, partial_score) = if has_guitar_term {
+            (vec![], 1.0)
+        } else {
+            self.get_partial_matches(channel_title, channel_description)
+        };
+
+        if !has_guitar_term && !ignore_guitar_terms {
+            self.non_guitar_channel_repo.upsert(channel_id).await;
         }
 
-        if ignore_guitar_terms == true {
+        if ignore_guitar_terms {
             has_guitar_term = true;
         }
 
@@ -73,6 +97,69 @@ impl GuitarTermsService {
         GuitarTermResult {
             has_guitar_term,
             is_blacklisted,
+            partial_terms,
+            partial_score,
+        }
+    }
+
+    /// Share of tagged videos with a tag containing a guitar term. A video with several guitar
+    /// tags counts more than once, so the score is capped at 1.0.
+    pub fn get_tag_score(&self, tag_profile: &TagProfile) -> f64 {
+        if tag_profile.tagged_video_count == 0 {
+            return 0.0;
+        }
+
+        let guitar_tag_count: i64 = tag_profile
+            .keywords
+            .iter()
+            .filter(|keyword| {
+                self.guitar_terms
+                    .iter()
+                    .any(|term| keyword.tag.contains(term.as_str()))
+            })
+            .map(|keyword| keyword.count)
+            .sum();
+
+        (guitar_tag_count as f64 / tag_profile.tagged_video_count as f64).min(1.0)
+    }
+
+    /// Share of the given videos with a guitar term, doesn't list the channel as non guitar.
+    pub fn get_video_score(&self, videos: &[Document]) -> f64 {
+        get_guitar_video_share(&self.guitar_terms, videos)
+    }
+
+    /// Scores how close a channel comes to a multi-word guitar term, e.g. "lesson" alone
+    /// scores 0.5 for "guitar lesson". The score is the best ratio over all terms.
+    fn get_partial_matches(
+        &self,
+        channel_title: &str,
+        channel_description: &str,
+    ) -> (Vec<String>, f64) {
+        let text = format!("{} {}", channel_title, channel_description).to_lowercase();
+        let words = text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<&str>>();
+
+        let mut partial_terms = vec![];
+        let mut partial_score: f64 = 0.0;
+
+        for term in &self.guitar_terms {
+            let term_words = term.split_whitespace().collect::<Vec<&str>>();
+            let matched_words = term_words
+                .iter()
+                .filter(|term_word| words.contains(term_word))
+                .count();
+
+            if term_words.len() < 2 || matched_words == 0 {
+                continue;
+            }
+
+            let score = matched_words as f64 / term_words.len() as f64;
+            partial_score = partial_score.max(score);
+            partial_terms.push(term.to_string());
         }
+
+        (partial_terms, partial_score)
     }
 }
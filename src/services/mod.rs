@@ -1,2 +1,13 @@
+pub mod channel_merge_service;
+pub mod channel_purge_service;
+pub mod channel_redirect_service;
+pub mod collaboration_service;
+pub mod crawl_request_service;
+pub mod gear_extraction_service;
 pub mod guitar_terms_service;
+pub mod opt_out_service;
+pub mod sentiment_service;
+pub mod song_recognition_service;
+pub mod tag_analytics_service;
+pub mod url_resolver_service;
 pub mod youtube_service;
@@ -0,0 +1,187 @@
+use log::info;
+use mongodb::bson::{doc, Document};
+
+use crate::{
+    errors::crawler_error::CrawlerError,
+    notifications::smtp_client::SmtpClient,
+    repos::{
+        additional_channel_store::AdditionalChannelStore,
+        channel_audit_store::{ChannelAuditStore, AUDIT_ACTION_OPTED_OUT},
+        channel_store::ChannelStore,
+        opt_out_store::OptOutStore,
+    },
+    services::channel_purge_service::ChannelPurgeService,
+    utils::purge_utils::get_counts_document,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptOut {
+    /// The channel id, or the handle while the channel is unknown
+    pub channel: String,
+    /// Whether stored data of the channel was deleted
+    pub purged: bool,
+}
+
+/// Registers removal requests of channel owners, mails them a confirmation code and, once a
+/// request is confirmed, deletes what is stored about the channel. Each removal leaves an entry in
+/// the channel audit under the anonymized id of the purge.
+pub struct OptOutService {
+    opt_out_repo: Box<dyn OptOutStore>,
+    channel_repo: Box<dyn ChannelStore>,
+    channel_purge_service: ChannelPurgeService,
+    additional_channel_repo: Box<dyn AdditionalChannelStore>,
+    channel_audit_repo: Box<dyn ChannelAuditStore>,
+    /// Mails the confirmation codes, set with `notifications.email`
+    smtp_client: Option<SmtpClient>,
+}
+
+impl OptOutService {
+    pub fn new(
+        opt_out_repo: Box<dyn OptOutStore>,
+        channel_repo: Box<dyn ChannelStore>,
+        channel_purge_service: ChannelPurgeService,
+        additional_channel_repo: Box<dyn AdditionalChannelStore>,
+        channel_audit_repo: Box<dyn ChannelAuditStore>,
+        smtp_client: Option<SmtpClient>,
+    ) -> OptOutService {
+        OptOutService {
+            opt_out_repo,
+            channel_repo,
+            channel_purge_service,
+            additional_channel_repo,
+            channel_audit_repo,
+            smtp_client,
+        }
+    }
+
+    pub async fn get_all(&self) -> Result<Vec<Document>, CrawlerError> {
+        Ok(self.opt_out_repo.get_all().await?)
+    }
+
+    /// Takes a channel id or `@handle` and returns the channel the request is registered under.
+    /// Nothing is skipped or purged until the request is confirmed with the code mailed to
+    /// `email`, so only the owner of the address can confirm it. Returns `None` without
+    /// `notifications.email` to mail the code. Repeated requests update the email and reason and
+    /// replace the code.
+    pub async fn request(
+        &self,
+        channel: &str,
+        email: &str,
+        reason: &str,
+    ) -> Result<Option<String>, CrawlerError> {
+        let smtp_client = match &self.smtp_client {
+            Some(smtp_client) => smtp_client,
+            None => return Ok(None),
+        };
+        let (channel_id, handle) = self.resolve(channel).await?;
+        let key = channel_id.unwrap_or_else(|| channel.to_string());
+        let confirmation_code = get_confirmation_code();
+
+        self.opt_out_repo
+            .insert(&key, handle, email, reason, &confirmation_code)
+            .await?;
+
+        let (subject, body) = get_confirmation_mail(channel, &confirmation_code);
+        smtp_client
+            .send_to(&[email.to_string()], &subject, &body)
+            .await?;
+
+        info!("Channel {} asked to opt out, waiting for confirmation", key);
+
+        Ok(Some(key))
+    }
+
+    /// Takes the channel id or `@handle` of the request. Returns `None` when no request carries
+    /// the code. Confirming a repeated request purges again whatever was stored in the meantime.
+    pub async fn confirm(
+        &self,
+        channel: &str,
+        confirmation_code: &str,
+    ) -> Result<Option<OptOut>, CrawlerError> {
+        if !self
+            .opt_out_repo
+            .confirm(channel, confirmation_code)
+            .await?
+        {
+            return Ok(None);
+        }
+
+        let (channel_id, _) = self.resolve(channel).await?;
+        let key = channel_id.clone().unwrap_or_else(|| channel.to_string());
+
+        self.additional_channel_repo.delete_one(&key).await?;
+
+        let channel_id = match channel_id {
+            Some(channel_id) if self.channel_repo.exists(&channel_id).await? => channel_id,
+            _ => {
+                info!("Channel {} opted out, nothing stored to purge", key);
+
+                return Ok(Some(OptOut {
+                    channel: key,
+                    purged: false,
+                }));
+            }
+        };
+
+        let purge = self.channel_purge_service.purge(&channel_id).await?;
+
+        self.opt_out_repo.set_purged(channel).await?;
+        // The free-text reason stays in the registry, the audit must not lead back to the channel
+        self.channel_audit_repo
+            .insert(
+                &purge.anonymized_id,
+                AUDIT_ACTION_OPTED_OUT,
+                doc! {"deleted": get_counts_document(&purge.counts)},
+            )
+            .await?;
+
+        info!("Channel {} opted out and purged", channel_id);
+
+        Ok(Some(OptOut {
+            channel: channel_id,
+            purged: true,
+        }))
+    }
+
+    /// The channel id, when known, and the handle of a channel id or `@handle`.
+    async fn resolve<'a>(
+        &self,
+        channel: &'a str,
+    ) -> Result<(Option<String>, Option<&'a str>), CrawlerError> {
+        if channel.starts_with('@') {
+            Ok((
+                self.channel_repo.get_id_by_handle(channel).await?,
+                Some(channel),
+            ))
+        } else {
+            Ok((Some(channel.to_string()), None))
+        }
+    }
+}
+
+fn get_confirmation_code() -> String {
+    format!("{:016x}", rand::random::<u64>())
+}
+
+fn get_confirmation_mail(channel: &str, confirmation_code: &str) -> (String, String) {
+    (
+        format!("Confirm the removal of {}", channel),
+        format!(
+            "The removal of the channel {} and everything stored about it was requested for this \
+            address.\n\nConfirm the request with the code {}\n\nNothing is removed without the \
+            confirmation, ignore this mail if you did not ask for it.",
+            channel, confirmation_code
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn mails_the_code() {
+        let (subject, body) = super::get_confirmation_mail("@riffs", "0123456789abcdef");
+
+        assert_eq!(subject, "Confirm the removal of @riffs");
+        assert!(body.contains("0123456789abcdef"));
+    }
+}
@@ -0,0 +1,57 @@
+use std::collections::HashSet;
+
+use futures::future::try_join_all;
+
+use crate::{
+    errors::crawler_error::CrawlerError,
+    repos::{comment_store::CommentStore, video_store::VideoStore},
+    utils::sentiment_utils::{get_comment_sentiment, score_comment},
+};
+
+/// Scores the sentiment of ingested comments by the terms of a lexicon and stores the mean score
+/// of its comments as `commentSentiment` on each video.
+pub struct SentimentService {
+    comment_repo: Box<dyn CommentStore>,
+    video_repo: Box<dyn VideoStore>,
+}
+
+impl SentimentService {
+    pub fn new(
+        comment_repo: Box<dyn CommentStore>,
+        video_repo: Box<dyn VideoStore>,
+    ) -> SentimentService {
+        SentimentService {
+            comment_repo,
+            video_repo,
+        }
+    }
+
+    /// Scores up to `limit` unscored comments and updates the sentiment of their videos. Returns
+    /// the number of scored comments.
+    pub async fn score_batch(&self, limit: i64) -> Result<usize, CrawlerError> {
+        let comments = self.comment_repo.get_unscored(limit).await?;
+
+        try_join_all(comments.iter().map(|(id, _, text)| {
+            self.comment_repo
+                .set_sentiment_score(id, score_comment(text))
+        }))
+        .await?;
+
+        let video_ids = comments
+            .iter()
+            .map(|(_, video_id, _)| video_id.as_str())
+            .collect::<HashSet<&str>>();
+
+        for video_id in video_ids {
+            let scores = self.comment_repo.get_sentiment_scores(video_id).await?;
+
+            if let Some(sentiment) = get_comment_sentiment(&scores) {
+                self.video_repo
+                    .set_comment_sentiment(video_id, &sentiment)
+                    .await?;
+            }
+        }
+
+        Ok(comments.len())
+    }
+}
@@ -0,0 +1,97 @@
+use mongodb::bson::Document;
+use regex::Regex;
+
+use crate::errors::crawler_error::CrawlerError;
+use crate::utils::song_utils::{get_song_document, get_song_key, CoverOf};
+
+/// Recognizes covers, lessons and tabs of songs in video titles with the configured rules,
+/// regexes with `artist` and `song` groups. The first matching rule wins.
+pub struct SongRecognitionService {
+    rules: Vec<Regex>,
+}
+
+impl SongRecognitionService {
+    pub fn new(rules: &[String]) -> Result<SongRecognitionService, CrawlerError> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                let regex = Regex::new(rule)?;
+                let group_names = regex.capture_names().flatten().collect::<Vec<&str>>();
+
+                if !group_names.contains(&"artist") || !group_names.contains(&"song") {
+                    return Err(CrawlerError::ParseError(format!(
+                        "Rule {} needs an artist and a song group",
+                        rule
+                    )));
+                }
+
+                Ok(regex)
+            })
+            .collect::<Result<Vec<Regex>, CrawlerError>>()?;
+
+        Ok(SongRecognitionService { rules })
+    }
+
+    pub fn recognize(&self, title: &str) -> Option<CoverOf> {
+        self.rules.iter().find_map(|rule| {
+            let captures = rule.captures(title)?;
+            let artist = captures.name("artist")?.as_str().trim();
+            let song = captures.name("song")?.as_str().trim();
+
+            if get_song_key(artist).is_empty() || get_song_key(song).is_empty() {
+                return None;
+            }
+
+            Some(CoverOf {
+                artist: artist.to_string(),
+                song: song.to_string(),
+            })
+        })
+    }
+
+    /// Stores `coverOf` with the lookup keys used by `VideoStore::get_covers`.
+    pub fn append_cover_of(&self, video: &mut Document, title: &str) {
+        if let Some(cover_of) = self.recognize(title) {
+            video.insert("coverOf", get_song_document(&cover_of));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{models::config::SongRecognitionConfig, utils::song_utils::CoverOf};
+
+    use super::SongRecognitionService;
+
+    fn cover_of(artist: &str, song: &str) -> Option<CoverOf> {
+        Some(CoverOf {
+            artist: artist.to_string(),
+            song: song.to_string(),
+        })
+    }
+
+    #[test]
+    fn recognize_default_rules() {
+        let service = SongRecognitionService::new(&SongRecognitionConfig::default().rules).unwrap();
+
+        assert_eq!(
+            service.recognize("Metallica – Nothing Else Matters (Guitar Cover)"),
+            cover_of("Metallica", "Nothing Else Matters")
+        );
+        assert_eq!(
+            service.recognize("Pink Floyd - Comfortably Numb | Solo Lesson with TAB"),
+            cover_of("Pink Floyd", "Comfortably Numb")
+        );
+        assert_eq!(
+            service.recognize("How to play Wonderwall by Oasis - Easy Guitar Tutorial"),
+            cover_of("Oasis", "Wonderwall")
+        );
+        assert_eq!(service.recognize("My new amp - first impressions"), None);
+    }
+
+    #[test]
+    fn reject_rules_without_groups() {
+        assert!(SongRecognitionService::new(&["(?P<song>.+) cover".to_string()]).is_err());
+        assert!(SongRecognitionService::new(&["(".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,109 @@
+use mongodb::bson::{doc, DateTime, Document};
+
+use crate::{
+    errors::crawler_error::CrawlerError,
+    models::tag_profile::{TagCount, TagProfile},
+    repos::{tag_profile_store::TagProfileStore, video_store::VideoStore},
+    utils::tag_utils::{build_tag_profile, detect_specializations},
+};
+
+// Profiles follow what a channel posts now, older uploads are left out
+const PROFILE_VIDEO_COUNT: i64 = 200;
+
+pub struct TagAnalyticsService {
+    video_repo: Box<dyn VideoStore>,
+    tag_profile_repo: Box<dyn TagProfileStore>,
+}
+
+impl TagAnalyticsService {
+    pub fn new(
+        video_repo: Box<dyn VideoStore>,
+        tag_profile_repo: Box<dyn TagProfileStore>,
+    ) -> TagAnalyticsService {
+        TagAnalyticsService {
+            video_repo,
+            tag_profile_repo,
+        }
+    }
+
+    /// Rebuilds the tag profile of a channel from the tags of its latest videos.
+    pub async fn update_profile(&self, channel_id: &str) -> Result<TagProfile, CrawlerError> {
+        let videos = self
+            .video_repo
+            .get_tags(channel_id, PROFILE_VIDEO_COUNT)
+            .await?;
+
+        let profile = TagProfile {
+            keywords: build_tag_profile(&videos),
+            tagged_video_count: videos.iter().filter(|(tags, _)| !tags.is_empty()).count() as i64,
+            specializations: detect_specializations(&videos),
+        };
+
+        let keywords = profile
+            .keywords
+            .iter()
+            .map(|keyword| {
+                doc! {
+                    "tag": &keyword.tag,
+                    "count": keyword.count,
+                    "lastUsedAt": keyword.last_used_at,
+                }
+            })
+            .collect::<Vec<Document>>();
+
+        self.tag_profile_repo
+            .upsert(
+                channel_id,
+                doc! {
+                    "keywords": keywords,
+                    "taggedVideoCount": profile.tagged_video_count,
+                    "specializations": &profile.specializations,
+                    "updatedAt": DateTime::now(),
+                },
+            )
+            .await?;
+
+        Ok(profile)
+    }
+
+    pub async fn get_profile(&self, channel_id: &str) -> Result<Option<TagProfile>, CrawlerError> {
+        let profile = match self.tag_profile_repo.get(channel_id).await? {
+            Some(profile) => profile,
+            None => return Ok(None),
+        };
+
+        let keywords = profile
+            .get_array("keywords")
+            .map(|keywords| {
+                keywords
+                    .iter()
+                    .filter_map(|keyword| keyword.as_document())
+                    .filter_map(|keyword| {
+                        Some(TagCount {
+                            tag: keyword.get_str("tag").ok()?.to_string(),
+                            count: keyword.get_i64("count").ok()?,
+                            last_used_at: keyword.get_i64("lastUsedAt").ok()?,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let specializations = profile
+            .get_array("specializations")
+            .map(|specializations| {
+                specializations
+                    .iter()
+                    .filter_map(|specialization| specialization.as_str())
+                    .map(|specialization| specialization.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Some(TagProfile {
+            keywords,
+            tagged_video_count: profile.get_i64("taggedVideoCount").unwrap_or(0),
+            specializations,
+        }))
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use log::{info, warn};
+use reqwest::{Client, Url};
+
+use crate::{
+    cache::{memory_cache::MemoryCache, response_cache::ResponseCache},
+    repos::resolved_url_store::ResolvedUrlStore,
+    utils::{
+        link_utils::{extract_urls, is_shortened_url, normalize_url},
+        throttle::Throttle,
+        url_guard::{is_blocked_error, UrlGuard},
+        youtube_url_utils::{parse_youtube_url, YoutubeResource},
+    },
+};
+
+const REQUEST_TIMEOUT_SECONDS: u64 = 10;
+const MAX_REDIRECTS: usize = 10;
+// Failed lookups are cached too, so a dead short link in a popular description is tried daily
+const CACHE_TTL_SECONDS: u64 = 24 * 60 * 60;
+const CACHE_MAX_ENTRIES: usize = 10_000;
+
+/// Expands shortened links in descriptions, e.g. bit.ly or amzn.to, to the canonical URL they
+/// redirect to. Resolved links are kept in `resolvedurls` and cached in process, and requests
+/// to each shortener are spaced by `domain_interval`. Links resolving or redirecting to private
+/// addresses are not followed.
+pub struct UrlResolverService {
+    http_client: Client,
+    url_guard: UrlGuard,
+    resolved_url_repo: Box<dyn ResolvedUrlStore>,
+    cache: MemoryCache,
+    domain_interval: Duration,
+    domain_throttles: Mutex<HashMap<String, Arc<Throttle>>>,
+}
+
+impl UrlResolverService {
+    pub fn new(
+        resolved_url_repo: Box<dyn ResolvedUrlStore>,
+        domain_interval: Duration,
+    ) -> UrlResolverService {
+        let url_guard = UrlGuard::default();
+
+        UrlResolverService {
+            http_client: url_guard
+                .build_client(Duration::from_secs(REQUEST_TIMEOUT_SECONDS), MAX_REDIRECTS),
+            url_guard,
+            resolved_url_repo,
+            cache: MemoryCache::new(Duration::from_secs(CACHE_TTL_SECONDS), CACHE_MAX_ENTRIES),
+            domain_interval,
+            domain_throttles: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the canonical URL of each shortened link in the text that could be resolved.
+    pub async fn resolve_all(&self, text: &str) -> HashMap<String, String> {
+        let mut request_budget = usize::MAX;
+
+        self.resolve_all_within(text, &mut request_budget).await
+    }
+
+    /// Like `resolve_all`, sending at most `request_budget` requests to the shorteners and
+    /// lowering it by the requests sent. Links over the budget stay unresolved without being
+    /// cached, so a later call resolves them.
+    pub async fn resolve_all_within(
+        &self,
+        text: &str,
+        request_budget: &mut usize,
+    ) -> HashMap<String, String> {
+        let mut resolved_urls = HashMap::new();
+
+        for url in extract_urls(text) {
+            if resolved_urls.contains_key(&url) || !is_shortened_url(&url) {
+                continue;
+            }
+
+            if let Some(resolved_url) = self.resolve(&url, request_budget).await {
+                resolved_urls.insert(url, resolved_url);
+            }
+        }
+
+        resolved_urls
+    }
+
+    pub async fn resolve(&self, url: &str, request_budget: &mut usize) -> Option<String> {
+        // youtu.be links are expanded without a request
+        if let Some(YoutubeResource::Video(video_id)) = parse_youtube_url(url) {
+            return Some(format!("https://youtube.com/watch?v={}", video_id));
+        }
+
+        let cache_key = format!("url:{}", url);
+        if let Ok(Some(resolved_url)) = self.cache.get(&cache_key).await {
+            return Some(resolved_url).filter(|resolved_url| !resolved_url.is_empty());
+        }
+
+        let resolved_url = match self.resolved_url_repo.get(url).await {
+            Ok(Some(resolved_url)) => Some(resolved_url),
+            Ok(None) if *request_budget == 0 => return None,
+            Ok(None) => {
+                *request_budget -= 1;
+                self.request(url).await
+            }
+            Err(e) => {
+                warn!("Failed to read resolved url {}: {}", url, e);
+                return None;
+            }
+        };
+
+        if let Some(resolved_url) = &resolved_url {
+            if let Err(e) = self.resolved_url_repo.insert(url, resolved_url).await {
+                warn!("Failed to store resolved url {}: {}", url, e);
+            }
+        }
+
+        let _ = self
+            .cache
+            .set(&cache_key, resolved_url.as_deref().unwrap_or_default())
+            .await;
+
+        resolved_url
+    }
+
+    /// Falls back to `GET` for shorteners that do not allow `HEAD`. Links that do not redirect
+    /// off the shorteners, e.g. unknown or deleted ones, are not resolved.
+    async fn request(&self, url: &str) -> Option<String> {
+        let host = Url::parse(url).ok()?.host_str()?.to_lowercase();
+        if self.url_guard.is_blocked(url).await {
+            info!("Skipped url {} to a private address", url);
+            return None;
+        }
+
+        self.get_throttle(&host).wait().await;
+
+        let mut result = self.http_client.head(url).send().await;
+
+        if let Ok(response) = &result {
+            if matches!(response.status().as_u16(), 405 | 501) {
+                result = self.http_client.get(url).send().await;
+            }
+        }
+
+        let response = match result {
+            Ok(response) => response,
+            Err(e) if is_blocked_error(&e) => {
+                info!("Skipped url {} redirecting to a private address", url);
+                return None;
+            }
+            Err(e) => {
+                info!("Failed to resolve url {}: {}", url, e);
+                return None;
+            }
+        };
+
+        // Shops often answer crawlers with an error, the redirect target is known regardless
+        let final_url = response.url().to_string();
+        if is_shortened_url(&final_url) {
+            info!("Failed to resolve url {}: {}", url, response.status());
+            return None;
+        }
+
+        normalize_url(&final_url)
+    }
+
+    fn get_throttle(&self, host: &str) -> Arc<Throttle> {
+        self.domain_throttles
+            .lock()
+            .unwrap()
+            .entry(host.to_string())
+            .or_insert_with(|| Arc::new(Throttle::new(self.domain_interval)))
+            .clone()
+    }
+}
@@ -1,54 +1,241 @@
-use anyhow::Error;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{TimeZone, Utc};
+use log::{debug, info, warn};
+use serde::de::DeserializeOwned;
+use tokio::time::sleep;
 
 use crate::{
+    cache::response_cache::{get_cache_key, ResponseCache},
+    errors::crawler_error::{ApiErrorReason, CrawlerError},
     models::{
-        youtube_channel_details::{YouTubeChannelDetails, YoutubeStatisticsItem},
+        apikey::ApiKey,
+        youtube_channel_details::{
+            YouTubeChannelDetails, YouTubeChannelIds, YoutubeStatisticsItem,
+        },
         youtube_channel_subscriptions::{
             YouTubeChannelSubscriptionSnippet, YoutubeChannelSubscriptions,
         },
+        youtube_comment_threads::YouTubeCommentThreads,
+        youtube_playlist_items::YouTubePlaylistItems,
+        youtube_playlists::YouTubePlaylists,
+        youtube_video_details::{YouTubeVideoDetails, YouTubeVideoItem},
+    },
+    notifications::notification_service::NotificationService,
+    repos::{apikey_store::ApiKeyStore, quota_breaker_store::QuotaBreakerStore},
+    utils::{
+        api_key_health_utils::mask_api_key,
+        api_scheduler::{ApiCaller, ApiPriority, ApiScheduler},
+        quota_utils::next_quota_reset,
     },
-    repos::apikeys_repo::ApiKeyRepository,
 };
 
-const BASE_URL: &str = "https://www.googleapis.com/youtube/v3/";
+const MAX_VIDEO_IDS_PER_REQUEST: usize = 50;
+const MAX_CHANNEL_IDS_PER_REQUEST: usize = 50;
+// A breaker tripped by another instance is noticed within this time
+const QUOTA_BREAKER_CHECK_SECONDS: i64 = 60;
 
+/// Calls the YouTube Data API, each call waiting for its turn at the shared `ApiScheduler`. A key
+/// that runs out of quota is skipped until the reset at Pacific midnight. Once no key has quota
+/// left, a circuit breaker shared through the settings collection trips: every API call of every
+/// instance waits until the reset. Errors caused by the api key count against its health, an
+/// invalid key is disabled.
 pub struct YoutubeService {
-    apikey_repo: ApiKeyRepository,
+    apikey_repo: Box<dyn ApiKeyStore>,
+    settings_repo: Box<dyn QuotaBreakerStore>,
+    scheduler: Arc<ApiScheduler>,
+    caller: ApiCaller,
+    base_url: String,
+    notification_service: Arc<NotificationService>,
+    response_cache: Option<Arc<dyn ResponseCache>>,
+    units_spent: AtomicU64,
+    /// The breaker state as last read from the settings, 0 when not paused
+    quota_paused_until: AtomicI64,
+    quota_checked_at: AtomicI64,
 }
 
 impl YoutubeService {
-    pub fn new(apikey_repo: ApiKeyRepository) -> YoutubeService {
-        YoutubeService { apikey_repo }
+    pub fn new(
+        apikey_repo: Box<dyn ApiKeyStore>,
+        settings_repo: Box<dyn QuotaBreakerStore>,
+        scheduler: Arc<ApiScheduler>,
+        caller: ApiCaller,
+        base_url: String,
+        notification_service: Arc<NotificationService>,
+        response_cache: Option<Arc<dyn ResponseCache>>,
+    ) -> YoutubeService {
+        YoutubeService {
+            apikey_repo,
+            settings_repo,
+            scheduler,
+            caller,
+            base_url,
+            notification_service,
+            response_cache,
+            units_spent: AtomicU64::new(0),
+            quota_paused_until: AtomicI64::new(0),
+            quota_checked_at: AtomicI64::new(0),
+        }
+    }
+
+    /// Quota units spent by this service so far, cached responses are free.
+    pub fn units_spent(&self) -> u64 {
+        self.units_spent.load(Ordering::Relaxed)
     }
 
     pub async fn get_channel_details(
         &self,
         channel_id: &str,
-    ) -> Result<YoutubeStatisticsItem, Error> {
+    ) -> Result<YoutubeStatisticsItem, CrawlerError> {
         let api_key = self.apikey_repo.get_least_used_api_key().await?;
 
         let url = format!(
-            "{}channels?part=snippet,brandingSettings,statistics&id={}&key={}",
-            BASE_URL, channel_id, api_key.key
+            "{}channels?part=snippet,brandingSettings,statistics,topicDetails&id={}&key={}",
+            self.base_url, channel_id, api_key.key
         );
 
-        let resp = reqwest::get(url)
-            .await?
-            .json::<YouTubeChannelDetails>()
+        let resp = self
+            .get_json::<YouTubeChannelDetails>(url, &api_key)
             .await?;
 
-        self.apikey_repo.update_usage(&api_key).await?;
-
         match resp.items {
             Some(items) => Ok(items[0].clone()),
-            None => Err(Error::msg("No items found")),
+            None => Err(CrawlerError::not_found(format!(
+                "No channel found for {}",
+                channel_id
+            ))),
+        }
+    }
+
+    /// Loads the details of many channels at one unit per 50 channels. Channels that no longer
+    /// exist are missing from the result.
+    pub async fn get_channels_details(
+        &self,
+        channel_ids: &[String],
+    ) -> Result<Vec<YoutubeStatisticsItem>, CrawlerError> {
+        let mut items = vec![];
+
+        for channel_ids_chunk in channel_ids.chunks(MAX_CHANNEL_IDS_PER_REQUEST) {
+            let api_key = self.apikey_repo.get_least_used_api_key().await?;
+
+            let url = format!(
+                "{}channels?part=snippet,brandingSettings,statistics,topicDetails&id={}&key={}",
+                self.base_url,
+                channel_ids_chunk.join(","),
+                api_key.key
+            );
+
+            let resp = self
+                .get_json::<YouTubeChannelDetails>(url, &api_key)
+                .await?;
+
+            items.extend(resp.items.unwrap_or_default());
+        }
+
+        Ok(items)
+    }
+
+    pub async fn resolve_handle(&self, handle: &str) -> Result<Option<String>, CrawlerError> {
+        let api_key = self.apikey_repo.get_least_used_api_key().await?;
+
+        let url = format!(
+            "{}channels?part=id&forHandle={}&key={}",
+            self.base_url, handle, api_key.key
+        );
+
+        let resp = self.get_json::<YouTubeChannelIds>(url, &api_key).await?;
+
+        let channel_id = resp
+            .items
+            .and_then(|items| items.into_iter().next())
+            .map(|item| item.id);
+
+        Ok(channel_id)
+    }
+
+    pub async fn get_video_details(
+        &self,
+        video_ids: &[String],
+    ) -> Result<Vec<YouTubeVideoItem>, CrawlerError> {
+        let mut items = vec![];
+
+        for video_ids_chunk in video_ids.chunks(MAX_VIDEO_IDS_PER_REQUEST) {
+            let api_key = self.apikey_repo.get_least_used_api_key().await?;
+
+            let url = format!(
+                "{}videos?part=snippet,contentDetails,statistics,status&id={}&key={}",
+                self.base_url,
+                video_ids_chunk.join(","),
+                api_key.key
+            );
+
+            let resp = self.get_json::<YouTubeVideoDetails>(url, &api_key).await?;
+
+            items.extend(resp.items);
+        }
+
+        Ok(items)
+    }
+
+    pub async fn get_playlist_items_page(
+        &self,
+        playlist_id: &str,
+        page_token: Option<&str>,
+    ) -> Result<YouTubePlaylistItems, CrawlerError> {
+        let api_key = self.apikey_repo.get_least_used_api_key().await?;
+
+        let mut url = format!(
+            "{}playlistItems?part=snippet,contentDetails&maxResults=50&playlistId={}&key={}",
+            self.base_url, playlist_id, api_key.key
+        );
+
+        if let Some(page_token) = page_token {
+            url = format!("{}&pageToken={}", url, page_token);
         }
+
+        self.get_json::<YouTubePlaylistItems>(url, &api_key).await
+    }
+
+    pub async fn get_channel_playlists_page(
+        &self,
+        channel_id: &str,
+        page_token: Option<&str>,
+    ) -> Result<YouTubePlaylists, CrawlerError> {
+        let api_key = self.apikey_repo.get_least_used_api_key().await?;
+
+        let mut url = format!(
+            "{}playlists?part=snippet,contentDetails&maxResults=50&channelId={}&key={}",
+            self.base_url, channel_id, api_key.key
+        );
+
+        if let Some(page_token) = page_token {
+            url = format!("{}&pageToken={}", url, page_token);
+        }
+
+        self.get_json::<YouTubePlaylists>(url, &api_key).await
+    }
+
+    /// The newest top-level comments of a video, one unit per page of up to 100.
+    pub async fn get_comment_threads_page(
+        &self,
+        video_id: &str,
+    ) -> Result<YouTubeCommentThreads, CrawlerError> {
+        let api_key = self.apikey_repo.get_least_used_api_key().await?;
+
+        let url = format!(
+            "{}commentThreads?part=snippet&maxResults=100&order=time&textFormat=plainText&videoId={}&key={}",
+            self.base_url, video_id, api_key.key
+        );
+
+        self.get_json::<YouTubeCommentThreads>(url, &api_key).await
     }
 
     pub async fn get_channel_subscriptions(
         &self,
         channel_id: &str,
-    ) -> Result<Vec<YouTubeChannelSubscriptionSnippet>, Error> {
+    ) -> Result<Vec<YouTubeChannelSubscriptionSnippet>, CrawlerError> {
         let mut page_token: Option<String> = None;
         let mut snippets = vec![];
 
@@ -79,25 +266,206 @@ impl YoutubeService {
         &self,
         channel_id: &str,
         page_token: Option<String>,
-    ) -> Result<YoutubeChannelSubscriptions, Error> {
+    ) -> Result<YoutubeChannelSubscriptions, CrawlerError> {
         let api_key = self.apikey_repo.get_least_used_api_key().await?;
 
         let mut url = format!(
             "{}subscriptions?part=snippet&maxResults=50&channelId={}&key={}",
-            BASE_URL, channel_id, api_key.key
+            self.base_url, channel_id, api_key.key
         );
 
-        if page_token.is_some() {
-            url = format!("{}&pageToken={}", url, page_token.unwrap());
+        if let Some(page_token) = page_token {
+            url = format!("{}&pageToken={}", url, page_token);
+        }
+
+        self.get_json::<YoutubeChannelSubscriptions>(url, &api_key)
+            .await
+    }
+
+    /// Changes the class the following calls wait in at the scheduler.
+    pub fn set_priority(&mut self, priority: ApiPriority) {
+        self.caller.priority = priority;
+    }
+
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        url: String,
+        api_key: &ApiKey,
+    ) -> Result<T, CrawlerError> {
+        let cache_key = get_cache_key(&url);
+
+        if let Some(cached) = self.get_cached::<T>(&cache_key).await {
+            return Ok(cached);
+        }
+
+        self.wait_for_quota_reset().await?;
+        self.scheduler.acquire(self.caller).await;
+
+        let response = reqwest::get(url)
+            .await
+            .map_err(|e| CrawlerError::api(e.to_string()))?;
+        self.units_spent.fetch_add(1, Ordering::Relaxed);
+        self.apikey_repo.update_usage(api_key).await?;
+
+        let status = response.status();
+
+        if status.is_success() {
+            let body = response
+                .text()
+                .await
+                .map_err(|e| CrawlerError::api(e.to_string()))?;
+            let value = serde_json::from_str::<T>(&body)?;
+
+            if let Some(response_cache) = &self.response_cache {
+                if let Err(e) = response_cache.set(&cache_key, &body).await {
+                    warn!("Failed to cache response for {}: {}", cache_key, e);
+                }
+            }
+
+            self.apikey_repo.record_success(api_key).await?;
+
+            return Ok(value);
+        }
+
+        let body = response.text().await.unwrap_or_default();
+
+        let error = CrawlerError::api_status(status.as_u16(), &body);
+
+        if let CrawlerError::ApiError { reason, .. } = &error {
+            self.record_key_result(api_key, *reason).await?;
+
+            if *reason == ApiErrorReason::QuotaExceeded {
+                self.record_quota_exceeded(api_key).await?;
+            }
+        }
+
+        Err(error)
+    }
+
+    async fn record_key_result(
+        &self,
+        api_key: &ApiKey,
+        reason: ApiErrorReason,
+    ) -> Result<(), CrawlerError> {
+        if !reason.is_key_error() {
+            self.apikey_repo.record_success(api_key).await?;
+            return Ok(());
+        }
+
+        let health = self
+            .apikey_repo
+            .record_error(api_key, reason, Utc::now().timestamp())
+            .await?;
+
+        if health != api_key.health {
+            warn!(
+                "API key {} is {} after a {} error",
+                mask_api_key(&api_key.key),
+                health,
+                reason.name()
+            );
         }
 
-        let resp = reqwest::get(url)
-            .await?
-            .json::<YoutubeChannelSubscriptions>()
+        Ok(())
+    }
+
+    /// Cache failures and unreadable entries fall through to the api.
+    async fn get_cached<T: DeserializeOwned>(&self, cache_key: &str) -> Option<T> {
+        let body = match self.response_cache.as_ref()?.get(cache_key).await {
+            Ok(body) => body?,
+            Err(e) => {
+                warn!("Failed to read cached response for {}: {}", cache_key, e);
+                return None;
+            }
+        };
+
+        debug!("Cache hit for {}", cache_key);
+
+        serde_json::from_str::<T>(&body).ok()
+    }
+
+    async fn record_quota_exceeded(&self, api_key: &ApiKey) -> Result<(), CrawlerError> {
+        let now = Utc::now();
+        let reset_at = next_quota_reset(now).timestamp();
+
+        self.apikey_repo
+            .set_quota_exceeded(api_key, reset_at)
             .await?;
 
-        self.apikey_repo.update_usage(&api_key).await?;
+        let keys_with_quota = self.apikey_repo.count_with_quota(now.timestamp()).await?;
+
+        if keys_with_quota > 0 {
+            warn!(
+                "QUOTA: Quota of API key {} exceeded, {} keys with quota left",
+                mask_api_key(&api_key.key),
+                keys_with_quota
+            );
+            return Ok(());
+        }
+
+        self.trip_quota_breaker().await
+    }
+
+    async fn trip_quota_breaker(&self) -> Result<(), CrawlerError> {
+        let paused_until = next_quota_reset(Utc::now());
+
+        warn!(
+            "QUOTA: Quota exceeded, pausing API crawling until {}",
+            paused_until.to_rfc3339()
+        );
+
+        self.settings_repo
+            .set_quota_paused_until(paused_until.timestamp())
+            .await?;
+        self.quota_paused_until
+            .store(paused_until.timestamp(), Ordering::Relaxed);
+        self.quota_checked_at
+            .store(Utc::now().timestamp(), Ordering::Relaxed);
+
+        self.notification_service
+            .notify_quota_exhausted(paused_until.timestamp())
+            .await;
+
+        Ok(())
+    }
+
+    /// Reads the breaker from the settings at most once a minute.
+    async fn wait_for_quota_reset(&self) -> Result<(), CrawlerError> {
+        let now = Utc::now().timestamp();
+
+        let paused_until =
+            if now - self.quota_checked_at.load(Ordering::Relaxed) < QUOTA_BREAKER_CHECK_SECONDS {
+                self.quota_paused_until.load(Ordering::Relaxed)
+            } else {
+                let paused_until = self
+                    .settings_repo
+                    .get_quota_paused_until()
+                    .await?
+                    .unwrap_or(0);
+                self.quota_paused_until
+                    .store(paused_until, Ordering::Relaxed);
+                self.quota_checked_at.store(now, Ordering::Relaxed);
+                paused_until
+            };
+
+        if paused_until == 0 {
+            return Ok(());
+        }
+
+        if paused_until > now {
+            info!(
+                "QUOTA: API crawling paused, waiting {} seconds until {}",
+                paused_until - now,
+                Utc.timestamp(paused_until, 0).to_rfc3339()
+            );
+
+            sleep(Duration::from_secs((paused_until - now) as u64)).await;
+        }
+
+        info!("QUOTA: Quota reset, resuming API crawling");
+        self.settings_repo.clear_quota_paused_until().await?;
+        self.quota_paused_until.store(0, Ordering::Relaxed);
 
-        Ok(resp)
+        Ok(())
     }
 }
@@ -0,0 +1,4 @@
+pub mod replay_server;
+pub mod simulation_seeder;
+pub mod simulation_server;
+pub mod synthetic_corpus;
@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fs;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Error;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server, StatusCode};
+use log::{info, warn};
+use reqwest::Url;
+use serde_json::{json, Value};
+
+use crate::simulation::simulation_server::{json_response, text_response};
+
+/// Serves recorded YouTube responses from a fixtures directory on the endpoints of the
+/// `SimulationServer`, so crawls over the same fixtures always see the same data:
+///
+/// - `feeds/{channelId}.xml` for the video feed
+/// - `channels/{channelId}.json` and `channels/@{handle}.json` for `channels.list`
+/// - `videos/{videoId}.json` for `videos.list`
+/// - `subscriptions/{channelId}.json`, `playlists/{channelId}.json` and
+///   `playlistItems/{playlistId}.json` for the paged lists, later pages as `{id}.{pageToken}.json`
+/// - `timedtext/{videoId}.xml` and `channelPages/{channelId}.html`
+///
+/// Item fixtures hold a single item or a whole recorded list response. Missing fixtures are
+/// answered like missing channels or videos on YouTube.
+pub struct ReplayServer {
+    fixtures_dir: PathBuf,
+}
+
+impl ReplayServer {
+    pub fn new(fixtures_dir: &str) -> ReplayServer {
+        ReplayServer {
+            fixtures_dir: PathBuf::from(fixtures_dir),
+        }
+    }
+
+    /// The recorded channels, which seed the replay database.
+    pub fn get_channel_ids(&self) -> Result<Vec<String>, Error> {
+        let mut channel_ids = vec![];
+
+        for entry in fs::read_dir(self.fixtures_dir.join("channels"))? {
+            let path = entry?.path();
+
+            if path.extension().and_then(|extension| extension.to_str()) != Some("json") {
+                continue;
+            }
+
+            match path.file_stem().and_then(|stem| stem.to_str()) {
+                Some(stem) if !stem.starts_with('@') => channel_ids.push(stem.to_string()),
+                _ => {}
+            }
+        }
+
+        channel_ids.sort();
+
+        Ok(channel_ids)
+    }
+
+    pub async fn serve(self, port: u16) -> Result<(), Error> {
+        let fixtures_dir = self.fixtures_dir.display().to_string();
+        let server = Arc::new(self);
+        let addr = SocketAddr::from(([127, 0, 0, 1], port));
+
+        let make_service = make_service_fn(move |_| {
+            let server = server.clone();
+
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req| {
+                    let server = server.clone();
+
+                    async move { Ok::<_, Infallible>(server.handle(req)) }
+                }))
+            }
+        });
+
+        info!(
+            "Replay server listening on {}, serving {}",
+            addr, fixtures_dir
+        );
+        Server::bind(&addr).serve(make_service).await?;
+
+        Ok(())
+    }
+
+    fn handle(&self, req: Request<Body>) -> Response<Body> {
+        let url = match Url::parse(&format!("http://localhost{}", req.uri())) {
+            Ok(url) => url,
+            Err(_) => return text_response(StatusCode::BAD_REQUEST, "", "text/plain"),
+        };
+        let params = url
+            .query_pairs()
+            .into_owned()
+            .collect::<HashMap<String, String>>();
+        let param = |key: &str| params.get(key).map(|value| value.as_str()).unwrap_or("");
+
+        match url.path() {
+            "/youtube/v3/channels" if !param("forHandle").is_empty() => {
+                let handle = format!("@{}", param("forHandle").trim_start_matches('@'));
+                self.list(
+                    "youtube#channelListResponse",
+                    "channels",
+                    &[&handle.to_lowercase()],
+                )
+            }
+            "/youtube/v3/channels" => self.list(
+                "youtube#channelListResponse",
+                "channels",
+                &param("id").split(',').collect::<Vec<&str>>(),
+            ),
+            "/youtube/v3/videos" => self.list(
+                "youtube#videoListResponse",
+                "videos",
+                &param("id").split(',').collect::<Vec<&str>>(),
+            ),
+            "/youtube/v3/subscriptions" => {
+                self.page("subscriptions", param("channelId"), param("pageToken"))
+            }
+            "/youtube/v3/playlists" => {
+                self.page("playlists", param("channelId"), param("pageToken"))
+            }
+            "/youtube/v3/playlistItems" => {
+                self.page("playlistItems", param("playlistId"), param("pageToken"))
+            }
+            "/feeds/videos.xml" => match self.read(&format!("feeds/{}.xml", param("channel_id"))) {
+                Some(feed) => text_response(StatusCode::OK, &feed, "text/xml"),
+                None => text_response(StatusCode::NOT_FOUND, "", "text/xml"),
+            },
+            "/api/timedtext" => {
+                let timed_text = self
+                    .read(&format!("timedtext/{}.xml", param("v")))
+                    .unwrap_or_default();
+                text_response(StatusCode::OK, &timed_text, "text/xml")
+            }
+            path if path.starts_with("/channel/") => {
+                let channel_id = path["/channel/".len()..].split('/').next().unwrap_or("");
+                let page = self
+                    .read(&format!("channelPages/{}.html", channel_id))
+                    .unwrap_or_else(|| "<html></html>".to_string());
+                text_response(StatusCode::OK, &page, "text/html")
+            }
+            _ => text_response(StatusCode::NOT_FOUND, "", "text/plain"),
+        }
+    }
+
+    /// Answers a list call by ids with the items of their fixtures.
+    fn list(&self, kind: &str, directory: &str, ids: &[&str]) -> Response<Body> {
+        let mut items = vec![];
+
+        for id in ids.iter().filter(|id| !id.is_empty()) {
+            let fixture = match self.read(&format!("{}/{}.json", directory, id)) {
+                Some(fixture) => fixture,
+                None => continue,
+            };
+
+            match serde_json::from_str::<Value>(&fixture) {
+                Ok(Value::Object(mut response)) if response.contains_key("items") => {
+                    if let Some(Value::Array(recorded)) = response.remove("items") {
+                        items.extend(recorded);
+                    }
+                }
+                Ok(item) => items.push(item),
+                Err(e) => warn!("Fixture {}/{}.json is invalid: {}", directory, id, e),
+            }
+        }
+
+        let mut response = json!({
+            "kind": kind,
+            "etag": "replay",
+            "pageInfo": {"totalResults": items.len(), "resultsPerPage": 50},
+        });
+
+        // The api leaves out `items` when nothing matched
+        if !items.is_empty() {
+            response["items"] = Value::Array(items);
+        }
+
+        json_response(response)
+    }
+
+    fn page(&self, directory: &str, id: &str, page_token: &str) -> Response<Body> {
+        let path = if page_token.is_empty() {
+            format!("{}/{}.json", directory, id)
+        } else {
+            format!("{}/{}.{}.json", directory, id, page_token)
+        };
+
+        match self.read(&path) {
+            Some(page) => text_response(StatusCode::OK, &page, "application/json"),
+            None => text_response(StatusCode::NOT_FOUND, "", "application/json"),
+        }
+    }
+
+    fn read(&self, path: &str) -> Option<String> {
+        // Ids come from the request, they must not leave the fixtures directory
+        if path.contains("..") {
+            return None;
+        }
+
+        fs::read_to_string(self.fixtures_dir.join(path)).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use hyper::{Body, Request, StatusCode};
+    use serde_json::Value;
+
+    use super::ReplayServer;
+
+    #[tokio::test]
+    async fn serves_recorded_fixtures() {
+        let fixtures_dir = std::env::temp_dir().join(format!("replay-{}", std::process::id()));
+        for directory in ["channels", "videos", "feeds"] {
+            fs::create_dir_all(fixtures_dir.join(directory)).unwrap();
+        }
+        fs::write(
+            fixtures_dir.join("channels/UCguitar.json"),
+            r#"{"kind": "youtube#channel", "id": "UCguitar"}"#,
+        )
+        .unwrap();
+        fs::write(
+            fixtures_dir.join("videos/video1.json"),
+            r#"{"kind": "youtube#videoListResponse", "items": [{"id": "video1"}]}"#,
+        )
+        .unwrap();
+        fs::write(fixtures_dir.join("feeds/UCguitar.xml"), "<feed></feed>").unwrap();
+
+        let server = ReplayServer::new(fixtures_dir.to_str().unwrap());
+        assert_eq!(server.get_channel_ids().unwrap(), vec!["UCguitar"]);
+
+        let get =
+            |uri: &str| server.handle(Request::builder().uri(uri).body(Body::empty()).unwrap());
+
+        let response = get("/youtube/v3/videos?id=video1,video2&key=replay");
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let videos = serde_json::from_slice::<Value>(&body).unwrap();
+        assert_eq!(videos["items"][0]["id"], "video1");
+        assert_eq!(videos["pageInfo"]["totalResults"], 1);
+
+        let response = get("/youtube/v3/channels?id=UCmissing&key=replay");
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert!(serde_json::from_slice::<Value>(&body).unwrap()["items"].is_null());
+
+        assert_eq!(
+            get("/feeds/videos.xml?channel_id=UCguitar").status(),
+            StatusCode::OK
+        );
+        assert_eq!(
+            get("/feeds/videos.xml?channel_id=../UCguitar").status(),
+            StatusCode::NOT_FOUND
+        );
+
+        fs::remove_dir_all(fixtures_dir).unwrap();
+    }
+}
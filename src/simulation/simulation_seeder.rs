@@ -0,0 +1,60 @@
+use anyhow::Error;
+use log::info;
+
+use crate::{repos::store_factory::StoreFactory, simulation::synthetic_corpus::SyntheticCorpus};
+
+const SIMULATION_API_KEY: &str = "simulation";
+const SIMULATION_DAILY_QUOTA: i32 = 1_000_000;
+const SIMULATION_GUITAR_TERMS: [&str; 3] = ["guitar", "riff", "fingerstyle"];
+// Only a handful of seed channels, the rest of the corpus is found through subscriptions
+const SIMULATION_SEED_CHANNELS: usize = 10;
+
+/// Prepares the simulation database so the crawlers have an api key, guitar terms and
+/// a set of seed channels to start from.
+pub async fn seed_simulation_database(
+    stores: &StoreFactory,
+    corpus: &SyntheticCorpus,
+) -> Result<(), Error> {
+    let channel_ids = corpus
+        .channels
+        .iter()
+        .take(SIMULATION_SEED_CHANNELS)
+        .map(|channel| channel.id.clone())
+        .collect::<Vec<String>>();
+
+    seed_database(stores, &channel_ids).await
+}
+
+/// Like `seed_simulation_database`, with all recorded channels as seed channels.
+pub async fn seed_replay_database(
+    stores: &StoreFactory,
+    channel_ids: &[String],
+) -> Result<(), Error> {
+    seed_database(stores, channel_ids).await
+}
+
+async fn seed_database(stores: &StoreFactory, channel_ids: &[String]) -> Result<(), Error> {
+    let apikey_repo = stores.apikey_store();
+    let guitar_term_repo = stores.guitar_term_store();
+    let additional_channel_repo = stores.additional_channel_store();
+
+    apikey_repo
+        .upsert(SIMULATION_API_KEY, SIMULATION_DAILY_QUOTA)
+        .await?;
+
+    for term in SIMULATION_GUITAR_TERMS.iter() {
+        guitar_term_repo.insert(term).await?;
+    }
+
+    for channel_id in channel_ids {
+        additional_channel_repo.insert(channel_id, false).await?;
+    }
+
+    info!(
+        "Seeded {} database with {} channels",
+        stores.environment(),
+        channel_ids.len()
+    );
+
+    Ok(())
+}
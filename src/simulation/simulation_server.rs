@@ -0,0 +1,323 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use anyhow::Error;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server, StatusCode};
+use log::info;
+use reqwest::Url;
+use serde_json::{json, Value};
+
+use crate::models::config::YoutubeConfig;
+use crate::simulation::synthetic_corpus::{SyntheticChannel, SyntheticCorpus, SyntheticVideo};
+
+const PLAYLIST_PAGE_SIZE: usize = 50;
+
+/// Serves a synthetic corpus through the same endpoints the crawler uses on YouTube: the Data API
+/// (`channels`, `subscriptions`, `videos`, `playlists`, `playlistItems`), the video RSS feed,
+/// timed text and empty channel pages.
+pub struct SimulationServer {
+    corpus: SyntheticCorpus,
+}
+
+impl SimulationServer {
+    pub fn new(corpus: SyntheticCorpus) -> SimulationServer {
+        SimulationServer { corpus }
+    }
+
+    pub fn youtube_config(port: u16) -> YoutubeConfig {
+        YoutubeConfig {
+            api_base_url: format!("http://127.0.0.1:{}/youtube/v3/", port),
+            feed_base_url: format!("http://127.0.0.1:{}/feeds/videos.xml", port),
+            timed_text_base_url: format!("http://127.0.0.1:{}/api/timedtext", port),
+            channel_page_base_url: format!("http://127.0.0.1:{}/channel/", port),
+            watch_page_base_url: format!("http://127.0.0.1:{}/watch?v=", port),
+            daily_api_calls: 0,
+        }
+    }
+
+    pub async fn serve(self, port: u16) -> Result<(), Error> {
+        let server = Arc::new(self);
+        let addr = SocketAddr::from(([127, 0, 0, 1], port));
+
+        let make_service = make_service_fn(move |_| {
+            let server = server.clone();
+
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req| {
+                    let server = server.clone();
+
+                    async move { Ok::<_, Infallible>(server.handle(req)) }
+                }))
+            }
+        });
+
+        info!("Simulation server listening on {}", addr);
+        Server::bind(&addr).serve(make_service).await?;
+
+        Ok(())
+    }
+
+    fn handle(&self, req: Request<Body>) -> Response<Body> {
+        let url = match Url::parse(&format!("http://localhost{}", req.uri())) {
+            Ok(url) => url,
+            Err(_) => return text_response(StatusCode::BAD_REQUEST, "", "text/plain"),
+        };
+        let params = url
+            .query_pairs()
+            .into_owned()
+            .collect::<HashMap<String, String>>();
+        let param = |key: &str| params.get(key).map(|value| value.as_str()).unwrap_or("");
+
+        match url.path() {
+            "/youtube/v3/channels" => self.channels(param("id"), param("forHandle")),
+            "/youtube/v3/subscriptions" => self.subscriptions(param("channelId")),
+            "/youtube/v3/videos" => self.videos(param("id")),
+            // The corpus has no playlists besides the uploads
+            "/youtube/v3/playlists" => json_response(json!({
+                "kind": "youtube#playlistListResponse",
+                "etag": "simulation",
+                "items": [],
+            })),
+            "/youtube/v3/playlistItems" => {
+                self.playlist_items(param("playlistId"), param("pageToken"))
+            }
+            "/feeds/videos.xml" => self.feed(param("channel_id")),
+            "/api/timedtext" => text_response(StatusCode::OK, "", "text/xml"),
+            path if path.starts_with("/channel/") || path == "/watch" => {
+                text_response(StatusCode::OK, "<html></html>", "text/html")
+            }
+            _ => text_response(StatusCode::NOT_FOUND, "", "text/plain"),
+        }
+    }
+
+    fn channels(&self, id: &str, handle: &str) -> Response<Body> {
+        let channel = if handle.is_empty() {
+            self.corpus.find_channel(id)
+        } else {
+            let handle = format!("@{}", handle.trim_start_matches('@').to_lowercase());
+
+            self.corpus
+                .channels
+                .iter()
+                .find(|channel| channel_handle(channel) == handle)
+        };
+
+        let items = channel.map(|c| vec![channel_item(c)]);
+
+        json_response(json!({
+            "kind": "youtube#channelListResponse",
+            "etag": "simulation",
+            "pageInfo": { "totalResults": items.as_ref().map(|i| i.len()).unwrap_or(0), "resultsPerPage": 5 },
+            "items": items,
+        }))
+    }
+
+    fn subscriptions(&self, channel_id: &str) -> Response<Body> {
+        let channel = match self.corpus.find_channel(channel_id) {
+            Some(channel) => channel,
+            None => return text_response(StatusCode::NOT_FOUND, "", "application/json"),
+        };
+
+        let items = channel
+            .subscriptions
+            .iter()
+            .filter_map(|id| self.corpus.find_channel(id))
+            .map(|subscription| {
+                json!({
+                    "kind": "youtube#subscription",
+                    "etag": "simulation",
+                    "id": format!("{}.{}", channel.id, subscription.id),
+                    "snippet": {
+                        "publishedAt": subscription.published_at.to_rfc3339(),
+                        "title": subscription.title,
+                        "description": subscription.description,
+                        "resourceId": { "kind": "youtube#channel", "channelId": subscription.id },
+                        "channelId": channel.id,
+                        "thumbnails": thumbnails(),
+                    }
+                })
+            })
+            .collect::<Vec<Value>>();
+
+        json_response(json!({
+            "kind": "youtube#subscriptionListResponse",
+            "etag": "simulation",
+            "pageInfo": { "totalResults": items.len(), "resultsPerPage": 50 },
+            "items": items,
+        }))
+    }
+
+    fn videos(&self, ids: &str) -> Response<Body> {
+        let items = ids
+            .split(',')
+            .filter_map(|id| self.corpus.find_video(id))
+            .map(video_item)
+            .collect::<Vec<Value>>();
+
+        json_response(json!({
+            "kind": "youtube#videoListResponse",
+            "etag": "simulation",
+            "items": items,
+        }))
+    }
+
+    fn playlist_items(&self, playlist_id: &str, page_token: &str) -> Response<Body> {
+        let channel_id = format!("UC{}", playlist_id.trim_start_matches("UU"));
+        let channel = match self.corpus.find_channel(&channel_id) {
+            Some(channel) => channel,
+            None => return text_response(StatusCode::NOT_FOUND, "", "application/json"),
+        };
+
+        let offset = page_token.parse::<usize>().unwrap_or(0);
+        let items = channel
+            .videos
+            .iter()
+            .skip(offset)
+            .take(PLAYLIST_PAGE_SIZE)
+            .map(|video| {
+                json!({
+                    "kind": "youtube#playlistItem",
+                    "etag": "simulation",
+                    "id": format!("{}.{}", playlist_id, video.id),
+                    "snippet": {
+                        "publishedAt": video.published_at.to_rfc3339(),
+                        "title": video.title,
+                        "description": video.description,
+                    },
+                    "contentDetails": {
+                        "videoId": video.id,
+                        "videoPublishedAt": video.published_at.to_rfc3339(),
+                    }
+                })
+            })
+            .collect::<Vec<Value>>();
+
+        let next_offset = offset + PLAYLIST_PAGE_SIZE;
+        let next_page_token = if next_offset < channel.videos.len() {
+            Some(next_offset.to_string())
+        } else {
+            None
+        };
+
+        json_response(json!({
+            "kind": "youtube#playlistItemListResponse",
+            "etag": "simulation",
+            "nextPageToken": next_page_token,
+            "items": items,
+        }))
+    }
+
+    fn feed(&self, channel_id: &str) -> Response<Body> {
+        let channel = match self.corpus.find_channel(channel_id) {
+            Some(channel) => channel,
+            None => return text_response(StatusCode::NOT_FOUND, "", "text/xml"),
+        };
+
+        let entries = channel
+            .videos
+            .iter()
+            .take(15)
+            .map(|video| feed_entry(channel, video))
+            .collect::<Vec<String>>()
+            .join("");
+
+        let xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?><feed xmlns:yt="http://www.youtube.com/xml/schemas/2015" xmlns:media="http://search.yahoo.com/mrss/" xmlns="http://www.w3.org/2005/Atom"><title>{}</title>{}</feed>"#,
+            channel.title, entries
+        );
+
+        text_response(StatusCode::OK, &xml, "text/xml")
+    }
+}
+
+fn channel_handle(channel: &SyntheticChannel) -> String {
+    format!("@{}", channel.id.to_lowercase())
+}
+
+fn channel_item(channel: &SyntheticChannel) -> Value {
+    json!({
+        "kind": "youtube#channel",
+        "etag": "simulation",
+        "id": channel.id,
+        "snippet": {
+            "title": channel.title,
+            "description": channel.description,
+            "customUrl": channel_handle(channel),
+            "publishedAt": channel.published_at.to_rfc3339(),
+            "thumbnails": {
+                "default": { "url": "http://127.0.0.1/default.jpg", "width": 88, "height": 88 },
+                "medium": { "url": "http://127.0.0.1/medium.jpg", "width": 240, "height": 240 },
+                "high": { "url": "http://127.0.0.1/high.jpg", "width": 800, "height": 800 },
+            },
+            "localized": { "title": channel.title, "description": channel.description },
+        },
+        "statistics": {
+            "viewCount": channel.views.to_string(),
+            "subscriberCount": channel.subscribers.to_string(),
+            "hiddenSubscriberCount": false,
+            "videoCount": channel.videos.len().to_string(),
+        },
+        "topicDetails": {
+            "topicCategories": ["https://en.wikipedia.org/wiki/Music"],
+        },
+        "brandingSettings": {
+            "channel": { "title": channel.title, "description": channel.description },
+        },
+    })
+}
+
+fn video_item(video: &SyntheticVideo) -> Value {
+    json!({
+        "kind": "youtube#video",
+        "etag": "simulation",
+        "id": video.id,
+        "contentDetails": {
+            "duration": format!("PT{}M{}S", video.duration_seconds / 60, video.duration_seconds % 60),
+            "definition": "hd",
+            "caption": "false",
+            "licensedContent": false,
+        },
+        "statistics": {
+            "viewCount": video.views.to_string(),
+            "likeCount": (video.views / 50).to_string(),
+            "commentCount": (video.views / 500).to_string(),
+        },
+    })
+}
+
+fn thumbnails() -> Value {
+    json!({
+        "default": { "url": "http://127.0.0.1/default.jpg" },
+        "medium": { "url": "http://127.0.0.1/medium.jpg" },
+        "high": { "url": "http://127.0.0.1/high.jpg" },
+    })
+}
+
+fn feed_entry(channel: &SyntheticChannel, video: &SyntheticVideo) -> String {
+    let published = video.published_at.to_rfc3339();
+
+    format!(
+        r#"<entry><id>yt:video:{id}</id><yt:videoId>{id}</yt:videoId><yt:channelId>{channel_id}</yt:channelId><title>{title}</title><published>{published}</published><updated>{published}</updated><media:group><media:title>{title}</media:title><media:description>{description}</media:description><media:community><media:statistics views="{views}"/></media:community></media:group></entry>"#,
+        id = video.id,
+        channel_id = channel.id,
+        title = video.title,
+        published = published,
+        description = video.description,
+        views = video.views
+    )
+}
+
+pub fn json_response(body: Value) -> Response<Body> {
+    text_response(StatusCode::OK, &body.to_string(), "application/json")
+}
+
+pub fn text_response(status: StatusCode, body: &str, content_type: &str) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", content_type)
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
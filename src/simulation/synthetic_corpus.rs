@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const ID_CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+const GUITAR_TOPICS: [&str; 6] = [
+    "Guitar Lessons",
+    "Blues Guitar",
+    "Metal Guitar Riffs",
+    "Acoustic Guitar Covers",
+    "Guitar Gear Reviews",
+    "Fingerstyle Guitar",
+];
+const OTHER_TOPICS: [&str; 4] = ["Cooking", "Travel Vlogs", "Gaming", "Fitness"];
+// Share of channels without a guitar topic, these should be rejected by the pipeline
+const NON_GUITAR_RATIO: f64 = 0.2;
+const MAX_SUBSCRIPTIONS_PER_CHANNEL: usize = 20;
+
+#[derive(Debug, Clone)]
+pub struct SyntheticVideo {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub published_at: DateTime<Utc>,
+    pub views: i64,
+    pub duration_seconds: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct SyntheticChannel {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub published_at: DateTime<Utc>,
+    pub subscribers: i64,
+    pub views: i64,
+    pub videos: Vec<SyntheticVideo>,
+    pub subscriptions: Vec<String>,
+}
+
+/// A deterministic corpus of channels, videos and subscriptions generated from a seed.
+pub struct SyntheticCorpus {
+    pub channels: Vec<SyntheticChannel>,
+    channel_index: HashMap<String, usize>,
+    video_index: HashMap<String, (usize, usize)>,
+}
+
+impl SyntheticCorpus {
+    pub fn generate(seed: u64, channel_count: usize, videos_per_channel: usize) -> SyntheticCorpus {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let now = Utc::now();
+
+        let mut channels = (0..channel_count)
+            .map(|i| {
+                let is_guitar_channel = !rng.gen_bool(NON_GUITAR_RATIO);
+                let topic = if is_guitar_channel {
+                    GUITAR_TOPICS[rng.gen_range(0..GUITAR_TOPICS.len())]
+                } else {
+                    OTHER_TOPICS[rng.gen_range(0..OTHER_TOPICS.len())]
+                };
+
+                // Upload intervals between a day and two months model both prolific and dormant channels
+                let upload_interval_hours = rng.gen_range(24..24 * 60);
+                let last_upload_hours_ago = rng.gen_range(1..24 * 90);
+
+                let videos = (0..videos_per_channel)
+                    .map(|v| SyntheticVideo {
+                        id: random_id(&mut rng, 11),
+                        title: format!("{} #{}", topic, videos_per_channel - v),
+                        description: format!("Episode {} of {}", videos_per_channel - v, topic),
+                        published_at: now
+                            - Duration::hours(
+                                last_upload_hours_ago + v as i64 * upload_interval_hours,
+                            ),
+                        views: rng.gen_range(10..1_000_000),
+                        duration_seconds: rng.gen_range(60..3600),
+                    })
+                    .collect::<Vec<SyntheticVideo>>();
+
+                SyntheticChannel {
+                    id: format!("UC{}", random_id(&mut rng, 22)),
+                    title: format!("{} {}", topic, i),
+                    description: format!("Synthetic channel about {}", topic.to_lowercase()),
+                    published_at: now - Duration::days(rng.gen_range(365..365 * 15)),
+                    subscribers: rng.gen_range(100..2_000_000),
+                    views: rng.gen_range(1_000..100_000_000),
+                    videos,
+                    subscriptions: vec![],
+                }
+            })
+            .collect::<Vec<SyntheticChannel>>();
+
+        let channel_ids = channels
+            .iter()
+            .map(|channel| channel.id.clone())
+            .collect::<Vec<String>>();
+
+        for channel in channels.iter_mut() {
+            let subscription_count = rng.gen_range(0..=MAX_SUBSCRIPTIONS_PER_CHANNEL);
+
+            channel.subscriptions = (0..subscription_count)
+                .map(|_| channel_ids[rng.gen_range(0..channel_ids.len())].clone())
+                .filter(|id| *id != channel.id)
+                .collect();
+        }
+
+        let mut channel_index = HashMap::new();
+        let mut video_index = HashMap::new();
+
+        for (c, channel) in channels.iter().enumerate() {
+            channel_index.insert(channel.id.clone(), c);
+
+            for (v, video) in channel.videos.iter().enumerate() {
+                video_index.insert(video.id.clone(), (c, v));
+            }
+        }
+
+        SyntheticCorpus {
+            channels,
+            channel_index,
+            video_index,
+        }
+    }
+
+    pub fn find_channel(&self, channel_id: &str) -> Option<&SyntheticChannel> {
+        self.channel_index
+            .get(channel_id)
+            .map(|c| &self.channels[*c])
+    }
+
+    pub fn find_video(&self, video_id: &str) -> Option<&SyntheticVideo> {
+        self.video_index
+            .get(video_id)
+            .map(|(c, v)| &self.channels[*c].videos[*v])
+    }
+}
+
+fn random_id(rng: &mut StdRng, length: usize) -> String {
+    (0..length)
+        .map(|_| ID_CHARS[rng.gen_range(0..ID_CHARS.len())] as char)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_is_deterministic() {
+        let a = SyntheticCorpus::generate(7, 20, 5);
+        let b = SyntheticCorpus::generate(7, 20, 5);
+
+        let ids_a = a.channels.iter().map(|c| &c.id).collect::<Vec<_>>();
+        let ids_b = b.channels.iter().map(|c| &c.id).collect::<Vec<_>>();
+
+        assert_eq!(ids_a, ids_b);
+        assert_eq!(a.channels[0].videos.len(), 5);
+    }
+
+    #[test]
+    fn finds_channels_and_videos() {
+        let corpus = SyntheticCorpus::generate(7, 20, 5);
+        let video_id = corpus.channels[3].videos[2].id.clone();
+
+        assert!(corpus.find_channel(&corpus.channels[3].id).is_some());
+        assert_eq!(corpus.find_video(&video_id).unwrap().id, video_id);
+        assert!(corpus.find_video("missing").is_none());
+    }
+}
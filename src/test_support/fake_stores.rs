@@ -0,0 +1,888 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
+use chrono::{TimeZone, Utc};
+use mongodb::bson::{doc, from_document, to_document, Document};
+use mongodb::options::{ClientOptions, ServerAddress};
+use mongodb::Client;
+
+use crate::{
+    classifiers::genre_classifier::{get_genre_tags_document, GenreScore},
+    errors::crawler_error::ApiErrorReason,
+    models::{apikey::ApiKey, feed_state::FeedState, upload_pattern::UploadPattern},
+    repos::{
+        apikey_store::ApiKeyStore, channel_store::ChannelStore,
+        quota_breaker_store::QuotaBreakerStore, video_store::VideoStore,
+    },
+    utils::{
+        api_key_health_utils::API_KEY_HEALTHY,
+        ban_evasion_utils::{get_ban_evasion_document, BanEvasionMatch},
+        channel_page_utils::ChannelPageHints,
+        edit_history_utils::append_edit_history,
+        gear_utils::{get_gear_documents, get_gear_names, GearCount},
+        image_change_utils::format_image_hash,
+        link_utils::{get_link_check_document, LinkCheck},
+        sentiment_utils::{get_comment_sentiment_document, CommentSentiment},
+        song_utils::CoverOf,
+        stat_anomaly_utils::append_anomalies,
+        topic_drift_utils::{get_topic_drift_document, TopicDrift},
+    },
+};
+
+/// A client for the MongoDB stores a test has no fake for. Nothing listens on its address, so their
+/// calls fail fast and the code under test takes its error paths.
+pub fn unreachable_mongo_client() -> Client {
+    let options = ClientOptions::builder()
+        .hosts(vec![ServerAddress::Tcp {
+            host: "127.0.0.1".to_string(),
+            port: Some(1),
+        }])
+        .server_selection_timeout(Duration::from_millis(50))
+        .build();
+
+    Client::with_options(options).unwrap()
+}
+
+type Documents = Arc<Mutex<BTreeMap<String, Document>>>;
+
+fn unsupported() -> Error {
+    anyhow!("Not supported by the fake store")
+}
+
+fn set_fields(documents: &Documents, id: &str, fields: Document) {
+    if let Some(document) = documents.lock().unwrap().get_mut(id) {
+        document.extend(fields);
+    }
+}
+
+fn get_page(documents: &Documents, after_id: Option<&str>, limit: i64) -> Vec<Document> {
+    documents
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(id, _)| after_id.is_none_or(|after_id| id.as_str() > after_id))
+        .take(limit as usize)
+        .map(|(_, document)| document.clone())
+        .collect()
+}
+
+/// In-memory channel store. Clones share their documents, so a test can inspect what the code
+/// under test stored. Methods no test needs yet return an error.
+#[derive(Clone, Default)]
+pub struct FakeChannelStore {
+    pub channels: Documents,
+    pub feed_states: Arc<Mutex<HashMap<String, FeedState>>>,
+}
+
+impl FakeChannelStore {
+    pub fn with_channels(channels: Vec<Document>) -> FakeChannelStore {
+        let store = FakeChannelStore::default();
+
+        for channel in channels {
+            let id = channel.get_str("_id").unwrap().to_string();
+            store.channels.lock().unwrap().insert(id, channel);
+        }
+
+        store
+    }
+
+    pub fn get(&self, id: &str) -> Option<Document> {
+        self.channels.lock().unwrap().get(id).cloned()
+    }
+}
+
+#[async_trait]
+impl ChannelStore for FakeChannelStore {
+    async fn exists(&self, channel_id: &str) -> Result<bool, Error> {
+        Ok(self.channels.lock().unwrap().contains_key(channel_id))
+    }
+
+    async fn get_id_by_handle(&self, handle: &str) -> Result<Option<String>, Error> {
+        let channels = self.channels.lock().unwrap();
+        let channel_id = channels
+            .iter()
+            .find(|(_, channel)| channel.get_str("handle").ok() == Some(handle))
+            .map(|(id, _)| id.clone());
+
+        Ok(channel_id)
+    }
+
+    async fn find_by_id(&self, id: &str) -> Result<Option<Document>, Error> {
+        Ok(self.get(id))
+    }
+
+    async fn get_all_ids(&self) -> Result<Vec<String>, Error> {
+        Ok(self.channels.lock().unwrap().keys().cloned().collect())
+    }
+
+    async fn get_page(
+        &self,
+        after_id: Option<&str>,
+        _min_subscribers: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<Document>, Error> {
+        Ok(get_page(&self.channels, after_id, limit))
+    }
+
+    async fn get_ids_upload_last_month(
+        &self,
+        _min_subscribers_count: i64,
+    ) -> Result<Vec<String>, Error> {
+        Err(unsupported())
+    }
+
+    async fn get_ids_by_country_and_topic(
+        &self,
+        _country: Option<&str>,
+        _topic: Option<&str>,
+    ) -> Result<Vec<String>, Error> {
+        Err(unsupported())
+    }
+
+    async fn get_ids_last_upload_before(
+        &self,
+        _last_upload_before: chrono::DateTime<Utc>,
+    ) -> Result<Vec<String>, Error> {
+        Err(unsupported())
+    }
+
+    async fn get_ids_due_for_scrape(
+        &self,
+        _now: chrono::DateTime<Utc>,
+    ) -> Result<Vec<String>, Error> {
+        Err(unsupported())
+    }
+
+    async fn get_ids_about_crawled_before(
+        &self,
+        _crawled_before: chrono::DateTime<Utc>,
+        _limit: i64,
+    ) -> Result<Vec<String>, Error> {
+        Err(unsupported())
+    }
+
+    async fn get_ids_reclassified_before(
+        &self,
+        _reclassified_before: chrono::DateTime<Utc>,
+        _limit: i64,
+    ) -> Result<Vec<String>, Error> {
+        Err(unsupported())
+    }
+
+    async fn get_ids_rebranded_since(
+        &self,
+        _since: chrono::DateTime<Utc>,
+        _limit: i64,
+    ) -> Result<Vec<String>, Error> {
+        Err(unsupported())
+    }
+
+    async fn get_ids_last_crawled_before(
+        &self,
+        _last_crawl_before: chrono::DateTime<Utc>,
+        _last_upload_after: chrono::DateTime<Utc>,
+    ) -> Result<Vec<String>, Error> {
+        Err(unsupported())
+    }
+
+    async fn count_grouped_by(
+        &self,
+        _field: &str,
+        _default_key: &str,
+    ) -> Result<Vec<(String, i64)>, Error> {
+        Err(unsupported())
+    }
+
+    async fn count_created_since(&self, _since: chrono::DateTime<Utc>) -> Result<i64, Error> {
+        Err(unsupported())
+    }
+
+    async fn count_uploading_by_country(
+        &self,
+        _uploaded_since: i64,
+        _limit: i64,
+    ) -> Result<Vec<(String, i64)>, Error> {
+        Err(unsupported())
+    }
+
+    async fn get_ids_with_due_refresh_override(
+        &self,
+        _now: chrono::DateTime<Utc>,
+    ) -> Result<Vec<String>, Error> {
+        Err(unsupported())
+    }
+
+    async fn set_refresh_override(
+        &self,
+        _id: &str,
+        _interval_seconds: i64,
+        _until_timestamp: i64,
+    ) -> Result<bool, Error> {
+        Err(unsupported())
+    }
+
+    async fn clear_refresh_override(&self, _id: &str) -> Result<bool, Error> {
+        Err(unsupported())
+    }
+
+    async fn get_classification_override(&self, id: &str) -> Result<Option<String>, Error> {
+        Ok(self.get(id).and_then(|channel| {
+            channel
+                .get_str("classificationOverride")
+                .ok()
+                .map(|classification_override| classification_override.to_string())
+        }))
+    }
+
+    async fn set_classification_override(
+        &self,
+        _id: &str,
+        _classification_override: &str,
+    ) -> Result<bool, Error> {
+        Err(unsupported())
+    }
+
+    async fn clear_classification_override(&self, _id: &str) -> Result<bool, Error> {
+        Err(unsupported())
+    }
+
+    async fn get_language(&self, _id: &str) -> Result<Option<String>, Error> {
+        Err(unsupported())
+    }
+
+    async fn get_detected_language(&self, _id: &str) -> Result<String, Error> {
+        Err(unsupported())
+    }
+
+    async fn delete(&self, id: &str) -> Result<(), Error> {
+        self.channels.lock().unwrap().remove(id);
+
+        Ok(())
+    }
+
+    async fn upsert(&self, id: &str, channel: Document) -> bool {
+        self.channels
+            .lock()
+            .unwrap()
+            .insert(id.to_string(), channel)
+            .is_none()
+    }
+
+    async fn set_video_count_last_upload(
+        &self,
+        id: &str,
+        video_count: i64,
+        last_upload_timestamp: i64,
+    ) {
+        set_fields(
+            &self.channels,
+            id,
+            doc! {
+                "videoCount": video_count,
+                "lastUploadAt": last_upload_timestamp,
+            },
+        );
+    }
+
+    async fn set_scrape_schedule(
+        &self,
+        id: &str,
+        next_scrape_at: i64,
+        upload_interval_seconds: Option<i64>,
+    ) -> Result<(), Error> {
+        set_fields(
+            &self.channels,
+            id,
+            doc! {
+                "nextScrapeAt": next_scrape_at,
+                "uploadIntervalSeconds": upload_interval_seconds,
+                "scrapeFailures": 0_i64,
+            },
+        );
+
+        Ok(())
+    }
+
+    async fn set_scrape_failure(
+        &self,
+        id: &str,
+        failures: i64,
+        next_scrape_at: i64,
+    ) -> Result<(), Error> {
+        set_fields(
+            &self.channels,
+            id,
+            doc! {"nextScrapeAt": next_scrape_at, "scrapeFailures": failures},
+        );
+
+        Ok(())
+    }
+
+    async fn set_about(
+        &self,
+        _id: &str,
+        _links: Vec<Document>,
+        _contact_emails: Vec<String>,
+    ) -> Result<(), Error> {
+        Err(unsupported())
+    }
+
+    async fn set_page_hints(&self, id: &str, hints: &ChannelPageHints) -> Result<(), Error> {
+        set_fields(
+            &self.channels,
+            id,
+            doc! {
+                "isVerified": hints.is_verified,
+                "hasMemberships": hints.has_memberships,
+                "isMonetized": hints.is_monetized,
+            },
+        );
+
+        Ok(())
+    }
+
+    async fn set_link_checks(&self, id: &str, checks: &[LinkCheck]) -> Result<(), Error> {
+        set_fields(
+            &self.channels,
+            id,
+            doc! {
+                "linkChecks": checks.iter().map(get_link_check_document).collect::<Vec<Document>>(),
+            },
+        );
+
+        Ok(())
+    }
+
+    async fn set_verified_metadata(&self, id: &str, changes: Document) -> Result<(), Error> {
+        set_fields(&self.channels, id, changes);
+
+        Ok(())
+    }
+
+    async fn set_upload_pattern(&self, id: &str, pattern: &UploadPattern) -> Result<(), Error> {
+        set_fields(
+            &self.channels,
+            id,
+            doc! { "uploadPattern": to_document(pattern)? },
+        );
+
+        Ok(())
+    }
+
+    async fn get_upload_pattern(&self, id: &str) -> Result<Option<UploadPattern>, Error> {
+        match self
+            .get(id)
+            .and_then(|channel| channel.get_document("uploadPattern").ok().cloned())
+        {
+            Some(pattern) => Ok(Some(from_document(pattern)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn set_genre_tags(&self, id: &str, genres: &[GenreScore]) -> Result<(), Error> {
+        set_fields(&self.channels, id, get_genre_tags_document(genres));
+
+        Ok(())
+    }
+
+    async fn set_gear(&self, id: &str, gear: &[GearCount]) -> Result<(), Error> {
+        set_fields(
+            &self.channels,
+            id,
+            doc! { "gear": get_gear_documents(gear) },
+        );
+
+        Ok(())
+    }
+
+    async fn set_lifecycle(
+        &self,
+        id: &str,
+        lifecycle: &str,
+        previous: Option<&str>,
+    ) -> Result<(), Error> {
+        if let Some(channel) = self.channels.lock().unwrap().get_mut(id) {
+            let mut history = channel
+                .get_array("lifecycleHistory")
+                .cloned()
+                .unwrap_or_default();
+            history.push(doc! {"from": previous, "to": lifecycle}.into());
+
+            channel.insert("lifecycle", lifecycle);
+            channel.insert("lifecycleHistory", history);
+        }
+
+        Ok(())
+    }
+
+    async fn set_duplicate_of(
+        &self,
+        id: &str,
+        canonical_id: &str,
+        reason: &str,
+    ) -> Result<(), Error> {
+        set_fields(
+            &self.channels,
+            id,
+            doc! { "duplicateOf": canonical_id, "duplicateReason": reason },
+        );
+
+        Ok(())
+    }
+
+    async fn set_topic_drift(&self, id: &str, drift: Option<&TopicDrift>) -> Result<(), Error> {
+        set_fields(
+            &self.channels,
+            id,
+            doc! { "topicDrift": drift.map(get_topic_drift_document) },
+        );
+
+        Ok(())
+    }
+
+    async fn set_ban_evasion(&self, id: &str, ban_evasion: &BanEvasionMatch) -> Result<(), Error> {
+        set_fields(
+            &self.channels,
+            id,
+            doc! { "banEvasion": get_ban_evasion_document(ban_evasion) },
+        );
+
+        Ok(())
+    }
+
+    async fn set_avatar_hash(&self, id: &str, avatar_hash: u64) -> Result<(), Error> {
+        set_fields(
+            &self.channels,
+            id,
+            doc! { "avatarHash": format_image_hash(avatar_hash) },
+        );
+
+        Ok(())
+    }
+
+    async fn set_merged(&self, id: &str, canonical_id: &str) -> Result<(), Error> {
+        set_fields(
+            &self.channels,
+            id,
+            doc! { "redirectsTo": canonical_id, "mergedInto": canonical_id },
+        );
+
+        Ok(())
+    }
+
+    async fn set_scrape_error(&self, id: &str, error: String) {
+        set_fields(
+            &self.channels,
+            id,
+            doc! { "scrapeError": { "error": error } },
+        );
+    }
+
+    async fn set_reclassified(&self, _id: &str) -> Result<(), Error> {
+        Err(unsupported())
+    }
+
+    async fn deactivate(&self, _id: &str, _reason: &str) -> Result<(), Error> {
+        Err(unsupported())
+    }
+
+    async fn get_redirect(&self, id: &str) -> Result<Option<String>, Error> {
+        let redirect = self
+            .get(id)
+            .and_then(|channel| channel.get_str("redirectsTo").ok().map(|r| r.to_string()));
+
+        Ok(redirect)
+    }
+
+    async fn set_redirect(&self, id: &str, redirects_to: &str) -> Result<(), Error> {
+        set_fields(&self.channels, id, doc! { "redirectsTo": redirects_to });
+
+        Ok(())
+    }
+
+    async fn get_feed_state(&self, id: &str) -> Result<FeedState, Error> {
+        let feed_states = self.feed_states.lock().unwrap();
+
+        Ok(feed_states.get(id).cloned().unwrap_or_default())
+    }
+
+    async fn set_feed_state(&self, id: &str, feed_state: &FeedState) -> Result<(), Error> {
+        self.feed_states
+            .lock()
+            .unwrap()
+            .insert(id.to_string(), feed_state.clone());
+
+        Ok(())
+    }
+}
+
+/// In-memory video store of hot videos, shared between clones like `FakeChannelStore`.
+#[derive(Clone, Default)]
+pub struct FakeVideoStore {
+    pub videos: Documents,
+}
+
+impl FakeVideoStore {
+    pub fn get(&self, id: &str) -> Option<Document> {
+        self.videos.lock().unwrap().get(id).cloned()
+    }
+
+    fn get_by_channel(&self, channel_id: &str) -> Vec<Document> {
+        self.videos
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|video| video.get_str("channel").ok() == Some(channel_id))
+            .cloned()
+            .collect()
+    }
+}
+
+#[async_trait]
+impl VideoStore for FakeVideoStore {
+    async fn find_by_id(&self, id: &str) -> Result<Option<Document>, Error> {
+        Ok(self.get(id))
+    }
+
+    async fn archive_by_channel(
+        &self,
+        _channel_id: &str,
+        _published_before: i64,
+        _keep_latest: u64,
+    ) -> Result<u64, Error> {
+        Err(unsupported())
+    }
+
+    async fn get_updated_lookup_by_ids(
+        &self,
+        channel_id: &str,
+        video_ids: &[String],
+    ) -> Result<HashMap<String, chrono::DateTime<Utc>>, Error> {
+        let lookup = self
+            .get_by_channel(channel_id)
+            .iter()
+            .filter_map(|video| {
+                let id = video.get_str("_id").ok()?.to_string();
+                let updated_at = video.get_i64("updatedAt").ok()?;
+
+                Some((id, Utc.timestamp(updated_at, 0)))
+            })
+            .filter(|(id, _)| video_ids.contains(id))
+            .collect();
+
+        Ok(lookup)
+    }
+
+    async fn delete_all_by_channel(&self, channel_id: &str) -> Result<(), Error> {
+        self.videos
+            .lock()
+            .unwrap()
+            .retain(|_, video| video.get_str("channel").ok() != Some(channel_id));
+
+        Ok(())
+    }
+
+    async fn upsert(&self, id: &str, mut video_doc: Document) -> Result<(), Error> {
+        let mut videos = self.videos.lock().unwrap();
+        let video = videos.entry(id.to_string()).or_default();
+
+        let now = Utc::now().timestamp();
+        append_edit_history(video, &mut video_doc, now);
+        append_anomalies(video, &mut video_doc, now);
+        video.extend(video_doc);
+
+        Ok(())
+    }
+
+    async fn count(&self, channel_id: &str) -> Result<u64, Error> {
+        Ok(self.get_by_channel(channel_id).len() as u64)
+    }
+
+    async fn get_ids_by_channel(&self, channel_id: &str) -> Result<Vec<String>, Error> {
+        Ok(self
+            .get_by_channel(channel_id)
+            .iter()
+            .filter_map(|video| video.get_str("_id").ok())
+            .map(|id| id.to_string())
+            .collect())
+    }
+
+    async fn get_views_by_ids(
+        &self,
+        channel_id: &str,
+        video_ids: &[String],
+    ) -> Result<HashMap<String, i64>, Error> {
+        let lookup = self
+            .get_by_channel(channel_id)
+            .iter()
+            .filter_map(|video| {
+                let id = video.get_str("_id").ok()?.to_string();
+                let views = video.get_i64("views").ok()?;
+
+                Some((id, views))
+            })
+            .filter(|(id, _)| video_ids.contains(id))
+            .collect();
+
+        Ok(lookup)
+    }
+
+    async fn get_ids_without_captions(
+        &self,
+        _retry_before: i64,
+        _excluded_channel_ids: &[String],
+        _limit: i64,
+    ) -> Result<Vec<(String, String)>, Error> {
+        Err(unsupported())
+    }
+
+    async fn set_caption_keywords(
+        &self,
+        _id: &str,
+        _caption_keywords: Vec<String>,
+        _caption_songs: &[CoverOf],
+    ) -> Result<(), Error> {
+        Err(unsupported())
+    }
+
+    async fn set_caption_retry(&self, _id: &str, _retry_at: i64) -> Result<(), Error> {
+        Err(unsupported())
+    }
+
+    async fn get_ids_comments_due(
+        &self,
+        _published_after: i64,
+        _crawled_before: i64,
+        _limit: i64,
+    ) -> Result<Vec<(String, String)>, Error> {
+        Err(unsupported())
+    }
+
+    async fn set_comments_crawled(&self, _id: &str, _crawled_at: i64) -> Result<(), Error> {
+        Err(unsupported())
+    }
+
+    async fn set_comment_sentiment(
+        &self,
+        id: &str,
+        sentiment: &CommentSentiment,
+    ) -> Result<(), Error> {
+        set_fields(
+            &self.videos,
+            id,
+            doc! {"commentSentiment": get_comment_sentiment_document(sentiment)},
+        );
+
+        Ok(())
+    }
+
+    async fn count_all(&self) -> Result<u64, Error> {
+        Ok(self.videos.lock().unwrap().len() as u64)
+    }
+
+    async fn get_page(
+        &self,
+        after_id: Option<&str>,
+        _channel_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Document>, Error> {
+        Ok(get_page(&self.videos, after_id, limit))
+    }
+
+    async fn set_view_snapshot(&self, id: &str, field: &str, views: i64) -> Result<(), Error> {
+        if let Some(video) = self.videos.lock().unwrap().get_mut(id) {
+            if !video.contains_key(field) {
+                video.insert(field, views);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn get_published_timestamps(
+        &self,
+        channel_id: &str,
+        limit: i64,
+    ) -> Result<Vec<i64>, Error> {
+        let mut timestamps = self
+            .get_by_channel(channel_id)
+            .iter()
+            .filter_map(|video| video.get_i64("publishedAt").ok())
+            .collect::<Vec<i64>>();
+
+        timestamps.sort_unstable_by(|a, b| b.cmp(a));
+        timestamps.truncate(limit as usize);
+
+        Ok(timestamps)
+    }
+
+    async fn get_tags(
+        &self,
+        channel_id: &str,
+        limit: i64,
+    ) -> Result<Vec<(Vec<String>, i64)>, Error> {
+        let tags = self
+            .get_by_channel(channel_id)
+            .iter()
+            .take(limit as usize)
+            .filter_map(|video| {
+                let tags = video
+                    .get_array("tags")
+                    .ok()?
+                    .iter()
+                    .filter_map(|tag| tag.as_str().map(|tag| tag.to_string()))
+                    .collect();
+
+                Some((tags, video.get_i64("publishedAt").ok()?))
+            })
+            .collect();
+
+        Ok(tags)
+    }
+
+    async fn get_gear(&self, channel_id: &str, limit: i64) -> Result<Vec<Vec<String>>, Error> {
+        Ok(self
+            .get_by_channel(channel_id)
+            .iter()
+            .take(limit as usize)
+            .map(get_gear_names)
+            .collect())
+    }
+
+    async fn get_latest_texts(&self, channel_id: &str, limit: i64) -> Result<Vec<Document>, Error> {
+        Ok(self
+            .get_by_channel(channel_id)
+            .into_iter()
+            .take(limit as usize)
+            .collect())
+    }
+
+    async fn get_ids_availability_checked_before(
+        &self,
+        _checked_before: i64,
+        _limit: i64,
+    ) -> Result<Vec<(String, String)>, Error> {
+        Err(unsupported())
+    }
+
+    async fn set_availability(&self, _id: &str, _availability: &str) -> Result<(), Error> {
+        Err(unsupported())
+    }
+
+    async fn move_to_channel(
+        &self,
+        _channel_id: &str,
+        _target_channel_id: &str,
+    ) -> Result<u64, Error> {
+        Err(unsupported())
+    }
+
+    async fn get_restricted_by_channel(
+        &self,
+        _channel_id: &str,
+        _limit: i64,
+    ) -> Result<Vec<Document>, Error> {
+        Err(unsupported())
+    }
+
+    async fn get_covers(
+        &self,
+        _song_key: &str,
+        _artist_key: Option<&str>,
+        _limit: i64,
+    ) -> Result<Vec<Document>, Error> {
+        Err(unsupported())
+    }
+
+    async fn get_cover_songs(&self, _limit: i64) -> Result<Vec<CoverOf>, Error> {
+        Err(unsupported())
+    }
+}
+
+/// A single api key with unlimited quota that counts its calls, for services calling the mocked
+/// Data API.
+#[derive(Clone, Default)]
+pub struct FakeApiKeyStore {
+    pub used_quota: Arc<Mutex<i32>>,
+}
+
+#[async_trait]
+impl ApiKeyStore for FakeApiKeyStore {
+    async fn get_least_used_api_key(&self) -> Result<ApiKey, Error> {
+        Ok(ApiKey {
+            key: "test-key".to_string(),
+            used_quota: *self.used_quota.lock().unwrap(),
+            daily_quota: 10_000,
+            pdt_day: 0,
+            health: API_KEY_HEALTHY.to_string(),
+            consecutive_errors: 0,
+            last_error: None,
+            last_error_at: None,
+            disabled_at: None,
+            quota_exceeded_until: None,
+        })
+    }
+
+    async fn count_with_quota(&self, _now: i64) -> Result<u64, Error> {
+        Ok(1)
+    }
+
+    async fn set_quota_exceeded(&self, _api_key: &ApiKey, _until: i64) -> Result<(), Error> {
+        Ok(())
+    }
+
+    async fn record_success(&self, _api_key: &ApiKey) -> Result<(), Error> {
+        Ok(())
+    }
+
+    async fn record_error(
+        &self,
+        _api_key: &ApiKey,
+        _reason: ApiErrorReason,
+        _now: i64,
+    ) -> Result<&'static str, Error> {
+        Ok(API_KEY_HEALTHY)
+    }
+
+    async fn update_usage(&self, _api_key: &ApiKey) -> Result<(), Error> {
+        *self.used_quota.lock().unwrap() += 1;
+
+        Ok(())
+    }
+
+    async fn get_all(&self) -> Result<Vec<ApiKey>, Error> {
+        Err(unsupported())
+    }
+
+    async fn upsert(&self, _key: &str, _daily_quota: i32) -> Result<(), Error> {
+        Err(unsupported())
+    }
+}
+
+/// A quota breaker that starts closed.
+#[derive(Clone, Default)]
+pub struct FakeQuotaBreakerStore {
+    pub paused_until: Arc<Mutex<Option<i64>>>,
+}
+
+#[async_trait]
+impl QuotaBreakerStore for FakeQuotaBreakerStore {
+    async fn get_quota_paused_until(&self) -> Result<Option<i64>, Error> {
+        Ok(*self.paused_until.lock().unwrap())
+    }
+
+    async fn set_quota_paused_until(&self, paused_until: i64) -> Result<(), Error> {
+        *self.paused_until.lock().unwrap() = Some(paused_until);
+
+        Ok(())
+    }
+
+    async fn clear_quota_paused_until(&self) -> Result<(), Error> {
+        *self.paused_until.lock().unwrap() = None;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,204 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::json;
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use crate::models::{
+    youtube_channel_details::{YouTubeChannelDetails, YoutubeStatisticsItem},
+    youtube_playlist_items::YouTubePlaylistItems,
+};
+
+const FEED_PATH: &str = "/feeds/videos.xml";
+const API_PATH: &str = "/youtube/v3/";
+
+pub struct FeedVideo {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    /// RFC 3339 timestamps, tests can replace them with invalid ones
+    pub published: String,
+    pub updated: String,
+    pub views: i64,
+}
+
+impl FeedVideo {
+    pub fn new(id: &str, title: &str, published_at: DateTime<Utc>) -> FeedVideo {
+        FeedVideo {
+            id: id.to_string(),
+            title: title.to_string(),
+            description: String::new(),
+            published: published_at.to_rfc3339(),
+            updated: published_at.to_rfc3339(),
+            views: 0,
+        }
+    }
+}
+
+/// Serves canned video feeds and Data API responses on a local port, so scrapers and crawlers run
+/// without network or quota. A YouTube service calling the mock gets its key from a
+/// `FakeApiKeyStore`.
+pub struct MockYoutube {
+    server: MockServer,
+}
+
+impl MockYoutube {
+    pub async fn start() -> MockYoutube {
+        MockYoutube {
+            server: MockServer::start().await,
+        }
+    }
+
+    pub fn feed_base_url(&self) -> String {
+        format!("{}{}", self.server.uri(), FEED_PATH)
+    }
+
+    pub fn api_base_url(&self) -> String {
+        format!("{}{}", self.server.uri(), API_PATH)
+    }
+
+    /// Serves the feed with the given `ETag`.
+    pub async fn mount_feed(&self, channel_id: &str, videos: &[FeedVideo], etag: &str) {
+        let response = ResponseTemplate::new(200)
+            .insert_header("ETag", etag)
+            .set_body_raw(get_feed_xml(channel_id, videos), "text/xml");
+
+        Mock::given(method("GET"))
+            .and(path(FEED_PATH))
+            .and(query_param("channel_id", channel_id))
+            .respond_with(response)
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Answers conditional feed requests carrying the `ETag` with 304 Not Modified.
+    pub async fn mount_feed_not_modified(&self, channel_id: &str, etag: &str) {
+        Mock::given(method("GET"))
+            .and(path(FEED_PATH))
+            .and(query_param("channel_id", channel_id))
+            .and(header("If-None-Match", etag))
+            .respond_with(ResponseTemplate::new(304))
+            .with_priority(1)
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Rate limits the next `times` feed requests, before any other feed mock answers.
+    pub async fn mount_feed_rate_limited(&self, channel_id: &str, times: u64) {
+        Mock::given(method("GET"))
+            .and(path(FEED_PATH))
+            .and(query_param("channel_id", channel_id))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .up_to_n_times(times)
+            .with_priority(1)
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Answers calls of the Data API `resource`, e.g. `channels`, whose query has `param` set to
+    /// `value`.
+    pub async fn mount_api<T: Serialize>(
+        &self,
+        resource: &str,
+        param: &str,
+        value: &str,
+        response: &T,
+    ) {
+        Mock::given(method("GET"))
+            .and(path(format!("{}{}", API_PATH, resource)))
+            .and(query_param(param, value))
+            .respond_with(ResponseTemplate::new(200).set_body_json(response))
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Fails the calls like the Data API, with the error `reason`, e.g. `quotaExceeded`.
+    pub async fn mount_api_error(
+        &self,
+        resource: &str,
+        param: &str,
+        value: &str,
+        status: u16,
+        reason: &str,
+    ) {
+        let body = json!({
+            "error": {
+                "code": status,
+                "errors": [{"reason": reason}],
+            }
+        });
+
+        Mock::given(method("GET"))
+            .and(path(format!("{}{}", API_PATH, resource)))
+            .and(query_param(param, value))
+            .respond_with(ResponseTemplate::new(status).set_body_json(body))
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Serves `channels.list` of the channel.
+    pub async fn mount_channel_details(&self, channel: YoutubeStatisticsItem) {
+        let channel_id = channel.id.clone();
+        let response = YouTubeChannelDetails {
+            items: Some(vec![channel]),
+            ..YouTubeChannelDetails::default()
+        };
+
+        self.mount_api("channels", "id", &channel_id, &response)
+            .await;
+    }
+
+    /// Serves the first page of the uploads playlist of the channel.
+    pub async fn mount_uploads(&self, channel_id: &str, page: &YouTubePlaylistItems) {
+        let uploads_playlist_id = format!("UU{}", channel_id.trim_start_matches("UC"));
+
+        self.mount_api("playlistItems", "playlistId", &uploads_playlist_id, page)
+            .await;
+    }
+
+    /// Counts the calls of the Data API `resource`, each costing quota in production.
+    pub async fn received_api_requests(&self, resource: &str) -> usize {
+        let resource_path = format!("{}{}", API_PATH, resource);
+
+        self.server
+            .received_requests()
+            .await
+            .unwrap_or_default()
+            .iter()
+            .filter(|request| request.url.path() == resource_path)
+            .count()
+    }
+
+    pub async fn received_feed_requests(&self) -> usize {
+        self.server
+            .received_requests()
+            .await
+            .unwrap_or_default()
+            .iter()
+            .filter(|request| request.url.path() == FEED_PATH)
+            .count()
+    }
+}
+
+fn get_feed_xml(channel_id: &str, videos: &[FeedVideo]) -> String {
+    let entries = videos
+        .iter()
+        .map(|video| {
+            format!(
+                r#"<entry><id>yt:video:{id}</id><yt:videoId>{id}</yt:videoId><yt:channelId>{channel_id}</yt:channelId><title>{title}</title><published>{published}</published><updated>{updated}</updated><media:group><media:title>{title}</media:title><media:description>{description}</media:description><media:community><media:statistics views="{views}"/></media:community></media:group></entry>"#,
+                id = video.id,
+                channel_id = channel_id,
+                title = video.title,
+                published = video.published,
+                updated = video.updated,
+                description = video.description,
+                views = video.views
+            )
+        })
+        .collect::<String>();
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?><feed xmlns:yt="http://www.youtube.com/xml/schemas/2015" xmlns:media="http://search.yahoo.com/mrss/" xmlns="http://www.w3.org/2005/Atom"><title>{}</title>{}</feed>"#,
+        channel_id, entries
+    )
+}
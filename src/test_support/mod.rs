@@ -0,0 +1,2 @@
+pub mod fake_stores;
+pub mod mock_youtube;
@@ -0,0 +1,81 @@
+use std::collections::{BTreeMap, HashMap};
+
+// A quota pause shorter than this is routine and only goes to the webhooks
+pub const QUOTA_ALERT_AFTER_SECONDS: i64 = 60 * 60;
+// A queue that did not drain for this long counts as stuck
+pub const QUEUE_STUCK_AFTER_SECONDS: i64 = 30 * 60;
+
+/// Depth of a non-empty queue and since when it has not gone down.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueueProgress {
+    pub depth: usize,
+    pub since: i64,
+}
+
+/// Updates the progress of each queue with its current depth and returns the queues that have
+/// not gone down for `QUEUE_STUCK_AFTER_SECONDS`, with their depth and since when.
+pub fn get_stuck_queues(
+    progress: &mut HashMap<String, QueueProgress>,
+    depths: &BTreeMap<String, usize>,
+    now: i64,
+) -> Vec<(String, QueueProgress)> {
+    let mut stuck = vec![];
+
+    for (queue, depth) in depths {
+        if *depth == 0 {
+            progress.remove(queue);
+            continue;
+        }
+
+        let entry = progress.entry(queue.clone()).or_insert(QueueProgress {
+            depth: *depth,
+            since: now,
+        });
+        if *depth < entry.depth {
+            entry.since = now;
+        }
+        entry.depth = *depth;
+
+        if now - entry.since >= QUEUE_STUCK_AFTER_SECONDS {
+            stuck.push((queue.clone(), entry.clone()));
+        }
+    }
+
+    stuck
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+
+    use super::QUEUE_STUCK_AFTER_SECONDS;
+
+    #[test]
+    fn detects_queues_that_do_not_drain() {
+        let mut progress = HashMap::new();
+        let depths = |video: usize, channel: usize| {
+            BTreeMap::from([
+                ("videoScraper".to_string(), video),
+                ("channelScraper".to_string(), channel),
+            ])
+        };
+
+        assert!(super::get_stuck_queues(&mut progress, &depths(10, 5), 0).is_empty());
+        // The channel queue drains, the video queue grows
+        assert!(super::get_stuck_queues(&mut progress, &depths(20, 2), 600).is_empty());
+
+        let stuck =
+            super::get_stuck_queues(&mut progress, &depths(20, 1), QUEUE_STUCK_AFTER_SECONDS);
+        assert_eq!(stuck.len(), 1);
+        assert_eq!(stuck[0].0, "videoScraper");
+        assert_eq!(stuck[0].1.depth, 20);
+        assert_eq!(stuck[0].1.since, 0);
+
+        assert!(super::get_stuck_queues(
+            &mut progress,
+            &depths(0, 1),
+            QUEUE_STUCK_AFTER_SECONDS + 600
+        )
+        .is_empty());
+    }
+}
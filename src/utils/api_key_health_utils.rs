@@ -0,0 +1,62 @@
+use crate::errors::crawler_error::ApiErrorReason;
+
+pub const API_KEY_HEALTHY: &str = "healthy";
+pub const API_KEY_DEGRADED: &str = "degraded";
+pub const API_KEY_DISABLED: &str = "disabled";
+
+// Keys failing this many calls in a row are only used when no healthy key is left
+const DEGRADED_AFTER_ERRORS: i32 = 3;
+const UNMASKED_KEY_CHARS: usize = 4;
+
+/// Health of a key after a call failed for `reason`, `consecutive_errors` counting that call. An
+/// invalid key is disabled for good, a successful call makes a degraded key healthy again.
+pub fn get_health_after_error(reason: ApiErrorReason, consecutive_errors: i32) -> &'static str {
+    if reason == ApiErrorReason::KeyInvalid {
+        API_KEY_DISABLED
+    } else if consecutive_errors >= DEGRADED_AFTER_ERRORS {
+        API_KEY_DEGRADED
+    } else {
+        API_KEY_HEALTHY
+    }
+}
+
+/// Keeps the last characters of a key, enough to tell keys apart.
+pub fn mask_api_key(key: &str) -> String {
+    let chars = key.chars().collect::<Vec<char>>();
+    let visible = chars.len().saturating_sub(UNMASKED_KEY_CHARS);
+
+    chars
+        .iter()
+        .enumerate()
+        .map(|(i, c)| if i < visible { '*' } else { *c })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::errors::crawler_error::ApiErrorReason;
+
+    use super::{API_KEY_DEGRADED, API_KEY_DISABLED, API_KEY_HEALTHY};
+
+    #[test]
+    fn health_after_error() {
+        assert_eq!(
+            super::get_health_after_error(ApiErrorReason::BackendError, 1),
+            API_KEY_HEALTHY
+        );
+        assert_eq!(
+            super::get_health_after_error(ApiErrorReason::BackendError, 3),
+            API_KEY_DEGRADED
+        );
+        assert_eq!(
+            super::get_health_after_error(ApiErrorReason::KeyInvalid, 1),
+            API_KEY_DISABLED
+        );
+    }
+
+    #[test]
+    fn masks_all_but_the_last_chars() {
+        assert_eq!(super::mask_api_key("AIzaSyD-1234abcd"), "************abcd");
+        assert_eq!(super::mask_api_key("abc"), "abc");
+    }
+}
@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::sync::Notify;
+use tokio::time::{sleep_until, Instant};
+
+use crate::utils::consts::{CHANNEL_SOURCE_ADDITIONAL, CHANNEL_SOURCE_CLI, CHANNEL_SOURCE_IMPORT};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Classes of Data API calls, from the highest. Queued calls of a higher class always go first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ApiPriority {
+    /// Crawls an operator asked for, through the admin api or the CLI
+    Admin,
+    /// Keeping stored channels and videos up to date
+    Freshness,
+    /// Discovery and other background work
+    Discovery,
+}
+
+/// Who is calling the api, calls of one class are served round robin between callers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ApiCaller {
+    pub name: &'static str,
+    pub priority: ApiPriority,
+}
+
+impl ApiCaller {
+    pub fn new(name: &'static str, priority: ApiPriority) -> ApiCaller {
+        ApiCaller { name, priority }
+    }
+}
+
+/// The class of a channel crawl by its source: operator crawls are admin, refreshes of stored
+/// channels (no source) keep them fresh, discovered and requested channels are discovery.
+pub fn get_source_priority(source: Option<&str>) -> ApiPriority {
+    match source {
+        Some(CHANNEL_SOURCE_CLI)
+        | Some(CHANNEL_SOURCE_IMPORT)
+        | Some(CHANNEL_SOURCE_ADDITIONAL) => ApiPriority::Admin,
+        None => ApiPriority::Freshness,
+        Some(_) => ApiPriority::Discovery,
+    }
+}
+
+struct Ticket {
+    id: u64,
+    caller: ApiCaller,
+}
+
+#[derive(Default)]
+struct SchedulerState {
+    waiting: Vec<Ticket>,
+    next_ticket_id: u64,
+    grants: u64,
+    /// The grant number of the last call of each caller
+    last_granted: HashMap<&'static str, u64>,
+    next_call_at: Option<Instant>,
+}
+
+/// Spaces all YouTube api calls of the process by a minimum interval, spreading the daily quota
+/// over the day instead of spending it in bursts. Waiting calls are served by priority class,
+/// then round robin between callers, then in order.
+pub struct ApiScheduler {
+    min_interval: Duration,
+    state: Mutex<SchedulerState>,
+    notify: Notify,
+}
+
+/// Leaves the queue when the waiting call is dropped, so it cannot block the calls behind it.
+struct WaitingTicket<'a> {
+    scheduler: &'a ApiScheduler,
+    id: u64,
+}
+
+impl Drop for WaitingTicket<'_> {
+    fn drop(&mut self) {
+        let mut state = self.scheduler.state.lock().unwrap();
+        state.waiting.retain(|ticket| ticket.id != self.id);
+        drop(state);
+
+        self.scheduler.notify.notify_waiters();
+    }
+}
+
+impl ApiScheduler {
+    pub fn new(min_interval: Duration) -> ApiScheduler {
+        ApiScheduler {
+            min_interval,
+            state: Mutex::new(SchedulerState::default()),
+            notify: Notify::new(),
+        }
+    }
+
+    /// Waits until it is the turn of the caller to call the api.
+    pub async fn acquire(&self, caller: ApiCaller) {
+        let id = {
+            let mut state = self.state.lock().unwrap();
+            state.next_ticket_id += 1;
+            let id = state.next_ticket_id;
+            state.waiting.push(Ticket { id, caller });
+            id
+        };
+        let _ticket = WaitingTicket {
+            scheduler: self,
+            id,
+        };
+
+        loop {
+            // Created before checking the queue, so a grant in between still wakes this call
+            let notified = self.notify.notified();
+
+            let wait_until = {
+                let mut state = self.state.lock().unwrap();
+
+                if get_next_ticket(&state.waiting, &state.last_granted) != Some(id) {
+                    None
+                } else {
+                    let now = Instant::now();
+                    match state.next_call_at {
+                        Some(next_call_at) if next_call_at > now => Some(next_call_at),
+                        _ => {
+                            state.grants += 1;
+                            let grant = state.grants;
+                            state.last_granted.insert(caller.name, grant);
+                            state.next_call_at = Some(now + self.min_interval);
+                            return;
+                        }
+                    }
+                }
+            };
+
+            match wait_until {
+                // A call of a higher class may queue up meanwhile, so the turn is checked again
+                Some(next_call_at) => sleep_until(next_call_at).await,
+                None => notified.await,
+            }
+        }
+    }
+}
+
+fn get_next_ticket(waiting: &[Ticket], last_granted: &HashMap<&'static str, u64>) -> Option<u64> {
+    waiting
+        .iter()
+        .min_by_key(|ticket| {
+            (
+                ticket.caller.priority,
+                last_granted.get(ticket.caller.name).copied().unwrap_or(0),
+                ticket.id,
+            )
+        })
+        .map(|ticket| ticket.id)
+}
+
+/// The interval spreading `daily_calls` over a day, or the request interval if that is longer.
+/// Without a daily limit calls are only spaced by the request interval.
+pub fn get_pacing_interval(request_interval: Duration, daily_calls: u64) -> Duration {
+    if daily_calls == 0 {
+        return request_interval;
+    }
+
+    request_interval.max(Duration::from_secs_f64(
+        SECONDS_PER_DAY as f64 / daily_calls as f64,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use super::{ApiCaller, ApiPriority, Ticket};
+    use crate::utils::consts::{
+        CHANNEL_SOURCE_ADDITIONAL, CHANNEL_SOURCE_CLI, CHANNEL_SOURCE_DISCOVERY,
+        CHANNEL_SOURCE_END_SCREEN, CHANNEL_SOURCE_INGEST,
+    };
+
+    #[test]
+    fn channel_crawls_take_the_priority_of_their_source() {
+        assert_eq!(
+            super::get_source_priority(Some(CHANNEL_SOURCE_CLI)),
+            ApiPriority::Admin
+        );
+        assert_eq!(
+            super::get_source_priority(Some(CHANNEL_SOURCE_ADDITIONAL)),
+            ApiPriority::Admin
+        );
+        assert_eq!(super::get_source_priority(None), ApiPriority::Freshness);
+        assert_eq!(
+            super::get_source_priority(Some(CHANNEL_SOURCE_DISCOVERY)),
+            ApiPriority::Discovery
+        );
+        assert_eq!(
+            super::get_source_priority(Some(CHANNEL_SOURCE_END_SCREEN)),
+            ApiPriority::Discovery
+        );
+        assert_eq!(
+            super::get_source_priority(Some(CHANNEL_SOURCE_INGEST)),
+            ApiPriority::Discovery
+        );
+    }
+
+    fn ticket(id: u64, name: &'static str, priority: ApiPriority) -> Ticket {
+        Ticket {
+            id,
+            caller: ApiCaller::new(name, priority),
+        }
+    }
+
+    #[test]
+    fn serves_priority_then_callers_round_robin() {
+        let waiting = vec![
+            ticket(1, "discovery", ApiPriority::Discovery),
+            ticket(2, "videoScraper", ApiPriority::Freshness),
+            ticket(3, "reconciliation", ApiPriority::Freshness),
+        ];
+        let mut last_granted = HashMap::new();
+
+        assert_eq!(super::get_next_ticket(&waiting, &last_granted), Some(2));
+
+        last_granted.insert("videoScraper", 5);
+        last_granted.insert("reconciliation", 4);
+        assert_eq!(super::get_next_ticket(&waiting, &last_granted), Some(3));
+
+        let waiting = vec![
+            ticket(4, "discovery", ApiPriority::Discovery),
+            ticket(5, "channelScraper", ApiPriority::Admin),
+        ];
+        assert_eq!(super::get_next_ticket(&waiting, &last_granted), Some(5));
+        assert_eq!(super::get_next_ticket(&[], &last_granted), None);
+    }
+
+    #[test]
+    fn paces_daily_calls_over_the_day() {
+        assert_eq!(
+            super::get_pacing_interval(Duration::ZERO, 86400),
+            Duration::from_secs(1)
+        );
+        assert_eq!(
+            super::get_pacing_interval(Duration::from_secs(2), 86400),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            super::get_pacing_interval(Duration::from_millis(500), 0),
+            Duration::from_millis(500)
+        );
+    }
+}
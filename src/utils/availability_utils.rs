@@ -0,0 +1,98 @@
+use crate::{
+    models::youtube_video_details::YouTubeVideoItem,
+    utils::consts::{
+        VIDEO_AVAILABILITY_AVAILABLE, VIDEO_AVAILABILITY_DELETED, VIDEO_AVAILABILITY_PRIVATE,
+        VIDEO_AVAILABILITY_REGION_BLOCKED,
+    },
+};
+
+/// Classifies a video by its `videos.list` item. The api leaves out deleted videos, and private
+/// videos of other channels, so a missing item counts as deleted. Any region restriction counts
+/// as region blocked, the site decides per visitor.
+pub fn get_availability(item: Option<&YouTubeVideoItem>) -> &'static str {
+    let item = match item {
+        Some(item) => item,
+        None => return VIDEO_AVAILABILITY_DELETED,
+    };
+
+    if let Some(status) = &item.status {
+        if matches!(
+            status.upload_status.as_str(),
+            "deleted" | "failed" | "rejected"
+        ) {
+            return VIDEO_AVAILABILITY_DELETED;
+        }
+
+        if status.privacy_status == "private" {
+            return VIDEO_AVAILABILITY_PRIVATE;
+        }
+    }
+
+    let region_restriction = item
+        .content_details
+        .as_ref()
+        .and_then(|content_details| content_details.region_restriction.as_ref());
+
+    if let Some(region_restriction) = region_restriction {
+        let is_blocked = region_restriction.allowed.is_some()
+            || region_restriction
+                .blocked
+                .as_ref()
+                .is_some_and(|blocked| !blocked.is_empty());
+
+        if is_blocked {
+            return VIDEO_AVAILABILITY_REGION_BLOCKED;
+        }
+    }
+
+    VIDEO_AVAILABILITY_AVAILABLE
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::youtube_video_details::{
+        ContentDetails, RegionRestriction, VideoStatus, YouTubeVideoItem,
+    };
+
+    fn item(upload_status: &str, privacy_status: &str) -> YouTubeVideoItem {
+        YouTubeVideoItem {
+            status: Some(VideoStatus {
+                upload_status: upload_status.to_string(),
+                privacy_status: privacy_status.to_string(),
+                embeddable: None,
+            }),
+            ..YouTubeVideoItem::default()
+        }
+    }
+
+    #[test]
+    fn availability_from_status() {
+        assert_eq!(super::get_availability(None), "deleted");
+        assert_eq!(
+            super::get_availability(Some(&item("rejected", "public"))),
+            "deleted"
+        );
+        assert_eq!(
+            super::get_availability(Some(&item("processed", "private"))),
+            "private"
+        );
+        assert_eq!(
+            super::get_availability(Some(&item("processed", "unlisted"))),
+            "available"
+        );
+    }
+
+    #[test]
+    fn availability_from_region_restriction() {
+        let mut blocked = item("processed", "public");
+        blocked.content_details = Some(ContentDetails {
+            region_restriction: Some(RegionRestriction {
+                allowed: None,
+                blocked: Some(vec!["DE".to_string()]),
+            }),
+            ..ContentDetails::default()
+        });
+
+        assert_eq!(super::get_availability(Some(&blocked)), "regionBlocked");
+    }
+}
@@ -0,0 +1,319 @@
+use std::collections::HashSet;
+
+use image::imageops::FilterType;
+use mongodb::bson::{doc, DateTime, Document};
+
+use crate::utils::image_change_utils::{format_image_hash, parse_image_hash};
+
+pub const BAN_EVASION_SIGNAL_TITLE: &str = "title";
+pub const BAN_EVASION_SIGNAL_AVATAR: &str = "avatar";
+pub const BAN_EVASION_SIGNAL_VIDEOS: &str = "videos";
+
+pub const REVIEW_REASON_BAN_EVASION: &str = "banEvasion";
+
+// Share of the title words both channels have in common
+const TITLE_SIMILARITY_THRESHOLD: f64 = 0.6;
+// Avatar hashes differing in at most this many of their 64 bits show the same image
+pub const AVATAR_MAX_DISTANCE: u32 = 10;
+// Share of the recent video titles of the channel with fewer videos found on the other one
+const VIDEO_OVERLAP_THRESHOLD: f64 = 0.5;
+const MIN_SIGNALS: usize = 2;
+
+/// What a channel is recognized by after a reupload: its title, avatar and recent video titles.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelFingerprint {
+    pub title_words: Vec<String>,
+    /// Difference hash of the avatar, see `get_avatar_hash`
+    pub avatar_hash: Option<u64>,
+    /// Avatars the channel used before, a reupload may bring back any of them
+    pub previous_avatar_hashes: Vec<u64>,
+    pub video_titles: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BanEvasionMatch {
+    /// The blocklist entry, either a channel id or a handle
+    pub blocked_channel: String,
+    /// Mean similarity of the title, avatar and videos, from 0 to 1
+    pub score: f64,
+    pub signals: Vec<&'static str>,
+}
+
+pub fn get_channel_fingerprint(
+    title: &str,
+    avatar_hash: Option<u64>,
+    video_titles: &[&str],
+) -> ChannelFingerprint {
+    let mut title_words = normalize(title)
+        .split(' ')
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_string())
+        .collect::<Vec<String>>();
+    title_words.sort();
+    title_words.dedup();
+
+    ChannelFingerprint {
+        title_words,
+        avatar_hash,
+        previous_avatar_hashes: vec![],
+        video_titles: video_titles
+            .iter()
+            .map(|title| normalize(title))
+            .filter(|title| !title.is_empty())
+            .collect(),
+    }
+}
+
+/// Hashes the avatar image by whether each pixel of a 9x8 grayscale scaling is darker than its
+/// right neighbour, so re-encoded or resized copies get the same or a close hash.
+pub fn get_avatar_hash(image_bytes: &[u8]) -> Option<u64> {
+    let image = image::load_from_memory(image_bytes)
+        .ok()?
+        .resize_exact(9, 8, FilterType::Triangle)
+        .to_luma8();
+
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if image.get_pixel(x, y)[0] < image.get_pixel(x + 1, y)[0] {
+                hash |= 1;
+            }
+        }
+    }
+
+    Some(hash)
+}
+
+/// Returns the blocked channel the candidate matches best on at least two signals.
+pub fn find_ban_evasion(
+    candidate: &ChannelFingerprint,
+    blocked: &[(String, ChannelFingerprint)],
+) -> Option<BanEvasionMatch> {
+    blocked
+        .iter()
+        .filter_map(|(blocked_channel, fingerprint)| {
+            let title_similarity = get_overlap(&candidate.title_words, &fingerprint.title_words)
+                * 2.0
+                / (candidate.title_words.len() + fingerprint.title_words.len()).max(1) as f64;
+            let avatar_distance = candidate.avatar_hash.and_then(|candidate_hash| {
+                fingerprint
+                    .avatar_hash
+                    .iter()
+                    .chain(&fingerprint.previous_avatar_hashes)
+                    .map(|hash| (candidate_hash ^ hash).count_ones())
+                    .min()
+            });
+            let video_overlap = get_overlap(&candidate.video_titles, &fingerprint.video_titles)
+                / candidate
+                    .video_titles
+                    .len()
+                    .min(fingerprint.video_titles.len())
+                    .max(1) as f64;
+
+            let mut signals = vec![];
+            if title_similarity >= TITLE_SIMILARITY_THRESHOLD {
+                signals.push(BAN_EVASION_SIGNAL_TITLE);
+            }
+            if avatar_distance.is_some_and(|distance| distance <= AVATAR_MAX_DISTANCE) {
+                signals.push(BAN_EVASION_SIGNAL_AVATAR);
+            }
+            if video_overlap >= VIDEO_OVERLAP_THRESHOLD {
+                signals.push(BAN_EVASION_SIGNAL_VIDEOS);
+            }
+
+            if signals.len() < MIN_SIGNALS {
+                return None;
+            }
+
+            let avatar_similarity = avatar_distance
+                .map(|distance| 1.0 - distance as f64 / 64.0)
+                .unwrap_or(0.0);
+
+            Some(BanEvasionMatch {
+                blocked_channel: blocked_channel.to_string(),
+                score: (title_similarity + avatar_similarity + video_overlap) / 3.0,
+                signals,
+            })
+        })
+        .max_by(|a, b| a.score.total_cmp(&b.score))
+}
+
+pub fn get_fingerprint_document(fingerprint: &ChannelFingerprint) -> Document {
+    doc! {
+        "titleWords": &fingerprint.title_words,
+        "avatarHash": fingerprint.avatar_hash.map(format_image_hash),
+        "previousAvatarHashes": fingerprint
+            .previous_avatar_hashes
+            .iter()
+            .map(|hash| format_image_hash(*hash))
+            .collect::<Vec<String>>(),
+        "videoTitles": &fingerprint.video_titles,
+    }
+}
+
+pub fn parse_fingerprint(fingerprint: &Document) -> Option<ChannelFingerprint> {
+    let get_strings = |key| -> Option<Vec<String>> {
+        Some(
+            fingerprint
+                .get_array(key)
+                .ok()?
+                .iter()
+                .filter_map(|value| value.as_str().map(|s| s.to_string()))
+                .collect(),
+        )
+    };
+
+    Some(ChannelFingerprint {
+        title_words: get_strings("titleWords")?,
+        avatar_hash: fingerprint
+            .get_str("avatarHash")
+            .ok()
+            .and_then(parse_image_hash),
+        // Missing on fingerprints taken before avatar changes were tracked
+        previous_avatar_hashes: get_strings("previousAvatarHashes")
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|hash| parse_image_hash(hash))
+            .collect(),
+        video_titles: get_strings("videoTitles")?,
+    })
+}
+
+pub fn get_ban_evasion_document(ban_evasion: &BanEvasionMatch) -> Document {
+    doc! {
+        "blockedChannel": &ban_evasion.blocked_channel,
+        "score": ban_evasion.score,
+        "signals": &ban_evasion.signals,
+        "detectedAt": DateTime::now(),
+    }
+}
+
+/// The review queue candidate of a channel that likely evades a ban.
+pub fn get_ban_evasion_review_document(
+    title: &str,
+    description: &str,
+    ban_evasion: &BanEvasionMatch,
+) -> Document {
+    doc! {
+        "title": title,
+        "description": description,
+        "score": ban_evasion.score,
+        "evidence": {
+            "reason": REVIEW_REASON_BAN_EVASION,
+            "blockedChannel": &ban_evasion.blocked_channel,
+            "signals": &ban_evasion.signals,
+        },
+    }
+}
+
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+fn get_overlap(a: &[String], b: &[String]) -> f64 {
+    let b = b.iter().collect::<HashSet<&String>>();
+
+    a.iter()
+        .collect::<HashSet<&String>>()
+        .iter()
+        .filter(|value| b.contains(*value))
+        .count() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{DynamicImage, GrayImage, ImageOutputFormat, Luma};
+    use std::io::Cursor;
+
+    use super::{
+        get_channel_fingerprint, BAN_EVASION_SIGNAL_AVATAR, BAN_EVASION_SIGNAL_TITLE,
+        BAN_EVASION_SIGNAL_VIDEOS,
+    };
+
+    fn png(size: u32) -> Vec<u8> {
+        // Brightening to the right in the upper half, darkening in the lower half
+        let image = GrayImage::from_fn(size, size, |x, y| {
+            let brightness = (x * 255 / size) as u8;
+            Luma([if y < size / 2 {
+                brightness
+            } else {
+                255 - brightness
+            }])
+        });
+        let mut bytes = Cursor::new(vec![]);
+        DynamicImage::ImageLuma8(image)
+            .write_to(&mut bytes, ImageOutputFormat::Png)
+            .unwrap();
+
+        bytes.into_inner()
+    }
+
+    #[test]
+    fn avatar_hash_survives_resizing() {
+        let small = super::get_avatar_hash(&png(88)).unwrap();
+        let large = super::get_avatar_hash(&png(240)).unwrap();
+
+        assert!((small ^ large).count_ones() <= 10);
+        assert_eq!(super::get_avatar_hash(b"not an image"), None);
+    }
+
+    #[test]
+    fn finds_reuploads_on_two_signals() {
+        let blocked = vec![(
+            "UCblocked".to_string(),
+            get_channel_fingerprint(
+                "Shred Guitar Lessons",
+                Some(0xff00ff00ff00ff00),
+                &["Sweep Picking 101", "Fastest Solo Ever!", "Tapping Lesson"],
+            ),
+        )];
+
+        let reupload = get_channel_fingerprint(
+            "shred guitar lessons 2",
+            Some(0xff00ff00ff00ff01),
+            &["sweep picking 101", "New intro"],
+        );
+        let ban_evasion = super::find_ban_evasion(&reupload, &blocked).unwrap();
+        assert_eq!(ban_evasion.blocked_channel, "UCblocked");
+        assert_eq!(
+            ban_evasion.signals,
+            vec![
+                BAN_EVASION_SIGNAL_TITLE,
+                BAN_EVASION_SIGNAL_AVATAR,
+                BAN_EVASION_SIGNAL_VIDEOS
+            ]
+        );
+
+        let same_title_only =
+            get_channel_fingerprint("Shred Guitar Lessons", None, &["Jazz Chords"]);
+        assert!(super::find_ban_evasion(&same_title_only, &blocked).is_none());
+
+        let mut rebranded = blocked.clone();
+        rebranded[0].1.avatar_hash = Some(0);
+        rebranded[0].1.previous_avatar_hashes = vec![0xff00ff00ff00ff00];
+        let old_avatar = get_channel_fingerprint(
+            "Shred Guitar Lessons",
+            Some(0xff00ff00ff00ff00),
+            &["Jazz Chords"],
+        );
+        assert!(super::find_ban_evasion(&old_avatar, &rebranded).is_some());
+    }
+
+    #[test]
+    fn fingerprint_document_roundtrip() {
+        let mut fingerprint = get_channel_fingerprint("Blues Lab", Some(u64::MAX), &["Slow Blues"]);
+        fingerprint.previous_avatar_hashes = vec![7];
+
+        assert_eq!(
+            super::parse_fingerprint(&super::get_fingerprint_document(&fingerprint)),
+            Some(fingerprint)
+        );
+    }
+}
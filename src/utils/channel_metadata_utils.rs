@@ -0,0 +1,74 @@
+use mongodb::bson::{doc, Document};
+
+use crate::models::youtube_channel_details::YoutubeStatisticsItem;
+
+/// Returns the fields of a `channels.list` item that differ from the stored channel, as the
+/// channel scraper stores them. Unchanged fields are left out.
+pub fn get_metadata_changes(stored: &Document, item: &YoutubeStatisticsItem) -> Document {
+    let mut metadata = doc! {
+        "title": &item.snippet.title,
+        "description": item.snippet.description.as_deref().unwrap_or_default(),
+        "thumbnail": &item.snippet.thumbnails.default.url,
+    };
+
+    if let Some(custom_url) = &item.snippet.custom_url {
+        if custom_url.starts_with('@') {
+            metadata.insert("handle", custom_url.to_lowercase());
+        }
+
+        metadata.insert("customUrl", custom_url);
+    }
+
+    metadata
+        .into_iter()
+        .filter(|(field, value)| stored.get(field) != Some(value))
+        .collect()
+}
+
+/// The `@handle` of a `channels.list` item, from its custom url.
+pub fn get_handle(item: &YoutubeStatisticsItem) -> Option<&str> {
+    item.snippet
+        .custom_url
+        .as_deref()
+        .filter(|custom_url| custom_url.starts_with('@'))
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::doc;
+
+    use crate::models::youtube_channel_details::YoutubeStatisticsItem;
+
+    #[test]
+    fn returns_changed_fields_only() {
+        let mut item = YoutubeStatisticsItem::default();
+        item.snippet.title = "Andy Guitar Lessons".to_string();
+        item.snippet.description = Some("Free lessons".to_string());
+        item.snippet.thumbnails.default.url = "https://yt3.ggpht.com/avatar".to_string();
+        item.snippet.custom_url = Some("@AndyGuitar".to_string());
+
+        let stored = doc! {
+            "title": "Andy Guitar",
+            "description": "Free lessons",
+            "thumbnail": "https://yt3.ggpht.com/avatar",
+            "handle": "@andyguitar",
+        };
+
+        assert_eq!(
+            super::get_metadata_changes(&stored, &item),
+            doc! {"title": "Andy Guitar Lessons", "customUrl": "@AndyGuitar"}
+        );
+    }
+
+    #[test]
+    fn handle_from_custom_url() {
+        let mut item = YoutubeStatisticsItem::default();
+        assert_eq!(super::get_handle(&item), None);
+
+        item.snippet.custom_url = Some("andyguitar".to_string());
+        assert_eq!(super::get_handle(&item), None);
+
+        item.snippet.custom_url = Some("@AndyGuitar".to_string());
+        assert_eq!(super::get_handle(&item), Some("@AndyGuitar"));
+    }
+}
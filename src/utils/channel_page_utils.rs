@@ -0,0 +1,63 @@
+// Embedded by channel pages only, consent walls and error pages lack it
+const INITIAL_DATA_MARKER: &str = "ytInitialData";
+// Markers in the initial data embedded in the channel page, the Data API does not expose them
+const VERIFIED_BADGE_MARKERS: [&str; 2] = [
+    "\"BADGE_STYLE_TYPE_VERIFIED\"",
+    "\"BADGE_STYLE_TYPE_VERIFIED_ARTIST\"",
+];
+const MEMBERSHIP_MARKERS: [&str; 2] = ["\"sponsorButton\"", "\"joinButton\""];
+const MONETIZATION_MARKERS: [&str; 2] = [
+    "{\"key\":\"is_monetization_enabled\",\"value\":\"true\"}",
+    "\"is_monetization_enabled\":true",
+];
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChannelPageHints {
+    pub is_verified: bool,
+    /// The channel offers memberships through a join button
+    pub has_memberships: bool,
+    pub is_monetized: bool,
+}
+
+/// Returns `None` for pages without the initial data, whose missing markers say nothing.
+pub fn parse_channel_page(html: &str) -> Option<ChannelPageHints> {
+    if !html.contains(INITIAL_DATA_MARKER) {
+        return None;
+    }
+
+    let contains_any = |markers: &[&str]| markers.iter().any(|marker| html.contains(marker));
+
+    Some(ChannelPageHints {
+        is_verified: contains_any(&VERIFIED_BADGE_MARKERS),
+        has_memberships: contains_any(&MEMBERSHIP_MARKERS),
+        is_monetized: contains_any(&MONETIZATION_MARKERS),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChannelPageHints;
+
+    #[test]
+    fn parse_channel_page_hints() {
+        let html = r#"<script>var ytInitialData = {"badges":[{"metadataBadgeRenderer":{"style":"BADGE_STYLE_TYPE_VERIFIED"}}],"sponsorButton":{}};</script>
+            <script>ytcfg.set({"params":[{"key":"is_monetization_enabled","value":"true"}]});</script>"#;
+
+        assert_eq!(
+            super::parse_channel_page(html),
+            Some(ChannelPageHints {
+                is_verified: true,
+                has_memberships: true,
+                is_monetized: true,
+            })
+        );
+        assert_eq!(
+            super::parse_channel_page("<script>var ytInitialData = {};</script>"),
+            Some(ChannelPageHints::default())
+        );
+        assert_eq!(
+            super::parse_channel_page("<form action=\"https://consent.youtube.com/save\"></form>"),
+            None
+        );
+    }
+}
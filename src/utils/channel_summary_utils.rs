@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+use mongodb::bson::{doc, Document};
+
+pub const LATEST_VIDEO_COUNT: usize = 5;
+pub const TOP_TAG_COUNT: usize = 10;
+
+/// Returns the ids of the newest videos of `(video id, published timestamp)` pairs.
+pub fn get_latest_video_ids(videos: &[(String, i64)]) -> Vec<String> {
+    let mut videos = videos.to_vec();
+    videos.sort_by_key(|(_, published)| std::cmp::Reverse(*published));
+
+    videos
+        .into_iter()
+        .take(LATEST_VIDEO_COUNT)
+        .map(|(video_id, _)| video_id)
+        .collect()
+}
+
+/// Builds the upload fields of a channel summary, the views are added by their change.
+pub fn get_channel_summary_document(
+    video_count: i64,
+    upload_interval_seconds: Option<i64>,
+    latest_video_ids: Vec<String>,
+) -> Document {
+    doc! {
+        "videoCount": video_count,
+        "uploadIntervalSeconds": upload_interval_seconds,
+        "latestVideoIds": latest_video_ids,
+    }
+}
+
+/// The change of the total views by the `(video id, views)` of the stored videos, new videos
+/// count with all their views.
+pub fn get_views_delta(previous_views: &HashMap<String, i64>, stored: &[(String, i64)]) -> i64 {
+    stored
+        .iter()
+        .map(|(video_id, views)| views - previous_views.get(video_id).copied().unwrap_or(0))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use mongodb::bson::Bson;
+
+    #[test]
+    fn summarize_latest_videos_and_views() {
+        let videos = (0..8)
+            .map(|i| (format!("video{}", i), 1650000000 + i * 3600))
+            .collect::<Vec<(String, i64)>>();
+
+        assert_eq!(
+            super::get_latest_video_ids(&videos),
+            vec!["video7", "video6", "video5", "video4", "video3"]
+        );
+
+        let summary = super::get_channel_summary_document(4, Some(86400), vec![]);
+        assert_eq!(summary.get_i64("videoCount").unwrap(), 4);
+        assert_eq!(summary.get_i64("uploadIntervalSeconds").unwrap(), 86400);
+
+        let summary = super::get_channel_summary_document(0, None, vec![]);
+        assert_eq!(summary.get("uploadIntervalSeconds"), Some(&Bson::Null));
+
+        let previous_views =
+            HashMap::from([("video1".to_string(), 100), ("video2".to_string(), 50)]);
+        let stored = vec![("video1".to_string(), 120), ("video3".to_string(), 30)];
+        assert_eq!(super::get_views_delta(&previous_views, &stored), 50);
+    }
+}
@@ -0,0 +1,113 @@
+use regex::Regex;
+
+// YouTube only shows chapters from at least three timestamps starting at 0:00
+const MIN_CHAPTERS: usize = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chapter {
+    pub start_seconds: i64,
+    pub title: String,
+}
+
+/// Extracts the chapters of a video from the timestamped lines of its description, like
+/// `0:00 Intro` or `[2:13] - Verse riff`.
+pub struct ChapterParser {
+    line_regex: Regex,
+}
+
+impl ChapterParser {
+    pub fn new() -> ChapterParser {
+        ChapterParser {
+            line_regex: Regex::new(
+                r"^\s*(?:\d+[.)]\s+)?[\[(]?((?:\d{1,2}:)?\d{1,2}:\d{2})[\])]?\s*[-–—:|]?\s*(\S.*?)\s*$",
+            )
+            .unwrap(),
+        }
+    }
+
+    /// Returns no chapters unless the timestamps start at 0:00 and keep ascending, like YouTube.
+    pub fn parse(&self, description: &str) -> Vec<Chapter> {
+        let chapters = description
+            .lines()
+            .filter_map(|line| {
+                let captures = self.line_regex.captures(line)?;
+
+                Some(Chapter {
+                    start_seconds: parse_timestamp(&captures[1])?,
+                    title: captures[2].to_string(),
+                })
+            })
+            .collect::<Vec<Chapter>>();
+
+        let starts_at_zero = chapters.first().map(|c| c.start_seconds) == Some(0);
+        let is_ascending = chapters
+            .windows(2)
+            .all(|pair| pair[0].start_seconds < pair[1].start_seconds);
+
+        if chapters.len() < MIN_CHAPTERS || !starts_at_zero || !is_ascending {
+            return vec![];
+        }
+
+        chapters
+    }
+}
+
+impl Default for ChapterParser {
+    fn default() -> Self {
+        ChapterParser::new()
+    }
+}
+
+fn parse_timestamp(timestamp: &str) -> Option<i64> {
+    let mut seconds = 0;
+
+    for (index, part) in timestamp.split(':').enumerate() {
+        let value = part.parse::<i64>().ok()?;
+
+        // Minutes and seconds after the first part stay below 60
+        if index > 0 && value >= 60 {
+            return None;
+        }
+
+        seconds = seconds * 60 + value;
+    }
+
+    Some(seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Chapter, ChapterParser};
+
+    #[test]
+    fn parse_chapters() {
+        let description = "Today's lesson\n\n0:00 Intro\n[2:13] - Verse riff\n1:02:05 Solo\n\nGear: 1:00 is not a chapter";
+
+        assert_eq!(
+            ChapterParser::new().parse(description),
+            vec![
+                Chapter {
+                    start_seconds: 0,
+                    title: "Intro".to_string()
+                },
+                Chapter {
+                    start_seconds: 133,
+                    title: "Verse riff".to_string()
+                },
+                Chapter {
+                    start_seconds: 3725,
+                    title: "Solo".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn ignore_incomplete_chapters() {
+        let parser = ChapterParser::new();
+
+        assert!(parser.parse("0:00 Intro\n2:13 Riff").is_empty());
+        assert!(parser.parse("0:30 Intro\n2:13 Riff\n4:00 Solo").is_empty());
+        assert!(parser.parse("0:00 Intro\n4:00 Solo\n2:13 Riff").is_empty());
+    }
+}
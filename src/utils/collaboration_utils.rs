@@ -0,0 +1,88 @@
+use regex::Regex;
+
+use crate::utils::youtube_url_utils::{parse_youtube_url, YoutubeResource};
+
+pub const COLLAB_KIND_FEATURE: &str = "feature";
+pub const COLLAB_KIND_LINK: &str = "link";
+pub const COLLAB_KIND_HANDLE: &str = "handle";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mention {
+    /// Channel id or lowercase `@handle`
+    pub channel: String,
+    pub kind: &'static str,
+}
+
+/// Finds other channels mentioned in a video: `feat. @handle` in the title, channel links and
+/// plain `@handles`. Each channel is returned once with its strongest kind.
+pub fn detect_mentions(title: &str, description: &str) -> Vec<Mention> {
+    let feature_regex =
+        Regex::new(r"(?i)\b(?:feat\.?|ft\.|featuring|with)\s+(@[A-Za-z0-9._-]{3,30})").unwrap();
+    let link_regex =
+        Regex::new(r"(?i)(?:https?://)?(?:www\.|m\.)?youtube\.com/(?:channel/UC[A-Za-z0-9_-]{22}|@[A-Za-z0-9._-]{3,30})")
+            .unwrap();
+    let handle_regex = Regex::new(r"(?:^|[\s(,])(@[A-Za-z0-9._-]{3,30})").unwrap();
+
+    let mut mentions: Vec<Mention> = vec![];
+
+    for captures in feature_regex.captures_iter(title) {
+        add_mention(&mut mentions, &captures[1], COLLAB_KIND_FEATURE);
+    }
+
+    for text in [title, description] {
+        for link in link_regex.find_iter(text) {
+            add_mention(&mut mentions, link.as_str(), COLLAB_KIND_LINK);
+        }
+
+        for captures in handle_regex.captures_iter(text) {
+            add_mention(&mut mentions, &captures[1], COLLAB_KIND_HANDLE);
+        }
+    }
+
+    mentions
+}
+
+fn add_mention(mentions: &mut Vec<Mention>, value: &str, kind: &'static str) {
+    let channel = match parse_youtube_url(value.trim_end_matches('.')) {
+        Some(YoutubeResource::Channel(channel_id)) => channel_id,
+        Some(YoutubeResource::Handle(handle)) => handle,
+        _ => return,
+    };
+
+    // Kinds are added from strongest to weakest, so the first one found is kept
+    if !mentions.iter().any(|mention| mention.channel == channel) {
+        mentions.push(Mention { channel, kind });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Mention, COLLAB_KIND_FEATURE, COLLAB_KIND_HANDLE, COLLAB_KIND_LINK};
+
+    #[test]
+    fn detects_features_links_and_handles() {
+        let mentions = super::detect_mentions(
+            "Blues jam feat. @GuitarFriend",
+            "Check out https://www.youtube.com/channel/UCuAXFkgsw1L7xaCfnd5JJOw and @bassplayer.\n\
+            Mixed by @GuitarFriend, contact mail@example.com",
+        );
+
+        assert_eq!(
+            mentions,
+            vec![
+                Mention {
+                    channel: "@guitarfriend".to_string(),
+                    kind: COLLAB_KIND_FEATURE,
+                },
+                Mention {
+                    channel: "UCuAXFkgsw1L7xaCfnd5JJOw".to_string(),
+                    kind: COLLAB_KIND_LINK,
+                },
+                Mention {
+                    channel: "@bassplayer".to_string(),
+                    kind: COLLAB_KIND_HANDLE,
+                },
+            ]
+        );
+    }
+}
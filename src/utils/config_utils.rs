@@ -0,0 +1,431 @@
+use std::net::IpAddr;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error};
+use figment::{
+    providers::{Env, Format, Json, Toml, Yaml},
+    Figment,
+};
+use log::LevelFilter;
+use reqwest::{Proxy, Url};
+
+use crate::classifiers::genre_classifier::build_genre_classifier;
+use crate::classifiers::video_type_classifier::build_video_type_classifier;
+use crate::models::config::Config;
+use crate::notifications::smtp_client::{
+    SMTP_SECURITY_NONE, SMTP_SECURITY_STARTTLS, SMTP_SECURITY_TLS,
+};
+use crate::notifications::webhook_notifier::{
+    WEBHOOK_FORMAT_DISCORD, WEBHOOK_FORMAT_JSON, WEBHOOK_FORMAT_SLACK,
+};
+use crate::services::song_recognition_service::SongRecognitionService;
+use crate::utils::consts::{
+    CACHE_BACKEND_MEMORY, CACHE_BACKEND_REDIS, CHANGE_SINK_KAFKA, CHANGE_SINK_NATS,
+    QUEUE_BACKEND_LOCAL, QUEUE_BACKEND_REDIS, STORAGE_BACKEND_MONGODB, STORAGE_BACKEND_POSTGRES,
+};
+
+/// Merges `config.json`, `config.toml` and `config.yaml` (later files win), then environment
+/// overrides. Nested keys are overridden with `CRAWLER_` variables split on `__`, e.g.
+/// `CRAWLER_INTERVALS__NEW_VIDEO=600`.
+pub fn load_config() -> Result<Config, Error> {
+    let config = Figment::new()
+        .merge(Json::file("config.json"))
+        .merge(Toml::file("config.toml"))
+        .merge(Yaml::file("config.yaml"))
+        .merge(Env::raw().only(&["MONGO_CONNECTION_STRING"]))
+        .merge(Env::prefixed("CRAWLER_").split("__"))
+        .extract()?;
+
+    Ok(config)
+}
+
+/// Reports all problems at once, so a broken deployment does not need one restart per mistake.
+pub fn validate_config(config: &Config) -> Result<(), Error> {
+    let mut problems = vec![];
+
+    if config.environment.is_empty() {
+        problems.push("environment must not be empty".to_string());
+    }
+
+    if LevelFilter::from_str(&config.log_level).is_err() {
+        problems.push(format!("log_level {} is not a log level", config.log_level));
+    }
+
+    for (name, url) in [
+        ("youtube.api_base_url", &config.youtube.api_base_url),
+        ("youtube.feed_base_url", &config.youtube.feed_base_url),
+        (
+            "youtube.timed_text_base_url",
+            &config.youtube.timed_text_base_url,
+        ),
+        (
+            "youtube.channel_page_base_url",
+            &config.youtube.channel_page_base_url,
+        ),
+        (
+            "youtube.watch_page_base_url",
+            &config.youtube.watch_page_base_url,
+        ),
+    ] {
+        if Url::parse(url).is_err() {
+            problems.push(format!("{} {} is not a valid url", name, url));
+        }
+    }
+
+    let intervals = &config.intervals;
+    for (name, seconds) in [
+        ("additional", intervals.additional),
+        ("discovery", intervals.discovery),
+        ("channel_update", intervals.channel_update),
+        ("new_video", intervals.new_video),
+        ("backfill", intervals.backfill),
+        ("captions", intervals.captions),
+        ("about", intervals.about),
+        ("corpus_snapshot", intervals.corpus_snapshot),
+        ("video_archive", intervals.video_archive),
+        ("reclassification", intervals.reclassification),
+        ("reconciliation", intervals.reconciliation),
+        ("related_channels", intervals.related_channels),
+        ("lifecycle", intervals.lifecycle),
+        ("duplicates", intervals.duplicates),
+        ("topic_drift", intervals.topic_drift),
+        ("stats_aggregation", intervals.stats_aggregation),
+        ("comments", intervals.comments),
+        ("comment_sentiment", intervals.comment_sentiment),
+        ("ban_evasion", intervals.ban_evasion),
+        ("link_verification", intervals.link_verification),
+        ("upload_pattern", intervals.upload_pattern),
+        ("metadata_refresh", intervals.metadata_refresh),
+        ("genre_tags", intervals.genre_tags),
+        ("reporting", intervals.reporting),
+        ("alerts", intervals.alerts),
+        ("end_screens", intervals.end_screens),
+        ("probation", intervals.probation),
+        ("courses", intervals.courses),
+    ] {
+        if seconds == 0 {
+            problems.push(format!("intervals.{} must be greater than 0", name));
+        }
+    }
+
+    if config.rate_limit.feed_requests_per_second <= 0.0 {
+        problems.push("rate_limit.feed_requests_per_second must be greater than 0".to_string());
+    }
+
+    let policy = &config.scrape_policy;
+    let age_thresholds = [
+        ("new_video_seconds", policy.new_video_seconds),
+        ("week_old_video_seconds", policy.week_old_video_seconds),
+        ("month_old_video_seconds", policy.month_old_video_seconds),
+        (
+            "half_year_old_video_seconds",
+            policy.half_year_old_video_seconds,
+        ),
+    ];
+    for (name, seconds) in age_thresholds
+        .iter()
+        .chain([("hot_video_seconds", policy.hot_video_seconds)].iter())
+    {
+        if *seconds <= 0 {
+            problems.push(format!("scrape_policy.{} must be greater than 0", name));
+        }
+    }
+    // Older videos change less, so they must not be updated more often than younger ones
+    for pair in age_thresholds.windows(2) {
+        if pair[1].1 < pair[0].1 {
+            problems.push(format!(
+                "scrape_policy.{} must not be less than scrape_policy.{}",
+                pair[1].0, pair[0].0
+            ));
+        }
+    }
+    if policy.hot_views_per_hour <= 0.0 {
+        problems.push("scrape_policy.hot_views_per_hour must be greater than 0".to_string());
+    }
+
+    for proxy_url in &config.proxy.urls {
+        if Proxy::all(proxy_url).is_err() {
+            problems.push(format!("proxy url {} is not a valid proxy", proxy_url));
+        }
+    }
+
+    for webhook in &config.notifications.webhooks {
+        if Url::parse(&webhook.url).is_err() {
+            problems.push(format!("webhook url {} is not a valid url", webhook.url));
+        }
+
+        if ![
+            WEBHOOK_FORMAT_JSON,
+            WEBHOOK_FORMAT_DISCORD,
+            WEBHOOK_FORMAT_SLACK,
+        ]
+        .contains(&webhook.format.as_str())
+        {
+            problems.push(format!("webhook format {} is unknown", webhook.format));
+        }
+    }
+
+    if config.monitoring.backpressure && config.monitoring.overload_pool_usage <= 0.0 {
+        problems.push("monitoring.overload_pool_usage must be greater than 0".to_string());
+    }
+
+    if let Some(email) = &config.notifications.email {
+        if ![
+            SMTP_SECURITY_STARTTLS,
+            SMTP_SECURITY_TLS,
+            SMTP_SECURITY_NONE,
+        ]
+        .contains(&email.security.as_str())
+        {
+            problems.push(format!(
+                "notifications.email.security {} is unknown",
+                email.security
+            ));
+        }
+
+        if email.to.is_empty() {
+            problems.push("notifications.email.to must not be empty".to_string());
+        }
+
+        if email.username.is_some() != email.password.is_some() {
+            problems
+                .push("notifications.email.username and password must be set together".to_string());
+        }
+    }
+
+    if config.notifications.crawl_failure_threshold == 0 {
+        problems.push("notifications.crawl_failure_threshold must be greater than 0".to_string());
+    }
+
+    if let Err(e) = SongRecognitionService::new(&config.song_recognition.rules) {
+        problems.push(format!("song_recognition.rules are invalid: {}", e));
+    }
+
+    if let Err(e) = build_video_type_classifier(&config.classification.video_type_classifier) {
+        problems.push(format!("classification.video_type_classifier: {}", e));
+    }
+
+    if let Err(e) = build_genre_classifier(&config.classification.genre_classifier) {
+        problems.push(format!("classification.genre_classifier: {}", e));
+    }
+
+    if config.probation.enabled && config.probation.days <= 0 {
+        problems.push("probation.days must be greater than 0".to_string());
+    }
+
+    if config.api_keys.iter().any(|key| key.trim().is_empty()) {
+        problems.push("api_keys must not contain empty keys".to_string());
+    }
+
+    match config.storage.backend.as_str() {
+        STORAGE_BACKEND_MONGODB => {
+            if !config.mongo_connection_string.starts_with("mongodb://")
+                && !config.mongo_connection_string.starts_with("mongodb+srv://")
+            {
+                problems.push(
+                    "mongo_connection_string must be a mongodb:// or mongodb+srv:// uri"
+                        .to_string(),
+                );
+            }
+        }
+        STORAGE_BACKEND_POSTGRES => {
+            if config.storage.postgres_connection_string.is_none() {
+                problems.push(
+                    "storage.postgres_connection_string is required for the postgres backend"
+                        .to_string(),
+                );
+            }
+        }
+        backend => problems.push(format!("storage.backend {} is unknown", backend)),
+    }
+
+    if config.cache.enabled {
+        match config.cache.backend.as_str() {
+            CACHE_BACKEND_MEMORY => {
+                if config.cache.max_entries == 0 {
+                    problems.push("cache.max_entries must be greater than 0".to_string());
+                }
+            }
+            CACHE_BACKEND_REDIS => {
+                if config.cache.redis_url.is_none() {
+                    problems.push("cache.redis_url is required for the redis backend".to_string());
+                }
+            }
+            backend => problems.push(format!("cache.backend {} is unknown", backend)),
+        }
+
+        if config.cache.ttl_seconds == 0 {
+            problems.push("cache.ttl_seconds must be greater than 0".to_string());
+        }
+    }
+
+    match config.queue.backend.as_str() {
+        QUEUE_BACKEND_LOCAL => {}
+        QUEUE_BACKEND_REDIS => {
+            if config.queue.redis_url.is_none() {
+                problems.push("queue.redis_url is required for the redis backend".to_string());
+            }
+        }
+        backend => problems.push(format!("queue.backend {} is unknown", backend)),
+    }
+
+    if config.events.enabled
+        && (config.events.kafka_brokers.is_empty() || config.events.topic.is_empty())
+    {
+        problems.push("events.kafka_brokers and events.topic are required for events".to_string());
+    }
+
+    if config.change_stream.enabled {
+        match config.change_stream.sink.as_str() {
+            CHANGE_SINK_KAFKA | CHANGE_SINK_NATS => {}
+            sink => problems.push(format!("change_stream.sink {} is unknown", sink)),
+        }
+
+        if config.change_stream.topic.is_empty() {
+            problems.push("change_stream.topic is required for the change stream".to_string());
+        }
+    }
+
+    if config.admin_api.enabled {
+        match config.admin_api.host.parse::<IpAddr>() {
+            Ok(host) if !host.is_loopback() && config.admin_api.token.is_none() => problems.push(
+                "admin_api.token is required when admin_api.host is not a loopback address"
+                    .to_string(),
+            ),
+            Ok(_) => {}
+            Err(_) => problems.push(format!(
+                "admin_api.host {} is not an ip address",
+                config.admin_api.host
+            )),
+        }
+
+        if config
+            .admin_api
+            .token
+            .as_ref()
+            .is_some_and(|token| token.trim().is_empty())
+        {
+            problems.push("admin_api.token must not be empty".to_string());
+        }
+    }
+
+    if config.admin_api.enabled
+        && config.simulation.enabled
+        && config.admin_api.port == config.simulation.port
+    {
+        problems.push("admin_api.port and simulation.port must differ".to_string());
+    }
+
+    if config.replay.enabled {
+        if config.simulation.enabled {
+            problems.push("replay and simulation cannot both be enabled".to_string());
+        }
+
+        if config.admin_api.enabled && config.admin_api.port == config.replay.port {
+            problems.push("admin_api.port and replay.port must differ".to_string());
+        }
+
+        if !Path::new(&config.replay.fixtures_dir).is_dir() {
+            problems.push(format!(
+                "replay.fixtures_dir {} is not a directory",
+                config.replay.fixtures_dir
+            ));
+        }
+    }
+
+    if config.grpc.enabled {
+        if config.admin_api.enabled && config.grpc.port == config.admin_api.port {
+            problems.push("grpc.port and admin_api.port must differ".to_string());
+        }
+
+        if config.grpc.event_buffer == 0 {
+            problems.push("grpc.event_buffer must be greater than 0".to_string());
+        }
+    }
+
+    if problems.is_empty() {
+        return Ok(());
+    }
+
+    Err(anyhow!("Invalid configuration: {}", problems.join("; ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use figment::{providers::Serialized, Figment};
+
+    use crate::models::config::Config;
+
+    fn config() -> Config {
+        Figment::new()
+            .merge(Serialized::defaults(serde_json::json!({
+                "mongo_connection_string": "mongodb://localhost:27017",
+                "environment": "development",
+                "log_level": "info",
+                "crawler": {
+                    "additional": true,
+                    "discovery": true,
+                    "video": true,
+                    "channel": true
+                }
+            })))
+            .extract()
+            .unwrap()
+    }
+
+    #[test]
+    fn validate_accepts_defaults() {
+        assert!(super::validate_config(&config()).is_ok());
+    }
+
+    #[test]
+    fn validate_reports_all_problems() {
+        let mut config = config();
+        config.log_level = "loud".to_string();
+        config.intervals.new_video = 0;
+        config.storage.backend = "postgres".to_string();
+        config.scrape_policy.week_old_video_seconds = 60;
+        config.cache.enabled = true;
+        config.cache.backend = "redis".to_string();
+        config.queue.backend = "redis".to_string();
+
+        let message = super::validate_config(&config).unwrap_err().to_string();
+
+        assert!(message.contains("log_level loud"));
+        assert!(message.contains("intervals.new_video"));
+        assert!(message.contains("storage.postgres_connection_string"));
+        assert!(message.contains("scrape_policy.week_old_video_seconds must not be less"));
+        assert!(message.contains("cache.redis_url"));
+        assert!(message.contains("queue.redis_url"));
+    }
+
+    #[test]
+    fn validate_accepts_postgres_without_mongo() {
+        let mut config = config();
+        config.mongo_connection_string = String::new();
+        config.storage.backend = "postgres".to_string();
+        config.storage.postgres_connection_string =
+            Some("postgres://localhost/crawler".to_string());
+        config.change_stream.enabled = true;
+
+        assert!(super::validate_config(&config).is_ok());
+
+        config.storage.backend = "mongodb".to_string();
+        let message = super::validate_config(&config).unwrap_err().to_string();
+        assert!(message.contains("mongo_connection_string"));
+    }
+
+    #[test]
+    fn validate_requires_admin_token_off_loopback() {
+        let mut config = config();
+        config.admin_api.enabled = true;
+        config.admin_api.host = "0.0.0.0".to_string();
+
+        let message = super::validate_config(&config).unwrap_err().to_string();
+        assert!(message.contains("admin_api.token is required"));
+
+        config.admin_api.token = Some("secret".to_string());
+        assert!(super::validate_config(&config).is_ok());
+    }
+}
@@ -1 +1,108 @@
 pub const ONE_DAYS_IN_SECONDS: u64 = 86400;
+
+pub const STRICT_COMPLIANCE_REQUEST_INTERVAL_MILLIS: u64 = 1000;
+
+pub const DATA_SOURCE_YOUTUBE_DATA_API: &str = "youtubeDataApi";
+pub const DATA_SOURCE_YOUTUBE_FEED: &str = "youtubeFeed";
+
+pub const SIMULATION_ENVIRONMENT: &str = "simulation";
+pub const REPLAY_ENVIRONMENT: &str = "replay";
+
+pub const STORAGE_BACKEND_MONGODB: &str = "mongodb";
+pub const STORAGE_BACKEND_POSTGRES: &str = "postgres";
+
+pub const CACHE_BACKEND_MEMORY: &str = "memory";
+pub const CACHE_BACKEND_REDIS: &str = "redis";
+
+pub const QUEUE_BACKEND_LOCAL: &str = "local";
+pub const QUEUE_BACKEND_REDIS: &str = "redis";
+
+pub const CHANGE_SINK_KAFKA: &str = "kafka";
+pub const CHANGE_SINK_NATS: &str = "nats";
+
+pub const DEFAULT_API_KEY_DAILY_QUOTA: i32 = 10000;
+// The connection pool size of the MongoDB driver when the connection string sets none
+pub const DEFAULT_MONGODB_MAX_POOL_SIZE: u32 = 10;
+
+pub const CHANNEL_SCRAPE_RETRY_BASE_MILLIS: u64 = 2;
+pub const CHANNEL_SCRAPE_RETRY_FACTOR: u64 = 1000;
+pub const CHANNEL_SCRAPE_MAX_RETRIES: usize = 3;
+pub const SCRAPER_QUEUE_CAPACITY: usize = usize::MAX >> 3;
+pub const QUEUE_RETRY_SECONDS: u64 = 5;
+// Receives of a command that kept failing before it is dropped
+pub const CHANNEL_COMMAND_MAX_ATTEMPTS: u32 = 3;
+pub const IMPORT_PROGRESS_INTERVAL: usize = 100;
+
+pub const CHANNEL_STATUS_ACTIVE: &str = "active";
+pub const CHANNEL_STATUS_DEACTIVATED: &str = "deactivated";
+/// New channels scraped with their latest videos only, see the probation job
+pub const CHANNEL_STATUS_PROBATION: &str = "probation";
+
+pub const CHANNEL_LIFECYCLE_ACTIVE: &str = "active";
+pub const CHANNEL_LIFECYCLE_SLOWING: &str = "slowing";
+pub const CHANNEL_LIFECYCLE_DORMANT: &str = "dormant";
+pub const CHANNEL_LIFECYCLE_ABANDONED: &str = "abandoned";
+
+pub const CHANNEL_SOURCE_ADDITIONAL: &str = "additional";
+pub const CHANNEL_SOURCE_CLI: &str = "cli";
+pub const CHANNEL_SOURCE_COLLABORATION: &str = "collaboration";
+pub const CHANNEL_SOURCE_COMMENTER: &str = "commenter";
+pub const CHANNEL_SOURCE_DISCOVERY: &str = "discovery";
+pub const CHANNEL_SOURCE_END_SCREEN: &str = "endScreen";
+pub const CHANNEL_SOURCE_IMPORT: &str = "import";
+pub const CHANNEL_SOURCE_INGEST: &str = "ingest";
+
+pub const FEATURE_DISCOVERY_ENABLED: &str = "discoveryEnabled";
+pub const FEATURE_VIDEO_SCRAPE_ENABLED: &str = "videoScrapeEnabled";
+pub const FEATURE_BACKFILL_ENABLED: &str = "backfillEnabled";
+pub const FEATURE_ADDITIONAL_SOURCE_ENABLED: &str = "additionalSourceEnabled";
+pub const FEATURE_IMPORT_SOURCE_ENABLED: &str = "importSourceEnabled";
+pub const FEATURE_COLLABORATION_SOURCE_ENABLED: &str = "collaborationSourceEnabled";
+pub const FEATURE_COMMENTER_SOURCE_ENABLED: &str = "commenterSourceEnabled";
+pub const FEATURE_INGEST_SOURCE_ENABLED: &str = "ingestSourceEnabled";
+pub const FEATURE_END_SCREEN_SOURCE_ENABLED: &str = "endScreenSourceEnabled";
+pub const FEATURE_FLAGS: [&str; 9] = [
+    FEATURE_DISCOVERY_ENABLED,
+    FEATURE_VIDEO_SCRAPE_ENABLED,
+    FEATURE_BACKFILL_ENABLED,
+    FEATURE_ADDITIONAL_SOURCE_ENABLED,
+    FEATURE_IMPORT_SOURCE_ENABLED,
+    FEATURE_COLLABORATION_SOURCE_ENABLED,
+    FEATURE_COMMENTER_SOURCE_ENABLED,
+    FEATURE_INGEST_SOURCE_ENABLED,
+    FEATURE_END_SCREEN_SOURCE_ENABLED,
+];
+pub const DEACTIVATION_REASON_REDIRECTED: &str = "redirected";
+pub const DEACTIVATION_REASON_MERGED: &str = "merged";
+pub const DEACTIVATION_REASON_BAN_EVASION: &str = "banEvasion";
+pub const DEACTIVATION_REASON_CLASSIFICATION_OVERRIDE: &str = "classificationOverride";
+pub const DEACTIVATION_REASON_PROBATION_FAILED: &str = "probationFailed";
+
+pub const CLASSIFICATION_OVERRIDE_INCLUDE: &str = "forceInclude";
+pub const CLASSIFICATION_OVERRIDE_EXCLUDE: &str = "forceExclude";
+pub const CLASSIFICATION_OVERRIDES: [&str; 2] = [
+    CLASSIFICATION_OVERRIDE_INCLUDE,
+    CLASSIFICATION_OVERRIDE_EXCLUDE,
+];
+
+pub const VIDEO_AVAILABILITY_AVAILABLE: &str = "available";
+pub const VIDEO_AVAILABILITY_DELETED: &str = "deleted";
+pub const VIDEO_AVAILABILITY_PRIVATE: &str = "private";
+pub const VIDEO_AVAILABILITY_REGION_BLOCKED: &str = "regionBlocked";
+
+pub const VIDEO_TYPE_LESSON: &str = "lesson";
+pub const VIDEO_TYPE_COVER: &str = "cover";
+pub const VIDEO_TYPE_REVIEW: &str = "review";
+pub const VIDEO_TYPE_VLOG: &str = "vlog";
+pub const VIDEO_TYPE_PERFORMANCE: &str = "performance";
+
+pub const VIDEO_TYPE_CLASSIFIER_RULES: &str = "rules";
+
+pub const GENRE_BLUES: &str = "blues";
+pub const GENRE_METAL: &str = "metal";
+pub const GENRE_CLASSICAL: &str = "classical";
+pub const GENRE_BASS: &str = "bass";
+pub const GENRE_LUTHIER: &str = "luthier";
+pub const GENRE_GEAR_REVIEWS: &str = "gearReviews";
+
+pub const GENRE_CLASSIFIER_RULES: &str = "rules";
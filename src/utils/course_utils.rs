@@ -0,0 +1,291 @@
+use regex::Regex;
+
+pub const COURSE_SIGNAL_NUMBERED: &str = "numbered";
+pub const COURSE_SIGNAL_CADENCE: &str = "cadence";
+pub const COURSE_SIGNAL_KEYWORDS: &str = "keywords";
+
+pub const MIN_COURSE_LESSONS: usize = 3;
+// One signal alone is common in playlists of any kind, e.g. numbered vlogs
+const MIN_COURSE_SIGNALS: usize = 2;
+// A numbered series may miss a lesson that was deleted or made private
+const MIN_NUMBERED_SHARE: f64 = 0.8;
+const MIN_SEQUENTIAL_SHARE: f64 = 0.8;
+// Upload gaps whose standard deviation stays below half their mean count as a steady cadence
+const MAX_CADENCE_VARIATION: f64 = 0.5;
+const MIN_KEYWORD_SHARE: f64 = 0.5;
+
+const LESSON_KEYWORDS: [&str; 11] = [
+    "lesson",
+    "course",
+    "tutorial",
+    "beginner",
+    "learn",
+    "masterclass",
+    "method",
+    "module",
+    "chapter",
+    "exercise",
+    "step by step",
+];
+// Playlist items of removed videos keep their position but lose their title
+const UNAVAILABLE_VIDEO_TITLES: [&str; 2] = ["Private video", "Deleted video"];
+
+/// A video of a playlist, at its position in the playlist.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaylistLesson {
+    pub video_id: String,
+    pub title: String,
+    pub position: usize,
+    pub published_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CourseLesson {
+    pub video_id: String,
+    pub title: String,
+    /// 1-based position in the course, by lesson number if the titles are numbered
+    pub order: usize,
+    pub number: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Course {
+    pub lessons: Vec<CourseLesson>,
+    pub signals: Vec<&'static str>,
+}
+
+/// The lesson number of a title like "Lesson 3", "Part 2/10", "#4" or "05. Barre chords".
+pub fn get_lesson_number(title: &str) -> Option<u32> {
+    let regex = Regex::new(
+        r"(?i)\b(?:lesson|lektion|part|pt|episode|ep|day|week|chapter|module|session|step)\.?\s*#?\s*(\d{1,3})\b|#(\d{1,3})\b|^\s*(\d{1,3})\s*[.):-]|\b(\d{1,3})\s*/\s*\d{1,3}\b",
+    )
+    .unwrap();
+
+    let captures = regex.captures(title)?;
+
+    captures
+        .iter()
+        .skip(1)
+        .flatten()
+        .next()
+        .and_then(|number| number.as_str().parse().ok())
+}
+
+/// Detects a course from the videos of a playlist: sequentially numbered titles, a steady
+/// upload cadence and lesson keywords, at least two of them. Returns the lessons in course
+/// order with the signals found, `None` for playlists that do not look like a course.
+pub fn detect_course(playlist_title: &str, lessons: &[PlaylistLesson]) -> Option<Course> {
+    let mut lessons = lessons
+        .iter()
+        .filter(|lesson| !UNAVAILABLE_VIDEO_TITLES.contains(&lesson.title.as_str()))
+        .cloned()
+        .collect::<Vec<PlaylistLesson>>();
+    lessons.sort_by_key(|lesson| lesson.position);
+
+    if lessons.len() < MIN_COURSE_LESSONS {
+        return None;
+    }
+
+    let numbers = lessons
+        .iter()
+        .map(|lesson| get_lesson_number(&lesson.title))
+        .collect::<Vec<Option<u32>>>();
+
+    let mut signals = vec![];
+
+    let is_numbered = is_sequentially_numbered(&numbers);
+    if is_numbered {
+        signals.push(COURSE_SIGNAL_NUMBERED);
+    }
+
+    let published_at = lessons
+        .iter()
+        .filter_map(|lesson| lesson.published_at)
+        .collect::<Vec<i64>>();
+    if has_steady_cadence(&published_at) {
+        signals.push(COURSE_SIGNAL_CADENCE);
+    }
+
+    let titles = lessons
+        .iter()
+        .map(|lesson| lesson.title.as_str())
+        .collect::<Vec<&str>>();
+    if has_lesson_keywords(playlist_title, &titles) {
+        signals.push(COURSE_SIGNAL_KEYWORDS);
+    }
+
+    if signals.len() < MIN_COURSE_SIGNALS {
+        return None;
+    }
+
+    let mut ordered = lessons.into_iter().zip(numbers).collect::<Vec<_>>();
+    if is_numbered {
+        // Stable, so unnumbered lessons like an intro keep their playlist position among equals
+        ordered.sort_by_key(|(_, number)| number.unwrap_or(u32::MAX));
+    }
+
+    let lessons = ordered
+        .into_iter()
+        .enumerate()
+        .map(|(index, (lesson, number))| CourseLesson {
+            video_id: lesson.video_id,
+            title: lesson.title,
+            order: index + 1,
+            number,
+        })
+        .collect();
+
+    Some(Course { lessons, signals })
+}
+
+fn is_sequentially_numbered(numbers: &[Option<u32>]) -> bool {
+    let mut found = numbers.iter().flatten().copied().collect::<Vec<u32>>();
+
+    if (found.len() as f64) < numbers.len() as f64 * MIN_NUMBERED_SHARE {
+        return false;
+    }
+
+    found.sort_unstable();
+    found.dedup();
+
+    // Repeated numbers, e.g. "Part 1" of several songs, are no sequence
+    if (found.len() as f64) < numbers.len() as f64 * MIN_NUMBERED_SHARE {
+        return false;
+    }
+
+    let sequential_count = found
+        .windows(2)
+        .filter(|pair| pair[1] - pair[0] == 1)
+        .count();
+
+    sequential_count as f64 >= (found.len() - 1) as f64 * MIN_SEQUENTIAL_SHARE
+}
+
+fn has_steady_cadence(published_at: &[i64]) -> bool {
+    let mut published_at = published_at.to_vec();
+    published_at.sort_unstable();
+
+    let gaps = published_at
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]) as f64)
+        .collect::<Vec<f64>>();
+
+    if gaps.len() < 2 {
+        return false;
+    }
+
+    let mean = gaps.iter().sum::<f64>() / gaps.len() as f64;
+    if mean <= 0.0 {
+        return false;
+    }
+
+    let variance = gaps.iter().map(|gap| (gap - mean).powi(2)).sum::<f64>() / gaps.len() as f64;
+
+    variance.sqrt() / mean <= MAX_CADENCE_VARIATION
+}
+
+fn has_lesson_keywords(playlist_title: &str, titles: &[&str]) -> bool {
+    let has_keyword = |text: &str| {
+        let text = text.to_lowercase();
+        LESSON_KEYWORDS.iter().any(|keyword| text.contains(keyword))
+    };
+
+    if has_keyword(playlist_title) {
+        return true;
+    }
+
+    let keyword_count = titles.iter().filter(|title| has_keyword(title)).count();
+
+    keyword_count as f64 >= titles.len() as f64 * MIN_KEYWORD_SHARE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        PlaylistLesson, COURSE_SIGNAL_CADENCE, COURSE_SIGNAL_KEYWORDS, COURSE_SIGNAL_NUMBERED,
+    };
+
+    const WEEK: i64 = 7 * 24 * 60 * 60;
+
+    fn lesson(position: usize, title: &str, published_at: i64) -> PlaylistLesson {
+        PlaylistLesson {
+            video_id: format!("video{}", position),
+            title: title.to_string(),
+            position,
+            published_at: Some(published_at),
+        }
+    }
+
+    #[test]
+    fn lesson_numbers() {
+        assert_eq!(
+            super::get_lesson_number("Beginner Guitar Lesson 3"),
+            Some(3)
+        );
+        assert_eq!(super::get_lesson_number("Jazz chords pt. 12"), Some(12));
+        assert_eq!(super::get_lesson_number("Sweep picking (2/10)"), Some(2));
+        assert_eq!(super::get_lesson_number("05. Barre chords"), Some(5));
+        assert_eq!(super::get_lesson_number("Blues #4 - Turnarounds"), Some(4));
+        assert_eq!(super::get_lesson_number("My 2024 rig rundown"), None);
+    }
+
+    #[test]
+    fn detects_numbered_weekly_courses_in_lesson_order() {
+        let lessons = vec![
+            lesson(0, "Blues Course Lesson 2: Shuffle", 2 * WEEK),
+            lesson(1, "Blues Course Lesson 1: The 12 bar", WEEK),
+            lesson(2, "Private video", 0),
+            lesson(3, "Blues Course Lesson 3: Turnarounds", 3 * WEEK),
+            lesson(4, "Blues Course Lesson 4: Soloing", 4 * WEEK + 3600),
+        ];
+
+        let course = super::detect_course("Blues guitar", &lessons).unwrap();
+
+        assert_eq!(
+            course.signals,
+            vec![
+                COURSE_SIGNAL_NUMBERED,
+                COURSE_SIGNAL_CADENCE,
+                COURSE_SIGNAL_KEYWORDS
+            ]
+        );
+        assert_eq!(
+            course
+                .lessons
+                .iter()
+                .map(|lesson| (lesson.video_id.as_str(), lesson.order, lesson.number))
+                .collect::<Vec<_>>(),
+            vec![
+                ("video1", 1, Some(1)),
+                ("video0", 2, Some(2)),
+                ("video3", 3, Some(3)),
+                ("video4", 4, Some(4)),
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_playlists_that_are_no_courses() {
+        // Song covers uploaded whenever, no numbering and no lesson keywords
+        let covers = vec![
+            lesson(0, "Metallica - One cover", 0),
+            lesson(1, "Slash solo cover", 2 * WEEK),
+            lesson(2, "Hendrix medley", 30 * WEEK),
+            lesson(3, "Part 1 of my Tool covers", 31 * WEEK),
+        ];
+        assert_eq!(super::detect_course("Covers", &covers), None);
+
+        // Keywords alone are not enough
+        let tutorials = vec![
+            lesson(0, "Tutorial: Slide guitar", 0),
+            lesson(1, "Tutorial: Tapping", WEEK),
+            lesson(2, "Tutorial: Hybrid picking", 40 * WEEK),
+        ];
+        assert_eq!(super::detect_course("Tutorials", &tutorials), None);
+
+        assert_eq!(
+            super::detect_course("Lessons", &[lesson(0, "Lesson 1", 0)]),
+            None
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::models::config::CrawlBudgetConfig;
+
+/// Counts the api units, feed fetches and time of the current run against its limits. Shared
+/// between a crawler and the scraper working off its commands, the crawler starts each cycle.
+pub struct CrawlBudget {
+    limits: CrawlBudgetConfig,
+    started_at: Mutex<Instant>,
+    api_units: AtomicU64,
+    feed_fetches: AtomicU64,
+}
+
+impl CrawlBudget {
+    pub fn new(limits: CrawlBudgetConfig) -> CrawlBudget {
+        CrawlBudget {
+            limits,
+            started_at: Mutex::new(Instant::now()),
+            api_units: AtomicU64::new(0),
+            feed_fetches: AtomicU64::new(0),
+        }
+    }
+
+    pub fn start(&self) {
+        *self.started_at.lock().unwrap() = Instant::now();
+        self.api_units.store(0, Ordering::Relaxed);
+        self.feed_fetches.store(0, Ordering::Relaxed);
+    }
+
+    pub fn record(&self, api_units: u64, feed_fetches: u64) {
+        self.api_units.fetch_add(api_units, Ordering::Relaxed);
+        self.feed_fetches.fetch_add(feed_fetches, Ordering::Relaxed);
+    }
+
+    /// Returns why the run is over budget, if it is.
+    pub fn get_exhausted_reason(&self) -> Option<String> {
+        get_exhausted_reason(
+            &self.limits,
+            self.api_units.load(Ordering::Relaxed),
+            self.feed_fetches.load(Ordering::Relaxed),
+            self.started_at.lock().unwrap().elapsed().as_secs(),
+        )
+    }
+}
+
+pub fn get_exhausted_reason(
+    limits: &CrawlBudgetConfig,
+    api_units: u64,
+    feed_fetches: u64,
+    seconds: u64,
+) -> Option<String> {
+    let budgets = [
+        ("api units", limits.max_api_units, api_units),
+        ("feed fetches", limits.max_feed_fetches, feed_fetches),
+        ("seconds", limits.max_seconds, seconds),
+    ];
+
+    budgets
+        .iter()
+        .find(|(_, max, spent)| *max > 0 && spent >= max)
+        .map(|(name, max, spent)| format!("{} of {} {} spent", spent, max, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::config::CrawlBudgetConfig;
+
+    #[test]
+    fn exhausts_on_the_first_spent_limit() {
+        let limits = CrawlBudgetConfig {
+            max_api_units: 100,
+            max_feed_fetches: 0,
+            max_seconds: 60,
+        };
+
+        assert_eq!(super::get_exhausted_reason(&limits, 99, 5000, 59), None);
+        assert_eq!(
+            super::get_exhausted_reason(&limits, 100, 0, 0),
+            Some("100 of 100 api units spent".to_string())
+        );
+        assert_eq!(
+            super::get_exhausted_reason(&limits, 0, 0, 61),
+            Some("61 of 60 seconds spent".to_string())
+        );
+        assert_eq!(
+            super::get_exhausted_reason(&CrawlBudgetConfig::default(), 1000, 1000, 1000),
+            None
+        );
+    }
+}
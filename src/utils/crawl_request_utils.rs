@@ -0,0 +1,44 @@
+use serde::Deserialize;
+
+use crate::utils::youtube_url_utils::{parse_youtube_url, YoutubeResource};
+
+#[derive(Debug, Deserialize)]
+struct CrawlRequest {
+    /// A channel id, `@handle` or channel url
+    channel: String,
+}
+
+/// Returns the channel id or handle a crawl request asks for, or why the request is invalid.
+pub fn parse_crawl_request(payload: &[u8]) -> Result<String, String> {
+    let request = serde_json::from_slice::<CrawlRequest>(payload)
+        .map_err(|e| format!("invalid request: {}", e))?;
+
+    parse_requested_channel(&request.channel)
+}
+
+/// Returns the channel id or handle of a channel id, `@handle` or channel url.
+pub fn parse_requested_channel(channel: &str) -> Result<String, String> {
+    match parse_youtube_url(channel) {
+        Some(YoutubeResource::Channel(channel_id)) => Ok(channel_id),
+        Some(YoutubeResource::Handle(handle)) => Ok(handle),
+        _ => Err(format!("{} is not a channel id, handle or url", channel)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn parse_channel_of_crawl_request() {
+        assert_eq!(
+            super::parse_crawl_request(
+                br#"{"channel": "https://www.youtube.com/channel/UCabcdefghijklmnopqrstuv"}"#
+            ),
+            Ok("UCabcdefghijklmnopqrstuv".to_string())
+        );
+        assert!(super::parse_crawl_request(br#"{"channel": "@GuitarLessons"}"#).is_ok());
+        assert!(
+            super::parse_crawl_request(br#"{"channel": "https://youtu.be/dQw4w9WgXcQ"}"#).is_err()
+        );
+        assert!(super::parse_crawl_request(b"UCabcdefghijklmnopqrstuv").is_err());
+    }
+}
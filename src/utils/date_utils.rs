@@ -0,0 +1,111 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+// Nothing on YouTube is older than its launch
+const MIN_TIMESTAMP: i64 = 1_114_214_400;
+// Clock skew between YouTube and the crawler, anything later is garbage
+const MAX_FUTURE_SECONDS: i64 = 24 * 60 * 60;
+
+const NAIVE_DATE_TIME_FORMATS: [&str; 2] = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"];
+
+/// Parses RFC 3339 timestamps and the variants seen in feeds and api responses: lowercase
+/// separators, offsets without a colon, no offset (UTC), a date only and RFC 2822. Returns
+/// `None` for unparsable values and for dates before YouTube existed or in the future.
+pub fn parse_timestamp(value: &str, now: i64) -> Option<DateTime<FixedOffset>> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    let timestamp = DateTime::parse_from_rfc3339(&value.to_uppercase())
+        .or_else(|_| DateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f%z"))
+        .or_else(|_| DateTime::parse_from_rfc2822(value))
+        .ok()
+        .or_else(|| {
+            NAIVE_DATE_TIME_FORMATS
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+                .or_else(|| {
+                    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                        .ok()
+                        .map(|date| date.and_hms(0, 0, 0))
+                })
+                .map(|naive| DateTime::<FixedOffset>::from(Utc.from_utc_datetime(&naive)))
+        })?;
+
+    if timestamp.timestamp() < MIN_TIMESTAMP || timestamp.timestamp() > now + MAX_FUTURE_SECONDS {
+        return None;
+    }
+
+    Some(timestamp)
+}
+
+/// Parses the first valid of the named candidates, e.g. the `published` and then the `updated`
+/// date of a feed entry. Returns the timestamp with the name of the candidate it came from.
+pub fn parse_first_timestamp<'a>(
+    candidates: &[(&'a str, &str)],
+    now: i64,
+) -> Option<(DateTime<FixedOffset>, &'a str)> {
+    candidates.iter().find_map(|(source, value)| {
+        parse_timestamp(value, now).map(|timestamp| (timestamp, *source))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    const NOW: i64 = 1_700_000_000;
+
+    fn parse(value: &str) -> Option<i64> {
+        super::parse_timestamp(value, NOW).map(|timestamp| timestamp.timestamp())
+    }
+
+    #[test]
+    fn parses_timestamp_variants() {
+        let expected = Some(1_641_340_800);
+
+        assert_eq!(parse("2022-01-05T00:00:00+00:00"), expected);
+        assert_eq!(parse(" 2022-01-05T01:00:00+01:00\n"), expected);
+        assert_eq!(parse("2022-01-05t00:00:00z"), expected);
+        assert_eq!(parse("2022-01-05T00:00:00.000Z"), expected);
+        assert_eq!(parse("2022-01-05T00:00:00+0000"), expected);
+        assert_eq!(parse("2022-01-05T00:00:00"), expected);
+        assert_eq!(parse("2022-01-05 00:00:00"), expected);
+        assert_eq!(parse("2022-01-05"), expected);
+        assert_eq!(parse("Wed, 05 Jan 2022 00:00:00 +0000"), expected);
+    }
+
+    #[test]
+    fn rejects_malformed_and_implausible_timestamps() {
+        for value in [
+            "",
+            "   ",
+            "not a date",
+            "2022-13-05T00:00:00Z",
+            "2022-01-32",
+            "2022-01-05T25:00:00Z",
+            "05/01/2022",
+            "1641340800",
+            "1970-01-01T00:00:00Z",
+            "2099-01-01T00:00:00Z",
+            "2022-01-05T00:00:00+00:00 trailing",
+        ] {
+            assert_eq!(parse(value), None, "{}", value);
+        }
+    }
+
+    #[test]
+    fn falls_back_to_later_candidates() {
+        let candidates = [
+            ("published", "0000-00-00T00:00:00+00:00"),
+            ("updated", "2022-01-05T00:00:00+00:00"),
+        ];
+
+        let (timestamp, source) = super::parse_first_timestamp(&candidates, NOW).unwrap();
+        assert_eq!(timestamp.timestamp(), 1_641_340_800);
+        assert_eq!(source, "updated");
+
+        assert_eq!(
+            super::parse_first_timestamp(&[("published", "garbage"), ("updated", "")], NOW),
+            None
+        );
+    }
+}
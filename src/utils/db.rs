@@ -1,3 +1,18 @@
-pub fn get_db_name(_environment: &str) -> String {
+use crate::utils::consts::SIMULATION_ENVIRONMENT;
+
+pub fn get_db_name(environment: &str) -> String {
+    if environment == SIMULATION_ENVIRONMENT {
+        return "guitar-channels-simulation".to_string();
+    }
+
     "guitar-channels".to_string()
 }
+
+/// The schema of the environment on the postgres backend, like the database of `get_db_name`.
+pub fn get_postgres_schema(environment: &str) -> &'static str {
+    if environment == SIMULATION_ENVIRONMENT {
+        return "simulation";
+    }
+
+    "public"
+}
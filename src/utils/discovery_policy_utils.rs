@@ -0,0 +1,108 @@
+use crate::{models::discovery_policy::DiscoveryPolicy, utils::consts::ONE_DAYS_IN_SECONDS};
+
+/// Returns why the channel statistics fail the policy. Hidden subscriber counts pass the
+/// subscriber thresholds.
+pub fn check_channel_stats(
+    policy: &DiscoveryPolicy,
+    subscribers: Option<i64>,
+    videos: Option<i64>,
+) -> Option<String> {
+    if let Some(subscribers) = subscribers {
+        if let Some(min_subscribers) = policy.min_subscribers.filter(|min| subscribers < *min) {
+            return Some(format!(
+                "{} subscribers are below {}",
+                subscribers, min_subscribers
+            ));
+        }
+
+        if let Some(max_subscribers) = policy.max_subscribers.filter(|max| subscribers > *max) {
+            return Some(format!(
+                "{} subscribers are above {}",
+                subscribers, max_subscribers
+            ));
+        }
+    }
+
+    if let (Some(videos), Some(min_videos)) = (videos, policy.min_videos) {
+        if videos < min_videos {
+            return Some(format!("{} videos are below {}", videos, min_videos));
+        }
+    }
+
+    None
+}
+
+/// Returns why the latest upload fails the policy, channels without uploads never pass it.
+pub fn check_activity(
+    policy: &DiscoveryPolicy,
+    now: i64,
+    last_upload_at: Option<i64>,
+) -> Option<String> {
+    let max_inactive_days = policy.max_inactive_days?;
+
+    match last_upload_at {
+        Some(last_upload_at)
+            if now - last_upload_at <= max_inactive_days * ONE_DAYS_IN_SECONDS as i64 =>
+        {
+            None
+        }
+        Some(last_upload_at) => Some(format!(
+            "no upload for {} days",
+            (now - last_upload_at) / ONE_DAYS_IN_SECONDS as i64
+        )),
+        None => Some("no uploads".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::discovery_policy::DiscoveryPolicy;
+
+    #[test]
+    fn check_channel_stats() {
+        let policy = DiscoveryPolicy {
+            min_subscribers: Some(100),
+            max_subscribers: Some(1_000_000),
+            min_videos: Some(5),
+            ..DiscoveryPolicy::default()
+        };
+
+        assert_eq!(
+            super::check_channel_stats(&policy, Some(5000), Some(20)),
+            None
+        );
+        assert_eq!(super::check_channel_stats(&policy, None, Some(20)), None);
+        assert!(super::check_channel_stats(&policy, Some(50), Some(20)).is_some());
+        assert!(super::check_channel_stats(&policy, Some(2_000_000), Some(20)).is_some());
+        assert!(super::check_channel_stats(&policy, Some(5000), Some(2)).is_some());
+        assert_eq!(super::check_channel_stats(&policy, Some(5000), None), None);
+        assert_eq!(
+            super::check_channel_stats(&DiscoveryPolicy::default(), Some(0), Some(0)),
+            None
+        );
+    }
+
+    #[test]
+    fn check_activity() {
+        let now = 1_700_000_000;
+        let day = 24 * 60 * 60;
+        let policy = DiscoveryPolicy {
+            max_inactive_days: Some(180),
+            ..DiscoveryPolicy::default()
+        };
+
+        assert_eq!(
+            super::check_activity(&policy, now, Some(now - 30 * day)),
+            None
+        );
+        assert_eq!(
+            super::check_activity(&policy, now, Some(now - 400 * day)),
+            Some("no upload for 400 days".to_string())
+        );
+        assert!(super::check_activity(&policy, now, None).is_some());
+        assert_eq!(
+            super::check_activity(&DiscoveryPolicy::default(), now, None),
+            None
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use mongodb::bson::{Bson, DateTime, Document};
+use serde_json::{Map, Number, Value};
+
+const DATE_KEY: &str = "$date";
+
+/// Converts a document to JSON for non-Mongo stores. Dates become `{"$date": millis}` so they
+/// stay comparable inside JSON queries and read back as dates.
+pub fn to_json(document: &Document) -> Value {
+    let map = document
+        .iter()
+        .map(|(key, value)| (key.to_string(), bson_to_json(value)))
+        .collect::<Map<String, Value>>();
+
+    Value::Object(map)
+}
+
+pub fn from_json(value: Value) -> Document {
+    match json_to_bson(value) {
+        Bson::Document(document) => document,
+        _ => Document::new(),
+    }
+}
+
+/// Reads an array of strings, skipping other values. Missing arrays are empty.
+pub fn get_strings(document: &Document, key: &str) -> Vec<String> {
+    document
+        .get_array(key)
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|value| value.as_str().map(|value| value.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn bson_to_json(value: &Bson) -> Value {
+    match value {
+        Bson::Double(number) => Number::from_f64(*number)
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        Bson::String(text) => Value::String(text.to_string()),
+        Bson::Array(values) => Value::Array(values.iter().map(bson_to_json).collect()),
+        Bson::Document(document) => to_json(document),
+        Bson::Boolean(flag) => Value::Bool(*flag),
+        Bson::Null => Value::Null,
+        Bson::Int32(number) => Value::from(*number),
+        Bson::Int64(number) => Value::from(*number),
+        Bson::DateTime(date) => {
+            let mut map = Map::new();
+            map.insert(DATE_KEY.to_string(), Value::from(date.timestamp_millis()));
+            Value::Object(map)
+        }
+        Bson::ObjectId(id) => Value::String(id.to_hex()),
+        other => other.clone().into_relaxed_extjson(),
+    }
+}
+
+fn json_to_bson(value: Value) -> Bson {
+    match value {
+        Value::Null => Bson::Null,
+        Value::Bool(flag) => Bson::Boolean(flag),
+        Value::Number(number) => match number.as_i64() {
+            Some(integer) => Bson::Int64(integer),
+            None => Bson::Double(number.as_f64().unwrap_or_default()),
+        },
+        Value::String(text) => Bson::String(text),
+        Value::Array(values) => Bson::Array(values.into_iter().map(json_to_bson).collect()),
+        Value::Object(map) => match get_date(&map) {
+            Some(millis) => Bson::DateTime(DateTime::from_millis(millis)),
+            None => Bson::Document(
+                map.into_iter()
+                    .map(|(key, value)| (key, json_to_bson(value)))
+                    .collect(),
+            ),
+        },
+    }
+}
+
+fn get_date(map: &Map<String, Value>) -> Option<i64> {
+    match map.get(DATE_KEY) {
+        Some(millis) if map.len() == 1 => millis.as_i64(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::{doc, DateTime};
+
+    #[test]
+    fn to_json_stores_dates_as_millis() {
+        let json = super::to_json(&doc! {
+            "lastCrawl": DateTime::from_millis(1650000000000),
+            "links": [{ "type": "website" }],
+        });
+
+        assert_eq!(json["lastCrawl"]["$date"], 1650000000000i64);
+        assert_eq!(json["links"][0]["type"], "website");
+    }
+
+    #[test]
+    fn from_json_round_trips_documents() {
+        let document = doc! {
+            "title": "Guitar",
+            "subscribers": 1200i64,
+            "detectedLanguage": true,
+            "topics": ["rock music"],
+            "refreshOverride": { "intervalSeconds": 3600i64 },
+            "lastCrawl": DateTime::from_millis(1650000000000),
+            "lifecycleHistory": [{ "at": DateTime::from_millis(1650000000000) }],
+        };
+
+        assert_eq!(super::from_json(super::to_json(&document)), document);
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use mongodb::bson::Document;
+
+pub const DUPLICATE_REASON_CUSTOM_URL: &str = "customUrl";
+pub const DUPLICATE_REASON_TITLE_AVATAR: &str = "titleAndAvatar";
+pub const DUPLICATE_REASON_REDIRECT: &str = "redirect";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Duplicate {
+    pub channel_id: String,
+    pub canonical_id: String,
+    pub reason: &'static str,
+}
+
+/// Identifies the avatar image of a thumbnail url, which only differs by its size params
+/// between requests.
+pub fn get_avatar_key(thumbnail_url: &str) -> String {
+    let without_query = thumbnail_url.split('?').next().unwrap_or_default();
+
+    without_query
+        .split('=')
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Finds channels stored twice under different ids: the same custom url, the same title and
+/// avatar, or a redirect to another stored channel. The avatar is compared by its url and by the
+/// `avatarHash` of the image, which also matches copies uploaded again. Merged channels are
+/// left out.
+pub fn find_duplicates(channels: &[Document]) -> Vec<Duplicate> {
+    let channels = channels
+        .iter()
+        .filter(|channel| !channel.contains_key("mergedInto"))
+        .collect::<Vec<&Document>>();
+    let known_ids = channels
+        .iter()
+        .filter_map(|channel| channel.get_str("_id").ok())
+        .collect::<Vec<&str>>();
+
+    let mut duplicates = channels
+        .iter()
+        .filter_map(|channel| {
+            let channel_id = channel.get_str("_id").ok()?;
+            let redirects_to = channel.get_str("redirectsTo").ok()?;
+
+            known_ids.contains(&redirects_to).then(|| Duplicate {
+                channel_id: channel_id.to_string(),
+                canonical_id: redirects_to.to_string(),
+                reason: DUPLICATE_REASON_REDIRECT,
+            })
+        })
+        .collect::<Vec<Duplicate>>();
+
+    // Redirected channels are deactivated, only active entries can share a url or avatar
+    let active_channels = channels
+        .iter()
+        .filter(|channel| !channel.contains_key("redirectsTo"))
+        .copied()
+        .collect::<Vec<&Document>>();
+
+    let groups = [
+        (
+            DUPLICATE_REASON_CUSTOM_URL,
+            group_by(&active_channels, |channel| {
+                Some(channel.get_str("customUrl").ok()?.to_lowercase())
+            }),
+        ),
+        (
+            DUPLICATE_REASON_TITLE_AVATAR,
+            group_by(&active_channels, |channel| {
+                let title = channel.get_str("title").ok()?.trim().to_lowercase();
+                let avatar = get_avatar_key(channel.get_str("thumbnail").ok()?);
+
+                Some(format!("{}\n{}", title, avatar))
+            }),
+        ),
+        (
+            DUPLICATE_REASON_TITLE_AVATAR,
+            group_by(&active_channels, |channel| {
+                let title = channel.get_str("title").ok()?.trim().to_lowercase();
+                let avatar_hash = channel.get_str("avatarHash").ok()?;
+
+                Some(format!("{}\n{}", title, avatar_hash))
+            }),
+        ),
+    ];
+
+    for (reason, groups) in groups {
+        for group in groups {
+            let canonical_id = get_canonical_id(&group);
+
+            for channel in group {
+                let channel_id = channel.get_str("_id").unwrap_or_default();
+                let is_known = duplicates
+                    .iter()
+                    .any(|duplicate| duplicate.channel_id == channel_id);
+
+                if channel_id != canonical_id && !is_known {
+                    duplicates.push(Duplicate {
+                        channel_id: channel_id.to_string(),
+                        canonical_id: canonical_id.to_string(),
+                        reason,
+                    });
+                }
+            }
+        }
+    }
+
+    duplicates
+}
+
+fn group_by<'a>(
+    channels: &[&'a Document],
+    get_key: impl Fn(&Document) -> Option<String>,
+) -> Vec<Vec<&'a Document>> {
+    let mut groups: HashMap<String, Vec<&Document>> = HashMap::new();
+
+    for channel in channels {
+        if let Some(key) = get_key(channel).filter(|key| !key.trim().is_empty()) {
+            groups.entry(key).or_default().push(channel);
+        }
+    }
+
+    let mut groups = groups
+        .into_values()
+        .filter(|group| group.len() > 1)
+        .collect::<Vec<Vec<&Document>>>();
+    groups.sort_by_key(|group| get_canonical_id(group).to_string());
+
+    groups
+}
+
+/// Keeps the channel id with the most subscribers, then the oldest one.
+fn get_canonical_id<'a>(group: &[&'a Document]) -> &'a str {
+    group
+        .iter()
+        .max_by_key(|channel| {
+            (
+                channel.get_str("_id").unwrap_or_default().starts_with("UC"),
+                channel.get_i64("subscribers").unwrap_or_default(),
+                -channel.get_i64("publishedAt").unwrap_or(i64::MAX),
+                std::cmp::Reverse(channel.get_str("_id").unwrap_or_default()),
+            )
+        })
+        .and_then(|channel| channel.get_str("_id").ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::doc;
+
+    use super::{
+        Duplicate, DUPLICATE_REASON_CUSTOM_URL, DUPLICATE_REASON_REDIRECT,
+        DUPLICATE_REASON_TITLE_AVATAR,
+    };
+
+    #[test]
+    fn avatar_key_without_size() {
+        assert_eq!(
+            super::get_avatar_key("https://yt3.ggpht.com/abc=s88-c-k-c0x00ffffff-no-rj"),
+            super::get_avatar_key("https://yt3.ggpht.com/abc=s240-c-k")
+        );
+    }
+
+    #[test]
+    fn find_duplicates() {
+        let channels = vec![
+            doc! {"_id": "UCa", "title": "Riffs", "customUrl": "@riffs", "subscribers": 500_i64},
+            doc! {"_id": "UCb", "title": "Riffs!", "customUrl": "@Riffs", "subscribers": 10_i64},
+            doc! {"_id": "UCc", "title": "Old", "redirectsTo": "UCa"},
+            doc! {"_id": "UCd", "title": "Gone", "redirectsTo": "UCunknown"},
+            doc! {"_id": "UCe", "title": "Merged", "customUrl": "@riffs", "mergedInto": "UCa"},
+            doc! {"_id": "UCf", "title": "Licks", "thumbnail": "https://a/1", "avatarHash": "ff"},
+            doc! {"_id": "UCg", "title": "Licks", "thumbnail": "https://a/2", "avatarHash": "ff"},
+        ];
+
+        assert_eq!(
+            super::find_duplicates(&channels),
+            vec![
+                Duplicate {
+                    channel_id: "UCc".to_string(),
+                    canonical_id: "UCa".to_string(),
+                    reason: DUPLICATE_REASON_REDIRECT,
+                },
+                Duplicate {
+                    channel_id: "UCb".to_string(),
+                    canonical_id: "UCa".to_string(),
+                    reason: DUPLICATE_REASON_CUSTOM_URL,
+                },
+                Duplicate {
+                    channel_id: "UCg".to_string(),
+                    canonical_id: "UCf".to_string(),
+                    reason: DUPLICATE_REASON_TITLE_AVATAR,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use regex::Regex;
+
+/// Parses ISO 8601 durations as returned by `contentDetails.duration`, e.g. `PT1H2M3S`.
+pub fn parse_iso8601_duration(duration: &str) -> Option<i64> {
+    let regex =
+        Regex::new(r"^P(?:(\d+)W)?(?:(\d+)D)?(?:T(?:(\d+)H)?(?:(\d+)M)?(?:(\d+)S)?)?$").unwrap();
+    let captures = regex.captures(duration)?;
+
+    let multipliers = [604800, 86400, 3600, 60, 1];
+    let seconds = multipliers
+        .iter()
+        .enumerate()
+        .map(|(i, multiplier)| {
+            captures
+                .get(i + 1)
+                .and_then(|value| value.as_str().parse::<i64>().ok())
+                .unwrap_or(0)
+                * multiplier
+        })
+        .sum();
+
+    Some(seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn parse_hours_minutes_seconds() {
+        let seconds = super::parse_iso8601_duration("PT1H2M3S");
+        assert_eq!(seconds, Some(3723));
+    }
+
+    #[test]
+    fn parse_days_and_missing_components() {
+        assert_eq!(super::parse_iso8601_duration("P1DT5M"), Some(86700));
+        assert_eq!(super::parse_iso8601_duration("PT0S"), Some(0));
+    }
+
+    #[test]
+    fn reject_invalid_duration() {
+        assert_eq!(super::parse_iso8601_duration("1H2M"), None);
+    }
+}
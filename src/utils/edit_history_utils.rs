@@ -0,0 +1,82 @@
+use mongodb::bson::{doc, Bson, Document};
+
+pub const EDIT_HISTORY_FIELDS: [&str; 3] = ["title", "description", "tags"];
+// Oldest edits are dropped beyond this, re-optimized videos can change daily
+pub const MAX_EDIT_HISTORY: usize = 50;
+
+/// Returns an edit entry for each tracked field the new video document changes. Fields missing
+/// from either document are not compared, partial updates leave them untouched.
+pub fn get_edits(previous: &Document, video_doc: &Document, edited_at: i64) -> Vec<Document> {
+    EDIT_HISTORY_FIELDS
+        .iter()
+        .filter_map(|field| match (previous.get(field), video_doc.get(field)) {
+            (Some(from), Some(to)) if from != to => Some(doc! {
+                "field": *field,
+                "from": from.clone(),
+                "to": to.clone(),
+                "at": edited_at,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Adds the previous edit history plus the new edits to a video document that replaces
+/// `editHistory` on write.
+pub fn append_edit_history(previous: &Document, video_doc: &mut Document, edited_at: i64) {
+    let edits = get_edits(previous, video_doc, edited_at);
+
+    if edits.is_empty() {
+        return;
+    }
+
+    let mut history = previous
+        .get_array("editHistory")
+        .cloned()
+        .unwrap_or_default();
+    history.extend(edits.into_iter().map(Bson::Document));
+
+    let overflow = history.len().saturating_sub(MAX_EDIT_HISTORY);
+    history.drain(..overflow);
+
+    video_doc.insert("editHistory", history);
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::doc;
+
+    #[test]
+    fn get_edits() {
+        let previous = doc! {"title": "Blues lick", "description": "Lesson", "tags": ["blues"]};
+        let video_doc = doc! {"title": "EASY Blues lick", "tags": ["blues"], "views": 10};
+
+        assert_eq!(
+            super::get_edits(&previous, &video_doc, 100),
+            vec![
+                doc! {"field": "title", "from": "Blues lick", "to": "EASY Blues lick", "at": 100_i64}
+            ]
+        );
+    }
+
+    #[test]
+    fn append_edit_history() {
+        let previous = doc! {
+            "title": "b",
+            "editHistory": (0..super::MAX_EDIT_HISTORY)
+                .map(|at| doc! {"field": "title", "from": "a", "to": "b", "at": at as i64})
+                .collect::<Vec<_>>(),
+        };
+        let mut video_doc = doc! {"title": "c"};
+
+        super::append_edit_history(&previous, &mut video_doc, 1000);
+
+        let history = video_doc.get_array("editHistory").unwrap();
+        assert_eq!(history.len(), super::MAX_EDIT_HISTORY);
+        assert_eq!(
+            history.last().unwrap().as_document().unwrap().get_i64("at"),
+            Ok(1000)
+        );
+        assert_eq!(history[0].as_document().unwrap().get_i64("at"), Ok(1));
+    }
+}
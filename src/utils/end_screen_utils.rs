@@ -0,0 +1,108 @@
+use serde_json::Value;
+
+// Keys of the player response embedded in the watch page, the Data API does not expose either
+const END_SCREEN_KEY: &str = "\"endscreen\":";
+const CARDS_KEY: &str = "\"cards\":";
+
+/// Returns the channels the end screen and then the cards of a watch page link to, each once and
+/// without `own_channel_id`, which most end screens promote as well.
+pub fn get_promoted_channel_ids(html: &str, own_channel_id: &str) -> Vec<String> {
+    let mut browse_ids = vec![];
+
+    for key in [END_SCREEN_KEY, CARDS_KEY] {
+        if let Some(value) = get_embedded_object(html, key) {
+            collect_browse_ids(&value, &mut browse_ids);
+        }
+    }
+
+    let mut channel_ids: Vec<String> = vec![];
+    for browse_id in browse_ids {
+        if browse_id != own_channel_id && !channel_ids.contains(&browse_id) {
+            channel_ids.push(browse_id);
+        }
+    }
+
+    channel_ids
+}
+
+/// Parses the JSON object following the first occurrence of `key`, found by matching braces
+/// outside of strings.
+fn get_embedded_object(html: &str, key: &str) -> Option<Value> {
+    let start = html.find(key)? + key.len();
+    let rest = html[start..].trim_start();
+
+    if !rest.starts_with('{') {
+        return None;
+    }
+
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, c) in rest.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_string = true,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return serde_json::from_str(&rest[..=i]).ok();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+fn collect_browse_ids(value: &Value, channel_ids: &mut Vec<String>) {
+    match value {
+        Value::Object(object) => {
+            if let Some(Value::String(browse_id)) = object
+                .get("browseEndpoint")
+                .and_then(|endpoint| endpoint.get("browseId"))
+            {
+                // Playlists and topics are browsed as well
+                if browse_id.starts_with("UC") {
+                    channel_ids.push(browse_id.clone());
+                }
+            }
+
+            for child in object.values() {
+                collect_browse_ids(child, channel_ids);
+            }
+        }
+        Value::Array(values) => {
+            for child in values {
+                collect_browse_ids(child, channel_ids);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn promoted_channels_of_end_screen_and_cards() {
+        let html = r#"<script>var ytInitialPlayerResponse = {"videoDetails":{"channelId":"UCown"},"cards":{"cardCollectionRenderer":{"cards":[{"cardRenderer":{"content":{"collaboratorInfoCardContentRenderer":{"endpoint":{"browseEndpoint":{"browseId":"UCcard"}}}}}},{"cardRenderer":{"content":{"videoInfoCardContentRenderer":{"action":{"watchEndpoint":{"videoId":"abc"}}}}}}]}},"endscreen":{"endscreenRenderer":{"elements":[{"endscreenElementRenderer":{"style":"CHANNEL","title":{"simpleText":"Say \"hi} {"},"endpoint":{"browseEndpoint":{"browseId":"UCend"}}}},{"endscreenElementRenderer":{"style":"CHANNEL","endpoint":{"browseEndpoint":{"browseId":"UCown"}}}},{"endscreenElementRenderer":{"style":"PLAYLIST","endpoint":{"browseEndpoint":{"browseId":"VLPLlist"}}}},{"endscreenElementRenderer":{"style":"CHANNEL","endpoint":{"browseEndpoint":{"browseId":"UCcard"}}}}]}}};</script>"#;
+
+        assert_eq!(
+            super::get_promoted_channel_ids(html, "UCown"),
+            vec!["UCend".to_string(), "UCcard".to_string()]
+        );
+        assert!(super::get_promoted_channel_ids("<html></html>", "UCown").is_empty());
+        assert!(super::get_promoted_channel_ids(r#""endscreen":{"broken"#, "UCown").is_empty());
+    }
+}
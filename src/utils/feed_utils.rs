@@ -0,0 +1,343 @@
+use std::collections::HashMap;
+
+use quick_xml::{de::from_str, DeError};
+
+use crate::models::{
+    youtube_playlist_items::PlaylistItem,
+    youtube_video_details::YouTubeVideoItem,
+    youtube_video_feed_response::{
+        Entry, MediaCommunity, MediaGroup, MediaStatistics, YoutubeVideoFeedResponse,
+    },
+};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Parses the RSS feed of a channel. Elements are matched by local name, so titles and
+/// descriptions mentioning `yt:` or `media:` come through unchanged.
+pub fn parse_video_feed(xml: &str) -> Result<YoutubeVideoFeedResponse, DeError> {
+    from_str::<YoutubeVideoFeedResponse>(xml)
+}
+
+/// Hashes the id and `updated` time of all entries with FNV-1a. The hash is stored, so it must
+/// not depend on the Rust version like `DefaultHasher` does. View counts are left out, they
+/// change on every fetch.
+pub fn hash_feed_entries(entries: &[Entry]) -> String {
+    let mut keys = entries
+        .iter()
+        .map(|entry| format!("{}@{}", entry.video_id, entry.updated))
+        .collect::<Vec<String>>();
+    keys.sort();
+
+    let mut hash = FNV_OFFSET_BASIS;
+    for byte in keys.join("\n").bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+
+    format!("{:016x}", hash)
+}
+
+/// Returns the channel id the entries were published under if it differs from the requested
+/// one, which happens for feeds of channels that moved to another id.
+pub fn get_canonical_channel_id(channel_id: &str, entries: &[Entry]) -> Option<String> {
+    entries
+        .iter()
+        .filter_map(|entry| entry.channel_id.as_deref())
+        .find(|entry_channel_id| *entry_channel_id != channel_id)
+        .map(|entry_channel_id| entry_channel_id.to_string())
+}
+
+/// Builds the feed of a channel from its uploads playlist items for channels without a usable
+/// RSS feed. Views come from the video details and are left unknown without them, the publish
+/// time doubles as `updated`.
+pub fn get_feed_from_playlist_items(
+    items: &[PlaylistItem],
+    details_lookup: &HashMap<String, YouTubeVideoItem>,
+) -> YoutubeVideoFeedResponse {
+    let entries = items
+        .iter()
+        .map(|item| {
+            let video_id = &item.content_details.video_id;
+            let published = item
+                .content_details
+                .video_published_at
+                .clone()
+                .unwrap_or_else(|| item.snippet.published_at.clone());
+            let views = details_lookup
+                .get(video_id)
+                .and_then(|d| d.statistics.as_ref())
+                .and_then(|s| s.view_count);
+
+            Entry {
+                video_id: video_id.clone(),
+                channel_id: None,
+                title: item.snippet.title.clone(),
+                published: published.clone(),
+                updated: published,
+                group: MediaGroup {
+                    title: item.snippet.title.clone(),
+                    description: item.snippet.description.clone(),
+                    community: MediaCommunity {
+                        statistics: MediaStatistics { views },
+                    },
+                },
+            }
+        })
+        .collect();
+
+    YoutubeVideoFeedResponse { entries }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+    use crate::models::{
+        youtube_playlist_items::{PlaylistItem, PlaylistItemContentDetails, PlaylistItemSnippet},
+        youtube_video_details::{VideoStatistics, YouTubeVideoItem},
+        youtube_video_feed_response::{Entry, MediaCommunity, MediaGroup, MediaStatistics},
+    };
+
+    const FEED_CASES: u64 = 200;
+    // Emojis, escaped characters and the namespace prefixes the feed uses itself
+    const TEXT_PARTS: [&str; 10] = [
+        "Blues",
+        "lick",
+        "🎸",
+        "🔥🤘",
+        "&",
+        "<>",
+        "yt:video",
+        "media:group",
+        "\"quoted\"",
+        "Ünïcode",
+    ];
+
+    fn random_text(rng: &mut StdRng) -> String {
+        (0..rng.gen_range(1..6))
+            .map(|_| *TEXT_PARTS.choose(rng).unwrap())
+            .collect::<Vec<&str>>()
+            .join(" ")
+    }
+
+    fn escape(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+
+    /// Writes the entry the way the feed could, in random element and attribute order, with
+    /// optional elements left out and the description as CDATA or escaped text.
+    fn random_entry_xml(rng: &mut StdRng, entry: &Entry) -> String {
+        let mut statistics_attributes = [
+            format!(
+                r#"views="{}""#,
+                entry.group.community.statistics.views.unwrap()
+            ),
+            r#"xmlns:media="http://search.yahoo.com/mrss/""#.to_string(),
+        ];
+        statistics_attributes.shuffle(rng);
+
+        let mut star_rating_attributes = [r#"count="12""#, r#"average="5.00""#, r#"min="1""#];
+        star_rating_attributes.shuffle(rng);
+
+        let mut community = [
+            format!("<media:statistics {}/>", statistics_attributes.join(" ")),
+            format!("<media:starRating {}/>", star_rating_attributes.join(" ")),
+        ];
+        community.shuffle(rng);
+
+        let mut group = vec![
+            format!("<media:title>{}</media:title>", escape(&entry.group.title)),
+            format!(
+                "<media:community>{}</media:community>",
+                community.join("")
+            ),
+            r#"<media:thumbnail url="https://i.ytimg.com/vi/x/hqdefault.jpg" width="480" height="360"/>"#
+                .to_string(),
+        ];
+        if !entry.group.description.is_empty() {
+            group.push(if rng.gen_bool(0.5) {
+                format!(
+                    "<media:description><![CDATA[{}]]></media:description>",
+                    entry.group.description
+                )
+            } else {
+                format!(
+                    "<media:description>{}</media:description>",
+                    escape(&entry.group.description)
+                )
+            });
+        }
+        group.shuffle(rng);
+
+        let mut elements = vec![
+            format!("<id>yt:video:{}</id>", entry.video_id),
+            format!("<yt:videoId>{}</yt:videoId>", entry.video_id),
+            format!("<title>{}</title>", escape(&entry.title)),
+            r#"<link rel="alternate" href="https://www.youtube.com/watch?v=x"/>"#.to_string(),
+            format!("<published>{}</published>", entry.published),
+            format!("<updated>{}</updated>", entry.updated),
+            "<author><name>Guitarist</name><uri>https://www.youtube.com/channel/x</uri></author>"
+                .to_string(),
+            format!("<media:group>{}</media:group>", group.join("")),
+        ];
+        if let Some(channel_id) = &entry.channel_id {
+            elements.push(format!("<yt:channelId>{}</yt:channelId>", channel_id));
+        }
+        elements.shuffle(rng);
+
+        format!("<entry>{}</entry>", elements.join("\n"))
+    }
+
+    fn random_entry(rng: &mut StdRng, index: usize) -> Entry {
+        let title = random_text(rng);
+
+        Entry {
+            video_id: format!("video{}", index),
+            channel_id: if rng.gen_bool(0.5) {
+                Some("UCguitar".to_string())
+            } else {
+                None
+            },
+            title: title.clone(),
+            published: "2022-01-01T00:00:00+00:00".to_string(),
+            updated: format!("2022-01-0{}T00:00:00+00:00", rng.gen_range(1..10)),
+            group: MediaGroup {
+                title,
+                description: if rng.gen_bool(0.2) {
+                    String::new()
+                } else {
+                    random_text(rng)
+                },
+                community: MediaCommunity {
+                    statistics: MediaStatistics {
+                        views: Some(rng.gen_range(0..10_000_000)),
+                    },
+                },
+            },
+        }
+    }
+
+    #[test]
+    fn parses_any_feed_layout() {
+        for seed in 0..FEED_CASES {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let entries = (0..rng.gen_range(0..5))
+                .map(|index| random_entry(&mut rng, index))
+                .collect::<Vec<Entry>>();
+
+            let xml = format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?><feed xmlns:yt="http://www.youtube.com/xml/schemas/2015" xmlns:media="http://search.yahoo.com/mrss/" xmlns="http://www.w3.org/2005/Atom"><link rel="self" href="https://www.youtube.com/feeds/videos.xml?channel_id=UCguitar"/><id>yt:channel:guitar</id><yt:channelId>guitar</yt:channelId><title>Guitar 🎸</title>{}</feed>"#,
+                entries
+                    .iter()
+                    .map(|entry| random_entry_xml(&mut rng, entry))
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            );
+
+            let feed = super::parse_video_feed(&xml)
+                .unwrap_or_else(|e| panic!("seed {} failed: {}\n{}", seed, e, xml));
+            assert_eq!(feed.entries, entries, "seed {}:\n{}", seed, xml);
+        }
+    }
+
+    fn entry(video_id: &str, updated: &str, views: i64) -> Entry {
+        Entry {
+            video_id: video_id.to_string(),
+            channel_id: Some("channel".to_string()),
+            title: "title".to_string(),
+            published: "2022-01-01T00:00:00+00:00".to_string(),
+            updated: updated.to_string(),
+            group: MediaGroup {
+                title: "title".to_string(),
+                description: "description".to_string(),
+                community: MediaCommunity {
+                    statistics: MediaStatistics { views: Some(views) },
+                },
+            },
+        }
+    }
+
+    #[test]
+    fn hash_ignores_views_and_order() {
+        let a = entry("a", "2022-01-02T00:00:00+00:00", 10);
+        let b = entry("b", "2022-01-03T00:00:00+00:00", 20);
+
+        assert_eq!(
+            super::hash_feed_entries(&[a.clone(), b.clone()]),
+            super::hash_feed_entries(&[entry("b", &b.updated, 99), a.clone()])
+        );
+        assert_ne!(
+            super::hash_feed_entries(&[a.clone(), b]),
+            super::hash_feed_entries(&[a, entry("c", "2022-01-03T00:00:00+00:00", 20)])
+        );
+    }
+
+    #[test]
+    fn canonical_channel_id_differs_from_requested() {
+        let mut moved = entry("a", "2022-01-02T00:00:00+00:00", 10);
+        moved.channel_id = Some("moved".to_string());
+
+        assert_eq!(
+            super::get_canonical_channel_id(
+                "channel",
+                &[entry("b", "2022-01-02T00:00:00+00:00", 10)]
+            ),
+            None
+        );
+        assert_eq!(
+            super::get_canonical_channel_id("channel", &[moved]),
+            Some("moved".to_string())
+        );
+    }
+
+    #[test]
+    fn feed_from_playlist_items() {
+        let item = |video_id: &str, video_published_at: Option<&str>| PlaylistItem {
+            snippet: PlaylistItemSnippet {
+                published_at: "2022-01-05T00:00:00Z".to_string(),
+                title: "Blues lick lesson".to_string(),
+                description: "description".to_string(),
+                position: 0,
+            },
+            content_details: PlaylistItemContentDetails {
+                video_id: video_id.to_string(),
+                video_published_at: video_published_at.map(|p| p.to_string()),
+            },
+            ..PlaylistItem::default()
+        };
+        let mut details_lookup = HashMap::new();
+        details_lookup.insert(
+            "a".to_string(),
+            YouTubeVideoItem {
+                id: "a".to_string(),
+                statistics: Some(VideoStatistics {
+                    view_count: Some(1200),
+                    ..VideoStatistics::default()
+                }),
+                ..YouTubeVideoItem::default()
+            },
+        );
+
+        let feed = super::get_feed_from_playlist_items(
+            &[item("a", Some("2022-01-01T00:00:00Z")), item("b", None)],
+            &details_lookup,
+        );
+
+        assert_eq!(feed.entries.len(), 2);
+        assert_eq!(feed.entries[0].published, "2022-01-01T00:00:00Z");
+        assert_eq!(feed.entries[0].updated, "2022-01-01T00:00:00Z");
+        assert_eq!(feed.entries[0].group.community.statistics.views, Some(1200));
+        assert_eq!(feed.entries[1].published, "2022-01-05T00:00:00Z");
+        assert_eq!(feed.entries[1].group.community.statistics.views, None);
+        assert_eq!(
+            super::get_canonical_channel_id("UCguitar", &feed.entries),
+            None
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+
+use mongodb::bson::{doc, Document};
+
+use crate::utils::document_utils::get_strings;
+
+// The channel gear lists what a channel plays mostly, rare mentions are left out
+const MAX_CHANNEL_GEAR: usize = 20;
+
+pub struct GearItem {
+    pub name: &'static str,
+    pub brand: &'static str,
+    /// Lowercase spellings found in titles, descriptions and tags
+    pub aliases: &'static [&'static str],
+}
+
+pub const GEAR_DICTIONARY: [GearItem; 26] = [
+    GearItem {
+        name: "Fender Stratocaster",
+        brand: "Fender",
+        aliases: &["stratocaster", "strat"],
+    },
+    GearItem {
+        name: "Fender Telecaster",
+        brand: "Fender",
+        aliases: &["telecaster", "tele"],
+    },
+    GearItem {
+        name: "Fender Jazzmaster",
+        brand: "Fender",
+        aliases: &["jazzmaster"],
+    },
+    GearItem {
+        name: "Gibson Les Paul",
+        brand: "Gibson",
+        aliases: &["les paul"],
+    },
+    GearItem {
+        name: "Gibson SG",
+        brand: "Gibson",
+        aliases: &["gibson sg"],
+    },
+    GearItem {
+        name: "Gibson ES-335",
+        brand: "Gibson",
+        aliases: &["es-335", "es335", "es 335"],
+    },
+    GearItem {
+        name: "Gibson Flying V",
+        brand: "Gibson",
+        aliases: &["flying v"],
+    },
+    GearItem {
+        name: "PRS",
+        brand: "PRS",
+        aliases: &["prs", "paul reed smith"],
+    },
+    GearItem {
+        name: "Ibanez",
+        brand: "Ibanez",
+        aliases: &["ibanez"],
+    },
+    GearItem {
+        name: "Schecter",
+        brand: "Schecter",
+        aliases: &["schecter"],
+    },
+    GearItem {
+        name: "ESP",
+        brand: "ESP",
+        aliases: &["esp ltd", "esp guitars"],
+    },
+    GearItem {
+        name: "Gretsch",
+        brand: "Gretsch",
+        aliases: &["gretsch"],
+    },
+    GearItem {
+        name: "Rickenbacker",
+        brand: "Rickenbacker",
+        aliases: &["rickenbacker"],
+    },
+    GearItem {
+        name: "Martin D-28",
+        brand: "Martin",
+        aliases: &["martin d-28", "martin d28"],
+    },
+    GearItem {
+        name: "Line 6 Helix",
+        brand: "Line 6",
+        aliases: &["helix"],
+    },
+    GearItem {
+        name: "Fractal Axe-Fx",
+        brand: "Fractal Audio",
+        aliases: &["axe-fx", "axe fx", "axefx"],
+    },
+    GearItem {
+        name: "Kemper Profiler",
+        brand: "Kemper",
+        aliases: &["kemper"],
+    },
+    GearItem {
+        name: "Neural DSP Quad Cortex",
+        brand: "Neural DSP",
+        aliases: &["quad cortex"],
+    },
+    GearItem {
+        name: "Boss Katana",
+        brand: "Boss",
+        aliases: &["boss katana"],
+    },
+    GearItem {
+        name: "Boss DS-1",
+        brand: "Boss",
+        aliases: &["ds-1", "boss ds1"],
+    },
+    GearItem {
+        name: "Marshall JCM800",
+        brand: "Marshall",
+        aliases: &["jcm800", "jcm 800"],
+    },
+    GearItem {
+        name: "Mesa Boogie",
+        brand: "Mesa Boogie",
+        aliases: &["mesa boogie", "mesa/boogie"],
+    },
+    GearItem {
+        name: "Vox AC30",
+        brand: "Vox",
+        aliases: &["ac30", "ac-30"],
+    },
+    GearItem {
+        name: "Ibanez Tube Screamer",
+        brand: "Ibanez",
+        aliases: &["tube screamer", "ts9", "ts808"],
+    },
+    GearItem {
+        name: "Electro-Harmonix Big Muff",
+        brand: "Electro-Harmonix",
+        aliases: &["big muff"],
+    },
+    GearItem {
+        name: "Dunlop Cry Baby",
+        brand: "Dunlop",
+        aliases: &["cry baby", "crybaby"],
+    },
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GearCount {
+    pub name: String,
+    pub brand: String,
+    pub video_count: i64,
+}
+
+/// Counts the videos mentioning each gear of the dictionary, most mentioned first.
+pub fn count_gear(videos: &[Vec<String>]) -> Vec<GearCount> {
+    let mut counts: HashMap<&str, i64> = HashMap::new();
+
+    for gear in videos {
+        for name in gear {
+            *counts.entry(name).or_insert(0) += 1;
+        }
+    }
+
+    let mut gear = GEAR_DICTIONARY
+        .iter()
+        .filter_map(|item| {
+            Some(GearCount {
+                name: item.name.to_string(),
+                brand: item.brand.to_string(),
+                video_count: *counts.get(item.name)?,
+            })
+        })
+        .collect::<Vec<GearCount>>();
+
+    gear.sort_by(|a, b| {
+        b.video_count
+            .cmp(&a.video_count)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    gear.truncate(MAX_CHANNEL_GEAR);
+
+    gear
+}
+
+pub fn get_gear_documents(gear: &[GearCount]) -> Vec<Document> {
+    gear.iter()
+        .map(|gear| doc! {"name": &gear.name, "brand": &gear.brand, "videoCount": gear.video_count})
+        .collect()
+}
+
+/// Reads the gear names stored on a video document.
+pub fn get_gear_names(video: &Document) -> Vec<String> {
+    get_strings(video, "gear")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GearCount;
+
+    #[test]
+    fn count_gear() {
+        let videos = vec![
+            vec![
+                "Fender Stratocaster".to_string(),
+                "Line 6 Helix".to_string(),
+            ],
+            vec!["Line 6 Helix".to_string()],
+            vec![],
+        ];
+
+        assert_eq!(
+            super::count_gear(&videos),
+            vec![
+                GearCount {
+                    name: "Line 6 Helix".to_string(),
+                    brand: "Line 6".to_string(),
+                    video_count: 2
+                },
+                GearCount {
+                    name: "Fender Stratocaster".to_string(),
+                    brand: "Fender".to_string(),
+                    video_count: 1
+                },
+            ]
+        );
+    }
+}
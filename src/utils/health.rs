@@ -0,0 +1,106 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tokio::sync::{mpsc::Sender, Mutex};
+
+use crate::repos::store_factory::StoreFactory;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+type QueueDepth = Box<dyn Fn() -> usize + Send + Sync>;
+
+/// Shared health state for the liveness and readiness probes. Crawlers and jobs record each
+/// successful run, scraper queues are registered once at startup.
+pub struct Health {
+    stores: StoreFactory,
+    youtube_api_base_url: String,
+    last_successes: Mutex<BTreeMap<String, DateTime<Utc>>>,
+    queues: Mutex<Vec<(String, QueueDepth)>>,
+}
+
+impl Health {
+    pub fn new(stores: StoreFactory, youtube_api_base_url: &str) -> Health {
+        Health {
+            stores,
+            youtube_api_base_url: youtube_api_base_url.to_string(),
+            last_successes: Mutex::new(BTreeMap::new()),
+            queues: Mutex::new(vec![]),
+        }
+    }
+
+    pub async fn record_success(&self, component: &str) {
+        self.last_successes
+            .lock()
+            .await
+            .insert(component.to_string(), Utc::now());
+    }
+
+    pub async fn get_last_successes(&self) -> BTreeMap<String, DateTime<Utc>> {
+        self.last_successes.lock().await.clone()
+    }
+
+    /// Queue depth is derived from the free capacity, so `capacity` has to match the capacity
+    /// the channel was created with.
+    pub async fn register_queue<T: Send + 'static>(
+        &self,
+        name: &str,
+        sender: Sender<T>,
+        capacity: usize,
+    ) {
+        self.queues.lock().await.push((
+            name.to_string(),
+            Box::new(move || capacity - sender.capacity()),
+        ));
+    }
+
+    pub async fn get_queue_depths(&self) -> BTreeMap<String, usize> {
+        self.queues
+            .lock()
+            .await
+            .iter()
+            .map(|(name, depth)| (name.clone(), depth()))
+            .collect()
+    }
+
+    /// Whether the database of the configured storage backend answers.
+    pub async fn is_database_reachable(&self) -> bool {
+        self.stores.is_reachable().await
+    }
+
+    /// Any HTTP response counts, the probe only checks that the API host can be reached.
+    pub async fn is_youtube_api_reachable(&self) -> bool {
+        let client = match reqwest::Client::builder().timeout(PROBE_TIMEOUT).build() {
+            Ok(client) => client,
+            Err(_) => return false,
+        };
+
+        client.get(&self.youtube_api_base_url).send().await.is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::Client;
+    use tokio::sync::mpsc::channel;
+
+    use super::Health;
+    use crate::repos::store_factory::StoreFactory;
+
+    #[tokio::test]
+    async fn reports_queue_depth_and_last_successes() {
+        let client = Client::with_uri_str("mongodb://localhost:27017")
+            .await
+            .unwrap();
+        let health = Health::new(StoreFactory::mongodb(client, "test"), "http://localhost/");
+        let (tx, _rx) = channel::<u32>(10);
+
+        health.register_queue("scraper", tx.clone(), 10).await;
+        tx.send(1).await.unwrap();
+        tx.send(2).await.unwrap();
+        health.record_success("crawler").await;
+
+        assert_eq!(health.get_queue_depths().await.get("scraper"), Some(&2));
+        assert!(health.get_last_successes().await.contains_key("crawler"));
+    }
+}
@@ -0,0 +1,132 @@
+use mongodb::bson::{doc, Bson, DateTime, Document};
+
+use crate::utils::ban_evasion_utils::AVATAR_MAX_DISTANCE;
+
+pub const IMAGE_AVATAR: &str = "avatar";
+pub const IMAGE_BANNER: &str = "banner";
+
+// Changes kept per channel, older ones are dropped
+const MAX_IMAGE_CHANGES: usize = 20;
+
+/// Hex, bson has no unsigned 64 bit integers.
+pub fn format_image_hash(hash: u64) -> String {
+    format!("{:016x}", hash)
+}
+
+pub fn parse_image_hash(hash: &str) -> Option<u64> {
+    u64::from_str_radix(hash, 16).ok()
+}
+
+/// Adds the image hashes of a crawl to the channel fields as `avatarHash` and `bannerHash`. An
+/// image that no longer looks like the stored one is appended to `imageChanges` as
+/// `{image, from, to, at}` and sets `rebrandedAt`. Images that failed to load keep their stored
+/// hash.
+pub fn add_image_hashes(
+    channel: &mut Document,
+    stored: Option<&Document>,
+    avatar_hash: Option<u64>,
+    banner_hash: Option<u64>,
+    now: DateTime,
+) {
+    let mut changes = stored
+        .and_then(|stored| stored.get_array("imageChanges").ok())
+        .cloned()
+        .unwrap_or_default();
+    let mut changed = false;
+
+    for (image, field, hash) in [
+        (IMAGE_AVATAR, "avatarHash", avatar_hash),
+        (IMAGE_BANNER, "bannerHash", banner_hash),
+    ] {
+        let hash = match hash {
+            Some(hash) => hash,
+            None => continue,
+        };
+
+        let previous = stored
+            .and_then(|stored| stored.get_str(field).ok())
+            .and_then(parse_image_hash);
+        if let Some(previous) = previous {
+            if (previous ^ hash).count_ones() > AVATAR_MAX_DISTANCE {
+                changes.push(Bson::Document(doc! {
+                    "image": image,
+                    "from": format_image_hash(previous),
+                    "to": format_image_hash(hash),
+                    "at": now,
+                }));
+                changed = true;
+            }
+        }
+
+        channel.insert(field, format_image_hash(hash));
+    }
+
+    if changed {
+        let dropped = changes.len().saturating_sub(MAX_IMAGE_CHANGES);
+        channel.insert("imageChanges", changes.split_off(dropped));
+        channel.insert("rebrandedAt", now);
+    }
+}
+
+/// The avatar hashes the channel had before its current one, from `imageChanges`.
+pub fn get_previous_avatar_hashes(channel: &Document) -> Vec<u64> {
+    channel
+        .get_array("imageChanges")
+        .map(|changes| {
+            changes
+                .iter()
+                .filter_map(|change| {
+                    let change = change.as_document()?;
+                    if change.get_str("image").ok()? != IMAGE_AVATAR {
+                        return None;
+                    }
+
+                    parse_image_hash(change.get_str("from").ok()?)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::{doc, DateTime};
+
+    #[test]
+    fn records_changed_images_only() {
+        let stored = doc! {
+            "avatarHash": super::format_image_hash(0xff00ff00ff00ff00),
+            "bannerHash": super::format_image_hash(0x00ff00ff00ff00ff),
+        };
+        let now = DateTime::from_millis(1_700_000_000_000);
+
+        let mut channel = doc! {};
+        super::add_image_hashes(
+            &mut channel,
+            Some(&stored),
+            Some(0xff00ff00ff00ff01),
+            None,
+            now,
+        );
+        assert_eq!(channel, doc! {"avatarHash": "ff00ff00ff00ff01"});
+
+        let mut channel = doc! {};
+        super::add_image_hashes(
+            &mut channel,
+            Some(&stored),
+            Some(0x0f0f0f0f0f0f0f0f),
+            None,
+            now,
+        );
+        assert_eq!(channel.get_datetime("rebrandedAt").ok(), Some(&now));
+        assert_eq!(
+            super::get_previous_avatar_hashes(&channel),
+            vec![0xff00ff00ff00ff00]
+        );
+
+        let mut channel = doc! {};
+        super::add_image_hashes(&mut channel, None, Some(1), Some(2), now);
+        assert!(!channel.contains_key("imageChanges"));
+        assert_eq!(channel.get_str("bannerHash").ok(), Some("0000000000000002"));
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::HashSet;
+
+use anyhow::{anyhow, Error};
+use serde_json::Value;
+
+use crate::utils::youtube_url_utils::{parse_youtube_url, YoutubeResource};
+
+// Columns and object keys that hold the channel, the first CSV column is used otherwise
+const CHANNEL_KEYS: [&str; 4] = ["channel", "id", "handle", "url"];
+
+#[derive(Debug, Default, PartialEq)]
+pub struct ChannelImport {
+    /// Channel ids and lowercase `@handles` in file order
+    pub channels: Vec<String>,
+    pub duplicates: Vec<String>,
+    pub invalid: Vec<String>,
+}
+
+/// Reads channel ids, handles or urls from a JSON array of strings or objects, or from CSV.
+pub fn parse_channel_import(content: &str, is_json: bool) -> Result<ChannelImport, Error> {
+    let entries = if is_json {
+        parse_json_entries(content)?
+    } else {
+        parse_csv_entries(content)
+    };
+
+    let mut import = ChannelImport::default();
+    let mut seen = HashSet::new();
+
+    for entry in entries {
+        let channel = match parse_youtube_url(&entry) {
+            Some(YoutubeResource::Channel(channel_id)) => channel_id,
+            Some(YoutubeResource::Handle(handle)) => handle,
+            _ => {
+                import.invalid.push(entry);
+                continue;
+            }
+        };
+
+        if seen.insert(channel.clone()) {
+            import.channels.push(channel);
+        } else {
+            import.duplicates.push(channel);
+        }
+    }
+
+    Ok(import)
+}
+
+fn parse_json_entries(content: &str) -> Result<Vec<String>, Error> {
+    let value: Value = serde_json::from_str(content)?;
+    let items = value
+        .as_array()
+        .ok_or_else(|| anyhow!("Expected a JSON array of channels"))?;
+
+    let entries = items
+        .iter()
+        .map(|item| match item {
+            Value::String(entry) => entry.to_string(),
+            Value::Object(object) => CHANNEL_KEYS
+                .iter()
+                .find_map(|key| object.get(*key).and_then(Value::as_str))
+                .unwrap_or_default()
+                .to_string(),
+            other => other.to_string(),
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+fn parse_csv_entries(content: &str) -> Vec<String> {
+    let mut lines = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .peekable();
+
+    let header = lines
+        .peek()
+        .map(|line| split_csv_line(line))
+        .unwrap_or_default();
+    let header_column = header
+        .iter()
+        .position(|column| CHANNEL_KEYS.contains(&column.to_lowercase().as_str()));
+
+    if header_column.is_some() {
+        lines.next();
+    }
+
+    let column = header_column.unwrap_or(0);
+
+    lines
+        .map(|line| {
+            split_csv_line(line)
+                .get(column)
+                .cloned()
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+fn split_csv_line(line: &str) -> Vec<String> {
+    line.split(',')
+        .map(|value| value.trim().trim_matches('"').to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    const CHANNEL_ID: &str = "UCuAXFkgsw1L7xaCfnd5JJOw";
+
+    #[test]
+    fn imports_csv_with_header() {
+        let content = format!(
+            "name,url\nFirst,https://www.youtube.com/channel/{}\nSecond,{}\nThird,@Handle\nFourth,not a channel\n",
+            CHANNEL_ID, CHANNEL_ID
+        );
+
+        let import = super::parse_channel_import(&content, false).unwrap();
+
+        assert_eq!(import.channels, vec![CHANNEL_ID, "@handle"]);
+        assert_eq!(import.duplicates, vec![CHANNEL_ID]);
+        assert_eq!(import.invalid, vec!["not a channel"]);
+    }
+
+    #[test]
+    fn imports_json_strings_and_objects() {
+        let content = format!(
+            r#"["@handle", {{"channel": "{}"}}, {{"handle": "@HANDLE"}}, 42]"#,
+            CHANNEL_ID
+        );
+
+        let import = super::parse_channel_import(&content, true).unwrap();
+
+        assert_eq!(import.channels, vec!["@handle", CHANNEL_ID]);
+        assert_eq!(import.duplicates, vec!["@handle"]);
+        assert_eq!(import.invalid, vec!["42"]);
+        assert!(super::parse_channel_import("{}", true).is_err());
+    }
+}
@@ -10,6 +10,31 @@ pub fn parse_keywords(keyword_str: &str) -> Vec<String> {
         .collect::<Vec<String>>()
 }
 
+pub fn extract_known_keywords(text: &str, vocabulary: &[String]) -> Vec<String> {
+    let text = text.to_lowercase();
+    let mut keywords = vocabulary
+        .iter()
+        .filter(|keyword| !keyword.is_empty() && text.contains(&keyword.to_lowercase()))
+        .map(|keyword| keyword.to_lowercase())
+        .collect::<Vec<String>>();
+
+    keywords.sort();
+    keywords.dedup();
+
+    keywords
+}
+
+/// Turns a topic category URL like `https://en.wikipedia.org/wiki/Rock_music` into `rock music`.
+pub fn parse_topic_category(topic_url: &str) -> Option<String> {
+    let topic = topic_url.trim_end_matches('/').rsplit('/').next()?;
+
+    if topic.is_empty() {
+        return None;
+    }
+
+    Some(topic.replace('_', " ").to_lowercase())
+}
+
 fn sanitize_keyword(keyword: &str) -> String {
     let keyword = keyword.replace("\"", "");
     let keyword = keyword.replace("\\", "");
@@ -30,4 +55,26 @@ mod tests {
         let keywords = super::parse_keywords("keyword \"keyword keyword1\" keyword2");
         assert_eq!(keywords.len(), 3);
     }
+
+    #[test]
+    fn parse_topic_category_from_wikipedia_url() {
+        let topic = super::parse_topic_category("https://en.wikipedia.org/wiki/Rock_music");
+        assert_eq!(topic, Some("rock music".to_string()));
+        assert_eq!(super::parse_topic_category(""), None);
+    }
+
+    #[test]
+    fn extract_known_keywords_case_insensitive_and_deduped() {
+        let vocabulary = vec![
+            "Sweep Picking".to_string(),
+            "tapping".to_string(),
+            "tapping".to_string(),
+            "legato".to_string(),
+        ];
+        let keywords = super::extract_known_keywords(
+            "Today some sweep picking and TAPPING licks",
+            &vocabulary,
+        );
+        assert_eq!(keywords, vec!["sweep picking", "tapping"]);
+    }
 }
@@ -0,0 +1,422 @@
+use std::collections::HashMap;
+
+use mongodb::bson::{doc, DateTime, Document};
+use regex::Regex;
+use reqwest::Url;
+
+pub const RESOURCE_TYPE_TAB: &str = "tab";
+pub const RESOURCE_TYPE_PATREON: &str = "patreon";
+pub const RESOURCE_TYPE_PDF: &str = "pdf";
+pub const RESOURCE_TYPE_LESSON: &str = "lesson";
+
+const TAB_HOSTS: [&str; 4] = [
+    "ultimate-guitar.com",
+    "songsterr.com",
+    "soundslice.com",
+    "guitarprotabs.org",
+];
+const LESSON_HOSTS: [&str; 3] = ["gumroad.com", "teachable.com", "thinkific.com"];
+
+pub const LINK_TYPE_WEBSITE: &str = "website";
+pub const LINK_TYPE_PATREON: &str = "patreon";
+pub const LINK_TYPE_INSTAGRAM: &str = "instagram";
+pub const LINK_TYPE_TWITTER: &str = "twitter";
+pub const LINK_TYPE_FACEBOOK: &str = "facebook";
+pub const LINK_TYPE_TIKTOK: &str = "tiktok";
+pub const LINK_TYPE_DISCORD: &str = "discord";
+pub const LINK_TYPE_TAB_STORE: &str = "tabStore";
+
+pub const LINK_STATUS_ALIVE: &str = "alive";
+pub const LINK_STATUS_DEAD: &str = "dead";
+/// Blocked crawlers, server errors and timeouts say nothing about the link itself
+pub const LINK_STATUS_UNKNOWN: &str = "unknown";
+/// Links to or redirecting to private, loopback or link-local addresses, which are not requested
+pub const LINK_STATUS_BLOCKED: &str = "blocked";
+
+const SOCIAL_HOSTS: [(&str, &str); 7] = [
+    ("patreon.com", LINK_TYPE_PATREON),
+    ("instagram.com", LINK_TYPE_INSTAGRAM),
+    ("twitter.com", LINK_TYPE_TWITTER),
+    ("x.com", LINK_TYPE_TWITTER),
+    ("facebook.com", LINK_TYPE_FACEBOOK),
+    ("tiktok.com", LINK_TYPE_TIKTOK),
+    ("discord.gg", LINK_TYPE_DISCORD),
+];
+const YOUTUBE_HOSTS: [&str; 3] = ["youtube.com", "youtu.be", "youtube-nocookie.com"];
+const URL_PATTERN: &str = r#"https?://[^\s<>"')\]]+"#;
+// Link shorteners common in descriptions, affiliate links mostly hide behind amzn.to
+const SHORTENER_HOSTS: [&str; 9] = [
+    "bit.ly",
+    "youtu.be",
+    "amzn.to",
+    "tinyurl.com",
+    "goo.gl",
+    "ow.ly",
+    "t.co",
+    "rebrand.ly",
+    "geni.us",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResourceLink {
+    pub resource_type: &'static str,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExternalLink {
+    pub link_type: &'static str,
+    pub url: String,
+    pub handle: Option<String>,
+}
+
+/// The outcome of resolving a stored link, typed by where its redirects end up.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkCheck {
+    pub url: String,
+    pub final_url: Option<String>,
+    pub link_type: &'static str,
+    pub status: &'static str,
+    pub http_status: Option<u16>,
+}
+
+pub fn extract_urls(text: &str) -> Vec<String> {
+    let regex = Regex::new(URL_PATTERN).unwrap();
+
+    let mut urls = regex
+        .find_iter(text)
+        .map(|m| {
+            m.as_str()
+                .trim_end_matches(&['.', ',', ';', '!'][..])
+                .to_string()
+        })
+        .collect::<Vec<String>>();
+
+    urls.dedup();
+
+    urls
+}
+
+pub fn is_shortened_url(url: &str) -> bool {
+    let host = match Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_lowercase))
+    {
+        Some(host) => host,
+        None => return false,
+    };
+    let host = host.trim_start_matches("www.");
+
+    SHORTENER_HOSTS.contains(&host)
+}
+
+/// Replaces each resolved URL in the text by where it leads, so the link mining sees the target.
+pub fn expand_urls(text: &str, resolved_urls: &HashMap<String, String>) -> String {
+    if resolved_urls.is_empty() {
+        return text.to_string();
+    }
+
+    let regex = Regex::new(URL_PATTERN).unwrap();
+
+    regex
+        .replace_all(text, |captures: &regex::Captures| {
+            let found = &captures[0];
+            let url = found.trim_end_matches(&['.', ',', ';', '!'][..]);
+
+            match resolved_urls.get(url) {
+                Some(resolved_url) => format!("{}{}", resolved_url, &found[url.len()..]),
+                None => found.to_string(),
+            }
+        })
+        .to_string()
+}
+
+/// Detects links to tabs, lesson shops and downloads, e.g. Ultimate Guitar, Songsterr or Patreon.
+pub fn detect_resource_links(text: &str) -> Vec<ResourceLink> {
+    let mut links: Vec<ResourceLink> = vec![];
+
+    for url in extract_urls(text) {
+        let resource_type = match classify_resource_url(&url) {
+            Some(resource_type) => resource_type,
+            None => continue,
+        };
+
+        if links.iter().any(|link| link.url == url) {
+            continue;
+        }
+
+        links.push(ResourceLink { resource_type, url });
+    }
+
+    links
+}
+
+/// Normalizes a URL for deduplication: https scheme, lowercase host without `www.`, no
+/// fragment, no tracking parameters and no trailing slash.
+pub fn normalize_url(url: &str) -> Option<String> {
+    let mut parsed = Url::parse(url).ok()?;
+    let host = parsed.host_str()?.to_lowercase();
+    let host = host.trim_start_matches("www.").to_string();
+
+    let query = parsed
+        .query_pairs()
+        .filter(|(key, _)| !key.starts_with("utm_"))
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<String>>();
+
+    parsed.set_fragment(None);
+    parsed.set_query(None);
+    parsed.set_scheme("https").ok()?;
+    parsed.set_host(Some(&host)).ok()?;
+
+    let mut normalized = parsed.to_string().trim_end_matches('/').to_string();
+
+    if !query.is_empty() {
+        normalized = format!("{}?{}", normalized, query.join("&"));
+    }
+
+    Some(normalized)
+}
+
+/// Extracts normalized, deduplicated links to websites and social profiles. Links back to
+/// YouTube are skipped.
+pub fn extract_external_links(text: &str) -> Vec<ExternalLink> {
+    let mut links: Vec<ExternalLink> = vec![];
+
+    for url in extract_urls(text) {
+        let url = match normalize_url(&url) {
+            Some(url) => url,
+            None => continue,
+        };
+
+        if links.iter().any(|link| link.url == url) {
+            continue;
+        }
+
+        if let Some(link) = classify_external_url(&url) {
+            links.push(link);
+        }
+    }
+
+    links
+}
+
+pub fn extract_emails(text: &str) -> Vec<String> {
+    let regex = Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}").unwrap();
+
+    let mut emails = regex
+        .find_iter(text)
+        .map(|m| m.as_str().to_lowercase())
+        .collect::<Vec<String>>();
+
+    emails.sort();
+    emails.dedup();
+
+    emails
+}
+
+/// The liveness of a link from the HTTP status it resolved to, `None` if the host did not answer.
+pub fn get_link_status(http_status: Option<u16>, connect_failed: bool) -> &'static str {
+    match http_status {
+        Some(200..=399) => LINK_STATUS_ALIVE,
+        Some(404) | Some(410) => LINK_STATUS_DEAD,
+        Some(_) => LINK_STATUS_UNKNOWN,
+        None if connect_failed => LINK_STATUS_DEAD,
+        None => LINK_STATUS_UNKNOWN,
+    }
+}
+
+pub fn get_link_check_document(check: &LinkCheck) -> Document {
+    doc! {
+        "url": &check.url,
+        "finalUrl": &check.final_url,
+        "type": check.link_type,
+        "status": check.status,
+        "httpStatus": check.http_status.map(|status| status as i32),
+        "checkedAt": DateTime::now(),
+    }
+}
+
+/// Types links to websites and social profiles, `None` for links back to YouTube.
+pub fn classify_external_url(url: &str) -> Option<ExternalLink> {
+    let parsed = Url::parse(url).ok()?;
+    let host = parsed.host_str()?.to_string();
+
+    let matches_host =
+        |candidate: &str| host == candidate || host.ends_with(&format!(".{}", candidate));
+
+    if YOUTUBE_HOSTS
+        .iter()
+        .any(|candidate| matches_host(candidate))
+    {
+        return None;
+    }
+
+    if TAB_HOSTS
+        .iter()
+        .chain(LESSON_HOSTS.iter())
+        .any(|candidate| matches_host(candidate))
+    {
+        return Some(ExternalLink {
+            link_type: LINK_TYPE_TAB_STORE,
+            url: url.to_string(),
+            handle: None,
+        });
+    }
+
+    let social = SOCIAL_HOSTS
+        .iter()
+        .find(|(candidate, _)| matches_host(candidate));
+
+    let link = match social {
+        Some((_, link_type)) => ExternalLink {
+            link_type,
+            url: url.to_string(),
+            handle: parsed
+                .path_segments()
+                .and_then(|mut segments| segments.find(|s| !s.is_empty()))
+                .map(|segment| segment.trim_start_matches('@').to_lowercase()),
+        },
+        None => ExternalLink {
+            link_type: LINK_TYPE_WEBSITE,
+            url: url.to_string(),
+            handle: None,
+        },
+    };
+
+    Some(link)
+}
+
+fn classify_resource_url(url: &str) -> Option<&'static str> {
+    let parsed = Url::parse(url).ok()?;
+    let host = parsed.host_str()?.to_lowercase();
+
+    let matches_host =
+        |candidate: &&str| host == *candidate || host.ends_with(&format!(".{}", candidate));
+
+    if parsed.path().to_lowercase().ends_with(".pdf") {
+        return Some(RESOURCE_TYPE_PDF);
+    }
+
+    if TAB_HOSTS.iter().any(matches_host) {
+        return Some(RESOURCE_TYPE_TAB);
+    }
+
+    if matches_host(&"patreon.com") {
+        return Some(RESOURCE_TYPE_PATREON);
+    }
+
+    if LESSON_HOSTS.iter().any(matches_host) {
+        return Some(RESOURCE_TYPE_LESSON);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{
+        LINK_STATUS_ALIVE, LINK_STATUS_DEAD, LINK_STATUS_UNKNOWN, LINK_TYPE_INSTAGRAM,
+        LINK_TYPE_TAB_STORE, LINK_TYPE_WEBSITE, RESOURCE_TYPE_PATREON, RESOURCE_TYPE_PDF,
+        RESOURCE_TYPE_TAB,
+    };
+
+    #[test]
+    fn extract_urls_strips_trailing_punctuation() {
+        let urls = super::extract_urls("Tabs: https://example.com/tab. More (https://a.io/b)");
+        assert_eq!(urls, vec!["https://example.com/tab", "https://a.io/b"]);
+    }
+
+    #[test]
+    fn detect_typed_resource_links() {
+        let description =
+            "Get the tab https://tabs.ultimate-guitar.com/tab/metallica/one-tabs-1234\n\
+            Songsterr: https://www.songsterr.com/a/wsa/one-tab-s123\n\
+            Support me https://www.patreon.com/guitarlessons\n\
+            PDF https://example.com/files/lesson.pdf\n\
+            Instagram https://instagram.com/guitarlessons";
+
+        let links = super::detect_resource_links(description);
+        let types = links
+            .iter()
+            .map(|link| link.resource_type)
+            .collect::<Vec<&str>>();
+
+        assert_eq!(
+            types,
+            vec![
+                RESOURCE_TYPE_TAB,
+                RESOURCE_TYPE_TAB,
+                RESOURCE_TYPE_PATREON,
+                RESOURCE_TYPE_PDF
+            ]
+        );
+    }
+
+    #[test]
+    fn normalize_url_strips_tracking_and_www() {
+        let url = super::normalize_url("http://www.Example.com/shop/?utm_source=yt&id=3#top");
+        assert_eq!(url, Some("https://example.com/shop?id=3".to_string()));
+    }
+
+    #[test]
+    fn extract_deduplicated_external_links() {
+        let text = "Website https://www.example.com/ and http://example.com\n\
+            Insta https://instagram.com/@GuitarLessons\n\
+            Video https://www.youtube.com/watch?v=abcdefghijk";
+
+        let links = super::extract_external_links(text);
+
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0].link_type, LINK_TYPE_WEBSITE);
+        assert_eq!(links[0].url, "https://example.com");
+        assert_eq!(links[1].link_type, LINK_TYPE_INSTAGRAM);
+        assert_eq!(links[1].handle, Some("guitarlessons".to_string()));
+    }
+
+    #[test]
+    fn classify_tab_stores_and_link_status() {
+        let link = super::classify_external_url("https://www.songsterr.com/a/wsa/one-tab-s123");
+        assert_eq!(link.map(|link| link.link_type), Some(LINK_TYPE_TAB_STORE));
+
+        assert_eq!(super::get_link_status(Some(200), false), LINK_STATUS_ALIVE);
+        assert_eq!(super::get_link_status(Some(404), false), LINK_STATUS_DEAD);
+        assert_eq!(
+            super::get_link_status(Some(403), false),
+            LINK_STATUS_UNKNOWN
+        );
+        assert_eq!(super::get_link_status(None, true), LINK_STATUS_DEAD);
+        assert_eq!(super::get_link_status(None, false), LINK_STATUS_UNKNOWN);
+    }
+
+    #[test]
+    fn expand_shortened_urls() {
+        assert!(super::is_shortened_url("https://bit.ly/3xYz"));
+        assert!(super::is_shortened_url("http://www.amzn.to/abc"));
+        assert!(!super::is_shortened_url(
+            "https://bitly.com.example.org/abc"
+        ));
+        assert!(!super::is_shortened_url("not a url"));
+
+        let resolved_urls = HashMap::from([(
+            "https://amzn.to/abc".to_string(),
+            "https://amazon.com/Fender-Player-Stratocaster/dp/B07".to_string(),
+        )]);
+
+        assert_eq!(
+            super::expand_urls(
+                "My guitar: https://amzn.to/abc. Tabs https://bit.ly/tab",
+                &resolved_urls
+            ),
+            "My guitar: https://amazon.com/Fender-Player-Stratocaster/dp/B07. Tabs https://bit.ly/tab"
+        );
+    }
+
+    #[test]
+    fn extract_emails_lowercased() {
+        let emails = super::extract_emails("Business: Booking@Example.com or booking@example.com");
+        assert_eq!(emails, vec!["booking@example.com"]);
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tokio::sync::{Mutex, Notify};
+use tokio::time::sleep;
+
+// Pause between units of work of scrapers and crawlers while the database is overloaded
+const OVERLOAD_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone)]
+pub struct MaintenanceState {
+    pub reason: String,
+    pub since: DateTime<Utc>,
+}
+
+/// Shared maintenance flag. Crawlers, scrapers and jobs call `checkpoint` between units of work,
+/// so in-flight work finishes and the component then waits until maintenance is lifted.
+///
+/// The overload flag is set by the `DbLoadMonitor`. Checkpoints are slowed down while it is set,
+/// and background jobs wait in `background_checkpoint` until the database recovers.
+pub struct Maintenance {
+    state: Mutex<Option<MaintenanceState>>,
+    paused_components: Mutex<BTreeSet<String>>,
+    resumed: Notify,
+    overloaded: AtomicBool,
+    recovered: Notify,
+}
+
+impl Maintenance {
+    pub fn new(reason: Option<String>) -> Maintenance {
+        let state = reason.map(|reason| MaintenanceState {
+            reason,
+            since: Utc::now(),
+        });
+
+        Maintenance {
+            state: Mutex::new(state),
+            paused_components: Mutex::new(BTreeSet::new()),
+            resumed: Notify::new(),
+            overloaded: AtomicBool::new(false),
+            recovered: Notify::new(),
+        }
+    }
+
+    /// Called from the event handlers of the MongoDB driver, so it does not wait.
+    pub fn set_overloaded(&self, overloaded: bool) {
+        self.overloaded.store(overloaded, Ordering::SeqCst);
+
+        if !overloaded {
+            self.recovered.notify_waiters();
+        }
+    }
+
+    pub fn is_overloaded(&self) -> bool {
+        self.overloaded.load(Ordering::SeqCst)
+    }
+
+    pub async fn enable(&self, reason: &str) {
+        *self.state.lock().await = Some(MaintenanceState {
+            reason: reason.to_string(),
+            since: Utc::now(),
+        });
+    }
+
+    pub async fn disable(&self) {
+        *self.state.lock().await = None;
+        self.resumed.notify_waiters();
+    }
+
+    pub async fn get_state(&self) -> Option<MaintenanceState> {
+        self.state.lock().await.clone()
+    }
+
+    pub async fn get_paused_components(&self) -> Vec<String> {
+        self.paused_components
+            .lock()
+            .await
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    pub async fn checkpoint(&self, component: &str) {
+        loop {
+            // Registered before checking the state so a concurrent `disable` is not missed
+            let resumed = self.resumed.notified();
+
+            if self.state.lock().await.is_none() {
+                self.paused_components.lock().await.remove(component);
+                break;
+            }
+
+            self.paused_components
+                .lock()
+                .await
+                .insert(component.to_string());
+
+            resumed.await;
+        }
+
+        if self.is_overloaded() {
+            sleep(OVERLOAD_DELAY).await;
+        }
+    }
+
+    /// Like `checkpoint`, for work that can wait: it is paused entirely while the database is
+    /// overloaded.
+    pub async fn background_checkpoint(&self, component: &str) {
+        loop {
+            let recovered = self.recovered.notified();
+
+            if !self.is_overloaded() {
+                self.checkpoint(component).await;
+                return;
+            }
+
+            self.paused_components
+                .lock()
+                .await
+                .insert(component.to_string());
+
+            recovered.await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::Maintenance;
+
+    #[tokio::test]
+    async fn checkpoint_waits_until_maintenance_is_disabled() {
+        let maintenance = Arc::new(Maintenance::new(Some("db upgrade".to_string())));
+
+        let waiting = maintenance.clone();
+        let handle = tokio::spawn(async move { waiting.checkpoint("crawler").await });
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(maintenance.get_paused_components().await, vec!["crawler"]);
+        assert!(!handle.is_finished());
+
+        maintenance.disable().await;
+        handle.await.unwrap();
+
+        assert!(maintenance.get_paused_components().await.is_empty());
+        assert!(maintenance.get_state().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn background_checkpoint_waits_until_the_database_recovers() {
+        let maintenance = Arc::new(Maintenance::new(None));
+        maintenance.set_overloaded(true);
+
+        let waiting = maintenance.clone();
+        let handle = tokio::spawn(async move { waiting.background_checkpoint("job").await });
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(maintenance.get_paused_components().await, vec!["job"]);
+        assert!(!handle.is_finished());
+
+        maintenance.set_overloaded(false);
+        handle.await.unwrap();
+
+        assert!(maintenance.get_paused_components().await.is_empty());
+    }
+}
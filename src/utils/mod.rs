@@ -1,3 +1,49 @@
+pub mod alert_utils;
+pub mod api_key_health_utils;
+pub mod api_scheduler;
+pub mod availability_utils;
+pub mod ban_evasion_utils;
+pub mod channel_metadata_utils;
+pub mod channel_page_utils;
+pub mod channel_summary_utils;
+pub mod chapter_parser;
+pub mod collaboration_utils;
+pub mod config_utils;
 pub mod consts;
+pub mod course_utils;
+pub mod crawl_budget;
+pub mod crawl_request_utils;
+pub mod date_utils;
 pub mod db;
+pub mod discovery_policy_utils;
+pub mod document_utils;
+pub mod duplicate_utils;
+pub mod duration_utils;
+pub mod edit_history_utils;
+pub mod end_screen_utils;
+pub mod feed_utils;
+pub mod gear_utils;
+pub mod health;
+pub mod image_change_utils;
+pub mod import_utils;
 pub mod keyword_utils;
+pub mod link_utils;
+pub mod maintenance;
+pub mod popularity_utils;
+pub mod probation_utils;
+pub mod proxy_pool;
+pub mod purge_utils;
+pub mod quota_utils;
+pub mod rate_limiter;
+pub mod schedule_utils;
+pub mod sentiment_utils;
+pub mod similarity_utils;
+pub mod song_utils;
+pub mod stat_anomaly_utils;
+pub mod subscriber_utils;
+pub mod tag_utils;
+pub mod throttle;
+pub mod topic_drift_utils;
+pub mod upload_pattern_utils;
+pub mod url_guard;
+pub mod youtube_url_utils;
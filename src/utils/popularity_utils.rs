@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+
+// A page view counts half after a week, so channels that stop being viewed lose their priority
+pub const POPULARITY_HALF_LIFE_SECONDS: i64 = 7 * 24 * 60 * 60;
+
+/// Decays a popularity score from when it was last updated to `now`.
+pub fn decay_popularity(score: f64, updated_at: i64, now: i64) -> f64 {
+    let elapsed = (now - updated_at).max(0) as f64;
+
+    score * 0.5f64.powf(elapsed / POPULARITY_HALF_LIFE_SECONDS as f64)
+}
+
+/// Orders channels by their popularity score, most viewed first. Channels without a score keep
+/// their order at the end.
+pub fn sort_by_popularity(channel_ids: &mut [String], scores: &HashMap<String, f64>) {
+    channel_ids.sort_by(|a, b| {
+        let score = |id: &String| scores.get(id).copied().unwrap_or(0.0);
+
+        score(b).partial_cmp(&score(a)).unwrap()
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::POPULARITY_HALF_LIFE_SECONDS;
+
+    #[test]
+    fn decays_and_sorts_by_popularity() {
+        assert_eq!(super::decay_popularity(100.0, 1000, 1000), 100.0);
+        assert_eq!(
+            super::decay_popularity(100.0, 0, 2 * POPULARITY_HALF_LIFE_SECONDS),
+            25.0
+        );
+
+        let mut channel_ids = vec!["UC1", "UC2", "UC3", "UC4"]
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<String>>();
+        let scores = HashMap::from([("UC3".to_string(), 10.0), ("UC2".to_string(), 2.5)]);
+
+        super::sort_by_popularity(&mut channel_ids, &scores);
+
+        assert_eq!(channel_ids, vec!["UC3", "UC2", "UC1", "UC4"]);
+    }
+}
@@ -0,0 +1,111 @@
+use mongodb::bson::Document;
+
+/// Videos of a channel on probation, as many as its RSS feed lists
+pub const PROBATION_VIDEOS: i64 = 15;
+
+pub const PROBATION_REASON_NO_VIDEOS: &str = "noVideos";
+pub const PROBATION_REASON_NO_GUITAR_VIDEOS: &str = "noGuitarVideos";
+pub const PROBATION_REASON_INACTIVE: &str = "inactive";
+
+// Channels passing the guitar terms with their title or description alone are often off-topic
+const MIN_GUITAR_VIDEO_SHARE: f64 = 0.3;
+const MAX_UPLOAD_AGE_SECONDS: i64 = 90 * 24 * 60 * 60;
+
+/// Share of the videos with a guitar term in their title, description or tags.
+pub fn get_guitar_video_share(guitar_terms: &[String], videos: &[Document]) -> f64 {
+    if videos.is_empty() {
+        return 0.0;
+    }
+
+    let guitar_video_count = videos
+        .iter()
+        .filter(|video| {
+            let mut texts = vec![
+                video.get_str("title").unwrap_or_default().to_lowercase(),
+                video
+                    .get_str("description")
+                    .unwrap_or_default()
+                    .to_lowercase(),
+            ];
+            if let Ok(tags) = video.get_array("tags") {
+                texts.extend(
+                    tags.iter()
+                        .filter_map(|tag| tag.as_str())
+                        .map(str::to_lowercase),
+                );
+            }
+
+            guitar_terms
+                .iter()
+                .any(|term| texts.iter().any(|text| text.contains(term.as_str())))
+        })
+        .count();
+
+    guitar_video_count as f64 / videos.len() as f64
+}
+
+/// Returns why a channel fails its probation, or `None` to confirm it: its latest videos have to
+/// be about guitars and it has to have uploaded recently.
+pub fn get_rejection_reason(
+    video_count: usize,
+    guitar_video_share: f64,
+    last_upload_at: Option<i64>,
+    now: i64,
+) -> Option<&'static str> {
+    if video_count == 0 {
+        return Some(PROBATION_REASON_NO_VIDEOS);
+    }
+
+    if guitar_video_share < MIN_GUITAR_VIDEO_SHARE {
+        return Some(PROBATION_REASON_NO_GUITAR_VIDEOS);
+    }
+
+    match last_upload_at {
+        Some(last_upload_at) if now - last_upload_at <= MAX_UPLOAD_AGE_SECONDS => None,
+        _ => Some(PROBATION_REASON_INACTIVE),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::doc;
+
+    use super::{PROBATION_REASON_INACTIVE, PROBATION_REASON_NO_GUITAR_VIDEOS};
+
+    #[test]
+    fn guitar_video_share_counts_titles_descriptions_and_tags() {
+        let terms = vec!["guitar".to_string(), "riff".to_string()];
+        let videos = vec![
+            doc! {"title": "Guitar lesson", "description": ""},
+            doc! {"title": "Vlog", "description": "", "tags": ["metal riffs"]},
+            doc! {"title": "Cooking", "description": "Pasta"},
+            doc! {"title": "Unboxing"},
+        ];
+
+        assert_eq!(super::get_guitar_video_share(&terms, &videos), 0.5);
+        assert_eq!(super::get_guitar_video_share(&terms, &[]), 0.0);
+    }
+
+    #[test]
+    fn rejects_off_topic_and_inactive_channels() {
+        let now = 100 * 24 * 60 * 60;
+
+        assert_eq!(
+            super::get_rejection_reason(15, 0.8, Some(now - 60), now),
+            None
+        );
+        assert_eq!(
+            super::get_rejection_reason(15, 0.1, Some(now - 60), now),
+            Some(PROBATION_REASON_NO_GUITAR_VIDEOS)
+        );
+        assert_eq!(
+            super::get_rejection_reason(15, 0.8, Some(0), now),
+            Some(PROBATION_REASON_INACTIVE)
+        );
+        assert_eq!(
+            super::get_rejection_reason(15, 0.8, None, now),
+            Some(PROBATION_REASON_INACTIVE)
+        );
+        assert!(super::get_rejection_reason(0, 0.0, None, now).is_some());
+    }
+}
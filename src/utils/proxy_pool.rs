@@ -0,0 +1,188 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use anyhow::Error;
+use log::warn;
+use reqwest::{header::HeaderMap, Client, Proxy, Response, StatusCode};
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+const MIN_HEALTHY_SCORE: f64 = 0.2;
+const SUCCESS_REWARD: f64 = 0.25;
+const FAILURE_PENALTY: f64 = 0.5;
+// Banned proxies get tried again once their score has recovered on its own
+const RECOVERY_PER_MINUTE: f64 = 0.05;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+struct ProxyHealth {
+    score: f64,
+    updated_at: Instant,
+}
+
+impl ProxyHealth {
+    fn current_score(&self, now: Instant) -> f64 {
+        let minutes = now.duration_since(self.updated_at).as_secs_f64() / 60.0;
+
+        (self.score + minutes * RECOVERY_PER_MINUTE).min(1.0)
+    }
+}
+
+/// Rotates requests over the configured HTTP(S) and SOCKS proxies. Each proxy has a health score
+/// that drops on connection errors, `403` and `429`, so banned proxies are skipped and a failed
+/// request is tried once more on every other proxy. Without proxies all requests go out directly.
+/// Only feed fetches use the pool.
+pub struct ProxyPool {
+    direct_client: Client,
+    proxy_clients: Vec<(String, Client)>,
+    health: Mutex<Vec<ProxyHealth>>,
+    next: AtomicUsize,
+}
+
+impl ProxyPool {
+    pub fn new(proxy_urls: &[String]) -> Result<ProxyPool, Error> {
+        let mut proxy_clients = vec![];
+
+        for proxy_url in proxy_urls {
+            let client = Client::builder()
+                .proxy(Proxy::all(proxy_url)?)
+                .timeout(REQUEST_TIMEOUT)
+                .build()?;
+            proxy_clients.push((proxy_url.clone(), client));
+        }
+
+        let now = Instant::now();
+        let health = proxy_urls
+            .iter()
+            .map(|_| ProxyHealth {
+                score: 1.0,
+                updated_at: now,
+            })
+            .collect();
+
+        Ok(ProxyPool {
+            direct_client: Client::builder().timeout(REQUEST_TIMEOUT).build()?,
+            proxy_clients,
+            health: Mutex::new(health),
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    pub async fn get(&self, url: &str, headers: HeaderMap) -> Result<Response, Error> {
+        if self.proxy_clients.is_empty() {
+            return Ok(self.direct_client.get(url).headers(headers).send().await?);
+        }
+
+        let mut tried = vec![];
+
+        loop {
+            let index = self.pick(&tried).await;
+            let (proxy_url, client) = &self.proxy_clients[index];
+            tried.push(index);
+
+            let result = client.get(url).headers(headers.clone()).send().await;
+            let is_healthy = match &result {
+                Ok(response) => !matches!(
+                    response.status(),
+                    StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS
+                ),
+                Err(_) => false,
+            };
+
+            self.record(index, is_healthy).await;
+            if is_healthy || tried.len() == self.proxy_clients.len() {
+                return Ok(result?);
+            }
+
+            warn!(
+                "Proxy {} failed, lower its health score and try the next one",
+                proxy_url
+            );
+        }
+    }
+
+    /// Picks among the proxies not `tried` yet for this request.
+    async fn pick(&self, tried: &[usize]) -> usize {
+        let health = self.health.lock().await;
+        let now = Instant::now();
+        let scores = health
+            .iter()
+            .enumerate()
+            .map(|(index, proxy)| match tried.contains(&index) {
+                true => f64::NEG_INFINITY,
+                false => proxy.current_score(now),
+            })
+            .collect::<Vec<f64>>();
+
+        pick_proxy(&scores, self.next.fetch_add(1, Ordering::Relaxed))
+    }
+
+    async fn record(&self, index: usize, is_healthy: bool) {
+        let mut health = self.health.lock().await;
+        let now = Instant::now();
+        let proxy = &mut health[index];
+        let score = proxy.current_score(now);
+
+        proxy.score = if is_healthy {
+            (score + SUCCESS_REWARD).min(1.0)
+        } else {
+            score * FAILURE_PENALTY
+        };
+        proxy.updated_at = now;
+    }
+}
+
+/// Takes the next healthy proxy in round-robin order from `start`, or the best one if none is
+/// healthy.
+fn pick_proxy(scores: &[f64], start: usize) -> usize {
+    (0..scores.len())
+        .map(|offset| (start + offset) % scores.len())
+        .find(|index| scores[*index] >= MIN_HEALTHY_SCORE)
+        .unwrap_or_else(|| {
+            (0..scores.len())
+                .max_by(|a, b| scores[*a].total_cmp(&scores[*b]))
+                .unwrap_or(0)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderMap;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[tokio::test]
+    async fn get_tries_next_proxy_on_connection_error() {
+        let proxy = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/feed"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&proxy)
+            .await;
+
+        // Nothing listens on port 9, the first proxy refuses the connection
+        let pool = super::ProxyPool::new(&["http://127.0.0.1:9".to_string(), proxy.uri()]).unwrap();
+        let response = pool
+            .get("http://feeds.example.com/feed", HeaderMap::new())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+    }
+
+    #[test]
+    fn pick_proxy_rotates_over_healthy_proxies() {
+        let scores = [1.0, 0.1, 0.8];
+
+        assert_eq!(super::pick_proxy(&scores, 0), 0);
+        assert_eq!(super::pick_proxy(&scores, 1), 2);
+        assert_eq!(super::pick_proxy(&scores, 2), 2);
+        assert_eq!(super::pick_proxy(&scores, 3), 0);
+    }
+
+    #[test]
+    fn pick_proxy_falls_back_to_best_score() {
+        assert_eq!(super::pick_proxy(&[0.05, 0.15, 0.1], 0), 1);
+    }
+}
@@ -0,0 +1,202 @@
+use mongodb::bson::{doc, Document};
+
+const ANONYMIZED_PREFIX: &str = "purged-";
+
+/// Filters selecting the data of a channel in the collections besides the channel and video
+/// stores. Thumbnails are only stored as urls on the channels and videos.
+pub fn get_channel_filters(channel_id: &str) -> Vec<(&'static str, Document)> {
+    vec![
+        ("comments", doc! {"channelId": channel_id}),
+        ("captions", doc! {"channel": channel_id}),
+        ("views", doc! {"_id.channel": channel_id}),
+        ("subscribers", doc! {"_id.channel": channel_id}),
+        ("tagprofiles", doc! {"_id": channel_id}),
+        ("channel_summary", doc! {"_id": channel_id}),
+        ("relatedchannels", doc! {"_id": channel_id}),
+        (
+            "collab_edges",
+            doc! {"$or": [{"from": channel_id}, {"to": channel_id}]},
+        ),
+        ("reviewqueue", doc! {"_id": channel_id}),
+        ("additional", doc! {"_id": channel_id}),
+        ("nonguitarchannels", doc! {"_id": channel_id}),
+        ("discoveryprovenance", doc! {"_id": channel_id}),
+        ("backfills", doc! {"_id": channel_id}),
+        ("crawlaudit", doc! {"channel": channel_id}),
+        ("channelpopularity", doc! {"_id": channel_id}),
+        ("endscreenscans", doc! {"channel": channel_id}),
+        ("channelprobations", doc! {"_id": channel_id}),
+        ("courses", doc! {"_id": channel_id}),
+        ("policyrejections", doc! {"_id": channel_id}),
+    ]
+}
+
+/// The conditions of `get_channel_filters` for the PostgreSQL tables, with the channel id as `$1`.
+pub fn get_postgres_channel_conditions() -> Vec<(&'static str, &'static str)> {
+    vec![
+        ("comments", "doc->>'channelId' = $1"),
+        ("captions", "doc->>'channel' = $1"),
+        ("views", "id->>'channel' = $1"),
+        ("subscribers", "id->>'channel' = $1"),
+        ("tagprofiles", "id = $1"),
+        ("channel_summary", "id = $1"),
+        ("relatedchannels", "id = $1"),
+        ("collab_edges", "doc->>'from' = $1 OR doc->>'to' = $1"),
+        ("reviewqueue", "id = $1"),
+        ("additional", "id = $1"),
+        ("nonguitarchannels", "id = $1"),
+        ("discoveryprovenance", "id = $1"),
+        ("backfills", "id = $1"),
+        ("crawlaudit", "doc->>'channel' = $1"),
+        ("channelpopularity", "id = $1"),
+        ("endscreenscans", "doc->>'channel' = $1"),
+        ("channelprobations", "id = $1"),
+        ("courses", "id = $1"),
+        ("policyrejections", "id = $1"),
+    ]
+}
+
+/// Filters selecting the reports that list a channel or its videos among others, with the update
+/// pulling them out. The rest of each report stays.
+pub fn get_report_pulls(
+    channel_id: &str,
+    video_ids: &[String],
+) -> Vec<(&'static str, Document, Document)> {
+    vec![
+        (
+            "digestreports",
+            doc! {"slowestCrawls.channelId": channel_id},
+            doc! {"$pull": {"slowestCrawls": {"channelId": channel_id}}},
+        ),
+        (
+            "reconciliationreports",
+            doc! {"changes.videoId": {"$in": video_ids}},
+            doc! {"$pull": {"changes": {"videoId": {"$in": video_ids}}}},
+        ),
+    ]
+}
+
+/// Random replacement of a purged channel id in the audit logs, shared by the entries of one
+/// purge, so its decisions can still be counted. Unlike a hash of the id, it cannot be recomputed
+/// from a channel id to find the channel's entries.
+pub fn get_anonymized_channel_id() -> String {
+    format!("{}{:016x}", ANONYMIZED_PREFIX, rand::random::<u64>())
+}
+
+pub fn get_counts_document(counts: &[(String, u64)]) -> Document {
+    counts
+        .iter()
+        .map(|(collection, count)| (collection.to_string(), (*count as i64).into()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::fs;
+    use std::path::Path;
+
+    use regex::Regex;
+
+    /// Collections purged by the channel and video stores or by `ChannelPurgeService` itself
+    const PURGED_ELSEWHERE: [&str; 4] = ["channels", "videos", "coldvideos", "channelaudit"];
+
+    /// Collections kept on purpose: opt-outs and blocks keep the channel out of future crawls
+    const KEPT: [&str; 3] = ["optouts", "blocklist", "blacklist"];
+
+    /// Tables emptied as their entries are sent, like the change outbox
+    const DRAINED: [&str; 1] = ["change_outbox"];
+
+    /// Collections without channel or video ids
+    const WITHOUT_CHANNEL_DATA: [&str; 8] = [
+        "apikeys",
+        "corpussnapshots",
+        "guitarterms",
+        "locks",
+        "resolvedurls",
+        "schema_migrations",
+        "settings",
+        "site_stats",
+    ];
+
+    /// Every collection a repo opens and every table a store creates, read from the sources so a
+    /// new one cannot be missed
+    fn get_repo_collections() -> HashSet<String> {
+        let pattern = Regex::new(
+            r#"collection::<\w+>\("(\w+)"\)|const TABLE: &str = "(\w+)"|CREATE TABLE IF NOT EXISTS (\w+) "#,
+        )
+        .unwrap();
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut collections = HashSet::new();
+        let mut dirs = vec![dir];
+
+        while let Some(dir) = dirs.pop() {
+            for entry in fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    dirs.push(path);
+                } else if path.extension().is_some_and(|extension| extension == "rs") {
+                    let source = fs::read_to_string(&path).unwrap();
+                    collections.extend(pattern.captures_iter(&source).filter_map(|captures| {
+                        captures
+                            .iter()
+                            .skip(1)
+                            .flatten()
+                            .next()
+                            .map(|name| name.as_str().to_string())
+                    }));
+                }
+            }
+        }
+
+        collections
+    }
+
+    #[test]
+    fn purges_every_collection_with_channel_data() {
+        let mut handled = super::get_channel_filters("UCabc")
+            .into_iter()
+            .map(|(name, _)| name)
+            .chain(
+                super::get_report_pulls("UCabc", &["video".to_string()])
+                    .into_iter()
+                    .map(|(name, _, _)| name),
+            )
+            .collect::<HashSet<&str>>();
+        handled.extend(PURGED_ELSEWHERE);
+        handled.extend(KEPT);
+        handled.extend(DRAINED);
+        handled.extend(WITHOUT_CHANNEL_DATA);
+
+        // A new collection has to be purged or listed above
+        let unhandled = get_repo_collections()
+            .into_iter()
+            .filter(|collection| !handled.contains(collection.as_str()))
+            .collect::<Vec<String>>();
+
+        assert!(unhandled.is_empty(), "Not purged: {:?}", unhandled);
+    }
+
+    #[test]
+    fn purges_the_same_postgres_tables() {
+        let collections = super::get_channel_filters("UCabc")
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<&str>>();
+        let tables = super::get_postgres_channel_conditions()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<&str>>();
+
+        assert_eq!(tables, collections);
+    }
+
+    #[test]
+    fn anonymizes_channel_ids_randomly() {
+        let anonymized = super::get_anonymized_channel_id();
+
+        assert_ne!(anonymized, super::get_anonymized_channel_id());
+        assert!(anonymized.starts_with("purged-"));
+        assert_eq!(anonymized.len(), "purged-".len() + 16);
+    }
+}
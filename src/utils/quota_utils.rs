@@ -0,0 +1,41 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use chrono_tz::US::Pacific;
+
+/// The Data API quota resets at midnight Pacific time.
+pub fn next_quota_reset(now: DateTime<Utc>) -> DateTime<Utc> {
+    let tomorrow = now.with_timezone(&Pacific).naive_local().date() + Duration::days(1);
+    let midnight = tomorrow.and_hms_opt(0, 0, 0).unwrap();
+
+    Pacific
+        .from_local_datetime(&midnight)
+        .earliest()
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn next_reset_is_pacific_midnight() {
+        // 2022-06-15 20:00 UTC is 13:00 PDT
+        let now = utc("2022-06-15T20:00:00Z");
+        let reset = super::next_quota_reset(now);
+        assert_eq!(reset, utc("2022-06-16T07:00:00Z"));
+    }
+
+    #[test]
+    fn next_reset_in_winter_time() {
+        // 2022-01-15 09:00 UTC is 01:00 PST
+        let now = utc("2022-01-15T09:00:00Z");
+        let reset = super::next_quota_reset(now);
+        assert_eq!(reset, utc("2022-01-16T08:00:00Z"));
+    }
+}
@@ -0,0 +1,129 @@
+use std::time::Duration;
+
+use tokio::sync::Mutex;
+use tokio::time::{sleep, Instant};
+
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(5 * 60);
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+    paused_until: Instant,
+    backoff: Duration,
+}
+
+/// Token bucket shared by every caller. A rate limited response pauses all callers, and the
+/// pause doubles for each rate limited response until a request succeeds again.
+pub struct RateLimiter {
+    requests_per_second: f64,
+    burst: f64,
+    bucket: Mutex<Bucket>,
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_second: f64, burst: u32) -> RateLimiter {
+        let now = Instant::now();
+
+        RateLimiter {
+            requests_per_second,
+            burst: burst.max(1) as f64,
+            bucket: Mutex::new(Bucket {
+                tokens: burst.max(1) as f64,
+                refilled_at: now,
+                paused_until: now,
+                backoff: Duration::ZERO,
+            }),
+        }
+    }
+
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().await;
+                let now = Instant::now();
+
+                if bucket.paused_until > now {
+                    bucket.paused_until - now
+                } else {
+                    let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+                    bucket.tokens =
+                        (bucket.tokens + elapsed * self.requests_per_second).min(self.burst);
+                    bucket.refilled_at = now;
+
+                    if bucket.tokens >= 1.0 {
+                        bucket.tokens -= 1.0;
+                        return;
+                    }
+
+                    Duration::from_secs_f64((1.0 - bucket.tokens) / self.requests_per_second)
+                }
+            };
+
+            sleep(wait).await;
+        }
+    }
+
+    /// Pauses all callers for `retry_after` if the server sent one, otherwise for the next
+    /// backoff step. Returns the pause.
+    pub async fn back_off(&self, retry_after: Option<Duration>) -> Duration {
+        let mut bucket = self.bucket.lock().await;
+
+        bucket.backoff = if bucket.backoff.is_zero() {
+            MIN_BACKOFF
+        } else {
+            (bucket.backoff * 2).min(MAX_BACKOFF)
+        };
+
+        let pause = retry_after.unwrap_or(bucket.backoff).min(MAX_BACKOFF);
+        let paused_until = Instant::now() + pause;
+
+        if paused_until > bucket.paused_until {
+            bucket.paused_until = paused_until;
+        }
+        bucket.tokens = 0.0;
+
+        pause
+    }
+
+    pub async fn reset_backoff(&self) {
+        self.bucket.lock().await.backoff = Duration::ZERO;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::time::Instant;
+
+    use super::RateLimiter;
+
+    #[tokio::test]
+    async fn burst_is_available_immediately() {
+        let rate_limiter = RateLimiter::new(0.001, 3);
+        let started_at = Instant::now();
+
+        for _ in 0..3 {
+            rate_limiter.acquire().await;
+        }
+
+        assert!(started_at.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn back_off_doubles_until_reset() {
+        let rate_limiter = RateLimiter::new(1.0, 1);
+
+        assert_eq!(rate_limiter.back_off(None).await, Duration::from_secs(1));
+        assert_eq!(rate_limiter.back_off(None).await, Duration::from_secs(2));
+        assert_eq!(
+            rate_limiter.back_off(Some(Duration::from_secs(30))).await,
+            Duration::from_secs(30)
+        );
+
+        rate_limiter.reset_backoff().await;
+
+        assert_eq!(rate_limiter.back_off(None).await, Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,290 @@
+use crate::models::config::ScrapePolicy;
+use crate::utils::consts::{
+    CHANNEL_LIFECYCLE_ABANDONED, CHANNEL_LIFECYCLE_ACTIVE, CHANNEL_LIFECYCLE_DORMANT,
+    CHANNEL_LIFECYCLE_SLOWING,
+};
+
+const ONE_HOUR_IN_SECONDS: i64 = 3600;
+const ONE_DAY_IN_SECONDS: i64 = 86400;
+const ONE_WEEK_IN_SECONDS: i64 = 604800;
+// View counts captured once a video reaches the given age, for comparing launches
+pub const VIEW_SNAPSHOTS: [(&str, i64); 2] = [
+    ("views24h", ONE_DAY_IN_SECONDS),
+    ("views7d", ONE_WEEK_IN_SECONDS),
+];
+// A snapshot taken later than this after the milestone is no longer representative
+const VIEW_SNAPSHOT_WINDOW_SECONDS: i64 = ONE_DAY_IN_SECONDS;
+// A channel uploading daily is polled hourly, the poll interval scales with the upload interval
+const UPLOAD_INTERVAL_TO_SCRAPE_INTERVAL_RATIO: i64 = 24;
+// Channels with a single upload count as uploading monthly
+const DEFAULT_UPLOAD_INTERVAL_SECONDS: i64 = 30 * ONE_DAY_IN_SECONDS;
+// Silence in upload intervals after which a channel is slowing or dormant, with minimum silences
+// so that channels uploading daily are not dormant after a week off
+const SLOWING_UPLOAD_INTERVALS: i64 = 2;
+const MIN_SLOWING_SECONDS: i64 = 2 * ONE_WEEK_IN_SECONDS;
+const DORMANT_UPLOAD_INTERVALS: i64 = 6;
+const MIN_DORMANT_SECONDS: i64 = 90 * ONE_DAY_IN_SECONDS;
+const ABANDONED_SECONDS: i64 = 365 * ONE_DAY_IN_SECONDS;
+const DORMANT_MIN_SCRAPE_INTERVAL_SECONDS: i64 = ONE_WEEK_IN_SECONDS;
+const ABANDONED_MIN_SCRAPE_INTERVAL_SECONDS: i64 = 4 * ONE_WEEK_IN_SECONDS;
+
+/// Returns the median interval in seconds between consecutive uploads, or `None` if there
+/// are fewer than two uploads.
+pub fn compute_upload_interval(published_timestamps: &[i64]) -> Option<i64> {
+    if published_timestamps.len() < 2 {
+        return None;
+    }
+
+    let mut sorted = published_timestamps.to_vec();
+    sorted.sort_unstable();
+
+    let mut gaps = sorted
+        .windows(2)
+        .map(|pair| pair[1] - pair[0])
+        .collect::<Vec<i64>>();
+    gaps.sort_unstable();
+
+    Some(gaps[gaps.len() / 2])
+}
+
+/// Computes when a channel should be scraped next, between one hour and one week from now.
+/// A long silence since the last upload counts as a long upload interval, so channels that
+/// stopped uploading drift towards the weekly poll.
+pub fn compute_next_scrape_at(now: i64, last_upload_at: i64, upload_interval: Option<i64>) -> i64 {
+    let since_last_upload = (now - last_upload_at).max(0);
+    let interval = upload_interval
+        .unwrap_or(ONE_WEEK_IN_SECONDS * UPLOAD_INTERVAL_TO_SCRAPE_INTERVAL_RATIO)
+        .max(since_last_upload);
+
+    let scrape_interval = (interval / UPLOAD_INTERVAL_TO_SCRAPE_INTERVAL_RATIO)
+        .clamp(ONE_HOUR_IN_SECONDS, ONE_WEEK_IN_SECONDS);
+
+    now + scrape_interval
+}
+
+/// Pushes the next scrape of a channel whose scrapes keep failing back, doubling from one hour
+/// with each failure in a row up to a week.
+pub fn compute_failure_backoff(now: i64, failures: i64) -> i64 {
+    let backoff = ONE_HOUR_IN_SECONDS
+        .saturating_mul(1 << (failures - 1).clamp(0, 16))
+        .min(ONE_WEEK_IN_SECONDS);
+
+    now + backoff
+}
+
+/// Returns the lifecycle of a channel from the silence since its last upload, measured in its
+/// usual upload interval. Channels without uploads have no lifecycle.
+pub fn compute_lifecycle(
+    now: i64,
+    last_upload_at: Option<i64>,
+    upload_interval: Option<i64>,
+) -> Option<&'static str> {
+    let since_last_upload = (now - last_upload_at.filter(|at| *at > 0)?).max(0);
+    let upload_interval = upload_interval
+        .unwrap_or(DEFAULT_UPLOAD_INTERVAL_SECONDS)
+        .max(ONE_DAY_IN_SECONDS);
+
+    let lifecycle = if since_last_upload >= ABANDONED_SECONDS {
+        CHANNEL_LIFECYCLE_ABANDONED
+    } else if since_last_upload
+        >= (DORMANT_UPLOAD_INTERVALS * upload_interval).max(MIN_DORMANT_SECONDS)
+    {
+        CHANNEL_LIFECYCLE_DORMANT
+    } else if since_last_upload
+        >= (SLOWING_UPLOAD_INTERVALS * upload_interval).max(MIN_SLOWING_SECONDS)
+    {
+        CHANNEL_LIFECYCLE_SLOWING
+    } else {
+        CHANNEL_LIFECYCLE_ACTIVE
+    };
+
+    Some(lifecycle)
+}
+
+/// Pushes the next scrape of dormant and abandoned channels back, they rarely upload again.
+pub fn deprioritize_by_lifecycle(now: i64, next_scrape_at: i64, lifecycle: Option<&str>) -> i64 {
+    let min_scrape_interval = match lifecycle {
+        Some(CHANNEL_LIFECYCLE_DORMANT) => DORMANT_MIN_SCRAPE_INTERVAL_SECONDS,
+        Some(CHANNEL_LIFECYCLE_ABANDONED) => ABANDONED_MIN_SCRAPE_INTERVAL_SECONDS,
+        _ => return next_scrape_at,
+    };
+
+    next_scrape_at.max(now + min_scrape_interval)
+}
+
+/// Returns the minimum seconds between two detail updates of a video with the given age and
+/// views.
+pub fn get_video_update_threshold(policy: &ScrapePolicy, age: i64, views: i64) -> i64 {
+    let threshold = if age >= 6 * 4 * ONE_WEEK_IN_SECONDS {
+        policy.half_year_old_video_seconds
+    } else if age >= 4 * ONE_WEEK_IN_SECONDS {
+        policy.month_old_video_seconds
+    } else if age >= ONE_WEEK_IN_SECONDS {
+        policy.week_old_video_seconds
+    } else {
+        policy.new_video_seconds
+    };
+
+    let views_per_hour = views as f64 / (age as f64 / ONE_HOUR_IN_SECONDS as f64).max(1.0);
+    if views_per_hour >= policy.hot_views_per_hour {
+        return threshold.min(policy.hot_video_seconds);
+    }
+
+    threshold
+}
+
+/// Returns the snapshot fields whose capture window is open for a video published at the given time.
+pub fn due_view_snapshots(now: i64, published_at: i64) -> Vec<&'static str> {
+    let age = now - published_at;
+
+    VIEW_SNAPSHOTS
+        .iter()
+        .filter(|(_, milestone)| {
+            age >= *milestone && age < milestone + VIEW_SNAPSHOT_WINDOW_SECONDS
+        })
+        .map(|(field, _)| *field)
+        .collect()
+}
+
+/// Returns the earliest upcoming snapshot milestone of the given uploads.
+pub fn next_view_snapshot_at(now: i64, published_timestamps: &[i64]) -> Option<i64> {
+    published_timestamps
+        .iter()
+        .flat_map(|published_at| {
+            VIEW_SNAPSHOTS
+                .iter()
+                .map(move |(_, milestone)| published_at + milestone)
+        })
+        .filter(|at| *at > now)
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::config::ScrapePolicy;
+
+    const DAY: i64 = 86400;
+
+    #[test]
+    fn video_update_threshold_grows_with_age() {
+        let policy = ScrapePolicy::default();
+
+        assert_eq!(super::get_video_update_threshold(&policy, DAY, 0), 3 * 3600);
+        assert_eq!(super::get_video_update_threshold(&policy, 10 * DAY, 0), DAY);
+        assert_eq!(
+            super::get_video_update_threshold(&policy, 60 * DAY, 0),
+            7 * DAY
+        );
+        assert_eq!(
+            super::get_video_update_threshold(&policy, 200 * DAY, 0),
+            28 * DAY
+        );
+    }
+
+    #[test]
+    fn hot_videos_are_updated_more_often() {
+        let policy = ScrapePolicy::default();
+
+        assert_eq!(
+            super::get_video_update_threshold(&policy, 10 * DAY, 240 * 1000),
+            3600
+        );
+        assert_eq!(
+            super::get_video_update_threshold(&policy, 10 * DAY, 240 * 999),
+            DAY
+        );
+        // Views of videos younger than an hour are not extrapolated
+        assert_eq!(
+            super::get_video_update_threshold(&policy, 60, 999),
+            3 * 3600
+        );
+    }
+
+    #[test]
+    fn upload_interval_is_median_gap() {
+        let timestamps = [0, DAY, 2 * DAY, 10 * DAY];
+        assert_eq!(super::compute_upload_interval(&timestamps), Some(DAY));
+        assert_eq!(super::compute_upload_interval(&[DAY]), None);
+    }
+
+    #[test]
+    fn prolific_channels_are_polled_hourly() {
+        let now = 100 * DAY;
+        let next = super::compute_next_scrape_at(now, now - 3600, Some(DAY / 2));
+        assert_eq!(next, now + 3600);
+    }
+
+    #[test]
+    fn failing_channels_back_off() {
+        let now = 100 * DAY;
+        assert_eq!(super::compute_failure_backoff(now, 1), now + 3600);
+        assert_eq!(super::compute_failure_backoff(now, 3), now + 4 * 3600);
+        assert_eq!(super::compute_failure_backoff(now, 50), now + 7 * DAY);
+    }
+
+    #[test]
+    fn view_snapshots_due_within_window() {
+        let now = 100 * DAY;
+        assert_eq!(
+            super::due_view_snapshots(now, now - DAY - 60),
+            vec!["views24h"]
+        );
+        assert_eq!(
+            super::due_view_snapshots(now, now - 7 * DAY),
+            vec!["views7d"]
+        );
+        assert!(super::due_view_snapshots(now, now - 3 * DAY).is_empty());
+    }
+
+    #[test]
+    fn next_view_snapshot_is_earliest_upcoming_milestone() {
+        let now = 100 * DAY;
+        let timestamps = [now - 2 * DAY, now - 3600];
+        assert_eq!(
+            super::next_view_snapshot_at(now, &timestamps),
+            Some(now - 3600 + DAY)
+        );
+        assert_eq!(super::next_view_snapshot_at(now, &[now - 30 * DAY]), None);
+    }
+
+    #[test]
+    fn dormant_channels_are_polled_weekly() {
+        let now = 1000 * DAY;
+        let next = super::compute_next_scrape_at(now, now - 400 * DAY, Some(DAY));
+        assert_eq!(next, now + 7 * DAY);
+        assert_eq!(super::compute_next_scrape_at(now, now, None), now + 7 * DAY);
+    }
+
+    #[test]
+    fn lifecycle_follows_upload_cadence() {
+        let now = 1000 * DAY;
+        let lifecycle = |silence: i64, interval: Option<i64>| {
+            super::compute_lifecycle(now, Some(now - silence), interval)
+        };
+
+        assert_eq!(lifecycle(3 * DAY, Some(DAY)), Some("active"));
+        assert_eq!(lifecycle(20 * DAY, Some(DAY)), Some("slowing"));
+        assert_eq!(lifecycle(100 * DAY, Some(DAY)), Some("dormant"));
+        assert_eq!(lifecycle(100 * DAY, Some(30 * DAY)), Some("slowing"));
+        assert_eq!(lifecycle(400 * DAY, Some(30 * DAY)), Some("abandoned"));
+        assert_eq!(super::compute_lifecycle(now, None, None), None);
+    }
+
+    #[test]
+    fn dormant_channels_are_deprioritized() {
+        let now = 1000 * DAY;
+
+        assert_eq!(
+            super::deprioritize_by_lifecycle(now, now + DAY, Some("dormant")),
+            now + 7 * DAY
+        );
+        assert_eq!(
+            super::deprioritize_by_lifecycle(now, now + 7 * DAY, Some("abandoned")),
+            now + 28 * DAY
+        );
+        assert_eq!(
+            super::deprioritize_by_lifecycle(now, now + DAY, Some("slowing")),
+            now + DAY
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use mongodb::bson::{doc, DateTime, Document};
+
+pub const POSITIVE_TERMS: [&str; 24] = [
+    "amazing",
+    "awesome",
+    "beautiful",
+    "best",
+    "brilliant",
+    "clear",
+    "easy",
+    "excellent",
+    "fantastic",
+    "finally",
+    "good",
+    "great",
+    "helped",
+    "helpful",
+    "incredible",
+    "inspiring",
+    "love",
+    "nailed",
+    "nice",
+    "perfect",
+    "thank",
+    "thanks",
+    "useful",
+    "wonderful",
+];
+
+pub const NEGATIVE_TERMS: [&str; 20] = [
+    "annoying",
+    "awful",
+    "bad",
+    "boring",
+    "clickbait",
+    "confusing",
+    "dislike",
+    "disappointing",
+    "fake",
+    "hate",
+    "horrible",
+    "lost",
+    "poor",
+    "sloppy",
+    "terrible",
+    "unclear",
+    "useless",
+    "waste",
+    "worst",
+    "wrong",
+];
+
+// Flip the sentiment of the term right after them
+const NEGATIONS: [&str; 6] = ["not", "no", "never", "dont", "don't", "isn't"];
+
+// Comments scoring above or below this count as positive or negative
+const POLARITY_THRESHOLD: f64 = 0.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommentSentiment {
+    /// Mean score of the scored comments, from -1 to 1
+    pub score: f64,
+    pub comments: i64,
+    pub positive: i64,
+    pub negative: i64,
+}
+
+/// Scores a comment from -1 to 1 by its positive and negative terms, `None` without any.
+pub fn score_comment(text: &str) -> Option<f64> {
+    let words = text
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_string())
+        .collect::<Vec<String>>();
+
+    let mut positive = 0;
+    let mut negative = 0;
+
+    for (i, word) in words.iter().enumerate() {
+        let polarity = if POSITIVE_TERMS.contains(&word.as_str()) {
+            1
+        } else if NEGATIVE_TERMS.contains(&word.as_str()) {
+            -1
+        } else {
+            continue;
+        };
+
+        let negated = i > 0 && NEGATIONS.contains(&words[i - 1].as_str());
+
+        match (polarity, negated) {
+            (1, false) | (-1, true) => positive += 1,
+            _ => negative += 1,
+        }
+    }
+
+    if positive + negative == 0 {
+        return None;
+    }
+
+    Some((positive - negative) as f64 / (positive + negative) as f64)
+}
+
+pub fn get_comment_sentiment(scores: &[f64]) -> Option<CommentSentiment> {
+    if scores.is_empty() {
+        return None;
+    }
+
+    Some(CommentSentiment {
+        score: scores.iter().sum::<f64>() / scores.len() as f64,
+        comments: scores.len() as i64,
+        positive: scores.iter().filter(|s| **s > POLARITY_THRESHOLD).count() as i64,
+        negative: scores.iter().filter(|s| **s < POLARITY_THRESHOLD).count() as i64,
+    })
+}
+
+pub fn get_comment_sentiment_document(sentiment: &CommentSentiment) -> Document {
+    doc! {
+        "score": sentiment.score,
+        "comments": sentiment.comments,
+        "positive": sentiment.positive,
+        "negative": sentiment.negative,
+        "scoredAt": DateTime::now(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn score_comments_by_terms_and_negations() {
+        assert_eq!(
+            super::score_comment("Thanks, this lesson helped a lot!"),
+            Some(1.0)
+        );
+        assert_eq!(
+            super::score_comment("Boring and way too confusing"),
+            Some(-1.0)
+        );
+        assert_eq!(
+            super::score_comment("Not bad, but the tab is wrong"),
+            Some(0.0)
+        );
+        assert_eq!(super::score_comment("What strings do you use?"), None);
+    }
+
+    #[test]
+    fn aggregate_comment_scores() {
+        let sentiment = super::get_comment_sentiment(&[1.0, 0.5, -1.0, 0.0]).unwrap();
+
+        assert_eq!(sentiment.score, 0.125);
+        assert_eq!(sentiment.comments, 4);
+        assert_eq!(sentiment.positive, 2);
+        assert_eq!(sentiment.negative, 1);
+        assert!(super::get_comment_sentiment(&[]).is_none());
+    }
+}
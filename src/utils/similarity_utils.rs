@@ -0,0 +1,139 @@
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelatedChannel {
+    pub channel_id: String,
+    /// Jaccard index of the two commenter sets
+    pub score: f64,
+    pub shared_commenters: usize,
+}
+
+/// Returns the `top_n` most similar channels of each channel by shared commenters, ignoring
+/// pairs with less than `min_shared` commenters in common.
+pub fn get_related_channels(
+    commenters: &HashMap<String, HashSet<String>>,
+    top_n: usize,
+    min_shared: usize,
+) -> HashMap<String, Vec<RelatedChannel>> {
+    let mut channels_by_commenter: HashMap<&str, Vec<&str>> = HashMap::new();
+
+    for (channel_id, channel_commenters) in commenters {
+        for commenter in channel_commenters {
+            channels_by_commenter
+                .entry(commenter)
+                .or_default()
+                .push(channel_id);
+        }
+    }
+
+    let mut related_channels = HashMap::new();
+
+    for (channel_id, channel_commenters) in commenters {
+        let mut shared_counts: HashMap<&str, usize> = HashMap::new();
+
+        for commenter in channel_commenters {
+            for other_channel_id in &channels_by_commenter[commenter.as_str()] {
+                if other_channel_id != channel_id {
+                    *shared_counts.entry(other_channel_id).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let mut related = shared_counts
+            .into_iter()
+            .filter(|(_, shared)| *shared >= min_shared)
+            .map(|(other_channel_id, shared)| {
+                let union = channel_commenters.len() + commenters[other_channel_id].len() - shared;
+
+                RelatedChannel {
+                    channel_id: other_channel_id.to_string(),
+                    score: shared as f64 / union as f64,
+                    shared_commenters: shared,
+                }
+            })
+            .collect::<Vec<RelatedChannel>>();
+
+        related.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap()
+                .then_with(|| a.channel_id.cmp(&b.channel_id))
+        });
+        related.truncate(top_n);
+
+        if !related.is_empty() {
+            related_channels.insert(channel_id.to_string(), related);
+        }
+    }
+
+    related_channels
+}
+
+/// Returns the commenters that comment on at least `min_channels` of the channels, most
+/// connected first.
+pub fn get_connected_commenters(
+    commenters: &HashMap<String, HashSet<String>>,
+    min_channels: usize,
+) -> Vec<(String, usize)> {
+    let mut channel_counts: HashMap<&str, usize> = HashMap::new();
+
+    for channel_commenters in commenters.values() {
+        for commenter in channel_commenters {
+            *channel_counts.entry(commenter).or_insert(0) += 1;
+        }
+    }
+
+    let mut connected = channel_counts
+        .into_iter()
+        .filter(|(_, count)| *count >= min_channels)
+        .map(|(commenter, count)| (commenter.to_string(), count))
+        .collect::<Vec<(String, usize)>>();
+
+    connected.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    connected
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    fn commenters() -> HashMap<String, HashSet<String>> {
+        let sets = [
+            ("a", vec!["x", "y", "z"]),
+            ("b", vec!["x", "y"]),
+            ("c", vec!["z", "w"]),
+        ];
+
+        sets.iter()
+            .map(|(channel, commenters)| {
+                (
+                    channel.to_string(),
+                    commenters.iter().map(|c| c.to_string()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn related_channels_by_jaccard_index() {
+        let related = super::get_related_channels(&commenters(), 1, 1);
+
+        assert_eq!(related["a"][0].channel_id, "b");
+        assert!((related["a"][0].score - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(related["c"][0].channel_id, "a");
+        assert_eq!(super::get_related_channels(&commenters(), 5, 2).len(), 2);
+    }
+
+    #[test]
+    fn connected_commenters() {
+        assert_eq!(
+            super::get_connected_commenters(&commenters(), 2),
+            vec![
+                ("x".to_string(), 2),
+                ("y".to_string(), 2),
+                ("z".to_string(), 2)
+            ]
+        );
+    }
+}
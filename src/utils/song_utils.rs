@@ -0,0 +1,104 @@
+use mongodb::bson::{doc, Document};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverOf {
+    pub artist: String,
+    pub song: String,
+}
+
+/// Normalizes an artist or song name for lookups, ignoring case, punctuation and spacing.
+pub fn get_song_key(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+/// The stored song with the lookup keys used by `VideoStore::get_covers`.
+pub fn get_song_document(cover_of: &CoverOf) -> Document {
+    doc! {
+        "artist": &cover_of.artist,
+        "song": &cover_of.song,
+        "artistKey": get_song_key(&cover_of.artist),
+        "songKey": get_song_key(&cover_of.song),
+    }
+}
+
+/// Finds the known songs mentioned in a text like a caption. Single word titles, e.g. "One",
+/// only count when the artist is mentioned too.
+pub fn find_song_mentions(text: &str, songs: &[CoverOf]) -> Vec<CoverOf> {
+    let text = format!(" {} ", get_words(text));
+    let is_mentioned = |name: &str| {
+        let words = get_words(name);
+        !words.is_empty() && text.contains(&format!(" {} ", words))
+    };
+
+    let mut mentions = songs
+        .iter()
+        .filter(|song| {
+            is_mentioned(&song.song)
+                && (get_words(&song.song).contains(' ') || is_mentioned(&song.artist))
+        })
+        .cloned()
+        .collect::<Vec<CoverOf>>();
+
+    mentions.dedup();
+
+    mentions
+}
+
+fn get_words(text: &str) -> String {
+    text.chars()
+        .filter(|c| *c != '\'')
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .flat_map(|c| c.to_lowercase())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CoverOf;
+
+    fn song(artist: &str, song: &str) -> CoverOf {
+        CoverOf {
+            artist: artist.to_string(),
+            song: song.to_string(),
+        }
+    }
+
+    #[test]
+    fn song_key() {
+        assert_eq!(super::get_song_key("Guns N' Roses"), "gunsnroses");
+        assert_eq!(
+            super::get_song_key("Sweet Child O' Mine"),
+            super::get_song_key("sweet child o mine")
+        );
+    }
+
+    #[test]
+    fn song_mentions() {
+        let songs = vec![
+            song("Guns N' Roses", "Sweet Child O' Mine"),
+            song("Metallica", "One"),
+            song("U2", "One"),
+            song("Oasis", "Wonderwall"),
+        ];
+
+        assert_eq!(
+            super::find_song_mentions(
+                "today we learn the intro of sweet child o mine and then one by metallica",
+                &songs
+            ),
+            vec![
+                song("Guns N' Roses", "Sweet Child O' Mine"),
+                song("Metallica", "One")
+            ]
+        );
+        assert!(
+            super::find_song_mentions("one more time, this is a wonderwall", &songs).is_empty()
+        );
+    }
+}
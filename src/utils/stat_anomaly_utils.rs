@@ -0,0 +1,120 @@
+use mongodb::bson::{doc, Bson, Document};
+
+pub const STAT_ANOMALY_FIELDS: [&str; 2] = ["views", "likes"];
+// Oldest anomalies are dropped beyond this, a glitching video would otherwise grow forever
+pub const MAX_ANOMALIES: usize = 20;
+
+pub const ANOMALY_VIEWS_DECREASED: &str = "viewsDecreased";
+pub const ANOMALY_LIKES_RESET: &str = "likesReset";
+
+// YouTube removes invalid views now and then, so small drops are routine
+const MAX_ROUTINE_VIEW_DROP: f64 = 0.1;
+const MIN_VIEWS: i64 = 1000;
+// Likes falling to a fraction of the previous count look like a reset or a re-upload
+const LIKES_RESET_RATIO: f64 = 0.5;
+const MIN_LIKES: i64 = 20;
+
+/// Returns an anomaly for each count of the new video document that dropped against the stored
+/// one: views decreasing sharply or likes resetting. Counts missing from either document, e.g.
+/// hidden likes, are not compared.
+pub fn get_anomalies(previous: &Document, video_doc: &Document, detected_at: i64) -> Vec<Document> {
+    let mut anomalies = vec![];
+
+    if let (Some(from), Some(to)) = (get_count(previous, "views"), get_count(video_doc, "views")) {
+        if from >= MIN_VIEWS && (to as f64) < from as f64 * (1.0 - MAX_ROUTINE_VIEW_DROP) {
+            anomalies.push(get_anomaly(
+                ANOMALY_VIEWS_DECREASED,
+                "views",
+                from,
+                to,
+                detected_at,
+            ));
+        }
+    }
+
+    if let (Some(from), Some(to)) = (get_count(previous, "likes"), get_count(video_doc, "likes")) {
+        if from >= MIN_LIKES && (to as f64) <= from as f64 * LIKES_RESET_RATIO {
+            anomalies.push(get_anomaly(
+                ANOMALY_LIKES_RESET,
+                "likes",
+                from,
+                to,
+                detected_at,
+            ));
+        }
+    }
+
+    anomalies
+}
+
+/// Adds the previous anomalies plus the new ones to a video document that replaces `anomalies`
+/// on write.
+pub fn append_anomalies(previous: &Document, video_doc: &mut Document, detected_at: i64) {
+    let anomalies = get_anomalies(previous, video_doc, detected_at);
+
+    if anomalies.is_empty() {
+        return;
+    }
+
+    let mut history = previous.get_array("anomalies").cloned().unwrap_or_default();
+    history.extend(anomalies.into_iter().map(Bson::Document));
+
+    let overflow = history.len().saturating_sub(MAX_ANOMALIES);
+    history.drain(..overflow);
+
+    video_doc.insert("anomalies", history);
+}
+
+fn get_anomaly(kind: &str, field: &str, from: i64, to: i64, detected_at: i64) -> Document {
+    doc! {
+        "kind": kind,
+        "field": field,
+        "from": from,
+        "to": to,
+        "at": detected_at,
+    }
+}
+
+fn get_count(document: &Document, key: &str) -> Option<i64> {
+    match document.get(key)? {
+        Bson::Int32(count) => Some(i64::from(*count)),
+        Bson::Int64(count) => Some(*count),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::doc;
+
+    use super::{ANOMALY_LIKES_RESET, ANOMALY_VIEWS_DECREASED};
+
+    #[test]
+    fn flags_dropping_counts() {
+        let previous = doc! {"views": 10_000i64, "likes": 400i64};
+
+        assert!(
+            super::get_anomalies(&previous, &doc! {"views": 9_500i64, "likes": 390i64}, 100)
+                .is_empty()
+        );
+        assert!(super::get_anomalies(&previous, &doc! {"title": "No counts"}, 100).is_empty());
+
+        let anomalies =
+            super::get_anomalies(&previous, &doc! {"views": 120i64, "likes": 0i64}, 100);
+        assert_eq!(
+            anomalies,
+            vec![
+                doc! {"kind": ANOMALY_VIEWS_DECREASED, "field": "views", "from": 10_000i64, "to": 120i64, "at": 100i64},
+                doc! {"kind": ANOMALY_LIKES_RESET, "field": "likes", "from": 400i64, "to": 0i64, "at": 100i64},
+            ]
+        );
+
+        // Small videos swing too much to tell
+        assert!(super::get_anomalies(
+            &doc! {"views": 50, "likes": 4},
+            &doc! {"views": 10, "likes": 0},
+            100
+        )
+        .is_empty());
+    }
+}
@@ -0,0 +1,78 @@
+// Public subscriber counts are truncated to three significant figures
+const SIGNIFICANT_FIGURES: u32 = 3;
+pub const SUBSCRIBER_MILESTONES: [i64; 3] = [10_000, 100_000, 1_000_000];
+
+/// Returns the step the public subscriber count is truncated to, e.g. 1000 for 1,234,000.
+pub fn rounding_unit(raw_subscribers: i64) -> i64 {
+    let digits = raw_subscribers.max(1).to_string().len() as u32;
+
+    if digits <= SIGNIFICANT_FIGURES {
+        return 1;
+    }
+
+    10_i64.pow(digits - SIGNIFICANT_FIGURES)
+}
+
+/// Combines a truncated API count with the previous best estimate. The real count lies within
+/// `[raw, raw + unit)`, so the previous estimate is kept while it fits that range, which keeps
+/// the series monotonic instead of stepping with every rounding boundary.
+pub fn reconcile_subscriber_count(raw_subscribers: i64, previous_estimate: Option<i64>) -> i64 {
+    let upper_bound = raw_subscribers + rounding_unit(raw_subscribers) - 1;
+
+    match previous_estimate {
+        Some(previous) => previous.clamp(raw_subscribers, upper_bound),
+        None => raw_subscribers,
+    }
+}
+
+/// Returns the milestones passed between two subscriber counts.
+pub fn get_crossed_milestones(previous_subscribers: i64, subscribers: i64) -> Vec<i64> {
+    SUBSCRIBER_MILESTONES
+        .iter()
+        .filter(|milestone| previous_subscribers < **milestone && subscribers >= **milestone)
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn rounding_unit_by_magnitude() {
+        assert_eq!(super::rounding_unit(0), 1);
+        assert_eq!(super::rounding_unit(999), 1);
+        assert_eq!(super::rounding_unit(12_300), 100);
+        assert_eq!(super::rounding_unit(1_230_000), 10_000);
+    }
+
+    #[test]
+    fn keeps_previous_estimate_within_rounding_range() {
+        assert_eq!(
+            super::reconcile_subscriber_count(12_300, Some(12_345)),
+            12_345
+        );
+        assert_eq!(super::reconcile_subscriber_count(12_300, None), 12_300);
+    }
+
+    #[test]
+    fn clamps_previous_estimate_to_rounding_range() {
+        assert_eq!(
+            super::reconcile_subscriber_count(12_400, Some(12_345)),
+            12_400
+        );
+        assert_eq!(
+            super::reconcile_subscriber_count(12_200, Some(12_345)),
+            12_299
+        );
+    }
+
+    #[test]
+    fn crossed_milestones_between_counts() {
+        assert_eq!(super::get_crossed_milestones(9_999, 10_000), vec![10_000]);
+        assert_eq!(
+            super::get_crossed_milestones(9_000, 150_000),
+            vec![10_000, 100_000]
+        );
+        assert!(super::get_crossed_milestones(10_000, 20_000).is_empty());
+        assert!(super::get_crossed_milestones(120_000, 90_000).is_empty());
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use crate::models::tag_profile::TagCount;
+
+// Only the most used tags of a channel are kept in its profile
+const MAX_PROFILE_KEYWORDS: usize = 50;
+// Share of tagged videos a genre needs to count as a specialization of the channel
+const MIN_SPECIALIZATION_SHARE: f64 = 0.25;
+
+const SPECIALIZATIONS: [(&str, &[&str]); 9] = [
+    ("blues", &["blues"]),
+    ("jazz", &["jazz", "bebop"]),
+    ("metal", &["metal", "djent", "shred"]),
+    ("rock", &["rock", "punk", "grunge"]),
+    ("country", &["country", "bluegrass", "chicken picking"]),
+    ("classical", &["classical", "baroque"]),
+    ("flamenco", &["flamenco", "rumba"]),
+    ("fingerstyle", &["fingerstyle", "fingerpicking"]),
+    ("funk", &["funk"]),
+];
+
+/// Counts how many videos use each tag and when a tag was last used. Takes the tags and the
+/// published timestamp of each video, tags are compared lowercase.
+pub fn build_tag_profile(videos: &[(Vec<String>, i64)]) -> Vec<TagCount> {
+    let mut counts: HashMap<String, TagCount> = HashMap::new();
+
+    for (tags, published_at) in videos {
+        let mut video_tags = tags
+            .iter()
+            .map(|tag| tag.trim().to_lowercase())
+            .filter(|tag| !tag.is_empty())
+            .collect::<Vec<String>>();
+        video_tags.sort();
+        video_tags.dedup();
+
+        for tag in video_tags {
+            let tag_count = counts.entry(tag.clone()).or_insert(TagCount {
+                tag,
+                count: 0,
+                last_used_at: *published_at,
+            });
+
+            tag_count.count += 1;
+            tag_count.last_used_at = tag_count.last_used_at.max(*published_at);
+        }
+    }
+
+    let mut profile = counts.into_values().collect::<Vec<TagCount>>();
+    profile.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then(b.last_used_at.cmp(&a.last_used_at))
+            .then(a.tag.cmp(&b.tag))
+    });
+    profile.truncate(MAX_PROFILE_KEYWORDS);
+
+    profile
+}
+
+/// Returns the genres that enough of the tagged videos have a tag for, e.g. "blues".
+pub fn detect_specializations(videos: &[(Vec<String>, i64)]) -> Vec<String> {
+    let tagged_videos = videos
+        .iter()
+        .filter(|(tags, _)| !tags.is_empty())
+        .collect::<Vec<_>>();
+
+    if tagged_videos.is_empty() {
+        return vec![];
+    }
+
+    SPECIALIZATIONS
+        .iter()
+        .filter(|(_, keywords)| {
+            let matching_videos = tagged_videos
+                .iter()
+                .filter(|(tags, _)| {
+                    tags.iter().any(|tag| {
+                        let tag = tag.to_lowercase();
+                        keywords.iter().any(|keyword| tag.contains(keyword))
+                    })
+                })
+                .count();
+
+            matching_videos as f64 / tagged_videos.len() as f64 >= MIN_SPECIALIZATION_SHARE
+        })
+        .map(|(specialization, _)| specialization.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    fn videos() -> Vec<(Vec<String>, i64)> {
+        vec![
+            (vec!["Blues".to_string(), "guitar lesson".to_string()], 300),
+            (vec!["blues".to_string(), "blues ".to_string()], 200),
+            (vec!["jazz standards".to_string()], 100),
+            (vec!["guitar lesson".to_string()], 400),
+            (vec![], 500),
+        ]
+    }
+
+    #[test]
+    fn build_tag_profile_counts_videos_per_tag() {
+        let profile = super::build_tag_profile(&videos());
+
+        assert_eq!(profile.len(), 3);
+        assert_eq!(profile[0].tag, "guitar lesson");
+        assert_eq!(profile[0].count, 2);
+        assert_eq!(profile[0].last_used_at, 400);
+        assert_eq!(profile[1].tag, "blues");
+        assert_eq!(profile[1].count, 2);
+        assert_eq!(profile[1].last_used_at, 300);
+    }
+
+    #[test]
+    fn detect_specializations_uses_share_of_tagged_videos() {
+        let specializations = super::detect_specializations(&videos());
+
+        assert_eq!(specializations, vec!["blues", "jazz"]);
+    }
+}
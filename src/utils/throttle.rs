@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+use tokio::sync::Mutex;
+use tokio::time::{sleep_until, Instant};
+
+pub struct Throttle {
+    min_interval: Duration,
+    next_request_at: Mutex<Instant>,
+}
+
+impl Throttle {
+    pub fn new(min_interval: Duration) -> Throttle {
+        Throttle {
+            min_interval,
+            next_request_at: Mutex::new(Instant::now()),
+        }
+    }
+
+    pub async fn wait(&self) {
+        if self.min_interval.is_zero() {
+            return;
+        }
+
+        let mut next_request_at = self.next_request_at.lock().await;
+        sleep_until(*next_request_at).await;
+
+        *next_request_at = Instant::now() + self.min_interval;
+    }
+}
@@ -0,0 +1,120 @@
+use mongodb::bson::{doc, DateTime, Document};
+
+pub const RECENT_VIDEO_COUNT: usize = 20;
+pub const BASELINE_VIDEO_COUNT: usize = 200;
+
+// A channel needs a guitar baseline before it can drift away from it
+const MIN_BASELINE_DENSITY: f64 = 0.4;
+// Recent videos mention guitar terms less than half as often as before
+const MAX_DENSITY_RATIO: f64 = 0.5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopicDrift {
+    pub recent_density: f64,
+    pub baseline_density: f64,
+}
+
+/// Returns the share of the videos whose title, description or tags contain a guitar term.
+pub fn get_guitar_term_density(guitar_terms: &[String], videos: &[Document]) -> f64 {
+    if videos.is_empty() {
+        return 0.0;
+    }
+
+    let matching = videos
+        .iter()
+        .filter(|video| {
+            let mut text = vec![
+                video.get_str("title").unwrap_or_default().to_lowercase(),
+                video
+                    .get_str("description")
+                    .unwrap_or_default()
+                    .to_lowercase(),
+            ];
+            if let Ok(tags) = video.get_array("tags") {
+                text.extend(
+                    tags.iter()
+                        .filter_map(|t| t.as_str())
+                        .map(str::to_lowercase),
+                );
+            }
+
+            guitar_terms
+                .iter()
+                .any(|term| text.iter().any(|t| t.contains(term.as_str())))
+        })
+        .count();
+
+    matching as f64 / videos.len() as f64
+}
+
+/// Compares the latest `RECENT_VIDEO_COUNT` videos, newest first, against the older ones.
+/// Channels without a full recent window and as many older videos are never drifting.
+pub fn get_topic_drift(guitar_terms: &[String], videos: &[Document]) -> Option<TopicDrift> {
+    if videos.len() < 2 * RECENT_VIDEO_COUNT {
+        return None;
+    }
+
+    let (recent, baseline) = videos.split_at(RECENT_VIDEO_COUNT);
+    let recent_density = get_guitar_term_density(guitar_terms, recent);
+    let baseline_density = get_guitar_term_density(guitar_terms, baseline);
+
+    if baseline_density < MIN_BASELINE_DENSITY
+        || recent_density >= baseline_density * MAX_DENSITY_RATIO
+    {
+        return None;
+    }
+
+    Some(TopicDrift {
+        recent_density,
+        baseline_density,
+    })
+}
+
+pub fn get_topic_drift_document(drift: &TopicDrift) -> Document {
+    doc! {
+        "recentDensity": drift.recent_density,
+        "baselineDensity": drift.baseline_density,
+        "detectedAt": DateTime::now(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::{doc, Document};
+
+    fn get_videos(count: usize, title: &str) -> Vec<Document> {
+        (0..count).map(|_| doc! {"title": title}).collect()
+    }
+
+    #[test]
+    fn density_matches_title_description_and_tags() {
+        let terms = vec!["guitar".to_string(), "riff".to_string()];
+        let videos = vec![
+            doc! {"title": "Guitar lesson"},
+            doc! {"title": "Vlog", "description": "the RIFF at 2:00"},
+            doc! {"title": "Vlog", "tags": ["guitar"]},
+            doc! {"title": "Cooking"},
+        ];
+
+        assert_eq!(super::get_guitar_term_density(&terms, &videos), 0.75);
+        assert_eq!(super::get_guitar_term_density(&terms, &[]), 0.0);
+    }
+
+    #[test]
+    fn detect_drift_from_guitar_baseline() {
+        let terms = vec!["guitar".to_string()];
+
+        let mut drifted = get_videos(20, "Cooking");
+        drifted.extend(get_videos(40, "Guitar lesson"));
+        let drift = super::get_topic_drift(&terms, &drifted).unwrap();
+        assert_eq!(drift.recent_density, 0.0);
+        assert_eq!(drift.baseline_density, 1.0);
+
+        let mut steady = get_videos(8, "Cooking");
+        steady.extend(get_videos(52, "Guitar lesson"));
+        assert!(super::get_topic_drift(&terms, &steady).is_none());
+
+        assert!(super::get_topic_drift(&terms, &get_videos(60, "Cooking")).is_none());
+        assert!(super::get_topic_drift(&terms, &drifted[..30]).is_none());
+    }
+}
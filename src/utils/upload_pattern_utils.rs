@@ -0,0 +1,199 @@
+use chrono::{Datelike, Duration, TimeZone, Timelike};
+use chrono_tz::Tz;
+
+use crate::models::upload_pattern::{UploadPattern, UploadSlot};
+
+const ONE_HOUR_IN_SECONDS: i64 = 3600;
+// Fewer uploads do not show a pattern
+const MIN_PATTERN_UPLOADS: usize = 5;
+// An hour of the week is a slot once it holds this share of the uploads, and at least two
+const MIN_SLOT_SHARE: f64 = 0.15;
+const MIN_SLOT_UPLOADS: i64 = 2;
+// Channels are polled this long after the end of a slot, when the feed has caught up
+const SLOT_POLL_DELAY_SECONDS: i64 = 15 * 60;
+
+// Countries spanning several timezones use the one most of their people live in
+const COUNTRY_TIMEZONES: [(&str, Tz); 32] = [
+    ("ar", chrono_tz::America::Argentina::Buenos_Aires),
+    ("at", chrono_tz::Europe::Vienna),
+    ("au", chrono_tz::Australia::Sydney),
+    ("be", chrono_tz::Europe::Brussels),
+    ("br", chrono_tz::America::Sao_Paulo),
+    ("ca", chrono_tz::America::Toronto),
+    ("ch", chrono_tz::Europe::Zurich),
+    ("cl", chrono_tz::America::Santiago),
+    ("cz", chrono_tz::Europe::Prague),
+    ("de", chrono_tz::Europe::Berlin),
+    ("dk", chrono_tz::Europe::Copenhagen),
+    ("es", chrono_tz::Europe::Madrid),
+    ("fi", chrono_tz::Europe::Helsinki),
+    ("fr", chrono_tz::Europe::Paris),
+    ("gb", chrono_tz::Europe::London),
+    ("ie", chrono_tz::Europe::Dublin),
+    ("in", chrono_tz::Asia::Kolkata),
+    ("it", chrono_tz::Europe::Rome),
+    ("jp", chrono_tz::Asia::Tokyo),
+    ("kr", chrono_tz::Asia::Seoul),
+    ("mx", chrono_tz::America::Mexico_City),
+    ("nl", chrono_tz::Europe::Amsterdam),
+    ("no", chrono_tz::Europe::Oslo),
+    ("nz", chrono_tz::Pacific::Auckland),
+    ("ph", chrono_tz::Asia::Manila),
+    ("pl", chrono_tz::Europe::Warsaw),
+    ("pt", chrono_tz::Europe::Lisbon),
+    ("ru", chrono_tz::Europe::Moscow),
+    ("se", chrono_tz::Europe::Stockholm),
+    ("ua", chrono_tz::Europe::Kiev),
+    ("us", chrono_tz::America::New_York),
+    ("za", chrono_tz::Africa::Johannesburg),
+];
+
+/// The timezone of the stored lowercase country code, UTC for unknown countries.
+pub fn get_country_timezone(country: Option<&str>) -> Tz {
+    country
+        .and_then(|country| {
+            COUNTRY_TIMEZONES
+                .iter()
+                .find(|(code, _)| *code == country)
+                .map(|(_, timezone)| *timezone)
+        })
+        .unwrap_or(Tz::UTC)
+}
+
+/// Counts the uploads per day of the week and hour of the day in the local time of the channel.
+/// Returns `None` for channels with too few uploads to show a pattern.
+pub fn compute_upload_pattern(published_timestamps: &[i64], timezone: Tz) -> Option<UploadPattern> {
+    if published_timestamps.len() < MIN_PATTERN_UPLOADS {
+        return None;
+    }
+
+    let mut weekdays = vec![0; 7];
+    let mut hours = vec![0; 24];
+    let mut hours_of_week = vec![0; 7 * 24];
+
+    for timestamp in published_timestamps {
+        let local = timezone.timestamp(*timestamp, 0);
+        let weekday = local.weekday().num_days_from_monday() as usize;
+        let hour = local.hour() as usize;
+
+        weekdays[weekday] += 1;
+        hours[hour] += 1;
+        hours_of_week[weekday * 24 + hour] += 1;
+    }
+
+    let uploads = published_timestamps.len() as i64;
+    let min_slot_uploads = MIN_SLOT_UPLOADS.max((uploads as f64 * MIN_SLOT_SHARE).ceil() as i64);
+    let slots = hours_of_week
+        .iter()
+        .enumerate()
+        .filter(|(_, count)| **count >= min_slot_uploads)
+        .map(|(hour_of_week, _)| UploadSlot {
+            weekday: (hour_of_week / 24) as u32,
+            hour: (hour_of_week % 24) as u32,
+        })
+        .collect();
+
+    Some(UploadPattern {
+        timezone: timezone.name().to_string(),
+        uploads,
+        weekdays,
+        hours,
+        slots,
+    })
+}
+
+/// Returns when to poll the channel after its next upload slot, `None` without slots. Slots keep
+/// their local hour across daylight saving time changes.
+pub fn next_upload_slot_poll_at(now: i64, pattern: &UploadPattern) -> Option<i64> {
+    let timezone = pattern.timezone.parse::<Tz>().ok()?;
+    let today = timezone.timestamp(now, 0).date().naive_local();
+
+    // Yesterday's slots may still be due, a poll lands after the end of its slot
+    (-1..=7)
+        .flat_map(|days| {
+            let date = today + Duration::days(days);
+
+            pattern
+                .slots
+                .iter()
+                .filter(move |slot| slot.weekday == date.weekday().num_days_from_monday())
+                .filter_map(move |slot| {
+                    timezone
+                        .from_local_datetime(&date.and_hms(slot.hour, 0, 0))
+                        .earliest()
+                })
+        })
+        .map(|start| start.timestamp() + ONE_HOUR_IN_SECONDS + SLOT_POLL_DELAY_SECONDS)
+        .filter(|poll_at| *poll_at > now)
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use chrono_tz::Tz;
+
+    use crate::models::upload_pattern::UploadSlot;
+
+    #[test]
+    fn finds_the_slots_in_local_time() {
+        // Fridays at 18:00 in Berlin, which is 17:00 UTC in winter and 16:00 UTC in summer
+        let published_timestamps = [
+            Utc.ymd(2024, 1, 5).and_hms(17, 2, 0).timestamp(),
+            Utc.ymd(2024, 1, 12).and_hms(17, 0, 0).timestamp(),
+            Utc.ymd(2024, 6, 7).and_hms(16, 30, 0).timestamp(),
+            Utc.ymd(2024, 6, 14).and_hms(16, 0, 0).timestamp(),
+            Utc.ymd(2024, 6, 18).and_hms(9, 0, 0).timestamp(),
+        ];
+
+        let timezone = super::get_country_timezone(Some("de"));
+        let pattern = super::compute_upload_pattern(&published_timestamps, timezone).unwrap();
+
+        assert_eq!(pattern.timezone, "Europe/Berlin");
+        assert_eq!(pattern.uploads, 5);
+        assert_eq!(pattern.weekdays, vec![0, 1, 0, 0, 4, 0, 0]);
+        assert_eq!(pattern.hours[18], 4);
+        assert_eq!(
+            pattern.slots,
+            vec![UploadSlot {
+                weekday: 4,
+                hour: 18
+            }]
+        );
+
+        assert!(super::compute_upload_pattern(&published_timestamps[..4], timezone).is_none());
+        assert_eq!(super::get_country_timezone(Some("xx")), Tz::UTC);
+        assert_eq!(super::get_country_timezone(None), Tz::UTC);
+    }
+
+    #[test]
+    fn polls_after_the_next_slot() {
+        let published_timestamps = [
+            Utc.ymd(2024, 1, 5).and_hms(17, 0, 0).timestamp(),
+            Utc.ymd(2024, 1, 12).and_hms(17, 0, 0).timestamp(),
+            Utc.ymd(2024, 1, 19).and_hms(17, 0, 0).timestamp(),
+            Utc.ymd(2024, 1, 26).and_hms(17, 0, 0).timestamp(),
+            Utc.ymd(2024, 2, 2).and_hms(17, 0, 0).timestamp(),
+        ];
+        let timezone = super::get_country_timezone(Some("de"));
+        let pattern = super::compute_upload_pattern(&published_timestamps, timezone).unwrap();
+
+        // Wednesday in summer, the slot is at 16:00 UTC
+        let now = Utc.ymd(2024, 7, 3).and_hms(12, 0, 0).timestamp();
+        assert_eq!(
+            super::next_upload_slot_poll_at(now, &pattern),
+            Some(Utc.ymd(2024, 7, 5).and_hms(17, 15, 0).timestamp())
+        );
+
+        // During the slot the poll follows its end
+        let now = Utc.ymd(2024, 7, 5).and_hms(16, 30, 0).timestamp();
+        assert_eq!(
+            super::next_upload_slot_poll_at(now, &pattern),
+            Some(Utc.ymd(2024, 7, 5).and_hms(17, 15, 0).timestamp())
+        );
+
+        let mut pattern = pattern;
+        pattern.slots.clear();
+        assert_eq!(super::next_upload_slot_poll_at(now, &pattern), None);
+    }
+}
@@ -0,0 +1,225 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use hyper::client::connect::dns::Name;
+use reqwest::dns::{Addrs, Resolve, Resolving};
+use reqwest::redirect::Policy;
+use reqwest::{Client, Url};
+use tokio::net::lookup_host;
+
+/// A request or redirect to a host with an address the guard does not allow.
+#[derive(Debug)]
+pub struct BlockedAddressError(String);
+
+impl fmt::Display for BlockedAddressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} resolves to a private address", self.0)
+    }
+}
+
+impl StdError for BlockedAddressError {}
+
+/// Keeps requests to URLs taken from YouTube, e.g. about page links and short links in
+/// descriptions, off private, loopback, link-local and unspecified addresses, so they cannot reach
+/// the crawler host or its network.
+#[derive(Clone, Copy)]
+pub struct UrlGuard {
+    is_allowed: fn(&IpAddr) -> bool,
+}
+
+impl Default for UrlGuard {
+    fn default() -> Self {
+        UrlGuard {
+            is_allowed: is_public_ip,
+        }
+    }
+}
+
+impl UrlGuard {
+    #[cfg(test)]
+    pub fn allowing(is_allowed: fn(&IpAddr) -> bool) -> UrlGuard {
+        UrlGuard { is_allowed }
+    }
+
+    /// Resolves the host of the URL. Unparsable URLs and unknown hosts are left to fail on the
+    /// request.
+    pub async fn is_blocked(&self, url: &str) -> bool {
+        let url = match Url::parse(url) {
+            Ok(url) => url,
+            Err(_) => return false,
+        };
+
+        if let Some(ip) = get_ip_host(&url) {
+            return !(self.is_allowed)(&ip);
+        }
+
+        match (url.host_str(), url.port_or_known_default()) {
+            (Some(host), Some(port)) => match lookup_host((host, port)).await {
+                Ok(addrs) => addrs.into_iter().any(|addr| !(self.is_allowed)(&addr.ip())),
+                Err(_) => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// The client resolves hosts through the guard, which covers the hosts of every redirect,
+    /// and its redirect policy stops at redirects to addresses in the URL, which are not resolved.
+    pub fn build_client(&self, timeout: Duration, max_redirects: usize) -> Client {
+        let is_allowed = self.is_allowed;
+
+        Client::builder()
+            .timeout(timeout)
+            .dns_resolver(Arc::new(GuardedResolver { is_allowed }))
+            .redirect(Policy::custom(move |attempt| {
+                if attempt.previous().len() > max_redirects {
+                    attempt.error("too many redirects")
+                } else if get_ip_host(attempt.url()).is_some_and(|ip| !is_allowed(&ip)) {
+                    let host = attempt.url().host_str().unwrap_or_default().to_string();
+                    attempt.error(BlockedAddressError(host))
+                } else {
+                    attempt.follow()
+                }
+            }))
+            .build()
+            .expect("Failed to build the guarded http client")
+    }
+}
+
+/// Whether the request failed on a host or redirect the guard blocked.
+pub fn is_blocked_error(error: &reqwest::Error) -> bool {
+    let mut source = error.source();
+
+    while let Some(error) = source {
+        if error.is::<BlockedAddressError>() {
+            return true;
+        }
+        source = error.source();
+    }
+
+    false
+}
+
+pub fn is_public_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast())
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ip(&IpAddr::V4(ip)),
+            None => {
+                let first_segment = ip.segments()[0];
+
+                // Unique local (fc00::/7) and link-local (fe80::/10) addresses
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || first_segment & 0xfe00 == 0xfc00
+                    || first_segment & 0xffc0 == 0xfe80)
+            }
+        },
+    }
+}
+
+fn get_ip_host(url: &Url) -> Option<IpAddr> {
+    let host = url.host_str()?;
+
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse()
+        .ok()
+}
+
+struct GuardedResolver {
+    is_allowed: fn(&IpAddr) -> bool,
+}
+
+impl Resolve for GuardedResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let is_allowed = self.is_allowed;
+
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs: Vec<SocketAddr> = lookup_host((host.as_str(), 0)).await?.collect();
+
+            if addrs.iter().any(|addr| !is_allowed(&addr.ip())) {
+                return Err(BlockedAddressError(host).into());
+            }
+
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+    use std::time::Duration;
+
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    use super::UrlGuard;
+
+    #[test]
+    fn public_ips() {
+        let is_public = |ip: &str| super::is_public_ip(&ip.parse().unwrap());
+
+        assert!(is_public("142.250.185.78"));
+        assert!(is_public("2a00:1450:4001:82b::200e"));
+        assert!(!is_public("127.0.0.1"));
+        assert!(!is_public("10.0.0.8"));
+        assert!(!is_public("192.168.1.1"));
+        assert!(!is_public("169.254.169.254"));
+        assert!(!is_public("0.0.0.0"));
+        assert!(!is_public("::1"));
+        assert!(!is_public("fd00::1"));
+        assert!(!is_public("fe80::1"));
+        assert!(!is_public("::ffff:127.0.0.1"));
+    }
+
+    #[tokio::test]
+    async fn blocks_private_hosts() {
+        let guard = UrlGuard::default();
+
+        assert!(guard.is_blocked("http://127.0.0.1/").await);
+        assert!(guard.is_blocked("http://[::1]:8080/admin").await);
+        assert!(guard.is_blocked("http://localhost/").await);
+        assert!(!guard.is_blocked("not a url").await);
+    }
+
+    #[tokio::test]
+    async fn stops_at_redirects_to_private_addresses() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut connection = BufReader::new(stream);
+            let mut line = String::new();
+            while connection.read_line(&mut line).await.unwrap() > 2 {
+                line.clear();
+            }
+            connection
+                .get_mut()
+                .write_all(
+                    b"HTTP/1.1 302 Found\r\nLocation: http://169.254.169.254/latest/meta-data/\r\nContent-Length: 0\r\n\r\n",
+                )
+                .await
+                .unwrap();
+        });
+
+        // Lets the test reach its own server on the loopback address
+        let guard = UrlGuard::allowing(|ip| *ip == "127.0.0.1".parse::<IpAddr>().unwrap());
+        let client = guard.build_client(Duration::from_secs(5), 10);
+
+        let error = client.head(&url).send().await.unwrap_err();
+
+        assert!(super::is_blocked_error(&error));
+    }
+}
@@ -0,0 +1,144 @@
+use regex::Regex;
+use reqwest::Url;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum YoutubeResource {
+    Video(String),
+    Channel(String),
+    Handle(String),
+}
+
+/// Normalizes the different YouTube URL forms (youtu.be, shorts, live, embed, music and mobile
+/// hosts) as well as bare channel ids and handles into canonical ids.
+pub fn parse_youtube_url(input: &str) -> Option<YoutubeResource> {
+    let input = input.trim();
+
+    if is_channel_id(input) {
+        return Some(YoutubeResource::Channel(input.to_string()));
+    }
+
+    if input.starts_with('@') {
+        return parse_handle(input);
+    }
+
+    let url = if input.contains("://") {
+        Url::parse(input).ok()?
+    } else {
+        Url::parse(&format!("https://{}", input)).ok()?
+    };
+
+    let host = url.host_str()?.to_lowercase();
+    let host = host.trim_start_matches("www.").trim_start_matches("m.");
+    let segments = url
+        .path_segments()
+        .map(|segments| segments.filter(|s| !s.is_empty()).collect::<Vec<&str>>())
+        .unwrap_or_default();
+
+    if host == "youtu.be" {
+        return segments.first().and_then(|id| parse_video_id(id));
+    }
+
+    if host != "youtube.com" && host != "music.youtube.com" && host != "youtube-nocookie.com" {
+        return None;
+    }
+
+    match segments.as_slice() {
+        ["watch", ..] => url
+            .query_pairs()
+            .find(|(key, _)| key == "v")
+            .and_then(|(_, id)| parse_video_id(&id)),
+        ["shorts", id, ..] | ["live", id, ..] | ["embed", id, ..] | ["v", id, ..] => {
+            parse_video_id(id)
+        }
+        ["channel", id, ..] if is_channel_id(id) => Some(YoutubeResource::Channel(id.to_string())),
+        [handle, ..] if handle.starts_with('@') => parse_handle(handle),
+        _ => None,
+    }
+}
+
+pub fn is_channel_id(value: &str) -> bool {
+    let regex = Regex::new(r"^UC[A-Za-z0-9_-]{22}$").unwrap();
+    regex.is_match(value)
+}
+
+fn parse_video_id(value: &str) -> Option<YoutubeResource> {
+    let regex = Regex::new(r"^[A-Za-z0-9_-]{11}$").unwrap();
+
+    if !regex.is_match(value) {
+        return None;
+    }
+
+    Some(YoutubeResource::Video(value.to_string()))
+}
+
+fn parse_handle(value: &str) -> Option<YoutubeResource> {
+    let regex = Regex::new(r"^@[A-Za-z0-9._-]{3,30}$").unwrap();
+
+    if !regex.is_match(value) {
+        return None;
+    }
+
+    Some(YoutubeResource::Handle(value.to_lowercase()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_youtube_url, YoutubeResource};
+
+    const VIDEO_ID: &str = "dQw4w9WgXcQ";
+    const CHANNEL_ID: &str = "UCuAXFkgsw1L7xaCfnd5JJOw";
+
+    #[test]
+    fn parse_video_url_forms() {
+        let urls = [
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=42s",
+            "https://youtu.be/dQw4w9WgXcQ?si=abc",
+            "youtube.com/shorts/dQw4w9WgXcQ",
+            "https://m.youtube.com/live/dQw4w9WgXcQ",
+            "https://music.youtube.com/watch?v=dQw4w9WgXcQ&list=RD",
+            "https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ",
+        ];
+
+        for url in urls {
+            assert_eq!(
+                parse_youtube_url(url),
+                Some(YoutubeResource::Video(VIDEO_ID.to_string())),
+                "{}",
+                url
+            );
+        }
+    }
+
+    #[test]
+    fn parse_channel_url_forms() {
+        assert_eq!(
+            parse_youtube_url("https://www.youtube.com/channel/UCuAXFkgsw1L7xaCfnd5JJOw/videos"),
+            Some(YoutubeResource::Channel(CHANNEL_ID.to_string()))
+        );
+        assert_eq!(
+            parse_youtube_url(CHANNEL_ID),
+            Some(YoutubeResource::Channel(CHANNEL_ID.to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_handle_forms() {
+        let expected = Some(YoutubeResource::Handle("@guitarlessons".to_string()));
+
+        assert_eq!(parse_youtube_url("@GuitarLessons"), expected);
+        assert_eq!(
+            parse_youtube_url("https://www.youtube.com/@GuitarLessons/featured"),
+            expected
+        );
+    }
+
+    #[test]
+    fn reject_unknown_urls() {
+        assert_eq!(parse_youtube_url("https://vimeo.com/123456"), None);
+        assert_eq!(parse_youtube_url("https://youtu.be/short"), None);
+        assert_eq!(
+            parse_youtube_url("https://www.youtube.com/feed/trending"),
+            None
+        );
+    }
+}